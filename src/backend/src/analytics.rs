@@ -0,0 +1,260 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::{
+    MEMORY_MANAGER, ORGANIZATIONS, PRODUCTS, RESELLERS,
+};
+use crate::models::{DailyVerificationCount, OrgAnalyticsCounters, Reseller, VerificationAnnotation};
+use crate::owned_products;
+use crate::serial_store;
+use crate::verification_store;
+
+// Days kept in the per-day verification ring buffer
+pub const VERIFICATION_WINDOW_DAYS: u64 = 30;
+
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const ORG_ANALYTICS_MEM_ID: MemoryId = MemoryId::new(28);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static ORG_ANALYTICS: RefCell<StableBTreeMap<Principal, OrgAnalyticsCounters, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_ANALYTICS_MEM_ID))
+        )
+    );
+}
+
+fn day_index(timestamp: u64) -> u64 {
+    timestamp / DAY_NS
+}
+
+fn empty_counters(org_id: Principal) -> OrgAnalyticsCounters {
+    OrgAnalyticsCounters {
+        org_id,
+        total_products: 0,
+        active_resellers: 0,
+        daily_verifications: Vec::new(),
+        grey_market_verifications: Some(0),
+        reseller_product_checks: Some(0),
+        unauthorized_reseller_product_checks: Some(0),
+        revoked_codes: Some(0),
+        revoked_code_scans: Some(0),
+        registered_owners: Some(0),
+        last_refreshed: clock::now(),
+    }
+}
+
+fn add_verification(counters: &mut OrgAnalyticsCounters, timestamp: u64) {
+    let day = day_index(timestamp);
+    let slot = (day % VERIFICATION_WINDOW_DAYS) as usize;
+    if counters.daily_verifications.len() < VERIFICATION_WINDOW_DAYS as usize {
+        counters.daily_verifications.resize(VERIFICATION_WINDOW_DAYS as usize, DailyVerificationCount { day: 0, count: 0 });
+    }
+    let bucket = &mut counters.daily_verifications[slot];
+    if bucket.day != day {
+        // The slot still holds a day that has left the window
+        bucket.day = day;
+        bucket.count = 0;
+    }
+    bucket.count += 1;
+}
+
+fn update_counters<F>(org_id: Principal, update: F)
+where
+    F: FnOnce(&mut OrgAnalyticsCounters),
+{
+    ORG_ANALYTICS.with(|analytics| {
+        let mut analytics_mut = analytics.borrow_mut();
+        let mut counters = analytics_mut.get(&org_id).unwrap_or_else(|| empty_counters(org_id));
+        update(&mut counters);
+        counters.last_refreshed = clock::now();
+        analytics_mut.insert(org_id, counters);
+    });
+}
+
+// A product was created (previous_org_id None) or moved between organizations
+pub fn on_product_saved(previous_org_id: Option<Principal>, org_id: Principal) {
+    if previous_org_id == Some(org_id) {
+        return;
+    }
+    if let Some(previous) = previous_org_id {
+        update_counters(previous, |c| c.total_products = c.total_products.saturating_sub(1));
+    }
+    update_counters(org_id, |c| c.total_products += 1);
+}
+
+// A reseller record was written; only changes in verified status or organization move the counters
+pub fn on_reseller_saved(previous: Option<&Reseller>, current: &Reseller) {
+    let was_active = previous.filter(|r| r.is_verified).map(|r| r.org_id);
+    let is_active = Some(current.org_id).filter(|_| current.is_verified);
+    if was_active == is_active {
+        return;
+    }
+    if let Some(org_id) = was_active {
+        update_counters(org_id, |c| c.active_resellers = c.active_resellers.saturating_sub(1));
+    }
+    if let Some(org_id) = is_active {
+        update_counters(org_id, |c| c.active_resellers += 1);
+    }
+}
+
+// A reseller record was deleted
+pub fn on_reseller_removed(reseller: &Reseller) {
+    if reseller.is_verified {
+        update_counters(reseller.org_id, |c| c.active_resellers = c.active_resellers.saturating_sub(1));
+    }
+}
+
+pub fn on_verification_recorded(org_id: Principal, timestamp: u64) {
+    update_counters(org_id, |c| add_verification(c, timestamp));
+}
+
+pub fn on_grey_market_verification(org_id: Principal) {
+    update_counters(org_id, |c| c.grey_market_verifications = Some(c.grey_market_verifications.unwrap_or(0) + 1));
+}
+
+// A reseller code was verified for a specific product
+pub fn on_reseller_product_check(org_id: Principal, authorized: bool) {
+    update_counters(org_id, |c| {
+        c.reseller_product_checks = Some(c.reseller_product_checks.unwrap_or(0) + 1);
+        if !authorized {
+            c.unauthorized_reseller_product_checks = Some(c.unauthorized_reseller_product_checks.unwrap_or(0) + 1);
+        }
+    });
+}
+
+pub fn on_codes_revoked(org_id: Principal, count: u64) {
+    update_counters(org_id, |c| c.revoked_codes = Some(c.revoked_codes.unwrap_or(0) + count));
+}
+
+pub fn on_revoked_code_scan(org_id: Principal) {
+    update_counters(org_id, |c| c.revoked_code_scans = Some(c.revoked_code_scans.unwrap_or(0) + 1));
+}
+
+// A consumer added a serial of the organization to their shelf (added) or took it off
+pub fn on_ownership_changed(org_id: Principal, added: bool) {
+    update_counters(org_id, |c| {
+        let owners = c.registered_owners.unwrap_or(0);
+        c.registered_owners = Some(if added { owners + 1 } else { owners.saturating_sub(1) });
+    });
+}
+
+// Verifications over the window ending today, in whole days
+pub fn verifications_in_window(counters: &OrgAnalyticsCounters, now: u64) -> u64 {
+    let today = day_index(now);
+    counters
+        .daily_verifications
+        .iter()
+        .filter(|bucket| bucket.day + VERIFICATION_WINDOW_DAYS > today && bucket.day <= today)
+        .map(|bucket| bucket.count)
+        .sum()
+}
+
+pub fn get_counters(org_id: Principal) -> Option<OrgAnalyticsCounters> {
+    ORG_ANALYTICS.with(|analytics| analytics.borrow().get(&org_id))
+}
+
+// Build the counters for an organization from a full scan of its products, resellers and verifications
+pub fn compute_counters(org_id: Principal) -> OrgAnalyticsCounters {
+    let mut counters = empty_counters(org_id);
+    let now = clock::now();
+    let window_start = (day_index(now) + 1).saturating_sub(VERIFICATION_WINDOW_DAYS) * DAY_NS;
+
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
+    });
+    counters.total_products = product_ids.len() as u64;
+
+    counters.active_resellers = RESELLERS.with(|resellers| {
+        resellers
+            .borrow()
+            .iter()
+            .filter(|(_, reseller)| reseller.org_id == org_id && reseller.is_verified)
+            .count() as u64
+    });
+
+    counters.revoked_codes = Some(
+        product_ids
+            .iter()
+            .flat_map(|product_id| serial_store::list_for_product(*product_id))
+            .map(|sn| sn.revoked_codes.map_or(0, |revoked| revoked.len() as u64))
+            .sum(),
+    );
+
+    counters.registered_owners = Some(
+        owned_products::counts_for_products(&product_ids).iter().map(|(_, count)| count).sum(),
+    );
+
+    for product_id in &product_ids {
+        for verification in verification_store::list_for_product(*product_id) {
+            if verification.is_test == Some(true) {
+                continue;
+            }
+            if verification.created_at >= window_start {
+                add_verification(&mut counters, verification.created_at);
+            }
+            if verification
+                .annotations
+                .as_ref()
+                .is_some_and(|a| a.contains(&VerificationAnnotation::GreyMarketSuspected))
+            {
+                counters.grey_market_verifications = Some(counters.grey_market_verifications.unwrap_or(0) + 1);
+            }
+        }
+    }
+
+    counters
+}
+
+// Recompute and store the counters, e.g. after an upgrade or to correct drift
+pub fn rebuild_counters(org_id: Principal) -> OrgAnalyticsCounters {
+    let mut counters = compute_counters(org_id);
+    // Reseller product checks and revoked-code scans leave no stored record to rebuild from, so they carry over
+    if let Some(existing) = get_counters(org_id) {
+        counters.reseller_product_checks = existing.reseller_product_checks;
+        counters.unauthorized_reseller_product_checks = existing.unauthorized_reseller_product_checks;
+        counters.revoked_code_scans = existing.revoked_code_scans;
+    }
+    ORG_ANALYTICS.with(|analytics| {
+        analytics.borrow_mut().insert(org_id, counters.clone());
+    });
+    counters
+}
+
+// Seed counters for every organization that does not have them yet
+pub fn rebuild_missing_counters() -> u32 {
+    let org_ids: Vec<Principal> = ORGANIZATIONS.with(|orgs| orgs.borrow().iter().map(|(id, _)| id).collect());
+    let mut rebuilt = 0;
+    for org_id in org_ids {
+        if get_counters(org_id).is_none() {
+            rebuild_counters(org_id);
+            rebuilt += 1;
+        }
+    }
+    rebuilt
+}
+
+// Reset ALL analytics counters (use with caution)
+pub fn reset_org_analytics() {
+    ORG_ANALYTICS.with(|analytics| {
+        let mut analytics_mut = analytics.borrow_mut();
+        let keys: Vec<_> = analytics_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            analytics_mut.remove(&key);
+        }
+    });
+    log_info!("All organization analytics counters have been reset.");
+}
@@ -1,9 +1,12 @@
-use candid::{CandidType, Deserialize, Principal};
-use ic_cdk::api;
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+use crate::auth::{AuditLogEntry, Permission};
+use crate::clock;
 use crate::error::{ApiError, ErrorDetails};
-use crate::models::{Metadata, Organization, OrganizationPublic, Product, ProductSerialNumber, ProductVerification, Reseller, User, ProductVerificationStatus};
+use crate::models::{Metadata, Organization, OrganizationPublic, Product, ProductSerialNumber, ProductVerification, Reseller, User, ProductVerificationStatus, NotificationRule, NotificationTrigger, Report, ReportFrequency, ReportSchedule, PrintJob, ProductUniqueCodeResultRecord, RateLimitConfig, FeatureFlag, PendingRedemption, UserNotification, Dispute, DisputeStatus, ProductVariant, VerifiedBrandBadge, IntegrityStore, IntegritySubKey, BiExportDelivery, VerificationChallengeStatus, RewardPenalty, WalletChain, RewardPool, VerificationAnnotation, SerialNumberStatus, CodeRevocationReason, ReportFilters, ReportShareLink, ReportProductStat, ResellerApplication, LabelTemplate, QrErrorCorrection, QrPlacement, UserRole, VerificationPrivacyPolicy, ClientVersionPolicy, OutboxDelivery, TermsVersion, TermsAcceptance, VerificationHeatmapKind, Bundle, BundleSummary, OrgProbeActivity, CyclesAlertConfig, CyclesStatus, MetadataFieldSchema, OrgPlan, PlanTier, RetiredVerificationHandling, AuthenticityWeights, Job, OutcallResponseLimits, VerificationDomain, CapabilityOperation, CapabilityToken, ProductRecall, RecallCampaign, RecallScope, ErrorCodeCount, CodeRevocation,
+    ModerationConfig, ModerationCase, PrintCodePolicy, SerialBatchDefaults, PublicBrandStats, AdminOperation, AdminOperationKind};
 
 // ====== Common API Structures ======
 
@@ -12,22 +15,25 @@ pub struct ResponseMetadata {
     pub timestamp: u64,
     pub version: String,
     pub request_id: Option<String>,
+    pub rate_limit: Option<RateLimitInfo>, // Set by verification endpoints on success and error alike
 }
 
 impl Default for ResponseMetadata {
     fn default() -> Self {
         ResponseMetadata {
-            timestamp: api::time(),
+            timestamp: clock::now(),
             version: "1.0".to_string(),
             request_id: None,
+            rate_limit: None,
         }
     }
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct PaginationRequest {
-    pub page: Option<u32>,
+    pub page: Option<u32>, // Deprecated: pass the previous response's next_cursor instead
     pub limit: Option<u32>,
+    pub cursor: Option<String>, // Takes precedence over page on endpoints that return cursors
 }
 
 impl Default for PaginationRequest {
@@ -35,16 +41,18 @@ impl Default for PaginationRequest {
         PaginationRequest {
             page: Some(1),
             limit: Some(10),
+            cursor: None,
         }
     }
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct PaginationResponse {
-    pub page: u32,
+    pub page: u32, // 0 when the page was requested by cursor
     pub limit: u32,
-    pub total: u64,
+    pub total: u64, // 0 on listings paged straight from a store, which do not count their items
     pub has_more: bool,
+    pub next_cursor: Option<String>, // Set when has_more, on endpoints that support cursors
 }
 
 // ====== Generic API Response Structures ======
@@ -66,12 +74,19 @@ impl<T> ApiResponse<T> {
     }
 
     pub fn error(error: ApiError) -> Self {
+        crate::endpoint_metrics::note_error(&error);
         ApiResponse {
             data: None,
             error: Some(error),
             metadata: ResponseMetadata::default(),
         }
     }
+
+    // Attach the caller's current rate limit so clients can throttle before hitting a lockout
+    pub fn with_rate_limit(mut self, rate_limit: Option<RateLimitInfo>) -> Self {
+        self.metadata.rate_limit = rate_limit;
+        self
+    }
 }
 
 // ===== Organization API Structures =====
@@ -117,6 +132,8 @@ pub struct CreateProductRequest {
     pub category: String,
     pub description: String,
     pub metadata: Vec<Metadata>,
+    pub sku: Option<String>,
+    pub force: Option<bool>, // Create even if the organization has a product with the same name and category
 }
 
 #[derive(CandidType, Serialize, Deserialize)]
@@ -140,12 +157,28 @@ pub struct ListProductsRequest {
     pub pagination: Option<PaginationRequest>,
 }
 
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SearchProductsRequest {
+    pub org_id: Principal,
+    // Product number ("12" or "#12"), or part of the product name or SKU
+    pub query: Option<String>,
+    pub pagination: Option<PaginationRequest>,
+}
+
 #[derive(CandidType, Serialize, Deserialize)]
 pub struct ProductsListResponse {
     pub products: Vec<Product>,
     pub pagination: Option<PaginationResponse>,
 }
 
+// Products that share a name and category after normalization
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct DuplicateProductGroup {
+    pub normalized_name: String,
+    pub normalized_category: String,
+    pub products: Vec<Product>, // Oldest first
+}
+
 // ===== Product Serial Number API Structures =====
 
 #[derive(CandidType, Deserialize)]
@@ -153,6 +186,20 @@ pub struct CreateProductSerialNumberRequest {
     pub product_id: Principal,
 }
 
+#[derive(CandidType, Deserialize)]
+pub struct CreateSerialNumbersBatchRequest {
+    pub product_id: Principal,
+    pub variant_id: Option<Principal>,
+    pub count: Option<u32>, // None uses the organization's default batch size
+    pub user_serial_prefix: Option<String>, // Assigns human-readable serials: prefix + zero-padded per-product counter. None uses the organization's default; empty uses none.
+    pub capability_token: Option<String>, // Lets a non-member create serials with a CreateSerials token
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SerialNumbersBatchResponse {
+    pub serial_numbers: Vec<ProductSerialNumber>,
+}
+
 #[derive(CandidType, Serialize, Deserialize)]
 pub struct ProductSerialNumberResponse {
     pub serial_number: ProductSerialNumber,
@@ -207,6 +254,40 @@ pub struct ProductVerificationsListResponse {
 pub struct VerifyProductEnhancedRequest {
     pub serial_no: Principal,
     pub unique_code: String,
+    pub guest_token: Option<String>, // Required when calling as the anonymous principal
+    pub country_code: Option<String>, // ISO 3166-1 alpha-2 code from the client's geolocation, if available
+    pub client_version: Option<String>, // Version of the scanning app, checked against the minimum version
+    pub locale: Option<String>, // Language of status_message, e.g. "pt-BR"; English when absent or unknown
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct GuestSessionResponse {
+    pub token: String, // Bearer token; keep it to claim the session's verifications later
+    pub session_id: Principal,
+    pub expires_at: u64,
+    pub max_verifications: u32,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ClaimGuestVerificationsResponse {
+    pub claimed_verifications: u32,
+    pub points_awarded: u32,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct IdentityLinkCodeResponse {
+    pub code: String, // Shown once; enter it while signed in with the other identity
+    pub expires_at: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct IdentityLinkResponse {
+    pub user_id: Principal, // The account both identities now sign in to
+    pub linked_principal: Principal,
+    pub merged_account: bool, // false when the linked identity had no account of its own
+    pub org_ids_added: u32,
+    pub points_transferred: u32,
+    pub verifications_transferred: u32,
 }
 
 #[derive(CandidType, Serialize, Deserialize)]
@@ -215,6 +296,157 @@ pub struct ProductVerificationEnhancedResponse {
     pub verification: Option<ProductVerification>,
     pub rewards: Option<VerificationRewards>,
     pub expiration: Option<u64>,
+    pub warning: Option<String>,
+    pub variant: Option<ProductVariant>, // Set when the verified serial belongs to a variant pool
+    pub message: Option<RenderedVerificationMessage>, // Brand message shown after a genuine scan
+    pub rate_limit: Option<RateLimitInfo>, // Attempts left after this verification
+    pub discontinued: bool,
+    pub successor: Option<ProductSuccessor>, // Newer product to point consumers to, if the brand named one
+    pub annotations: Vec<VerificationAnnotation>,
+    pub revocation_reason: Option<CodeRevocationReason>, // Set with the CodeRevoked status
+    // Set on genuine verifications unless the brand's privacy policy hides them
+    pub scan_count: Option<u32>, // Verifications of this serial, including this one
+    pub first_scanned_at: Option<u64>,
+    pub custody_status: Option<SerialNumberStatus>,
+    pub federation: Option<FederatedVerification>, // Set when a delegated manufacturer signed the code
+    pub is_test: bool, // The product is in test mode; no points were credited
+    pub bundle: Option<BundleSummary>, // Set when the verified code is the outer code of a multipack
+    pub authenticity: Option<AuthenticityScore>, // Set on genuine verifications
+    pub status_message: String, // Human-readable text for the status, in the requested locale when the catalog has it
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthenticityFactorKind {
+    Signature,
+    ScanVelocity,
+    Geography,
+    Custody,
+    Reports,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AuthenticityFactor {
+    pub kind: AuthenticityFactorKind,
+    pub score: u8, // 0-100 for this signal alone
+    pub weight: u32,
+    pub detail: String,
+}
+
+// Confidence that the scanned item is genuine, from 0 to 100, with the signals behind it
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AuthenticityScore {
+    pub score: u8,
+    pub factors: Vec<AuthenticityFactor>,
+}
+
+// Both parties behind a code signed under a verification delegation
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct FederatedVerification {
+    pub brand: OrganizationPublic,
+    pub manufacturer: OrganizationPublic,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ProductSuccessor {
+    pub product_id: Principal,
+    pub name: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct StartVerificationChallengeRequest {
+    pub serial_no: Principal,
+    pub guest_token: Option<String>, // Required for anonymous callers, as in verify_product_v2
+    pub client_version: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct VerificationChallengeResponse {
+    pub challenge_id: Principal,
+    pub nonce: String, // Hex; the tag signs "{product_id}_{serial_no}_{nonce}"
+    pub product_id: Principal,
+    pub expires_at: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct CompleteVerificationChallengeRequest {
+    pub challenge_id: Principal,
+    pub signature: String, // Hex-encoded signature produced by the tag or brand app
+    pub guest_token: Option<String>,
+    pub country_code: Option<String>,
+    pub locale: Option<String>, // As in verify_product_v2
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ChallengeVerificationResponse {
+    pub status: VerificationChallengeStatus,
+    pub result: Option<ProductVerificationEnhancedResponse>, // Set only when the challenge is Verified
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RenderedVerificationMessage {
+    pub text: String,
+    pub cta_label: Option<String>,
+    pub cta_url: Option<String>,
+}
+
+// Outcome simulate_verification answers with; one per branch a client has to render
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationScenario {
+    FirstVerification,
+    MultipleVerification,
+    Invalid,
+    VoidedSerial,
+    CodeRevoked,
+    NotYetActive,
+    SupersededCode,
+    Discontinued, // Genuine scan of a discontinued product, with its successor
+    GreyMarket, // Genuine scan outside the product's markets
+    SuspectedClone, // Genuine scan of a serial past the duplicate-scan threshold
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SimulateVerificationRequest {
+    pub product_id: Principal, // Must be a test-mode product
+    pub scenario: VerificationScenario,
+    pub locale: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct BatchVerificationItem {
+    pub serial_no: Principal,
+    pub unique_code: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchVerificationItemStatus {
+    Genuine,
+    Invalid,        // Unknown serial, malformed code or signature mismatch
+    Voided,         // Genuine code on a voided label
+    Revoked,        // Genuine code whose printed version the brand revoked
+    SuspectedClone, // Genuine code on a serial flagged as cloned
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BatchVerificationItemResult {
+    pub serial_no: Principal,
+    pub product_id: Option<Principal>,
+    pub status: BatchVerificationItemStatus,
+    pub message: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BatchVerificationSummary {
+    pub total: u32,
+    pub genuine: u32,
+    pub invalid: u32,
+    pub suspect: u32, // Voided or suspected-clone serials
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct BatchVerificationResponse {
+    pub results: Vec<BatchVerificationItemResult>,
+    pub summary: BatchVerificationSummary,
+    pub rate_limit: RateLimitInfo,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -229,10 +461,12 @@ pub struct VerificationRewards {
 
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub struct RedeemRewardRequest {
-    pub wallet_address: String,
+    pub wallet_address: String, // Raw address; leave empty to use wallet_id or the default saved wallet
+    pub wallet_id: Option<Principal>, // Saved wallet from the caller's address book
     // We need the original verification details to validate the redemption request
     pub serial_no: Principal, 
     pub unique_code: String, 
+    pub redeem_as_coupon: Option<bool>, // Exchange the points for one of the brand's coupon codes; no wallet needed
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -240,6 +474,43 @@ pub struct RedeemRewardResponse {
     pub success: bool,
     pub transaction_id: Option<String>, // Optional transaction ID from ledger
     pub message: String, // User-friendly message
+    pub pending_redemption_id: Option<Principal>, // Set when the payout is held for approval
+    pub coupon: Option<RedeemedCoupon>, // Set when the reward was redeemed as a coupon code
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RedeemedCoupon {
+    pub code: String,
+    pub description: Option<String>,
+}
+
+// One line of a brand's approved distributor list
+#[derive(CandidType, Deserialize)]
+pub struct PreapprovedResellerRow {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct PreapproveResellersResponse {
+    pub added: u32,
+    pub updated: u32, // Emails that were already pre-approved
+    pub certified: u32, // Pending applications certified by this upload
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct UploadCouponCodesRequest {
+    pub org_id: Principal,
+    pub product_id: Option<Principal>, // None makes the codes usable for any of the organization's products
+    pub codes: Vec<String>,
+    pub description: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct UploadCouponCodesResponse {
+    pub added: u32,
+    pub skipped_duplicates: u32, // Already uploaded, or repeated within the request
+    pub available: u64, // Unredeemed codes in the pool the codes were added to
 }
 
 // ===== Rate Limiting Structures =====
@@ -247,8 +518,10 @@ pub struct RedeemRewardResponse {
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct RateLimitInfo {
     pub remaining_attempts: u32,
-    pub reset_time: u64,
+    pub reset_time: u64, // End of the current window, or of the lockout while one is active
     pub current_window_start: u64,
+    pub penalty_level: u32, // Recent exhaustions; each doubles the next lockout
+    pub locked_until: Option<u64>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -285,6 +558,20 @@ pub struct UpdateUserRequest {
     pub detail_meta: Option<Vec<Metadata>>,
 }
 
+#[derive(CandidType, Deserialize, Default)]
+pub struct UserListFilter {
+    pub role: Option<UserRole>,
+    pub is_enabled: Option<bool>,
+    pub org_id: Option<Principal>,
+    pub query: Option<String>, // Case-insensitive match on name, email or principal
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct UsersListResponse {
+    pub users: Vec<User>, // Newest first
+    pub pagination: PaginationResponse,
+}
+
 // ===== Reseller API Structures =====
 
 #[derive(CandidType, Deserialize)]
@@ -321,6 +608,8 @@ pub struct VerifyResellerRequest {
     pub unique_code: String,
     pub timestamp: u64, // Timestamp from the generated code
     pub context: Option<String>, // Context must match if provided during generation
+    pub product_id: Option<Principal>, // Product being bought, checked against the reseller's authorization
+    pub client_version: Option<String>, // Version of the scanning app, checked against the minimum version
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug, Clone)]
@@ -331,6 +620,7 @@ pub enum ResellerVerificationStatus {
     ReplayAttackDetected,
     ResellerNotFound,
     OrganizationNotFound,
+    CertificationExpired,
     InternalError,
 }
 
@@ -339,6 +629,7 @@ pub struct ResellerVerificationResponse {
     pub status: ResellerVerificationStatus,
     pub organization: Option<OrganizationPublic>,
     pub reseller: Option<Reseller>,
+    pub product_authorized: Option<bool>, // Only set on success, when the request named a product
 }
 
 // Function to apply pagination to any vector of items
@@ -364,11 +655,114 @@ pub fn paginate<T: Clone>(
         limit,
         total,
         has_more: end < items.len(),
+        next_cursor: None,
     };
     
     (paginated_items, pagination)
 }
 
+// Largest page the cursor-paginated listings return
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+pub fn page_limit(request: &PaginationRequest) -> u32 {
+    request.limit.unwrap_or(10).clamp(1, MAX_PAGE_LIMIT)
+}
+
+// Cursors are the hex of the candid-encoded key of the last item on the page; clients treat them as opaque
+pub fn encode_cursor<K: CandidType>(key: &K) -> String {
+    hex::encode(encode_one(key).unwrap_or_default())
+}
+
+pub fn decode_cursor<K: CandidType + DeserializeOwned>(cursor: &str) -> Result<K, ApiError> {
+    hex::decode(cursor)
+        .ok()
+        .and_then(|bytes| decode_one(&bytes).ok())
+        .ok_or_else(|| ApiError::invalid_input("Invalid pagination cursor"))
+}
+
+// Like paginate, for lists with a unique key per item. The items are ordered by that key, and a
+// cursor resumes right after the key it was taken from, so items added or removed earlier in the
+// list do not shift later pages. Without a cursor the deprecated page number is used.
+pub fn paginate_by_key<T, K, F>(
+    mut items: Vec<T>,
+    request: &PaginationRequest,
+    key: F,
+) -> Result<(Vec<T>, PaginationResponse), ApiError>
+where
+    K: CandidType + DeserializeOwned + Ord,
+    F: Fn(&T) -> K,
+{
+    items.sort_by_key(|a| key(a));
+    let limit = page_limit(request);
+    let total = items.len() as u64;
+
+    let (start, page) = match request.cursor.as_deref() {
+        Some(cursor) => {
+            let after: K = decode_cursor(cursor)?;
+            (items.partition_point(|item| key(item) <= after), 0)
+        }
+        None => {
+            let page = request.page.unwrap_or(1).max(1);
+            ((page as usize - 1).saturating_mul(limit as usize), page)
+        }
+    };
+    let start = start.min(items.len());
+    let end = start.saturating_add(limit as usize).min(items.len());
+    let has_more = end < items.len();
+
+    let page_items: Vec<T> = items.drain(start..end).collect();
+    let next_cursor = if has_more { page_items.last().map(|item| encode_cursor(&key(item))) } else { None };
+    Ok((
+        page_items,
+        PaginationResponse {
+            page,
+            limit,
+            total,
+            has_more,
+            next_cursor,
+        },
+    ))
+}
+
+// Like paginate_by_key, for listings read straight from a store in key order. `fetch(after, n)` returns up
+// to n items following the key `after`, or from the start when None, so only the requested page is read.
+// The deprecated page number reads and skips the earlier pages.
+pub fn paginate_from_store<T, K, F, G>(
+    request: &PaginationRequest,
+    key: G,
+    fetch: F,
+) -> Result<(Vec<T>, PaginationResponse), ApiError>
+where
+    K: CandidType + DeserializeOwned,
+    F: FnOnce(Option<K>, usize) -> Vec<T>,
+    G: Fn(&T) -> K,
+{
+    let limit = page_limit(request);
+    let (after, page, skip) = match request.cursor.as_deref() {
+        Some(cursor) => (Some(decode_cursor(cursor)?), 0, 0),
+        None => {
+            let page = request.page.unwrap_or(1).max(1);
+            (None, page, (page as usize - 1).saturating_mul(limit as usize))
+        }
+    };
+
+    // One item past the page tells whether there is more
+    let mut items = fetch(after, skip.saturating_add(limit as usize + 1));
+    let has_more = items.len() > skip.saturating_add(limit as usize);
+    let page_items: Vec<T> = items.drain(skip.min(items.len())..).take(limit as usize).collect();
+    let next_cursor = if has_more { page_items.last().map(|item| encode_cursor(&key(item))) } else { None };
+    Ok((
+        page_items,
+        PaginationResponse {
+            page,
+            limit,
+            total: 0,
+            has_more,
+            next_cursor,
+        },
+    ))
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct ProductVerificationDetail {
     pub user_email: Option<String>,
@@ -379,6 +773,36 @@ pub struct ProductVerificationDetail {
     pub status: ProductVerificationStatus,
 }
 
+// Every filter that is set must match; lists match any of their entries
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct OrgVerificationFilters {
+    pub product_ids: Option<Vec<Principal>>,
+    pub statuses: Option<Vec<ProductVerificationStatus>>,
+    // Recorded at or after `from` and before `to`, in nanoseconds
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    // Domain of the verifying user's email, e.g. "example.com"
+    pub user_email_domain: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ListOrgVerificationsRequest {
+    pub org_id: Principal,
+    pub filters: Option<OrgVerificationFilters>,
+    // next_cursor of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+// A page can come back short, or even empty, with has_more set when the filters skipped many records;
+// keep following next_cursor until has_more is false
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgVerificationsResponse {
+    pub verifications: Vec<ProductVerificationDetail>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
 // ===== Reset API Structures =====
 
 #[derive(CandidType, Serialize, Deserialize)]
@@ -397,5 +821,1121 @@ pub struct GetOrganizationAnalyticRequest {
 pub struct OrganizationAnalyticData {
     pub total_products: u64,
     pub active_resellers: u64,
-    pub verifications_this_month: u64, // Defined as verifications in the last 30 days
-}
\ No newline at end of file
+    pub verifications_this_month: u64, // Verifications over the last 30 days, counted in whole days
+    pub grey_market_verifications: u64, // All-time scans outside a product's allowed markets
+    pub reseller_product_checks: u64,
+    pub unauthorized_reseller_product_checks: u64,
+    pub revoked_codes: u64,
+    pub revoked_code_scans: u64,
+    pub registered_owners: u64, // Serials consumers have added to their product shelf
+    pub last_refreshed: u64, // When the underlying counters were last updated
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct VerificationHeatmapRange {
+    pub period_start: Option<u64>, // None: 30 days before period_end
+    pub period_end: Option<u64>, // None: now
+    pub kind: Option<VerificationHeatmapKind>, // None: all genuine verifications
+    pub utc_offset_hours: Option<i8>, // Shifts rows and columns to the brand's local time
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct VerificationHeatmapResponse {
+    pub kind: VerificationHeatmapKind,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub utc_offset_hours: i8,
+    pub counts: Vec<Vec<u64>>, // 7 rows, Monday first, of 24 hourly counts
+    pub total: u64,
+}
+
+// ===== Notification Rule API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct CreateNotificationRuleRequest {
+    pub product_id: Principal,
+    pub trigger: NotificationTrigger,
+    pub min_scan_count: Option<u32>,
+    pub webhook_url: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct NotificationRuleResponse {
+    pub rule: NotificationRule,
+}
+
+// ===== Organization Settings API Structures =====
+
+// Fields left as None keep their current value
+#[derive(CandidType, Deserialize)]
+pub struct UpdateOrgSettingsRequest {
+    pub org_id: Principal,
+    pub certification_validity_seconds: Option<u64>,
+    pub certification_grace_period_seconds: Option<u64>,
+    pub duplicate_scan_threshold: Option<u32>,
+    pub redemption_approval_threshold: Option<u32>,
+    pub single_use_reseller_codes: Option<bool>,
+    pub verification_privacy: Option<VerificationPrivacyPolicy>,
+    pub client_version_policy: Option<ClientVersionPolicy>, // An empty min_version removes the requirement
+    pub authenticity_weights: Option<AuthenticityWeights>, // All zero restores the defaults
+    pub serial_batch_defaults: Option<SerialBatchDefaults>, // Replaces all batch defaults; all None clears them
+    pub public_stats_enabled: Option<bool>,
+}
+
+// ===== Consumer Activity API Structures =====
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ConsumerVerificationActivity {
+    pub verification_id: Principal,
+    pub product_id: Principal,
+    pub product_name: String,
+    pub org_id: Option<Principal>,
+    pub serial_no: Principal,
+    pub status: ProductVerificationStatus,
+    pub created_at: u64,
+    pub reward_claimed: bool,
+    pub reward_transaction_id: Option<String>,
+    pub coupon: Option<RedeemedCoupon>, // Coupon code the reward was exchanged for
+}
+
+// A first verification whose reward has not been redeemed yet
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct UnclaimedReward {
+    pub verification_id: Principal,
+    pub product_id: Principal,
+    pub product_name: String,
+    pub serial_no: Principal,
+    pub verified_at: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ConsumerActivityResponse {
+    pub verifications: Vec<ConsumerVerificationActivity>, // Newest first, paginated
+    pub pagination: PaginationResponse,
+    pub reward_points: u32,
+    pub total_verifications: u32,
+    pub first_verifications: u32,
+    pub unclaimed_rewards: Vec<UnclaimedReward>,
+    pub pending_redemptions: Vec<PendingRedemption>, // Redemptions awaiting brand owner or admin approval
+    pub reward_penalties: Vec<RewardPenalty>, // Points clawed back from the caller, newest first
+    pub owned_products: Vec<MyProductEntry>, // Registered products with their warranty status, most recently registered first
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct PenalizeUserRewardsRequest {
+    pub user_id: Principal,
+    pub points: u32,
+    pub reason: String,
+    pub org_id: Option<Principal>, // Required for brand owners, who can only claw back points earned on their products
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListRewardPenaltiesRequest {
+    pub org_id: Option<Principal>, // Required for brand listings; admins may omit it to list every penalty
+    pub user_id: Option<Principal>,
+    pub pagination: Option<PaginationRequest>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct RewardPenaltiesListResponse {
+    pub penalties: Vec<RewardPenalty>, // Newest first
+    pub pagination: PaginationResponse,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct RejectRedemptionRequest {
+    pub redemption_id: Principal,
+    pub reason: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct RejectResellerApplicationRequest {
+    pub application_id: Principal,
+    pub reason: String, // Shown to the applicant
+}
+
+// An application together with the profile it was submitted with
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ResellerApplicationDetail {
+    pub application: ResellerApplication,
+    pub reseller: Option<Reseller>,
+}
+
+// ===== Notification Inbox API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct ListNotificationsRequest {
+    pub unread_only: Option<bool>,
+    pub pagination: Option<PaginationRequest>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct NotificationsListResponse {
+    pub notifications: Vec<UserNotification>, // Newest first
+    pub unread_count: u32,
+    pub pagination: PaginationResponse,
+}
+
+// ===== Dispute API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct OpenDisputeRequest {
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub verification_id: Option<Principal>,
+    pub subject: String,
+    pub message: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct AddDisputeMessageRequest {
+    pub dispute_id: Principal,
+    pub message: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct UpdateDisputeStatusRequest {
+    pub dispute_id: Principal,
+    pub status: DisputeStatus,
+    pub resolution: Option<String>, // Required when resolving
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListDisputesRequest {
+    pub org_id: Option<Principal>, // Required for brand listings; admins may omit it to list every organization
+    pub status: Option<DisputeStatus>,
+    pub pagination: Option<PaginationRequest>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct DisputesListResponse {
+    pub disputes: Vec<Dispute>, // Most recently updated first
+    pub pagination: PaginationResponse,
+}
+
+// ===== Wallet Address Book API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct AddWalletRequest {
+    pub label: String,
+    pub chain: WalletChain,
+    pub address: String,
+    pub make_default: bool, // The first saved wallet is always the default
+}
+
+// ===== Reward Pool API Structures =====
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct RewardPoolBalanceResponse {
+    pub pool: RewardPool,
+    pub points_covered: u64, // Points the current balance can pay out
+    pub redemptions_require_pool: bool, // Whether redemptions are currently drawn from the pool
+    pub deposit_account_owner: Principal, // ICRC-1 account to transfer deposits to
+    pub deposit_subaccount: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DepositRewardPoolRequest {
+    pub org_id: Principal,
+    pub amount: u64, // Ledger base units, pulled with icrc2_transfer_from
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SetRewardPoolSettingsRequest {
+    pub org_id: Principal,
+    pub units_per_point: Option<u64>,
+    pub low_balance_threshold: Option<u64>,
+}
+
+// ===== Onboarding API Structures =====
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnboardingStepKind {
+    BrandingSet,
+    FirstProductCreated,
+    SerialsPrinted,
+    FirstResellerCertified,
+    WebhookConfigured,
+    RewardPolicySet,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OnboardingStep {
+    pub step: OnboardingStepKind,
+    pub completed: bool,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct OrgOnboardingStatusResponse {
+    pub org_id: Principal,
+    pub steps: Vec<OnboardingStep>, // In the order the dashboard should present them
+    pub completed_steps: u32,
+    pub total_steps: u32,
+    pub next_step: Option<OnboardingStepKind>, // First incomplete step; None once onboarding is done
+}
+
+// ===== Brand Dashboard API Structures =====
+
+// A product's serial pool, or one of its variant pools, running out of unprinted serials
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct LowStockSerialPool {
+    pub product_id: Principal,
+    pub product_name: String,
+    pub variant_id: Option<Principal>, // None for the product's own pool
+    pub available_serials: u64, // Created but not yet printed
+    pub total_serials: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct BrandDashboardResponse {
+    pub org_id: Principal,
+    pub analytics: OrganizationAnalyticData,
+    pub recent_verifications: Vec<ProductVerificationDetail>, // Newest first
+    pub low_stock_serial_pools: Vec<LowStockSerialPool>, // Emptiest first
+    pub pending_reseller_applications: Vec<Reseller>, // Applicants awaiting review, oldest first
+    pub notifications: Vec<UserNotification>, // The caller's latest, newest first
+    pub unread_notifications: u32,
+    pub generated_at: u64,
+}
+
+// ===== Product Variant API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct CreateProductVariantRequest {
+    pub product_id: Principal,
+    pub name: String,
+    pub sku: Option<String>,
+    pub attributes: Vec<Metadata>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct UpdateProductVariantRequest {
+    pub variant_id: Principal,
+    pub name: Option<String>,
+    pub sku: Option<String>,
+    pub attributes: Option<Vec<Metadata>>,
+}
+
+// ===== Public Directory API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct UpdateDirectoryListingRequest {
+    pub org_id: Principal,
+    pub is_public: Option<bool>,
+    pub logo_url: Option<String>,
+    pub website_url: Option<String>,
+    pub tagline: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ModerateDirectoryListingRequest {
+    pub org_id: Principal,
+    pub featured: Option<bool>,
+    pub suspended: Option<bool>,
+    pub suspension_reason: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ReviewModerationCaseRequest {
+    pub entity_id: Principal,
+    pub approve: bool,
+    pub note: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ModerationCasesResponse {
+    pub cases: Vec<ModerationCase>,
+    pub pagination: Option<PaginationResponse>,
+}
+
+// Stats for a brand widget. When the caller already has the current version, stats is left out.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PublicBrandStatsResponse {
+    pub org_id: Principal,
+    pub version: u64,
+    pub not_modified: bool,
+    pub stats: Option<PublicBrandStats>,
+    pub max_age_seconds: u64, // How long the response may be cached
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PublicBrand {
+    pub organization: OrganizationPublic,
+    pub logo_url: Option<String>,
+    pub website_url: Option<String>,
+    pub tagline: Option<String>,
+    pub product_count: u64,
+    pub featured: bool,
+    pub badge: VerifiedBrandBadge,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct PublicBrandsListResponse {
+    pub brands: Vec<PublicBrand>, // Featured brands first, then by name
+    pub pagination: PaginationResponse,
+}
+
+// Brand behind a code typed into the generic verify page. Listing details are only filled in for
+// brands in the public directory.
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct BrandLookupResponse {
+    pub organization: OrganizationPublic,
+    pub product_id: Principal,
+    pub product_name: String,
+    pub logo_url: Option<String>,
+    pub website_url: Option<String>,
+    pub badge: Option<VerifiedBrandBadge>,
+}
+
+// ===== Report API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct CreateReportScheduleRequest {
+    pub org_id: Principal,
+    pub frequency: ReportFrequency,
+    pub delivery_url: String,
+    pub recipient_emails: Vec<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ReportScheduleResponse {
+    pub schedule: ReportSchedule,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListReportsRequest {
+    pub org_id: Principal,
+    pub pagination: Option<PaginationRequest>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ReportsListResponse {
+    pub reports: Vec<Report>, // Newest first
+    pub pagination: PaginationResponse,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct CreateReportShareLinkRequest {
+    pub org_id: Principal,
+    pub filters: ReportFilters,
+    pub ttl_seconds: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ReportShareLinkResponse {
+    pub link: ReportShareLink,
+    pub token: String, // Shown only once; hand it to the partner
+}
+
+// Report as seen through a share link, computed when it is viewed
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SharedReportResponse {
+    pub organization_name: String,
+    pub filters: ReportFilters,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub total_verifications: u64,
+    pub first_verifications: u64,
+    pub multiple_verifications: u64,
+    pub invalid_verifications: u64,
+    pub suspicious_serials: u64,
+    pub top_products: Vec<ReportProductStat>,
+    pub generated_at: u64,
+    pub expires_at: u64, // When the link stops working
+}
+
+// ===== BI Export API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct SetBiExportConfigRequest {
+    pub org_id: Principal,
+    pub endpoint_url: String,
+    pub is_active: bool,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListBiExportDeliveriesRequest {
+    pub org_id: Principal,
+    pub pagination: Option<PaginationRequest>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct BiExportDeliveriesListResponse {
+    pub deliveries: Vec<BiExportDelivery>, // Newest first
+    pub pagination: PaginationResponse,
+}
+
+// ===== Print Job API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct BulkPrintRequest {
+    pub product_id: Principal,
+    pub serial_nos: Vec<Principal>,
+    pub printer_metadata: Vec<Metadata>,
+    pub label_template_version: Option<String>,
+    pub capability_token: Option<String>, // Lets a non-member print with a PrintSerials token
+    pub activation_time: Option<u64>, // Codes do not verify before this time, e.g. the launch date; activate early with activate_print_job_v2. None applies the organization's activation delay; 0 activates right away.
+    pub code_policy: Option<PrintCodePolicy>, // None uses the organization's default
+}
+
+// A printed version of a serial's code. Details are None for versions printed before history was kept.
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SerialPrintHistoryEntry {
+    pub print_version: u8,
+    pub printed_at: Option<u64>,
+    pub printed_by: Option<Principal>,
+    pub print_job_id: Option<Principal>,
+    pub is_current: bool, // Only the current version's code verifies; older ones report SupersededCode
+    pub revocation: Option<CodeRevocation>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SerialPrintHistoryResponse {
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub current_print_version: u8, // 0 when never printed
+    pub versions: Vec<SerialPrintHistoryEntry>, // Newest first
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct BulkPrintResponse {
+    pub print_job: PrintJob,
+    pub codes: Vec<ProductUniqueCodeResultRecord>,
+    pub label_template: Option<LabelTemplate>, // The product's print spec, if one is set
+}
+
+// ===== Verification Delegation API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct DelegateVerificationRequest {
+    pub product_id: Principal,
+    pub manufacturer_org_id: Principal,
+}
+
+// ===== Label Template API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct SetLabelTemplateRequest {
+    pub product_id: Principal,
+    pub width_mm: f64,
+    pub height_mm: f64,
+    pub qr_version: Option<u8>,
+    pub qr_error_correction: QrErrorCorrection,
+    pub qr_placement: QrPlacement,
+    pub show_human_readable_code: bool,
+    pub artwork_asset_url: Option<String>,
+}
+
+// ===== Serial Number Lifecycle API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct UpdateSerialStatusRequest {
+    pub product_id: Principal,
+    pub serial_nos: Vec<Principal>,
+    pub reason: Option<String>, // Kept in the serial's metadata
+    pub capability_token: Option<String>, // Lets a non-member ship or sell with an UpdateSerialStatus token
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SerialStatusUpdateResponse {
+    pub serial_numbers: Vec<ProductSerialNumber>,
+}
+
+// Scan coverage of serials numbered from_seq..=to_seq, e.g. one production lot
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SerialRangeStatsResponse {
+    pub product_id: Principal,
+    pub from_seq: u64,
+    pub to_seq: u64,
+    pub serials_in_range: u64, // Serials that exist with a sequence number in the range
+    pub scanned_serials: u64, // Of those, serials verified at least once
+    pub coverage_percent: f64, // scanned_serials / serials_in_range; 0 for an empty range
+    pub total_verifications: u64,
+    pub first_scan_at: Option<u64>,
+    pub last_scan_at: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct RequestKeyEscrowExportRequest {
+    pub org_id: Principal,
+    pub recipient_public_key: String, // Hex SEC1 secp256k1 public key; only its private key can open the export
+    pub reason: Option<String>,
+}
+
+// Encrypted signing key of an organization; see key_escrow::ENCRYPTION_ALGORITHM for the scheme
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct KeyEscrowExport {
+    pub request_id: Principal,
+    pub org_id: Principal,
+    pub algorithm: String,
+    pub ephemeral_public_key: String, // Hex, uncompressed
+    pub nonce: String, // Hex
+    pub ciphertext: String, // Hex; AES-GCM output including the tag
+}
+
+// What the caller may do, mirroring the checks the endpoints enforce
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct CapabilitiesResponse {
+    pub is_registered: bool,
+    pub is_enabled: bool,
+    pub role: Option<UserRole>,
+    pub is_admin: bool, // Admins hold their permissions in every organization, not just the listed ones
+    pub permissions: Vec<Permission>, // Granted by the role
+    pub features: Vec<FeatureFlag>, // Global flag values
+    pub organizations: Vec<OrgCapabilities>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct OrgCapabilities {
+    pub org_id: Principal,
+    pub permissions: Vec<Permission>,
+    pub features: Vec<FeatureFlag>, // With the organization's overrides applied
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct CreateBundleRequest {
+    pub outer_product_id: Principal,
+    pub outer_serial_no: Principal,
+    pub member_serial_nos: Vec<Principal>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct BundleContentsResponse {
+    pub bundle: Bundle,
+    pub summary: BundleSummary,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SetMetadataSchemaRequest {
+    pub org_id: Principal,
+    pub category: String,
+    pub product_fields: Vec<MetadataFieldSchema>,
+    pub serial_fields: Vec<MetadataFieldSchema>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SetSerialMetadataRequest {
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub metadata: Vec<Metadata>, // Replaces the serial's metadata
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SignPayloadResponse {
+    pub signature: String, // Hex, 64-byte compact ECDSA signature
+    pub public_key: String, // Hex, uncompressed SEC1
+    pub signed_at: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct PublishTermsRequest {
+    pub version: String,
+    pub url: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct TermsStatusResponse {
+    pub current: Option<TermsVersion>, // None until an admin publishes terms
+    pub accepted: bool, // The caller accepted the current version, or there is none
+    pub acceptances: Vec<TermsAcceptance>, // The caller's full consent history
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct OutboxDeliveriesResponse {
+    pub deliveries: Vec<OutboxDelivery>,
+    pub pagination: Option<PaginationResponse>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct AuditLogListResponse {
+    pub entries: Vec<AuditLogEntry>,
+    pub pagination: Option<PaginationResponse>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct SuspiciousActivityResponse {
+    pub suspected_cloned_serials: Vec<ProductSerialNumber>,
+    pub probe_activity: Option<OrgProbeActivity>, // None until a probing principal hits one of the brand's serials
+    pub pagination: Option<PaginationResponse>, // Over suspected_cloned_serials
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct PlatformMetricsResponse {
+    pub cycles: CyclesStatus,
+    pub organizations: u64,
+    pub products: u64,
+    pub users: u64,
+    pub generated_at: u64,
+}
+
+// Latency is measured in instructions, since time does not advance within a call
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct EndpointMetricsSummary {
+    pub endpoint: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate_percent: f64,
+    pub error_codes: Vec<ErrorCodeCount>, // Most frequent first
+    pub p50_instructions: u64, // Over the latest calls, see sampled_calls
+    pub p95_instructions: u64,
+    pub mean_instructions: u64, // Over every call since `since`
+    pub max_instructions: u64,
+    pub sampled_calls: u32,
+    pub since: u64,
+    pub last_called_at: u64,
+}
+
+// Read-only snapshot of a serial for partner apps; every field past `exists` is None/false when it does not
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SerialStatusCheckResponse {
+    pub serial_no: Principal,
+    pub exists: bool,
+    pub print_version: Option<u8>,
+    pub status: Option<SerialNumberStatus>,
+    pub voided: bool,
+    pub code_revoked: bool, // The currently printed version was revoked
+    pub suspected_cloned: bool,
+    pub discontinued: bool, // The owning product is no longer made; recalls are not tracked separately
+    pub product_id: Option<Principal>,
+    pub product_name: Option<String>,
+    pub org_id: Option<Principal>,
+    pub org_name: Option<String>,
+}
+
+// ===== Canister Configuration API Structures =====
+
+// Fields left as None keep their current value
+#[derive(CandidType, Deserialize)]
+pub struct UpdateCanisterConfigRequest {
+    pub llm_provider: Option<String>,
+    pub llm_api_host: Option<String>,
+    pub llm_model: Option<String>,
+    pub llm_api_key: Option<String>,
+    pub scraper_url: Option<String>,
+    pub ledger_canister_id: Option<Principal>,
+    pub email_relay_url: Option<String>, // Empty string disables the relay
+    pub rate_limit: Option<RateLimitConfig>,
+    pub feature_flags: Option<Vec<FeatureFlag>>, // Sets the listed global flags, others are left untouched
+    pub client_version_policy: Option<ClientVersionPolicy>, // An empty min_version removes the requirement
+    pub cycles_alerts: Option<CyclesAlertConfig>, // An empty webhook_url stops alerting
+    pub outcall_limits: Option<OutcallResponseLimits>,
+    pub moderation: Option<ModerationConfig>, // Replaces the blocklist and LLM setting
+    pub live_gateways: Option<Vec<Principal>>, // Replaces the gateway allowlist; empty allows none
+}
+
+// ===== Feature Flag API Structures =====
+
+// Sets the global value, or the organization's override when org_id is given
+#[derive(CandidType, Deserialize)]
+pub struct SetFeatureFlagRequest {
+    pub flag: String,
+    pub enabled: bool,
+    pub org_id: Option<Principal>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct FeatureFlagStatus {
+    pub name: String,
+    pub global_enabled: Option<bool>,
+    pub org_override: Option<bool>,
+    pub effective: bool,
+}
+
+// ===== Quota API Structures =====
+
+// Fields left as None fall back to the platform defaults
+#[derive(CandidType, Deserialize)]
+pub struct SetOrgQuotaRequest {
+    pub org_id: Principal,
+    pub max_outcalls_per_day: Option<u32>,
+    pub max_serials_per_month: Option<u32>,
+    pub max_storage_bytes: Option<u64>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgUsageResponse {
+    pub org_id: Principal,
+    pub outcalls_today: u32,
+    pub outcalls_limit: u32,
+    pub outcalls_window_resets_at: u64,
+    pub oversized_responses_today: u32, // Outcalls whose response was larger than the integration's limit
+    pub serials_this_month: u32,
+    pub serials_limit: u32,
+    pub serials_window_resets_at: u64,
+    pub storage_bytes: u64,
+    pub storage_limit: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PlanLimits {
+    pub max_products: Option<u64>, // None: unlimited
+    pub max_serials_per_month: u32,
+    pub analytics_retention_days: u32,
+    pub max_webhooks: Option<u32>, // Notification rules across the organization; None: unlimited
+}
+
+// Usage next to the limits that apply to it. serials_limit includes any admin quota override.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PlanUsageResponse {
+    pub plan: OrgPlan,
+    pub effective_tier: PlanTier, // Free while the plan is cancelled
+    pub limits: PlanLimits,
+    pub products: u64,
+    pub serials_this_month: u32,
+    pub serials_limit: u32,
+    pub serials_window_resets_at: u64,
+    pub webhooks: u32,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SetVerificationRetentionRequest {
+    pub org_id: Principal,
+    pub retention_months: Option<u32>,
+    pub handling: RetiredVerificationHandling,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ArchivedAggregatesQuery {
+    pub org_id: Principal,
+    pub product_id: Option<Principal>,
+    pub from_month: Option<u32>, // YYYYMM, inclusive
+    pub to_month: Option<u32>, // YYYYMM, inclusive
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct VerificationRollupResult {
+    pub rolled_up: u64,
+    pub archived: u64,
+    pub deleted: u64,
+    pub complete: bool, // false: the per-run limit was reached and the next run continues
+}
+
+// ===== Background Job API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub enum JobRequest {
+    SerialImport {
+        product_id: Principal,
+        variant_id: Option<Principal>,
+        user_serial_prefix: Option<String>,
+        count: u32,
+    },
+    VerificationExport {
+        product_ids: Option<Vec<Principal>>, // None exports every product of the organization except test products
+        period_start: Option<u64>, // None: as far back as the plan keeps analytics
+        period_end: Option<u64>, // None: the moment the job is submitted
+    },
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SubmitJobRequest {
+    pub org_id: Principal,
+    pub job: JobRequest,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct JobStatusResponse {
+    pub job: Job,
+    pub progress_percent: u8,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct JobResultChunkResponse {
+    pub job_id: Principal,
+    pub chunk_index: u32,
+    pub columns: Vec<String>,
+    pub rows: Vec<String>, // Comma-separated, in the order of `columns`
+    pub total_chunks: u32, // Written so far; grows while the job runs
+    pub is_last: bool, // The job has finished and no chunk follows this one
+}
+
+// ===== Capability Token API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct MintCapabilityTokenRequest {
+    pub org_id: Principal,
+    pub holder: Principal,
+    pub operations: Vec<CapabilityOperation>,
+    pub product_ids: Vec<Principal>,
+    pub ttl_seconds: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct MintCapabilityTokenResponse {
+    pub token: String, // Shown only once; pass it as `capability_token` on the calls it covers
+    pub record: CapabilityToken,
+}
+
+// ===== Product Shelf API Structures =====
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarrantyStatus {
+    NotOffered,
+    Active,
+    Expired,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MyProductEntry {
+    pub serial_no: Principal,
+    pub user_serial_no: Option<String>,
+    pub product_id: Principal,
+    pub product_name: String,
+    pub category: String,
+    pub org_id: Principal,
+    pub org_name: Option<String>,
+    pub registered_at: u64,
+    pub warranty_status: WarrantyStatus,
+    pub warranty_expires_at: Option<u64>,
+    pub recall: Option<ProductRecall>, // Set while the product is recalled
+    pub discontinued: bool,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SetProductRecallRequest {
+    pub product_id: Principal,
+    pub reason: String,
+    pub instructions: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct ProductOwnershipCount {
+    pub product_id: Principal,
+    pub product_name: String,
+    pub registered_owners: u64,
+}
+
+// Shelf registrations of an organization's products, aggregated so no owner can be identified
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct OwnershipStatsResponse {
+    pub org_id: Principal,
+    pub total_registered: u64,
+    pub products: Vec<ProductOwnershipCount>, // Most registered first; products without registrations are left out
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct StartRecallCampaignRequest {
+    pub product_id: Principal,
+    pub scope: RecallScope, // PrintJob or Serials; whole products are recalled with set_product_recall_v2
+    pub reason: String,
+    pub instructions: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct RecallCampaignStatusResponse {
+    pub campaign: RecallCampaign,
+    pub notices_sent: u64,
+    pub inbox_read: u64,
+    pub relay_queued: u64,
+    pub relay_delivered: u64,
+    pub relay_failed: u64,
+    pub relay_skipped: u64, // No email on file, no relay configured, or no outcall quota left
+    pub owners_reached: u64, // Read the inbox notice or had the email delivered
+    pub reach_percent: u8, // owners_reached out of the campaign's affected owners
+}
+
+// ===== Status Message API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct SetStatusMessageRequest {
+    pub status: ProductVerificationStatus,
+    pub locale: String,
+    pub text: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct RemoveStatusMessageRequest {
+    pub status: ProductVerificationStatus,
+    pub locale: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct StatusDescriptionResponse {
+    pub status: ProductVerificationStatus,
+    pub locale: String, // Locale the message is in, which falls back to the language and then to English
+    pub message: String,
+}
+
+// ===== Verification Domain API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct RegisterVerificationDomainRequest {
+    pub org_id: Principal,
+    pub domain: String, // e.g. "verify.brand.com"
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct VerificationDomainResponse {
+    pub domain: VerificationDomain,
+    pub proof_record_name: String, // Where to publish the TXT record
+    pub proof_record_value: String, // What the TXT record must contain
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SetPostVerificationMessageRequest {
+    pub org_id: Principal,
+    pub product_id: Option<Principal>, // None sets the organization-wide default
+    pub template: String,
+    pub cta_label: Option<String>,
+    pub cta_url: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct IntegrityFailure {
+    pub key: Principal,
+    pub sub_key: Option<IntegritySubKey>,
+    pub size_bytes: u64,
+    pub error: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct IntegrityScanReport {
+    pub store: IntegrityStore,
+    pub scanned: u64,
+    pub failures: Vec<IntegrityFailure>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvariantKind {
+    ProductOrganizationMissing,
+    SerialsProductMissing, // Serial list stored under a product ID that does not exist
+    SerialProductMismatch, // Serial whose product_id differs from the product it is stored under
+    VerificationsProductMissing,
+    ResellerOrganizationMissing,
+    ResellerUserMissing,
+    UserOrganizationMissing,
+    SessionKeyConflict, // Session key held by two users, or equal to another user's ID
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct InvariantViolation {
+    pub kind: InvariantKind,
+    pub entity_id: Principal,
+    pub referenced_id: Principal,
+    pub detail: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct InvariantReport {
+    pub checked_at: u64,
+    pub violation_count: u64, // May exceed violations.len(), which is capped
+    pub violations: Vec<InvariantViolation>,
+}
+
+// ===== Reseller Product Authorization API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct UpdateResellerProductsRequest {
+    pub reseller_id: Principal,
+    pub product_ids: Vec<Principal>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ResellerProductsResponse {
+    pub reseller_id: Principal,
+    pub restricted: bool, // false: authorized for every product of the organization
+    pub product_ids: Vec<Principal>,
+    pub updated_at: Option<u64>,
+}
+
+// ===== Live Event API Structures =====
+
+// Event streams a dashboard connection can follow; an organization's connections only see its own events
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LiveTopic {
+    Verifications,
+    Counterfeits, // Invalid codes, suspected clones and grey market scans
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterfeitSignal {
+    InvalidCode,
+    SuspectedClone,
+    GreyMarket,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum LiveEvent {
+    Subscribed {
+        topics: Vec<LiveTopic>,
+    },
+    Verification {
+        product_id: Principal,
+        serial_no: Principal,
+        status: ProductVerificationStatus,
+        country_code: Option<String>,
+        recorded_at: u64,
+    },
+    Counterfeit {
+        signal: CounterfeitSignal,
+        product_id: Principal,
+        serial_no: Principal,
+        country_code: Option<String>,
+        detected_at: u64,
+    },
+    Closed {
+        reason: String,
+    },
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct WsOpenRequest {
+    pub gateway: Principal, // Gateway relaying the connection, one the admins allowed; only it can poll the connection's messages
+    pub org_id: Principal,
+    pub topics: Vec<LiveTopic>, // Empty follows every topic
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct WsOpenResponse {
+    pub connection_id: u64,
+    pub topics: Vec<LiveTopic>,
+    pub idle_timeout_seconds: u64, // Send a Ping more often than this to keep the connection
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum WsClientMessage {
+    Subscribe(Vec<LiveTopic>), // Replaces the connection's topics; empty follows every topic
+    Ping,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct WsMessageRequest {
+    pub connection_id: u64,
+    pub message: WsClientMessage,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct WsOutgoingMessage {
+    pub seq: u64, // Increasing per gateway
+    pub connection_id: u64,
+    pub client: Principal,
+    pub event: LiveEvent,
+    pub sent_at: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct WsGetMessagesResponse {
+    pub messages: Vec<WsOutgoingMessage>,
+    pub next_seq: u64, // Pass as from_seq on the next poll
+}
+
+// ===== Admin Approval API Structures =====
+
+#[derive(CandidType, Deserialize)]
+pub struct ProposeAdminOperationRequest {
+    pub kind: AdminOperationKind,
+    pub reason: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct AdminOperationDecisionRequest {
+    pub operation_id: Principal,
+    pub note: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct AdminOperationsResponse {
+    pub operations: Vec<AdminOperation>,
+    pub pagination: Option<PaginationResponse>,
+}
@@ -0,0 +1,353 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::config;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, USERS};
+use crate::models::{AdminApproval, AdminOperation, AdminOperationKind, AdminOperationStatus, User, UserRole};
+
+// Distinct admins that must approve a destructive operation, the proposer included, until the admins
+// agree on another number. With fewer admins than this, all of them must approve.
+pub const DEFAULT_REQUIRED_APPROVALS: u32 = 2;
+// A proposal that has not gathered its approvals within this window lapses and has to be proposed again
+pub const APPROVAL_WINDOW_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_NOTE_LENGTH: usize = 500;
+
+// Define a unique MemoryId for this structure
+const ADMIN_OPERATIONS_MEM_ID: MemoryId = MemoryId::new(90);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Every proposal, decided or not, so the approval trail survives
+    static ADMIN_OPERATIONS: RefCell<StableBTreeMap<Principal, AdminOperation, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ADMIN_OPERATIONS_MEM_ID))
+        )
+    );
+}
+
+// A pending operation past its window reads as expired, whether or not that was saved yet
+fn with_expiry(mut operation: AdminOperation, now: u64) -> AdminOperation {
+    if operation.status == AdminOperationStatus::Pending && now >= operation.expires_at {
+        operation.status = AdminOperationStatus::Expired;
+    }
+    operation
+}
+
+pub fn get(operation_id: Principal, now: u64) -> Option<AdminOperation> {
+    ADMIN_OPERATIONS
+        .with(|operations| operations.borrow().get(&operation_id))
+        .map(|operation| with_expiry(operation, now))
+}
+
+fn save(operation: AdminOperation) {
+    ADMIN_OPERATIONS.with(|operations| {
+        operations.borrow_mut().insert(operation.id, operation);
+    });
+}
+
+// Newest first
+pub fn list(status: Option<AdminOperationStatus>, now: u64) -> Vec<AdminOperation> {
+    let mut operations: Vec<AdminOperation> = ADMIN_OPERATIONS.with(|operations| {
+        operations
+            .borrow()
+            .iter()
+            .map(|(_, operation)| with_expiry(operation, now))
+            .filter(|operation| status.is_none_or(|s| operation.status == s))
+            .collect()
+    });
+    operations.sort_by_key(|a| std::cmp::Reverse(a.proposed_at));
+    operations
+}
+
+// The pending operation of this kind, if one was proposed and is still in its window
+pub fn open_for_kind(kind: &AdminOperationKind, now: u64) -> Option<AdminOperation> {
+    list(Some(AdminOperationStatus::Pending), now).into_iter().find(|operation| operation.kind == *kind)
+}
+
+// Approvals a new operation of this kind needs. The admins there are can always lower a configured
+// threshold they can no longer reach, e.g. after one of them was disabled.
+pub fn required_approvals(kind: &AdminOperationKind) -> u32 {
+    let admins = enabled_admin_count().max(1);
+    match config::get_config().required_admin_approvals {
+        None => DEFAULT_REQUIRED_APPROVALS.min(admins),
+        Some(required) if matches!(kind, AdminOperationKind::SetRequiredApprovals { .. }) => required.min(admins),
+        Some(required) => required,
+    }
+}
+
+// A new threshold must be reachable by the admins there are now
+pub fn validate_required_approvals(required: u32) -> Result<(), ApiError> {
+    let admins = enabled_admin_count();
+    if required == 0 || required > admins {
+        return Err(ApiError::invalid_input(&format!(
+            "Required approvals must be between 1 and the {} enabled admin(s)",
+            admins
+        )));
+    }
+    Ok(())
+}
+
+// Admins able to approve: enabled users with the Admin role
+fn is_enabled_admin(user: &User) -> bool {
+    user.is_enabled && matches!(user.user_role, Some(UserRole::Admin))
+}
+
+pub fn enabled_admin_count() -> u32 {
+    USERS.with(|users| users.borrow().iter().filter(|(_, user)| is_enabled_admin(user)).count() as u32)
+}
+
+// Whether the operation has its approvals, counting only admins who are still enabled admins now
+pub fn is_ready(operation: &AdminOperation) -> bool {
+    let current = USERS.with(|users| {
+        let users = users.borrow();
+        operation
+            .approvals
+            .iter()
+            .filter(|approval| users.get(&approval.admin_id).is_some_and(|user| is_enabled_admin(&user)))
+            .count() as u32
+    });
+    current >= operation.required_approvals
+}
+
+fn normalize_note(note: Option<String>) -> Result<Option<String>, ApiError> {
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    if note.as_ref().is_some_and(|n| n.chars().count() > MAX_NOTE_LENGTH) {
+        return Err(ApiError::invalid_input(&format!(
+            "Note must be at most {} characters",
+            MAX_NOTE_LENGTH
+        )));
+    }
+    Ok(note)
+}
+
+// Open a proposal, approved by its proposer. Fails when there are too few admins to ever approve it.
+pub fn propose(
+    id: Principal,
+    kind: AdminOperationKind,
+    reason: Option<String>,
+    admin_id: Principal,
+    now: u64,
+) -> Result<AdminOperation, ApiError> {
+    let reason = normalize_note(reason)?;
+    if open_for_kind(&kind, now).is_some() {
+        return Err(ApiError::already_exists("This operation is already waiting for approval"));
+    }
+    let required = required_approvals(&kind);
+    let admins = enabled_admin_count();
+    if admins < required {
+        return Err(ApiError::invalid_input(&format!(
+            "This operation needs {} admins to approve it, but only {} enabled admin(s) exist",
+            required, admins
+        )));
+    }
+
+    let operation = AdminOperation {
+        id,
+        kind,
+        reason,
+        status: AdminOperationStatus::Pending,
+        required_approvals: required,
+        approvals: vec![AdminApproval {
+            admin_id,
+            approved_at: now,
+            note: None,
+        }],
+        proposed_by: admin_id,
+        proposed_at: now,
+        expires_at: now.saturating_add(APPROVAL_WINDOW_NS),
+        rejected_by: None,
+        rejected_at: None,
+        rejection_note: None,
+        executed_at: None,
+        result: None,
+    };
+    save(operation.clone());
+    Ok(operation)
+}
+
+fn get_pending(operation_id: Principal, now: u64) -> Result<AdminOperation, ApiError> {
+    let operation = get(operation_id, now).ok_or_else(|| ApiError::not_found("Admin operation not found"))?;
+    match operation.status {
+        AdminOperationStatus::Pending => Ok(operation),
+        AdminOperationStatus::Expired => {
+            save(operation);
+            Err(ApiError::invalid_input("The approval window of this operation has passed; propose it again"))
+        }
+        _ => Err(ApiError::invalid_input("This operation has already been decided")),
+    }
+}
+
+// Add an admin's approval. Returns the operation and whether it now has all the approvals it needs;
+// the caller runs it and records the outcome with `finish`.
+pub fn approve(operation_id: Principal, admin_id: Principal, note: Option<String>, now: u64) -> Result<(AdminOperation, bool), ApiError> {
+    let note = normalize_note(note)?;
+    let mut operation = get_pending(operation_id, now)?;
+    if operation.approvals.iter().any(|a| a.admin_id == admin_id) {
+        return Err(ApiError::invalid_input("You have already approved this operation; another admin has to"));
+    }
+    operation.approvals.push(AdminApproval {
+        admin_id,
+        approved_at: now,
+        note,
+    });
+    let ready = is_ready(&operation);
+    save(operation.clone());
+    Ok((operation, ready))
+}
+
+// Any one admin, the proposer included, can turn a pending operation down
+pub fn reject(operation_id: Principal, admin_id: Principal, note: Option<String>, now: u64) -> Result<AdminOperation, ApiError> {
+    let note = normalize_note(note)?;
+    let mut operation = get_pending(operation_id, now)?;
+    operation.status = AdminOperationStatus::Rejected;
+    operation.rejected_by = Some(admin_id);
+    operation.rejected_at = Some(now);
+    operation.rejection_note = note;
+    save(operation.clone());
+    Ok(operation)
+}
+
+// Record how a fully approved operation ran. Saves the record even if the operation wiped the store,
+// so a storage reset leaves behind the approvals that allowed it.
+pub fn finish(mut operation: AdminOperation, outcome: &Result<String, ApiError>, now: u64) -> AdminOperation {
+    operation.executed_at = Some(now);
+    match outcome {
+        Ok(summary) => {
+            operation.status = AdminOperationStatus::Executed;
+            operation.result = Some(summary.clone());
+        }
+        Err(e) => {
+            operation.status = AdminOperationStatus::Failed;
+            operation.result = Some(format!("{:?}", e));
+        }
+    }
+    save(operation.clone());
+    operation
+}
+
+// Reset ALL admin operations (use with caution)
+pub fn reset_admin_operations() {
+    ADMIN_OPERATIONS.with(|operations| {
+        let mut operations_mut = operations.borrow_mut();
+        let keys: Vec<_> = operations_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            operations_mut.remove(&key);
+        }
+    });
+    log_info!("All admin operations have been reset.");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::clock::{self, MockClock};
+    use crate::models::CanisterConfig;
+
+    const NOW: u64 = 1_700_000_000_000_000_000;
+
+    fn add_admin(seed: u8) -> Principal {
+        let id = Principal::from_slice(&[seed]);
+        USERS.with(|users| {
+            users.borrow_mut().insert(
+                id,
+                User {
+                    id,
+                    user_role: Some(UserRole::Admin),
+                    is_principal: true,
+                    is_enabled: true,
+                    org_ids: Vec::new(),
+                    active_org_id: None,
+                    first_name: None,
+                    last_name: None,
+                    phone_no: None,
+                    email: None,
+                    detail_meta: Vec::new(),
+                    session_keys: Vec::new(),
+                    created_at: 0,
+                    created_by: id,
+                    updated_at: 0,
+                    updated_by: id,
+                },
+            )
+        });
+        id
+    }
+
+    fn disable(id: Principal) {
+        USERS.with(|users| {
+            let mut users = users.borrow_mut();
+            let mut user = users.get(&id).unwrap();
+            user.is_enabled = false;
+            users.insert(id, user);
+        });
+    }
+
+    fn propose_reset(admin_id: Principal) -> Result<AdminOperation, ApiError> {
+        propose(Principal::from_slice(&[100]), AdminOperationKind::ResetStorage, None, admin_id, NOW)
+    }
+
+    #[test]
+    fn lone_admin_runs_operations_on_their_own_approval() {
+        let admin = add_admin(1);
+        let operation = propose_reset(admin).unwrap();
+        assert_eq!(operation.required_approvals, 1);
+        assert!(is_ready(&operation));
+    }
+
+    #[test]
+    fn two_admins_both_have_to_approve() {
+        let proposer = add_admin(1);
+        let approver = add_admin(2);
+        let operation = propose_reset(proposer).unwrap();
+        assert_eq!(operation.required_approvals, DEFAULT_REQUIRED_APPROVALS);
+        assert!(!is_ready(&operation));
+
+        assert!(approve(operation.id, proposer, None, NOW).is_err());
+        let (_, ready) = approve(operation.id, approver, None, NOW).unwrap();
+        assert!(ready);
+    }
+
+    #[test]
+    fn approvals_of_admins_disabled_since_do_not_count() {
+        let proposer = add_admin(1);
+        let second = add_admin(2);
+        let third = add_admin(3);
+        let operation = propose_reset(proposer).unwrap();
+        disable(proposer);
+
+        let (_, ready) = approve(operation.id, second, None, NOW).unwrap();
+        assert!(!ready);
+        let (_, ready) = approve(operation.id, third, None, NOW).unwrap();
+        assert!(ready);
+    }
+
+    #[test]
+    fn remaining_admin_can_lower_an_unreachable_threshold() {
+        clock::set_clock(Rc::new(MockClock::new(NOW)));
+        let admin = add_admin(1);
+        config::set_config(CanisterConfig {
+            required_admin_approvals: Some(2),
+            ..config::get_config()
+        })
+        .unwrap();
+
+        assert!(propose_reset(admin).is_err());
+        let lower = propose(
+            Principal::from_slice(&[101]),
+            AdminOperationKind::SetRequiredApprovals { required: 1 },
+            None,
+            admin,
+            NOW,
+        )
+        .unwrap();
+        assert!(is_ready(&lower));
+    }
+}
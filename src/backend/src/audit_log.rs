@@ -0,0 +1,70 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use candid::{decode_one, encode_one, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+use crate::auth::AuditLogEntry;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+
+// Define a unique MemoryId for this structure
+const AUDIT_LOG_MEM_ID: MemoryId = MemoryId::new(45);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+impl Storable for AuditLogEntry {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+thread_local! {
+    // Keyed by a sequence number, so iteration is in the order entries were written
+    static AUDIT_LOG: RefCell<StableBTreeMap<u64, AuditLogEntry, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(AUDIT_LOG_MEM_ID))
+        )
+    );
+}
+
+// Persist an entry. Only deliberate state changes are recorded; routine access checks stay in the log buffer.
+pub fn record(entry: AuditLogEntry) {
+    AUDIT_LOG.with(|log| {
+        let mut log_mut = log.borrow_mut();
+        let next = log_mut.last_key_value().map_or(0, |(seq, _)| seq + 1);
+        log_mut.insert(next, entry);
+    });
+}
+
+// Entries about one resource, newest first
+pub fn list_for_resource(resource_type: &str, resource_id: Principal) -> Vec<AuditLogEntry> {
+    let mut entries: Vec<AuditLogEntry> = AUDIT_LOG.with(|log| {
+        log.borrow()
+            .iter()
+            .map(|(_, entry)| entry)
+            .filter(|entry| entry.resource_type == resource_type && entry.resource_id == resource_id)
+            .collect()
+    });
+    entries.reverse();
+    entries
+}
+
+// Reset the audit log (use with caution)
+pub fn reset_audit_log() {
+    AUDIT_LOG.with(|log| {
+        let mut log_mut = log.borrow_mut();
+        let keys: Vec<_> = log_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            log_mut.remove(&key);
+        }
+    });
+    log_info!("The audit log has been reset.");
+}
@@ -2,11 +2,14 @@ use candid::{CandidType, Principal, Deserialize};
 use serde::Serialize;
 use std::collections::HashSet;
 
+use crate::audit_log;
+use crate::capability_tokens;
+use crate::clock;
 use crate::error::ApiError;
 use crate::global_state::{ORGANIZATIONS, PRODUCTS, USERS};
-use crate::models::{Metadata, Organization, UserRole};
+use crate::models::{CapabilityClaims, CapabilityOperation, Metadata, Organization, Product, ReadonlyPrincipal, UserRole};
 use crate::models::User;
-use ic_cdk::api;
+use crate::readonly_principals;
 use std::convert::TryInto;
 
 // Define permission types
@@ -22,6 +25,10 @@ pub enum Permission {
     WriteReseller,
     ManageVerifications,
     AdminAccess,
+    VerifyProduct,
+    RedeemRewards,
+    ReadSelf,
+    WriteSelf,
 }
 
 // Define audit log entry
@@ -53,6 +60,10 @@ pub fn get_role_permissions(role: &UserRole) -> HashSet<Permission> {
             permissions.insert(Permission::WriteReseller);
             permissions.insert(Permission::ManageVerifications);
             permissions.insert(Permission::AdminAccess);
+            permissions.insert(Permission::VerifyProduct);
+            permissions.insert(Permission::RedeemRewards);
+            permissions.insert(Permission::ReadSelf);
+            permissions.insert(Permission::WriteSelf);
         },
         UserRole::BrandOwner => {
             // Brand owners can manage their own organizations and products
@@ -65,6 +76,9 @@ pub fn get_role_permissions(role: &UserRole) -> HashSet<Permission> {
             permissions.insert(Permission::ReadReseller);
             permissions.insert(Permission::WriteReseller);
             permissions.insert(Permission::ManageVerifications);
+            permissions.insert(Permission::VerifyProduct);
+            permissions.insert(Permission::ReadSelf);
+            permissions.insert(Permission::WriteSelf);
         },
         UserRole::Reseller => {
             // Resellers have limited permissions
@@ -72,18 +86,64 @@ pub fn get_role_permissions(role: &UserRole) -> HashSet<Permission> {
             permissions.insert(Permission::ReadProduct);
             permissions.insert(Permission::ReadReseller);
             permissions.insert(Permission::ManageVerifications);
+            permissions.insert(Permission::VerifyProduct);
+            permissions.insert(Permission::ReadSelf);
+            permissions.insert(Permission::WriteSelf);
         },
         UserRole::Customer => {
-            permissions.insert(Permission::ReadProduct); 
-            // Add any other specific customer permissions here if needed in the future
+            // Customers only verify products, redeem rewards and manage their own profile
+            permissions.insert(Permission::VerifyProduct);
+            permissions.insert(Permission::RedeemRewards);
+            permissions.insert(Permission::ReadSelf);
+            permissions.insert(Permission::WriteSelf);
         }
     }
     
     permissions
 }
 
+// Every permission, in the order they are reported to clients
+const ALL_PERMISSIONS: [Permission; 14] = [
+    Permission::ReadOrganization,
+    Permission::WriteOrganization,
+    Permission::ReadProduct,
+    Permission::WriteProduct,
+    Permission::ReadUser,
+    Permission::WriteUser,
+    Permission::ReadReseller,
+    Permission::WriteReseller,
+    Permission::ManageVerifications,
+    Permission::AdminAccess,
+    Permission::VerifyProduct,
+    Permission::RedeemRewards,
+    Permission::ReadSelf,
+    Permission::WriteSelf,
+];
+
+// All a read-only principal may do in its organization: the analytics and list queries.
+// User records are left out on purpose, as they hold contact details.
+pub const READONLY_PRINCIPAL_PERMISSIONS: [Permission; 3] = [
+    Permission::ReadOrganization,
+    Permission::ReadProduct,
+    Permission::ReadReseller,
+];
+
+// Permissions the checks above would grant the user: none when disabled, a Customer's when role-less
+pub fn effective_permissions(user: &User) -> Vec<Permission> {
+    if !user.is_enabled {
+        return Vec::new();
+    }
+    let permissions = permissions_for(user);
+    ALL_PERMISSIONS.iter().filter(|p| permissions.contains(*p)).cloned().collect()
+}
+
+// A registered user who has not picked a role yet is a consumer, with a Customer's permissions
+fn permissions_for(user: &User) -> HashSet<Permission> {
+    get_role_permissions(user.user_role.as_ref().unwrap_or(&UserRole::Customer))
+}
+
 // Helper function to find user by session key or direct principal
-fn find_user_by_caller(caller_principal: Principal) -> Option<User> {
+pub fn find_user_by_caller(caller_principal: Principal) -> Option<User> {
     // 1. Try direct lookup (caller might be the root principal)
     let direct_user = USERS.with(|users| users.borrow().get(&caller_principal).clone());
     if direct_user.is_some() {
@@ -95,7 +155,7 @@ fn find_user_by_caller(caller_principal: Principal) -> Option<User> {
     USERS.with(|users| {
         users.borrow().iter().find_map(|(_, user)| {
             if user.session_keys.contains(&caller_principal) {
-                ic_cdk::print(format!("ℹ️ [find_user_by_caller] Found user {} via session key {}", user.id, caller_principal));
+                log_debug!("[find_user_by_caller] Found user {} via session key {}", user.id, caller_principal);
                 Some(user.clone())
             } else {
                 None
@@ -104,40 +164,59 @@ fn find_user_by_caller(caller_principal: Principal) -> Option<User> {
     })
 }
 
+pub fn ensure_enabled(user: &User) -> Result<(), ApiError> {
+    if !user.is_enabled {
+        log_error!("[ensure_enabled] User {} is disabled.", user.id);
+        return Err(ApiError::account_disabled("User account is disabled"));
+    }
+    Ok(())
+}
+
+// Registered callers need VerifyProduct; unregistered callers verify like guests
+pub fn ensure_can_verify(caller: Principal) -> Result<(), ApiError> {
+    match find_user_by_caller(caller) {
+        Some(_) => check_permission(caller, &Permission::VerifyProduct),
+        None => Ok(()),
+    }
+}
+
+// Fails only for callers with a disabled account; unregistered callers pass
+pub fn ensure_caller_enabled(caller: Principal) -> Result<(), ApiError> {
+    match find_user_by_caller(caller) {
+        Some(user) => ensure_enabled(&user),
+        None => Ok(()),
+    }
+}
+
 // Check if user has required permission - uses find_user_by_caller
 pub fn check_permission(user_id: Principal, required_permission: &Permission) -> Result<(), ApiError> {
     let caller_principal = user_id; // user_id passed is api::caller()
-    ic_cdk::print(format!("ℹ️ [check_permission] Checking permission for caller: {} for permission: {:?}", caller_principal, required_permission)); 
+    log_debug!("[check_permission] Checking permission for caller: {} for permission: {:?}", caller_principal, required_permission); 
     
     let user_opt = find_user_by_caller(caller_principal);
-    ic_cdk::print(format!("ℹ️ [check_permission] User lookup result for caller {}: {:?}", caller_principal, user_opt.is_some()));
+    log_debug!("[check_permission] User lookup result for caller {}: {:?}", caller_principal, user_opt.is_some());
     
     if user_opt.is_none() {
-        ic_cdk::print(format!("❌ ERROR [check_permission] User NOT FOUND for caller: {}", caller_principal)); 
+        log_error!("[check_permission] User NOT FOUND for caller: {}", caller_principal); 
         return Err(ApiError::not_found("User not found or session key invalid!")); // Modified error
     }
     
     let user = user_opt.unwrap();
-    ic_cdk::print(format!("ℹ️ [check_permission] Found user record with ID: {}", user.id));
-    
-    // Check if user has a role
-    if user.user_role.is_none() {
-        ic_cdk::print(format!("❌ ERROR [check_permission] User {} has no role assigned.", user.id)); 
-        return Err(ApiError::unauthorized("User has no assigned role"));
-    }
-    let user_role = user.user_role.unwrap(); // Safe to unwrap here
+    log_debug!("[check_permission] Found user record with ID: {}", user.id);
+    ensure_enabled(&user)?;
     
-    // Get permissions for the user's role
-    let permissions = get_role_permissions(&user_role);
-    ic_cdk::print(format!("ℹ️ [check_permission] Permissions for user {} (Role: {:?}): {:?}", user.id, user_role, permissions)); 
+    // Role-less users get a Customer's permissions
+    let user_role = user.user_role.unwrap_or(UserRole::Customer);
+    let permissions = permissions_for(&user);
+    log_debug!("[check_permission] Permissions for user {} (Role: {:?}): {:?}", user.id, user_role, permissions); 
     
     // Check if the user has the required permission
     if !permissions.contains(required_permission) {
-         ic_cdk::print(format!("❌ ERROR [check_permission] User {} (Role: {:?}) lacks required permission: {:?}", user.id, user_role, required_permission)); 
+         log_error!("[check_permission] User {} (Role: {:?}) lacks required permission: {:?}", user.id, user_role, required_permission); 
         return Err(ApiError::unauthorized(&format!("User lacks permission: {:?}", required_permission)));
     }
     
-    ic_cdk::print(format!("✅ [check_permission] User {} (Role: {:?}) has required permission: {:?}", user.id, user_role, required_permission)); 
+    log_debug!("[check_permission] User {} (Role: {:?}) has required permission: {:?}", user.id, user_role, required_permission); 
     Ok(())
 }
 
@@ -148,35 +227,41 @@ pub fn authorize_for_organization(
     permission: Permission
 ) -> Result<Organization, ApiError> {
     let caller_principal = user_id; // user_id passed is api::caller()
-    ic_cdk::print(format!("ℹ️ [authorize_for_organization] Authorizing caller: {} for org: {} with permission: {:?}", caller_principal, org_id, permission)); 
+    log_debug!("[authorize_for_organization] Authorizing caller: {} for org: {} with permission: {:?}", caller_principal, org_id, permission); 
     
-    let user = find_user_by_caller(caller_principal)
-        .ok_or_else(|| {
-            ic_cdk::print(format!("❌ ERROR [authorize_for_organization] User NOT FOUND for caller: {}", caller_principal)); 
-            ApiError::not_found("User not found or session key invalid!")
-        })?;    
-    ic_cdk::print(format!("ℹ️ [authorize_for_organization] Found user record ID: {} for caller {}", user.id, caller_principal));
+    let user = match find_user_by_caller(caller_principal) {
+        Some(user) => user,
+        None => {
+            if let Some(grant) = readonly_principals::get_active(caller_principal) {
+                return authorize_readonly_principal(&grant, org_id, &permission);
+            }
+            log_error!("[authorize_for_organization] User NOT FOUND for caller: {}", caller_principal);
+            return Err(ApiError::not_found("User not found or session key invalid!"));
+        }
+    };
+    log_debug!("[authorize_for_organization] Found user record ID: {} for caller {}", user.id, caller_principal);
+    ensure_enabled(&user)?;
 
     let user_role = user.user_role.ok_or_else(|| {
-        ic_cdk::print(format!("❌ ERROR [authorize_for_organization] User {} has no role.", user.id));
+        log_error!("[authorize_for_organization] User {} has no role.", user.id);
         ApiError::unauthorized("User has no assigned role")
     })?; 
     let permissions = get_role_permissions(&user_role);
     if !permissions.contains(&permission) {
-        ic_cdk::print(format!("❌ ERROR [authorize_for_organization] User {} (Role: {:?}) lacks required permission: {:?}", user.id, user_role, permission));
+        log_error!("[authorize_for_organization] User {} (Role: {:?}) lacks required permission: {:?}", user.id, user_role, permission);
        return Err(ApiError::unauthorized(&format!("User lacks permission: {:?}", permission)));
     }
-    ic_cdk::print(format!("ℹ️ [authorize_for_organization] User {} (Role: {:?}) has required permission: {:?}. Checking org association...", user.id, user_role, permission));
+    log_debug!("[authorize_for_organization] User {} (Role: {:?}) has required permission: {:?}. Checking org association...", user.id, user_role, permission);
     
     let organization_opt = ORGANIZATIONS.with(|orgs_refcell| orgs_refcell.borrow().get(&org_id).clone());
     if organization_opt.is_none() {
-        ic_cdk::print(format!("❌ ERROR [authorize_for_organization] Organization not found: {}", org_id)); 
+        log_error!("[authorize_for_organization] Organization not found: {}", org_id); 
         return Err(ApiError::not_found("Organization not found!"));
     }
     let organization = organization_opt.unwrap();
     
     if user_role != UserRole::Admin && !user.org_ids.contains(&org_id) {
-        ic_cdk::print(format!("❌ ERROR [authorize_for_organization] User {} (Role: {:?}) is not associated with org {}", user.id, user_role, org_id)); 
+        log_error!("[authorize_for_organization] User {} (Role: {:?}) is not associated with org {}", user.id, user_role, org_id); 
         return Err(ApiError::unauthorized("User is not authorized for this organization!"));
     }
     
@@ -185,15 +270,122 @@ pub fn authorize_for_organization(
         action: format!("Access with permission: {:?}", permission),
         resource_type: "Organization".to_string(),
         resource_id: org_id,
-        timestamp: api::time(),
+        timestamp: clock::now(),
         metadata: vec![],
         success: true,
     };
-    ic_cdk::print(format!("✅ [authorize_for_organization] Authorization successful for caller {} (User ID: {}) on org {}", caller_principal, user.id, org_id)); 
+    log_debug!("[authorize_for_organization] Authorization successful for caller {} (User ID: {}) on org {}", caller_principal, user.id, org_id); 
     
     Ok(organization) // Return the organization (already cloned)
 }
 
+// Read-only principals are not users: they pass only for read permissions in their own organization
+fn authorize_readonly_principal(
+    grant: &ReadonlyPrincipal,
+    org_id: Principal,
+    permission: &Permission,
+) -> Result<Organization, ApiError> {
+    if !READONLY_PRINCIPAL_PERMISSIONS.contains(permission) {
+        log_error!("[authorize_readonly_principal] Read-only principal {} denied {:?}", grant.principal, permission);
+        return Err(ApiError::unauthorized("Read-only principals can only run analytics and list queries"));
+    }
+    if grant.org_id != org_id {
+        log_error!("[authorize_readonly_principal] Read-only principal {} is not scoped to org {}", grant.principal, org_id);
+        return Err(ApiError::unauthorized("Read-only principal is not authorized for this organization!"));
+    }
+    ORGANIZATIONS
+        .with(|orgs| orgs.borrow().get(&org_id))
+        .ok_or_else(|| ApiError::not_found("Organization not found!"))
+}
+
+fn validate_capability(
+    caller: Principal,
+    claims: &CapabilityClaims,
+    claims_bytes: &[u8],
+    signature_bytes: &[u8],
+    operation: CapabilityOperation,
+    product: &Product,
+    now: u64,
+) -> Result<Organization, ApiError> {
+    ensure_caller_enabled(caller)?;
+    let organization = ORGANIZATIONS
+        .with(|orgs| orgs.borrow().get(&claims.org_id))
+        .ok_or_else(|| ApiError::not_found("Organization not found!"))?;
+    if !capability_tokens::verify_signature(&organization, claims_bytes, signature_bytes)? {
+        return Err(ApiError::unauthorized("Capability token signature is invalid"));
+    }
+    let record = capability_tokens::get(claims.token_id)
+        .ok_or_else(|| ApiError::unauthorized("Capability token is not recognized"))?;
+    if record.revoked_at.is_some() {
+        return Err(ApiError::unauthorized("Capability token has been revoked"));
+    }
+    if now >= claims.expires_at {
+        return Err(ApiError::unauthorized("Capability token has expired"));
+    }
+    if claims.holder != caller {
+        return Err(ApiError::unauthorized("Capability token was issued to another principal"));
+    }
+    if !claims.operations.contains(&operation) {
+        return Err(ApiError::unauthorized(&format!("Capability token does not allow {:?}", operation)));
+    }
+    if product.org_id != claims.org_id || !claims.product_ids.contains(&product.id) {
+        return Err(ApiError::unauthorized("Capability token does not cover this product"));
+    }
+    Ok(organization)
+}
+
+// Authorize a call made with a capability token rather than organization membership: the token must
+// be signed with the organization's key, held by the caller, unexpired, unrevoked and cover both the
+// operation and the product. Every attempt is written to the audit log under the token.
+pub fn authorize_with_capability(
+    caller: Principal,
+    token: &str,
+    operation: CapabilityOperation,
+    product: &Product,
+) -> Result<Organization, ApiError> {
+    let now = clock::now();
+    let (claims, claims_bytes, signature_bytes) = capability_tokens::decode_claims(token)?;
+
+    let outcome = validate_capability(caller, &claims, &claims_bytes, &signature_bytes, operation, product, now);
+
+    let mut metadata = vec![
+        Metadata { key: "operation".to_string(), value: format!("{:?}", operation) },
+        Metadata { key: "product_id".to_string(), value: product.id.to_string() },
+    ];
+    if let Err(e) = &outcome {
+        metadata.push(Metadata { key: "error".to_string(), value: format!("{:?}", e) });
+        log_error!("[authorize_with_capability] Caller {} denied {:?} on product {} with token {}: {:?}", caller, operation, product.id, claims.token_id, e);
+    } else {
+        capability_tokens::record_use(claims.token_id, now);
+        log_info!("[authorize_with_capability] Caller {} allowed {:?} on product {} with token {}", caller, operation, product.id, claims.token_id);
+    }
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "UseCapabilityToken".to_string(),
+        resource_type: "CapabilityToken".to_string(),
+        resource_id: claims.token_id,
+        timestamp: now,
+        metadata,
+        success: outcome.is_ok(),
+    });
+    outcome
+}
+
+// Authorize an operation on a product through the caller's organization membership, or through a
+// capability token when the call carries one
+pub fn authorize_product_operation(
+    caller: Principal,
+    product: &Product,
+    permission: Permission,
+    operation: CapabilityOperation,
+    capability_token: Option<&str>,
+) -> Result<Organization, ApiError> {
+    match capability_token {
+        Some(token) => authorize_with_capability(caller, token, operation, product),
+        None => authorize_for_organization(caller, product.org_id, permission),
+    }
+}
+
 // Legacy function for backward compatibility
 pub fn authorize_user_organization(user_id: Principal, org_id: Principal) -> Result<Organization, ApiError> {
     // This now correctly uses the updated authorize_for_organization logic
@@ -238,7 +430,7 @@ pub fn authorize_for_product(
         action: format!("Access with permission: {:?}", permission),
         resource_type: "Product".to_string(),
         resource_id: product_id,
-        timestamp: api::time(),
+        timestamp: clock::now(),
         metadata: vec![],
         success: true,
     };
@@ -251,26 +443,139 @@ pub fn authorize_for_product(
 // Check if caller is admin - uses find_user_by_caller
 pub fn ensure_admin(user_id: Principal) -> Result<(), ApiError> {
     let caller_principal = user_id; // user_id passed is api::caller()
-    ic_cdk::print(format!("ℹ️ [ensure_admin] Checking admin status for caller: {}", caller_principal));
+    log_debug!("[ensure_admin] Checking admin status for caller: {}", caller_principal);
 
     let user_opt = find_user_by_caller(caller_principal);
     if user_opt.is_none() {
-         ic_cdk::print(format!("❌ ERROR [ensure_admin] User NOT FOUND for caller: {}", caller_principal)); 
+         log_error!("[ensure_admin] User NOT FOUND for caller: {}", caller_principal); 
         return Err(ApiError::not_found("User not found or session key invalid!"));
     }
     
     let user = user_opt.unwrap();
-    ic_cdk::print(format!("ℹ️ [ensure_admin] Found user record ID: {}", user.id));
+    log_debug!("[ensure_admin] Found user record ID: {}", user.id);
+    ensure_enabled(&user)?;
     
     // Check if user has admin role
     match user.user_role {
         Some(UserRole::Admin) => {
-             ic_cdk::print(format!("✅ [ensure_admin] User {} is Admin.", user.id));
+             log_debug!("[ensure_admin] User {} is Admin.", user.id);
              Ok(())
         },
         _ => {
-            ic_cdk::print(format!("❌ ERROR [ensure_admin] User {} is NOT Admin (Role: {:?})", user.id, user.user_role));
+            log_error!("[ensure_admin] User {} is NOT Admin (Role: {:?})", user.id, user.user_role);
             Err(ApiError::unauthorized("Admin access required"))
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    // A brand owner of a fresh organization; the caller picks whether the account is enabled
+    fn setup_brand_owner(is_enabled: bool) -> (Principal, Product) {
+        clock::set_clock(Rc::new(MockClock::new(1_700_000_000_000_000_000)));
+        let owner_id = Principal::from_slice(&[1]);
+        let org_id = Principal::from_slice(&[2]);
+        let product_id = Principal::from_slice(&[3]);
+
+        ORGANIZATIONS.with(|orgs| {
+            orgs.borrow_mut().insert(
+                org_id,
+                Organization {
+                    id: org_id,
+                    name: "Acme".to_string(),
+                    description: String::new(),
+                    private_key: String::new(),
+                    metadata: Vec::new(),
+                    created_at: 0,
+                    created_by: owner_id,
+                    updated_at: 0,
+                    updated_by: owner_id,
+                },
+            )
+        });
+        USERS.with(|users| {
+            users.borrow_mut().insert(
+                owner_id,
+                User {
+                    id: owner_id,
+                    user_role: Some(UserRole::BrandOwner),
+                    is_principal: true,
+                    is_enabled,
+                    org_ids: vec![org_id],
+                    active_org_id: Some(org_id),
+                    first_name: None,
+                    last_name: None,
+                    phone_no: None,
+                    email: None,
+                    detail_meta: Vec::new(),
+                    session_keys: Vec::new(),
+                    created_at: 0,
+                    created_by: owner_id,
+                    updated_at: 0,
+                    updated_by: owner_id,
+                },
+            )
+        });
+        let product = Product {
+            id: product_id,
+            name: "Widget".to_string(),
+            org_id,
+            description: String::new(),
+            category: String::new(),
+            metadata: Vec::new(),
+            public_key: String::new(),
+            product_number: None,
+            sku: None,
+            challenge_required: None,
+            discontinued_at: None,
+            successor_product_id: None,
+            allowed_markets: None,
+            sequential_serials: None,
+            is_test: None,
+            warranty_days: None,
+            recall: None,
+            created_at: 0,
+            created_by: owner_id,
+            updated_at: 0,
+            updated_by: owner_id,
+        };
+        (owner_id, product)
+    }
+
+    fn is_account_disabled<T>(result: &Result<T, ApiError>) -> bool {
+        matches!(result, Err(ApiError::AccountDisabled { .. }))
+    }
+
+    #[test]
+    fn enabled_brand_owner_can_print_codes_and_read_analytics() {
+        let (owner_id, product) = setup_brand_owner(true);
+        assert!(authorize_product_operation(owner_id, &product, Permission::WriteProduct, CapabilityOperation::PrintSerials, None).is_ok());
+        assert!(authorize_for_organization(owner_id, product.org_id, Permission::ReadOrganization).is_ok());
+    }
+
+    #[test]
+    fn disabled_brand_owner_cannot_print_codes() {
+        let (owner_id, product) = setup_brand_owner(false);
+        // The bulk print path and the single-serial print path
+        assert!(is_account_disabled(&authorize_product_operation(
+            owner_id,
+            &product,
+            Permission::WriteProduct,
+            CapabilityOperation::PrintSerials,
+            None,
+        )));
+        assert!(is_account_disabled(&authorize_for_organization(owner_id, product.org_id, Permission::WriteProduct)));
+    }
+
+    #[test]
+    fn disabled_brand_owner_cannot_read_analytics() {
+        let (owner_id, product) = setup_brand_owner(false);
+        assert!(is_account_disabled(&authorize_for_organization(owner_id, product.org_id, Permission::ReadOrganization)));
+        assert!(is_account_disabled(&check_permission(owner_id, &Permission::ReadOrganization)));
+        assert!(effective_permissions(&find_user_by_caller(owner_id).unwrap()).is_empty());
+    }
+}
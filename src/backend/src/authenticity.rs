@@ -0,0 +1,129 @@
+use candid::Principal;
+
+use crate::api::{AuthenticityFactor, AuthenticityFactorKind, AuthenticityScore};
+use crate::error::ApiError;
+use crate::models::{AuthenticityWeights, SerialNumberStatus};
+use crate::org_settings;
+
+pub const DEFAULT_WEIGHTS: AuthenticityWeights = AuthenticityWeights {
+    signature: 30,
+    scan_velocity: 25,
+    geography: 15,
+    custody: 10,
+    reports: 20,
+};
+
+pub const MAX_WEIGHT: u32 = 1_000;
+
+// Scans of one serial within a day that still look like normal handling
+const NORMAL_SCANS_PER_DAY: u32 = 5;
+
+// What is known about a genuine scan when it is recorded
+pub struct AuthenticitySignals {
+    pub signature_valid: bool,
+    pub distinct_scanners: u32,
+    pub scans_last_day: u32, // Including this one
+    pub duplicate_scan_threshold: u32,
+    pub suspected_cloned: bool,
+    pub countries: Vec<String>, // Distinct countries the serial was scanned in, this scan included
+    pub grey_market: bool,
+    pub custody_status: SerialNumberStatus,
+    pub open_reports: u32, // Unresolved disputes on the serial
+}
+
+pub fn weights(org_id: Principal) -> AuthenticityWeights {
+    org_settings::get_org_settings(org_id).authenticity_weights.unwrap_or(DEFAULT_WEIGHTS)
+}
+
+pub fn validate_weights(weights: &AuthenticityWeights) -> Result<(), ApiError> {
+    let all = [weights.signature, weights.scan_velocity, weights.geography, weights.custody, weights.reports];
+    if all.iter().any(|w| *w > MAX_WEIGHT) {
+        return Err(ApiError::invalid_input(&format!("Each weight must be at most {}", MAX_WEIGHT)));
+    }
+    Ok(())
+}
+
+pub fn is_unset(weights: &AuthenticityWeights) -> bool {
+    weights.signature == 0 && weights.scan_velocity == 0 && weights.geography == 0 && weights.custody == 0 && weights.reports == 0
+}
+
+fn signature_factor(signals: &AuthenticitySignals) -> (u8, String) {
+    if signals.signature_valid {
+        (100, "The code's signature matches the brand's key".to_string())
+    } else {
+        (0, "The code's signature does not match the brand's key".to_string())
+    }
+}
+
+// The lower of two sub-scores: how close the distinct scanners are to the clone threshold, and
+// how far the last day's scans exceed normal handling
+fn scan_velocity_factor(signals: &AuthenticitySignals) -> (u8, String) {
+    if signals.suspected_cloned {
+        return (0, "The serial was flagged as a suspected cloned label".to_string());
+    }
+    let threshold = signals.duplicate_scan_threshold.max(1);
+    let spread = 100u32.saturating_sub(signals.distinct_scanners.saturating_sub(1).saturating_mul(100) / threshold);
+    let burst = 100u32.saturating_sub(signals.scans_last_day.saturating_sub(NORMAL_SCANS_PER_DAY).saturating_mul(10));
+    let score = spread.min(burst) as u8;
+    let detail = format!(
+        "{} distinct scanner(s), {} scan(s) in the last 24 hours",
+        signals.distinct_scanners, signals.scans_last_day
+    );
+    (score, detail)
+}
+
+fn geography_factor(signals: &AuthenticitySignals) -> (u8, String) {
+    let (mut score, mut detail) = match signals.countries.len() {
+        0 => (100, "No location was reported for this serial".to_string()),
+        1 => (100, format!("Scanned only in {}", signals.countries[0])),
+        2 => (70, format!("Scanned in 2 countries: {}", signals.countries.join(", "))),
+        3 => (40, format!("Scanned in 3 countries: {}", signals.countries.join(", "))),
+        n => (10, format!("Scanned in {} countries", n)),
+    };
+    if signals.grey_market {
+        score = score.min(30);
+        detail.push_str("; this scan is outside the product's markets");
+    }
+    (score, detail)
+}
+
+fn custody_factor(signals: &AuthenticitySignals) -> (u8, String) {
+    let score = match signals.custody_status {
+        SerialNumberStatus::Sold => 100,
+        SerialNumberStatus::Shipped => 80,
+        SerialNumberStatus::Printed => 50,
+        SerialNumberStatus::Created => 30, // Scanned although no label was ever printed for it
+        SerialNumberStatus::Void => 0,
+    };
+    (score, format!("Last recorded custody step: {:?}", signals.custody_status))
+}
+
+fn reports_factor(signals: &AuthenticitySignals) -> (u8, String) {
+    match signals.open_reports {
+        0 => (100, "No open reports for this serial".to_string()),
+        1 => (50, "1 open report for this serial".to_string()),
+        n => (0, format!("{} open reports for this serial", n)),
+    }
+}
+
+// Weighted average of the signal scores, rounded; zero-weight signals are listed but do not count
+pub fn score(signals: &AuthenticitySignals, weights: &AuthenticityWeights) -> AuthenticityScore {
+    let parts = [
+        (AuthenticityFactorKind::Signature, weights.signature, signature_factor(signals)),
+        (AuthenticityFactorKind::ScanVelocity, weights.scan_velocity, scan_velocity_factor(signals)),
+        (AuthenticityFactorKind::Geography, weights.geography, geography_factor(signals)),
+        (AuthenticityFactorKind::Custody, weights.custody, custody_factor(signals)),
+        (AuthenticityFactorKind::Reports, weights.reports, reports_factor(signals)),
+    ];
+
+    let total_weight: u64 = parts.iter().map(|(_, weight, _)| *weight as u64).sum();
+    let weighted: u64 = parts.iter().map(|(_, weight, (score, _))| *weight as u64 * *score as u64).sum();
+    // Rounded to the nearest point; no weighted factors at all counts as fully confident
+    let score = (weighted + total_weight / 2).checked_div(total_weight).map_or(100, |score| score as u8);
+
+    let factors = parts
+        .into_iter()
+        .map(|(kind, weight, (score, detail))| AuthenticityFactor { kind, score, weight, detail })
+        .collect();
+    AuthenticityScore { score, factors }
+}
@@ -0,0 +1,346 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk_timers::set_timer_interval;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use serde::Serialize;
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::{BiExportConfig, BiExportDelivery, BiExportDeliveryStatus, Product, ProductVerificationStatus};
+use crate::utils::{async_delay, generate_unique_principal};
+use crate::quotas;
+use crate::rewards;
+use crate::serial_store;
+use crate::verification_store;
+use crate::webhooks;
+
+// Bumped whenever the payload layout changes so warehouses can route records to the right parser
+pub const BI_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+// Exports cover one day each
+pub const EXPORT_PERIOD_NS: u64 = DAY_NS;
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+// How often the scheduler looks for due exports
+const EXPORT_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+// Delivery retries back off exponentially from the base delay
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(60);
+
+// Oldest delivery log entries beyond this are dropped per organization
+const MAX_DELIVERIES_PER_ORG: usize = 90;
+
+// Define unique Memory IDs for the structures in this module
+const BI_EXPORT_CONFIGS_MEM_ID: MemoryId = MemoryId::new(36);
+const BI_EXPORT_DELIVERIES_MEM_ID: MemoryId = MemoryId::new(37);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static BI_EXPORT_CONFIGS: RefCell<StableBTreeMap<Principal, BiExportConfig, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(BI_EXPORT_CONFIGS_MEM_ID))
+        )
+    );
+
+    static BI_EXPORT_DELIVERIES: RefCell<StableBTreeMap<Principal, BiExportDelivery, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(BI_EXPORT_DELIVERIES_MEM_ID))
+        )
+    );
+}
+
+// Body posted to the organization's ingestion URL
+#[derive(Serialize)]
+struct BiExportPayload {
+    schema_version: u32,
+    event: String,
+    delivery_id: String,
+    org_id: String,
+    period_start: u64,
+    period_end: u64,
+    generated_at: u64,
+    daily_verifications: Vec<DailyVerificationRow>,
+    rewards: Vec<ProductRewardRow>,
+    counterfeit: CounterfeitFlags,
+}
+
+#[derive(Serialize, Default)]
+struct DailyVerificationRow {
+    day: u64, // Days since the Unix epoch
+    total: u64,
+    first: u64,
+    multiple: u64,
+    invalid: u64,
+}
+
+// Cumulative totals per product, so consumers can diff consecutive exports
+#[derive(Serialize)]
+struct ProductRewardRow {
+    product_id: String,
+    product_number: Option<u64>,
+    product_name: String,
+    sku: Option<String>,
+    points_issued: u64,
+    points_redeemed: u64,
+    redemption_count: u64,
+}
+
+#[derive(Serialize, Default)]
+struct CounterfeitFlags {
+    invalid_scans: u64,
+    voided_scans: u64,
+    suspected_clone_serials: Vec<String>,
+}
+
+pub fn get_config(org_id: Principal) -> Option<BiExportConfig> {
+    BI_EXPORT_CONFIGS.with(|configs| configs.borrow().get(&org_id))
+}
+
+pub fn save_config(config: BiExportConfig) {
+    BI_EXPORT_CONFIGS.with(|configs| {
+        configs.borrow_mut().insert(config.org_id, config);
+    });
+}
+
+fn save_delivery(delivery: BiExportDelivery) {
+    BI_EXPORT_DELIVERIES.with(|deliveries| {
+        deliveries.borrow_mut().insert(delivery.id, delivery);
+    });
+}
+
+// Delivery log of an organization, newest first
+pub fn list_deliveries_for_org(org_id: Principal) -> Vec<BiExportDelivery> {
+    let mut deliveries: Vec<BiExportDelivery> = BI_EXPORT_DELIVERIES.with(|deliveries| {
+        deliveries
+            .borrow()
+            .iter()
+            .filter(|(_, delivery)| delivery.org_id == org_id)
+            .map(|(_, delivery)| delivery)
+            .collect()
+    });
+    deliveries.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    deliveries
+}
+
+fn prune_deliveries(org_id: Principal) {
+    let stale: Vec<Principal> = list_deliveries_for_org(org_id)
+        .into_iter()
+        .skip(MAX_DELIVERIES_PER_ORG)
+        .map(|delivery| delivery.id)
+        .collect();
+    BI_EXPORT_DELIVERIES.with(|deliveries| {
+        let mut deliveries_mut = deliveries.borrow_mut();
+        for id in stale {
+            deliveries_mut.remove(&id);
+        }
+    });
+}
+
+// Register the recurring scheduler. Timers do not survive upgrades, so this runs from both init and post_upgrade.
+pub fn start_bi_export_scheduler() {
+    set_timer_interval(EXPORT_CHECK_INTERVAL, run_due_exports);
+}
+
+fn run_due_exports() {
+    let now = clock::now();
+    let due: Vec<BiExportConfig> = BI_EXPORT_CONFIGS.with(|configs| {
+        configs
+            .borrow()
+            .iter()
+            .filter(|(_, config)| config.is_active && config.next_push_at <= now)
+            .map(|(_, config)| config)
+            .collect()
+    });
+
+    if due.is_empty() {
+        return;
+    }
+    log_info!("[run_due_exports] {} BI export(s) due", due.len());
+
+    for mut config in due {
+        let period_start = config.last_period_end.unwrap_or_else(|| now.saturating_sub(EXPORT_PERIOD_NS));
+        start_export(&config, period_start, now);
+
+        config.last_period_end = Some(now);
+        config.next_push_at = now.saturating_add(EXPORT_PERIOD_NS);
+        save_config(config);
+    }
+}
+
+// Build the export for [period_start, period_end), log it and deliver it asynchronously
+pub fn start_export(config: &BiExportConfig, period_start: u64, period_end: u64) -> BiExportDelivery {
+    let mut delivery = BiExportDelivery {
+        id: generate_unique_principal(config.org_id),
+        org_id: config.org_id,
+        schema_version: BI_EXPORT_SCHEMA_VERSION,
+        period_start,
+        period_end,
+        status: BiExportDeliveryStatus::Pending,
+        attempts: 0,
+        last_error: None,
+        payload_bytes: 0,
+        created_at: clock::now(),
+        delivered_at: None,
+    };
+
+    let payload = build_payload(config.org_id, delivery.id, period_start, period_end);
+    match serde_json::to_string(&payload) {
+        Ok(body) => {
+            delivery.payload_bytes = body.len() as u64;
+            save_delivery(delivery.clone());
+            ic_cdk::spawn(deliver_export(delivery.id, config.endpoint_url.clone(), body));
+        }
+        Err(e) => {
+            log_error!("[start_export] Failed to serialize export {}: {:?}", delivery.id, e);
+            delivery.status = BiExportDeliveryStatus::Failed;
+            delivery.last_error = Some(format!("Serialization failed: {:?}", e));
+            save_delivery(delivery.clone());
+        }
+    }
+    prune_deliveries(config.org_id);
+    delivery
+}
+
+async fn deliver_export(delivery_id: Principal, url: String, body: String) {
+    let mut delay = RETRY_BASE_DELAY;
+    for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+        let org_id = match BI_EXPORT_DELIVERIES.with(|deliveries| deliveries.borrow().get(&delivery_id)) {
+            Some(delivery) => delivery.org_id,
+            None => return, // Pruned or reset while waiting
+        };
+
+        let result = match quotas::consume_outcalls(org_id, 1) {
+            Ok(()) => webhooks::post_json(&url, body.clone(), Some(org_id)).await.map_err(|e| format!("{:?}", e)),
+            Err(e) => Err(format!("{:?}", e)),
+        };
+        let delivered = result.is_ok();
+        record_attempt(delivery_id, attempt, result, attempt == MAX_DELIVERY_ATTEMPTS);
+        if delivered || attempt == MAX_DELIVERY_ATTEMPTS {
+            return;
+        }
+
+        async_delay(delay).await;
+        delay *= 2;
+    }
+}
+
+fn record_attempt(delivery_id: Principal, attempt: u32, result: Result<(), String>, is_last_attempt: bool) {
+    BI_EXPORT_DELIVERIES.with(|deliveries| {
+        let mut deliveries_mut = deliveries.borrow_mut();
+        if let Some(mut delivery) = deliveries_mut.get(&delivery_id) {
+            delivery.attempts = attempt;
+            match result {
+                Ok(()) => {
+                    delivery.status = BiExportDeliveryStatus::Delivered;
+                    delivery.delivered_at = Some(clock::now());
+                    delivery.last_error = None;
+                }
+                Err(e) => {
+                    log_warn!("[deliver_export] Attempt {} for export {} failed: {}", attempt, delivery_id, e);
+                    if is_last_attempt {
+                        delivery.status = BiExportDeliveryStatus::Failed;
+                    }
+                    delivery.last_error = Some(e);
+                }
+            }
+            deliveries_mut.insert(delivery_id, delivery);
+        }
+    });
+}
+
+fn build_payload(org_id: Principal, delivery_id: Principal, period_start: u64, period_end: u64) -> BiExportPayload {
+    let products: Vec<(Principal, Product)> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id && !product.is_test.unwrap_or(false))
+            .collect()
+    });
+
+    let mut days: BTreeMap<u64, DailyVerificationRow> = BTreeMap::new();
+    let mut counterfeit = CounterfeitFlags::default();
+    let mut rewards_rows = Vec::with_capacity(products.len());
+
+    for (product_id, product) in &products {
+        for verification in verification_store::list_for_product(*product_id)
+            .iter()
+            .filter(|v| v.created_at >= period_start && v.created_at < period_end)
+        {
+            let day = verification.created_at / DAY_NS;
+            let row = days.entry(day).or_insert_with(|| DailyVerificationRow { day, ..Default::default() });
+            row.total += 1;
+            match verification.status {
+                ProductVerificationStatus::FirstVerification => row.first += 1,
+                ProductVerificationStatus::MultipleVerification => row.multiple += 1,
+                ProductVerificationStatus::Invalid => {
+                    row.invalid += 1;
+                    counterfeit.invalid_scans += 1;
+                }
+                ProductVerificationStatus::VoidedSerial
+                | ProductVerificationStatus::CodeRevoked
+                | ProductVerificationStatus::NotYetActive
+                | ProductVerificationStatus::SupersededCode => {
+                    row.invalid += 1;
+                    counterfeit.voided_scans += 1;
+                }
+            }
+        }
+
+        counterfeit.suspected_clone_serials.extend(
+            serial_store::list_for_product(*product_id)
+                .iter()
+                .filter(|sn| sn.suspected_cloned.unwrap_or(false))
+                .map(|sn| sn.serial_no.to_text()),
+        );
+
+        let stats = rewards::get_product_reward_stats(*product_id);
+        rewards_rows.push(ProductRewardRow {
+            product_id: product_id.to_text(),
+            product_number: product.product_number,
+            product_name: product.name.clone(),
+            sku: product.sku.clone(),
+            points_issued: stats.points_issued,
+            points_redeemed: stats.points_redeemed,
+            redemption_count: stats.redemption_count,
+        });
+    }
+
+    BiExportPayload {
+        schema_version: BI_EXPORT_SCHEMA_VERSION,
+        event: "analytics_export".to_string(),
+        delivery_id: delivery_id.to_text(),
+        org_id: org_id.to_text(),
+        period_start,
+        period_end,
+        generated_at: clock::now(),
+        daily_verifications: days.into_values().collect(),
+        rewards: rewards_rows,
+        counterfeit,
+    }
+}
+
+// Reset ALL BI export configs and delivery logs (use with caution)
+pub fn reset_bi_exports() {
+    BI_EXPORT_CONFIGS.with(|configs| {
+        let mut configs_mut = configs.borrow_mut();
+        let keys: Vec<_> = configs_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            configs_mut.remove(&key);
+        }
+    });
+    BI_EXPORT_DELIVERIES.with(|deliveries| {
+        let mut deliveries_mut = deliveries.borrow_mut();
+        let keys: Vec<_> = deliveries_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            deliveries_mut.remove(&key);
+        }
+    });
+    log_info!("All BI export configs and deliveries have been reset.");
+}
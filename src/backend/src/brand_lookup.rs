@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use candid::Principal;
+
+use crate::error::ApiError;
+
+// Shortest accepted code prefix: the first two groups of a serial number ("xxxxx-xxxxx")
+pub const MIN_CODE_PREFIX_LENGTH: usize = 11;
+
+// Signed-in callers get their own budget. Anonymous callers cannot be told apart (the canister never
+// sees client IPs), so they share one canister-wide budget instead.
+const MAX_LOOKUPS_PER_PRINCIPAL: u32 = 30;
+const MAX_ANONYMOUS_LOOKUPS: u32 = 2_000;
+const LOOKUP_WINDOW_NS: u64 = 60 * 60 * 1_000_000_000;
+
+// Entries tracked before windows that have ended are swept out
+const MAX_TRACKED_PRINCIPALS: usize = 10_000;
+
+thread_local! {
+    // principal -> (window_start, lookups in window); heap only, so an upgrade simply opens new windows
+    static LOOKUP_WINDOWS: RefCell<HashMap<Principal, (u64, u32)>> = RefCell::new(HashMap::new());
+}
+
+// Count a lookup against the caller's budget, failing once it is used up for the current window
+pub fn record_lookup(caller: Principal, now: u64) -> Result<(), ApiError> {
+    let max_lookups = if caller == Principal::anonymous() {
+        MAX_ANONYMOUS_LOOKUPS
+    } else {
+        MAX_LOOKUPS_PER_PRINCIPAL
+    };
+
+    LOOKUP_WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        if windows.len() >= MAX_TRACKED_PRINCIPALS {
+            windows.retain(|_, (start, _)| now < start.saturating_add(LOOKUP_WINDOW_NS));
+        }
+
+        let window = windows.entry(caller).or_insert((now, 0));
+        if now >= window.0.saturating_add(LOOKUP_WINDOW_NS) {
+            *window = (now, 0);
+        }
+        if window.1 >= max_lookups {
+            return Err(ApiError::invalid_input(&format!(
+                "Rate limit exceeded. Try again after {}",
+                window.0 + LOOKUP_WINDOW_NS
+            )));
+        }
+        window.1 += 1;
+        Ok(())
+    })
+}
+
+// Lowercased, trimmed prefix if it is long enough and could start a serial number
+pub fn normalize_code_prefix(code_prefix: &str) -> Result<String, ApiError> {
+    let prefix = code_prefix.trim().to_lowercase();
+    if prefix.len() < MIN_CODE_PREFIX_LENGTH {
+        return Err(ApiError::invalid_input(&format!(
+            "Enter at least the first {} characters of the code",
+            MIN_CODE_PREFIX_LENGTH
+        )));
+    }
+    if !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(ApiError::invalid_input("Code contains invalid characters"));
+    }
+    Ok(prefix)
+}
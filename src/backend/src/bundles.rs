@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{Bundle, BundleSummary, BundleUnit, SerialNumberStatus};
+use crate::serial_store;
+
+// Most inner units one outer code can attest to
+pub const MAX_BUNDLE_MEMBERS: usize = 200;
+
+// Define unique Memory IDs for the structures in this module
+const BUNDLES_MEM_ID: MemoryId = MemoryId::new(64);
+const BUNDLE_MEMBER_INDEX_MEM_ID: MemoryId = MemoryId::new(65);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by the outer serial number
+    static BUNDLES: RefCell<StableBTreeMap<Principal, Bundle, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(BUNDLES_MEM_ID))
+        )
+    );
+
+    // Member serial number -> outer serial number, so a unit sits in at most one bundle
+    static BUNDLE_MEMBER_INDEX: RefCell<StableBTreeMap<Principal, Principal, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(BUNDLE_MEMBER_INDEX_MEM_ID))
+        )
+    );
+}
+
+pub fn save_bundle(bundle: Bundle) {
+    BUNDLE_MEMBER_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        for member in &bundle.members {
+            index_mut.insert(member.serial_no, bundle.outer_serial_no);
+        }
+    });
+    BUNDLES.with(|bundles| {
+        bundles.borrow_mut().insert(bundle.outer_serial_no, bundle);
+    });
+}
+
+pub fn get_bundle(outer_serial_no: Principal) -> Option<Bundle> {
+    BUNDLES.with(|bundles| bundles.borrow().get(&outer_serial_no))
+}
+
+// Outer serial number of the bundle containing this unit, if any
+pub fn bundle_of_member(serial_no: Principal) -> Option<Principal> {
+    BUNDLE_MEMBER_INDEX.with(|index| index.borrow().get(&serial_no))
+}
+
+// Current validity of every unit in the bundle. A unit is valid unless its label was voided or
+// the printed version on it was revoked.
+pub fn summarize(bundle: &Bundle) -> BundleSummary {
+    let units: Vec<BundleUnit> = bundle
+        .members
+        .iter()
+        .map(|member| {
+            let record = serial_store::get(member.product_id, member.serial_no);
+            let voided = record.as_ref().is_none_or(|sn| sn.effective_status() == SerialNumberStatus::Void);
+            let revoked = record.as_ref().is_some_and(|sn| sn.revocation_for(sn.print_version).is_some());
+            BundleUnit {
+                product_id: member.product_id,
+                serial_no: member.serial_no,
+                user_serial_no: record.and_then(|sn| sn.user_serial_no),
+                valid: !voided && !revoked,
+                voided,
+                revoked,
+            }
+        })
+        .collect();
+    BundleSummary {
+        outer_serial_no: bundle.outer_serial_no,
+        total_units: units.len() as u32,
+        valid_units: units.iter().filter(|unit| unit.valid).count() as u32,
+        units,
+    }
+}
+
+// Reset ALL bundles (use with caution)
+pub fn reset_bundles() {
+    BUNDLES.with(|bundles| {
+        let mut bundles_mut = bundles.borrow_mut();
+        let keys: Vec<_> = bundles_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            bundles_mut.remove(&key);
+        }
+    });
+    BUNDLE_MEMBER_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    log_info!("All bundles have been reset.");
+}
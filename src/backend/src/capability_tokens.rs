@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+
+use candid::{decode_one, encode_one, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::ecdsa::Signature;
+
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::keys;
+use crate::models::{CapabilityClaims, CapabilityToken, Organization};
+
+// Tokens are meant for a job, not a standing grant
+pub const MAX_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+pub const MAX_ACTIVE_PER_ORG: usize = 50;
+pub const MAX_PRODUCTS_PER_TOKEN: usize = 100;
+
+// Define a unique MemoryId for this structure
+const CAPABILITY_TOKENS_MEM_ID: MemoryId = MemoryId::new(80);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by token ID; revoked and expired tokens are kept for the audit trail
+    static CAPABILITY_TOKENS: RefCell<StableBTreeMap<Principal, CapabilityToken, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(CAPABILITY_TOKENS_MEM_ID))
+        )
+    );
+}
+
+// "<hex candid claims>.<hex signature>", signed with the organization's key like its unique codes
+pub fn encode_token(claims: &CapabilityClaims, organization: &Organization) -> Result<String, ApiError> {
+    let claims_bytes = encode_one(claims).map_err(|_| ApiError::internal_error("Failed to encode token claims"))?;
+    let signature = keys::org_signing_key(organization)?.sign(&claims_bytes);
+    Ok(format!("{}.{}", hex::encode(&claims_bytes), hex::encode(signature.to_bytes().as_slice())))
+}
+
+// Claims a token carries, before its signature is checked
+pub fn decode_claims(token: &str) -> Result<(CapabilityClaims, Vec<u8>, Vec<u8>), ApiError> {
+    let invalid = || ApiError::unauthorized("Malformed capability token");
+    let (claims_hex, signature_hex) = token.trim().split_once('.').ok_or_else(invalid)?;
+    let claims_bytes = hex::decode(claims_hex).map_err(|_| invalid())?;
+    let signature_bytes = hex::decode(signature_hex).map_err(|_| invalid())?;
+    let claims: CapabilityClaims = decode_one(&claims_bytes).map_err(|_| invalid())?;
+    Ok((claims, claims_bytes, signature_bytes))
+}
+
+pub fn verify_signature(organization: &Organization, claims_bytes: &[u8], signature_bytes: &[u8]) -> Result<bool, ApiError> {
+    let signature = match Signature::from_slice(signature_bytes) {
+        Ok(s) => s,
+        Err(_) => return Ok(false),
+    };
+    Ok(keys::org_verifying_key(organization)?.verify(claims_bytes, &signature))
+}
+
+pub fn save(token: CapabilityToken) {
+    CAPABILITY_TOKENS.with(|tokens| {
+        tokens.borrow_mut().insert(token.claims.token_id, token);
+    });
+}
+
+pub fn get(token_id: Principal) -> Option<CapabilityToken> {
+    CAPABILITY_TOKENS.with(|tokens| tokens.borrow().get(&token_id))
+}
+
+pub fn is_active(token: &CapabilityToken, now: u64) -> bool {
+    token.revoked_at.is_none() && now < token.claims.expires_at
+}
+
+// Tokens of the organization, newest first
+pub fn list_for_org(org_id: Principal) -> Vec<CapabilityToken> {
+    let mut tokens: Vec<CapabilityToken> = CAPABILITY_TOKENS.with(|tokens| {
+        tokens
+            .borrow()
+            .iter()
+            .map(|(_, token)| token)
+            .filter(|token| token.claims.org_id == org_id)
+            .collect()
+    });
+    tokens.sort_by_key(|a| std::cmp::Reverse(a.claims.issued_at));
+    tokens
+}
+
+pub fn active_count_for_org(org_id: Principal, now: u64) -> usize {
+    list_for_org(org_id).iter().filter(|token| is_active(token, now)).count()
+}
+
+pub fn record_use(token_id: Principal, now: u64) {
+    if let Some(mut token) = get(token_id) {
+        token.use_count += 1;
+        token.last_used_at = Some(now);
+        save(token);
+    }
+}
+
+// Remove the organization's capability tokens, revoked ones included when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    CAPABILITY_TOKENS.with(|tokens| {
+        let mut tokens_mut = tokens.borrow_mut();
+        let keys: Vec<_> = tokens_mut.iter().filter(|(_, token)| token.claims.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            tokens_mut.remove(key);
+        }
+        keys.len() as u32
+    })
+}
+
+// Reset ALL capability tokens (use with caution)
+pub fn reset_capability_tokens() {
+    CAPABILITY_TOKENS.with(|tokens| {
+        let mut tokens_mut = tokens.borrow_mut();
+        let keys: Vec<_> = tokens_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            tokens_mut.remove(&key);
+        }
+    });
+    log_info!("All capability tokens have been reset.");
+}
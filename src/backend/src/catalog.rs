@@ -0,0 +1,177 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::Product;
+
+pub const MAX_SKU_LENGTH: usize = 64;
+
+// Define a unique MemoryId for this structure
+const ORG_PRODUCT_COUNTERS_MEM_ID: MemoryId = MemoryId::new(38);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Last product number handed out per organization. Numbers are never reused, even after deletes or moves.
+    static ORG_PRODUCT_COUNTERS: RefCell<StableBTreeMap<Principal, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_PRODUCT_COUNTERS_MEM_ID))
+        )
+    );
+}
+
+// Allocate the next product number (starting at 1) for the organization
+pub fn next_product_number(org_id: Principal) -> u64 {
+    ORG_PRODUCT_COUNTERS.with(|counters| {
+        let mut counters_mut = counters.borrow_mut();
+        let next = counters_mut.get(&org_id).unwrap_or(0) + 1;
+        counters_mut.insert(org_id, next);
+        next
+    })
+}
+
+// Trimmed SKU, or None when blank. SKUs are limited to letters, digits and - _ . /
+pub fn normalize_sku(sku: &str) -> Result<Option<String>, &'static str> {
+    let sku = sku.trim();
+    if sku.is_empty() {
+        return Ok(None);
+    }
+    if sku.chars().count() > MAX_SKU_LENGTH {
+        return Err("SKU must be at most 64 characters");
+    }
+    if !sku.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/')) {
+        return Err("SKU can only contain letters, digits and - _ . /");
+    }
+    Ok(Some(sku.to_string()))
+}
+
+// SKUs are unique per organization, compared case-insensitively
+pub fn sku_taken(org_id: Principal, sku: &str, exclude_product_id: Option<Principal>) -> bool {
+    PRODUCTS.with(|products| {
+        products.borrow().iter().any(|(id, product)| {
+            product.org_id == org_id
+                && Some(id) != exclude_product_id
+                && product.sku.as_deref().is_some_and(|existing| existing.eq_ignore_ascii_case(sku))
+        })
+    })
+}
+
+// Search query matching: "#12" or "12" matches product number 12, anything else is a
+// case-insensitive substring of the name or SKU
+pub fn matches_query(product: &Product, query: &str) -> bool {
+    let query = query.trim();
+    if query.is_empty() {
+        return true;
+    }
+    let digits = query.strip_prefix('#').unwrap_or(query);
+    if let Ok(number) = digits.parse::<u64>() {
+        if product.product_number == Some(number) {
+            return true;
+        }
+    }
+    let query = query.to_lowercase();
+    product.name.to_lowercase().contains(&query)
+        || product.sku.as_deref().is_some_and(|sku| sku.to_lowercase().contains(&query))
+}
+
+// Name or category reduced for duplicate detection: lowercase words, punctuation and extra spaces dropped
+pub fn normalize_for_comparison(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn comparison_key(product: &Product) -> (String, String) {
+    (normalize_for_comparison(&product.name), normalize_for_comparison(&product.category))
+}
+
+// Products of the organization whose normalized name and category both match
+pub fn find_similar_products(org_id: Principal, name: &str, category: &str) -> Vec<Principal> {
+    let key = (normalize_for_comparison(name), normalize_for_comparison(category));
+    PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id && comparison_key(product) == key)
+            .map(|(id, _)| id)
+            .collect()
+    })
+}
+
+// Products of the organization sharing a normalized name and category with at least one other,
+// grouped by that key; groups sorted by name, products oldest first
+pub fn suspected_duplicates(org_id: Principal) -> Vec<((String, String), Vec<Product>)> {
+    let mut groups: HashMap<(String, String), Vec<Product>> = HashMap::new();
+    PRODUCTS.with(|products| {
+        for (_, product) in products.borrow().iter().filter(|(_, product)| product.org_id == org_id) {
+            groups.entry(comparison_key(&product)).or_default().push(product);
+        }
+    });
+    let mut duplicates: Vec<((String, String), Vec<Product>)> =
+        groups.into_iter().filter(|(_, products)| products.len() > 1).collect();
+    for (_, products) in duplicates.iter_mut() {
+        products.sort_by_key(|product| product.created_at);
+    }
+    duplicates.sort_by(|a, b| a.0.cmp(&b.0));
+    duplicates
+}
+
+// Number products created before numbering existed, oldest first, and make sure every counter
+// is at least the highest number in use. Idempotent.
+pub fn backfill_product_numbers() -> u32 {
+    let mut unnumbered: Vec<(u64, Principal, Principal)> = Vec::new();
+    let mut highest: HashMap<Principal, u64> = HashMap::new();
+    PRODUCTS.with(|products| {
+        for (id, product) in products.borrow().iter() {
+            match product.product_number {
+                Some(number) => {
+                    let entry = highest.entry(product.org_id).or_insert(0);
+                    *entry = (*entry).max(number);
+                }
+                None => unnumbered.push((product.created_at, id, product.org_id)),
+            }
+        }
+    });
+
+    ORG_PRODUCT_COUNTERS.with(|counters| {
+        let mut counters_mut = counters.borrow_mut();
+        for (org_id, number) in highest {
+            if counters_mut.get(&org_id).unwrap_or(0) < number {
+                counters_mut.insert(org_id, number);
+            }
+        }
+    });
+
+    unnumbered.sort();
+    let backfilled = unnumbered.len() as u32;
+    for (_, product_id, org_id) in unnumbered {
+        let number = next_product_number(org_id);
+        PRODUCTS.with(|products| {
+            let mut products_mut = products.borrow_mut();
+            if let Some(mut product) = products_mut.get(&product_id) {
+                product.product_number = Some(number);
+                products_mut.insert(product_id, product);
+            }
+        });
+    }
+    backfilled
+}
+
+// Reset ALL product number counters (use with caution)
+pub fn reset_product_counters() {
+    ORG_PRODUCT_COUNTERS.with(|counters| {
+        let mut counters_mut = counters.borrow_mut();
+        let keys: Vec<_> = counters_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            counters_mut.remove(&key);
+        }
+    });
+    log_info!("All product number counters have been reset.");
+}
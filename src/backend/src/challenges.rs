@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use rand::prelude::StdRng;
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{VerificationChallenge, VerificationChallengeStatus};
+use crate::utils::generate_unique_principal;
+
+// Challenge lifetimes (in nanoseconds, matching clock::now())
+pub const CHALLENGE_TTL_NS: u64 = 2 * 60 * 1_000_000_000; // 2 minutes to tap the tag and answer
+const CHALLENGE_RETENTION_NS: u64 = 60 * 60 * 1_000_000_000; // finished challenges are kept an hour for lookups
+
+// Define a unique MemoryId for this structure
+const VERIFICATION_CHALLENGES_MEM_ID: MemoryId = MemoryId::new(39);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static VERIFICATION_CHALLENGES: RefCell<StableBTreeMap<Principal, VerificationChallenge, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_CHALLENGES_MEM_ID))
+        )
+    );
+}
+
+// Drop challenges past their retention window
+fn prune_stale_challenges(now: u64) {
+    VERIFICATION_CHALLENGES.with(|challenges| {
+        let mut challenges_mut = challenges.borrow_mut();
+        let stale: Vec<Principal> = challenges_mut
+            .iter()
+            .filter(|(_, challenge)| now >= challenge.expires_at.saturating_add(CHALLENGE_RETENTION_NS))
+            .map(|(k, _)| k)
+            .collect();
+        for key in stale {
+            challenges_mut.remove(&key);
+        }
+    });
+}
+
+// Issue a fresh single-use nonce for the serial number
+pub fn start_challenge(product_id: Principal, serial_no: Principal, requested_by: Principal) -> VerificationChallenge {
+    let now = clock::now();
+    prune_stale_challenges(now);
+
+    let mut nonce_bytes = [0u8; 32];
+    StdRng::from_entropy().fill_bytes(&mut nonce_bytes);
+
+    let challenge = VerificationChallenge {
+        id: generate_unique_principal(Principal::anonymous()),
+        product_id,
+        serial_no,
+        nonce: hex::encode(nonce_bytes),
+        requested_by,
+        status: VerificationChallengeStatus::Pending,
+        created_at: now,
+        expires_at: now.saturating_add(CHALLENGE_TTL_NS),
+        completed_at: None,
+    };
+    VERIFICATION_CHALLENGES.with(|challenges| challenges.borrow_mut().insert(challenge.id, challenge.clone()));
+    challenge
+}
+
+pub fn get_challenge(challenge_id: Principal) -> Option<VerificationChallenge> {
+    VERIFICATION_CHALLENGES.with(|challenges| challenges.borrow().get(&challenge_id))
+}
+
+// Close a pending challenge so its nonce cannot be answered again
+pub fn finish_challenge(challenge_id: Principal, status: VerificationChallengeStatus) {
+    VERIFICATION_CHALLENGES.with(|challenges| {
+        let mut challenges_mut = challenges.borrow_mut();
+        if let Some(mut challenge) = challenges_mut.get(&challenge_id) {
+            challenge.status = status;
+            challenge.completed_at = Some(clock::now());
+            challenges_mut.insert(challenge_id, challenge);
+        }
+    });
+}
+
+// Reset ALL verification challenges (use with caution)
+pub fn reset_challenges() {
+    VERIFICATION_CHALLENGES.with(|challenges| {
+        let mut challenges_mut = challenges.borrow_mut();
+        let keys: Vec<_> = challenges_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            challenges_mut.remove(&key);
+        }
+    });
+    log_info!("All verification challenges have been reset.");
+}
@@ -0,0 +1,211 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use ic_cdk_timers::set_timer;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{BreakerState, CircuitBreaker, ExternalService};
+
+// Consecutive failed calls that open a breaker
+pub const FAILURE_THRESHOLD: u32 = 5;
+// Cooldown after the first opening; doubled every time the probe fails, up to the maximum
+const BASE_COOLDOWN_SECONDS: u64 = 60;
+const MAX_COOLDOWN_SECONDS: u64 = 30 * 60;
+// A probe that never reported back (e.g. its call trapped) stops blocking others after this long
+const PROBE_TIMEOUT_SECONDS: u64 = 5 * 60;
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+pub const ALL_SERVICES: [ExternalService; 3] = [ExternalService::OpenAi, ExternalService::Scraper, ExternalService::DnsResolver];
+
+// Define a unique MemoryId for this structure
+const CIRCUIT_BREAKERS_MEM_ID: MemoryId = MemoryId::new(87);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by service name
+    static CIRCUIT_BREAKERS: RefCell<StableBTreeMap<String, CircuitBreaker, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(CIRCUIT_BREAKERS_MEM_ID))
+        )
+    );
+}
+
+pub fn service_name(service: ExternalService) -> &'static str {
+    match service {
+        ExternalService::OpenAi => "OpenAI",
+        ExternalService::Scraper => "Review scraper",
+        ExternalService::DnsResolver => "DNS resolver",
+    }
+}
+
+fn closed(service: ExternalService) -> CircuitBreaker {
+    CircuitBreaker {
+        service,
+        state: BreakerState::Closed,
+        consecutive_failures: 0,
+        consecutive_opens: 0,
+        opened_at: None,
+        half_open_at: None,
+        probe_started_at: None,
+        last_failure: None,
+        last_failure_at: None,
+        last_success_at: None,
+        total_short_circuited: 0,
+    }
+}
+
+pub fn get(service: ExternalService) -> CircuitBreaker {
+    CIRCUIT_BREAKERS
+        .with(|breakers| breakers.borrow().get(&service_name(service).to_string()))
+        .unwrap_or_else(|| closed(service))
+}
+
+fn save(breaker: CircuitBreaker) {
+    CIRCUIT_BREAKERS.with(|breakers| {
+        breakers.borrow_mut().insert(service_name(breaker.service).to_string(), breaker);
+    });
+}
+
+fn cooldown_nanos(consecutive_opens: u32) -> u64 {
+    let doublings = consecutive_opens.saturating_sub(1).min(16);
+    BASE_COOLDOWN_SECONDS
+        .saturating_mul(1 << doublings)
+        .min(MAX_COOLDOWN_SECONDS)
+        * NANOS_PER_SECOND
+}
+
+// Move an open breaker to half-open once its cooldown is over
+fn schedule_half_open(service: ExternalService, at: u64) {
+    let delay = Duration::from_nanos(at.saturating_sub(clock::now()));
+    set_timer(delay, move || {
+        let mut breaker = get(service);
+        if breaker.state == BreakerState::Open && breaker.half_open_at.is_some_and(|at| at <= clock::now()) {
+            breaker.state = BreakerState::HalfOpen;
+            breaker.probe_started_at = None;
+            save(breaker);
+            log_info!("[circuit_breakers] {} breaker is half-open; the next call probes the service", service_name(service));
+        }
+    });
+}
+
+// Fails fast with ServiceUnavailable while the breaker is open, and while another call is already
+// probing a half-open breaker. Use before spending quota on a call.
+pub fn check(service: ExternalService) -> Result<(), ApiError> {
+    admit(service, false)
+}
+
+// Ask right before each call to the service. Like check, and a call let through a half-open
+// breaker becomes its probe.
+pub fn begin_call(service: ExternalService) -> Result<(), ApiError> {
+    admit(service, true)
+}
+
+fn admit(service: ExternalService, claim_probe: bool) -> Result<(), ApiError> {
+    let now = clock::now();
+    let mut breaker = get(service);
+    // The timer may not have run yet, e.g. right after an upgrade
+    if breaker.state == BreakerState::Open && breaker.half_open_at.is_none_or(|at| at <= now) {
+        breaker.state = BreakerState::HalfOpen;
+        breaker.probe_started_at = None;
+    }
+    match breaker.state {
+        BreakerState::Closed => Ok(()),
+        BreakerState::HalfOpen
+            if breaker
+                .probe_started_at
+                .is_none_or(|started| now >= started.saturating_add(PROBE_TIMEOUT_SECONDS * NANOS_PER_SECOND)) =>
+        {
+            if claim_probe {
+                breaker.probe_started_at = Some(now);
+                save(breaker);
+            }
+            Ok(())
+        }
+        BreakerState::HalfOpen | BreakerState::Open => {
+            let retry_after = breaker.half_open_at;
+            breaker.total_short_circuited += 1;
+            save(breaker);
+            Err(ApiError::service_unavailable(service_name(service), retry_after))
+        }
+    }
+}
+
+// The service answered; a 4xx answer counts too, since the service itself is up
+pub fn record_success(service: ExternalService) {
+    let mut breaker = get(service);
+    if breaker.state != BreakerState::Closed {
+        log_info!("[circuit_breakers] {} breaker closed after a successful call", service_name(service));
+    }
+    breaker.state = BreakerState::Closed;
+    breaker.consecutive_failures = 0;
+    breaker.consecutive_opens = 0;
+    breaker.opened_at = None;
+    breaker.half_open_at = None;
+    breaker.probe_started_at = None;
+    breaker.last_success_at = Some(clock::now());
+    save(breaker);
+}
+
+// The call was rejected or the service answered with a server error
+pub fn record_failure(service: ExternalService, error: &str) {
+    let now = clock::now();
+    let mut breaker = get(service);
+    breaker.consecutive_failures = breaker.consecutive_failures.saturating_add(1);
+    breaker.last_failure = Some(error.chars().take(200).collect());
+    breaker.last_failure_at = Some(now);
+
+    let trips = breaker.state == BreakerState::HalfOpen
+        || (breaker.state == BreakerState::Closed && breaker.consecutive_failures >= FAILURE_THRESHOLD);
+    if trips {
+        breaker.state = BreakerState::Open;
+        breaker.consecutive_opens = breaker.consecutive_opens.saturating_add(1);
+        breaker.opened_at = Some(now);
+        breaker.probe_started_at = None;
+        let half_open_at = now.saturating_add(cooldown_nanos(breaker.consecutive_opens));
+        breaker.half_open_at = Some(half_open_at);
+        log_warn!(
+            "[circuit_breakers] {} breaker opened after {} consecutive failures; probing again in {}s",
+            service_name(service),
+            breaker.consecutive_failures,
+            (half_open_at - now) / NANOS_PER_SECOND
+        );
+        schedule_half_open(service, half_open_at);
+    }
+    save(breaker);
+}
+
+// Close a breaker by hand, e.g. once the operator knows the service is back
+pub fn reset(service: ExternalService) -> CircuitBreaker {
+    let mut breaker = closed(service);
+    breaker.last_success_at = get(service).last_success_at;
+    save(breaker.clone());
+    breaker
+}
+
+// Timers do not survive upgrades; put back the half-open timer of every open breaker
+pub fn resume_breakers() {
+    for service in ALL_SERVICES {
+        let breaker = get(service);
+        if let (BreakerState::Open, Some(at)) = (breaker.state, breaker.half_open_at) {
+            schedule_half_open(service, at);
+        }
+    }
+}
+
+// Reset ALL circuit breakers (use with caution)
+pub fn reset_circuit_breakers() {
+    CIRCUIT_BREAKERS.with(|breakers| {
+        let mut breakers_mut = breakers.borrow_mut();
+        let keys: Vec<_> = breakers_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            breakers_mut.remove(&key);
+        }
+    });
+    log_info!("All circuit breakers have been reset.");
+}
@@ -0,0 +1,63 @@
+use candid::Principal;
+
+use crate::config;
+use crate::error::ApiError;
+use crate::models::ClientVersionPolicy;
+use crate::org_settings;
+
+// Versions are dotted numbers ("2.4.1", optionally "v2.4.1"); anything after '-' or '+' is a
+// pre-release or build tag and is ignored. Trailing zeros are dropped so "2.4" equals "2.4.0".
+pub fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let version = version.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let core = version.split(['-', '+']).next()?;
+    if core.is_empty() {
+        return None;
+    }
+    let mut parts = core
+        .split('.')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some(parts)
+}
+
+pub fn validate_policy(policy: &ClientVersionPolicy) -> Result<(), ApiError> {
+    if parse_version(&policy.min_version).is_none() {
+        return Err(ApiError::invalid_input("Minimum client version must be a dotted number such as 2.4.0"));
+    }
+    if let Some(url) = &policy.download_url {
+        if !url.starts_with("https://") {
+            return Err(ApiError::invalid_input("Client download URL must start with https://"));
+        }
+    }
+    Ok(())
+}
+
+// Reject clients older than the stricter of the platform's and the organization's minimum. A client
+// that does not report its version is treated as outdated once a minimum is set. The download URL
+// comes from the organization, falling back to the platform's.
+pub fn check(org_id: Option<Principal>, client_version: Option<&str>) -> Result<(), ApiError> {
+    let org_policy = org_id.and_then(|id| org_settings::get_org_settings(id).client_version_policy);
+    let global_policy = config::get_config().client_version_policy;
+
+    let required = [&org_policy, &global_policy]
+        .into_iter()
+        .flatten()
+        .filter_map(|policy| parse_version(&policy.min_version).map(|v| (v, policy.min_version.clone())))
+        .max_by(|a, b| a.0.cmp(&b.0));
+    let (required, min_version) = match required {
+        Some(required) => required,
+        None => return Ok(()),
+    };
+    if client_version.and_then(parse_version).is_some_and(|v| v >= required) {
+        return Ok(());
+    }
+
+    let download_url = org_policy
+        .and_then(|policy| policy.download_url)
+        .or_else(|| global_policy.and_then(|policy| policy.download_url));
+    Err(ApiError::upgrade_required(&min_version, download_url))
+}
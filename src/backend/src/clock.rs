@@ -0,0 +1,82 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use ic_cdk::api;
+
+// Source of the current time, in nanoseconds since the Unix epoch like api::time()
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+// The IC's time, which is the same for the whole of a message
+pub struct IcClock;
+
+impl Clock for IcClock {
+    fn now(&self) -> u64 {
+        api::time()
+    }
+}
+
+// Time that only moves when told to, so expiry and rate-limit logic can be driven outside a replica
+#[derive(Default)]
+pub struct MockClock {
+    now: Cell<u64>,
+}
+
+impl MockClock {
+    pub fn new(now: u64) -> Self {
+        MockClock { now: Cell::new(now) }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.set(now);
+    }
+
+    pub fn advance(&self, nanos: u64) {
+        self.now.set(self.now.get().saturating_add(nanos));
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.get()
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Rc<dyn Clock>> = RefCell::new(Rc::new(IcClock));
+}
+
+// Current time from the installed clock
+pub fn now() -> u64 {
+    CLOCK.with(|clock| clock.borrow().now())
+}
+
+// Install another clock. Keep a handle to a MockClock to move it afterwards.
+pub fn set_clock(clock: Rc<dyn Clock>) {
+    CLOCK.with(|current| *current.borrow_mut() = clock);
+}
+
+// Go back to the IC's time
+pub fn reset_clock() {
+    set_clock(Rc::new(IcClock));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_only_moves_when_told() {
+        let mock = Rc::new(MockClock::new(1_000));
+        set_clock(mock.clone());
+        assert_eq!(now(), 1_000);
+        assert_eq!(now(), 1_000);
+
+        mock.advance(500);
+        assert_eq!(now(), 1_500);
+
+        mock.set(42);
+        assert_eq!(now(), 42);
+    }
+}
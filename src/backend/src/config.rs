@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableCell};
+
+use crate::clock;
+use crate::error::ApiError;
+use crate::feature_flags;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{CanisterConfig, LlmProviderConfig, ModerationConfig, OutcallResponseLimits, RateLimitConfig};
+
+// Defaults applied until an admin configures the canister
+pub const DEFAULT_LLM_PROVIDER: &str = "openai";
+pub const DEFAULT_LLM_API_HOST: &str = "api.openai.com";
+pub const DEFAULT_LLM_MODEL: &str = "gpt-4o";
+pub const DEFAULT_MAX_ATTEMPTS_PER_WINDOW: u32 = 5;
+pub const DEFAULT_WINDOW_DURATION_SECONDS: u64 = 60 * 5; // 5 minutes
+
+// Returned in place of secrets by the config getter
+pub const REDACTED_SECRET: &str = "********";
+
+// Define a unique MemoryId for this structure
+const CANISTER_CONFIG_MEM_ID: MemoryId = MemoryId::new(17);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static CANISTER_CONFIG: RefCell<StableCell<CanisterConfig, Memory>> = RefCell::new(
+        StableCell::init(MEMORY_MANAGER.with(|m| m.borrow().get(CANISTER_CONFIG_MEM_ID)), CanisterConfig::default())
+            .expect("Failed to initialize canister config cell")
+    );
+}
+
+pub fn get_config() -> CanisterConfig {
+    CANISTER_CONFIG.with(|cell| cell.borrow().get().clone())
+}
+
+pub fn set_config(mut config: CanisterConfig) -> Result<(), ApiError> {
+    config.updated_at = clock::now();
+    CANISTER_CONFIG
+        .with(|cell| cell.borrow_mut().set(config))
+        .map(|_| ())
+        .map_err(|e| {
+            log_error!("[set_config] Failed to write canister config: {:?}", e);
+            ApiError::internal_error("Failed to update configuration")
+        })
+}
+
+// Config as shown to admins, with secrets replaced and the global feature flags filled in
+pub fn redacted_config() -> CanisterConfig {
+    let mut config = get_config();
+    config.feature_flags = feature_flags::list_global_flags();
+    if !config.llm.api_key.is_empty() {
+        config.llm.api_key = REDACTED_SECRET.to_string();
+    }
+    config
+}
+
+pub fn llm_config() -> LlmProviderConfig {
+    get_config().llm
+}
+
+pub fn scraper_url() -> String {
+    get_config().scraper_url
+}
+
+pub fn email_relay_url() -> Option<String> {
+    get_config().email_relay_url.filter(|url| !url.is_empty())
+}
+
+pub fn outcall_limits() -> Option<OutcallResponseLimits> {
+    get_config().outcall_limits
+}
+
+pub fn rate_limit_config() -> RateLimitConfig {
+    get_config().rate_limit
+}
+
+pub fn moderation_config() -> ModerationConfig {
+    get_config().moderation.unwrap_or_default()
+}
+
+pub fn is_live_gateway(gateway: Principal) -> bool {
+    get_config().live_gateways.is_some_and(|gateways| gateways.contains(&gateway))
+}
+
+// Restore defaults (use with caution)
+pub fn reset_canister_config() {
+    let _ = set_config(CanisterConfig::default());
+    log_info!("Canister config has been reset to defaults.");
+}
@@ -0,0 +1,110 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::CouponCode;
+
+// Limits on uploads
+pub const MAX_COUPON_CODES_PER_UPLOAD: usize = 1_000;
+pub const MAX_COUPON_CODE_LENGTH: usize = 64;
+pub const MAX_COUPON_DESCRIPTION_LENGTH: usize = 200;
+
+// Define a unique MemoryId for this structure
+const COUPON_CODES_MEM_ID: MemoryId = MemoryId::new(55);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static COUPON_CODES: RefCell<StableBTreeMap<Principal, CouponCode, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(COUPON_CODES_MEM_ID))
+        )
+    );
+}
+
+pub fn save_coupon(coupon: CouponCode) {
+    COUPON_CODES.with(|coupons| {
+        coupons.borrow_mut().insert(coupon.id, coupon);
+    });
+}
+
+// Codes are unique per organization, compared case-insensitively
+pub fn code_exists(org_id: Principal, code: &str) -> bool {
+    COUPON_CODES.with(|coupons| {
+        coupons
+            .borrow()
+            .iter()
+            .any(|(_, c)| c.org_id == org_id && c.code.eq_ignore_ascii_case(code))
+    })
+}
+
+// Unredeemed coupons usable for the product: those uploaded for it, or organization-wide ones
+pub fn count_available(org_id: Principal, product_id: Option<Principal>) -> u64 {
+    COUPON_CODES.with(|coupons| {
+        coupons
+            .borrow()
+            .iter()
+            .filter(|(_, c)| c.org_id == org_id && c.redeemed_at.is_none() && c.product_id == product_id)
+            .count() as u64
+    })
+}
+
+// Hand out the oldest unredeemed coupon for the product, preferring product-specific codes over
+// organization-wide ones, and mark it redeemed in the same step
+pub fn claim_coupon(
+    org_id: Principal,
+    product_id: Principal,
+    user_id: Principal,
+    verification_id: Principal,
+) -> Option<CouponCode> {
+    let mut coupon = COUPON_CODES.with(|coupons| {
+        coupons
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .filter(|c| c.org_id == org_id && c.redeemed_at.is_none())
+            .filter(|c| c.product_id.is_none_or(|p| p == product_id))
+            .min_by_key(|c| (c.product_id.is_none(), c.uploaded_at))
+    })?;
+    coupon.redeemed_by = Some(user_id);
+    coupon.redeemed_at = Some(clock::now());
+    coupon.verification_id = Some(verification_id);
+    save_coupon(coupon.clone());
+    Some(coupon)
+}
+
+// Return a claimed coupon to the pool, e.g. when the redemption it was claimed for is undone
+pub fn release_coupon(mut coupon: CouponCode) {
+    coupon.redeemed_by = None;
+    coupon.redeemed_at = None;
+    coupon.verification_id = None;
+    save_coupon(coupon);
+}
+
+// Coupon handed out for a verification's reward, if it was redeemed as one
+pub fn find_for_verification(verification_id: Principal) -> Option<CouponCode> {
+    COUPON_CODES.with(|coupons| {
+        coupons
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .find(|c| c.verification_id == Some(verification_id))
+    })
+}
+
+// Reset ALL coupon codes (use with caution)
+pub fn reset_coupon_codes() {
+    COUPON_CODES.with(|coupons| {
+        let mut coupons_mut = coupons.borrow_mut();
+        let keys: Vec<_> = coupons_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            coupons_mut.remove(&key);
+        }
+    });
+    log_info!("All coupon codes have been reset.");
+}
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use ic_cdk::api;
+use ic_cdk_timers::{set_timer, set_timer_interval};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use serde::Serialize;
+
+use crate::clock;
+use crate::config;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{CyclesAlertConfig, CyclesLevel, CyclesSample, CyclesStatus};
+use crate::webhooks;
+
+// Thresholds used until an admin configures them
+pub const DEFAULT_WARNING_THRESHOLD: u128 = 5_000_000_000_000;
+pub const DEFAULT_CRITICAL_THRESHOLD: u128 = 1_000_000_000_000;
+
+const CYCLES_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Hourly samples kept: 30 days
+const MAX_CYCLES_SAMPLES: u64 = 30 * 24;
+
+// Define a unique MemoryId for this structure
+const CYCLES_HISTORY_MEM_ID: MemoryId = MemoryId::new(69);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// Body posted to the operator webhook
+#[derive(Serialize, Clone, Debug)]
+struct CyclesAlertPayload {
+    event: String,
+    canister_id: String,
+    level: String,
+    balance: String, // Decimal; cycle balances do not fit every JSON parser's numbers
+    threshold: String,
+    checked_at: u64,
+}
+
+thread_local! {
+    // Keyed by the time of the check
+    static CYCLES_HISTORY: RefCell<StableBTreeMap<u64, CyclesSample, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(CYCLES_HISTORY_MEM_ID))
+        )
+    );
+}
+
+// Register the recurring check and take a first sample right away. Timers do not survive upgrades,
+// so this runs from both init and post_upgrade.
+pub fn start_cycles_monitor() {
+    set_timer(Duration::ZERO, check_cycles);
+    set_timer_interval(CYCLES_CHECK_INTERVAL, check_cycles);
+}
+
+fn alert_config() -> CyclesAlertConfig {
+    config::get_config().cycles_alerts.unwrap_or(CyclesAlertConfig {
+        warning_threshold: DEFAULT_WARNING_THRESHOLD,
+        critical_threshold: DEFAULT_CRITICAL_THRESHOLD,
+        webhook_url: None,
+    })
+}
+
+fn level_for(balance: u128, alerts: &CyclesAlertConfig) -> CyclesLevel {
+    if balance < alerts.critical_threshold {
+        CyclesLevel::Critical
+    } else if balance < alerts.warning_threshold {
+        CyclesLevel::Warning
+    } else {
+        CyclesLevel::Healthy
+    }
+}
+
+fn latest_sample() -> Option<CyclesSample> {
+    CYCLES_HISTORY.with(|history| history.borrow().last_key_value().map(|(_, sample)| sample))
+}
+
+// Record the balance and alert the operator when it has dropped to a worse level than last time.
+// Staying at a level does not alert again, so a low balance produces one alert per level.
+pub fn check_cycles() {
+    let alerts = alert_config();
+    let now = clock::now();
+    let balance = api::canister_balance128();
+    let level = level_for(balance, &alerts);
+    let previous_level = latest_sample().map_or(CyclesLevel::Healthy, |sample| sample.level);
+    let alerted = level > previous_level;
+
+    if alerted {
+        let threshold = if level == CyclesLevel::Critical { alerts.critical_threshold } else { alerts.warning_threshold };
+        log_warn!("[check_cycles] Cycle balance {} is below the {:?} threshold {}", balance, level, threshold);
+        if let Some(url) = alerts.webhook_url {
+            let payload = CyclesAlertPayload {
+                event: "cycles_alert".to_string(),
+                canister_id: api::id().to_string(),
+                level: format!("{:?}", level),
+                balance: balance.to_string(),
+                threshold: threshold.to_string(),
+                checked_at: now,
+            };
+            match serde_json::to_string(&payload) {
+                Ok(body) => {
+                    webhooks::enqueue_webhook(url, body, None);
+                }
+                Err(e) => log_error!("[check_cycles] Failed to serialize cycles alert: {:?}", e),
+            }
+        }
+    }
+
+    CYCLES_HISTORY.with(|history| {
+        let mut history_mut = history.borrow_mut();
+        history_mut.insert(now, CyclesSample { timestamp: now, balance, level, alerted });
+        while history_mut.len() > MAX_CYCLES_SAMPLES {
+            match history_mut.first_key_value() {
+                Some((oldest, _)) => history_mut.remove(&oldest),
+                None => break,
+            };
+        }
+    });
+}
+
+// Live balance against the configured thresholds, with the last alert sent
+pub fn current_status() -> CyclesStatus {
+    let alerts = alert_config();
+    let balance = api::canister_balance128();
+    let last_alert_at = CYCLES_HISTORY.with(|history| {
+        history.borrow().iter().filter(|(_, sample)| sample.alerted).map(|(timestamp, _)| timestamp).last()
+    });
+    CyclesStatus {
+        balance,
+        level: level_for(balance, &alerts),
+        warning_threshold: alerts.warning_threshold,
+        critical_threshold: alerts.critical_threshold,
+        alert_webhook_configured: alerts.webhook_url.is_some(),
+        last_checked_at: latest_sample().map(|sample| sample.timestamp),
+        last_alert_at,
+    }
+}
+
+// Most recent samples first
+pub fn history(limit: usize) -> Vec<CyclesSample> {
+    CYCLES_HISTORY.with(|history| {
+        let history = history.borrow();
+        let skip = (history.len() as usize).saturating_sub(limit);
+        let mut samples: Vec<CyclesSample> = history.iter().skip(skip).map(|(_, sample)| sample).collect();
+        samples.reverse();
+        samples
+    })
+}
+
+// Reset ALL cycle samples (use with caution)
+pub fn reset_cycles_history() {
+    CYCLES_HISTORY.with(|history| {
+        let mut history_mut = history.borrow_mut();
+        let keys: Vec<_> = history_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            history_mut.remove(&key);
+        }
+    });
+    log_info!("All cycle balance samples have been reset.");
+}
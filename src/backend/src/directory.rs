@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, StableCell};
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use k256::elliptic_curve::rand_core::SeedableRng;
+use k256::sha2::{Digest, Sha256};
+use rand::prelude::StdRng;
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{StorableString, MEMORY_MANAGER};
+use crate::models::{DirectoryListing, VerifiedBrandBadge};
+
+// Limits on listing content
+pub const MAX_TAGLINE_LENGTH: usize = 160;
+pub const MAX_LISTING_URL_LENGTH: usize = 2048;
+
+// Define unique Memory IDs for the structures in this module
+const DIRECTORY_LISTINGS_MEM_ID: MemoryId = MemoryId::new(29);
+const BADGE_SIGNING_KEY_MEM_ID: MemoryId = MemoryId::new(30);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static DIRECTORY_LISTINGS: RefCell<StableBTreeMap<Principal, DirectoryListing, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(DIRECTORY_LISTINGS_MEM_ID))
+        )
+    );
+
+    // Canister-held key that signs verified-brand badges (hex encoded, empty until first use)
+    static BADGE_SIGNING_KEY: RefCell<StableCell<StorableString, Memory>> = RefCell::new(
+        StableCell::init(MEMORY_MANAGER.with(|m| m.borrow().get(BADGE_SIGNING_KEY_MEM_ID)), StorableString::default())
+            .expect("Failed to initialize badge signing key cell")
+    );
+}
+
+// Stored listing for the organization, or a private, unmoderated one if none was saved
+pub fn get_listing(org_id: Principal) -> DirectoryListing {
+    DIRECTORY_LISTINGS
+        .with(|listings| listings.borrow().get(&org_id))
+        .unwrap_or_else(|| DirectoryListing {
+            org_id,
+            ..Default::default()
+        })
+}
+
+pub fn save_listing(listing: DirectoryListing) {
+    DIRECTORY_LISTINGS.with(|listings| {
+        listings.borrow_mut().insert(listing.org_id, listing);
+    });
+}
+
+// Listings shown in the public directory: opted in and not suspended
+pub fn list_visible_listings() -> Vec<DirectoryListing> {
+    DIRECTORY_LISTINGS.with(|listings| {
+        listings
+            .borrow()
+            .iter()
+            .map(|(_, listing)| listing)
+            .filter(|listing| listing.is_public && !listing.suspended)
+            .collect()
+    })
+}
+
+pub fn validate_listing_url(url: &str) -> Result<(), ApiError> {
+    if !url.starts_with("https://") {
+        return Err(ApiError::invalid_input("Listing URLs must start with https://"));
+    }
+    if url.len() > MAX_LISTING_URL_LENGTH {
+        return Err(ApiError::invalid_input(&format!(
+            "Listing URLs cannot exceed {} characters",
+            MAX_LISTING_URL_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+// Generated on first use so that canisters that never list a brand carry no key
+fn badge_signing_key() -> SigningKey {
+    let stored = BADGE_SIGNING_KEY.with(|cell| cell.borrow().get().0.clone());
+    if let Some(key) = hex::decode(&stored).ok().and_then(|bytes| SigningKey::from_slice(&bytes).ok()) {
+        return key;
+    }
+
+    let mut rng = StdRng::from_entropy();
+    let key = SigningKey::random(&mut rng);
+    BADGE_SIGNING_KEY.with(|cell| {
+        cell.borrow_mut()
+            .set(StorableString(hex::encode(key.to_bytes())))
+            .expect("Failed to store badge signing key");
+    });
+    key
+}
+
+pub fn badge_message(org_id: Principal, issued_at: u64) -> String {
+    format!("verified-brand_{}_{}", org_id, issued_at)
+}
+
+// Sign a badge attesting that the organization is a verified brand on this canister
+pub fn issue_badge(org_id: Principal) -> VerifiedBrandBadge {
+    let key = badge_signing_key();
+    let issued_at = clock::now();
+
+    let mut hasher = Sha256::new();
+    hasher.update(badge_message(org_id, issued_at));
+    let signature: Signature = key.sign(&hasher.finalize());
+
+    VerifiedBrandBadge {
+        org_id,
+        issued_at,
+        signature: hex::encode(signature.to_bytes()),
+        public_key: hex::encode(key.verifying_key().to_encoded_point(false).as_bytes()),
+    }
+}
+
+// Reset ALL directory listings and the badge key (use with caution)
+pub fn reset_directory() {
+    DIRECTORY_LISTINGS.with(|listings| {
+        let mut listings_mut = listings.borrow_mut();
+        let keys: Vec<_> = listings_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            listings_mut.remove(&key);
+        }
+    });
+    BADGE_SIGNING_KEY.with(|cell| {
+        let _ = cell.borrow_mut().set(StorableString::default());
+    });
+    log_info!("All directory listings have been reset.");
+}
@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{Dispute, DisputeStatus};
+
+// Limits on ticket content
+pub const MAX_DISPUTE_SUBJECT_LENGTH: usize = 200;
+pub const MAX_DISPUTE_MESSAGE_LENGTH: usize = 2000;
+pub const MAX_MESSAGES_PER_DISPUTE: usize = 100;
+
+// Define a unique MemoryId for this structure
+const DISPUTES_MEM_ID: MemoryId = MemoryId::new(24);
+const OPEN_DISPUTES_BY_SERIAL_MEM_ID: MemoryId = MemoryId::new(95);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static DISPUTES: RefCell<StableBTreeMap<Principal, Dispute, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(DISPUTES_MEM_ID))
+        )
+    );
+
+    // (serial_no, dispute_id) of every dispute not yet resolved, so verifications count a serial's
+    // open reports without walking all disputes
+    static OPEN_DISPUTES_BY_SERIAL: RefCell<StableBTreeMap<(Principal, Principal), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(OPEN_DISPUTES_BY_SERIAL_MEM_ID))
+        )
+    );
+}
+
+fn unindex_open(dispute: &Dispute) {
+    OPEN_DISPUTES_BY_SERIAL.with(|index| index.borrow_mut().remove(&(dispute.serial_no, dispute.id)));
+}
+
+fn index_if_open(dispute: &Dispute) {
+    if dispute.status != DisputeStatus::Resolved {
+        OPEN_DISPUTES_BY_SERIAL.with(|index| index.borrow_mut().insert((dispute.serial_no, dispute.id), ()));
+    }
+}
+
+pub fn save_dispute(dispute: Dispute) {
+    DISPUTES.with(|disputes| {
+        let mut disputes_mut = disputes.borrow_mut();
+        if let Some(previous) = disputes_mut.get(&dispute.id) {
+            unindex_open(&previous);
+        }
+        index_if_open(&dispute);
+        disputes_mut.insert(dispute.id, dispute);
+    });
+}
+
+// Disputes on the serial that are not resolved yet
+pub fn count_open_for_serial(serial_no: Principal) -> u32 {
+    let start = (serial_no, Principal::from_slice(&[]));
+    let end = (serial_no, Principal::from_slice(&[0xFF; 29]));
+    OPEN_DISPUTES_BY_SERIAL.with(|index| index.borrow().range(start..=end).count() as u32)
+}
+
+pub fn get_dispute(dispute_id: Principal) -> Option<Dispute> {
+    DISPUTES.with(|disputes| disputes.borrow().get(&dispute_id))
+}
+
+// Disputes matching the filter, most recently updated first
+pub fn list_disputes<F>(filter: F) -> Vec<Dispute>
+where
+    F: Fn(&Dispute) -> bool,
+{
+    let mut matching: Vec<Dispute> = DISPUTES.with(|disputes| {
+        disputes
+            .borrow()
+            .iter()
+            .map(|(_, dispute)| dispute)
+            .filter(|dispute| filter(dispute))
+            .collect()
+    });
+    matching.sort_by_key(|a| std::cmp::Reverse(a.updated_at));
+    matching
+}
+
+pub fn matches_status(dispute: &Dispute, status: Option<DisputeStatus>) -> bool {
+    status.is_none_or(|s| dispute.status == s)
+}
+
+// Index the open disputes filed before the serial index existed. Only runs while the index is empty,
+// and rebuilding it from the disputes is harmless when there was nothing to index.
+pub fn backfill_open_serial_index() -> u64 {
+    if OPEN_DISPUTES_BY_SERIAL.with(|index| !index.borrow().is_empty()) {
+        return 0;
+    }
+    let open: Vec<Dispute> = DISPUTES.with(|disputes| {
+        disputes
+            .borrow()
+            .iter()
+            .map(|(_, dispute)| dispute)
+            .filter(|dispute| dispute.status != DisputeStatus::Resolved)
+            .collect()
+    });
+    for dispute in &open {
+        index_if_open(dispute);
+    }
+    open.len() as u64
+}
+
+// Remove the organization's disputes when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    DISPUTES.with(|disputes| {
+        let mut disputes_mut = disputes.borrow_mut();
+        let removed: Vec<Dispute> = disputes_mut.iter().map(|(_, dispute)| dispute).filter(|dispute| dispute.org_id == org_id).collect();
+        for dispute in &removed {
+            disputes_mut.remove(&dispute.id);
+            unindex_open(dispute);
+        }
+        removed.len() as u32
+    })
+}
+
+// Reset ALL disputes (use with caution)
+pub fn reset_disputes() {
+    DISPUTES.with(|disputes| {
+        let mut disputes_mut = disputes.borrow_mut();
+        let keys: Vec<_> = disputes_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            disputes_mut.remove(&key);
+        }
+    });
+    OPEN_DISPUTES_BY_SERIAL.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    log_info!("All disputes have been reset.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dispute(id: u8, serial_no: Principal, status: DisputeStatus) -> Dispute {
+        Dispute {
+            id: Principal::from_slice(&[id]),
+            org_id: Principal::from_slice(&[1]),
+            product_id: Principal::from_slice(&[2]),
+            serial_no,
+            verification_id: None,
+            opened_by: Principal::from_slice(&[3]),
+            subject: "Label looks off".to_string(),
+            status,
+            messages: Vec::new(),
+            resolution: None,
+            created_at: 0,
+            updated_at: 0,
+            resolved_at: None,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn open_count_follows_resolution_and_deletion() {
+        let serial_no = Principal::from_slice(&[7]);
+        save_dispute(dispute(10, serial_no, DisputeStatus::Open));
+        save_dispute(dispute(11, serial_no, DisputeStatus::InReview));
+        save_dispute(dispute(12, Principal::from_slice(&[8]), DisputeStatus::Open));
+        assert_eq!(count_open_for_serial(serial_no), 2);
+
+        save_dispute(dispute(10, serial_no, DisputeStatus::Resolved));
+        assert_eq!(count_open_for_serial(serial_no), 1);
+
+        delete_for_org(Principal::from_slice(&[1]));
+        assert_eq!(count_open_for_serial(serial_no), 0);
+    }
+}
@@ -0,0 +1,147 @@
+use std::cell::RefCell;
+
+use ic_cdk::api;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{EndpointMetrics, ErrorCodeCount};
+
+// Latest calls kept per endpoint for the latency percentiles
+pub const LATENCY_SAMPLE_SIZE: usize = 128;
+
+// Define a unique MemoryId for this structure
+const ENDPOINT_METRICS_MEM_ID: MemoryId = MemoryId::new(85);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// The call being measured: its endpoint and the last error response it built
+struct CurrentCall {
+    endpoint: &'static str,
+    error_code: Option<&'static str>,
+}
+
+thread_local! {
+    // Keyed by endpoint name
+    static ENDPOINT_METRICS: RefCell<StableBTreeMap<String, EndpointMetrics, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ENDPOINT_METRICS_MEM_ID))
+        )
+    );
+
+    static CURRENT_CALL: RefCell<Option<CurrentCall>> = const { RefCell::new(None) };
+}
+
+// Measures one call until dropped. Hold it for the whole endpoint body:
+// `let _metrics = endpoint_metrics::track("endpoint_name");`
+pub struct EndpointTimer {
+    active: bool,
+}
+
+// Start measuring an update call. Only synchronous update endpoints are tracked: queries cannot keep
+// what they record, and an async call spans several messages that could interleave with others.
+// Endpoints called from another tracked endpoint are counted as part of the outer call.
+pub fn track(endpoint: &'static str) -> EndpointTimer {
+    let active = CURRENT_CALL.with(|current| {
+        let mut current = current.borrow_mut();
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(CurrentCall { endpoint, error_code: None });
+        true
+    });
+    EndpointTimer { active }
+}
+
+// Called by ApiResponse::error, so a call is counted as failed when it answers with an error
+pub fn note_error(error: &ApiError) {
+    CURRENT_CALL.with(|current| {
+        if let Some(call) = current.borrow_mut().as_mut() {
+            call.error_code = Some(error.code());
+        }
+    });
+}
+
+impl Drop for EndpointTimer {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        if let Some(call) = CURRENT_CALL.with(|current| current.borrow_mut().take()) {
+            // Instructions executed by the message so far, which is the whole call here
+            record(call.endpoint, call.error_code, api::performance_counter(0), clock::now());
+        }
+    }
+}
+
+fn record(endpoint: &str, error_code: Option<&str>, instructions: u64, now: u64) {
+    let mut metrics = get(endpoint).unwrap_or_else(|| EndpointMetrics {
+        endpoint: endpoint.to_string(),
+        calls: 0,
+        errors: 0,
+        error_codes: Vec::new(),
+        total_instructions: 0,
+        max_instructions: 0,
+        recent_instructions: Vec::new(),
+        next_sample: 0,
+        since: now,
+        last_called_at: now,
+    });
+
+    metrics.calls += 1;
+    metrics.last_called_at = now;
+    metrics.total_instructions += instructions as u128;
+    metrics.max_instructions = metrics.max_instructions.max(instructions);
+    if metrics.recent_instructions.len() < LATENCY_SAMPLE_SIZE {
+        metrics.recent_instructions.push(instructions);
+    } else {
+        let slot = metrics.next_sample as usize % LATENCY_SAMPLE_SIZE;
+        metrics.recent_instructions[slot] = instructions;
+        metrics.next_sample = ((slot + 1) % LATENCY_SAMPLE_SIZE) as u32;
+    }
+
+    if let Some(code) = error_code {
+        metrics.errors += 1;
+        match metrics.error_codes.iter_mut().find(|c| c.code == code) {
+            Some(count) => count.count += 1,
+            None => metrics.error_codes.push(ErrorCodeCount { code: code.to_string(), count: 1 }),
+        }
+    }
+
+    ENDPOINT_METRICS.with(|all| {
+        all.borrow_mut().insert(metrics.endpoint.clone(), metrics);
+    });
+}
+
+pub fn get(endpoint: &str) -> Option<EndpointMetrics> {
+    ENDPOINT_METRICS.with(|all| all.borrow().get(&endpoint.to_string()))
+}
+
+pub fn list() -> Vec<EndpointMetrics> {
+    ENDPOINT_METRICS.with(|all| all.borrow().iter().map(|(_, metrics)| metrics).collect())
+}
+
+// Percentile of the sampled calls, taken from the sorted samples; 0 without samples
+pub fn percentile(samples: &[u64], percent: u32) -> u64 {
+    if samples.is_empty() {
+        return 0;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    sorted[(sorted.len() - 1) * percent.min(100) as usize / 100]
+}
+
+// Reset ALL endpoint metrics (use with caution), e.g. to start a new SLA reporting period
+pub fn reset_endpoint_metrics() {
+    ENDPOINT_METRICS.with(|all| {
+        let mut all_mut = all.borrow_mut();
+        let keys: Vec<_> = all_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            all_mut.remove(&key);
+        }
+    });
+    log_info!("All endpoint metrics have been reset.");
+}
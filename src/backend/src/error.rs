@@ -1,4 +1,4 @@
-use candid::{CandidType, Deserialize};
+use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
 
 use crate::models::Metadata;
@@ -18,6 +18,13 @@ impl Default for ErrorDetails {
     }
 }
 
+// A single field that failed validation
+#[derive(CandidType, Serialize, Deserialize, Debug, Clone)]
+pub struct ValidationError {
+    pub field: String, // e.g. "email" or "ecommerce_urls[2].value"
+    pub message: String,
+}
+
 // Define specific error categories
 #[derive(CandidType, Serialize, Deserialize, Debug, Clone)]
 pub enum ApiError {
@@ -29,6 +36,15 @@ pub enum ApiError {
     AlreadyExists { details: ErrorDetails },
     MalformedData { details: ErrorDetails },
     ExternalApiError { details: ErrorDetails },
+    QuotaExceeded { details: ErrorDetails },
+    ValidationFailed { details: ErrorDetails, errors: Vec<ValidationError> },
+    AccountDisabled { details: ErrorDetails }, // The caller's account was disabled by an admin
+    PossibleDuplicate { details: ErrorDetails, product_ids: Vec<Principal> }, // Retry with force to create anyway
+    UpgradeRequired { details: ErrorDetails, min_version: String, download_url: Option<String> }, // Client is older than the required version
+    TermsNotAccepted { details: ErrorDetails, version: String, url: String }, // Accept this version with accept_terms_v2 and retry
+    ServiceUnavailable { details: ErrorDetails, service: String, retry_after: Option<u64> }, // An external service is failing; retry after this time
+    FeatureDisabled { details: ErrorDetails, feature: String }, // The feature flag is off for the caller's organization
+    RateLimited { details: ErrorDetails, retry_after: u64 }, // Too many attempts; the caller is locked out until this time
 }
 
 // Helper functions to create errors (optional, but can be convenient)
@@ -60,4 +76,104 @@ impl ApiError {
     pub fn external_api_error(message: &str) -> Self {
         ApiError::ExternalApiError { details: ErrorDetails { message: message.to_string(), ..Default::default() } }
     }
-}
\ No newline at end of file
+
+    pub fn quota_exceeded(message: &str) -> Self {
+        ApiError::QuotaExceeded { details: ErrorDetails { message: message.to_string(), ..Default::default() } }
+    }
+
+    pub fn account_disabled(message: &str) -> Self {
+        ApiError::AccountDisabled { details: ErrorDetails { message: message.to_string(), ..Default::default() } }
+    }
+
+    pub fn possible_duplicate(product_ids: Vec<Principal>) -> Self {
+        ApiError::PossibleDuplicate {
+            details: ErrorDetails {
+                message: format!("{} existing product(s) have the same name and category", product_ids.len()),
+                ..Default::default()
+            },
+            product_ids,
+        }
+    }
+
+    pub fn upgrade_required(min_version: &str, download_url: Option<String>) -> Self {
+        ApiError::UpgradeRequired {
+            details: ErrorDetails {
+                message: format!("Please update the app to version {} or later", min_version),
+                ..Default::default()
+            },
+            min_version: min_version.to_string(),
+            download_url,
+        }
+    }
+
+    pub fn terms_not_accepted(version: &str, url: &str) -> Self {
+        ApiError::TermsNotAccepted {
+            details: ErrorDetails {
+                message: format!("Please accept the terms (version {}) to continue", version),
+                ..Default::default()
+            },
+            version: version.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    pub fn service_unavailable(service: &str, retry_after: Option<u64>) -> Self {
+        ApiError::ServiceUnavailable {
+            details: ErrorDetails {
+                message: format!("{} is temporarily unavailable, please try again later", service),
+                ..Default::default()
+            },
+            service: service.to_string(),
+            retry_after,
+        }
+    }
+
+    pub fn feature_disabled(feature: &str) -> Self {
+        ApiError::FeatureDisabled {
+            details: ErrorDetails {
+                message: format!("Feature '{}' is not enabled", feature),
+                ..Default::default()
+            },
+            feature: feature.to_string(),
+        }
+    }
+
+    pub fn rate_limited(retry_after: u64) -> Self {
+        ApiError::RateLimited {
+            details: ErrorDetails {
+                message: format!("Rate limit exceeded. Try again after {}", retry_after),
+                ..Default::default()
+            },
+            retry_after,
+        }
+    }
+
+    pub fn validation_failed(errors: Vec<ValidationError>) -> Self {
+        ApiError::ValidationFailed {
+            details: ErrorDetails { message: format!("{} field(s) failed validation", errors.len()), ..Default::default() },
+            errors,
+        }
+    }
+
+    // Name of the variant, for counting errors by kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound { .. } => "NotFound",
+            ApiError::Unauthorized { .. } => "Unauthorized",
+            ApiError::InvalidInput { .. } => "InvalidInput",
+            ApiError::InternalError { .. } => "InternalError",
+            ApiError::AlreadyExists { .. } => "AlreadyExists",
+            ApiError::MalformedData { .. } => "MalformedData",
+            ApiError::ExternalApiError { .. } => "ExternalApiError",
+            ApiError::QuotaExceeded { .. } => "QuotaExceeded",
+            ApiError::ValidationFailed { .. } => "ValidationFailed",
+            ApiError::AccountDisabled { .. } => "AccountDisabled",
+            ApiError::PossibleDuplicate { .. } => "PossibleDuplicate",
+            ApiError::UpgradeRequired { .. } => "UpgradeRequired",
+            ApiError::TermsNotAccepted { .. } => "TermsNotAccepted",
+            ApiError::ServiceUnavailable { .. } => "ServiceUnavailable",
+            ApiError::FeatureDisabled { .. } => "FeatureDisabled",
+            ApiError::RateLimited { .. } => "RateLimited",
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{StorableString, MEMORY_MANAGER};
+use crate::models::FeatureFlag;
+
+// Flags consulted by features that are being rolled out gradually. Unset flags are off.
+pub const FLAG_WEBHOOKS: &str = "webhooks";
+pub const FLAG_SHORT_CODES: &str = "short_codes";
+pub const FLAG_TECDSA: &str = "tecdsa";
+pub const FLAG_REWARD_POOLS: &str = "reward_pools";
+pub const FLAG_VERIFICATION_SIMULATOR: &str = "verification_simulator";
+
+const KNOWN_FLAGS: [&str; 5] = [FLAG_WEBHOOKS, FLAG_SHORT_CODES, FLAG_TECDSA, FLAG_REWARD_POOLS, FLAG_VERIFICATION_SIMULATOR];
+
+// Define unique Memory IDs for the structures in this module
+const GLOBAL_FLAGS_MEM_ID: MemoryId = MemoryId::new(18);
+const ORG_FLAG_OVERRIDES_MEM_ID: MemoryId = MemoryId::new(19);
+
+// Key for per-organization overrides
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FlagOverrideKey {
+    pub org_id: Principal,
+    pub flag: String,
+}
+
+impl Storable for FlagOverrideKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static GLOBAL_FLAGS: RefCell<StableBTreeMap<StorableString, bool, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(GLOBAL_FLAGS_MEM_ID))
+        )
+    );
+
+    static ORG_FLAG_OVERRIDES: RefCell<StableBTreeMap<FlagOverrideKey, bool, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_FLAG_OVERRIDES_MEM_ID))
+        )
+    );
+}
+
+// Flag names are stored trimmed and lowercase, so every endpoint that sets one must pass it through here
+pub fn normalize_flag_name(flag: &str) -> Result<String, ApiError> {
+    let flag = flag.trim().to_lowercase();
+    if flag.is_empty() {
+        return Err(ApiError::invalid_input("Flag name cannot be empty"));
+    }
+    Ok(flag)
+}
+
+pub fn set_global_flag(flag: &str, enabled: bool) {
+    GLOBAL_FLAGS.with(|flags| {
+        flags.borrow_mut().insert(StorableString(flag.to_string()), enabled);
+    });
+}
+
+pub fn get_global_flag(flag: &str) -> Option<bool> {
+    GLOBAL_FLAGS.with(|flags| flags.borrow().get(&StorableString(flag.to_string())))
+}
+
+pub fn list_global_flags() -> Vec<FeatureFlag> {
+    GLOBAL_FLAGS.with(|flags| {
+        flags
+            .borrow()
+            .iter()
+            .map(|(name, enabled)| FeatureFlag { name: name.0, enabled })
+            .collect()
+    })
+}
+
+pub fn set_org_override(org_id: Principal, flag: &str, enabled: bool) {
+    ORG_FLAG_OVERRIDES.with(|overrides| {
+        overrides.borrow_mut().insert(FlagOverrideKey { org_id, flag: flag.to_string() }, enabled);
+    });
+}
+
+pub fn clear_org_override(org_id: Principal, flag: &str) -> Option<bool> {
+    ORG_FLAG_OVERRIDES.with(|overrides| {
+        overrides.borrow_mut().remove(&FlagOverrideKey { org_id, flag: flag.to_string() })
+    })
+}
+
+pub fn get_org_override(org_id: Principal, flag: &str) -> Option<bool> {
+    ORG_FLAG_OVERRIDES.with(|overrides| {
+        overrides.borrow().get(&FlagOverrideKey { org_id, flag: flag.to_string() })
+    })
+}
+
+pub fn list_org_overrides(org_id: Principal) -> Vec<FeatureFlag> {
+    ORG_FLAG_OVERRIDES.with(|overrides| {
+        overrides
+            .borrow()
+            .iter()
+            .filter(|(key, _)| key.org_id == org_id)
+            .map(|(key, enabled)| FeatureFlag { name: key.flag, enabled })
+            .collect()
+    })
+}
+
+// An organization override wins over the global value; flags never set are off
+pub fn is_enabled(flag: &str, org_id: Option<Principal>) -> bool {
+    org_id
+        .and_then(|org| get_org_override(org, flag))
+        .or_else(|| get_global_flag(flag))
+        .unwrap_or(false)
+}
+
+// Effective value of every known or configured flag, for clients deciding what to show
+pub fn effective_flags(org_id: Option<Principal>) -> Vec<FeatureFlag> {
+    let mut names: Vec<String> = KNOWN_FLAGS.iter().map(|flag| flag.to_string()).collect();
+    let configured = list_global_flags()
+        .into_iter()
+        .chain(org_id.map(list_org_overrides).unwrap_or_default())
+        .map(|flag| flag.name);
+    for name in configured {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+        .into_iter()
+        .map(|name| FeatureFlag { enabled: is_enabled(&name, org_id), name })
+        .collect()
+}
+
+// Guard for endpoints behind a flag
+pub fn ensure_enabled(flag: &str, org_id: Option<Principal>) -> Result<(), ApiError> {
+    if is_enabled(flag, org_id) {
+        Ok(())
+    } else {
+        Err(ApiError::feature_disabled(flag))
+    }
+}
+
+// Reset ALL flags and overrides (use with caution)
+pub fn reset_feature_flags() {
+    GLOBAL_FLAGS.with(|flags| {
+        let mut flags_mut = flags.borrow_mut();
+        let keys: Vec<_> = flags_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            flags_mut.remove(&key);
+        }
+    });
+    ORG_FLAG_OVERRIDES.with(|overrides| {
+        let mut overrides_mut = overrides.borrow_mut();
+        let keys: Vec<_> = overrides_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            overrides_mut.remove(&key);
+        }
+    });
+    log_info!("All feature flags have been reset.");
+}
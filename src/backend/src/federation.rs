@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{VerificationDelegation, VerificationDelegationStatus};
+
+// Define a unique MemoryId for this structure
+const VERIFICATION_DELEGATIONS_MEM_ID: MemoryId = MemoryId::new(54);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by product ID: a product has at most one delegate manufacturer at a time. Revoked
+    // delegations stay until the brand delegates the product again.
+    static VERIFICATION_DELEGATIONS: RefCell<StableBTreeMap<Principal, VerificationDelegation, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_DELEGATIONS_MEM_ID))
+        )
+    );
+}
+
+pub fn save_delegation(delegation: VerificationDelegation) {
+    VERIFICATION_DELEGATIONS.with(|delegations| {
+        delegations.borrow_mut().insert(delegation.product_id, delegation);
+    });
+}
+
+pub fn get_delegation(product_id: Principal) -> Option<VerificationDelegation> {
+    VERIFICATION_DELEGATIONS.with(|delegations| delegations.borrow().get(&product_id))
+}
+
+// The product's delegation, if it is currently in force
+pub fn active_delegation(product_id: Principal) -> Option<VerificationDelegation> {
+    get_delegation(product_id).filter(|d| d.status == VerificationDelegationStatus::Active)
+}
+
+// Delegations the organization granted as a brand or received as a manufacturer, newest first
+pub fn list_for_org(org_id: Principal) -> Vec<VerificationDelegation> {
+    let mut matching: Vec<VerificationDelegation> = VERIFICATION_DELEGATIONS.with(|delegations| {
+        delegations
+            .borrow()
+            .iter()
+            .map(|(_, d)| d)
+            .filter(|d| d.brand_org_id == org_id || d.manufacturer_org_id == org_id)
+            .collect()
+    });
+    matching.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    matching
+}
+
+// Reset ALL verification delegations (use with caution)
+pub fn reset_delegations() {
+    VERIFICATION_DELEGATIONS.with(|delegations| {
+        let mut delegations_mut = delegations.borrow_mut();
+        let keys: Vec<_> = delegations_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            delegations_mut.remove(&key);
+        }
+    });
+    log_info!("All verification delegations have been reset.");
+}
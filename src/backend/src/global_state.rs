@@ -12,12 +12,12 @@ use serde::Serialize;
 use crate::models::{Organization, Product, User, Reseller, ProductSerialNumber, ProductVerification};
 
 // Define Memory IDs for stable structures
-const ORGANIZATION_MEM_ID: MemoryId = MemoryId::new(0);
-const PRODUCT_MEM_ID: MemoryId = MemoryId::new(1);
-const USER_MEM_ID: MemoryId = MemoryId::new(2);
-const RESELLER_MEM_ID: MemoryId = MemoryId::new(3);
-const PRODUCT_SERIAL_NUMBER_MEM_ID: MemoryId = MemoryId::new(4);
-const PRODUCT_VERIFICATION_MEM_ID: MemoryId = MemoryId::new(5);
+pub(crate) const ORGANIZATION_MEM_ID: MemoryId = MemoryId::new(0);
+pub(crate) const PRODUCT_MEM_ID: MemoryId = MemoryId::new(1);
+pub(crate) const USER_MEM_ID: MemoryId = MemoryId::new(2);
+pub(crate) const RESELLER_MEM_ID: MemoryId = MemoryId::new(3);
+pub(crate) const PRODUCT_SERIAL_NUMBER_MEM_ID: MemoryId = MemoryId::new(4);
+pub(crate) const PRODUCT_VERIFICATION_MEM_ID: MemoryId = MemoryId::new(5);
 // Reserve IDs 6, 7, 8, 9 for rate_limiter and rewards
 const CONFIG_OPENAI_KEY_MEM_ID: MemoryId = MemoryId::new(10);
 const CONFIG_SCRAPER_URL_MEM_ID: MemoryId = MemoryId::new(11);
@@ -30,7 +30,7 @@ type Memory = VirtualMemory<DefaultMemoryImpl>;
 pub struct StorableBytes(pub Vec<u8>);
 
 impl Storable for StorableBytes {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(&self.0)
     }
 
@@ -46,7 +46,7 @@ impl Storable for StorableBytes {
 pub struct StorableString(pub String);
 
 impl Storable for StorableString {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         // Borrow the inner string's bytes
         Cow::Borrowed(self.0.as_bytes())
     }
@@ -60,7 +60,7 @@ impl Storable for StorableString {
 }
 
 thread_local! {
-    static RNG: RefCell<Option<StdRng>> = RefCell::new(None);
+    static RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
 
     pub static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
         RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
@@ -119,24 +119,40 @@ pub fn encode_product_verifications(data: &Vec<ProductVerification>) -> Storable
 fn _restart_rng() {
     let _timer_id = ic_cdk_timers::set_timer(Duration::ZERO, || ic_cdk::spawn(async {
         let (seed,): ([u8; 32],) = ic_cdk::call(Principal::management_canister(), "raw_rand", ()).await.unwrap();
-        ic_cdk::println!("Got seed");
+        log_debug!("Got seed");
         RNG.with(|rng| *rng.borrow_mut() = Some(StdRng::from_seed(seed)));
     }));
-    ic_cdk::println!("registered timer {:?}", _timer_id);
+    log_debug!("registered timer {:?}", _timer_id);
 }
 
 #[post_upgrade]
 fn post_upgrade() {
     _restart_rng();
+    crate::migrations::run_post_upgrade_migrations();
+    crate::invariants::check_after_upgrade();
+    crate::reports::start_report_scheduler();
+    crate::bi_export::start_bi_export_scheduler();
+    crate::outbox::start_outbox_dispatcher();
+    crate::cycles_monitor::start_cycles_monitor();
+    crate::verification_retention::start_rollup_scheduler();
+    crate::jobs::resume_jobs();
+    crate::recall_campaigns::resume_campaigns();
+    crate::circuit_breakers::resume_breakers();
+    crate::public_stats::resume_publishing();
 }
 
 #[init]
 fn init() {
     _restart_rng();
+    crate::reports::start_report_scheduler();
+    crate::bi_export::start_bi_export_scheduler();
+    crate::outbox::start_outbox_dispatcher();
+    crate::cycles_monitor::start_cycles_monitor();
+    crate::verification_retention::start_rollup_scheduler();
 }
 
 fn custom_getrandom(buf: &mut [u8]) -> Result<(), getrandom::Error> {
-    ic_cdk::println!("custom_getrandom");
+    log_debug!("custom_getrandom");
     RNG.with(|rng| rng.borrow_mut().as_mut().unwrap().fill_bytes(buf));
     Ok(())
 }
@@ -0,0 +1,210 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use k256::sha2::{Digest, Sha256};
+use rand::prelude::StdRng;
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{StorableString, MEMORY_MANAGER};
+use crate::models::{GuestSession, GuestVerificationRef};
+use crate::utils::generate_unique_principal;
+
+// Session lifetimes (in nanoseconds, matching clock::now())
+pub const GUEST_SESSION_TTL_NS: u64 = 24 * 60 * 60 * 1_000_000_000; // 24 hours
+pub const GUEST_CLAIM_WINDOW_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // claimable for 7 days after expiry
+
+// Verifications a single guest session may record
+pub const MAX_VERIFICATIONS_PER_GUEST_SESSION: usize = 20;
+
+// Canister-wide cap on new guest sessions, since anonymous callers cannot be told apart
+const MAX_GUEST_SESSIONS_PER_HOUR: u32 = 1_000;
+const SESSION_WINDOW_NS: u64 = 60 * 60 * 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const GUEST_SESSIONS_MEM_ID: MemoryId = MemoryId::new(31);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by the hex SHA-256 of the session token so raw tokens are never stored
+    static GUEST_SESSIONS: RefCell<StableBTreeMap<StorableString, GuestSession, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(GUEST_SESSIONS_MEM_ID))
+        )
+    );
+
+    // (window_start, sessions started in window); heap only, so an upgrade simply opens a new window
+    static SESSION_WINDOW: RefCell<(u64, u32)> = const { RefCell::new((0, 0)) };
+}
+
+fn token_key(token: &str) -> StorableString {
+    let mut hasher = Sha256::new();
+    hasher.update(token.trim().as_bytes());
+    StorableString(hex::encode(hasher.finalize()))
+}
+
+fn reserve_session_slot(now: u64) -> Result<(), ApiError> {
+    SESSION_WINDOW.with(|window| {
+        let mut window = window.borrow_mut();
+        if now >= window.0.saturating_add(SESSION_WINDOW_NS) {
+            *window = (now, 0);
+        }
+        if window.1 >= MAX_GUEST_SESSIONS_PER_HOUR {
+            return Err(ApiError::invalid_input(&format!(
+                "Too many guest sessions. Try again after {}",
+                window.0 + SESSION_WINDOW_NS
+            )));
+        }
+        window.1 += 1;
+        Ok(())
+    })
+}
+
+// Drop sessions that can no longer be used or claimed
+fn prune_stale_sessions(now: u64) {
+    GUEST_SESSIONS.with(|sessions| {
+        let mut sessions_mut = sessions.borrow_mut();
+        let stale: Vec<StorableString> = sessions_mut
+            .iter()
+            .filter(|(_, session)| now >= session.expires_at.saturating_add(GUEST_CLAIM_WINDOW_NS))
+            .map(|(k, _)| k)
+            .collect();
+        for key in stale {
+            sessions_mut.remove(&key);
+        }
+    });
+}
+
+// Start a new session and return it together with its bearer token
+pub fn start_session() -> Result<(String, GuestSession), ApiError> {
+    let now = clock::now();
+    reserve_session_slot(now)?;
+    prune_stale_sessions(now);
+
+    let mut token_bytes = [0u8; 32];
+    StdRng::from_entropy().fill_bytes(&mut token_bytes);
+    let token = hex::encode(token_bytes);
+
+    let session = GuestSession {
+        id: generate_unique_principal(Principal::anonymous()),
+        created_at: now,
+        expires_at: now.saturating_add(GUEST_SESSION_TTL_NS),
+        verifications: Vec::new(),
+        claimed_by: None,
+        claimed_at: None,
+    };
+    GUEST_SESSIONS.with(|sessions| sessions.borrow_mut().insert(token_key(&token), session.clone()));
+    Ok((token, session))
+}
+
+// Session the token grants verification rights to: known, unexpired, unclaimed and under its cap
+pub fn authorize_verification(token: &str) -> Result<GuestSession, ApiError> {
+    let session = GUEST_SESSIONS
+        .with(|sessions| sessions.borrow().get(&token_key(token)))
+        .ok_or_else(|| ApiError::unauthorized("Invalid guest session token"))?;
+
+    if session.claimed_by.is_some() {
+        return Err(ApiError::unauthorized("Guest session has already been claimed; sign in to keep verifying"));
+    }
+    if clock::now() >= session.expires_at {
+        return Err(ApiError::unauthorized("Guest session has expired"));
+    }
+    if session.verifications.len() >= MAX_VERIFICATIONS_PER_GUEST_SESSION {
+        return Err(ApiError::invalid_input(&format!(
+            "Guest sessions are limited to {} verifications; sign in to continue",
+            MAX_VERIFICATIONS_PER_GUEST_SESSION
+        )));
+    }
+    Ok(session)
+}
+
+pub fn record_verification(token: &str, verification_id: Principal, product_id: Principal) {
+    GUEST_SESSIONS.with(|sessions| {
+        let mut sessions_mut = sessions.borrow_mut();
+        let key = token_key(token);
+        if let Some(mut session) = sessions_mut.get(&key) {
+            session.verifications.push(GuestVerificationRef { verification_id, product_id });
+            sessions_mut.insert(key, session);
+        }
+    });
+}
+
+// Mark the session as claimed by `user_id`, returning it so the caller can transfer its verifications
+pub fn claim(token: &str, user_id: Principal) -> Result<GuestSession, ApiError> {
+    let key = token_key(token);
+    let now = clock::now();
+    GUEST_SESSIONS.with(|sessions| {
+        let mut sessions_mut = sessions.borrow_mut();
+        let mut session = sessions_mut
+            .get(&key)
+            .ok_or_else(|| ApiError::not_found("Guest session not found"))?;
+
+        if session.claimed_by.is_some() {
+            return Err(ApiError::already_exists("Guest session has already been claimed"));
+        }
+        if now >= session.expires_at.saturating_add(GUEST_CLAIM_WINDOW_NS) {
+            return Err(ApiError::invalid_input("Guest session can no longer be claimed"));
+        }
+
+        session.claimed_by = Some(user_id);
+        session.claimed_at = Some(now);
+        sessions_mut.insert(key, session.clone());
+        Ok(session)
+    })
+}
+
+// Reset ALL guest sessions (use with caution)
+pub fn reset_guest_sessions() {
+    GUEST_SESSIONS.with(|sessions| {
+        let mut sessions_mut = sessions.borrow_mut();
+        let keys: Vec<_> = sessions_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            sessions_mut.remove(&key);
+        }
+    });
+    log_info!("All guest sessions have been reset.");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn mock_clock() -> Rc<MockClock> {
+        let mock = Rc::new(MockClock::new(1_700_000_000_000_000_000));
+        clock::set_clock(mock.clone());
+        mock
+    }
+
+    #[test]
+    fn session_stops_verifying_at_expiry_but_stays_claimable() {
+        let mock = mock_clock();
+        let (token, _) = start_session().unwrap();
+        assert!(authorize_verification(&token).is_ok());
+
+        mock.advance(GUEST_SESSION_TTL_NS - 1);
+        assert!(authorize_verification(&token).is_ok());
+        mock.advance(1);
+        assert!(authorize_verification(&token).is_err());
+
+        mock.advance(GUEST_CLAIM_WINDOW_NS - 1);
+        let claimed = claim(&token, Principal::from_slice(&[1])).unwrap();
+        assert_eq!(claimed.claimed_at, Some(clock::now()));
+    }
+
+    #[test]
+    fn session_cannot_be_claimed_after_the_claim_window() {
+        let mock = mock_clock();
+        let (token, _) = start_session().unwrap();
+
+        mock.advance(GUEST_SESSION_TTL_NS + GUEST_CLAIM_WINDOW_NS);
+        assert!(claim(&token, Principal::from_slice(&[1])).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::{ProductVerification, ProductVerificationStatus, VerificationHeatmapDay, VerificationHeatmapKind};
+use crate::verification_store;
+
+// Days of hourly counts kept per organization
+pub const HEATMAP_RETENTION_DAYS: u64 = 366;
+
+const HOUR_NS: u64 = 60 * 60 * 1_000_000_000;
+const DAY_NS: u64 = 24 * HOUR_NS;
+
+// Define a unique MemoryId for this structure
+const VERIFICATION_HEATMAP_MEM_ID: MemoryId = MemoryId::new(63);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// (org_id, day since the Unix epoch in UTC)
+type HeatmapKey = (Principal, u64);
+
+thread_local! {
+    static VERIFICATION_HEATMAP: RefCell<StableBTreeMap<HeatmapKey, VerificationHeatmapDay, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_HEATMAP_MEM_ID))
+        )
+    );
+}
+
+fn empty_day() -> VerificationHeatmapDay {
+    VerificationHeatmapDay {
+        all: vec![0; 24],
+        first: vec![0; 24],
+        invalid: vec![0; 24],
+    }
+}
+
+fn is_empty(day: &VerificationHeatmapDay) -> bool {
+    day.all.iter().chain(&day.first).chain(&day.invalid).all(|count| *count == 0)
+}
+
+fn first_retained_day(now: u64) -> u64 {
+    (now / DAY_NS + 1).saturating_sub(HEATMAP_RETENTION_DAYS)
+}
+
+fn org_keys(org_id: Principal, before_day: u64) -> Vec<HeatmapKey> {
+    VERIFICATION_HEATMAP.with(|heatmap| {
+        heatmap.borrow().range((org_id, 0)..(org_id, before_day)).map(|(k, _)| k).collect()
+    })
+}
+
+fn add_to_hour<F>(org_id: Principal, timestamp: u64, update: F)
+where
+    F: FnOnce(&mut VerificationHeatmapDay, usize),
+{
+    let day = timestamp / DAY_NS;
+    let hour = ((timestamp % DAY_NS) / HOUR_NS) as usize;
+    let expired = org_keys(org_id, first_retained_day(timestamp));
+    VERIFICATION_HEATMAP.with(|heatmap| {
+        let mut heatmap_mut = heatmap.borrow_mut();
+        let mut bucket = heatmap_mut.get(&(org_id, day)).unwrap_or_else(empty_day);
+        update(&mut bucket, hour);
+        heatmap_mut.insert((org_id, day), bucket);
+        // Days that have left the retention window go as soon as the organization records something new
+        for key in expired {
+            heatmap_mut.remove(&key);
+        }
+    });
+}
+
+fn count_verification(bucket: &mut VerificationHeatmapDay, hour: usize, status: &ProductVerificationStatus) {
+    match status {
+        ProductVerificationStatus::FirstVerification => {
+            bucket.all[hour] += 1;
+            bucket.first[hour] += 1;
+        }
+        ProductVerificationStatus::MultipleVerification => bucket.all[hour] += 1,
+        _ => {}
+    }
+}
+
+// A genuine verification was recorded
+pub fn on_verification_recorded(org_id: Principal, verification: &ProductVerification) {
+    add_to_hour(org_id, verification.created_at, |bucket, hour| {
+        count_verification(bucket, hour, &verification.status)
+    });
+}
+
+// A scanned code did not verify. Such scans leave no stored record, so these counts cannot be rebuilt.
+pub fn on_invalid_scan(org_id: Principal, timestamp: u64) {
+    add_to_hour(org_id, timestamp, |bucket, hour| bucket.invalid[hour] += 1);
+}
+
+// 7x24 matrix of counts between `from` and `to`, rows Monday first. Hours are shifted by
+// `utc_offset_hours` before being placed, so rows and columns follow the brand's local time.
+// Precision is one hour: an hour is counted whole if any part of it falls in the range.
+pub fn heatmap(
+    org_id: Principal,
+    from: u64,
+    to: u64,
+    kind: &VerificationHeatmapKind,
+    utc_offset_hours: i64,
+) -> (Vec<Vec<u64>>, u64) {
+    let mut counts = vec![vec![0u64; 24]; 7];
+    let mut total = 0;
+    VERIFICATION_HEATMAP.with(|heatmap| {
+        for ((_, day), bucket) in heatmap.borrow().range((org_id, from / DAY_NS)..=(org_id, to / DAY_NS)) {
+            let hours = match kind {
+                VerificationHeatmapKind::All => &bucket.all,
+                VerificationHeatmapKind::FirstOnly => &bucket.first,
+                VerificationHeatmapKind::InvalidOnly => &bucket.invalid,
+            };
+            for (hour, count) in hours.iter().enumerate() {
+                let hour_start = day * DAY_NS + hour as u64 * HOUR_NS;
+                if *count == 0 || hour_start.saturating_add(HOUR_NS) <= from || hour_start > to {
+                    continue;
+                }
+                let local_hour = (day * 24 + hour as u64) as i64 + utc_offset_hours;
+                // Day 0 of the epoch was a Thursday
+                let weekday = (local_hour.div_euclid(24) + 3).rem_euclid(7) as usize;
+                counts[weekday][local_hour.rem_euclid(24) as usize] += count;
+                total += count;
+            }
+        }
+    });
+    (counts, total)
+}
+
+// Recompute an organization's genuine verification counts from stored verifications.
+// Invalid scan counts are carried over, as there is nothing to rebuild them from.
+pub fn rebuild(org_id: Principal) {
+    let first_day = first_retained_day(clock::now());
+    let mut days: BTreeMap<u64, VerificationHeatmapDay> = VERIFICATION_HEATMAP.with(|heatmap| {
+        heatmap
+            .borrow()
+            .range((org_id, first_day)..=(org_id, u64::MAX))
+            .map(|((_, day), bucket)| (day, VerificationHeatmapDay { invalid: bucket.invalid, ..empty_day() }))
+            .collect()
+    });
+
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
+    });
+    for product_id in product_ids {
+        for verification in verification_store::list_for_product(product_id) {
+            let day = verification.created_at / DAY_NS;
+            if verification.is_test == Some(true) || day < first_day {
+                continue;
+            }
+            let hour = ((verification.created_at % DAY_NS) / HOUR_NS) as usize;
+            count_verification(days.entry(day).or_insert_with(empty_day), hour, &verification.status);
+        }
+    }
+
+    let stale = org_keys(org_id, u64::MAX);
+    VERIFICATION_HEATMAP.with(|heatmap| {
+        let mut heatmap_mut = heatmap.borrow_mut();
+        for key in stale {
+            heatmap_mut.remove(&key);
+        }
+        for (day, bucket) in days {
+            if !is_empty(&bucket) {
+                heatmap_mut.insert((org_id, day), bucket);
+            }
+        }
+    });
+}
+
+// Seed counts for organizations that have verifications but no heatmap yet, in a single pass
+pub fn backfill_missing() -> u32 {
+    let seeded: HashSet<Principal> =
+        VERIFICATION_HEATMAP.with(|heatmap| heatmap.borrow().iter().map(|((org_id, _), _)| org_id).collect());
+    let product_orgs: HashMap<Principal, Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| !seeded.contains(&product.org_id))
+            .map(|(id, product)| (id, product.org_id))
+            .collect()
+    });
+
+    let first_day = first_retained_day(clock::now());
+    let mut days: BTreeMap<HeatmapKey, VerificationHeatmapDay> = BTreeMap::new();
+    verification_store::for_each(|verification| {
+        let org_id = match product_orgs.get(&verification.product_id) {
+            Some(org_id) => *org_id,
+            None => return,
+        };
+        let day = verification.created_at / DAY_NS;
+        if verification.is_test == Some(true) || day < first_day {
+            return;
+        }
+        let hour = ((verification.created_at % DAY_NS) / HOUR_NS) as usize;
+        count_verification(days.entry((org_id, day)).or_insert_with(empty_day), hour, &verification.status);
+    });
+
+    let orgs: HashSet<Principal> = days.keys().map(|(org_id, _)| *org_id).collect();
+    VERIFICATION_HEATMAP.with(|heatmap| {
+        let mut heatmap_mut = heatmap.borrow_mut();
+        for (key, bucket) in days {
+            if !is_empty(&bucket) {
+                heatmap_mut.insert(key, bucket);
+            }
+        }
+    });
+    orgs.len() as u32
+}
+
+// Reset ALL verification heatmap counts (use with caution)
+pub fn reset_verification_heatmap() {
+    VERIFICATION_HEATMAP.with(|heatmap| {
+        let mut heatmap_mut = heatmap.borrow_mut();
+        let keys: Vec<_> = heatmap_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            heatmap_mut.remove(&key);
+        }
+    });
+    log_info!("All verification heatmap counts have been reset.");
+}
@@ -1,15 +1,10 @@
 use candid::Principal;
 use ic_cdk::{api, query, update};
 use k256::{
-    ecdsa::{
-        signature::{Signer, Verifier},
-        Signature, SigningKey, VerifyingKey,
-    },
-    elliptic_curve::sec1::ToEncodedPoint,
+    ecdsa::Signature,
     sha2::{Digest, Sha256},
-    EncodedPoint, SecretKey,
 };
-use crate::auth::{authorize_for_organization, ensure_admin, Permission};
+use crate::auth::{authorize_for_organization, authorize_product_operation, check_permission, ensure_admin, ensure_can_verify, ensure_caller_enabled, ensure_enabled, effective_permissions, find_user_by_caller, AuditLogEntry, Permission};
 use crate::error::ApiError;
 use crate::models::{Metadata, Organization, OrganizationInput, OrganizationPublic, OrganizationResult, PrivateKeyResult, Product, ProductInput, ProductResult, ProductSerialNumber, ProductSerialNumberResult, ProductUniqueCodeResult, ProductUniqueCodeResultRecord, ProductVerification, ProductVerificationResult, ProductVerificationStatus, Reseller, ResellerInput, ResellerVerificationResult, UniqueCodeResult, User, UserDetailsInput, UserResult, UserRole, UserPublic, AuthContextResponse, BrandOwnerContextDetails, ResellerContextDetails, LogoutResponse, CreateOrganizationWithOwnerContextRequest, OrganizationContextResponse, CompleteResellerProfileRequest, ResellerCertificationPageContext, ResellerPublic, NavigationContextResponse};
 use crate::api::{ // Corrected: Import from crate::api
@@ -21,8 +16,7 @@ use crate::api::{ // Corrected: Import from crate::api
 use crate::utils::generate_unique_principal;
 use crate::{
     global_state::{
-        decode_product_serial_numbers, decode_product_verifications, encode_product_serial_numbers,
-        encode_product_verifications, ORGANIZATIONS, PRODUCTS, PRODUCT_SERIAL_NUMBERS,
+        ORGANIZATIONS, PRODUCTS, PRODUCT_SERIAL_NUMBERS,
         PRODUCT_VERIFICATIONS, RESELLERS, USERS,
         CONFIG_OPENAI_API_KEY, CONFIG_SCRAPER_URL, StorableString,
     },
@@ -31,27 +25,140 @@ use crate::{
 
 use ic_cdk::api::management_canister::http_request::{
     http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod, HttpResponse, TransformArgs,
-    TransformContext, TransformFunc,
 };
 
 use serde_json::{self, Value};
-use rand::prelude::StdRng;
-use k256::elliptic_curve::rand_core::SeedableRng;
 use ic_cdk_timers::set_timer;
 use std::time::Duration;
 use std::convert::TryInto;
 
 use crate::api::{
     ApiResponse, CreateOrganizationRequest, FindOrganizationsRequest, OrganizationResponse,
-    UpdateOrganizationRequest, OrganizationsListResponse, PaginationRequest, paginate,
+    UpdateOrganizationRequest, OrganizationsListResponse, PaginationRequest, PaginationResponse, paginate, paginate_by_key, paginate_from_store, page_limit,
     VerifyProductEnhancedRequest, ProductVerificationEnhancedResponse, RateLimitInfo,
+    BatchVerificationItem, BatchVerificationItemStatus, BatchVerificationItemResult,
+    BatchVerificationSummary, BatchVerificationResponse, VerificationRewards, GuestSessionResponse,
+    ClaimGuestVerificationsResponse, RenderedVerificationMessage, SetPostVerificationMessageRequest,
+    VerificationScenario, SimulateVerificationRequest,
+    IntegrityScanReport, SetBiExportConfigRequest, ListBiExportDeliveriesRequest, BiExportDeliveriesListResponse,
+    SearchProductsRequest, ProductsListResponse, StartVerificationChallengeRequest, VerificationChallengeResponse,
+    CompleteVerificationChallengeRequest, ChallengeVerificationResponse, PenalizeUserRewardsRequest,
+    ListRewardPenaltiesRequest, RewardPenaltiesListResponse, OnboardingStep, OnboardingStepKind,
+    OrgOnboardingStatusResponse, AddWalletRequest, RewardPoolBalanceResponse, DepositRewardPoolRequest,
+    SetRewardPoolSettingsRequest, ProductSuccessor,
+    UpdateResellerProductsRequest, ResellerProductsResponse, SerialStatusCheckResponse, InvariantReport,
+    AuditLogListResponse, CreateReportShareLinkRequest, ReportShareLinkResponse, SharedReportResponse,
+    BrandDashboardResponse, LowStockSerialPool, RejectResellerApplicationRequest, ResellerApplicationDetail,
+    SetLabelTemplateRequest, BrandLookupResponse, UserListFilter, UsersListResponse,
+    CreateProductRequest, DuplicateProductGroup, DelegateVerificationRequest, FederatedVerification,
+    RedeemedCoupon, UploadCouponCodesRequest, UploadCouponCodesResponse, SerialRangeStatsResponse,
+    CreateSerialNumbersBatchRequest, SerialNumbersBatchResponse, OutboxDeliveriesResponse,
+    PublishTermsRequest, TermsStatusResponse, RequestKeyEscrowExportRequest, KeyEscrowExport,
+    SignPayloadResponse, VerificationHeatmapRange, VerificationHeatmapResponse, CreateBundleRequest,
+    BundleContentsResponse, CapabilitiesResponse, OrgCapabilities, PreapprovedResellerRow,
+    PreapproveResellersResponse, SuspiciousActivityResponse, PlatformMetricsResponse, SetMetadataSchemaRequest,
+    SetSerialMetadataRequest, PlanUsageResponse, SetVerificationRetentionRequest, ArchivedAggregatesQuery,
+    VerificationRollupResult, IdentityLinkCodeResponse, IdentityLinkResponse, SubmitJobRequest, JobRequest,
+    JobStatusResponse, JobResultChunkResponse, ListProductsRequest, ListProductSerialNumbersRequest,
+    ProductSerialNumbersListResponse, ListProductVerificationsRequest, ProductVerificationsListResponse,
+    RegisterVerificationDomainRequest, VerificationDomainResponse, MintCapabilityTokenRequest, MintCapabilityTokenResponse,
+    WarrantyStatus, MyProductEntry, SetProductRecallRequest, ProductOwnershipCount, OwnershipStatsResponse,
+    StartRecallCampaignRequest, RecallCampaignStatusResponse,
+    SetStatusMessageRequest, RemoveStatusMessageRequest, StatusDescriptionResponse, EndpointMetricsSummary,
+    SerialPrintHistoryEntry, SerialPrintHistoryResponse, ModerationCasesResponse, ReviewModerationCaseRequest,
     GenerateResellerUniqueCodeRequest, ResellerUniqueCodeResponse, VerifyResellerRequest,
     ResellerVerificationResponse, ResellerVerificationStatus, UserResponse, ProductResponse,
-    ProductVerificationDetail, ResetStorageResponse,
+    ProductVerificationDetail, ResetStorageResponse, ListOrgVerificationsRequest, OrgVerificationsResponse,
+    PublicBrandStatsResponse, LiveEvent, LiveTopic, CounterfeitSignal, WsOpenRequest, WsOpenResponse, WsClientMessage,
+    WsMessageRequest, WsGetMessagesResponse, ProposeAdminOperationRequest, AdminOperationDecisionRequest, AdminOperationsResponse,
+    encode_cursor, decode_cursor,
+    CreateNotificationRuleRequest, NotificationRuleResponse, UpdateOrgSettingsRequest,
+    ConsumerActivityResponse, ConsumerVerificationActivity, UnclaimedReward, RejectRedemptionRequest,
+    ListNotificationsRequest, NotificationsListResponse,
+    OpenDisputeRequest, AddDisputeMessageRequest, UpdateDisputeStatusRequest, ListDisputesRequest, DisputesListResponse,
+    CreateProductVariantRequest, UpdateProductVariantRequest,
+    UpdateDirectoryListingRequest, ModerateDirectoryListingRequest, PublicBrand, PublicBrandsListResponse,
+    CreateReportScheduleRequest, ReportScheduleResponse, ListReportsRequest, ReportsListResponse,
+    BulkPrintRequest, BulkPrintResponse, UpdateSerialStatusRequest, SerialStatusUpdateResponse,
+    UpdateCanisterConfigRequest, SetFeatureFlagRequest, FeatureFlagStatus,
+    SetOrgQuotaRequest, OrgUsageResponse,
 };
+use crate::models::{NotificationRule, OrgSettings, RenewResellerCertificationRequest, Report, ReportSchedule, PrintJob, PrintJobEntry, SerialNumberStatus, CanisterConfig, OrgQuota, PendingRedemption, RedemptionApprovalStatus, UserNotification, UserNotificationKind, Dispute, DisputeMessage, DisputeParty, DisputeStatus, ProductVariant, ProductRewardStats, DirectoryListing, GuestSession, PostVerificationMessage, IntegrityStore, IntegritySubKey, QuarantinedEntry, LogEntry, LogLevel, BiExportConfig, BiExportDelivery, VerificationChallengeStatus, RewardPenalty, SavedWallet, RewardPool, VerificationAnnotation, CodeRevocation, CodeRevocationReason, ReportShareLink, OrgAnalyticsCounters, ResellerApplication, ResellerApplicationStatus, LabelTemplate, VerificationDelegation, VerificationDelegationStatus, CouponCode, OutboxDelivery, OutboxDeliveryStatus, TermsVersion, TermsAcceptance, KeyEscrowRequest, KeyEscrowStatus, VerificationHeatmapKind, Bundle, BundleMember, ResellerPreapproval, ProbeRecord, CyclesSample, MetadataSchema, MetadataFieldSchema, ReadonlyPrincipal, OrgPlan, PlanTier, PlanStatus,
+    VerificationRetentionPolicy, VerificationMonthlyAggregate, Job, JobSpec, VerificationDomain, CapabilityOperation, CapabilityClaims, CapabilityToken, OwnedProduct, ProductRecall, RecallCampaign, RecallRelayStatus, RecallScope, StatusMessage, PrintRecord,
+    ExternalService, CircuitBreaker, ModerationCase, ModerationStatus, PrintCodePolicy, SerialBatchDefaults,
+    AdminOperation, AdminOperationKind, AdminOperationStatus};
+use crate::notifications;
+use crate::org_settings;
+use crate::analytics;
+use crate::config;
+use crate::directory;
+use crate::guest_sessions;
+use crate::verification_messages;
+use crate::integrity;
+use crate::logging;
+use crate::bi_export;
+use crate::catalog;
+use crate::challenges;
+use crate::wallets;
+use crate::reward_pools;
+use crate::reseller_products;
+use crate::id_registry;
+use crate::invariants;
+use crate::audit_log;
+use crate::verification_store;
+use crate::serial_store;
+use crate::reseller_applications;
+use crate::reseller_email;
+use crate::label_templates;
+use crate::brand_lookup;
+use crate::response_privacy;
+use crate::federation;
+use crate::coupons;
+use crate::client_version;
+use crate::outbox;
+use crate::terms;
+use crate::key_escrow;
+use crate::signing;
+use crate::heatmap;
+use crate::bundles;
+use crate::probe_guard;
+use crate::cycles_monitor;
+use crate::metadata_schemas;
+use crate::readonly_principals;
+use crate::plans;
+use crate::verification_retention;
+use crate::identity_links;
+use crate::authenticity;
+use crate::clock;
+use crate::jobs;
+use crate::outcall_transforms::{self, OutcallKind};
+use crate::verification_domains;
+use crate::capability_tokens;
+use crate::owned_products;
+use crate::recall_campaigns;
+use crate::status_messages;
+use crate::endpoint_metrics;
+use crate::print_history;
+use crate::unit_of_work::UnitOfWork;
+use crate::circuit_breakers;
+use crate::moderation;
+use crate::public_stats;
+use crate::live_events;
+use crate::keys::{self, OrgSigningKey};
+use crate::approvals;
+use crate::disputes;
+use crate::quotas;
+use crate::feature_flags;
+use crate::print_jobs;
+use crate::reports;
+use crate::reseller_codes::{self, ConsumedCodeKey};
 use crate::rate_limiter;
+use crate::redemptions;
 use crate::rewards;
 use crate::utils;
+use crate::validation::{self, Validator};
+use crate::variants;
+use crate::webhooks;
 
 #[query]
 pub fn get_organization_by_id(id: Principal) -> OrganizationResult {
@@ -96,20 +203,20 @@ pub fn get_organization_by_id_v2(id: Principal) -> ApiResponse<OrganizationRespo
             // This fixes the chicken-and-egg problem where users need to see the org but don't have it in their list yet
             if matches!(role, UserRole::BrandOwner) {
                 // Log this situation for debugging
-                ic_cdk::print(format!("ℹ️ [get_organization_by_id_v2] BrandOwner accessing org {}", id));
+                log_info!("[get_organization_by_id_v2] BrandOwner accessing org {}", id);
                 
                 // Continue with the function to get the organization
             }
             else if matches!(role, UserRole::Reseller) {
                 // Log this situation for debugging
-                ic_cdk::print(format!("ℹ️ [get_organization_by_id_v2] Reseller accessing org {}", id));
+                log_info!("[get_organization_by_id_v2] Reseller accessing org {}", id);
 
                 // Continue with the function to get the organization
             }
             // If user is Admin, they can see any organization
             else if matches!(role, UserRole::Admin) {
                 // Log this situation for debugging
-                ic_cdk::print(format!("ℹ️ [get_organization_by_id_v2] Admin accessing org {}", id));
+                log_info!("[get_organization_by_id_v2] Admin accessing org {}", id);
                 
                 // Continue with the function to get the organization
             }
@@ -150,14 +257,12 @@ pub fn create_organization(input: OrganizationInput) -> OrganizationPublic {
         let _ = register();
     }
 
-    let id = generate_unique_principal(Principal::anonymous()); // Generate a unique ID for the organization
-    // Generate ECDSA keys for demonstration
-    let mut rng = StdRng::from_entropy();
-    let signing_key = SigningKey::random(&mut rng);
+    // This legacy endpoint has no error variant; trapping rolls the call back
+    let id = id_registry::allocate_organization_id().unwrap_or_else(|e| ic_cdk::trap(&format!("{:?}", e)));
     let organization = Organization {
         id,
         name: input.name,
-        private_key: hex::encode(&signing_key.to_bytes()),
+        private_key: OrgSigningKey::generate().to_hex(),
         description: input.description,
         metadata: input.metadata,
         ..Default::default()
@@ -166,6 +271,8 @@ pub fn create_organization(input: OrganizationInput) -> OrganizationPublic {
     ORGANIZATIONS.with(|orgs| {
         orgs.borrow_mut().insert(id, organization.clone());
     });
+    plans::start_free_plan(id, caller);
+    moderation::on_organization_saved(&organization, caller);
 
     OrganizationPublic::from(organization)
 }
@@ -187,13 +294,14 @@ pub fn update_organization(id: Principal, input: OrganizationInput) -> Organizat
                     name: input.name,
                     description: input.description,
                     metadata: input.metadata,
-                    updated_at: api::time(),
+                    updated_at: clock::now(),
                     updated_by: api::caller(),
                     ..org.clone()
                 };
 
                 // Insert the updated organization
                 orgs_mut.insert(id, updated_org.clone());
+                moderation::on_organization_saved(&updated_org, api::caller());
 
                 OrganizationResult::Organization(OrganizationPublic::from(updated_org))
             }
@@ -205,14 +313,16 @@ pub fn update_organization(id: Principal, input: OrganizationInput) -> Organizat
     })
 }
 
+// Deprecated: the signing key no longer leaves the canister. Label printers use
+// sign_payload_for_org; a key backup goes through the key escrow export.
 #[query]
 pub fn get_organization_private_key(org_id: Principal) -> PrivateKeyResult {
-    // Accessing private key requires higher permission level (write access to the organization)
-    let result = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization);
-    match result {
-        Ok(org) => PrivateKeyResult::Key(org.private_key),
-        Err(err) => PrivateKeyResult::Error(err),
+    if let Err(err) = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization) {
+        return PrivateKeyResult::Error(err);
     }
+    PrivateKeyResult::Error(ApiError::unauthorized(
+        "Private keys are no longer exposed. Use sign_payload_for_org to sign, or export_org_key_escrow for a backup",
+    ))
 }
 
 #[query]
@@ -225,12 +335,26 @@ pub fn find_organizations_by_name(name: String) -> Vec<OrganizationPublic> {
         // Directly filter all organizations by name
         orgs_borrow
             .iter()
-            .filter(|(_, org)| org.name.to_lowercase().contains(&filter))
+            .filter(|(id, org)| org.name.to_lowercase().contains(&filter) && !moderation::is_quarantined(*id))
             .map(|(_, org)| OrganizationPublic::from(org.clone()))
             .collect()
     })
 }
 
+// Normalized SKU for a product in `org_id`, rejecting one already used by another product there
+fn resolve_product_sku(org_id: Principal, sku: Option<&str>, product_id: Option<Principal>) -> Result<Option<String>, ApiError> {
+    let sku = match sku {
+        Some(sku) => catalog::normalize_sku(sku).map_err(ApiError::invalid_input)?,
+        None => None,
+    };
+    if let Some(sku) = &sku {
+        if catalog::sku_taken(org_id, sku, product_id) {
+            return Err(ApiError::already_exists(&format!("SKU '{}' is already used by another product in this organization", sku)));
+        }
+    }
+    Ok(sku)
+}
+
 #[update]
 pub fn create_product(input: ProductInput) -> ProductResult {
     // Use enhanced authorization that checks for write permission
@@ -241,28 +365,40 @@ pub fn create_product(input: ProductInput) -> ProductResult {
     }
 
     let organization = authorization_result.ok().unwrap();
-    let new_product_id = generate_unique_principal(Principal::anonymous()); // Generate a unique ID for the product
-
-    let private_key_bytes_result = hex::decode(&organization.private_key);
-    if private_key_bytes_result.is_err() {
-        return ProductResult::Error(ApiError::invalid_input(&format!(
-            "Invalid private key format for organization {}: {}",
-            organization.id,
-            private_key_bytes_result.err().unwrap()
-        )));
+
+    let sku = match resolve_product_sku(input.org_id, input.sku.as_deref(), None) {
+        Ok(sku) => sku,
+        Err(e) => return ProductResult::Error(e),
+    };
+    if let Err(e) = metadata_schemas::validate_product_metadata(input.org_id, &input.category, &input.metadata) {
+        return ProductResult::Error(e);
+    }
+    if let Err(e) = plans::ensure_product_capacity(input.org_id, 1) {
+        return ProductResult::Error(e);
     }
-    let private_key_bytes = private_key_bytes_result.unwrap();
 
-    let signing_key_result = SigningKey::from_slice(&private_key_bytes);
-    if signing_key_result.is_err() {
-        return ProductResult::Error(ApiError::internal_error(&format!(
-            "Failed to process private key for organization {}: {}",
-            organization.id,
-            signing_key_result.err().unwrap()
-        )));
+    // A new product also creates its first serial number
+    let org_id = input.org_id;
+    if let Err(e) = quotas::ensure_storage_available(org_id).and_then(|_| quotas::consume_serials(org_id, 1)) {
+        return ProductResult::Error(e);
     }
-    let signing_key = signing_key_result.unwrap();
-    let public_key = signing_key.verifying_key();
+    // Every write below is undone if a later step fails, so no orphaned serials or IDs remain
+    let mut work = UnitOfWork::new("create_product");
+    work.on_rollback(move || quotas::release_serials(org_id, 1));
+
+    let new_product_id = match id_registry::allocate_product_id() {
+        Ok(id) => id,
+        Err(e) => return ProductResult::Error(e),
+    };
+    work.on_rollback(move || id_registry::release_id(new_product_id));
+
+    let signing_key = match keys::org_signing_key(&organization) {
+        Ok(key) => key,
+        Err(e) => {
+            log_error!("Failed to process private key for organization {}: {:?}", organization.id, e);
+            return ProductResult::Error(e);
+        }
+    };
     
     let mut product_metadata = input.metadata;
 
@@ -274,43 +410,52 @@ pub fn create_product(input: ProductInput) -> ProductResult {
         category: input.category,
         description: input.description,
         metadata: product_metadata, // Initial metadata from input
-        public_key: hex::encode(public_key.to_encoded_point(false).as_bytes()),
+        public_key: signing_key.verifying_key().to_hex(),
+        sku,
         ..Default::default()
     };
 
     // Create and store an initial ProductSerialNumber for this new product
-    let new_serial_principal = generate_unique_principal(Principal::anonymous());
+    let new_serial_principal = match id_registry::allocate_serial_no(new_product_id) {
+        Ok(id) => id,
+        Err(e) => return ProductResult::Error(e),
+    };
+    work.on_rollback(move || id_registry::release_id(new_serial_principal));
     let initial_product_serial_number = ProductSerialNumber {
         product_id: new_product_id,
         serial_no: new_serial_principal,
         print_version: 0, // Will be incremented to 1 by the "print" logic
         metadata: vec![],
-        created_at: api::time(),
+        last_print_job_id: None,
+        status: Some(SerialNumberStatus::Created),
+        suspected_cloned: None,
+        variant_id: None,
+        revoked_codes: None,
+        sequence_no: None,
+        user_serial_no: None,
+        created_at: clock::now(),
         created_by: api::caller(),
-        updated_at: api::time(),
+        updated_at: clock::now(),
         updated_by: api::caller(),
     };
 
-    PRODUCT_SERIAL_NUMBERS.with(|serial_numbers_refcell| {
-        let mut serial_numbers_map = serial_numbers_refcell.borrow_mut();
-        // Ensure a Vec exists for this product_id, then add the new serial number
-        let mut sn_vec = serial_numbers_map.get(&new_product_id)
-            .map_or_else(Vec::new, |bytes| decode_product_serial_numbers(&bytes));
-        sn_vec.push(initial_product_serial_number);
-        serial_numbers_map.insert(new_product_id, encode_product_serial_numbers(&sn_vec));
+    serial_store::insert(initial_product_serial_number);
+    work.on_rollback(move || {
+        serial_store::remove(new_product_id, new_serial_principal);
+        print_history::remove_for_serial(new_serial_principal);
     });
-    ic_cdk::print(format!("ℹ️ Stored initial serial number {} (version 0) for product {}", new_serial_principal, new_product_id));
+    log_info!("Stored initial serial number {} (version 0) for product {}", new_serial_principal, new_product_id);
 
     // Now, "print" this serial number to generate its first unique code
-    match generate_and_store_unique_code_for_serial(new_product_id, new_serial_principal, &organization.private_key) {
+    match generate_and_store_unique_code_for_serial(new_product_id, new_serial_principal, &signing_key, None) {
         Ok(unique_code_record) => {
-            ic_cdk::print(format!(
-                "ℹ️ Generated initial unique_code {} (print_version {}) for product {} serial {}", 
+            log_info!(
+                "Generated initial unique_code {} (print_version {}) for product {} serial {}", 
                 unique_code_record.unique_code, 
                 unique_code_record.print_version, 
                 new_product_id, 
                 new_serial_principal
-            ));
+            );
             // Add the generated unique code and its version to the product's metadata
             product_to_create.metadata.push(Metadata {
                 key: "initial_unique_code".to_string(),
@@ -326,32 +471,67 @@ pub fn create_product(input: ProductInput) -> ProductResult {
             });
         }
         Err(e) => {
-            ic_cdk::print(format!(
-                "❌ ERROR: Failed to generate initial unique code for product {}: {:?}. Product creation will proceed without it.", 
-                new_product_id, 
+            log_error!(
+                "Failed to generate initial unique code for product {}: {:?}. Product creation is undone.",
+                new_product_id,
                 e
-            ));
-            // Depending on policy, you might want to return ProductResult::Error(e) here.
-            // For now, product creation proceeds, but metadata won't have the code.
-             return ProductResult::Error(ApiError::internal_error(&format!(
+            );
+            return ProductResult::Error(ApiError::internal_error(&format!(
                 "Failed to generate initial unique code for product {}: {:?}", new_product_id, e
             )));
         }
     }
     
     // Update product's own updated_at and updated_by fields since metadata changed
-    product_to_create.updated_at = api::time();
+    product_to_create.updated_at = clock::now();
     product_to_create.updated_by = api::caller();
+    // Numbered last so failed creations don't leave gaps
+    product_to_create.product_number = Some(catalog::next_product_number(product_to_create.org_id));
 
     // Store the final product (with unique code metadata) to PRODUCTS
     PRODUCTS.with(|products_refcell| {
         products_refcell.borrow_mut().insert(new_product_id, product_to_create.clone());
     });
-    ic_cdk::print(format!("ℹ️ Successfully created and stored product {} with initial unique code metadata.", new_product_id));
+    analytics::on_product_saved(None, product_to_create.org_id);
+    work.commit();
+    moderation::on_product_saved(&product_to_create, api::caller());
+    log_info!("Successfully created and stored product {} with initial unique code metadata.", new_product_id);
 
     ProductResult::Product(product_to_create)
 }
 
+// Like create_product, but refuses with PossibleDuplicate when the organization already has a
+// product with the same normalized name and category, unless the request sets `force`
+#[update]
+pub fn create_product_v2(request: CreateProductRequest) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("create_product_v2");
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    if !request.force.unwrap_or(false) {
+        let similar = catalog::find_similar_products(request.org_id, &request.name, &request.category);
+        if !similar.is_empty() {
+            log_info!("[create_product_v2] '{}' in org {} looks like {} existing product(s)", request.name, request.org_id, similar.len());
+            return ApiResponse::error(ApiError::possible_duplicate(similar));
+        }
+    }
+
+    let input = ProductInput {
+        name: request.name,
+        org_id: request.org_id,
+        category: request.category,
+        description: request.description,
+        metadata: request.metadata,
+        sku: request.sku,
+    };
+    match create_product(input) {
+        ProductResult::Product(product) => ApiResponse::success(ProductResponse { product }),
+        ProductResult::Error(e) => ApiResponse::error(e),
+        ProductResult::None => ApiResponse::error(ApiError::internal_error("Product was not created")),
+    }
+}
+
 #[query]
 pub fn list_products(org_id: Principal) -> Vec<Product> {
     // Check for read product permission within the organization
@@ -371,6 +551,79 @@ pub fn list_products(org_id: Principal) -> Vec<Product> {
     })
 }
 
+#[query]
+pub fn search_products_v2(request: SearchProductsRequest) -> ApiResponse<ProductsListResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let query = request.query.unwrap_or_default();
+    let matching: Vec<Product> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == request.org_id && catalog::matches_query(product, &query))
+            .map(|(_, product)| product)
+            .collect()
+    });
+    let (page_items, page_info) = match paginate_by_key(matching, &request.pagination.unwrap_or_default(), |product| {
+        (product.product_number.unwrap_or(u64::MAX), product.id)
+    }) {
+        Ok(page) => page,
+        Err(e) => return ApiResponse::error(e),
+    };
+    ApiResponse::success(ProductsListResponse {
+        products: page_items,
+        pagination: Some(page_info),
+    })
+}
+
+// Products of the organization in ID order, a page at a time
+#[query]
+pub fn list_products_v2(request: ListProductsRequest) -> ApiResponse<ProductsListResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let fetch = |after: Option<Principal>, n: usize| -> Vec<Product> {
+        let start = after.map_or(std::ops::Bound::Unbounded, std::ops::Bound::Excluded);
+        PRODUCTS.with(|products| {
+            products
+                .borrow()
+                .range((start, std::ops::Bound::Unbounded))
+                .filter(|(_, product)| product.org_id == request.org_id)
+                .take(n)
+                .map(|(_, product)| product)
+                .collect()
+        })
+    };
+    match paginate_from_store(&request.pagination.unwrap_or_default(), |product| product.id, fetch) {
+        Ok((page_items, page_info)) => ApiResponse::success(ProductsListResponse {
+            products: page_items,
+            pagination: Some(page_info),
+        }),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Groups of products in the organization that look like accidental duplicates of each other
+#[query]
+pub fn list_suspected_duplicate_products_v2(org_id: Principal) -> ApiResponse<Vec<DuplicateProductGroup>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let groups = catalog::suspected_duplicates(org_id)
+        .into_iter()
+        .map(|((normalized_name, normalized_category), products)| DuplicateProductGroup {
+            normalized_name,
+            normalized_category,
+            products,
+        })
+        .collect();
+    ApiResponse::success(groups)
+}
+
 #[query]
 pub fn list_resellers_by_org_id(org_id: Principal) -> Vec<Reseller> {
     // Check for read permission within the organization. 
@@ -379,7 +632,7 @@ pub fn list_resellers_by_org_id(org_id: Principal) -> Vec<Reseller> {
         authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization); 
     if authorization_result.is_err() {
         // Return empty vector if user does not have permission or org doesn't exist
-        ic_cdk::print(format!("Authorization failed for listing resellers in org {}: {:?}", org_id, authorization_result.err()));
+        log_warn!("Authorization failed for listing resellers in org {}: {:?}", org_id, authorization_result.err());
         return vec![]; 
     }
 
@@ -443,7 +696,26 @@ pub fn update_product(id: Principal, input: ProductInput) -> ProductResult {
                 "Cannot move product to an organization you don't have write access to",
             ));
         }
+        if let Err(e) = plans::ensure_product_capacity(input.org_id, 1) {
+            return ProductResult::Error(e);
+        }
+    }
+
+    // None keeps the current SKU, which still has to be unique if the product changes organization
+    let requested_sku = input.sku.as_deref().or(product.sku.as_deref());
+    let sku = match resolve_product_sku(input.org_id, requested_sku, Some(id)) {
+        Ok(sku) => sku,
+        Err(e) => return ProductResult::Error(e),
+    };
+    if let Err(e) = metadata_schemas::validate_product_metadata(input.org_id, &input.category, &input.metadata) {
+        return ProductResult::Error(e);
     }
+    // Product numbers are per organization, so a moved product is numbered in its new one
+    let product_number = if product.org_id != input.org_id {
+        Some(catalog::next_product_number(input.org_id))
+    } else {
+        product.product_number
+    };
 
     PRODUCTS.with(|products| {
         let mut products_mut = products.borrow_mut();
@@ -455,13 +727,16 @@ pub fn update_product(id: Principal, input: ProductInput) -> ProductResult {
             description: input.description,
             category: input.category,
             metadata: input.metadata,
-            updated_at: api::time(),
+            sku,
+            product_number,
+            updated_at: clock::now(),
             updated_by: api::caller(),
             ..product.clone()
         };
 
         // Insert the updated product
         products_mut.insert(id, updated_product.clone());
+        analytics::on_product_saved(Some(product.org_id), updated_product.org_id);
 
         ProductResult::Product(updated_product)
     })
@@ -472,16 +747,16 @@ pub fn register() -> User {
     USERS.with(|users| {
         let mut users_mut = users.borrow_mut();
         let caller = api::caller();
-        ic_cdk::print(format!("ℹ️ [Register] Called by: {}", caller));
+        log_info!("[Register] Called by: {}", caller);
 
         // If user already exists, return their current state
         if let Some(existing_user) = users_mut.get(&caller) {
-            ic_cdk::print(format!("ℹ️ [Register] Found existing user: {}", caller));
+            log_info!("[Register] Found existing user: {}", caller);
             return existing_user.clone();
         }
 
         // If user does not exist, create a new one with default values
-        ic_cdk::print(format!("ℹ️ [Register] Creating NEW user: {}", caller));
+        log_info!("[Register] Creating NEW user: {}", caller);
         let user = User {
             id: caller,
             // is_principal logic is likely unnecessary and removed for simplicity
@@ -493,7 +768,7 @@ pub fn register() -> User {
         
         // --- Diagnostic Read --- 
         let inserted_user = users_mut.get(&caller);
-        ic_cdk::print(format!("ℹ️ [Register] Diagnostic read after insert for {}: {:?}", caller, inserted_user.is_some()));
+        log_info!("[Register] Diagnostic read after insert for {}: {:?}", caller, inserted_user.is_some());
         // --- End Diagnostic --- 
         
         user
@@ -518,22 +793,47 @@ pub fn whoami() -> Option<User> {
         let users_ref = users.borrow();
         let caller = api::caller();
         // Log the caller principal received by whoami
-        ic_cdk::print(format!("ℹ️ [whoami] Called by: {}", caller));
+        log_info!("[whoami] Called by: {}", caller);
         match users_ref.get(&caller) {
             Some(user) => {
-                 ic_cdk::print(format!("ℹ️ [whoami] Found user: {}", caller));
+                 log_info!("[whoami] Found user: {}", caller);
                  Some(user.clone())
             },
             None => {
-                 ic_cdk::print(format!("ℹ️ [whoami] User not found: {}", caller));
+                 log_info!("[whoami] User not found: {}", caller);
                  None
             }
         }
     })
 }
 
+// Validated and normalized user profile details
+struct UserDetailsFields {
+    first_name: String,
+    last_name: String,
+    email: Option<String>,
+    phone_no: Option<String>,
+}
+
+fn validate_user_details(input: &UserDetailsInput) -> Result<UserDetailsFields, ApiError> {
+    let mut validator = Validator::new();
+    let first_name = validator.required_text("first_name", &input.first_name, validation::MAX_NAME_LENGTH);
+    let last_name = validator.required_text("last_name", &input.last_name, validation::MAX_NAME_LENGTH);
+    let email = validator.email("email", &input.email);
+    let phone_no = validator.phone("phone_no", &input.phone_no);
+    validator.metadata("detail_meta", &input.detail_meta);
+    validator.finish()?;
+
+    Ok(UserDetailsFields { first_name, last_name, email, phone_no })
+}
+
 #[update]
 pub fn update_self_details(input: UserDetailsInput) -> UserResult {
+    let details = match validate_user_details(&input) {
+        Ok(d) => d,
+        Err(e) => return UserResult::Error(e),
+    };
+
     USERS.with(|users| {
         let mut users_mut = users.borrow_mut();
         let caller = api::caller();
@@ -541,12 +841,12 @@ pub fn update_self_details(input: UserDetailsInput) -> UserResult {
         if let Some(user) = users_mut.get(&caller) {
             // Create an updated user
             let updated_user = User {
-                first_name: Some(input.first_name),
-                last_name: Some(input.last_name),
-                phone_no: Some(input.phone_no),
-                email: Some(input.email),
+                first_name: Some(details.first_name),
+                last_name: Some(details.last_name),
+                phone_no: details.phone_no,
+                email: details.email,
                 detail_meta: input.detail_meta,
-                updated_at: api::time(),
+                updated_at: clock::now(),
                 updated_by: caller,
                 ..user.clone()
             };
@@ -608,20 +908,20 @@ pub fn set_self_role(role: UserRole) -> UserResult {
                 let org_id_str = has_requested_org.unwrap();
                 match Principal::from_text(&org_id_str) {
                     Ok(org_id) => {
-                        ic_cdk::print(format!("ℹ️ [set_self_role] Adding organization {} to user {}", org_id, caller));
+                        log_info!("[set_self_role] Adding organization {} to user {}", org_id, caller);
                         
                         // Check if org exists
                         let org_exists = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id).is_some());
                         
                         if org_exists && !org_ids.contains(&org_id) {
                             org_ids.push(org_id);
-                            ic_cdk::print(format!("ℹ️ [set_self_role] Successfully added org {} to BrandOwner {}", org_id, caller));
+                            log_info!("[set_self_role] Successfully added org {} to BrandOwner {}", org_id, caller);
                         } else if !org_exists {
-                            ic_cdk::print(format!("⚠️ [set_self_role] Organization {} not found for user {}", org_id, caller));
+                            log_warn!("[set_self_role] Organization {} not found for user {}", org_id, caller);
                         }
                     },
                     Err(e) => {
-                        ic_cdk::print(format!("❌ ERROR: Invalid organization ID format: {}, error: {}", org_id_str, e));
+                        log_error!("Invalid organization ID format: {}, error: {}", org_id_str, e);
                     }
                 }
             }
@@ -629,7 +929,7 @@ pub fn set_self_role(role: UserRole) -> UserResult {
             let updated_user = User {
                 user_role: Some(role),
                 org_ids,  // Use potentially updated org_ids
-                updated_at: api::time(),
+                updated_at: clock::now(),
                 updated_by: caller,
                 ..user.clone()
             };
@@ -662,7 +962,7 @@ pub fn register_as_organization(input: OrganizationInput) -> UserResult {
             let updated_user = User {
                 org_ids,
                 user_role: Some(UserRole::BrandOwner),
-                updated_at: api::time(),
+                updated_at: clock::now(),
                 updated_by: caller,
                 ..user.clone()
             };
@@ -679,6 +979,7 @@ pub fn register_as_organization(input: OrganizationInput) -> UserResult {
 
 #[update]
 pub fn register_as_reseller_v2(input: ResellerInput) -> ApiResponse<UserResponse> {
+    let _metrics = endpoint_metrics::track("register_as_reseller_v2");
     let caller = api::caller();
 
     // --- 1. Input Validation ---
@@ -718,34 +1019,22 @@ pub fn register_as_reseller_v2(input: ResellerInput) -> ApiResponse<UserResponse
     let organization = org_opt.unwrap(); // Safe to unwrap
 
     // --- 4. Key Processing ---
-    let private_key_bytes = match hex::decode(&organization.private_key) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to decode private key for org {}: {}", organization.id, e));
-            return ApiResponse::error(ApiError::internal_error(
-                "Failed to process organization secret key",
-            ));
-        }
-    };
-
-    let private_key = match SecretKey::from_slice(&private_key_bytes) { // Note: Using SecretKey, assuming this is correct for Reseller key generation
-        Ok(key) => key,
+    // Resellers carry the organization's public key; their codes are signed with the organization's key
+    let public_key_hex = match keys::org_verifying_key(&organization) {
+        Ok(key) => key.to_hex(),
         Err(e) => {
-             ic_cdk::print(format!("❌ ERROR: Failed to create secret key from slice for org {}: {}", organization.id, e));
-            return ApiResponse::error(ApiError::internal_error(
-                "Malformed secret key for organization",
-            ));
+            log_error!("Failed to process private key for org {}: {:?}", organization.id, e);
+            return ApiResponse::error(e);
         }
     };
-    
-    // Derive public key - assuming reseller needs its own keypair based on org's key?
-    // Or should the reseller use the org's public key directly?
-    // Let's stick to the previous logic: generate public key from org private key for now.
-    let public_key = private_key.public_key();
-    let public_key_hex = hex::encode(public_key.to_encoded_point(false).as_bytes());
 
     // --- 5. Reseller Creation ---
-    let reseller_id = generate_unique_principal(Principal::anonymous());
+    let mut work = UnitOfWork::new("register_as_reseller_v2");
+    let reseller_id = match id_registry::allocate_reseller_id() {
+        Ok(id) => id,
+        Err(e) => return ApiResponse::error(e),
+    };
+    work.on_rollback(move || id_registry::release_id(reseller_id));
 
     let reseller = Reseller {
         id: reseller_id,
@@ -754,22 +1043,28 @@ pub fn register_as_reseller_v2(input: ResellerInput) -> ApiResponse<UserResponse
         ecommerce_urls: input.ecommerce_urls,
         metadata: input.metadata,
         public_key: public_key_hex, // Storing derived public key
-        created_at: api::time(),
+        created_at: clock::now(),
         created_by: caller,
-        updated_at: api::time(),
+        updated_at: clock::now(),
         updated_by: caller,
         ..Default::default() // Ensure other fields like date_joined are handled
     };
 
+    analytics::on_reseller_saved(None, &reseller);
     RESELLERS.with(|resellers| {
-        resellers.borrow_mut().insert(reseller_id, reseller);
+        resellers.borrow_mut().insert(reseller_id, reseller.clone());
+    });
+    work.on_rollback(move || {
+        if let Some(reseller) = RESELLERS.with(|resellers| resellers.borrow_mut().remove(&reseller_id)) {
+            analytics::on_reseller_removed(&reseller);
+        }
     });
 
     // --- 6. Update User Role ---
     let updated_user = User {
         user_role: Some(UserRole::Reseller),
         org_ids: vec![input.org_id], // Associate user with this org
-        updated_at: api::time(),
+        updated_at: clock::now(),
         updated_by: caller,
         ..user.clone()
     };
@@ -777,7 +1072,9 @@ pub fn register_as_reseller_v2(input: ResellerInput) -> ApiResponse<UserResponse
     USERS.with(|users| {
         users.borrow_mut().insert(caller, updated_user.clone());
     });
-    
+    work.commit();
+    moderation::on_reseller_saved(&reseller, caller);
+
     // --- 7. Success --- 
     ApiResponse::success(UserResponse { user: updated_user })
 }
@@ -804,14 +1101,19 @@ pub fn create_user(id: Principal, input: UserDetailsInput) -> UserResult {
         return UserResult::Error(ApiError::already_exists("User already exists"));
     }
 
+    let details = match validate_user_details(&input) {
+        Ok(d) => d,
+        Err(e) => return UserResult::Error(e),
+    };
+
     let user = User {
         id,
         is_enabled: true,
         is_principal: false,
-        first_name: Some(input.first_name),
-        last_name: Some(input.last_name),
-        email: Some(input.email),
-        phone_no: Some(input.phone_no),
+        first_name: Some(details.first_name),
+        last_name: Some(details.last_name),
+        email: details.email,
+        phone_no: details.phone_no,
         detail_meta: input.detail_meta,
         ..Default::default()
     };
@@ -837,18 +1139,23 @@ pub fn update_user(id: Principal, input: UserDetailsInput) -> UserResult {
         }
     }
 
+    let details = match validate_user_details(&input) {
+        Ok(d) => d,
+        Err(e) => return UserResult::Error(e),
+    };
+
     USERS.with(|users| {
         let mut users_mut = users.borrow_mut();
 
         if let Some(user) = users_mut.get(&id) {
             // Create an updated user
             let updated_user = User {
-                first_name: Some(input.first_name),
-                last_name: Some(input.last_name),
-                phone_no: Some(input.phone_no),
-                email: Some(input.email),
+                first_name: Some(details.first_name),
+                last_name: Some(details.last_name),
+                phone_no: details.phone_no,
+                email: details.email,
                 detail_meta: input.detail_meta,
-                updated_at: api::time(),
+                updated_at: clock::now(),
                 updated_by: caller,
                 ..user.clone()
             };
@@ -902,8 +1209,8 @@ pub fn update_user_orgs(id: Principal, org_ids: Vec<Principal>) -> UserResult {
         if let Some(user) = users_mut.get(&id) {
             // Create an updated user with new organization IDs
             let updated_user = User {
-                org_ids: org_ids,
-                updated_at: api::time(),
+                org_ids,
+                updated_at: clock::now(),
                 updated_by: caller,
                 ..user.clone()
             };
@@ -919,10 +1226,8 @@ pub fn update_user_orgs(id: Principal, org_ids: Vec<Principal>) -> UserResult {
 }
 
 const REVIEW_REFRESH_INTERVAL: u64 = 86400; // 24 hours in seconds
-const OPENAI_HOST: &str = "api.openai.com";
-const GPT_MODEL: &str = "gpt-4o";
-const REQUEST_CYCLES: u64 = 230_949_972_000;
 const UNIQUE_CODE_EXPIRATION_SECONDS: u64 = 300; // 5 minutes
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
 const MAX_HTTP_RETRIES: u32 = 3;
 const RETRY_DELAY_SECONDS: u64 = 2;
 
@@ -934,30 +1239,42 @@ async fn generate_product_review_v2(product_id: Principal) -> ApiResponse<Produc
     };
 
     if !should_generate_new_review(&product) {
-        ic_cdk::print(format!("ℹ️ Product review for {} is up-to-date. Skipping generation.", product_id));
+        log_info!("Product review for {} is up-to-date. Skipping generation.", product_id);
         // Return current product data if review is fresh
         return ApiResponse::success(ProductResponse { product }); 
     }
     
-    ic_cdk::print(format!("ℹ️ Generating new product review for {}.", product_id));
+    log_info!("Generating new product review for {}.", product_id);
+
+    // Fail fast while either integration is down, before spending quota on it
+    for service in [ExternalService::Scraper, ExternalService::OpenAi] {
+        if let Err(e) = circuit_breakers::check(service) {
+            return ApiResponse::error(e);
+        }
+    }
+
+    // One scraper call plus one LLM call
+    if let Err(e) = quotas::consume_outcalls(product.org_id, 2) {
+        return ApiResponse::error(e);
+    }
 
     // Scrape Review Summary - Handle the Result
     let review_summary_result = scrape_product_review(&product).await;
     let review_summary = match review_summary_result {
         Ok(summary) => summary,
         Err(e) => {
-            ic_cdk::print(format!("⚠️ Failed to scrape review for {}: {:?}", product_id, e));
+            log_warn!("Failed to scrape review for {}: {:?}", product_id, e);
             // Return the scraping error
             return ApiResponse::error(e);
         }
     };
 
     // Analyze Sentiment (already returns Result, handled below)
-    let sentiment_analysis_result = analyze_sentiment_with_openai(&review_summary).await;
+    let sentiment_analysis_result = analyze_sentiment_with_openai(product.org_id, &review_summary).await;
     let sentiment_analysis = match sentiment_analysis_result {
         Ok(sentiment) => sentiment,
         Err(e) => {
-            ic_cdk::print(format!("⚠️ Failed to analyze sentiment for {}: {:?}", product_id, e));
+            log_warn!("Failed to analyze sentiment for {}: {:?}", product_id, e);
             return ApiResponse::error(e); 
         }
     };
@@ -965,11 +1282,11 @@ async fn generate_product_review_v2(product_id: Principal) -> ApiResponse<Produc
     // Update Product with Review
     match update_product_with_review(product, sentiment_analysis) {
         Ok(updated_product) => {
-            ic_cdk::print(format!("✅ Successfully generated review for product {}.", product_id));
+            log_info!("Successfully generated review for product {}.", product_id);
             ApiResponse::success(ProductResponse { product: updated_product })
         }
         Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to update product {} with review: {:?}", product_id, e));
+            log_error!("Failed to update product {} with review: {:?}", product_id, e);
             ApiResponse::error(e)
         }
     }
@@ -993,11 +1310,11 @@ fn should_generate_new_review(product: &Product) -> bool {
         .and_then(|v| v.value.parse::<u64>().ok());
 
     latest_review_time
-        .map(|time| time < api::time() - REVIEW_REFRESH_INTERVAL)
+        .map(|time| time < clock::now() - REVIEW_REFRESH_INTERVAL)
         .unwrap_or(true)
 }
 
-async fn analyze_sentiment_with_openai(review_text: &str) -> Result<String, ApiError> {
+async fn analyze_sentiment_with_openai(org_id: Principal, review_text: &str) -> Result<String, ApiError> {
     let request = match create_openai_request(review_text) {
         Ok(req) => req,
         Err(e) => return Err(e),
@@ -1006,10 +1323,11 @@ async fn analyze_sentiment_with_openai(review_text: &str) -> Result<String, ApiE
     let mut attempts = 0;
     loop {
         attempts += 1;
-        ic_cdk::print(format!("ℹ️ Attempt {} analyzing sentiment with OpenAI.", attempts));
+        log_info!("Attempt {} analyzing sentiment with OpenAI.", attempts);
+        // Stops the retries too once the failures so far opened the breaker
+        circuit_breakers::begin_call(ExternalService::OpenAi)?;
 
-        // Cast REQUEST_CYCLES to u128
-        match http_request(request.clone(), REQUEST_CYCLES as u128).await {
+        match http_request(request.clone(), outcall_transforms::request_cycles(&request)).await {
             Ok((response,)) => {
                 // Clone status for potential logging before moving its inner value
                 let original_status = response.status.clone();
@@ -1018,19 +1336,27 @@ async fn analyze_sentiment_with_openai(review_text: &str) -> Result<String, ApiE
                     Ok(code) => code,
                     Err(_) => {
                         // Use the cloned status for logging
-                        ic_cdk::print(format!("❌ ERROR: Invalid status code received from OpenAI: {}", original_status));
+                        log_error!("Invalid status code received from OpenAI: {}", original_status);
+                        circuit_breakers::record_failure(ExternalService::OpenAi, "Invalid status code received");
                         return Err(ApiError::external_api_error("Invalid status code received"));
                     }
                 };
 
+                // Client errors mean the service is up; only server errors count against the breaker
+                if status_code >= 500 {
+                    circuit_breakers::record_failure(ExternalService::OpenAi, &format!("status {}", status_code));
+                } else {
+                    circuit_breakers::record_success(ExternalService::OpenAi);
+                }
+
                 if status_code >= 200 && status_code < 300 {
                     let response_body = String::from_utf8(response.body).map_err(|e| {
-                        ic_cdk::print(format!("❌ ERROR: Invalid UTF-8 in OpenAI response: {:?}", e));
+                        log_error!("Invalid UTF-8 in OpenAI response: {:?}", e);
                         ApiError::external_api_error("Invalid UTF-8 in OpenAI response")
                     })?;
 
                     let parsed: Value = serde_json::from_str(&response_body).map_err(|e| {
-                        ic_cdk::print(format!("❌ ERROR: Invalid JSON in OpenAI response: {:?}, Body: {}", e, response_body));
+                        log_error!("Invalid JSON in OpenAI response: {:?}, Body: {}", e, response_body);
                         ApiError::external_api_error("Invalid JSON response from OpenAI")
                     })?;
 
@@ -1045,11 +1371,11 @@ async fn analyze_sentiment_with_openai(review_text: &str) -> Result<String, ApiE
                         status_code, // Use converted status code
                         String::from_utf8_lossy(&response.body)
                     );
-                    ic_cdk::print(format!("❌ ERROR: {}", error_message));
+                    log_error!("{}", error_message);
 
                     // Treat server-side errors (5xx) as potentially retryable
                     if status_code >= 500 && attempts < MAX_HTTP_RETRIES {
-                        ic_cdk::print(format!("⏱️ Retrying analyze_sentiment after delay..."));
+                        log_info!("Retrying analyze_sentiment after delay...");
                         utils::async_delay(Duration::from_secs(RETRY_DELAY_SECONDS * attempts as u64)).await;
                         continue; // Retry the loop
                     }
@@ -1062,11 +1388,18 @@ async fn analyze_sentiment_with_openai(review_text: &str) -> Result<String, ApiE
                     "HTTP request to OpenAI failed. RejectionCode: {:?}, Error: {}",
                     rejection_code, message
                 );
-                ic_cdk::print(format!("❌ ERROR: {}", error_message));
+                log_error!("{}", error_message);
 
+                // A retry would get the same oversized response
+                if outcall_transforms::is_oversized_rejection(&message) {
+                    quotas::record_oversized_response(org_id);
+                    circuit_breakers::record_success(ExternalService::OpenAi);
+                    return Err(ApiError::external_api_error(&error_message));
+                }
+                circuit_breakers::record_failure(ExternalService::OpenAi, &error_message);
                  // Retry on most errors up to the limit
                 if attempts < MAX_HTTP_RETRIES {
-                    ic_cdk::print(format!("⏱️ Retrying analyze_sentiment after rejection delay..."));
+                    log_info!("Retrying analyze_sentiment after rejection delay...");
                     utils::async_delay(Duration::from_secs(RETRY_DELAY_SECONDS * attempts as u64)).await;
                     continue; // Retry the loop
                 }
@@ -1078,10 +1411,12 @@ async fn analyze_sentiment_with_openai(review_text: &str) -> Result<String, ApiE
 }
 
 fn create_openai_request(review_text: &str) -> Result<CanisterHttpRequestArgument, ApiError> {
+    let llm = config::llm_config();
+    let model = llm.model;
     let escaped_review = review_text.replace("\"", "\\\"");
     let request_body = format!(
         r#"{{
-        "model": "{GPT_MODEL}",
+        "model": "{model}",
         "messages": [{{
             "role": "user",
             "content": "With this product review summary: {}\n Please help summarize what is the overall sentiment of the product"
@@ -1092,33 +1427,23 @@ fn create_openai_request(review_text: &str) -> Result<CanisterHttpRequestArgumen
     );
 
     Ok(CanisterHttpRequestArgument {
-        url: format!("https://{OPENAI_HOST}/v1/chat/completions"),
+        url: format!("https://{}/v1/chat/completions", llm.api_host),
         method: HttpMethod::POST,
         body: Some(request_body.into_bytes()),
-        max_response_bytes: None,
-        transform: Some(TransformContext {
-            function: TransformFunc(candid::Func {
-                principal: api::id(),
-                method: "transform".to_string(),
-            }),
-            context: vec![],
-        }),
-        headers: create_request_headers(),
+        max_response_bytes: Some(outcall_transforms::max_response_bytes(OutcallKind::OpenAi)),
+        transform: Some(outcall_transforms::transform_context(OutcallKind::OpenAi)),
+        headers: create_request_headers(&llm.api_host, &llm.api_key),
     })
 }
 
-fn create_request_headers() -> Vec<HttpHeader> {
-    // Read StorableString from stable storage
-    let api_key_storable = CONFIG_OPENAI_API_KEY.with(|cell| cell.borrow().get().clone());
-    let api_key = &api_key_storable.0; // Get reference to inner String
-    
+fn create_request_headers(api_host: &str, api_key: &str) -> Vec<HttpHeader> {
     if api_key.is_empty() {
-        ic_cdk::print("⚠️ WARNING: OpenAI API Key is not configured.");
+        log_warn!("OpenAI API Key is not configured.");
         // Return headers without Authorization if key is missing
         return vec![
             HttpHeader {
                 name: "Host".to_string(),
-                value: format!("{OPENAI_HOST}:443"),
+                value: format!("{}:443", api_host),
             },
             HttpHeader {
                 name: "User-Agent".to_string(),
@@ -1138,7 +1463,7 @@ fn create_request_headers() -> Vec<HttpHeader> {
     vec![
         HttpHeader {
             name: "Host".to_string(),
-            value: format!("{OPENAI_HOST}:443"),
+            value: format!("{}:443", api_host),
         },
         HttpHeader {
             name: "User-Agent".to_string(),
@@ -1169,7 +1494,7 @@ fn update_product_with_review(
     };
     let timestamp_metadata = Metadata {
         key: "latest_product_review_generation".to_string(),
-        value: api::time().to_string(),
+        value: clock::now().to_string(),
     };
 
     product.metadata.push(review_metadata);
@@ -1183,12 +1508,10 @@ fn update_product_with_review(
 }
 
 async fn scrape_product_review(product: &Product) -> Result<String, ApiError> {
-    // Read StorableString from stable storage
-    let base_scraper_url_storable = CONFIG_SCRAPER_URL.with(|cell| cell.borrow().get().clone());
-    let base_scraper_url = &base_scraper_url_storable.0; // Get reference to inner String
+    let base_scraper_url = config::scraper_url();
 
     if base_scraper_url.is_empty() {
-        ic_cdk::print("⚠️ WARNING: Scraper URL is not configured.");
+        log_warn!("Scraper URL is not configured.");
         return Err(ApiError::internal_error("Scraper service URL not configured"));
     }
 
@@ -1196,31 +1519,26 @@ async fn scrape_product_review(product: &Product) -> Result<String, ApiError> {
     let url = format!(
         "{}/product-review?id={}",
         base_scraper_url,
-        product.id.to_string()
+        product.id
     );
 
     let request = CanisterHttpRequestArgument {
         url: url.clone(), // Clone url for potential retries
         method: HttpMethod::GET,
         body: None,
-        max_response_bytes: None, // Consider setting a limit
-        transform: Some(TransformContext {
-            function: TransformFunc(candid::Func {
-                principal: api::id(),
-                method: "transform".to_string(),
-            }),
-            context: vec![],
-        }),
+        max_response_bytes: Some(outcall_transforms::max_response_bytes(OutcallKind::Scraper)),
+        transform: Some(outcall_transforms::transform_context(OutcallKind::Scraper)),
         headers: vec![],
     };
 
     let mut attempts = 0;
     loop {
         attempts += 1;
-        ic_cdk::print(format!("ℹ️ Attempt {} scraping review from: {}", attempts, request.url));
+        log_info!("Attempt {} scraping review from: {}", attempts, request.url);
+        // Stops the retries too once the failures so far opened the breaker
+        circuit_breakers::begin_call(ExternalService::Scraper)?;
 
-        // Cast REQUEST_CYCLES to u128
-        match http_request(request.clone(), REQUEST_CYCLES as u128).await {
+        match http_request(request.clone(), outcall_transforms::request_cycles(&request)).await {
             Ok((response,)) => {
                 // Clone status for potential logging before moving its inner value
                 let original_status = response.status.clone();
@@ -1229,14 +1547,22 @@ async fn scrape_product_review(product: &Product) -> Result<String, ApiError> {
                     Ok(code) => code,
                     Err(_) => {
                         // Use the cloned status for logging
-                        ic_cdk::print(format!("❌ ERROR: Invalid status code received from scraper: {}", original_status));
+                        log_error!("Invalid status code received from scraper: {}", original_status);
+                        circuit_breakers::record_failure(ExternalService::Scraper, "Invalid status code received");
                         return Err(ApiError::external_api_error("Invalid status code received"));
                     }
                 };
 
+                // Client errors mean the service is up; only server errors count against the breaker
+                if status_code >= 500 {
+                    circuit_breakers::record_failure(ExternalService::Scraper, &format!("status {}", status_code));
+                } else {
+                    circuit_breakers::record_success(ExternalService::Scraper);
+                }
+
                 if status_code >= 200 && status_code < 300 {
                     return String::from_utf8(response.body).map_err(|e| {
-                        ic_cdk::print(format!("❌ ERROR: Failed to decode scraper response body: {:?}", e));
+                        log_error!("Failed to decode scraper response body: {:?}", e);
                         ApiError::external_api_error("Failed to decode scraper response")
                     });
                 } else {
@@ -1245,11 +1571,11 @@ async fn scrape_product_review(product: &Product) -> Result<String, ApiError> {
                         status_code, // Use converted status code
                         String::from_utf8_lossy(&response.body)
                     );
-                    ic_cdk::print(format!("❌ ERROR: {}", error_message));
+                    log_error!("{}", error_message);
 
                     // Treat server-side errors (5xx) as potentially retryable
                     if status_code >= 500 && attempts < MAX_HTTP_RETRIES {
-                        ic_cdk::print(format!("⏱️ Retrying scrape_product_review after delay..."));
+                        log_info!("Retrying scrape_product_review after delay...");
                         utils::async_delay(Duration::from_secs(RETRY_DELAY_SECONDS * attempts as u64)).await;
                         continue; // Retry the loop
                     }
@@ -1262,12 +1588,19 @@ async fn scrape_product_review(product: &Product) -> Result<String, ApiError> {
                     "HTTP request to scraper failed. RejectionCode: {:?}, Error: {}",
                     rejection_code, message
                 );
-                ic_cdk::print(format!("❌ ERROR: {}", error_message));
+                log_error!("{}", error_message);
 
+                // A retry would get the same oversized response
+                if outcall_transforms::is_oversized_rejection(&message) {
+                    quotas::record_oversized_response(product.org_id);
+                    circuit_breakers::record_success(ExternalService::Scraper);
+                    return Err(ApiError::external_api_error(&error_message));
+                }
+                circuit_breakers::record_failure(ExternalService::Scraper, &error_message);
                 // Retry on specific rejection codes if desired (e.g., network errors)
                 // For now, let's retry on most errors up to the limit
                 if attempts < MAX_HTTP_RETRIES {
-                    ic_cdk::print(format!("⏱️ Retrying scrape_product_review after rejection delay..."));
+                    log_info!("Retrying scrape_product_review after rejection delay...");
                     utils::async_delay(Duration::from_secs(RETRY_DELAY_SECONDS * attempts as u64)).await;
                     continue; // Retry the loop
                 }
@@ -1283,47 +1616,25 @@ pub fn greet(name: String) -> String {
     format!("Hello, {}!", name)
 }
 
+// Outcall transforms, one per integration; see outcall_transforms
 #[query]
-fn transform(raw: TransformArgs) -> HttpResponse {
-    let headers = vec![
-        HttpHeader {
-            name: "Content-Security-Policy".to_string(),
-            value: "default-src 'self'".to_string(),
-        },
-        HttpHeader {
-            name: "Referrer-Policy".to_string(),
-            value: "strict-origin".to_string(),
-        },
-        HttpHeader {
-            name: "Permissions-Policy".to_string(),
-            value: "geolocation=(self)".to_string(),
-        },
-        HttpHeader {
-            name: "Strict-Transport-Security".to_string(),
-            value: "max-age=63072000".to_string(),
-        },
-        HttpHeader {
-            name: "X-Frame-Options".to_string(),
-            value: "DENY".to_string(),
-        },
-        HttpHeader {
-            name: "X-Content-Type-Options".to_string(),
-            value: "nosniff".to_string(),
-        },
-    ];
+fn transform_openai(raw: TransformArgs) -> HttpResponse {
+    outcall_transforms::normalize(OutcallKind::OpenAi, raw)
+}
 
-    let mut res = HttpResponse {
-        status: raw.response.status.clone(),
-        body: raw.response.body.clone(),
-        headers,
-    };
+#[query]
+fn transform_scraper(raw: TransformArgs) -> HttpResponse {
+    outcall_transforms::normalize(OutcallKind::Scraper, raw)
+}
 
-    if res.status == 200u64 {
-        res.body = raw.response.body;
-    } else {
-        api::print(format!("Received an error: err = {:?}", raw));
-    }
-    res
+#[query]
+fn transform_webhook(raw: TransformArgs) -> HttpResponse {
+    outcall_transforms::normalize(OutcallKind::Webhook, raw)
+}
+
+#[query]
+fn transform_dns(raw: TransformArgs) -> HttpResponse {
+    outcall_transforms::normalize(OutcallKind::DnsLookup, raw)
 }
 
 #[query]
@@ -1334,33 +1645,49 @@ pub fn find_resellers_by_name_or_id(name: String) -> Vec<Reseller> {
         resellers
             .borrow()
             .iter()
-            .filter(|(_, reseller)| reseller.name.to_lowercase().contains(&filter))
+            .filter(|(id, reseller)| reseller.name.to_lowercase().contains(&filter) && !moderation::is_quarantined(*id))
             .map(|(_, reseller)| reseller.clone())
             .collect()
     })
 }
 
-#[query]
+// Update call so that single-use codes can be marked as consumed
+#[update]
 pub fn verify_reseller_v2(request: VerifyResellerRequest) -> ApiResponse<ResellerVerificationResponse> {
-    let current_time = api::time();
+    let _metrics = endpoint_metrics::track("verify_reseller_v2");
+    // An unknown reseller is still checked against the platform-wide minimum
+    let org_id = RESELLERS.with(|resellers| resellers.borrow().get(&request.reseller_id).map(|r| r.org_id));
+    if let Err(e) = client_version::check(org_id, request.client_version.as_deref()) {
+        return ApiResponse::error(e);
+    }
+    let mut response = verify_reseller_code(request);
+    response.data = response.data.map(response_privacy::shape_reseller_verification_response);
+    response
+}
+
+fn verify_reseller_code(request: VerifyResellerRequest) -> ApiResponse<ResellerVerificationResponse> {
+    let current_time = clock::now();
     let reseller_id = request.reseller_id;
     let code_timestamp = request.timestamp;
     let context_str = request.context.as_deref().unwrap_or("");
 
-    // 1. Check for expiration / replay attack
-    if current_time > code_timestamp + UNIQUE_CODE_EXPIRATION_SECONDS {
+    // 1. Check for expiration (timestamps are in nanoseconds)
+    let code_expires_at = code_timestamp.saturating_add(UNIQUE_CODE_EXPIRATION_SECONDS * NANOS_PER_SECOND);
+    if current_time > code_expires_at {
         return ApiResponse::success(ResellerVerificationResponse {
             status: ResellerVerificationStatus::ExpiredCode,
             organization: None,
             reseller: None,
+            product_authorized: None,
         });
     }
     // Basic check for future timestamps (allowing a small clock skew, e.g., 60 seconds)
-    if code_timestamp > current_time + 60 {
+    if code_timestamp > current_time + 60 * NANOS_PER_SECOND {
          return ApiResponse::success(ResellerVerificationResponse {
             status: ResellerVerificationStatus::InvalidCode, // Or a more specific error
             organization: None,
             reseller: None,
+            product_authorized: None,
         });
     }
 
@@ -1371,6 +1698,7 @@ pub fn verify_reseller_v2(request: VerifyResellerRequest) -> ApiResponse<Reselle
             status: ResellerVerificationStatus::ResellerNotFound,
             organization: None,
             reseller: None,
+            product_authorized: None,
         });
     }
     let reseller = reseller_opt.unwrap();
@@ -1382,6 +1710,7 @@ pub fn verify_reseller_v2(request: VerifyResellerRequest) -> ApiResponse<Reselle
             status: ResellerVerificationStatus::OrganizationNotFound,
             organization: None,
             reseller: Some(reseller), // Can still return reseller info
+            product_authorized: None,
         });
     }
     let organization = org_opt.unwrap();
@@ -1391,39 +1720,20 @@ pub fn verify_reseller_v2(request: VerifyResellerRequest) -> ApiResponse<Reselle
     // Let's assume the verification should use the ORGANIZATION's public key, 
     // derived from the private key used in generation.
     // If reseller should have its own keypair, the model and generation logic need adjustment.
-    let public_key_bytes = match hex::decode(&organization.private_key) { // Using org's key for verification
-        Ok(bytes) => bytes,
-        Err(_) => {
-             return ApiResponse::success(ResellerVerificationResponse {
-                status: ResellerVerificationStatus::InternalError,
-                organization: Some(OrganizationPublic::from(organization.clone())), 
-                reseller: Some(reseller),
-            });
-        }
-    };
-    let public_key_encoded_point = match EncodedPoint::from_bytes(public_key_bytes) {
-        Ok(point) => point,
-        Err(_) => {
-             return ApiResponse::success(ResellerVerificationResponse {
-                status: ResellerVerificationStatus::InternalError,
-                organization: Some(OrganizationPublic::from(organization.clone())), 
-                reseller: Some(reseller),
-            });
-        }
-    };
-    let public_key = match VerifyingKey::from_encoded_point(&public_key_encoded_point) {
+    let public_key = match keys::org_verifying_key(&organization) {
         Ok(key) => key,
         Err(_) => {
              return ApiResponse::success(ResellerVerificationResponse {
                 status: ResellerVerificationStatus::InternalError,
                 organization: Some(OrganizationPublic::from(organization.clone())), 
                 reseller: Some(reseller),
+                product_authorized: None,
             });
         }
     };
 
     // 5. Prepare message hash
-    let msg = format!("{}_{}_{}", reseller_id.to_string(), code_timestamp, context_str);
+    let msg = format!("{}_{}_{}", reseller_id, code_timestamp, context_str);
     let mut hasher = Sha256::new();
     hasher.update(msg);
     let hashed_message = hasher.finalize();
@@ -1436,6 +1746,7 @@ pub fn verify_reseller_v2(request: VerifyResellerRequest) -> ApiResponse<Reselle
                 status: ResellerVerificationStatus::InvalidCode,
                 organization: Some(OrganizationPublic::from(organization.clone())), 
                 reseller: Some(reseller),
+                product_authorized: None,
             });
         }
     };
@@ -1446,31 +1757,73 @@ pub fn verify_reseller_v2(request: VerifyResellerRequest) -> ApiResponse<Reselle
                 status: ResellerVerificationStatus::InvalidCode,
                 organization: Some(OrganizationPublic::from(organization.clone())), 
                 reseller: Some(reseller),
+                product_authorized: None,
             });
          }
      };
 
     // 7. Verify signature
-    match public_key.verify(&hashed_message, &signature) {
-        Ok(_) => {
-            ApiResponse::success(ResellerVerificationResponse {
-                status: ResellerVerificationStatus::Success,
+    if public_key.verify(&hashed_message, &signature) {
+        // 8. A code already consumed in single-use mode is a replay
+        let mut sig_hasher = Sha256::new();
+        sig_hasher.update(&decoded_code);
+        let consumed_key = ConsumedCodeKey {
+            reseller_id,
+            timestamp: code_timestamp,
+            signature_hash: hex::encode(sig_hasher.finalize()),
+        };
+        if reseller_codes::is_consumed(&consumed_key) {
+            log_warn!("[verify_reseller_v2] Replay of consumed code for reseller {}", reseller_id);
+            return ApiResponse::success(ResellerVerificationResponse {
+                status: ResellerVerificationStatus::ReplayAttackDetected,
                 organization: Some(OrganizationPublic::from(organization)),
                 reseller: Some(reseller),
-            })
+                product_authorized: None,
+            });
         }
-        Err(_) => {
-            ApiResponse::success(ResellerVerificationResponse {
-                status: ResellerVerificationStatus::InvalidCode,
-                organization: Some(OrganizationPublic::from(organization)), // Still return org/reseller info on failure
+
+        // 9. A valid code from a reseller whose certification lapsed (past the grace period) is not accepted
+        if org_settings::is_certification_expired(&reseller, current_time) {
+            return ApiResponse::success(ResellerVerificationResponse {
+                status: ResellerVerificationStatus::CertificationExpired,
+                organization: Some(OrganizationPublic::from(organization)),
                 reseller: Some(reseller),
-            })
+                product_authorized: None,
+            });
         }
+
+        if org_settings::single_use_reseller_codes(reseller.org_id) {
+            reseller_codes::consume(consumed_key, code_expires_at, current_time);
+        }
+
+        // 10. Product-level authorization, when the consumer says what they are buying
+        let product_authorized = request.product_id.map(|product_id| {
+            let authorized = reseller_products::is_authorized(reseller_id, product_id);
+            if !authorized {
+                log_warn!("[verify_reseller_v2] Reseller {} is not authorized for product {}", reseller_id, product_id);
+            }
+            analytics::on_reseller_product_check(reseller.org_id, authorized);
+            authorized
+        });
+        ApiResponse::success(ResellerVerificationResponse {
+            status: ResellerVerificationStatus::Success,
+            organization: Some(OrganizationPublic::from(organization)),
+            reseller: Some(reseller),
+            product_authorized,
+        })
+    } else {
+        ApiResponse::success(ResellerVerificationResponse {
+            status: ResellerVerificationStatus::InvalidCode,
+            organization: Some(OrganizationPublic::from(organization)), // Still return org/reseller info on failure
+            reseller: Some(reseller),
+            product_authorized: None,
+        })
     }
 }
 
 #[update]
 pub fn generate_reseller_unique_code_v2(request: GenerateResellerUniqueCodeRequest) -> ApiResponse<ResellerUniqueCodeResponse> {
+    let _metrics = endpoint_metrics::track("generate_reseller_unique_code_v2");
     let reseller_id = request.reseller_id;
     let context_str = request.context.as_deref().unwrap_or(""); // Use empty string if None
 
@@ -1493,54 +1846,32 @@ pub fn generate_reseller_unique_code_v2(request: GenerateResellerUniqueCodeReque
     }
 
     // Check if an organization exists
-    let mut org_found = false;
-    let mut org_private_key = String::new();
-
-    ORGANIZATIONS.with(|orgs| {
-        if let Some(org) = orgs.borrow().get(&reseller_org_id) {
-            org_found = true;
-            org_private_key = org.private_key.clone();
-        }
-    });
-
-    if !org_found {
-        return ApiResponse::error(ApiError::not_found(&format!(
-            "Organization with ID {} not found for reseller {}",
-            reseller_org_id,
-            reseller_id
-        )));
-    }
-
-    // Deserialize private key
-    let private_key_bytes = match hex::decode(&org_private_key) {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            return ApiResponse::error(ApiError::internal_error(
-                "Malformed secret key for organization",
-            ))
+    let organization = match ORGANIZATIONS.with(|orgs| orgs.borrow().get(&reseller_org_id)) {
+        Some(org) => org,
+        None => {
+            return ApiResponse::error(ApiError::not_found(&format!(
+                "Organization with ID {} not found for reseller {}",
+                reseller_org_id,
+                reseller_id
+            )))
         }
     };
 
-    let private_key = match SigningKey::from_slice(&private_key_bytes.as_slice()) {
+    let signing_key = match keys::org_signing_key(&organization) {
         Ok(key) => key,
-        Err(_) => {
-            return ApiResponse::error(ApiError::internal_error(
-                "Malformed secret key for organization",
-            ))
-        }
+        Err(e) => return ApiResponse::error(e),
     };
 
     // Create message including reseller ID, current timestamp, and context
-    let current_time = api::time();
-    let msg = format!("{}_{}_{}", reseller_id.to_string(), current_time, context_str);
+    let current_time = clock::now();
+    let msg = format!("{}_{}_{}", reseller_id, current_time, context_str);
     
     // Hash and sign
     let mut hasher = Sha256::new();
     hasher.update(msg);
     let hashed_message = hasher.finalize();
 
-    let signature: Signature = private_key.sign(&hashed_message);
-    let signature_hex = hex::encode(signature.to_bytes());
+    let signature_hex = hex::encode(signing_key.sign(&hashed_message).to_bytes());
 
     ApiResponse::success(ResellerUniqueCodeResponse {
         unique_code: signature_hex,
@@ -1562,15 +1893,73 @@ pub fn list_product_serial_numbers(
     }
 }
 
+// Products a v2 listing covers: the one named, which must belong to the organization when both are
+// given, or all of the organization's products. The caller needs ReadProduct on the organization.
+fn listing_product_ids(
+    caller: Principal,
+    organization_id: Option<Principal>,
+    product_id: Option<Principal>,
+) -> Result<Vec<Principal>, ApiError> {
+    match (organization_id, product_id) {
+        (_, Some(product_id)) => {
+            let product = get_product(&product_id)?;
+            if organization_id.is_some_and(|org_id| org_id != product.org_id) {
+                return Err(ApiError::not_found("Product not found"));
+            }
+            authorize_for_organization(caller, product.org_id, Permission::ReadProduct)?;
+            Ok(vec![product_id])
+        }
+        (Some(org_id), None) => {
+            authorize_for_organization(caller, org_id, Permission::ReadProduct)?;
+            Ok(get_organization_product_ids(org_id))
+        }
+        (None, None) => Err(ApiError::invalid_input("An organization_id or a product_id is required")),
+    }
+}
+
+// Serials ordered by product and serial number, a page at a time
+#[query]
+pub fn list_product_serial_numbers_v2(request: ListProductSerialNumbersRequest) -> ApiResponse<ProductSerialNumbersListResponse> {
+    let product_ids = match listing_product_ids(api::caller(), request.organization_id, request.product_id) {
+        Ok(ids) => ids,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let mut product_ids = product_ids;
+    product_ids.sort();
+    product_ids.dedup();
+    // Keyed by the product a serial is stored under, which its own product_id may disagree with
+    let fetch = |after: Option<(Principal, Principal)>, n: usize| -> Vec<(Principal, ProductSerialNumber)> {
+        let mut serial_numbers = Vec::new();
+        for product_id in product_ids {
+            if serial_numbers.len() >= n {
+                break;
+            }
+            let after_serial = match after {
+                Some((after_product, _)) if product_id < after_product => continue,
+                Some((after_product, serial_no)) if product_id == after_product => Some(serial_no),
+                _ => None,
+            };
+            serial_numbers.extend(
+                serial_store::list_for_product_after(product_id, after_serial, n - serial_numbers.len())
+                    .into_iter()
+                    .map(|sn| (product_id, sn)),
+            );
+        }
+        serial_numbers
+    };
+    match paginate_from_store(&request.pagination.unwrap_or_default(), |(product_id, sn)| (*product_id, sn.serial_no), fetch) {
+        Ok((page_items, page_info)) => ApiResponse::success(ProductSerialNumbersListResponse {
+            serial_numbers: page_items.into_iter().map(|(_, sn)| sn).collect(),
+            pagination: Some(page_info),
+        }),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
 fn fetch_all_serial_numbers() -> Result<Vec<ProductSerialNumber>, ApiError> {
     let mut serial_numbers = Vec::new();
-
-    PRODUCT_SERIAL_NUMBERS.with(|sn_store| {
-        sn_store.borrow().iter().for_each(|(_, serialized_sn)| {
-            let decoded_numbers = decode_product_serial_numbers(&serialized_sn);
-            serial_numbers.extend(decoded_numbers);
-        });
-    });
+    serial_store::for_each(|sn| serial_numbers.push(sn.clone()));
 
     Ok(serial_numbers)
 }
@@ -1580,16 +1969,9 @@ fn fetch_organization_serial_numbers(
 ) -> Result<Vec<ProductSerialNumber>, ApiError> {
     let product_ids = get_organization_product_ids(org_id);
     let mut serial_numbers = Vec::new();
-
-    PRODUCT_SERIAL_NUMBERS.with(|sn_store| {
-        let store = sn_store.borrow();
-        for product_id in product_ids {
-            if let Some(serialized_sn) = store.get(&product_id) {
-                let decoded_numbers = decode_product_serial_numbers(&serialized_sn);
-                serial_numbers.extend(decoded_numbers);
-            }
-        }
-    });
+    for product_id in product_ids {
+        serial_numbers.extend(serial_store::list_for_product(product_id));
+    }
 
     Ok(serial_numbers)
 }
@@ -1602,16 +1984,7 @@ fn fetch_product_serial_numbers(
         return Ok(Vec::new());
     }
 
-    let serial_numbers = PRODUCT_SERIAL_NUMBERS.with(|sn_store| {
-        sn_store
-            .borrow()
-            .get(&product_id)
-            .map_or(Vec::new(), |serialized_sn| {
-                decode_product_serial_numbers(&serialized_sn)
-            })
-    });
-
-    Ok(serial_numbers)
+    Ok(serial_store::list_for_product(product_id))
 }
 
 fn get_organization_product_ids(org_id: Principal) -> Vec<Principal> {
@@ -1633,7 +2006,7 @@ fn is_product_owned_by_organization(product_id: Principal, org_id: Principal) ->
         products
             .borrow()
             .get(&product_id)
-            .map_or(false, |product| product.org_id == org_id)
+            .is_some_and(|product| product.org_id == org_id)
     })
 }
 
@@ -1641,58 +2014,78 @@ fn is_product_owned_by_organization(product_id: Principal, org_id: Principal) ->
 pub fn create_product_serial_number(
     product_id: Principal,
 ) -> ProductSerialNumberResult {
-    // Check if the product exists
-    let product_opt = PRODUCTS.with(|products| products.borrow().get(&product_id));
-
-    if product_opt.is_none() {
-        return ProductSerialNumberResult::Error(ApiError::not_found(&format!(
-            "Product with ID {} not found",
-            product_id
-        )));
+    match create_serial_number_in_pool(product_id, None) {
+        Ok(product_serial_number) => ProductSerialNumberResult::Result(product_serial_number),
+        Err(e) => ProductSerialNumberResult::Error(e),
     }
+}
 
-    let product = product_opt.unwrap();
+// Create a serial number for the product, optionally tagged with one of its variants
+fn create_serial_number_in_pool(
+    product_id: Principal,
+    variant_id: Option<Principal>,
+) -> Result<ProductSerialNumber, ApiError> {
+    // Check if the product exists
+    let product = PRODUCTS
+        .with(|products| products.borrow().get(&product_id))
+        .ok_or_else(|| ApiError::not_found(&format!("Product with ID {} not found", product_id)))?;
 
     // Check for write product permission
-    let authorization_result =
-        authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct);
-    if authorization_result.is_err() {
-        return ProductSerialNumberResult::Error(authorization_result.err().unwrap());
-    }
+    authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct)?;
+
+    quotas::ensure_storage_available(product.org_id)?;
+    quotas::consume_serials(product.org_id, 1)?;
 
-    // Continue with existing logic
-    let serial_no = generate_unique_principal(Principal::anonymous());
+    store_new_serial_number(&product, variant_id, None, api::caller())
+}
+
+// Allocate and store one serial. A user serial prefix numbers it sequentially even when the product
+// does not, since the human-readable serial is built from the sequence number. `created_by` is the
+// user the serial is recorded for, which is not the caller when a background job stores it.
+pub(crate) fn store_new_serial_number(
+    product: &Product,
+    variant_id: Option<Principal>,
+    user_serial_prefix: Option<&str>,
+    created_by: Principal,
+) -> Result<ProductSerialNumber, ApiError> {
+    let serial_no = id_registry::allocate_serial_no(product.id)?;
+    let sequence_no = if product.sequential_serials.unwrap_or(false) || user_serial_prefix.is_some() {
+        Some(serial_store::next_sequence_no(product.id))
+    } else {
+        None
+    };
+    let user_serial_no = match (user_serial_prefix, sequence_no) {
+        (Some(prefix), Some(seq)) => {
+            let user_serial_no = format!("{}{:0width$}", prefix, seq, width = USER_SERIAL_PAD_WIDTH);
+            if serial_store::user_serial_taken(product.id, &user_serial_no) {
+                return Err(ApiError::already_exists(&format!("Serial {} already exists for this product", user_serial_no)));
+            }
+            Some(user_serial_no)
+        }
+        _ => None,
+    };
 
     let product_serial_number = ProductSerialNumber {
-        product_id,
+        product_id: product.id,
         serial_no,
         print_version: 0,
         metadata: vec![],
-        created_at: api::time(),
-        created_by: api::caller(),
-        updated_at: api::time(),
-        updated_by: api::caller(),
+        last_print_job_id: None,
+        status: Some(SerialNumberStatus::Created),
+        suspected_cloned: None,
+        variant_id,
+        revoked_codes: None,
+        sequence_no,
+        user_serial_no,
+        created_at: clock::now(),
+        created_by,
+        updated_at: clock::now(),
+        updated_by: created_by,
     };
 
-    PRODUCT_SERIAL_NUMBERS.with(|serial_numbers| {
-        let mut serial_numbers_mut = serial_numbers.borrow_mut();
-
-        // Get existing serial numbers for this product, if any
-        let current_entries = match serial_numbers_mut.get(&product_id) {
-            Some(serialized_sn_vec) => decode_product_serial_numbers(&serialized_sn_vec),
-            None => Vec::new(),
-        };
-
-        // Create a new collection with existing items plus the new one
-        let mut updated_entries = current_entries;
-        updated_entries.push(product_serial_number.clone());
-
-        // Serialize and store the updated collection
-        let serialized_entries = encode_product_serial_numbers(&updated_entries);
-        serial_numbers_mut.insert(product_id, serialized_entries);
-    });
+    serial_store::insert(product_serial_number.clone());
 
-    ProductSerialNumberResult::Result(product_serial_number)
+    Ok(product_serial_number)
 }
 
 #[update]
@@ -1700,121 +2093,88 @@ pub fn update_product_serial_number(
     product_id: Principal,
     serial_no: Principal,
 ) -> ProductSerialNumberResult {
-    PRODUCT_SERIAL_NUMBERS.with(|serial_numbers| {
-        let mut serial_numbers_mut = serial_numbers.borrow_mut();
-
-        // Check if the product exists
-        if let Some(serialized_sn_vec) = serial_numbers_mut.get(&product_id) {
-            // Decode the collection
-            let mut product_sn_vec = decode_product_serial_numbers(&serialized_sn_vec);
-
-            // Find the serial number to update
-            let sn_index = product_sn_vec.iter().position(|s| s.serial_no == serial_no);
-
-            if let Some(idx) = sn_index {
-                // Update the serial number
-                let mut updated_sn = product_sn_vec[idx].clone();
-                updated_sn.updated_at = api::time();
-                updated_sn.updated_by = api::caller();
-
-                // Update in a collection
-                product_sn_vec[idx] = updated_sn.clone();
-
-                // Save an updated collection
-                serial_numbers_mut
-                    .insert(product_id, encode_product_serial_numbers(&product_sn_vec));
+    if serial_store::count_for_product(product_id) == 0 {
+        return ProductSerialNumberResult::Error(ApiError::not_found(
+            "Product has no registered serial_nos",
+        ));
+    }
 
-                ProductSerialNumberResult::Result(updated_sn)
-            } else {
-                ProductSerialNumberResult::Error(ApiError::not_found("Serial number not found"))
-            }
-        } else {
-            ProductSerialNumberResult::Error(ApiError::not_found(
-                "Product has no registered serial_nos",
-            ))
-        }
-    })
+    let updated = serial_store::update(product_id, serial_no, |sn| {
+        sn.updated_at = clock::now();
+        sn.updated_by = api::caller();
+    });
+    match updated {
+        Some(updated_sn) => ProductSerialNumberResult::Result(updated_sn),
+        None => ProductSerialNumberResult::Error(ApiError::not_found("Serial number not found")),
+    }
 }
 
 fn generate_and_store_unique_code_for_serial(
     product_id: Principal,
     serial_no: Principal,
-    organization_private_key_hex: &str,
+    signing_key: &OrgSigningKey,
+    print_job_id: Option<Principal>,
 ) -> Result<ProductUniqueCodeResultRecord, ApiError> {
-    PRODUCT_SERIAL_NUMBERS.with(|serial_numbers_refcell| {
-        let mut serial_numbers_map = serial_numbers_refcell.borrow_mut();
-
-        // Check if the product has any serial numbers stored and get them
-        let mut product_sn_vec = match serial_numbers_map.get(&product_id) {
-            Some(serialized_sn_vec) => decode_product_serial_numbers(&serialized_sn_vec),
-            None => {
-                return Err(ApiError::not_found(
-                    &format!("Product {} has no serial numbers recorded for printing", product_id)
-                ));
-            }
-        };
-
-        // Find the specific serial number to be "printed"
-        let sn_index = product_sn_vec
-            .iter()
-            .position(|sn| sn.serial_no == serial_no);
-
-        if sn_index.is_none() {
-            return Err(ApiError::not_found(&format!(
-                "Serial number {} for product {} not found for printing",
-                serial_no,
-                product_id
-            )));
-        }
-        let sn_idx = sn_index.unwrap();
-
-        // Deserialize the organization's private key
-        let private_key_bytes = match hex::decode(organization_private_key_hex) {
-            Ok(bytes) => bytes,
-            Err(_) => {
-                return Err(ApiError::internal_error(
-                    "Malformed secret key for organization during code generation",
-                ));
-            }
-        };
-        let private_key = match SigningKey::from_slice(&private_key_bytes) {
-            Ok(key) => key,
-            Err(_) => {
-                return Err(ApiError::internal_error(
-                    "Invalid secret key for organization during code generation",
-                ));
-            }
-        };
+    // Find the specific serial number to be "printed"
+    let mut serial = serial_store::get(product_id, serial_no).ok_or_else(|| {
+        ApiError::not_found(&format!(
+            "Serial number {} for product {} not found for printing",
+            serial_no,
+            product_id
+        ))
+    })?;
+
+    let current_status = serial.effective_status();
+    if current_status == SerialNumberStatus::Void {
+        return Err(ApiError::invalid_input(&format!(
+            "Serial number {} has been voided and cannot be printed",
+            serial_no
+        )));
+    }
 
-        // Increment the print version and update timestamps for the serial number
-        product_sn_vec[sn_idx].print_version = product_sn_vec[sn_idx].print_version.saturating_add(1);
-        product_sn_vec[sn_idx].updated_at = api::time();
-        product_sn_vec[sn_idx].updated_by = api::caller();
+    // Increment the print version and update timestamps for the serial number
+    serial.print_version = serial.print_version.saturating_add(1);
+    serial.last_print_job_id = print_job_id;
+    // Reprints keep a shipped or sold serial in its current state
+    serial.status = Some(if current_status == SerialNumberStatus::Created {
+        SerialNumberStatus::Printed
+    } else {
+        current_status
+    });
+    serial.updated_at = clock::now();
+    serial.updated_by = api::caller();
 
-        let updated_sn_clone = product_sn_vec[sn_idx].clone();
+    // Save the updated serial number back to stable storage
+    serial_store::insert(serial.clone());
+    print_history::record_print(PrintRecord {
+        serial_no,
+        product_id,
+        print_version: serial.print_version,
+        print_job_id,
+        printed_at: serial.updated_at,
+        printed_by: serial.updated_by,
+    });
 
-        // Save the updated collection of serial numbers back to stable storage
-        serial_numbers_map.insert(product_id, encode_product_serial_numbers(&product_sn_vec));
+    // Links on the brand's own domain when it has one verified, also for codes a manufacturer prints
+    let brand_org_id = PRODUCTS.with(|p| p.borrow().get(&product_id)).map(|p| p.org_id);
 
-        // Create the unique code by signing a message that includes the new print version
-        let msg_to_sign = format!(
-            "{}_{}_{}",
-            product_id.to_string(),
-            serial_no.to_string(),
-            updated_sn_clone.print_version // Use the incremented version
-        );
-        let mut hasher = Sha256::new();
-        hasher.update(msg_to_sign);
-        let hashed_message = hasher.finalize();
-        let signature: Signature = private_key.sign(&hashed_message);
-
-        Ok(ProductUniqueCodeResultRecord {
-            unique_code: hex::encode(signature.to_bytes().as_slice()), // Use .as_slice() for clarity
-            print_version: updated_sn_clone.print_version,
-            product_id: updated_sn_clone.product_id,
-            serial_no: updated_sn_clone.serial_no,
-            created_at: updated_sn_clone.created_at, // This is original created_at of SN, not this record
-        })
+    // Create the unique code by signing a message that includes the new print version
+    let msg_to_sign = format!(
+        "{}_{}_{}",
+        product_id,
+        serial_no,
+        serial.print_version // Use the incremented version
+    );
+    let unique_code = signing_key.sign_message(&msg_to_sign);
+
+    Ok(ProductUniqueCodeResultRecord {
+        verification_url: brand_org_id
+            .and_then(|org_id| verification_domains::verification_url(org_id, serial.serial_no, &unique_code)),
+        unique_code,
+        print_version: serial.print_version,
+        product_id: serial.product_id,
+        serial_no: serial.serial_no,
+        created_at: serial.created_at, // This is original created_at of SN, not this record
     })
 }
 
@@ -1832,1397 +2192,8945 @@ pub fn print_product_serial_number(
     }
     let product = product_opt.unwrap();
 
-    // Fetch organization to get private key
-    let organization_opt = ORGANIZATIONS.with(|o| o.borrow().get(&product.org_id));
-    if organization_opt.is_none() {
-        return ProductUniqueCodeResult::Error(ApiError::not_found(
-            &format!("Organization with ID {} not found for product {}", product.org_id, product_id)
-        ));
-    }
-    let organization = organization_opt.unwrap();
+    // Only enabled members of the product's organization may print its codes
+    let organization = match authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct) {
+        Ok(org) => org,
+        Err(e) => return ProductUniqueCodeResult::Error(e),
+    };
+    let signing_key = match keys::org_signing_key(&organization) {
+        Ok(key) => key,
+        Err(e) => return ProductUniqueCodeResult::Error(e),
+    };
 
     // Call the internal helper
-    match generate_and_store_unique_code_for_serial(product_id, serial_no, &organization.private_key) {
+    match generate_and_store_unique_code_for_serial(product_id, serial_no, &signing_key, None) {
         Ok(record) => ProductUniqueCodeResult::Result(record),
         Err(err) => ProductUniqueCodeResult::Error(err),
     }
 }
 
+// Check a unique code's signature over "{product_id}_{serial_no}_{print_version}".
+// Ok(false) means the code is well-formed but was not signed for this serial number.
+fn verify_unique_code_signature(
+    product: &Product,
+    serial_no: Principal,
+    print_version: u8,
+    unique_code: &str,
+) -> Result<bool, ApiError> {
+    Ok(unique_code_signer(product, serial_no, print_version, unique_code)?.is_some())
+}
+
+// Organization whose key signed the code: the product's own organization, or the manufacturer the
+// product is actively delegated to. None if neither key matches.
+fn unique_code_signer(
+    product: &Product,
+    serial_no: Principal,
+    print_version: u8,
+    unique_code: &str,
+) -> Result<Option<Principal>, ApiError> {
+    let msg = format!("{}_{}_{}", product.id, serial_no, print_version);
+    if verify_product_key_signature(product, &msg, unique_code)? {
+        return Ok(Some(product.org_id));
+    }
+    let manufacturer = match federation::active_delegation(product.id)
+        .and_then(|d| ORGANIZATIONS.with(|orgs| orgs.borrow().get(&d.manufacturer_org_id)))
+    {
+        Some(org) => org,
+        None => return Ok(None),
+    };
+    let public_key = keys::org_verifying_key(&manufacturer)?;
+    Ok(public_key.verify_message(&msg, unique_code)?.then_some(manufacturer.id))
+}
+
+// Earlier print version of the serial that the code was signed for, if any. Only the most recent
+// versions are tried, newest first.
+fn superseded_code_version(product: &Product, serial: &ProductSerialNumber, unique_code: &str) -> Option<u8> {
+    let oldest = serial
+        .print_version
+        .saturating_sub(print_history::MAX_SUPERSEDED_VERSIONS_CHECKED)
+        .max(1);
+    (oldest..serial.print_version)
+        .rev()
+        .find(|version| matches!(unique_code_signer(product, serial.serial_no, *version, unique_code), Ok(Some(_))))
+}
+
+// Checks a hex-encoded signature over the SHA-256 of `msg` against the product's public key
+fn verify_product_key_signature(product: &Product, msg: &str, signature_hex: &str) -> Result<bool, ApiError> {
+    keys::verifying_key(&product.public_key)?.verify_message(msg, signature_hex)
+}
+
+// Anonymous callers verify through a guest session, which then stands in for them.
+// Returns the guest token (if any) and the principal verifications are recorded against.
+fn resolve_verifier(caller: Principal, guest_token: Option<&str>) -> Result<(Option<String>, Principal), ApiError> {
+    if caller != Principal::anonymous() {
+        ensure_can_verify(caller)?;
+        return Ok((None, caller));
+    }
+    let token = guest_token
+        .ok_or_else(|| ApiError::unauthorized("Sign in or start a guest session to verify products"))?;
+    let session = guest_sessions::authorize_verification(token)?;
+    Ok((Some(token.to_string()), session.id))
+}
+
+// Product ID and stored record for a serial number
+fn find_serial_number(serial_no: Principal) -> Result<(Principal, ProductSerialNumber), ApiError> {
+    serial_store::find(serial_no).ok_or_else(|| ApiError::not_found("Serial number not valid or not found"))
+}
+
+// Same lookup on behalf of a consumer, counted against the verifier's probe budget so the serial space
+// cannot be mapped by guessing
+fn find_serial_number_for_verifier(verifier: Principal, serial_no: Principal) -> Result<(Principal, ProductSerialNumber), ApiError> {
+    let found = serial_store::find(serial_no);
+    let org_id = found
+        .as_ref()
+        .and_then(|(product_id, _)| PRODUCTS.with(|products| products.borrow().get(product_id)))
+        .map(|product| product.org_id);
+    probe_guard::record_probe(verifier, org_id, clock::now())?;
+    found.ok_or_else(|| ApiError::not_found("Serial number not valid or not found"))
+}
+
 #[update]
 pub fn verify_product_v2(request: VerifyProductEnhancedRequest) -> ApiResponse<ProductVerificationEnhancedResponse> {
+    let _metrics = endpoint_metrics::track("verify_product_v2");
     let caller = api::caller();
 
-    // --- 1. Find Product ID and ProductSerialNumber from the given serial_no ---
-    let mut found_product_id: Option<Principal> = None;
-    let mut found_product_sn_record: Option<ProductSerialNumber> = None;
-
-    PRODUCT_SERIAL_NUMBERS.with(|serial_numbers_map_ref| {
-        let serial_numbers_map = serial_numbers_map_ref.borrow();
-        for (p_id, storable_bytes) in serial_numbers_map.iter() {
-            let sn_vec = decode_product_serial_numbers(&storable_bytes);
-            if let Some(matching_sn) = sn_vec.iter().find(|sn| sn.serial_no == request.serial_no) {
-                found_product_id = Some(p_id);
-                found_product_sn_record = Some(matching_sn.clone());
-                break; 
-            }
-        }
-    });
-
-    let product_id = match found_product_id {
-        Some(id) => id,
-        None => return ApiResponse::error(ApiError::not_found("Serial number not valid or not found")),
+    // --- 0. Anonymous callers verify through a guest session, which stands in for them below ---
+    let (guest_token, verifier) = match resolve_verifier(caller, request.guest_token.as_deref()) {
+        Ok(resolved) => resolved,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let country_code = match request.country_code.as_deref().map(validation::normalize_country_code).transpose() {
+        Ok(code) => code,
+        Err(message) => return ApiResponse::error(ApiError::invalid_input(message)),
     };
 
-    let product_sn_record = match found_product_sn_record {
-        Some(psn) => psn,
-        // This case should ideally not be reached if product_id was found, but as a safeguard:
-        None => return ApiResponse::error(ApiError::internal_error("Inconsistent serial number data")), 
+    // --- 1. Find Product ID and ProductSerialNumber from the given serial_no ---
+    let (product_id, product_sn_record) = match find_serial_number_for_verifier(verifier, request.serial_no) {
+        Ok(found) => found,
+        Err(e) => return ApiResponse::error(e),
     };
 
     // --- 2. Check for rate limiting (using derived product_id) ---
-    let rate_limit_result = rate_limiter::record_verification_attempt(caller, product_id);
-    if let Err(error) = rate_limit_result {
-        return ApiResponse::error(error);
-    }
+    let rate_limit = match rate_limiter::record_verification_attempt(verifier, product_id) {
+        Ok(info) => info,
+        Err(error) => {
+            return ApiResponse::error(error).with_rate_limit(rate_limiter::check_rate_limit(verifier, product_id).ok());
+        }
+    };
     
     // --- 3. Get the Product (using derived product_id) ---
     let product_opt = PRODUCTS.with(|products| products.borrow().get(&product_id).map(|p| p.clone()));
     
     if product_opt.is_none() {
         // This implies data inconsistency if serial number was found but product wasn't.
-        return ApiResponse::error(ApiError::internal_error("Product data inconsistent: Product not found for existing serial number"));
+        return ApiResponse::error(ApiError::internal_error("Product data inconsistent: Product not found for existing serial number"))
+            .with_rate_limit(Some(rate_limit));
     }
     let product = product_opt.unwrap();
+    if let Err(e) = client_version::check(Some(product.org_id), request.client_version.as_deref()) {
+        return ApiResponse::error(e).with_rate_limit(Some(rate_limit));
+    }
+    if product.challenge_required.unwrap_or(false) {
+        return ApiResponse::error(ApiError::invalid_input(
+            "This product must be verified with a challenge; use start_verification_challenge",
+        ))
+        .with_rate_limit(Some(rate_limit));
+    }
 
     // --- 4. Use print_version from storage ---
     let print_version_from_storage = product_sn_record.print_version;
     
-    // --- 5. Deserialize public key (remains the same) ---
-    let public_key_bytes = match hex::decode(&product.public_key) {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            return ApiResponse::error(ApiError::internal_error("Malformed public key"));
-        }
+    // --- 5-7. Verify the unique code against the product's (or its delegate manufacturer's) public key ---
+    let signer_org_id = match unique_code_signer(
+        &product,
+        request.serial_no,
+        print_version_from_storage, // Use print_version from the stored ProductSerialNumber
+        &request.unique_code,
+    ) {
+        Ok(signer) => signer,
+        Err(e) => return ApiResponse::error(e).with_rate_limit(Some(rate_limit)),
     };
+    let is_genuine = signer_org_id.is_some();
 
-    let public_key_encoded_point = match EncodedPoint::from_bytes(public_key_bytes) {
-        Ok(point) => point,
-        Err(_) => {
-            return ApiResponse::error(ApiError::internal_error("Malformed public key"));
-        }
+    // --- 7a. A genuine code of an earlier print version was replaced by a reprint of the label ---
+    let superseded_version = if is_genuine {
+        None
+    } else {
+        superseded_code_version(&product, &product_sn_record, &request.unique_code)
     };
+    if let Some(version) = superseded_version {
+        let revocation_reason = product_sn_record.revocation_for(version).map(|revocation| revocation.reason);
+        let status = if revocation_reason.is_some() {
+            analytics::on_revoked_code_scan(product.org_id);
+            ProductVerificationStatus::CodeRevoked
+        } else {
+            ProductVerificationStatus::SupersededCode
+        };
+        webhooks::evaluate_verification_rules(product.org_id, product_id, request.serial_no, &status, 0);
 
-    let public_key = match VerifyingKey::from_encoded_point(&public_key_encoded_point) {
-        Ok(key) => key,
-        Err(_) => {
-            return ApiResponse::error(ApiError::internal_error("Malformed public key"));
+        return ApiResponse::success(ProductVerificationEnhancedResponse {
+            revocation_reason,
+            ..rejected_verification_response(status, &product, Some(rate_limit.clone()), request.locale.as_deref())
+        })
+        .with_rate_limit(Some(rate_limit));
+    }
+    
+    if !is_genuine {
+        if !product.is_test.unwrap_or(false) {
+            heatmap::on_invalid_scan(product.org_id, clock::now());
+            live_events::publish(
+                product.org_id,
+                LiveTopic::Counterfeits,
+                LiveEvent::Counterfeit {
+                    signal: CounterfeitSignal::InvalidCode,
+                    product_id,
+                    serial_no: request.serial_no,
+                    country_code: country_code.clone(),
+                    detected_at: clock::now(),
+                },
+            );
         }
-    };
+        webhooks::evaluate_verification_rules(
+            product.org_id,
+            product_id,
+            request.serial_no,
+            &ProductVerificationStatus::Invalid,
+            0,
+        );
 
-    // --- 6. Create message to verify (using derived product_id and stored print_version) ---
-    let msg = format!(
-        "{}_{}_{}",
-        product_id.to_string(),
-        request.serial_no.to_string(),
-        print_version_from_storage // Use print_version from the stored ProductSerialNumber
-    );
-    
-    let mut hasher = Sha256::new();
-    hasher.update(msg);
-    let hashed_message = hasher.finalize();
+        let response = rejected_verification_response(
+            ProductVerificationStatus::Invalid,
+            &product,
+            Some(rate_limit.clone()),
+            request.locale.as_deref(),
+        );
+        return ApiResponse::success(response).with_rate_limit(Some(rate_limit));
+    }
 
-    let decoded_code = match hex::decode(&request.unique_code) {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            return ApiResponse::error(ApiError::invalid_input("Malformed unique code"));
-        }
-    };
-    
-    let signature = match Signature::from_slice(decoded_code.as_slice()) {
-        Ok(sig) => sig,
-        Err(_) => {
-            return ApiResponse::error(ApiError::invalid_input("Invalid signature format"));
-        }
-    };
-    
-    // --- 7. Verify the signature ---
-    let verify_result = public_key.verify(&hashed_message, &signature);
-    
-    if verify_result.is_err() {
-        let response = ProductVerificationEnhancedResponse {
-            status: ProductVerificationStatus::Invalid,
-            verification: None,
-            rewards: None,
-            expiration: None,
-        };
-        return ApiResponse::success(response);
+    // --- 7b. A genuine code on a voided label is reported as such, not as a valid product ---
+    if product_sn_record.effective_status() == SerialNumberStatus::Void {
+        webhooks::evaluate_verification_rules(
+            product.org_id,
+            product_id,
+            request.serial_no,
+            &ProductVerificationStatus::VoidedSerial,
+            0,
+        );
+
+        return ApiResponse::success(rejected_verification_response(
+            ProductVerificationStatus::VoidedSerial,
+            &product,
+            Some(rate_limit.clone()),
+            request.locale.as_deref(),
+        ))
+        .with_rate_limit(Some(rate_limit));
+    }
+
+    // --- 7c. So is a genuine code from a printed version the brand revoked ---
+    if let Some(revocation) = product_sn_record.revocation_for(print_version_from_storage) {
+        log_warn!("[verify_product_v2] Revoked code scanned for serial {} (version {})", request.serial_no, print_version_from_storage);
+        analytics::on_revoked_code_scan(product.org_id);
+        webhooks::evaluate_verification_rules(
+            product.org_id,
+            product_id,
+            request.serial_no,
+            &ProductVerificationStatus::CodeRevoked,
+            0,
+        );
+
+        return ApiResponse::success(ProductVerificationEnhancedResponse {
+            revocation_reason: Some(revocation.reason),
+            ..rejected_verification_response(
+                ProductVerificationStatus::CodeRevoked,
+                &product,
+                Some(rate_limit.clone()),
+                request.locale.as_deref(),
+            )
+        })
+        .with_rate_limit(Some(rate_limit));
+    }
+
+    // --- 7d. Codes printed ahead of a launch do not verify before their print job is activated ---
+    if !print_jobs::is_activated(product_sn_record.last_print_job_id, clock::now()) {
+        return ApiResponse::success(rejected_verification_response(
+            ProductVerificationStatus::NotYetActive,
+            &product,
+            Some(rate_limit.clone()),
+            request.locale.as_deref(),
+        ))
+        .with_rate_limit(Some(rate_limit));
     }
     
+    let mut response = record_genuine_verification(
+        caller,
+        verifier,
+        guest_token.as_deref(),
+        &product,
+        &product_sn_record,
+        country_code,
+        request.locale.as_deref(),
+    );
+    response.rate_limit = Some(rate_limit.clone());
+    response.federation = signer_org_id
+        .filter(|signer| *signer != product.org_id)
+        .and_then(|manufacturer_org_id| federated_verification(product.org_id, manufacturer_org_id));
+    ApiResponse::success(response).with_rate_limit(Some(rate_limit))
+}
+
+fn federated_verification(brand_org_id: Principal, manufacturer_org_id: Principal) -> Option<FederatedVerification> {
+    let brand = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&brand_org_id))?;
+    let manufacturer = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&manufacturer_org_id))?;
+    Some(FederatedVerification {
+        brand: OrganizationPublic::from(brand),
+        manufacturer: OrganizationPublic::from(manufacturer),
+    })
+}
+
+// Steps shared by every successful verification path: status, rewards, history, alerts and the response.
+// Callers attach their own rate limit state.
+fn record_genuine_verification(
+    caller: Principal,
+    verifier: Principal,
+    guest_token: Option<&str>,
+    product: &Product,
+    product_sn_record: &ProductSerialNumber,
+    country_code: Option<String>,
+    locale: Option<&str>,
+) -> ProductVerificationEnhancedResponse {
+    let product_id = product.id;
+    let serial_no = product_sn_record.serial_no;
+    let annotations = verification_annotations(product, country_code.as_deref());
+    let is_test = product.is_test.unwrap_or(false);
+
     // --- 8. Determine verification status and calculate rewards (using derived product_id) ---
-    let verification_status = if rewards::is_first_verification_for_user(caller, product_id) {
+    let verification_status = if rewards::is_first_verification_for_user(verifier, product_id) {
         ProductVerificationStatus::FirstVerification
     } else {
         ProductVerificationStatus::MultipleVerification
     };
     
-    let rewards_result = rewards::calculate_verification_rewards(
-        caller, 
-        product_id, 
-        &verification_status
-    );
+    // Guest rewards are only credited once the session is claimed by a signed-in user
+    let rewards_result = if is_test {
+        rewards::simulate_verification_rewards(verifier, product_id, &verification_status)
+    } else if guest_token.is_some() {
+        rewards::record_product_verification(verifier, product_id);
+        VerificationRewards {
+            points: rewards::reward_points_for(product_id, &verification_status),
+            is_first_verification: verification_status == ProductVerificationStatus::FirstVerification,
+            special_reward: None,
+            reward_description: Some("Sign in and claim this guest session to collect these points".to_string()),
+        }
+    } else {
+        rewards::calculate_verification_rewards(caller, product_id, &verification_status)
+    };
     
     // --- 9. Record the verification (using derived product_id and stored print_version) ---
     let verification_id = generate_unique_principal(Principal::anonymous());
     
     let verification = ProductVerification {
         id: verification_id,
-        product_id: product_id, // Use derived product_id
-        serial_no: request.serial_no,
-        print_version: print_version_from_storage, // Use stored print_version
+        product_id, // Use derived product_id
+        serial_no,
+        variant_id: product_sn_record.variant_id,
+        print_version: product_sn_record.print_version, // Use stored print_version
         metadata: Vec::new(), // Metadata removed from request
-        created_at: api::time(),
-        created_by: caller,
+        created_at: clock::now(),
+        created_by: verifier,
         status: verification_status.clone(),
         reward_claimed: false, // Initialize as false
         reward_transaction_id: None, // Initialize as None
+        country_code: country_code.clone(),
+        annotations: Some(annotations.clone()).filter(|a| !a.is_empty()),
+        is_test: Some(true).filter(|_| is_test),
     };
     
-    PRODUCT_VERIFICATIONS.with(|verifications| {
-        let mut verifications_mut = verifications.borrow_mut();
-        let mut verification_vec = if let Some(serialized_verifications) = verifications_mut.get(&product_id) {
-            decode_product_verifications(&serialized_verifications)
+    verification_store::append(&verification);
+    let (serial_scan_count, distinct_scanners, first_scanned_at, scans_last_day, scan_countries) = {
+        let serial_verifications = verification_store::list_for_serial(product_id, serial_no);
+        let scan_count = serial_verifications.len() as u32;
+        let scanners = serial_verifications.iter().map(|v| v.created_by).collect::<std::collections::HashSet<_>>().len() as u32;
+        let first_scanned_at = serial_verifications.iter().map(|v| v.created_at).min();
+        let day_ago = verification.created_at.saturating_sub(24 * 60 * 60 * NANOS_PER_SECOND);
+        let scans_last_day = serial_verifications.iter().filter(|v| v.created_at >= day_ago).count() as u32;
+        let mut countries: Vec<String> = serial_verifications.iter().filter_map(|v| v.country_code.clone()).collect();
+        countries.sort();
+        countries.dedup();
+        (scan_count, scanners, first_scanned_at, scans_last_day, countries)
+    };
+    
+    // --- 10. Record successful verification in rate limiter (using derived product_id) ---
+    rate_limiter::record_successful_verification(verifier, product_id);
+    if let Some(token) = guest_token {
+        guest_sessions::record_verification(token, verification.id, product_id);
+    }
+    if !is_test {
+        analytics::on_verification_recorded(product.org_id, verification.created_at);
+        heatmap::on_verification_recorded(product.org_id, &verification);
+        public_stats::on_verification_recorded(product.org_id, &verification);
+        live_events::publish(
+            product.org_id,
+            LiveTopic::Verifications,
+            LiveEvent::Verification {
+                product_id,
+                serial_no,
+                status: verification.status.clone(),
+                country_code: verification.country_code.clone(),
+                recorded_at: verification.created_at,
+            },
+        );
+    }
+
+    // --- 10b. Evaluate per-product notification rules (delivery is asynchronous) ---
+    webhooks::evaluate_verification_rules(
+        product.org_id,
+        product_id,
+        serial_no,
+        &verification_status,
+        serial_scan_count,
+    );
+
+    // --- 10b2. Grey market alerting ---
+    if annotations.contains(&VerificationAnnotation::GreyMarketSuspected) {
+        let country = country_code.as_deref().unwrap_or_default();
+        log_warn!("[record_genuine_verification] Product {} scanned outside its markets in {}", product_id, country);
+        if !is_test {
+            analytics::on_grey_market_verification(product.org_id);
+            live_events::publish(
+                product.org_id,
+                LiveTopic::Counterfeits,
+                LiveEvent::Counterfeit {
+                    signal: CounterfeitSignal::GreyMarket,
+                    product_id,
+                    serial_no,
+                    country_code: country_code.clone(),
+                    detected_at: verification.created_at,
+                },
+            );
+        }
+        webhooks::notify_grey_market(product.org_id, product_id, serial_no, country);
+    }
+
+    // --- 10c. Duplicate-scan alerting ---
+    let warning = check_duplicate_scan_threshold(
+        product.org_id,
+        product_id,
+        product_sn_record,
+        distinct_scanners,
+    );
+
+    // --- 10d. Inbox notification for earned points ---
+    if rewards_result.points > 0 && guest_token.is_none() && !is_test {
+        notifications::notify_user(
+            caller,
+            UserNotificationKind::RewardGranted,
+            "Reward points earned",
+            format!("You earned {} points for verifying {}.", rewards_result.points, product.name),
+            Some(verification.id),
+        );
+    }
+    
+    // --- 10e. Brand message for the consumer ---
+    let message = verification_messages::resolve_message(product_id, product.org_id).map(|template| {
+        let brand_name = ORGANIZATIONS
+            .with(|orgs| orgs.borrow().get(&product.org_id).map(|org| org.name))
+            .unwrap_or_default();
+        // Guests have no balance until they claim their session
+        let total_points = if guest_token.is_some() {
+            rewards_result.points
         } else {
-            Vec::new()
+            rewards::get_user_rewards(caller).map_or(rewards_result.points, |r| r.total_points)
+        };
+        let context = verification_messages::MessageContext {
+            product_name: &product.name,
+            brand_name: &brand_name,
+            points: rewards_result.points,
+            total_points,
         };
-        verification_vec.push(verification.clone());
-        verifications_mut.insert(product_id, encode_product_verifications(&verification_vec));
+        RenderedVerificationMessage {
+            text: verification_messages::render(&template.template, &context),
+            cta_label: template.cta_label,
+            cta_url: template.cta_url,
+        }
     });
     
-    // --- 10. Record successful verification in rate limiter (using derived product_id) ---
-    rate_limiter::record_successful_verification(caller, product_id);
-    
+    // --- 10f. Authenticity confidence; the signature was already checked to get here ---
+    let authenticity = authenticity::score(
+        &authenticity::AuthenticitySignals {
+            signature_valid: true,
+            distinct_scanners,
+            scans_last_day,
+            duplicate_scan_threshold: org_settings::duplicate_scan_threshold(product.org_id),
+            suspected_cloned: warning.is_some(),
+            countries: scan_countries,
+            grey_market: annotations.contains(&VerificationAnnotation::GreyMarketSuspected),
+            custody_status: product_sn_record.effective_status(),
+            open_reports: disputes::count_open_for_serial(serial_no),
+        },
+        &authenticity::weights(product.org_id),
+    );
+
     // --- 11. Calculate expiration time (remains the same) ---
-    let expiration_time = api::time() + 86400; // 24 hours
+    let expiration_time = clock::now() + 86400; // 24 hours
     
     let response = ProductVerificationEnhancedResponse {
-        status: verification_status,
         verification: Some(verification),
         rewards: Some(rewards_result),
         expiration: Some(expiration_time),
+        warning,
+        variant: product_sn_record.variant_id.and_then(variants::get_variant),
+        message,
+        annotations,
+        scan_count: Some(serial_scan_count),
+        first_scanned_at,
+        custody_status: Some(product_sn_record.effective_status()),
+        bundle: bundles::get_bundle(serial_no).map(|bundle| bundles::summarize(&bundle)),
+        authenticity: Some(authenticity),
+        ..rejected_verification_response(verification_status, product, None, locale)
     };
-    
-    ApiResponse::success(response)
+    response_privacy::shape_verification_response(product.org_id, response)
 }
 
-#[query]
-pub fn get_verification_rate_limit(product_id: Principal) -> ApiResponse<RateLimitInfo> {
-    let caller = api::caller();
-    
-    match rate_limiter::check_rate_limit(caller, product_id) {
-        Ok(rate_limit_info) => ApiResponse::success(rate_limit_info),
-        Err(error) => ApiResponse::error(error),
+// Grey market check: only flagged when the product limits its markets and the client reported a country
+fn verification_annotations(product: &Product, country_code: Option<&str>) -> Vec<VerificationAnnotation> {
+    let mut annotations = Vec::new();
+    if let (Some(markets), Some(country)) = (product.allowed_markets.as_ref(), country_code) {
+        if !markets.is_empty() && !markets.iter().any(|m| m == country) {
+            annotations.push(VerificationAnnotation::GreyMarketSuspected);
+        }
+    }
+    annotations
+}
+
+// Response for a scan that stops before a verification is recorded: only the status and what the product says.
+// The genuine and simulated paths start from it and fill in the rest.
+fn rejected_verification_response(
+    status: ProductVerificationStatus,
+    product: &Product,
+    rate_limit: Option<RateLimitInfo>,
+    locale: Option<&str>,
+) -> ProductVerificationEnhancedResponse {
+    let status_message = status_messages::message_for(&status, locale);
+    ProductVerificationEnhancedResponse {
+        status,
+        verification: None,
+        rewards: None,
+        expiration: None,
+        warning: None,
+        variant: None,
+        message: None,
+        rate_limit,
+        discontinued: product.discontinued_at.is_some(),
+        successor: product_successor(product),
+        annotations: Vec::new(),
+        revocation_reason: None,
+        scan_count: None,
+        first_scanned_at: None,
+        custody_status: None,
+        federation: None,
+        is_test: product.is_test.unwrap_or(false),
+        bundle: None,
+        authenticity: None,
+        status_message,
+    }
+}
+
+// Successor shown alongside verifications of a discontinued product
+fn product_successor(product: &Product) -> Option<ProductSuccessor> {
+    product.discontinued_at?;
+    let successor_id = product.successor_product_id?;
+    if moderation::is_quarantined(successor_id) {
+        return None;
     }
+    PRODUCTS
+        .with(|products| products.borrow().get(&successor_id))
+        .map(|successor| ProductSuccessor { product_id: successor_id, name: successor.name })
 }
 
+// ====== Product Lifecycle ======
+
+// Mark a product as end-of-life. It keeps verifying, and scans point consumers to the successor if one is given.
+// Calling it again updates the successor.
 #[update]
-pub fn list_organizations_v2(request: FindOrganizationsRequest) -> ApiResponse<OrganizationsListResponse> {
-    let filter = request.name.trim().to_lowercase();
+pub fn discontinue_product_v2(product_id: Principal, successor_product_id: Option<Principal>) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("discontinue_product_v2");
     let caller = api::caller();
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
 
-    // Get user to check role and permissions
-    let user_opt = USERS.with(|users| users.borrow().get(&caller));
-
-    // Check if user exists
-    if user_opt.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User not found"));
+    if let Some(successor_id) = successor_product_id {
+        if successor_id == product_id {
+            return ApiResponse::error(ApiError::invalid_input("A product cannot be its own successor"));
+        }
+        let successor = match get_product(&successor_id) {
+            Ok(p) => p,
+            Err(e) => return ApiResponse::error(e),
+        };
+        if successor.org_id != product.org_id {
+            return ApiResponse::error(ApiError::invalid_input("Successor must belong to the same organization"));
+        }
+        if successor.discontinued_at.is_some() {
+            return ApiResponse::error(ApiError::invalid_input("Successor product is itself discontinued"));
+        }
     }
 
-    let user = user_opt.unwrap();
+    let now = clock::now();
+    product.discontinued_at = product.discontinued_at.or(Some(now));
+    product.successor_product_id = successor_product_id;
+    product.updated_at = now;
+    product.updated_by = caller;
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    log_info!("Product {} discontinued by {} (successor: {:?})", product_id, caller, successor_product_id);
 
-    // Check if user has a role
-    if user.user_role.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User has no role assigned"));
+    ApiResponse::success(ProductResponse { product })
+}
+
+// Limit where a product is meant to be sold. An empty list lifts the restriction.
+#[update]
+pub fn set_product_markets_v2(product_id: Principal, markets: Vec<String>) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("set_product_markets_v2");
+    let caller = api::caller();
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
     }
 
-    let role = user.user_role.unwrap();
+    let mut validator = Validator::new();
+    let mut normalized: Vec<String> = Vec::with_capacity(markets.len());
+    for (i, market) in markets.iter().enumerate() {
+        match validation::normalize_country_code(market) {
+            Ok(code) if !normalized.contains(&code) => normalized.push(code),
+            Ok(_) => {}
+            Err(message) => validator.add_error(&format!("markets[{}]", i), message),
+        }
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
 
-    ORGANIZATIONS.with(|orgs| {
-        let orgs_borrow = orgs.borrow();
-        
-        // Filter organizations based on name and user's permissions
-        let filtered_orgs: Vec<OrganizationPublic> = if matches!(role, UserRole::Admin) {
-            // Admin can see all organizations matching the filter
-            orgs_borrow
-                .iter()
-                .filter(|(_, org)| org.name.to_lowercase().contains(&filter))
-                .map(|(_, org)| OrganizationPublic::from(org.clone()))
-                .collect()
-        } else {
-            // Non-admin users can only see organizations they belong to
-            orgs_borrow
-                .iter()
-                .filter(|(org_id, org)| {
-                    org.name.to_lowercase().contains(&filter) && user.org_ids.contains(org_id)
-                })
-                .map(|(_, org)| OrganizationPublic::from(org.clone()))
-                .collect()
-        };
-        
-        // Apply pagination if requested
-        let pagination_request = request.pagination.unwrap_or_default();
-        let (paginated_orgs, pagination) = paginate(filtered_orgs, &pagination_request);
-        
-        // Create the response
-        let response = OrganizationsListResponse {
-            organizations: paginated_orgs,
-            pagination: Some(pagination),
-        };
-        
-        ApiResponse::success(response)
-    })
+    product.allowed_markets = Some(normalized).filter(|m| !m.is_empty());
+    product.updated_at = clock::now();
+    product.updated_by = caller;
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    ApiResponse::success(ProductResponse { product })
 }
 
+// ====== Test Mode ======
+
+// Test products behave like real ones for scanning, but credit no points and stay out of analytics,
+// scheduled reports and BI exports, so brands can trial the flow without polluting their numbers
 #[update]
-pub fn create_organization_v2(request: CreateOrganizationRequest) -> ApiResponse<OrganizationResponse> {
-    // Input validation
-    if request.name.trim().is_empty() {
-        return ApiResponse::error(ApiError::invalid_input("Organization name cannot be empty"));
+pub fn set_product_test_mode_v2(product_id: Principal, is_test: bool) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("set_product_test_mode_v2");
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
     }
 
-    // For creation, we don't need to check existing permissions since this creates a brand new org
-    // However, we should check if the user has a registered account at minimum
+    product.is_test = Some(is_test);
+    product.updated_at = clock::now();
+    product.updated_by = api::caller();
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    // Verifications recorded before or after the switch are counted by their own flag, so rebuild
+    analytics::rebuild_counters(product.org_id);
+    log_info!("Test mode {} for product {}.", if is_test { "enabled" } else { "disabled" }, product_id);
+    ApiResponse::success(ProductResponse { product })
+}
+
+// ====== Verification Simulator ======
+
+// Fully populated verification response for a scenario, so client apps can exercise every branch
+// without printing bad labels or scanning real codes. A query: nothing is recorded, no points are
+// credited and no alerts go out. Only for test-mode products of organizations with the flag on.
+#[query]
+pub fn simulate_verification(request: SimulateVerificationRequest) -> ApiResponse<ProductVerificationEnhancedResponse> {
     let caller = api::caller();
-    let user_exists = USERS.with(|users| users.borrow().get(&caller).is_some());
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    if let Err(e) = feature_flags::ensure_enabled(feature_flags::FLAG_VERIFICATION_SIMULATOR, Some(product.org_id)) {
+        return ApiResponse::error(e);
+    }
+    if !product.is_test.unwrap_or(false) {
+        return ApiResponse::error(ApiError::invalid_input(
+            "Verifications can only be simulated for test-mode products; enable it with set_product_test_mode_v2",
+        ));
+    }
+    let locale = match request.locale.as_deref().map(status_messages::normalize_locale).transpose() {
+        Ok(locale) => locale,
+        Err(message) => return ApiResponse::error(ApiError::invalid_input(&message)),
+    };
 
-    if !user_exists {
-        // Register the user automatically
-        let register_result = register();
-        if register_result.id == Principal::anonymous() {
-            return ApiResponse::error(ApiError::internal_error("Failed to register user automatically"));
+    ApiResponse::success(simulated_verification_response(caller, &product, request.scenario, locale.as_deref()))
+}
+
+fn simulated_verification_response(
+    caller: Principal,
+    product: &Product,
+    scenario: VerificationScenario,
+    locale: Option<&str>,
+) -> ProductVerificationEnhancedResponse {
+    let now = clock::now();
+    let status = match scenario {
+        VerificationScenario::FirstVerification | VerificationScenario::Discontinued | VerificationScenario::GreyMarket => {
+            ProductVerificationStatus::FirstVerification
         }
-    }
+        VerificationScenario::MultipleVerification | VerificationScenario::SuspectedClone => {
+            ProductVerificationStatus::MultipleVerification
+        }
+        VerificationScenario::Invalid => ProductVerificationStatus::Invalid,
+        VerificationScenario::VoidedSerial => ProductVerificationStatus::VoidedSerial,
+        VerificationScenario::CodeRevoked => ProductVerificationStatus::CodeRevoked,
+        VerificationScenario::NotYetActive => ProductVerificationStatus::NotYetActive,
+        VerificationScenario::SupersededCode => ProductVerificationStatus::SupersededCode,
+    };
+    let rate_limit_config = config::rate_limit_config();
+    let successor = if scenario == VerificationScenario::Discontinued {
+        // The product's own successor if it names one, else the product itself as a stand-in
+        product_successor(&Product { discontinued_at: Some(now), ..product.clone() })
+            .or_else(|| Some(ProductSuccessor { product_id: product.id, name: product.name.clone() }))
+    } else {
+        None
+    };
+    let annotations = if scenario == VerificationScenario::GreyMarket {
+        vec![VerificationAnnotation::GreyMarketSuspected]
+    } else {
+        Vec::new()
+    };
 
-    let id = generate_unique_principal(Principal::anonymous()); // Generate a unique ID for the organization
-    
-    // Generate ECDSA keys for demonstration
-    let mut rng = StdRng::from_entropy();
-    let signing_key = SigningKey::random(&mut rng);
-    
-    let organization = Organization {
-        id,
-        name: request.name,
-        private_key: hex::encode(&signing_key.to_bytes()),
-        description: request.description,
-        metadata: request.metadata,
-        created_at: api::time(),
-        created_by: caller,
-        updated_at: api::time(),
-        updated_by: caller,
+    let rate_limit = RateLimitInfo {
+        remaining_attempts: rate_limit_config.max_attempts_per_window.saturating_sub(1),
+        reset_time: now.saturating_add(rate_limit_config.window_duration_seconds.saturating_mul(NANOS_PER_SECOND)),
+        current_window_start: now,
+        penalty_level: 0,
+        locked_until: None,
     };
 
-    ORGANIZATIONS.with(|orgs| {
-        orgs.borrow_mut().insert(id, organization.clone());
-    });
+    let mut response = ProductVerificationEnhancedResponse {
+        discontinued: successor.is_some(),
+        successor,
+        annotations: annotations.clone(),
+        revocation_reason: Some(CodeRevocationReason::Stolen).filter(|_| scenario == VerificationScenario::CodeRevoked),
+        is_test: true,
+        ..rejected_verification_response(status.clone(), product, Some(rate_limit), locale)
+    };
+    if !matches!(status, ProductVerificationStatus::FirstVerification | ProductVerificationStatus::MultipleVerification) {
+        return response;
+    }
 
-    // Add the organization to the user's organizations
-    let add_org_to_user_result = USERS.with(|users| {
-        let mut users_mut = users.borrow_mut();
-        match users_mut.get(&caller) {
-            Some(user) => {
-                let mut updated_user = user.clone();
-                updated_user.org_ids.push(id);
-                updated_user.updated_at = api::time();
-                users_mut.insert(caller, updated_user);
-                true
-            }
-            None => false,
+    let is_first_verification = status == ProductVerificationStatus::FirstVerification;
+    let duplicate_scan_threshold = org_settings::duplicate_scan_threshold(product.org_id);
+    let (scan_count, distinct_scanners) = match scenario {
+        VerificationScenario::SuspectedClone => (duplicate_scan_threshold + 1, duplicate_scan_threshold + 1),
+        _ if is_first_verification => (1, 1),
+        _ => (3, 2),
+    };
+    let points = rewards::reward_points_for(product.id, &status);
+    let warning = Some(SUSPECTED_CLONE_WARNING.to_string()).filter(|_| scenario == VerificationScenario::SuspectedClone);
+
+    response.verification = Some(ProductVerification {
+        id: product.id,
+        product_id: product.id,
+        serial_no: product.id,
+        print_version: 1,
+        created_at: now,
+        created_by: caller,
+        status: status.clone(),
+        annotations: Some(annotations.clone()).filter(|a| !a.is_empty()),
+        is_test: Some(true),
+        ..Default::default()
+    });
+    response.rewards = Some(VerificationRewards {
+        points,
+        is_first_verification,
+        special_reward: None,
+        reward_description: Some("Simulated verification; no points were credited".to_string()),
+    });
+    response.expiration = Some(now + 86400);
+    response.message = verification_messages::resolve_message(product.id, product.org_id).map(|template| {
+        let brand_name = ORGANIZATIONS
+            .with(|orgs| orgs.borrow().get(&product.org_id).map(|org| org.name))
+            .unwrap_or_default();
+        let context = verification_messages::MessageContext {
+            product_name: &product.name,
+            brand_name: &brand_name,
+            points,
+            total_points: points,
+        };
+        RenderedVerificationMessage {
+            text: verification_messages::render(&template.template, &context),
+            cta_label: template.cta_label,
+            cta_url: template.cta_url,
         }
     });
+    response.scan_count = Some(scan_count);
+    response.first_scanned_at = Some(if is_first_verification {
+        now
+    } else {
+        now.saturating_sub(7 * 24 * 60 * 60 * NANOS_PER_SECOND)
+    });
+    response.custody_status = Some(SerialNumberStatus::Sold);
+    response.authenticity = Some(authenticity::score(
+        &authenticity::AuthenticitySignals {
+            signature_valid: true,
+            distinct_scanners,
+            scans_last_day: scan_count,
+            duplicate_scan_threshold,
+            suspected_cloned: warning.is_some(),
+            countries: Vec::new(),
+            grey_market: !annotations.is_empty(),
+            custody_status: SerialNumberStatus::Sold,
+            open_reports: 0,
+        },
+        &authenticity::weights(product.org_id),
+    ));
+    response.warning = warning;
+    response_privacy::shape_verification_response(product.org_id, response)
+}
 
-    if !add_org_to_user_result {
-        // This is unlikely but handle it anyway
-        return ApiResponse::error(ApiError::internal_error("Failed to add organization to user"));
+// ====== Verification Challenges ======
+
+#[update]
+pub fn set_product_challenge_mode_v2(product_id: Principal, required: bool) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("set_product_challenge_mode_v2");
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
     }
 
-    ApiResponse::success(OrganizationResponse {
-        organization: OrganizationPublic::from(organization),
-    })
+    product.challenge_required = Some(required);
+    product.updated_at = clock::now();
+    product.updated_by = api::caller();
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    log_info!("Challenge verification {} for product {}.", if required { "enabled" } else { "disabled" }, product_id);
+    ApiResponse::success(ProductResponse { product })
 }
 
+// First half of challenge-response verification: a single-use nonce for the tag to sign
 #[update]
-pub fn update_organization_v2(request: UpdateOrganizationRequest) -> ApiResponse<OrganizationResponse> {
-    // Input validation
-    if request.name.trim().is_empty() {
-        return ApiResponse::error(ApiError::invalid_input("Organization name cannot be empty"));
+pub fn start_verification_challenge(request: StartVerificationChallengeRequest) -> ApiResponse<VerificationChallengeResponse> {
+    let _metrics = endpoint_metrics::track("start_verification_challenge");
+    let (_, verifier) = match resolve_verifier(api::caller(), request.guest_token.as_deref()) {
+        Ok(resolved) => resolved,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let (product_id, _) = match find_serial_number_for_verifier(verifier, request.serial_no) {
+        Ok(found) => found,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = client_version::check(Some(product.org_id), request.client_version.as_deref()) {
+        return ApiResponse::error(e);
+    }
+    if !product.challenge_required.unwrap_or(false) {
+        return ApiResponse::error(ApiError::invalid_input(
+            "Challenge verification is not enabled for this product; use verify_product_v2",
+        ));
     }
+    // Issuing a challenge counts as an attempt, so nonces cannot be farmed
+    let rate_limit = match rate_limiter::record_verification_attempt(verifier, product_id) {
+        Ok(info) => info,
+        Err(e) => return ApiResponse::error(e).with_rate_limit(rate_limiter::check_rate_limit(verifier, product_id).ok()),
+    };
 
-    // Check that user has write permission for this organization
-    let result = authorize_for_organization(ic_cdk::caller(), request.id, Permission::WriteOrganization);
-    if result.is_err() {
-        return ApiResponse::error(result.err().unwrap());
+    let challenge = challenges::start_challenge(product_id, request.serial_no, verifier);
+    ApiResponse::success(VerificationChallengeResponse {
+        challenge_id: challenge.id,
+        nonce: challenge.nonce,
+        product_id,
+        expires_at: challenge.expires_at,
+    })
+    .with_rate_limit(Some(rate_limit))
+}
+
+// Second half: check the tag's signature over the nonce and, if genuine, record the verification
+#[update]
+pub fn complete_verification_challenge(request: CompleteVerificationChallengeRequest) -> ApiResponse<ChallengeVerificationResponse> {
+    let _metrics = endpoint_metrics::track("complete_verification_challenge");
+    let caller = api::caller();
+    let (guest_token, verifier) = match resolve_verifier(caller, request.guest_token.as_deref()) {
+        Ok(resolved) => resolved,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let country_code = match request.country_code.as_deref().map(validation::normalize_country_code).transpose() {
+        Ok(code) => code,
+        Err(message) => return ApiResponse::error(ApiError::invalid_input(message)),
+    };
+
+    let challenge = match challenges::get_challenge(request.challenge_id) {
+        Some(c) if c.requested_by == verifier => c,
+        _ => return ApiResponse::error(ApiError::not_found("Verification challenge not found")),
+    };
+    // The attempt was charged when the challenge was issued; report where that left the caller
+    let rate_limit = rate_limiter::check_rate_limit(verifier, challenge.product_id).ok();
+    if challenge.status != VerificationChallengeStatus::Pending {
+        return ApiResponse::error(ApiError::invalid_input("Verification challenge has already been completed"))
+            .with_rate_limit(rate_limit);
+    }
+    if clock::now() >= challenge.expires_at {
+        challenges::finish_challenge(challenge.id, VerificationChallengeStatus::Expired);
+        return ApiResponse::success(ChallengeVerificationResponse {
+            status: VerificationChallengeStatus::Expired,
+            result: None,
+        })
+        .with_rate_limit(rate_limit);
     }
 
-    ORGANIZATIONS.with(|orgs| {
-        let mut orgs_mut = orgs.borrow_mut();
-        match orgs_mut.get(&request.id) {
-            Some(org) => {
-                // Create a new organization with updated fields
-                let updated_org = Organization {
-                    name: request.name,
-                    description: request.description,
-                    metadata: request.metadata,
-                    updated_at: api::time(),
-                    updated_by: api::caller(),
-                    ..org.clone()
-                };
+    let (product_id, product_sn_record) = match find_serial_number(challenge.serial_no) {
+        Ok(found) if found.0 == challenge.product_id => found,
+        Ok(_) => {
+            return ApiResponse::error(ApiError::internal_error("Serial number moved since the challenge was issued"))
+                .with_rate_limit(rate_limit);
+        }
+        Err(e) => return ApiResponse::error(e).with_rate_limit(rate_limit),
+    };
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e).with_rate_limit(rate_limit),
+    };
 
-                // Insert the updated organization
-                orgs_mut.insert(request.id, updated_org.clone());
+    let msg = format!("{}_{}_{}", product_id, challenge.serial_no, challenge.nonce);
+    let is_genuine = match verify_product_key_signature(&product, &msg, &request.signature) {
+        Ok(valid) => valid,
+        Err(e) => return ApiResponse::error(e).with_rate_limit(rate_limit),
+    };
 
-                ApiResponse::success(OrganizationResponse {
-                    organization: OrganizationPublic::from(updated_org),
-                })
-            }
-            None => ApiResponse::error(ApiError::not_found(&format!(
-                "Organization with ID {} not found",
-                request.id
-            ))),
+    let is_void = product_sn_record.effective_status() == SerialNumberStatus::Void;
+    let is_revoked = product_sn_record.revocation_for(product_sn_record.print_version).is_some();
+    let is_activated = print_jobs::is_activated(product_sn_record.last_print_job_id, clock::now());
+    if is_genuine && !is_void && !is_revoked && is_activated {
+        challenges::finish_challenge(challenge.id, VerificationChallengeStatus::Verified);
+        let mut result = record_genuine_verification(
+            caller,
+            verifier,
+            guest_token.as_deref(),
+            &product,
+            &product_sn_record,
+            country_code,
+            request.locale.as_deref(),
+        );
+        result.rate_limit = rate_limit.clone();
+        return ApiResponse::success(ChallengeVerificationResponse {
+            status: VerificationChallengeStatus::Verified,
+            result: Some(result),
+        })
+        .with_rate_limit(rate_limit);
+    }
+
+    // A wrong answer still uses up the nonce
+    let (status, verification_status) = if is_genuine && is_void {
+        (VerificationChallengeStatus::VoidedSerial, ProductVerificationStatus::VoidedSerial)
+    } else if is_genuine && is_revoked {
+        analytics::on_revoked_code_scan(product.org_id);
+        (VerificationChallengeStatus::CodeRevoked, ProductVerificationStatus::CodeRevoked)
+    } else if is_genuine {
+        // Not activated yet; like verify_product_v2 this fires no rules
+        challenges::finish_challenge(challenge.id, VerificationChallengeStatus::NotYetActive);
+        return ApiResponse::success(ChallengeVerificationResponse {
+            status: VerificationChallengeStatus::NotYetActive,
+            result: None,
+        })
+        .with_rate_limit(rate_limit);
+    } else {
+        if !product.is_test.unwrap_or(false) {
+            heatmap::on_invalid_scan(product.org_id, clock::now());
+            live_events::publish(
+                product.org_id,
+                LiveTopic::Counterfeits,
+                LiveEvent::Counterfeit {
+                    signal: CounterfeitSignal::InvalidCode,
+                    product_id,
+                    serial_no: challenge.serial_no,
+                    country_code: country_code.clone(),
+                    detected_at: clock::now(),
+                },
+            );
         }
-    })
+        (VerificationChallengeStatus::InvalidResponse, ProductVerificationStatus::Invalid)
+    };
+    challenges::finish_challenge(challenge.id, status);
+    webhooks::evaluate_verification_rules(product.org_id, product_id, challenge.serial_no, &verification_status, 0);
+    ApiResponse::success(ChallengeVerificationResponse { status, result: None }).with_rate_limit(rate_limit)
 }
 
-// ===== Configuration Endpoints (Admin Only) =====
+// Upper bound on items in a single batch verification call
+const MAX_BATCH_VERIFICATION_ITEMS: usize = 500;
 
+// Bulk check of unique codes for warehouse and store audits. Unlike verify_product_v2 this records no
+// verifications, grants no rewards and fires no webhooks; the whole batch is charged as one rate-limit attempt.
 #[update]
-pub fn set_openai_api_key(key: String) -> ApiResponse<()> {
-    // Ensure caller is admin
-    if let Err(e) = ensure_admin(api::caller()) {
+pub fn verify_products_batch_v2(items: Vec<BatchVerificationItem>) -> ApiResponse<BatchVerificationResponse> {
+    let _metrics = endpoint_metrics::track("verify_products_batch_v2");
+    let caller = api::caller();
+
+    let user = match USERS.with(|users| users.borrow().get(&caller)) {
+        Some(user) => user,
+        None => return ApiResponse::error(ApiError::unauthorized("User not registered")),
+    };
+    if let Err(e) = ensure_enabled(&user) {
         return ApiResponse::error(e);
     }
-    
-    if key.trim().is_empty() {
-        return ApiResponse::error(ApiError::invalid_input("OpenAI API key cannot be empty"));
+    if !matches!(user.user_role, Some(UserRole::Admin) | Some(UserRole::BrandOwner) | Some(UserRole::Reseller)) {
+        return ApiResponse::error(ApiError::unauthorized("Batch verification is restricted to brand owners and resellers"));
     }
 
-    // Wrap the String in StorableString before setting
-    match CONFIG_OPENAI_API_KEY.with(|cell| cell.borrow_mut().set(StorableString(key))) {
-        Ok(_) => ApiResponse::success(()),
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to set OpenAI API Key: {:?}", e));
-            ApiResponse::error(ApiError::internal_error("Failed to update configuration"))
+    if items.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one item is required"));
+    }
+    if items.len() > MAX_BATCH_VERIFICATION_ITEMS {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A batch can contain at most {} items",
+            MAX_BATCH_VERIFICATION_ITEMS
+        )));
+    }
+
+    let rate_limit = match rate_limiter::record_batch_verification_attempt(caller) {
+        Ok(info) => info,
+        Err(e) => return ApiResponse::error(e).with_rate_limit(rate_limiter::check_rate_limit(caller, api::id()).ok()),
+    };
+
+    // Resolve every requested serial through the serial index
+    let requested: std::collections::HashSet<Principal> = items.iter().map(|item| item.serial_no).collect();
+    let serials: std::collections::HashMap<Principal, ProductSerialNumber> = requested
+        .iter()
+        .filter_map(|serial_no| serial_store::find(*serial_no).map(|(_, sn)| (*serial_no, sn)))
+        .collect();
+
+    let mut products: std::collections::HashMap<Principal, Product> = std::collections::HashMap::new();
+    let mut summary = BatchVerificationSummary::default();
+    let mut results = Vec::with_capacity(items.len());
+
+    for item in items {
+        let (product_id, status, message) = match serials.get(&item.serial_no) {
+            None => (None, BatchVerificationItemStatus::Invalid, Some("Serial number not found".to_string())),
+            Some(sn) => {
+                let product = products
+                    .entry(sn.product_id)
+                    .or_insert_with(|| get_product(&sn.product_id).unwrap_or_default());
+                match verify_unique_code_signature(product, sn.serial_no, sn.print_version, &item.unique_code) {
+                    Err(e) => (Some(sn.product_id), BatchVerificationItemStatus::Invalid, Some(format!("{:?}", e))),
+                    Ok(false) => (Some(sn.product_id), BatchVerificationItemStatus::Invalid, None),
+                    Ok(true) if sn.effective_status() == SerialNumberStatus::Void => {
+                        (Some(sn.product_id), BatchVerificationItemStatus::Voided, None)
+                    }
+                    Ok(true) if sn.revocation_for(sn.print_version).is_some() => {
+                        (Some(sn.product_id), BatchVerificationItemStatus::Revoked, None)
+                    }
+                    Ok(true) if sn.suspected_cloned.unwrap_or(false) => (
+                        Some(sn.product_id),
+                        BatchVerificationItemStatus::SuspectedClone,
+                        Some(SUSPECTED_CLONE_WARNING.to_string()),
+                    ),
+                    Ok(true) => (Some(sn.product_id), BatchVerificationItemStatus::Genuine, None),
+                }
+            }
+        };
+
+        summary.total += 1;
+        match status {
+            BatchVerificationItemStatus::Genuine => summary.genuine += 1,
+            BatchVerificationItemStatus::Invalid => summary.invalid += 1,
+            BatchVerificationItemStatus::Voided
+            | BatchVerificationItemStatus::Revoked
+            | BatchVerificationItemStatus::SuspectedClone => summary.suspect += 1,
         }
+        results.push(BatchVerificationItemResult {
+            serial_no: item.serial_no,
+            product_id,
+            status,
+            message,
+        });
     }
+
+    ApiResponse::success(BatchVerificationResponse { results, summary, rate_limit })
 }
 
-#[query]
-pub fn get_openai_api_key() -> ApiResponse<String> {
-    // Ensure caller is admin
-    if let Err(e) = ensure_admin(api::caller()) {
-        return ApiResponse::error(e);
+// Issue a short-lived guest session so anonymous consumers can verify without signing in
+#[update]
+pub fn start_guest_session() -> ApiResponse<GuestSessionResponse> {
+    let _metrics = endpoint_metrics::track("start_guest_session");
+    match guest_sessions::start_session() {
+        Ok((token, session)) => ApiResponse::success(GuestSessionResponse {
+            token,
+            session_id: session.id,
+            expires_at: session.expires_at,
+            max_verifications: guest_sessions::MAX_VERIFICATIONS_PER_GUEST_SESSION as u32,
+        }),
+        Err(e) => ApiResponse::error(e),
     }
-
-    // Get the StorableString, access the inner String with .0, then clone it
-    let storable_string = CONFIG_OPENAI_API_KEY.with(|cell| cell.borrow().get().clone());
-    ApiResponse::success(storable_string.0) // Return the inner String
 }
 
+// Move a guest session's verifications to the signed-in caller and credit the deferred rewards
 #[update]
-pub fn set_scraper_url(url: String) -> ApiResponse<()> {
-    // Ensure caller is admin
-    if let Err(e) = ensure_admin(api::caller()) {
+pub fn claim_guest_verifications(token: String) -> ApiResponse<ClaimGuestVerificationsResponse> {
+    let _metrics = endpoint_metrics::track("claim_guest_verifications");
+    let caller = api::caller();
+    if caller == Principal::anonymous() {
+        return ApiResponse::error(ApiError::unauthorized("Sign in to claim guest verifications"));
+    }
+    if let Err(e) = ensure_caller_enabled(caller) {
         return ApiResponse::error(e);
     }
-    
-    if url.trim().is_empty() {
-        return ApiResponse::error(ApiError::invalid_input("Scraper URL cannot be empty"));
+    // Claiming credits the session's deferred rewards
+    if let Err(e) = terms::ensure_terms_accepted(caller) {
+        return ApiResponse::error(e);
     }
-    // Basic URL validation might be added here (e.g., check for http/https)
 
-    // Wrap the String in StorableString before setting
-    match CONFIG_SCRAPER_URL.with(|cell| cell.borrow_mut().set(StorableString(url))) {
-        Ok(_) => ApiResponse::success(()),
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to set Scraper URL: {:?}", e));
-            ApiResponse::error(ApiError::internal_error("Failed to update configuration"))
-        }
+    match guest_sessions::claim(&token, caller) {
+        Ok(session) => ApiResponse::success(transfer_guest_verifications(&session, caller)),
+        Err(e) => ApiResponse::error(e),
     }
 }
 
-#[query]
-pub fn get_scraper_url() -> ApiResponse<String> {
-    // Ensure caller is admin
-    if let Err(e) = ensure_admin(api::caller()) {
+// Attach the verification history of a guest session to the caller's newly registered account
+#[update]
+pub fn link_verification_history(claim_token: String) -> ApiResponse<ClaimGuestVerificationsResponse> {
+    let _metrics = endpoint_metrics::track("link_verification_history");
+    let caller = api::caller();
+    if caller == Principal::anonymous() {
+        return ApiResponse::error(ApiError::unauthorized("Sign in to link verification history"));
+    }
+    if let Err(e) = ensure_caller_enabled(caller) {
+        return ApiResponse::error(e);
+    }
+    if USERS.with(|users| users.borrow().get(&caller)).is_none() {
+        return ApiResponse::error(ApiError::unauthorized("Register an account before linking verification history"));
+    }
+    if let Err(e) = terms::ensure_terms_accepted(caller) {
         return ApiResponse::error(e);
     }
 
-    // Get the StorableString, access the inner String with .0, then clone it
-    let storable_string = CONFIG_SCRAPER_URL.with(|cell| cell.borrow().get().clone());
-    ApiResponse::success(storable_string.0) // Return the inner String
+    match guest_sessions::claim(&claim_token, caller) {
+        Ok(session) => ApiResponse::success(transfer_guest_verifications(&session, caller)),
+        Err(e) => ApiResponse::error(e),
+    }
 }
 
-#[query]
-pub fn list_product_verifications_by_org_id(org_id: Principal) -> Vec<ProductVerificationDetail> {
-    // Check for read product permission within the organization
-    let authorization_result =
-        authorize_for_organization(api::caller(), org_id, Permission::ReadProduct);
-    if authorization_result.is_err() {
-        ic_cdk::print(format!(
+// Re-attribute every verification recorded by the guest session to `user_id`, replace the session's
+// verified-product history with the user's and credit the rewards that were deferred at scan time.
+// Nothing here awaits, so the whole transfer commits or traps as one message.
+fn transfer_guest_verifications(session: &GuestSession, user_id: Principal) -> ClaimGuestVerificationsResponse {
+    rewards::take_verified_products(session.id);
+
+    let mut claimed_verifications = 0u32;
+    let mut points_awarded = 0u32;
+
+    for guest_ref in &session.verifications {
+        let product_id = guest_ref.product_id;
+        // Status is re-evaluated for the user, who may already have verified this product
+        let status = if rewards::is_first_verification_for_user(user_id, product_id) {
+            ProductVerificationStatus::FirstVerification
+        } else {
+            ProductVerificationStatus::MultipleVerification
+        };
+
+        let transferred = verification_store::update_first(
+            product_id,
+            |v| v.id == guest_ref.verification_id && v.created_by == session.id,
+            |verification| {
+                verification.created_by = user_id;
+                verification.status = status.clone();
+            },
+        );
+        let transferred = match transferred {
+            Some(verification) => verification,
+            None => continue,
+        };
+        claimed_verifications += 1;
+
+        // Records the product in the user's verified history and credits the points
+        if transferred.is_test == Some(true) {
+            rewards::simulate_verification_rewards(user_id, product_id, &status);
+        } else {
+            points_awarded += rewards::calculate_verification_rewards(user_id, product_id, &status).points;
+        }
+    }
+
+    if points_awarded > 0 {
+        notifications::notify_user(
+            user_id,
+            UserNotificationKind::RewardGranted,
+            "Guest verifications claimed",
+            format!("You earned {} points for {} verification(s) made as a guest.", points_awarded, claimed_verifications),
+            Some(session.id),
+        );
+    }
+
+    ClaimGuestVerificationsResponse {
+        claimed_verifications,
+        points_awarded,
+    }
+}
+
+// ====== Identity Linking ======
+
+// Issue a one-time code on the account that should be kept; redeem it with complete_identity_link
+// while signed in with the other identity
+#[update]
+pub fn start_identity_link() -> ApiResponse<IdentityLinkCodeResponse> {
+    let _metrics = endpoint_metrics::track("start_identity_link");
+    let caller = api::caller();
+    let user = match find_user_by_caller(caller) {
+        Some(user) => user,
+        None => return ApiResponse::error(ApiError::unauthorized("Register an account before linking identities")),
+    };
+    if let Err(e) = ensure_enabled(&user) {
+        return ApiResponse::error(e);
+    }
+    let (code, record) = identity_links::issue_code(user.id, clock::now());
+    ApiResponse::success(IdentityLinkCodeResponse { code, expires_at: record.expires_at })
+}
+
+// Link the caller to the account that issued the code. If the caller has an account of its own,
+// it is merged into that account; see identity_links::merge_accounts for the rules.
+#[update]
+pub fn complete_identity_link(code: String) -> ApiResponse<IdentityLinkResponse> {
+    let _metrics = endpoint_metrics::track("complete_identity_link");
+    let caller = api::caller();
+    if caller == Principal::anonymous() {
+        return ApiResponse::error(ApiError::unauthorized("Sign in with the identity to link"));
+    }
+    let now = clock::now();
+    let record = match identity_links::take_code(&code, now) {
+        Some(record) => record,
+        None => return ApiResponse::error(ApiError::unauthorized("Link code is invalid or has expired")),
+    };
+    let primary = match USERS.with(|users| users.borrow().get(&record.user_id)) {
+        Some(user) => user,
+        None => return ApiResponse::error(ApiError::not_found("The account that issued this code no longer exists")),
+    };
+    if let Err(e) = ensure_enabled(&primary) {
+        return ApiResponse::error(e);
+    }
+    if caller == primary.id || primary.session_keys.contains(&caller) {
+        return ApiResponse::error(ApiError::already_exists("This identity is already linked to the account"));
+    }
+
+    let secondary = USERS.with(|users| users.borrow().get(&caller));
+    let result = match secondary {
+        Some(secondary) => match ensure_enabled(&secondary) {
+            Ok(()) => identity_links::merge_accounts(primary.clone(), secondary, now),
+            Err(e) => Err(e),
+        },
+        // Session keys of another account belong to that account
+        None if find_user_by_caller(caller).is_some() => {
+            Err(ApiError::invalid_input("This identity is linked to another account"))
+        }
+        None => {
+            let mut linked = primary.clone();
+            linked.session_keys.push(caller);
+            linked.updated_at = now;
+            linked.updated_by = caller;
+            USERS.with(|users| users.borrow_mut().insert(linked.id, linked));
+            Ok(IdentityLinkResponse {
+                user_id: primary.id,
+                linked_principal: caller,
+                merged_account: false,
+                org_ids_added: 0,
+                points_transferred: 0,
+                verifications_transferred: 0,
+            })
+        }
+    };
+
+    let mut audit_metadata = vec![Metadata { key: "linked_principal".to_string(), value: caller.to_string() }];
+    if let Ok(linked) = &result {
+        audit_metadata.push(Metadata { key: "merged_account".to_string(), value: linked.merged_account.to_string() });
+    }
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "LinkIdentity".to_string(),
+        resource_type: "User".to_string(),
+        resource_id: primary.id,
+        timestamp: now,
+        metadata: audit_metadata,
+        success: result.is_ok(),
+    });
+
+    match result {
+        Ok(linked) => {
+            log_info!("[complete_identity_link] Linked {} to user {}", caller, primary.id);
+            ApiResponse::success(linked)
+        }
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+const SUSPECTED_CLONE_WARNING: &str = "This serial number has been scanned by an unusually high number of different users and may be a cloned label.";
+
+// Flags the serial as suspected cloned the first time its distinct scanners exceed the org's
+// threshold and notifies the brand. Returns the warning to attach to the verification response.
+fn check_duplicate_scan_threshold(
+    org_id: Principal,
+    product_id: Principal,
+    serial: &ProductSerialNumber,
+    distinct_scanners: u32,
+) -> Option<String> {
+    if serial.suspected_cloned == Some(true) {
+        return Some(SUSPECTED_CLONE_WARNING.to_string());
+    }
+
+    if distinct_scanners <= org_settings::duplicate_scan_threshold(org_id) {
+        return None;
+    }
+
+    serial_store::update(product_id, serial.serial_no, |sn| {
+        sn.suspected_cloned = Some(true);
+        sn.updated_at = clock::now();
+    });
+    log_warn!("[check_duplicate_scan_threshold] Serial {} of product {} flagged as suspected cloned ({} distinct scanners)", serial.serial_no, product_id, distinct_scanners);
+
+    webhooks::notify_suspected_clone(org_id, product_id, serial.serial_no, distinct_scanners);
+    live_events::publish(
+        org_id,
+        LiveTopic::Counterfeits,
+        LiveEvent::Counterfeit {
+            signal: CounterfeitSignal::SuspectedClone,
+            product_id,
+            serial_no: serial.serial_no,
+            country_code: None,
+            detected_at: clock::now(),
+        },
+    );
+    Some(SUSPECTED_CLONE_WARNING.to_string())
+}
+
+#[query]
+pub fn get_verification_rate_limit(product_id: Principal) -> ApiResponse<RateLimitInfo> {
+    let caller = api::caller();
+    
+    match rate_limiter::check_rate_limit(caller, product_id) {
+        Ok(rate_limit_info) => ApiResponse::success(rate_limit_info),
+        Err(error) => ApiResponse::error(error),
+    }
+}
+
+#[update]
+pub fn list_organizations_v2(request: FindOrganizationsRequest) -> ApiResponse<OrganizationsListResponse> {
+    let _metrics = endpoint_metrics::track("list_organizations_v2");
+    let filter = request.name.trim().to_lowercase();
+    let caller = api::caller();
+
+    // Get user to check role and permissions
+    let user_opt = USERS.with(|users| users.borrow().get(&caller));
+
+    // Check if user exists
+    if user_opt.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User not found"));
+    }
+
+    let user = user_opt.unwrap();
+
+    // Check if user has a role
+    if user.user_role.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User has no role assigned"));
+    }
+
+    let role = user.user_role.unwrap();
+
+    ORGANIZATIONS.with(|orgs| {
+        let orgs_borrow = orgs.borrow();
+        
+        // Filter organizations based on name and user's permissions
+        let filtered_orgs: Vec<OrganizationPublic> = if matches!(role, UserRole::Admin) {
+            // Admin can see all organizations matching the filter
+            orgs_borrow
+                .iter()
+                .filter(|(_, org)| org.name.to_lowercase().contains(&filter))
+                .map(|(_, org)| OrganizationPublic::from(org.clone()))
+                .collect()
+        } else {
+            // Non-admin users can only see organizations they belong to
+            orgs_borrow
+                .iter()
+                .filter(|(org_id, org)| {
+                    org.name.to_lowercase().contains(&filter) && user.org_ids.contains(org_id)
+                })
+                .map(|(_, org)| OrganizationPublic::from(org.clone()))
+                .collect()
+        };
+        
+        // Apply pagination if requested
+        let pagination_request = request.pagination.unwrap_or_default();
+        let (paginated_orgs, pagination) = match paginate_by_key(filtered_orgs, &pagination_request, |org| org.id) {
+            Ok(page) => page,
+            Err(e) => return ApiResponse::error(e),
+        };
+        
+        // Create the response
+        let response = OrganizationsListResponse {
+            organizations: paginated_orgs,
+            pagination: Some(pagination),
+        };
+        
+        ApiResponse::success(response)
+    })
+}
+
+#[update]
+pub fn create_organization_v2(request: CreateOrganizationRequest) -> ApiResponse<OrganizationResponse> {
+    let _metrics = endpoint_metrics::track("create_organization_v2");
+    // Input validation
+    if request.name.trim().is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("Organization name cannot be empty"));
+    }
+
+    // For creation, we don't need to check existing permissions since this creates a brand new org
+    // However, we should check if the user has a registered account at minimum
+    let caller = api::caller();
+    let user_exists = USERS.with(|users| users.borrow().get(&caller).is_some());
+
+    if !user_exists {
+        // Register the user automatically
+        let register_result = register();
+        if register_result.id == Principal::anonymous() {
+            return ApiResponse::error(ApiError::internal_error("Failed to register user automatically"));
+        }
+    }
+
+    let id = match id_registry::allocate_organization_id() {
+        Ok(id) => id,
+        Err(e) => return ApiResponse::error(e),
+    };
+    
+    let organization = Organization {
+        id,
+        name: request.name,
+        private_key: OrgSigningKey::generate().to_hex(),
+        description: request.description,
+        metadata: request.metadata,
+        created_at: clock::now(),
+        created_by: caller,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+
+    ORGANIZATIONS.with(|orgs| {
+        orgs.borrow_mut().insert(id, organization.clone());
+    });
+    plans::start_free_plan(id, caller);
+    moderation::on_organization_saved(&organization, caller);
+
+    // Add the organization to the user's organizations
+    let add_org_to_user_result = USERS.with(|users| {
+        let mut users_mut = users.borrow_mut();
+        match users_mut.get(&caller) {
+            Some(user) => {
+                let mut updated_user = user.clone();
+                updated_user.org_ids.push(id);
+                updated_user.updated_at = clock::now();
+                users_mut.insert(caller, updated_user);
+                true
+            }
+            None => false,
+        }
+    });
+
+    if !add_org_to_user_result {
+        // This is unlikely but handle it anyway
+        return ApiResponse::error(ApiError::internal_error("Failed to add organization to user"));
+    }
+
+    ApiResponse::success(OrganizationResponse {
+        organization: OrganizationPublic::from(organization),
+    })
+}
+
+#[update]
+pub fn update_organization_v2(request: UpdateOrganizationRequest) -> ApiResponse<OrganizationResponse> {
+    let _metrics = endpoint_metrics::track("update_organization_v2");
+    // Input validation
+    if request.name.trim().is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("Organization name cannot be empty"));
+    }
+
+    // Check that user has write permission for this organization
+    let result = authorize_for_organization(ic_cdk::caller(), request.id, Permission::WriteOrganization);
+    if result.is_err() {
+        return ApiResponse::error(result.err().unwrap());
+    }
+
+    ORGANIZATIONS.with(|orgs| {
+        let mut orgs_mut = orgs.borrow_mut();
+        match orgs_mut.get(&request.id) {
+            Some(org) => {
+                // Create a new organization with updated fields
+                let updated_org = Organization {
+                    name: request.name,
+                    description: request.description,
+                    metadata: request.metadata,
+                    updated_at: clock::now(),
+                    updated_by: api::caller(),
+                    ..org.clone()
+                };
+
+                // Insert the updated organization
+                orgs_mut.insert(request.id, updated_org.clone());
+                moderation::on_organization_saved(&updated_org, api::caller());
+
+                ApiResponse::success(OrganizationResponse {
+                    organization: OrganizationPublic::from(updated_org),
+                })
+            }
+            None => ApiResponse::error(ApiError::not_found(&format!(
+                "Organization with ID {} not found",
+                request.id
+            ))),
+        }
+    })
+}
+
+// ===== Configuration Endpoints (Admin Only) =====
+
+#[update]
+pub fn set_openai_api_key(key: String) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("set_openai_api_key");
+    // Ensure caller is admin
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    
+    if key.trim().is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("OpenAI API key cannot be empty"));
+    }
+
+    let mut canister_config = config::get_config();
+    canister_config.llm.api_key = key;
+    canister_config.updated_by = api::caller();
+    match config::set_config(canister_config) {
+        Ok(_) => ApiResponse::success(()),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[query]
+pub fn get_openai_api_key() -> ApiResponse<String> {
+    // Ensure caller is admin
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(config::llm_config().api_key)
+}
+
+#[update]
+pub fn set_scraper_url(url: String) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("set_scraper_url");
+    // Ensure caller is admin
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    
+    if url.trim().is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("Scraper URL cannot be empty"));
+    }
+    // Basic URL validation might be added here (e.g., check for http/https)
+
+    let mut canister_config = config::get_config();
+    canister_config.scraper_url = url;
+    canister_config.updated_by = api::caller();
+    match config::set_config(canister_config) {
+        Ok(_) => ApiResponse::success(()),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[query]
+pub fn get_scraper_url() -> ApiResponse<String> {
+    // Ensure caller is admin
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(config::scraper_url())
+}
+
+#[query]
+pub fn get_canister_config_v2() -> ApiResponse<CanisterConfig> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(config::redacted_config())
+}
+
+#[update]
+pub fn update_canister_config_v2(request: UpdateCanisterConfigRequest) -> ApiResponse<CanisterConfig> {
+    let _metrics = endpoint_metrics::track("update_canister_config_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+
+    let mut flags = Vec::new();
+    for flag in request.feature_flags.unwrap_or_default() {
+        match feature_flags::normalize_flag_name(&flag.name) {
+            Ok(name) => flags.push((name, flag.enabled)),
+            Err(e) => return ApiResponse::error(e),
+        }
+    }
+
+    let mut canister_config = config::get_config();
+
+    if let Some(provider) = request.llm_provider {
+        canister_config.llm.provider = provider;
+    }
+    if let Some(api_host) = request.llm_api_host {
+        if api_host.trim().is_empty() || api_host.contains('/') {
+            return ApiResponse::error(ApiError::invalid_input("LLM API host must be a bare host name"));
+        }
+        canister_config.llm.api_host = api_host;
+    }
+    if let Some(model) = request.llm_model {
+        canister_config.llm.model = model;
+    }
+    // Ignore the redaction placeholder so a config read back from the getter does not wipe the key
+    if let Some(api_key) = request.llm_api_key.filter(|key| key != config::REDACTED_SECRET) {
+        canister_config.llm.api_key = api_key;
+    }
+    if let Some(scraper_url) = request.scraper_url {
+        if !scraper_url.is_empty() && !scraper_url.starts_with("https://") {
+            return ApiResponse::error(ApiError::invalid_input("Scraper URL must start with https://"));
+        }
+        canister_config.scraper_url = scraper_url;
+    }
+    if request.ledger_canister_id.is_some() {
+        canister_config.ledger_canister_id = request.ledger_canister_id;
+    }
+    if let Some(email_relay_url) = request.email_relay_url {
+        if !email_relay_url.is_empty() && !email_relay_url.starts_with("https://") {
+            return ApiResponse::error(ApiError::invalid_input("Email relay URL must start with https://"));
+        }
+        canister_config.email_relay_url = Some(email_relay_url).filter(|url| !url.is_empty());
+    }
+    if let Some(rate_limit) = request.rate_limit {
+        if rate_limit.max_attempts_per_window == 0 || rate_limit.window_duration_seconds == 0 {
+            return ApiResponse::error(ApiError::invalid_input("Rate limit attempts and window must be greater than zero"));
+        }
+        canister_config.rate_limit = rate_limit;
+    }
+    if let Some(policy) = request.client_version_policy {
+        if policy.min_version.trim().is_empty() {
+            canister_config.client_version_policy = None;
+        } else if let Err(e) = client_version::validate_policy(&policy) {
+            return ApiResponse::error(e);
+        } else {
+            canister_config.client_version_policy = Some(policy);
+        }
+    }
+    if let Some(mut alerts) = request.cycles_alerts {
+        alerts.webhook_url = alerts.webhook_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+        if alerts.critical_threshold >= alerts.warning_threshold {
+            return ApiResponse::error(ApiError::invalid_input("Critical cycles threshold must be below the warning threshold"));
+        }
+        if alerts.webhook_url.as_ref().is_some_and(|url| !url.starts_with("https://")) {
+            return ApiResponse::error(ApiError::invalid_input("Cycles alert webhook URL must start with https://"));
+        }
+        canister_config.cycles_alerts = Some(alerts);
+    }
+    if let Some(limits) = request.outcall_limits {
+        if let Err(e) = outcall_transforms::validate_limits(&limits) {
+            return ApiResponse::error(e);
+        }
+        canister_config.outcall_limits = Some(limits);
+    }
+    if let Some(moderation) = request.moderation {
+        match moderation::normalize_config(moderation) {
+            Ok(moderation) => canister_config.moderation = Some(moderation),
+            Err(e) => return ApiResponse::error(e),
+        }
+    }
+    if let Some(mut gateways) = request.live_gateways {
+        if gateways.contains(&Principal::anonymous()) {
+            return ApiResponse::error(ApiError::invalid_input("The anonymous principal cannot be a live gateway"));
+        }
+        gateways.sort();
+        gateways.dedup();
+        canister_config.live_gateways = Some(gateways);
+    }
+    canister_config.updated_by = caller;
+
+    if let Err(e) = config::set_config(canister_config) {
+        return ApiResponse::error(e);
+    }
+    for (name, enabled) in flags {
+        feature_flags::set_global_flag(&name, enabled);
+    }
+    log_info!("[update_canister_config_v2] Canister config updated by {}", caller);
+
+    ApiResponse::success(config::redacted_config())
+}
+
+// Verifications ordered by product and then by the order they were recorded in, a page at a time. With a
+// serial_number filter a page can come back short with has_more set; keep following next_cursor.
+#[query]
+pub fn list_product_verifications_v2(request: ListProductVerificationsRequest) -> ApiResponse<ProductVerificationsListResponse> {
+    let product_ids = match listing_product_ids(api::caller(), request.organization_id, request.product_id) {
+        Ok(ids) => ids,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let pagination = request.pagination.unwrap_or_default();
+    let limit = page_limit(&pagination);
+    let (after, page, skip) = match pagination.cursor.as_deref() {
+        Some(cursor) => match decode_cursor::<verification_store::VerificationKey>(cursor) {
+            Ok(key) => (Some(key), 0, 0),
+            Err(e) => return ApiResponse::error(e),
+        },
+        None => {
+            let page = pagination.page.unwrap_or(1).max(1);
+            (None, page, (page as usize - 1).saturating_mul(limit as usize))
+        }
+    };
+
+    let (mut verifications, resume_after) = verification_store::scan_products(
+        &product_ids,
+        after,
+        skip.saturating_add(limit as usize),
+        ORG_VERIFICATIONS_MAX_SCANNED,
+        |v| request.serial_number.is_none_or(|serial_no| v.serial_no == serial_no),
+    );
+    let page_items = verifications.split_off(skip.min(verifications.len()));
+    ApiResponse::success(ProductVerificationsListResponse {
+        verifications: page_items,
+        pagination: Some(PaginationResponse {
+            page,
+            limit,
+            total: 0,
+            has_more: resume_after.is_some(),
+            next_cursor: resume_after.map(|key| encode_cursor(&key)),
+        }),
+    })
+}
+
+// Deprecated: loads and sorts every verification of the organization; use list_org_verifications_v2
+#[query]
+pub fn list_product_verifications_by_org_id(org_id: Principal) -> Vec<ProductVerificationDetail> {
+    // Check for read product permission within the organization
+    let authorization_result =
+        authorize_for_organization(api::caller(), org_id, Permission::ReadProduct);
+    if authorization_result.is_err() {
+        log_warn!(
             "Authorization failed for listing verifications in org {}: {:?}",
             org_id,
             authorization_result.err()
-        ));
+        );
         return vec![];
     }
 
-    // Get product IDs for the organization
-    let products_in_org = PRODUCTS.with(|products| {
-        products
-            .borrow()
-            .iter()
-            .filter(|(_, product)| product.org_id == org_id)
-            .map(|(id, product)| (id, product.clone())) // Keep both ID and product
-            .collect::<Vec<(Principal, Product)>>()
+    // Get product IDs for the organization
+    let products_in_org = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, product)| (id, product.clone())) // Keep both ID and product
+            .collect::<Vec<(Principal, Product)>>()
+    });
+
+    let mut all_verification_details = Vec::new();
+
+    // Pre-fetch user emails into a HashMap to avoid multiple reads inside the loop
+    let user_emails: std::collections::HashMap<Principal, Option<String>> = USERS.with(|users_store| {
+        users_store
+            .borrow()
+            .iter()
+            .map(|(id, user)| (id, user.email.clone()))
+            .collect()
+    });
+
+    for (product_id, product) in products_in_org {
+        for verification in verification_store::list_for_product(product_id) {
+            // Find the user who created the verification using the pre-fetched map
+            // .cloned() on Option<&V> (where V=Option<String>) gives Option<Option<String>>
+            // .flatten() on Option<Option<String>> gives Option<String>
+            let user_email = user_emails.get(&verification.created_by).cloned().flatten();
+
+            let detail = ProductVerificationDetail {
+                user_email,
+                product_id: verification.product_id,
+                product_name: product.name.clone(), // Use product name from fetched products
+                serial_no: verification.serial_no,
+                created_at: verification.created_at,
+                status: verification.status.clone(), // Populate the new status field
+            };
+            all_verification_details.push(detail);
+        }
+    }
+
+    // Optionally sort the results, e.g., by creation date descending
+    all_verification_details.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+    all_verification_details
+}
+
+const ORG_VERIFICATIONS_DEFAULT_LIMIT: u32 = 20;
+const ORG_VERIFICATIONS_MAX_LIMIT: u32 = 100;
+// Records looked at per call, so a page with selective filters stays within the query's instruction limit
+const ORG_VERIFICATIONS_MAX_SCANNED: usize = 5_000;
+
+// The organization's verifications ordered by product and then by the order they were recorded in,
+// walked a chunk at a time from the verification store
+#[query]
+pub fn list_org_verifications_v2(request: ListOrgVerificationsRequest) -> ApiResponse<OrgVerificationsResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    let filters = request.filters.unwrap_or_default();
+    if let (Some(from), Some(to)) = (filters.from, filters.to) {
+        if from >= to {
+            return ApiResponse::error(ApiError::invalid_input("'from' must be before 'to'"));
+        }
+    }
+    let email_domain = filters
+        .user_email_domain
+        .as_deref()
+        .map(|domain| domain.trim().trim_start_matches('@').to_lowercase())
+        .filter(|domain| !domain.is_empty());
+    let after: Option<verification_store::VerificationKey> = match request.cursor.as_deref() {
+        Some(cursor) => match decode_cursor(cursor) {
+            Ok(key) => Some(key),
+            Err(e) => return ApiResponse::error(e),
+        },
+        None => None,
+    };
+    let limit = request
+        .limit
+        .unwrap_or(ORG_VERIFICATIONS_DEFAULT_LIMIT)
+        .clamp(1, ORG_VERIFICATIONS_MAX_LIMIT) as usize;
+
+    let product_names: std::collections::HashMap<Principal, String> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == request.org_id)
+            .map(|(id, product)| (id, product.name))
+            .collect()
+    });
+    let product_ids: Vec<Principal> = match &filters.product_ids {
+        Some(wanted) => wanted.iter().copied().filter(|id| product_names.contains_key(id)).collect(),
+        None => product_names.keys().copied().collect(),
+    };
+
+    // Emails are read per verifier as records come up, and only when a page needs them
+    let mut user_emails: std::collections::HashMap<Principal, Option<String>> = std::collections::HashMap::new();
+    let mut email_of = |user_id: Principal| -> Option<String> {
+        user_emails
+            .entry(user_id)
+            .or_insert_with(|| USERS.with(|users| users.borrow().get(&user_id).and_then(|user| user.email)))
+            .clone()
+    };
+
+    let (verifications, resume_after) = verification_store::scan_products(
+        &product_ids,
+        after,
+        limit,
+        ORG_VERIFICATIONS_MAX_SCANNED,
+        |verification| {
+            filters.statuses.as_ref().is_none_or(|statuses| statuses.contains(&verification.status))
+                && filters.from.is_none_or(|from| verification.created_at >= from)
+                && filters.to.is_none_or(|to| verification.created_at < to)
+                && email_domain.as_ref().is_none_or(|domain| {
+                    email_of(verification.created_by)
+                        .and_then(|email| email.rsplit_once('@').map(|(_, d)| d.to_lowercase()))
+                        .is_some_and(|d| d == *domain)
+                })
+        },
+    );
+
+    let verifications = verifications
+        .into_iter()
+        .map(|verification| ProductVerificationDetail {
+            user_email: email_of(verification.created_by),
+            product_id: verification.product_id,
+            product_name: product_names.get(&verification.product_id).cloned().unwrap_or_default(),
+            serial_no: verification.serial_no,
+            created_at: verification.created_at,
+            status: verification.status,
+        })
+        .collect();
+
+    ApiResponse::success(OrgVerificationsResponse {
+        verifications,
+        has_more: resume_after.is_some(),
+        next_cursor: resume_after.map(|key| encode_cursor(&key)),
+    })
+}
+
+// Deprecated: a storage reset needs the approval of several admins. Propose a ResetStorage operation
+// with propose_admin_operation_v2; it runs once enough admins approved it.
+#[update]
+pub fn reset_all_stable_storage() -> ApiResponse<ResetStorageResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::error(ApiError::invalid_input(
+        "Storage is no longer reset directly. Propose a ResetStorage operation with propose_admin_operation_v2",
+    ))
+}
+
+// Wipes every store. Only run as an approved ResetStorage admin operation.
+fn reset_all_storage() -> Result<(), ApiError> {
+    log_warn!("Resetting all stable storage initiated.");
+
+    // Clear StableBTreeMaps by iterating and removing
+    ORGANIZATIONS.with(|orgs| {
+        let mut orgs_mut = orgs.borrow_mut();
+        let keys: Vec<_> = orgs_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            orgs_mut.remove(&key);
+        }
+    });
+    PRODUCTS.with(|prods| {
+        let mut prods_mut = prods.borrow_mut();
+        let keys: Vec<_> = prods_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            prods_mut.remove(&key);
+        }
+    });
+    USERS.with(|users| {
+        let mut users_mut = users.borrow_mut();
+        let keys: Vec<_> = users_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            users_mut.remove(&key);
+        }
+    });
+    RESELLERS.with(|resellers| {
+        let mut resellers_mut = resellers.borrow_mut();
+        let keys: Vec<_> = resellers_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            resellers_mut.remove(&key);
+        }
+    });
+    PRODUCT_SERIAL_NUMBERS.with(|sns| {
+        let mut sns_mut = sns.borrow_mut();
+        let keys: Vec<_> = sns_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            sns_mut.remove(&key);
+        }
+    });
+    serial_store::reset_serial_records();
+    PRODUCT_VERIFICATIONS.with(|vers| {
+        let mut vers_mut = vers.borrow_mut();
+        let keys: Vec<_> = vers_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            vers_mut.remove(&key);
+        }
+    });
+    verification_store::reset_verification_records();
+
+    // Clear StableCells by setting them to default
+    match CONFIG_OPENAI_API_KEY.with(|cell| cell.borrow_mut().set(StorableString::default())) {
+        Ok(_) => log_info!("Cleared OpenAI API Key config."),
+        Err(e) => {
+            log_error!("Failed to reset OpenAI API Key config: {:?}", e);
+            return Err(ApiError::internal_error("Failed to reset OpenAI key config"));
+        }
+    }
+    match CONFIG_SCRAPER_URL.with(|cell| cell.borrow_mut().set(StorableString::default())) {
+        Ok(_) => log_info!("Cleared Scraper URL config."),
+        Err(e) => {
+            log_error!("Failed to reset Scraper URL config: {:?}", e);
+            return Err(ApiError::internal_error("Failed to reset scraper URL config"));
+        }
+    }
+
+    // Consider clearing rate limiter and rewards storage if they use stable memory too
+    rate_limiter::reset_rate_limits();
+    rewards::reset_rewards_storage();
+    webhooks::reset_notification_rules();
+    org_settings::reset_org_settings();
+    reports::reset_reports_storage();
+    print_jobs::reset_print_jobs();
+    config::reset_canister_config();
+    feature_flags::reset_feature_flags();
+    quotas::reset_quotas();
+    redemptions::reset_pending_redemptions();
+    notifications::reset_notifications();
+    disputes::reset_disputes();
+    variants::reset_product_variants();
+    reseller_codes::reset_consumed_codes();
+    analytics::reset_org_analytics();
+    directory::reset_directory();
+    guest_sessions::reset_guest_sessions();
+    verification_messages::reset_verification_messages();
+    integrity::reset_quarantine();
+    bi_export::reset_bi_exports();
+    catalog::reset_product_counters();
+    challenges::reset_challenges();
+    wallets::reset_wallet_books();
+    reward_pools::reset_reward_pools();
+    reseller_products::reset_authorized_products();
+    reseller_applications::reset_reseller_applications();
+    reseller_email::reset_email_confirmations();
+    label_templates::reset_label_templates();
+    federation::reset_delegations();
+    coupons::reset_coupon_codes();
+    outbox::reset_outbox();
+    terms::reset_terms();
+    key_escrow::reset_key_escrow_requests();
+    heatmap::reset_verification_heatmap();
+    bundles::reset_bundles();
+    probe_guard::reset_probe_guard();
+    cycles_monitor::reset_cycles_history();
+    metadata_schemas::reset_metadata_schemas();
+    readonly_principals::reset_readonly_principals();
+    plans::reset_org_plans();
+    verification_retention::reset_verification_retention();
+    identity_links::reset_identity_link_codes();
+    jobs::reset_jobs();
+    verification_domains::reset_verification_domains();
+    capability_tokens::reset_capability_tokens();
+    owned_products::reset_owned_products();
+    recall_campaigns::reset_recall_campaigns();
+    status_messages::reset_status_messages();
+    endpoint_metrics::reset_endpoint_metrics();
+    print_history::reset_print_history();
+    circuit_breakers::reset_circuit_breakers();
+    moderation::reset_moderation_cases();
+    public_stats::reset_public_stats();
+    approvals::reset_admin_operations();
+    id_registry::reset_id_registry();
+    audit_log::reset_audit_log();
+
+    log_info!("All stable storage reset successfully.");
+    Ok(())
+}
+
+#[query]
+pub fn check_reseller_verification(org_id: Principal) -> ApiResponse<bool> {
+    let caller = api::caller(); 
+    
+    // Fetch the user based on the caller's principal
+    match USERS.with(|users| users.borrow().get(&caller).clone()) {
+        Some(user) => {
+            // Check if the user has the Reseller role
+            if let Some(UserRole::Reseller) = user.user_role {
+                // Check if the user is associated with the provided organization ID
+                if user.org_ids.contains(&org_id) {
+                    // Reseller role and associated with the correct org
+                    ApiResponse::success(true) 
+                } else {
+                    // Reseller role, but not associated with this org
+                    log_info!("User {} is a Reseller but not associated with org {}", caller, org_id);
+                    ApiResponse::success(false)
+                }
+            } else {
+                // User exists but is not a Reseller
+                log_info!("User {} is not a Reseller.", caller);
+                ApiResponse::success(false)
+            }
+        }
+        None => {
+            // User not found
+            log_info!("User {} not found.", caller);
+            // Return false to align with previous behaviour on user not found.
+            // Alternatively, return an error:
+            // ApiResponse::error(ApiError::not_found("User not found"))
+            ApiResponse::success(false)
+        }
+    }
+}
+
+// ====== Phase 1: Core Authentication & Context ======
+
+#[query]
+pub fn get_available_roles() -> ApiResponse<Vec<UserRole>> {
+    ApiResponse::success(vec![UserRole::BrandOwner, UserRole::Reseller, UserRole::Customer])
+}
+
+#[update]
+pub fn initialize_user_session(selected_role: Option<UserRole>) -> ApiResponse<AuthContextResponse> {
+    let _metrics = endpoint_metrics::track("initialize_user_session");
+    let session_principal = api::caller(); 
+    let user_principal_key = session_principal;
+
+    log_info!("[initialize_user_session] Called by session_principal: {} with role: {:?}", session_principal, selected_role);
+
+    // Corrected AGAIN: Use .clone() on Option<&User> to get Option<User>
+    let user_record_opt = USERS.with(|users| users.borrow().get(&user_principal_key).clone());
+
+    let final_user_state: User = match user_record_opt {
+        Some(mut user) => { // User exists
+            log_info!("[initialize_user_session] Existing user {} found.", user_principal_key);
+            if let Err(e) = ensure_enabled(&user) {
+                return ApiResponse::error(e);
+            }
+            
+            if user.user_role.is_none() {
+                if let Some(role_to_assign) = selected_role {
+                    user.user_role = Some(role_to_assign);
+                    log_info!("[initialize_user_session] Assigned role {:?} to existing user {} who had no role.", role_to_assign, user.id);
+                } else {
+                    // This case should ideally not be hit if frontend always sends a role (including Customer)
+                    log_warn!("[initialize_user_session] Role selection was None for existing user {} who had no role. This is unexpected.", user_principal_key);
+                    return ApiResponse::error(ApiError::invalid_input(
+                        "A role must be selected to complete registration for an unassigned user.",
+                    ));
+                }
+            } else if let Some(new_role_selected) = selected_role {
+                 // User has an existing role, check if the selected role matches
+                 if user.user_role != Some(new_role_selected) {
+                     log_warn!("[initialize_user_session] User {} attempted to change role from {:?} to {:?}", user.id, user.user_role, new_role_selected);
+                     return ApiResponse::error(ApiError::unauthorized(
+                         "User role has already been set and cannot be changed through this flow.",
+                     ));
+                 }
+                 // If roles match, it's fine, proceed to session key update
+                 log_info!("[initialize_user_session] User {} already has role {:?}, which matches selection.", user.id, user.user_role);
+            } else {
+                // User has an existing role, but no role was selected in this session init (e.g. subsequent logins)
+                // This is fine, just proceed with the existing role.
+                log_info!("[initialize_user_session] User {} has existing role {:?}. No new role selected in this session.", user.id, user.user_role);
+            }
+
+            // ALWAYS add the current session_principal to session_keys if not already present
+            if !user.session_keys.contains(&session_principal) {
+                log_info!("[initialize_user_session] Adding session key {} for user {}", session_principal, user.id);
+                user.session_keys.push(session_principal);
+                user.updated_at = clock::now();
+                user.updated_by = session_principal;
+                // Save the updated user record
+                USERS.with(|users| users.borrow_mut().insert(user.id, user.clone()));
+            } else {
+                 log_info!("[initialize_user_session] Session key {} already exists for user {}", session_principal, user.id);
+            }
+            user // Return potentially modified user
+        }
+        None => { // New user
+            log_info!("[initialize_user_session] New user: {}. Creating record.", user_principal_key);
+            match selected_role {
+                Some(role) => {
+                    // Create user with the calling principal as ID and also add it as the first session key
+                    let new_user = User {
+                        id: user_principal_key, // User ID is the principal that called this
+                        user_role: Some(role), // Assign the selected role (e.g., Customer)
+                        session_keys: vec![session_principal], // Always add the session key used for creation
+                        created_by: user_principal_key, // Created by the root identity (same as caller here)
+                        updated_by: session_principal, // Updated by the session identity during this call
+                        ..Default::default()
+                    };
+                    USERS.with(|users| users.borrow_mut().insert(user_principal_key, new_user.clone()));
+                    log_info!("[initialize_user_session] Created new user {} with role {:?} and initial session key {}", user_principal_key, role, session_principal);
+                    new_user
+                }
+                None => {
+                    // This case should ideally not be hit if frontend always sends a role for new users (including Customer)
+                    log_warn!("[initialize_user_session] Role selection was None for new user {}. This is unexpected if FE sends Customer role.", user_principal_key);
+                    return ApiResponse::error(ApiError::invalid_input(
+                        "A role must be selected for new user registration.",
+                    ));
+                }
+            }
+        }
+    };
+
+    // Construct AuthContextResponse using the final helper
+    let auth_context = build_auth_context_response(&final_user_state);
+    ApiResponse::success(auth_context)
+}
+
+// Final version of build_auth_context_response incorporating all phases
+fn build_auth_context_response(user: &User) -> AuthContextResponse {
+    let user_public = UserPublic {
+        id: user.id,
+        first_name: user.first_name.clone(),
+        last_name: user.last_name.clone(),
+        email: user.email.clone(),
+        created_at: user.created_at,
+    };
+
+    let mut brand_owner_details: Option<BrandOwnerContextDetails> = None;
+    if user.user_role == Some(UserRole::BrandOwner) {
+        let mut org_public_list = Vec::new();
+        let mut active_org_public: Option<OrganizationPublic> = None;
+        ORGANIZATIONS.with(|orgs_map| {
+            let orgs_ref = orgs_map.borrow();
+            for org_id_principal in &user.org_ids {
+                if let Some(org_record) = orgs_ref.get(org_id_principal) {
+                    org_public_list.push(OrganizationPublic::from(org_record.clone()));
+                }
+            }
+            if let Some(active_org_id_principal) = user.active_org_id {
+                if let Some(active_org_record) = orgs_ref.get(&active_org_id_principal) {
+                    active_org_public = Some(OrganizationPublic::from(active_org_record.clone()));
+                }
+            }
+        });
+        brand_owner_details = Some(BrandOwnerContextDetails {
+            has_organizations: !org_public_list.is_empty(),
+            organizations: if org_public_list.is_empty() { None } else { Some(org_public_list) },
+            active_organization: active_org_public,
+        });
+    }
+
+    let mut reseller_details_ctx: Option<ResellerContextDetails> = None;
+    if user.user_role == Some(UserRole::Reseller) {
+        if let Some(reseller_record) = get_reseller_by_user_id(user.id) { // Assuming get_reseller_by_user_id exists
+            let associated_org_public = ORGANIZATIONS.with(|orgs_map| {
+                orgs_map.borrow().get(&reseller_record.org_id).map(|org| OrganizationPublic::from(org.clone()))
+            });
+
+            reseller_details_ctx = Some(ResellerContextDetails {
+                is_profile_complete_and_verified: reseller_record.is_verified,
+                associated_organization: associated_org_public,
+                certification_code: reseller_record.certification_code.clone(),
+                certification_timestamp: reseller_record.certification_timestamp,
+                certification_expires_at: org_settings::certification_expiry(&reseller_record),
+            });
+        } else {
+            reseller_details_ctx = Some(ResellerContextDetails {
+                is_profile_complete_and_verified: false,
+                associated_organization: None,
+                certification_code: None,
+                certification_timestamp: None,
+                certification_expires_at: None,
+            });
+        }
+    }
+
+    AuthContextResponse {
+        user: Some(user_public),
+        is_registered: true,
+        role: user.user_role,
+        brand_owner_details,
+        reseller_details: reseller_details_ctx,
+    }
+}
+
+// Final version of get_auth_context
+#[query]
+pub fn get_auth_context() -> ApiResponse<AuthContextResponse> {
+    let caller = api::caller();
+    log_info!("[get_auth_context] Called by: {}", caller);
+
+    match USERS.with(|users| users.borrow().get(&caller).clone()) { // Cloned here
+        Some(user) => {
+            log_info!("[get_auth_context] Found user: {}", user.id);
+            let auth_context = build_auth_context_response(&user);
+            ApiResponse::success(auth_context)
+        }
+        None => {
+            log_info!("[get_auth_context] User not found: {}. Returning not registered.", caller);
+            ApiResponse::success(AuthContextResponse {
+                user: None,
+                is_registered: false,
+                role: None,
+                brand_owner_details: None,
+                reseller_details: None,
+            })
+        }
+    }
+}
+
+// Effective permissions and feature flags of the caller, so frontends show only what the backend allows
+#[query]
+pub fn get_my_capabilities_v2() -> ApiResponse<CapabilitiesResponse> {
+    let user = match find_user_by_caller(api::caller()) {
+        Some(user) => user,
+        None => {
+            return ApiResponse::success(CapabilitiesResponse {
+                is_registered: false,
+                is_enabled: false,
+                role: None,
+                is_admin: false,
+                permissions: Vec::new(),
+                features: feature_flags::effective_flags(None),
+                organizations: Vec::new(),
+            })
+        }
+    };
+
+    let permissions = effective_permissions(&user);
+    let organizations = user
+        .org_ids
+        .iter()
+        .filter(|org_id| ORGANIZATIONS.with(|orgs| orgs.borrow().contains_key(*org_id)))
+        .map(|org_id| OrgCapabilities {
+            org_id: *org_id,
+            permissions: permissions.clone(),
+            features: feature_flags::effective_flags(Some(*org_id)),
+        })
+        .collect();
+    ApiResponse::success(CapabilitiesResponse {
+        is_registered: true,
+        is_enabled: user.is_enabled,
+        is_admin: user.is_enabled && user.user_role == Some(UserRole::Admin),
+        role: user.user_role,
+        permissions,
+        features: feature_flags::effective_flags(None),
+        organizations,
+    })
+}
+
+#[update]
+pub fn logout_user() -> ApiResponse<LogoutResponse> {
+    let _metrics = endpoint_metrics::track("logout_user");
+    let caller = api::caller();
+    log_info!("[logout_user] User {} attempting to log out.", caller);
+    ApiResponse::success(LogoutResponse {
+        message: "Successfully logged out.".to_string(),
+        redirect_url: None, 
+    })
+}
+
+// ====== Phase 2: Brand Owner Flow ======
+
+#[update]
+pub fn create_organization_for_owner(request: CreateOrganizationWithOwnerContextRequest) -> ApiResponse<OrganizationContextResponse> {
+    let _metrics = endpoint_metrics::track("create_organization_for_owner");
+    let caller = api::caller();
+    log_info!("[create_organization_for_owner] Called by: {}", caller);
+
+    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
+    if user_opt.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    }
+    let mut user = user_opt.unwrap();
+
+    if user.user_role != Some(UserRole::BrandOwner) {
+        return ApiResponse::error(ApiError::unauthorized("Only Brand Owners can create organizations."));
+    }
+
+    let org_id = match id_registry::allocate_organization_id() {
+        Ok(id) => id,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let new_organization = Organization {
+        id: org_id,
+        name: request.name,
+        description: request.description,
+        private_key: OrgSigningKey::generate().to_hex(),
+        metadata: request.metadata,
+        created_at: clock::now(),
+        created_by: caller,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+
+    ORGANIZATIONS.with(|orgs| {
+        orgs.borrow_mut().insert(org_id, new_organization.clone());
+    });
+    plans::start_free_plan(org_id, caller);
+    moderation::on_organization_saved(&new_organization, caller);
+    log_info!("[create_organization_for_owner] Organization {} created.", org_id);
+
+    if !user.org_ids.contains(&org_id) {
+        user.org_ids.push(org_id);
+    }
+    user.active_org_id = Some(org_id);
+    user.updated_at = clock::now();
+    user.updated_by = caller;
+
+    USERS.with(|users| {
+        users.borrow_mut().insert(caller, user.clone());
+    });
+    log_info!("[create_organization_for_owner] User {} updated with new org {} and active org set.", caller, org_id);
+
+    let org_public = OrganizationPublic::from(new_organization);
+    let updated_auth_context = build_auth_context_response(&user); 
+
+    ApiResponse::success(OrganizationContextResponse {
+        organization: org_public,
+        user_auth_context: updated_auth_context,
+    })
+}
+
+#[update]
+pub fn select_active_organization(org_id: Principal) -> ApiResponse<AuthContextResponse> {
+    let _metrics = endpoint_metrics::track("select_active_organization");
+    let caller = api::caller();
+    log_info!("[select_active_organization] Called by: {} to select org: {}", caller, org_id);
+
+    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
+    if user_opt.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    }
+    let mut user = user_opt.unwrap();
+
+    if user.user_role != Some(UserRole::BrandOwner) {
+        return ApiResponse::error(ApiError::unauthorized("Only Brand Owners can select an active organization."));
+    }
+
+    if !user.org_ids.contains(&org_id) {
+        return ApiResponse::error(ApiError::unauthorized("User is not associated with this organization."));
+    }
+    
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Organization not found."));
+    }
+
+    user.active_org_id = Some(org_id);
+    user.updated_at = clock::now();
+    user.updated_by = caller;
+
+    USERS.with(|users| {
+        users.borrow_mut().insert(caller, user.clone());
+    });
+    log_info!("[select_active_organization] User {} set active org to {}.", caller, org_id);
+
+    let updated_auth_context = build_auth_context_response(&user); 
+    ApiResponse::success(updated_auth_context)
+}
+
+#[query]
+pub fn get_my_organizations() -> ApiResponse<Vec<OrganizationPublic>> {
+    let caller = api::caller();
+    log_info!("[get_my_organizations] Called by: {}", caller);
+
+    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
+    if user_opt.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    }
+    let user = user_opt.unwrap();
+
+    if user.user_role != Some(UserRole::BrandOwner) {
+        return ApiResponse::error(ApiError::unauthorized("Only Brand Owners can list their organizations."));
+    }
+
+    let mut org_public_list = Vec::new();
+    ORGANIZATIONS.with(|orgs_map| {
+        let orgs_ref = orgs_map.borrow();
+        for org_id_principal in &user.org_ids {
+            if let Some(org_record) = orgs_ref.get(org_id_principal) {
+                org_public_list.push(OrganizationPublic::from(org_record.clone()));
+            }
+        }
+    });
+
+    ApiResponse::success(org_public_list)
+}
+
+// ====== Phase 3: Reseller Flow ======
+
+// Helper to get Reseller record by user_id
+fn get_reseller_by_user_id(user_id_principal: Principal) -> Option<Reseller> {
+    RESELLERS.with(|resellers_map| {
+        resellers_map
+            .borrow()
+            .iter()
+            .find(|(_, reseller_val)| reseller_val.user_id == user_id_principal)
+            .map(|(_, reseller_val)| reseller_val.clone())
+    })
+}
+
+// Validated and normalized reseller contact details
+struct ResellerProfileFields {
+    name: String,
+    contact_email: Option<String>,
+    contact_phone: Option<String>,
+}
+
+fn validate_reseller_profile(
+    reseller_name: &str,
+    contact_email: &Option<String>,
+    contact_phone: &Option<String>,
+    ecommerce_urls: &[Metadata],
+    additional_metadata: &Option<Vec<Metadata>>,
+) -> Result<ResellerProfileFields, ApiError> {
+    let mut validator = Validator::new();
+    let name = validator.required_text("reseller_name", reseller_name, validation::MAX_NAME_LENGTH);
+    let contact_email = contact_email.as_deref().and_then(|email| validator.email("contact_email", email));
+    let contact_phone = contact_phone.as_deref().and_then(|phone| validator.phone("contact_phone", phone));
+    validator.url_metadata("ecommerce_urls", ecommerce_urls);
+    if let Some(additional) = additional_metadata {
+        validator.metadata("additional_metadata", additional);
+    }
+    validator.finish()?;
+
+    Ok(ResellerProfileFields { name, contact_email, contact_phone })
+}
+
+#[update]
+pub fn complete_reseller_profile(request: CompleteResellerProfileRequest) -> ApiResponse<AuthContextResponse> {
+    let _metrics = endpoint_metrics::track("complete_reseller_profile");
+    let caller = api::caller();
+    log_info!("[complete_reseller_profile] Called by: {} for organization {}", caller, request.target_organization_id);
+
+    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
+    if user_opt.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    }
+    let mut user = user_opt.unwrap();
+
+    if user.user_role != Some(UserRole::Reseller) {
+        return ApiResponse::error(ApiError::unauthorized("Only Resellers can complete this profile."));
+    }
+
+    let profile = match validate_reseller_profile(
+        &request.reseller_name,
+        &request.contact_email,
+        &request.contact_phone,
+        &request.ecommerce_urls,
+        &request.additional_metadata,
+    ) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.target_organization_id.clone())).is_none() {
+        return ApiResponse::error(ApiError::not_found("Target organization not found."));
+    }
+
+    let org_opt = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.target_organization_id)).unwrap();
+    let public_key_hex = match keys::org_verifying_key(&org_opt) {
+        Ok(key) => key.to_hex(),
+        Err(e) => {
+            log_error!("Failed to process private key for org {}: {:?}", org_opt.id, e);
+            return ApiResponse::error(e);
+        }
+    };
+    let existing_reseller_opt = get_reseller_by_user_id(caller);
+    let reseller_id = match existing_reseller_opt.as_ref() {
+        Some(r) => r.id,
+        None => match id_registry::allocate_reseller_id() {
+            Ok(id) => id,
+            Err(e) => return ApiResponse::error(e),
+        },
+    };
+    
+    // A reseller already certified by this organization keeps its certification when updating its
+    // profile. Anyone else applies and waits for a brand owner's review.
+    let certified = existing_reseller_opt
+        .as_ref()
+        .filter(|r| r.is_verified && r.org_id == request.target_organization_id);
+    // A contact email stays confirmed only while it is unchanged
+    let email_verified = profile.contact_email.as_ref().map(|email| {
+        existing_reseller_opt
+            .as_ref()
+            .is_some_and(|r| r.contact_email.as_ref() == Some(email) && r.email_verified == Some(true))
+    });
+
+    let reseller_record = Reseller {
+        id: reseller_id,
+        user_id: caller,
+        org_id: request.target_organization_id,
+        name: profile.name,
+        contact_email: profile.contact_email,
+        contact_phone: profile.contact_phone,
+        ecommerce_urls: request.ecommerce_urls,
+        additional_metadata: request.additional_metadata,
+        is_verified: certified.is_some(),
+        certification_code: certified.and_then(|r| r.certification_code.clone()),
+        certification_timestamp: certified.and_then(|r| r.certification_timestamp),
+        certification_expires_at: certified.and_then(|r| r.certification_expires_at),
+        email_verified,
+        created_by: caller,
+        updated_by: caller,
+        date_joined: existing_reseller_opt.as_ref().map_or(clock::now(), |r| r.date_joined),
+        metadata: existing_reseller_opt.as_ref().map_or(Vec::new(), |r| r.metadata.clone()), 
+        public_key: public_key_hex,
+        created_at: existing_reseller_opt.as_ref().map_or(clock::now(), |r| r.created_at),
+        updated_at: clock::now(), 
+    };
+
+    RESELLERS.with(|resellers| {
+        resellers.borrow_mut().insert(reseller_id, reseller_record.clone());
+    });
+    analytics::on_reseller_saved(existing_reseller_opt.as_ref(), &reseller_record);
+    moderation::on_reseller_saved(&reseller_record, caller);
+    log_info!("[complete_reseller_profile] Reseller record {} for user {} processed.", reseller_id, caller);
+
+    if reseller_record.email_verified == Some(false) {
+        // The profile is saved either way; the reseller can ask for a new email later
+        if let Err(e) = reseller_email::send_confirmation(&reseller_record) {
+            log_warn!("[complete_reseller_profile] Confirmation email for reseller {} not sent: {:?}", reseller_id, e);
+        }
+    } else {
+        reseller_email::cancel_confirmation(reseller_id);
+    }
+
+    if certified.is_none() {
+        let pending = reseller_applications::find_pending_for_reseller(reseller_id);
+        match pending {
+            // Resubmitting to the same organization only updates the profile under review
+            Some(application) if application.org_id == request.target_organization_id => {}
+            _ => {
+                if let Some(mut superseded) = pending {
+                    superseded.status = ResellerApplicationStatus::Rejected;
+                    superseded.decided_at = Some(clock::now());
+                    superseded.decided_by = Some(caller);
+                    superseded.rejection_reason = Some("Withdrawn by the applicant".to_string());
+                    reseller_applications::save_application(superseded);
+                }
+                let application = ResellerApplication {
+                    id: generate_unique_principal(reseller_id),
+                    reseller_id,
+                    user_id: caller,
+                    org_id: request.target_organization_id,
+                    status: ResellerApplicationStatus::Pending,
+                    submitted_at: clock::now(),
+                    decided_at: None,
+                    decided_by: None,
+                    rejection_reason: None,
+                };
+                log_info!("[complete_reseller_profile] Application {} of reseller {} to org {} awaits review.", application.id, reseller_id, request.target_organization_id);
+                reseller_applications::save_application(application);
+            }
+        }
+    }
+
+    // Organization access comes with the certification, not with the application
+    user.org_ids = if certified.is_some() { vec![request.target_organization_id] } else { Vec::new() };
+    user.updated_at = clock::now();
+    user.updated_by = caller;
+    USERS.with(|users| {
+        users.borrow_mut().insert(caller, user.clone());
+    });
+    log_info!("[complete_reseller_profile] User {} updated for org {}.", caller, request.target_organization_id);
+
+    // Pre-approved partners skip the manual review
+    if certified.is_none() && certify_if_preapproved(&reseller_record) {
+        user = USERS.with(|users| users.borrow().get(&caller)).unwrap_or(user);
+    }
+
+    let updated_auth_context = build_auth_context_response(&user); 
+    ApiResponse::success(updated_auth_context)
+}
+
+#[query]
+pub fn get_my_reseller_certification() -> ApiResponse<ResellerCertificationPageContext> {
+    let caller = api::caller();
+    log_info!("[get_my_reseller_certification] Called by: {}", caller);
+
+    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
+    if user_opt.is_none() {
+        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    }
+    let user = user_opt.unwrap();
+
+    if user.user_role != Some(UserRole::Reseller) {
+        return ApiResponse::error(ApiError::unauthorized("Only Resellers can access certification details."));
+    }
+
+    let reseller_record_opt = get_reseller_by_user_id(caller);
+    if reseller_record_opt.is_none() || !reseller_record_opt.as_ref().unwrap().is_verified {
+        return ApiResponse::error(ApiError::unauthorized("Reseller profile is not complete or verified."));
+    }
+    let reseller_record = reseller_record_opt.unwrap(); 
+
+    let associated_org_public_opt = ORGANIZATIONS.with(|orgs_map| {
+        orgs_map.borrow().get(&reseller_record.org_id).map(|org| OrganizationPublic::from(org.clone()))
+    });
+    if associated_org_public_opt.is_none() {
+        return ApiResponse::error(ApiError::internal_error("Associated organization not found for reseller."));
+    }
+    let associated_organization = associated_org_public_opt.unwrap();
+
+    let reseller_public = ResellerPublic {
+        id: reseller_record.id,
+        user_id: reseller_record.user_id,
+        organization_id: reseller_record.org_id,
+        name: reseller_record.name.clone(),
+        public_key: reseller_record.public_key.clone(),
+        contact_email: reseller_record.contact_email.clone(),
+        contact_phone: reseller_record.contact_phone.clone(),
+        ecommerce_urls: reseller_record.ecommerce_urls.clone(),
+        additional_metadata: reseller_record.additional_metadata.clone(),
+        is_verified: reseller_record.is_verified,
+        certification_code: reseller_record.certification_code.clone(),
+        certification_timestamp: reseller_record.certification_timestamp,
+        certification_expires_at: org_settings::certification_expiry(&reseller_record),
+        created_at: reseller_record.created_at,
+        updated_at: reseller_record.updated_at,
+    };
+
+    let user_details_public = UserPublic {
+        id: user.id,
+        first_name: user.first_name.clone(),
+        last_name: user.last_name.clone(),
+        email: user.email.clone(),
+        created_at: user.created_at,
+    };
+    
+    if reseller_public.certification_code.is_none() || reseller_public.certification_timestamp.is_none() {
+        log_error!("[get_my_reseller_certification] Missing cert code or timestamp for verified reseller {}", reseller_public.id);
+        return ApiResponse::error(ApiError::internal_error("Certification details missing for verified reseller."));
+    }
+
+    ApiResponse::success(ResellerCertificationPageContext {
+        reseller_profile: reseller_public.clone(),
+        associated_organization,
+        certification_code: reseller_public.certification_code.unwrap(), 
+        certification_timestamp: reseller_public.certification_timestamp.unwrap(), 
+        certification_expires_at: reseller_public.certification_expires_at,
+        user_details: user_details_public,
+    })
+}
+
+#[update]
+pub fn renew_reseller_certification_v2(request: RenewResellerCertificationRequest) -> ApiResponse<AuthContextResponse> {
+    let _metrics = endpoint_metrics::track("renew_reseller_certification_v2");
+    let caller = api::caller();
+    log_info!("[renew_reseller_certification_v2] Called by: {}", caller);
+
+    let user = match USERS.with(|users| users.borrow().get(&caller)) {
+        Some(u) => u,
+        None => return ApiResponse::error(ApiError::unauthorized("User not registered.")),
+    };
+
+    if user.user_role != Some(UserRole::Reseller) {
+        return ApiResponse::error(ApiError::unauthorized("Only Resellers can renew a certification."));
+    }
+
+    let mut reseller = match get_reseller_by_user_id(caller) {
+        Some(r) if r.is_verified && r.certification_code.is_some() => r,
+        _ => return ApiResponse::error(ApiError::invalid_input("Reseller has no certification to renew. Complete the reseller profile first.")),
+    };
+
+    let profile = match validate_reseller_profile(
+        &request.reseller_name,
+        &request.contact_email,
+        &request.contact_phone,
+        &request.ecommerce_urls,
+        &request.additional_metadata,
+    ) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&reseller.org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Associated organization not found for reseller."));
+    }
+
+    // Re-confirmed profile details replace the ones captured at the previous certification
+    let now = clock::now();
+    reseller.name = profile.name;
+    reseller.contact_email = profile.contact_email;
+    reseller.contact_phone = profile.contact_phone;
+    reseller.ecommerce_urls = request.ecommerce_urls;
+    reseller.additional_metadata = request.additional_metadata;
+    reseller.certification_timestamp = Some(now);
+    reseller.certification_expires_at = Some(now.saturating_add(org_settings::certification_validity_ns(reseller.org_id)));
+    reseller.updated_at = now;
+    reseller.updated_by = caller;
+
+    RESELLERS.with(|resellers| {
+        resellers.borrow_mut().insert(reseller.id, reseller.clone());
+    });
+    log_info!("[renew_reseller_certification_v2] Certification for reseller {} renewed until {:?}.", reseller.id, reseller.certification_expires_at);
+    notifications::notify_user(
+        caller,
+        UserNotificationKind::CertificationRenewed,
+        "Reseller certification renewed",
+        "Your reseller certification has been renewed.".to_string(),
+        Some(reseller.id),
+    );
+
+    ApiResponse::success(build_auth_context_response(&user))
+}
+
+// ====== Reseller Product Authorization ======
+
+fn get_reseller(reseller_id: Principal) -> Result<Reseller, ApiError> {
+    RESELLERS
+        .with(|resellers| resellers.borrow().get(&reseller_id))
+        .ok_or_else(|| ApiError::not_found(&format!("Reseller with ID {} not found", reseller_id)))
+}
+
+fn reseller_products_response(reseller_id: Principal) -> ResellerProductsResponse {
+    match reseller_products::get_authorization(reseller_id) {
+        Some(entry) => ResellerProductsResponse {
+            reseller_id,
+            restricted: true,
+            product_ids: entry.product_ids,
+            updated_at: Some(entry.updated_at),
+        },
+        None => ResellerProductsResponse {
+            reseller_id,
+            restricted: false,
+            product_ids: Vec::new(),
+            updated_at: None,
+        },
+    }
+}
+
+// Load the reseller and check the caller may manage it; every product must belong to the reseller's organization
+fn authorize_reseller_products_update(
+    caller: Principal,
+    request: &UpdateResellerProductsRequest,
+) -> Result<Reseller, ApiError> {
+    let reseller = get_reseller(request.reseller_id)?;
+    authorize_for_organization(caller, reseller.org_id, Permission::WriteReseller)?;
+    if request.product_ids.is_empty() {
+        return Err(ApiError::invalid_input("At least one product is required"));
+    }
+    for product_id in &request.product_ids {
+        let product = get_product(product_id)?;
+        if product.org_id != reseller.org_id {
+            return Err(ApiError::invalid_input(&format!(
+                "Product {} does not belong to the reseller's organization",
+                product_id
+            )));
+        }
+    }
+    Ok(reseller)
+}
+
+#[query]
+pub fn get_reseller_products_v2(reseller_id: Principal) -> ApiResponse<ResellerProductsResponse> {
+    let reseller = match get_reseller(reseller_id) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), reseller.org_id, Permission::ReadReseller) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(reseller_products_response(reseller_id))
+}
+
+#[update]
+pub fn grant_reseller_products_v2(request: UpdateResellerProductsRequest) -> ApiResponse<ResellerProductsResponse> {
+    let _metrics = endpoint_metrics::track("grant_reseller_products_v2");
+    let caller = api::caller();
+    let reseller = match authorize_reseller_products_update(caller, &request) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+    reseller_products::grant(&reseller, &request.product_ids, caller);
+    log_info!("[grant_reseller_products_v2] {} granted reseller {} {} product(s)", caller, reseller.id, request.product_ids.len());
+    ApiResponse::success(reseller_products_response(reseller.id))
+}
+
+#[update]
+pub fn revoke_reseller_products_v2(request: UpdateResellerProductsRequest) -> ApiResponse<ResellerProductsResponse> {
+    let _metrics = endpoint_metrics::track("revoke_reseller_products_v2");
+    let caller = api::caller();
+    let reseller = match authorize_reseller_products_update(caller, &request) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+    reseller_products::revoke(&reseller, &request.product_ids, caller);
+    log_info!("[revoke_reseller_products_v2] {} revoked {} product(s) from reseller {}", caller, request.product_ids.len(), reseller.id);
+    ApiResponse::success(reseller_products_response(reseller.id))
+}
+
+// Drop the product list so the reseller is authorized for the whole organization again
+#[update]
+pub fn clear_reseller_products_v2(reseller_id: Principal) -> ApiResponse<ResellerProductsResponse> {
+    let _metrics = endpoint_metrics::track("clear_reseller_products_v2");
+    let caller = api::caller();
+    let reseller = match get_reseller(reseller_id) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, reseller.org_id, Permission::WriteReseller) {
+        return ApiResponse::error(e);
+    }
+    reseller_products::clear(reseller_id);
+    log_info!("[clear_reseller_products_v2] {} restored org-wide authorization for reseller {}", caller, reseller_id);
+    ApiResponse::success(reseller_products_response(reseller_id))
+}
+
+// ====== Reseller Applications ======
+
+#[query]
+pub fn list_reseller_applications_v2(
+    org_id: Principal,
+    status: Option<ResellerApplicationStatus>,
+) -> ApiResponse<Vec<ResellerApplicationDetail>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let details = reseller_applications::list_for_org(org_id, status)
+        .into_iter()
+        .map(|application| ResellerApplicationDetail {
+            reseller: RESELLERS.with(|resellers| resellers.borrow().get(&application.reseller_id)),
+            application,
+        })
+        .collect();
+    ApiResponse::success(details)
+}
+
+// Load an application that is still awaiting a decision and check the caller may decide on it
+fn get_application_for_decision(caller: Principal, application_id: Principal) -> Result<ResellerApplication, ApiError> {
+    let application = reseller_applications::get_application(application_id)
+        .ok_or_else(|| ApiError::not_found("Reseller application not found"))?;
+
+    authorize_for_organization(caller, application.org_id, Permission::WriteOrganization)?;
+
+    if application.status != ResellerApplicationStatus::Pending {
+        return Err(ApiError::invalid_input("Reseller application has already been decided"));
+    }
+    Ok(application)
+}
+
+#[update]
+pub fn approve_reseller_application_v2(application_id: Principal) -> ApiResponse<ResellerApplicationDetail> {
+    let _metrics = endpoint_metrics::track("approve_reseller_application_v2");
+    let caller = api::caller();
+    log_info!("[approve_reseller_application_v2] Called by: {} for application: {}", caller, application_id);
+
+    let application = match get_application_for_decision(caller, application_id) {
+        Ok(a) => a,
+        Err(e) => return ApiResponse::error(e),
+    };
+    match certify_reseller_application(application, caller) {
+        Ok(detail) => ApiResponse::success(detail),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Issue the certification an application asked for. `decided_by` is the canister itself when a
+// pre-approval certified the reseller.
+fn certify_reseller_application(
+    mut application: ResellerApplication,
+    decided_by: Principal,
+) -> Result<ResellerApplicationDetail, ApiError> {
+    let previous = match RESELLERS.with(|resellers| resellers.borrow().get(&application.reseller_id)) {
+        Some(r) if r.org_id == application.org_id => r,
+        _ => return Err(ApiError::not_found("Reseller profile for this application no longer exists")),
+    };
+    if previous.email_verified == Some(false) {
+        return Err(ApiError::invalid_input("The reseller has not confirmed their contact email yet"));
+    }
+    let organization_name = ORGANIZATIONS
+        .with(|orgs| orgs.borrow().get(&application.org_id))
+        .map_or_else(String::new, |org| org.name);
+
+    // The certification code is only issued here
+    let now = clock::now();
+    let mut reseller = previous.clone();
+    reseller.is_verified = true;
+    reseller.certification_code = Some(format!(
+        "CERT-{}-{}",
+        application.org_id.to_string().chars().take(5).collect::<String>(),
+        reseller.id.to_string().chars().take(5).collect::<String>()
+    ));
+    reseller.certification_timestamp = Some(now);
+    reseller.certification_expires_at = Some(now.saturating_add(org_settings::certification_validity_ns(application.org_id)));
+    reseller.updated_at = now;
+    reseller.updated_by = decided_by;
+    RESELLERS.with(|resellers| {
+        resellers.borrow_mut().insert(reseller.id, reseller.clone());
+    });
+    analytics::on_reseller_saved(Some(&previous), &reseller);
+
+    if let Some(mut user) = USERS.with(|users| users.borrow().get(&application.user_id)) {
+        user.org_ids = vec![application.org_id];
+        user.updated_at = now;
+        user.updated_by = decided_by;
+        USERS.with(|users| {
+            users.borrow_mut().insert(user.id, user);
+        });
+    }
+
+    application.status = ResellerApplicationStatus::Approved;
+    application.decided_at = Some(now);
+    application.decided_by = Some(decided_by);
+    reseller_applications::save_application(application.clone());
+    notifications::notify_user(
+        application.user_id,
+        UserNotificationKind::CertificationIssued,
+        "Reseller certification issued",
+        format!("You are now a certified reseller for {}.", organization_name),
+        Some(reseller.id),
+    );
+    log_info!("[certify_reseller_application] Reseller {} certified for org {}.", reseller.id, application.org_id);
+
+    Ok(ResellerApplicationDetail { application, reseller: Some(reseller) })
+}
+
+// Certify the reseller's pending application right away if the brand pre-approved its confirmed
+// contact email. Returns whether it did; anything else stays with manual review.
+fn certify_if_preapproved(reseller: &Reseller) -> bool {
+    if reseller.email_verified != Some(true) {
+        return false;
+    }
+    let email = match &reseller.contact_email {
+        Some(email) => email,
+        None => return false,
+    };
+    let application = match reseller_applications::find_pending_for_reseller(reseller.id) {
+        Some(a) if a.org_id == reseller.org_id => a,
+        _ => return false,
+    };
+    // A pre-approval certifies one reseller
+    let mut preapproval = match reseller_applications::get_preapproval(application.org_id, email) {
+        Some(p) if p.used_by_reseller_id.is_none_or(|id| id == reseller.id) => p,
+        _ => return false,
+    };
+
+    match certify_reseller_application(application, api::id()) {
+        Ok(_) => {
+            preapproval.used_by_reseller_id = Some(reseller.id);
+            preapproval.used_at = Some(clock::now());
+            reseller_applications::save_preapproval(preapproval);
+            true
+        }
+        Err(e) => {
+            log_warn!("[certify_if_preapproved] Pre-approved reseller {} not certified: {:?}", reseller.id, e);
+            false
+        }
+    }
+}
+
+// Upload a brand's approved distributors. Existing entries for the same email are updated, and
+// pending applications that now match are certified.
+#[update]
+pub fn preapprove_resellers_v2(org_id: Principal, rows: Vec<PreapprovedResellerRow>) -> ApiResponse<PreapproveResellersResponse> {
+    let _metrics = endpoint_metrics::track("preapprove_resellers_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if rows.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one row is required"));
+    }
+    if rows.len() > reseller_applications::MAX_PREAPPROVALS_PER_UPLOAD {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "At most {} rows can be uploaded at once",
+            reseller_applications::MAX_PREAPPROVALS_PER_UPLOAD
+        )));
+    }
+
+    let mut validator = Validator::new();
+    let mut entries = Vec::with_capacity(rows.len());
+    for (i, row) in rows.iter().enumerate() {
+        let name = validator.required_text(&format!("rows[{}].name", i), &row.name, validation::MAX_NAME_LENGTH);
+        match validator.email(&format!("rows[{}].email", i), &row.email) {
+            Some(email) => entries.push((name, email)),
+            None => validator.add_error(&format!("rows[{}].email", i), "This field is required"),
+        }
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    let now = clock::now();
+    let (mut added, mut updated) = (0, 0);
+    for (name, email) in entries {
+        let preapproval = match reseller_applications::get_preapproval(org_id, &email) {
+            Some(existing) => {
+                updated += 1;
+                ResellerPreapproval { name, ..existing }
+            }
+            None => {
+                added += 1;
+                ResellerPreapproval {
+                    org_id,
+                    email,
+                    name,
+                    created_at: now,
+                    created_by: caller,
+                    used_by_reseller_id: None,
+                    used_at: None,
+                }
+            }
+        };
+        reseller_applications::save_preapproval(preapproval);
+    }
+
+    let mut certified = 0;
+    for application in reseller_applications::list_for_org(org_id, Some(ResellerApplicationStatus::Pending)) {
+        let reseller = RESELLERS.with(|resellers| resellers.borrow().get(&application.reseller_id));
+        if reseller.is_some_and(|r| certify_if_preapproved(&r)) {
+            certified += 1;
+        }
+    }
+    log_info!("[preapprove_resellers_v2] {} added {} and updated {} pre-approval(s) for org {}; {} reseller(s) certified.", caller, added, updated, org_id, certified);
+
+    ApiResponse::success(PreapproveResellersResponse { added, updated, certified })
+}
+
+#[query]
+pub fn list_reseller_preapprovals_v2(org_id: Principal) -> ApiResponse<Vec<ResellerPreapproval>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(reseller_applications::list_preapprovals(org_id))
+}
+
+// Certifications already issued through the pre-approval are not affected
+#[update]
+pub fn remove_reseller_preapproval_v2(org_id: Principal, email: String) -> ApiResponse<ResellerPreapproval> {
+    let _metrics = endpoint_metrics::track("remove_reseller_preapproval_v2");
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    match reseller_applications::remove_preapproval(org_id, &email.trim().to_lowercase()) {
+        Some(preapproval) => ApiResponse::success(preapproval),
+        None => ApiResponse::error(ApiError::not_found("No pre-approval for this email")),
+    }
+}
+
+#[update]
+pub fn reject_reseller_application_v2(request: RejectResellerApplicationRequest) -> ApiResponse<ResellerApplicationDetail> {
+    let _metrics = endpoint_metrics::track("reject_reseller_application_v2");
+    let caller = api::caller();
+    log_info!("[reject_reseller_application_v2] Called by: {} for application: {}", caller, request.application_id);
+
+    let mut validator = Validator::new();
+    let reason = validator.required_text("reason", &request.reason, reseller_applications::MAX_REJECTION_REASON_LENGTH);
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    let mut application = match get_application_for_decision(caller, request.application_id) {
+        Ok(a) => a,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    application.status = ResellerApplicationStatus::Rejected;
+    application.decided_at = Some(clock::now());
+    application.decided_by = Some(caller);
+    application.rejection_reason = Some(reason.clone());
+    reseller_applications::save_application(application.clone());
+    notifications::notify_user(
+        application.user_id,
+        UserNotificationKind::ResellerApplicationRejected,
+        "Reseller application rejected",
+        format!("Your reseller application was rejected: {}", reason),
+        Some(application.id),
+    );
+
+    ApiResponse::success(ResellerApplicationDetail {
+        reseller: RESELLERS.with(|resellers| resellers.borrow().get(&application.reseller_id)),
+        application,
+    })
+}
+
+// Confirm a reseller's contact email with the token from the confirmation link
+#[update]
+pub fn confirm_reseller_email(token: String) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("confirm_reseller_email");
+    let confirmation = match reseller_email::take_confirmation(token.trim(), clock::now()) {
+        Some(c) => c,
+        None => return ApiResponse::error(ApiError::not_found("Confirmation link is invalid or has expired")),
+    };
+
+    let mut reseller = match RESELLERS.with(|resellers| resellers.borrow().get(&confirmation.reseller_id)) {
+        Some(r) => r,
+        None => return ApiResponse::error(ApiError::not_found("Reseller not found")),
+    };
+    // A link sent for an address the reseller has since replaced confirms nothing
+    if reseller.contact_email.as_ref() != Some(&confirmation.email) {
+        return ApiResponse::error(ApiError::invalid_input("Contact email has changed since this link was sent"));
+    }
+
+    reseller.email_verified = Some(true);
+    reseller.updated_at = clock::now();
+    RESELLERS.with(|resellers| {
+        resellers.borrow_mut().insert(reseller.id, reseller.clone());
+    });
+    log_info!("[confirm_reseller_email] Contact email of reseller {} confirmed.", reseller.id);
+    certify_if_preapproved(&reseller);
+
+    ApiResponse::success(())
+}
+
+#[update]
+pub fn resend_reseller_email_confirmation_v2() -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("resend_reseller_email_confirmation_v2");
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+
+    let reseller = match get_reseller_by_user_id(caller) {
+        Some(r) => r,
+        None => return ApiResponse::error(ApiError::not_found("Reseller profile not found")),
+    };
+    if reseller.email_verified != Some(false) {
+        return ApiResponse::error(ApiError::invalid_input("There is no unconfirmed contact email"));
+    }
+
+    match reseller_email::send_confirmation(&reseller) {
+        Ok(_) => ApiResponse::success(()),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// ====== Phase 4: Profile and Navigation ======
+
+#[query]
+pub fn get_navigation_context() -> ApiResponse<NavigationContextResponse> {
+    let caller = api::caller();
+    log_info!("[get_navigation_context] Called by: {}", caller);
+
+    match USERS.with(|users| users.borrow().get(&caller).clone()) { // Cloned here
+        Some(user) => {
+            let display_name = user.first_name.as_ref().map_or_else(
+                || user.email.as_ref().map_or_else(|| user.id.to_string(), |e| e.clone()),
+                |f_name| f_name.clone()
+            );
+
+            let mut current_org_name: Option<String> = None;
+
+            if user.user_role == Some(UserRole::BrandOwner) {
+                if let Some(active_org_id) = user.active_org_id {
+                    current_org_name = ORGANIZATIONS.with(|orgs| 
+                        orgs.borrow().get(&active_org_id).map(|org| org.name.clone())
+                    );
+                }
+            } else if user.user_role == Some(UserRole::Reseller) {
+                if let Some(reseller_record) = get_reseller_by_user_id(user.id) {
+                    current_org_name = ORGANIZATIONS.with(|orgs| 
+                        orgs.borrow().get(&reseller_record.org_id).map(|org| org.name.clone())
+                    );
+                }
+            }
+
+            ApiResponse::success(NavigationContextResponse {
+                user_display_name: display_name,
+                user_avatar_id: None, 
+                current_organization_name: current_org_name,
+            })
+        }
+        None => {
+            log_info!("[get_navigation_context] User {} not found.", caller);
+            ApiResponse::error(ApiError::unauthorized("User not authenticated.")) 
+        }
+    }
+}
+
+// ====== Phase 5: Reward Redemption (New Endpoint) ======
+
+#[update]
+pub fn redeem_product_reward(request: RedeemRewardRequest) -> ApiResponse<RedeemRewardResponse> {
+    let _metrics = endpoint_metrics::track("redeem_product_reward");
+    let caller = api::caller();
+    log_info!("[redeem_product_reward] Called by: {} for serial: {}", caller, request.serial_no);
+
+    if let Err(e) = check_permission(caller, &Permission::RedeemRewards) {
+        return ApiResponse::error(e);
+    }
+    if let Err(e) = terms::ensure_terms_accepted(caller) {
+        return ApiResponse::error(e);
+    }
+    let as_coupon = request.redeem_as_coupon.unwrap_or(false);
+    let wallet_address = if as_coupon {
+        String::new()
+    } else {
+        match resolve_redemption_wallet(caller, request.wallet_id, &request.wallet_address) {
+            Ok(address) => address,
+            Err(e) => return ApiResponse::error(e),
+        }
+    };
+
+    // --- 1. Re-verify the original verification request to ensure legitimacy & get product_id/print_version --- 
+    let (product_id, product_sn_record) = match serial_store::find(request.serial_no) {
+        Some(found) => found,
+        None => return ApiResponse::error(ApiError::invalid_input("Serial number not found or invalid for redemption.")),
+    };
+
+    let product_opt = PRODUCTS.with(|products| products.borrow().get(&product_id).map(|p| p.clone()));
+    if product_opt.is_none() {
+        return ApiResponse::error(ApiError::internal_error("Product data inconsistent: Product not found for existing serial number during redemption."));
+    }
+    let product = product_opt.unwrap();
+    let print_version_from_storage = product_sn_record.print_version;
+
+    // Verify signature again to ensure this request is for the same valid code
+    let public_key = match keys::verifying_key(&product.public_key) {
+        Ok(key) => key,
+        Err(_) => return ApiResponse::error(ApiError::internal_error("Malformed public key during redemption.")),
+    };
+    let msg_to_verify = format!(
+        "{}_{}_{}",
+        product_id,
+        request.serial_no,
+        print_version_from_storage
+    );
+    match public_key.verify_message(&msg_to_verify, &request.unique_code) {
+        Ok(true) => {}
+        Ok(false) => {
+            return ApiResponse::error(ApiError::invalid_input("Unique code verification failed during redemption attempt."))
+        }
+        Err(e) => return ApiResponse::error(e),
+    }
+
+    // --- 2. Find the specific verification record for this user, product, serial, and version --- 
+    let (verification_seq, mut verification_to_update) = match verification_store::find_for_product(product_id, |verification| {
+        verification.created_by == caller
+            && verification.serial_no == request.serial_no
+            && verification.print_version == print_version_from_storage
+    }) {
+        Some(found) => found,
+        None => {
+            log_warn!("[redeem_product_reward] No matching verification found for user {}, serial {}, version {}", caller, request.serial_no, print_version_from_storage);
+            return ApiResponse::error(ApiError::not_found("No eligible verification record found for this redemption request."));
+        }
+    };
+
+    if product.is_test.unwrap_or(false) {
+        return ApiResponse::error(ApiError::invalid_input("Rewards are not paid out for test products"));
+    }
+
+    // --- 3. Check if reward was already claimed or if it wasn't a first verification --- 
+    if verification_to_update.reward_claimed {
+        return ApiResponse::success(RedeemRewardResponse {
+            success: false,
+            transaction_id: verification_to_update.reward_transaction_id.clone(),
+            message: "Reward for this verification has already been claimed.".to_string(),
+            pending_redemption_id: None,
+            coupon: None,
+        });
+    }
+
+    if verification_to_update.status != ProductVerificationStatus::FirstVerification {
+        return ApiResponse::success(RedeemRewardResponse {
+            success: false,
+            transaction_id: None,
+            message: "Reward can only be claimed for the first verification.".to_string(),
+            pending_redemption_id: None,
+            coupon: None,
+        });
+    }
+
+    // --- 4. Calculate expected reward points (optional, could be stored in verification metadata) ---
+    // Points were credited when the verification was recorded; only the amount is needed here
+    let reward_points = rewards::reward_points_for(product_id, &verification_to_update.status);
+    if reward_points == 0 {
+        // This case might happen if reward logic changes or there was an issue during initial calculation
+        // Mark as claimed anyway to prevent future attempts
+        verification_to_update.reward_claimed = true;
+        // Persist the change
+        verification_store::replace(product_id, verification_seq, verification_to_update.clone());
+        return ApiResponse::success(RedeemRewardResponse {
+            success: false,
+            transaction_id: None,
+            message: "No points were associated with this verification.".to_string(),
+            pending_redemption_id: None,
+            coupon: None,
+        });
+    }
+
+    // --- 5. Coupons come from a pool the brand stocked itself, so they are handed out right away ---
+    if as_coupon {
+        return match redeem_reward_as_coupon(caller, product.org_id, product_id, verification_to_update.id, reward_points) {
+            Ok(coupon) => ApiResponse::success(RedeemRewardResponse {
+                success: true,
+                transaction_id: None,
+                message: format!("Exchanged {} points for a coupon code.", reward_points),
+                pending_redemption_id: None,
+                coupon: Some(RedeemedCoupon { code: coupon.code, description: coupon.description }),
+            }),
+            Err(e) => ApiResponse::error(e),
+        };
+    }
+
+    // --- 6. Hold large payouts until a brand owner or admin approves them ---
+    if reward_points > org_settings::redemption_approval_threshold(product.org_id) {
+        if let Some(existing) = redemptions::find_for_verification(verification_to_update.id) {
+            let message = match existing.status {
+                RedemptionApprovalStatus::Rejected => "Redemption for this verification was rejected.".to_string(),
+                _ => "Redemption is awaiting approval.".to_string(),
+            };
+            return ApiResponse::success(RedeemRewardResponse {
+                success: false,
+                transaction_id: existing.transaction_id.clone(),
+                message,
+                pending_redemption_id: Some(existing.id),
+                coupon: None,
+            });
+        }
+
+        let pending = PendingRedemption {
+            id: generate_unique_principal(verification_to_update.id),
+            org_id: product.org_id,
+            product_id,
+            verification_id: verification_to_update.id,
+            serial_no: request.serial_no,
+            user_id: caller,
+            wallet_address: wallet_address.clone(),
+            points: reward_points,
+            status: RedemptionApprovalStatus::Pending,
+            requested_at: clock::now(),
+            decided_at: None,
+            decided_by: None,
+            rejection_reason: None,
+            transaction_id: None,
+        };
+        redemptions::save_redemption(pending.clone());
+        notifications::notify_user(
+            caller,
+            UserNotificationKind::RedemptionPending,
+            "Redemption awaiting approval",
+            format!("Your redemption of {} points is awaiting approval by the brand owner.", reward_points),
+            Some(pending.id),
+        );
+        log_info!(
+            "[redeem_product_reward] Redemption of {} points for verification {} held for approval as {}",
+            reward_points, verification_to_update.id, pending.id
+        );
+
+        return ApiResponse::success(RedeemRewardResponse {
+            success: false,
+            transaction_id: None,
+            message: format!("Redemption of {} points requires approval by the brand owner.", reward_points),
+            pending_redemption_id: Some(pending.id),
+            coupon: None,
+        });
+    }
+
+    // --- 7. Transfer and mark the verification as claimed ---
+    match complete_reward_redemption(product.org_id, product_id, verification_to_update.id, reward_points, &wallet_address) {
+        Ok(transaction_id) => ApiResponse::success(RedeemRewardResponse {
+            success: true,
+            transaction_id: Some(transaction_id),
+            message: format!("Successfully redeemed {} points.", reward_points),
+            pending_redemption_id: None,
+            coupon: None,
+        }),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Claim a coupon for the verification's reward and mark the verification as claimed
+fn redeem_reward_as_coupon(
+    caller: Principal,
+    org_id: Principal,
+    product_id: Principal,
+    verification_id: Principal,
+    points: u32,
+) -> Result<CouponCode, ApiError> {
+    let mut work = UnitOfWork::new("redeem_reward_as_coupon");
+    let coupon = coupons::claim_coupon(org_id, product_id, caller, verification_id)
+        .ok_or_else(|| ApiError::not_found("No coupon codes are available for this product"))?;
+    let claimed_coupon = coupon.clone();
+    work.on_rollback(move || coupons::release_coupon(claimed_coupon));
+
+    let claimed = verification_store::update_first(
+        product_id,
+        |v| v.id == verification_id,
+        |verification| {
+            verification.reward_claimed = true;
+            verification.reward_transaction_id = Some(format!("coupon-{}", coupon.id));
+        },
+    );
+    if claimed.is_none() {
+        log_error!("[redeem_reward_as_coupon] Verification {} of product {} not found. The coupon is returned.", verification_id, product_id);
+        return Err(ApiError::internal_error("Verification record not found; the coupon was not handed out"));
+    }
+    rewards::record_points_redeemed(product_id, points);
+    work.commit();
+    log_info!("[redeem_reward_as_coupon] Coupon {} handed out to {} for verification {}", coupon.id, caller, verification_id);
+    Ok(coupon)
+}
+
+// Payout address for a redemption: the referenced saved wallet, else the raw address, else the default wallet
+fn resolve_redemption_wallet(caller: Principal, wallet_id: Option<Principal>, wallet_address: &str) -> Result<String, ApiError> {
+    if let Some(wallet_id) = wallet_id {
+        return wallets::get_wallet(caller, wallet_id)
+            .map(|wallet| wallet.address)
+            .ok_or_else(|| ApiError::not_found("Saved wallet not found"));
+    }
+    let wallet_address = wallet_address.trim();
+    if !wallet_address.is_empty() {
+        return Ok(wallet_address.to_string());
+    }
+    wallets::default_wallet(caller)
+        .map(|wallet| wallet.address)
+        .ok_or_else(|| ApiError::invalid_input("Provide a wallet address or save a default wallet"))
+}
+
+// Pay out `points` for a verification and record the claim on the verification record
+fn complete_reward_redemption(
+    org_id: Principal,
+    product_id: Principal,
+    verification_id: Principal,
+    points: u32,
+    wallet_address: &str,
+) -> Result<String, ApiError> {
+    let mut work = UnitOfWork::new("complete_reward_redemption");
+    // Organizations on escrowed reward pools back every payout from their pool
+    if feature_flags::is_enabled(feature_flags::FLAG_REWARD_POOLS, Some(org_id)) {
+        reward_pools::debit_for_redemption(org_id, points)?;
+        work.on_rollback(move || reward_pools::refund_redemption(org_id, points));
+    }
+
+    // Simulate Reward Transfer (TODO: Replace with actual ledger interaction)
+    log_info!(
+        "[complete_reward_redemption] SIMULATING transfer of {} points to wallet {} for verification {}",
+        points, wallet_address, verification_id
+    );
+
+    // Simulate success and generate a fake transaction ID
+    let simulated_tx_id = format!("simulated-tx-{}", verification_id);
+    let redemption_successful = true; // Assume simulation success for now
+    if !redemption_successful {
+        // Handle simulated failure (or real failure from ledger)
+        return Err(ApiError::external_api_error("Failed to process reward transaction."));
+    }
+
+    // Persist the claim on the verification record
+    let claimed = verification_store::update_first(
+        product_id,
+        |v| v.id == verification_id,
+        |verification| {
+            verification.reward_claimed = true;
+            verification.reward_transaction_id = Some(simulated_tx_id.clone());
+        },
+    );
+    if claimed.is_none() {
+        log_error!("[complete_reward_redemption] Verification {} of product {} not found. The payout is undone.", verification_id, product_id);
+        return Err(ApiError::internal_error("Verification record not found; the reward was not paid out"));
+    }
+    rewards::record_points_redeemed(product_id, points);
+    work.commit();
+    log_info!("[complete_reward_redemption] Marked verification {} as claimed.", verification_id);
+
+    Ok(simulated_tx_id)
+}
+
+// Make sure to export the new types if they are in a different module and used by Candid.
+// However, these are directly in models.rs which is part of the crate.
+
+#[query]
+pub fn get_organization_analytic(request: GetOrganizationAnalyticRequest) -> ApiResponse<OrganizationAnalyticData> {
+    let caller = api::caller();
+
+    // Authorize user
+    match authorize_for_organization(caller, request.org_id, Permission::ReadOrganization) {
+        Ok(_) => {
+            // Served from the write-time counters; organizations without counters yet are computed on the fly
+            let counters = analytics::get_counters(request.org_id)
+                .unwrap_or_else(|| analytics::compute_counters(request.org_id));
+            ApiResponse::success(organization_analytic_data(&counters))
+        }
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Recompute the organization's analytics counters from a full scan
+#[update]
+pub fn refresh_organization_analytic_v2(org_id: Principal) -> ApiResponse<OrganizationAnalyticData> {
+    let _metrics = endpoint_metrics::track("refresh_organization_analytic_v2");
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let counters = analytics::rebuild_counters(org_id);
+    heatmap::rebuild(org_id);
+    ApiResponse::success(organization_analytic_data(&counters))
+}
+
+// Longest period a heatmap can cover; older hourly counts are not kept
+const MAX_HEATMAP_PERIOD_NS: u64 = heatmap::HEATMAP_RETENTION_DAYS * 24 * 60 * 60 * NANOS_PER_SECOND;
+const DEFAULT_HEATMAP_PERIOD_NS: u64 = 30 * 24 * 60 * 60 * NANOS_PER_SECOND;
+
+// Verification counts by day of week and hour of day, served from write-time counts
+#[query]
+pub fn get_verification_heatmap_v2(org_id: Principal, range: VerificationHeatmapRange) -> ApiResponse<VerificationHeatmapResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    let utc_offset_hours = range.utc_offset_hours.unwrap_or(0);
+    if !(-12..=14).contains(&utc_offset_hours) {
+        return ApiResponse::error(ApiError::invalid_input("UTC offset must be between -12 and 14 hours"));
+    }
+    let period_end = range.period_end.unwrap_or_else(clock::now);
+    let period_start = range.period_start.unwrap_or_else(|| period_end.saturating_sub(DEFAULT_HEATMAP_PERIOD_NS));
+    if period_start > period_end {
+        return ApiResponse::error(ApiError::invalid_input("Period start must not be after its end"));
+    }
+    if let Err(e) = plans::ensure_within_retention(org_id, period_start, clock::now()) {
+        return ApiResponse::error(e);
+    }
+    if period_end - period_start > MAX_HEATMAP_PERIOD_NS {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A heatmap can cover at most {} days",
+            heatmap::HEATMAP_RETENTION_DAYS
+        )));
+    }
+
+    let kind = range.kind.unwrap_or(VerificationHeatmapKind::All);
+    let (counts, total) = heatmap::heatmap(org_id, period_start, period_end, &kind, utc_offset_hours as i64);
+    ApiResponse::success(VerificationHeatmapResponse {
+        kind,
+        period_start,
+        period_end,
+        utc_offset_hours,
+        counts,
+        total,
+    })
+}
+
+fn organization_analytic_data(counters: &OrgAnalyticsCounters) -> OrganizationAnalyticData {
+    OrganizationAnalyticData {
+        total_products: counters.total_products,
+        active_resellers: counters.active_resellers,
+        verifications_this_month: analytics::verifications_in_window(counters, clock::now()),
+        grey_market_verifications: counters.grey_market_verifications.unwrap_or(0),
+        reseller_product_checks: counters.reseller_product_checks.unwrap_or(0),
+        unauthorized_reseller_product_checks: counters.unauthorized_reseller_product_checks.unwrap_or(0),
+        revoked_codes: counters.revoked_codes.unwrap_or(0),
+        revoked_code_scans: counters.revoked_code_scans.unwrap_or(0),
+        registered_owners: counters.registered_owners.unwrap_or(0),
+        last_refreshed: counters.last_refreshed,
+    }
+}
+
+// ====== Product Shelf ======
+
+const MAX_WARRANTY_DAYS: u32 = 36_500; // Long enough for lifetime warranties
+const MAX_RECALL_REASON_LENGTH: usize = 500;
+const MAX_RECALL_INSTRUCTIONS_LENGTH: usize = 2_000;
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * NANOS_PER_SECOND;
+
+fn my_product_entry(entry: &OwnedProduct, now: u64) -> Option<MyProductEntry> {
+    let product = PRODUCTS.with(|products| products.borrow().get(&entry.product_id))?;
+    let serial = serial_store::get(entry.product_id, entry.serial_no);
+    let org_name = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&product.org_id)).map(|org| org.name);
+    let warranty_expires_at = product
+        .warranty_days
+        .map(|days| entry.registered_at.saturating_add(days as u64 * NANOS_PER_DAY));
+    let warranty_status = match warranty_expires_at {
+        None => WarrantyStatus::NotOffered,
+        Some(expires_at) if now < expires_at => WarrantyStatus::Active,
+        Some(_) => WarrantyStatus::Expired,
+    };
+    Some(MyProductEntry {
+        serial_no: entry.serial_no,
+        user_serial_no: serial.and_then(|sn| sn.user_serial_no),
+        product_id: product.id,
+        product_name: product.name,
+        category: product.category,
+        org_id: product.org_id,
+        org_name,
+        registered_at: entry.registered_at,
+        warranty_status,
+        warranty_expires_at,
+        recall: product
+            .recall
+            .or_else(|| recall_campaigns::active_recall_for_serial(product.id, entry.serial_no)),
+        discontinued: product.discontinued_at.is_some(),
+    })
+}
+
+// Add a serial the caller has verified as genuine to their shelf, which starts its warranty
+#[update]
+pub fn add_to_my_products(serial_no: Principal) -> ApiResponse<MyProductEntry> {
+    let _metrics = endpoint_metrics::track("add_to_my_products");
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+    let user = match find_user_by_caller(caller) {
+        Some(u) => u,
+        None => return ApiResponse::error(ApiError::not_found("User not found")),
+    };
+    let (product_id, serial) = match find_serial_number(serial_no) {
+        Ok(found) => found,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if serial.effective_status() == SerialNumberStatus::Void {
+        return ApiResponse::error(ApiError::invalid_input("This serial number has been voided"));
+    }
+    match owned_products::get(serial_no) {
+        Some(existing) if existing.user_id == user.id => {
+            return match my_product_entry(&existing, clock::now()) {
+                Some(entry) => ApiResponse::success(entry),
+                None => ApiResponse::error(ApiError::not_found("Product not found")),
+            };
+        }
+        Some(_) => {
+            return ApiResponse::error(ApiError::already_exists(
+                "This item is registered to another owner, who must remove it from their products first",
+            ))
+        }
+        None => {}
+    }
+
+    // Only a genuine scan by this user counts, from any of their principals
+    let verified = verification_store::find_for_product(product_id, |v| {
+        v.serial_no == serial_no
+            && matches!(v.status, ProductVerificationStatus::FirstVerification | ProductVerificationStatus::MultipleVerification)
+            && (v.created_by == user.id || v.created_by == caller || user.session_keys.contains(&v.created_by))
+    })
+    .is_some();
+    if !verified {
+        return ApiResponse::error(ApiError::invalid_input("Verify this item before adding it to your products"));
+    }
+    if owned_products::list_for_user(user.id).len() >= owned_products::MAX_OWNED_PER_USER {
+        return ApiResponse::error(ApiError::quota_exceeded(&format!(
+            "You can register at most {} products",
+            owned_products::MAX_OWNED_PER_USER
+        )));
+    }
+
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let now = clock::now();
+    let owned = OwnedProduct {
+        serial_no,
+        user_id: user.id,
+        product_id,
+        registered_at: now,
+    };
+    owned_products::insert(owned.clone());
+    analytics::on_ownership_changed(product.org_id, true);
+    log_info!("[add_to_my_products] User {} registered serial {} of product {}", user.id, serial_no, product_id);
+
+    match my_product_entry(&owned, now) {
+        Some(entry) => ApiResponse::success(entry),
+        None => ApiResponse::error(ApiError::not_found("Product not found")),
+    }
+}
+
+#[update]
+pub fn remove_from_my_products_v2(serial_no: Principal) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("remove_from_my_products_v2");
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+    let user = match find_user_by_caller(caller) {
+        Some(u) => u,
+        None => return ApiResponse::error(ApiError::not_found("User not found")),
+    };
+    match owned_products::get(serial_no) {
+        Some(entry) if entry.user_id == user.id => {
+            owned_products::remove(serial_no);
+            if let Some(product) = PRODUCTS.with(|products| products.borrow().get(&entry.product_id)) {
+                analytics::on_ownership_changed(product.org_id, false);
+            }
+            ApiResponse::success(())
+        }
+        _ => ApiResponse::error(ApiError::not_found("This item is not in your products")),
+    }
+}
+
+// The caller's shelf with warranty status and any recall, most recently added first
+#[query]
+pub fn list_my_products_v2() -> ApiResponse<Vec<MyProductEntry>> {
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::ReadSelf) {
+        return ApiResponse::error(e);
+    }
+    let user = match find_user_by_caller(caller) {
+        Some(u) => u,
+        None => return ApiResponse::error(ApiError::not_found("User not found")),
+    };
+    let now = clock::now();
+    let entries = owned_products::list_for_user(user.id)
+        .iter()
+        .filter_map(|entry| my_product_entry(entry, now))
+        .collect();
+    ApiResponse::success(entries)
+}
+
+// Warranty owners get from the day they register the item; None stops offering one
+#[update]
+pub fn set_product_warranty_v2(product_id: Principal, warranty_days: Option<u32>) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("set_product_warranty_v2");
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    if warranty_days.is_some_and(|days| days == 0 || days > MAX_WARRANTY_DAYS) {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Warranty must be between 1 and {} days",
+            MAX_WARRANTY_DAYS
+        )));
+    }
+
+    product.warranty_days = warranty_days;
+    product.updated_at = clock::now();
+    product.updated_by = api::caller();
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    log_info!("Warranty of product {} set to {:?} days.", product_id, warranty_days);
+    ApiResponse::success(ProductResponse { product })
+}
+
+// Reason and instructions of a recall, trimmed and checked
+fn validated_recall(caller: Principal, reason: &str, instructions: Option<&str>, now: u64) -> Result<ProductRecall, ApiError> {
+    let mut validator = Validator::new();
+    let reason = validator.required_text("reason", reason, MAX_RECALL_REASON_LENGTH);
+    let instructions = instructions.map(str::trim).filter(|i| !i.is_empty()).map(str::to_string);
+    if instructions.as_ref().is_some_and(|i| i.chars().count() > MAX_RECALL_INSTRUCTIONS_LENGTH) {
+        validator.add_error("instructions", "is too long");
+    }
+    validator.finish()?;
+    Ok(ProductRecall {
+        reason,
+        instructions,
+        issued_at: now,
+        issued_by: caller,
+    })
+}
+
+// Recall a whole product. Owners with one of its serials on their shelf are notified by a recall
+// campaign that runs after this call; see start_recall_campaign_v2 for recalling a batch.
+#[update]
+pub fn set_product_recall_v2(request: SetProductRecallRequest) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("set_product_recall_v2");
+    let caller = api::caller();
+    let mut product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    let now = clock::now();
+    let recall = match validated_recall(caller, &request.reason, request.instructions.as_deref(), now) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    product.recall = Some(recall.clone());
+    product.updated_at = now;
+    product.updated_by = caller;
+    PRODUCTS.with(|products| products.borrow_mut().insert(product.id, product.clone()));
+
+    let campaign = recall_campaigns::start(product.org_id, product.id, RecallScope::Product, recall);
+    log_info!("[set_product_recall_v2] Product {} recalled by {}; campaign {} notifies {} owner(s)", product.id, caller, campaign.id, campaign.affected_owners);
+    ApiResponse::success(ProductResponse { product })
+}
+
+#[update]
+pub fn clear_product_recall_v2(product_id: Principal) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("clear_product_recall_v2");
+    let caller = api::caller();
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    if product.recall.is_none() {
+        return ApiResponse::error(ApiError::invalid_input("The product is not recalled"));
+    }
+    let now = clock::now();
+    product.recall = None;
+    product.updated_at = now;
+    product.updated_by = caller;
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    for campaign in recall_campaigns::list_for_org(product.org_id) {
+        if campaign.product_id == product_id && campaign.scope == RecallScope::Product {
+            recall_campaigns::close(campaign.id, caller, now);
+        }
+    }
+    log_info!("[clear_product_recall_v2] Recall of product {} cleared by {}", product_id, caller);
+    ApiResponse::success(ProductResponse { product })
+}
+
+const MAX_RECALL_SERIALS: usize = 5_000;
+
+fn recall_campaign_status(campaign: RecallCampaign) -> RecallCampaignStatusResponse {
+    let notices = recall_campaigns::list_notices(campaign.id);
+    let mut response = RecallCampaignStatusResponse {
+        notices_sent: 0,
+        inbox_read: 0,
+        relay_queued: 0,
+        relay_delivered: 0,
+        relay_failed: 0,
+        relay_skipped: 0,
+        owners_reached: 0,
+        reach_percent: 0,
+        campaign,
+    };
+    for notice in notices.iter().filter(|n| n.sent_at.is_some()) {
+        response.notices_sent += 1;
+        let read = recall_campaigns::is_read(notice);
+        let relay = recall_campaigns::relay_status(notice);
+        if read {
+            response.inbox_read += 1;
+        }
+        match relay {
+            Some(RecallRelayStatus::Queued) => response.relay_queued += 1,
+            Some(RecallRelayStatus::Delivered) => response.relay_delivered += 1,
+            Some(RecallRelayStatus::Failed) => response.relay_failed += 1,
+            Some(RecallRelayStatus::Skipped) | None => response.relay_skipped += 1,
+        }
+        if read || relay == Some(RecallRelayStatus::Delivered) {
+            response.owners_reached += 1;
+        }
+    }
+    response.reach_percent = (response.owners_reached * 100)
+        .checked_div(response.campaign.affected_owners)
+        .map_or(100, |percent| percent.min(100) as u8);
+    response
+}
+
+// Recall a product or part of it, e.g. one print job's batch, and notify every owner who registered
+// a covered serial: an inbox notification, plus an email through the relay when the owner has an email
+#[update]
+pub fn start_recall_campaign_v2(request: StartRecallCampaignRequest) -> ApiResponse<RecallCampaignStatusResponse> {
+    let _metrics = endpoint_metrics::track("start_recall_campaign_v2");
+    let caller = api::caller();
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    let scope = match request.scope {
+        RecallScope::Product => {
+            return ApiResponse::error(ApiError::invalid_input("Use set_product_recall_v2 to recall a whole product"))
+        }
+        RecallScope::PrintJob { print_job_id } => match print_jobs::get_print_job(print_job_id) {
+            Some(job) if job.product_id == product.id => RecallScope::PrintJob { print_job_id },
+            _ => return ApiResponse::error(ApiError::not_found("Print job not found for this product")),
+        },
+        RecallScope::Serials { mut serial_nos } => {
+            serial_nos.sort();
+            serial_nos.dedup();
+            if serial_nos.is_empty() || serial_nos.len() > MAX_RECALL_SERIALS {
+                return ApiResponse::error(ApiError::invalid_input(&format!(
+                    "A recall can name between 1 and {} serial numbers",
+                    MAX_RECALL_SERIALS
+                )));
+            }
+            if let Some(unknown) = serial_nos.iter().find(|sn| serial_store::get(product.id, **sn).is_none()) {
+                return ApiResponse::error(ApiError::not_found(&format!(
+                    "Serial number {} does not belong to product {}",
+                    unknown, product.id
+                )));
+            }
+            RecallScope::Serials { serial_nos }
+        }
+    };
+    let recall = match validated_recall(caller, &request.reason, request.instructions.as_deref(), clock::now()) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let campaign = recall_campaigns::start(product.org_id, product.id, scope, recall);
+    log_info!("[start_recall_campaign_v2] Campaign {} for product {} started by {}; {} owner(s) to notify", campaign.id, product.id, caller, campaign.affected_owners);
+    ApiResponse::success(recall_campaign_status(campaign))
+}
+
+// Delivery and read counts of a campaign. An owner counts as reached once they opened the inbox
+// notice or the relay delivered their email.
+#[query]
+pub fn get_recall_campaign_status_v2(campaign_id: Principal) -> ApiResponse<RecallCampaignStatusResponse> {
+    let campaign = match recall_campaigns::get_campaign(campaign_id) {
+        Some(c) => c,
+        None => return ApiResponse::error(ApiError::not_found("Recall campaign not found")),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), campaign.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(recall_campaign_status(campaign))
+}
+
+#[query]
+pub fn list_recall_campaigns_v2(org_id: Principal) -> ApiResponse<Vec<RecallCampaign>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(recall_campaigns::list_for_org(org_id))
+}
+
+// End a recall. Owners already notified keep their notice; notices not yet sent are dropped.
+#[update]
+pub fn close_recall_campaign_v2(campaign_id: Principal) -> ApiResponse<RecallCampaign> {
+    let _metrics = endpoint_metrics::track("close_recall_campaign_v2");
+    let caller = api::caller();
+    let campaign = match recall_campaigns::get_campaign(campaign_id) {
+        Some(c) => c,
+        None => return ApiResponse::error(ApiError::not_found("Recall campaign not found")),
+    };
+    if let Err(e) = authorize_for_organization(caller, campaign.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    if campaign.scope == RecallScope::Product {
+        return ApiResponse::error(ApiError::invalid_input("Use clear_product_recall_v2 to end a whole-product recall"));
+    }
+    match recall_campaigns::close(campaign_id, caller, clock::now()) {
+        Some(campaign) => {
+            log_info!("[close_recall_campaign_v2] Campaign {} closed by {}", campaign_id, caller);
+            ApiResponse::success(campaign)
+        }
+        None => ApiResponse::error(ApiError::invalid_input("The recall campaign is already closed")),
+    }
+}
+
+#[query]
+pub fn get_ownership_stats_v2(org_id: Principal) -> ApiResponse<OwnershipStatsResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    let products: Vec<Product> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .map(|(_, p)| p)
+            .filter(|p| p.org_id == org_id)
+            .collect()
+    });
+    let product_ids: Vec<Principal> = products.iter().map(|p| p.id).collect();
+    let mut counts: Vec<ProductOwnershipCount> = owned_products::counts_for_products(&product_ids)
+        .into_iter()
+        .filter_map(|(product_id, registered_owners)| {
+            products.iter().find(|p| p.id == product_id).map(|p| ProductOwnershipCount {
+                product_id,
+                product_name: p.name.clone(),
+                registered_owners,
+            })
+        })
+        .collect();
+    counts.sort_by_key(|a| std::cmp::Reverse(a.registered_owners));
+    ApiResponse::success(OwnershipStatsResponse {
+        org_id,
+        total_registered: counts.iter().map(|c| c.registered_owners).sum(),
+        products: counts,
+    })
+}
+
+// ====== Wallet Address Book ======
+
+#[update]
+pub fn add_my_wallet_v2(request: AddWalletRequest) -> ApiResponse<SavedWallet> {
+    let _metrics = endpoint_metrics::track("add_my_wallet_v2");
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+
+    let mut validator = Validator::new();
+    let label = validator.required_text("label", &request.label, wallets::MAX_WALLET_LABEL_LENGTH);
+    let address = request.address.trim().to_string();
+    if let Err(message) = wallets::validate_address(request.chain, &address) {
+        validator.add_error("address", message);
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    match wallets::add_wallet(caller, label, request.chain, address, request.make_default) {
+        Ok(wallet) => ApiResponse::success(wallet),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[query]
+pub fn list_my_wallets_v2() -> ApiResponse<Vec<SavedWallet>> {
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::ReadSelf) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(wallets::list_wallets(caller))
+}
+
+#[update]
+pub fn set_default_wallet_v2(wallet_id: Principal) -> ApiResponse<SavedWallet> {
+    let _metrics = endpoint_metrics::track("set_default_wallet_v2");
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+    match wallets::set_default_wallet(caller, wallet_id) {
+        Ok(wallet) => ApiResponse::success(wallet),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[update]
+pub fn remove_my_wallet_v2(wallet_id: Principal) -> ApiResponse<SavedWallet> {
+    let _metrics = endpoint_metrics::track("remove_my_wallet_v2");
+    let caller = api::caller();
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+    match wallets::remove_wallet(caller, wallet_id) {
+        Ok(wallet) => ApiResponse::success(wallet),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// ====== Public Brand Directory ======
+
+// Anonymous query: opted-in, non-suspended organizations with their verified-brand badge
+#[query]
+pub fn list_public_brands(pagination: Option<PaginationRequest>) -> ApiResponse<PublicBrandsListResponse> {
+    let mut brands: Vec<PublicBrand> = directory::list_visible_listings()
+        .into_iter()
+        .filter(|listing| !moderation::is_quarantined(listing.org_id))
+        .filter_map(|listing| {
+            let organization = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&listing.org_id))?;
+            let badge = listing.badge?;
+            let product_count = analytics::get_counters(listing.org_id)
+                .map(|counters| counters.total_products)
+                .unwrap_or_else(|| get_organization_product_ids(listing.org_id).len() as u64);
+            Some(PublicBrand {
+                organization: OrganizationPublic::from(organization),
+                logo_url: listing.logo_url,
+                website_url: listing.website_url,
+                tagline: listing.tagline,
+                product_count,
+                featured: listing.featured,
+                badge,
+            })
+        })
+        .collect();
+    brands.sort_by(|a, b| {
+        b.featured
+            .cmp(&a.featured)
+            .then_with(|| a.organization.name.to_lowercase().cmp(&b.organization.name.to_lowercase()))
+    });
+
+    let (page_items, page_info) = paginate(brands, &pagination.unwrap_or_default());
+    ApiResponse::success(PublicBrandsListResponse {
+        brands: page_items,
+        pagination: page_info,
+    })
+}
+
+// ====== Live Events ======
+
+// Brand dashboards follow their organization's verification and counterfeit events through an IC
+// WebSocket gateway instead of polling the list endpoints. The client opens, messages and closes its
+// connection under its own identity, relayed by the gateway, and the gateway polls ws_get_messages
+// for what to push to its clients.
+#[update]
+pub fn ws_open(request: WsOpenRequest) -> ApiResponse<WsOpenResponse> {
+    let _metrics = endpoint_metrics::track("ws_open");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    match live_events::open(caller, request.gateway, request.org_id, request.topics, clock::now()) {
+        Ok((connection_id, topics)) => {
+            log_info!("[ws_open] Connection {} opened by {} for org {}", connection_id, caller, request.org_id);
+            ApiResponse::success(WsOpenResponse {
+                connection_id,
+                topics,
+                idle_timeout_seconds: live_events::IDLE_TIMEOUT_SECONDS,
+            })
+        }
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Pings and topic changes. Access to the organization is checked again on every message, so a
+// member who loses it is disconnected at their next ping.
+#[update]
+pub fn ws_message(request: WsMessageRequest) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("ws_message");
+    let caller = api::caller();
+    let now = clock::now();
+    let org_id = match live_events::connection_org(request.connection_id, caller) {
+        Ok(org_id) => org_id,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::ReadProduct) {
+        let _ = live_events::close(request.connection_id, caller, "Access to the organization was revoked", now);
+        return ApiResponse::error(e);
+    }
+
+    let topics = match request.message {
+        WsClientMessage::Subscribe(topics) => Some(topics),
+        WsClientMessage::Ping => None,
+    };
+    match live_events::on_client_message(request.connection_id, caller, topics, now) {
+        Ok(()) => ApiResponse::success(()),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Called by the client, or by its gateway when the socket drops
+#[update]
+pub fn ws_close(connection_id: u64) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("ws_close");
+    match live_events::close(connection_id, api::caller(), "Closed", clock::now()) {
+        Ok(()) => ApiResponse::success(()),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Messages for the calling gateway's connections, oldest first. Only gateways on the allowlist in the
+// canister config can poll.
+#[query]
+pub fn ws_get_messages(from_seq: u64) -> ApiResponse<WsGetMessagesResponse> {
+    let gateway = api::caller();
+    if gateway == Principal::anonymous() {
+        return ApiResponse::error(ApiError::unauthorized("Gateways must call with their own identity"));
+    }
+    if !config::is_live_gateway(gateway) {
+        return ApiResponse::error(ApiError::unauthorized("The caller is not an allowed live gateway"));
+    }
+
+    // Access is checked again at every poll, so events stop the moment a member loses it
+    let (messages, next_seq) = live_events::messages_for_gateway(gateway, from_seq, |client, org_id| {
+        authorize_for_organization(client, org_id, Permission::ReadProduct).is_ok()
+    });
+    ApiResponse::success(WsGetMessagesResponse { messages, next_seq })
+}
+
+// ====== Public Brand Stats ======
+
+// Anonymous query behind brands' "X products verified" widgets, for organizations that opted in.
+// It reads one stored record and the numbers are republished at most once per publish interval, so
+// polling is cheap and responses may be cached for max_age_seconds. Pass the version of the last
+// response as known_version to get a not-modified answer without stats while nothing changed.
+#[query]
+pub fn get_public_brand_stats(org_id: Principal, known_version: Option<u64>) -> ApiResponse<PublicBrandStatsResponse> {
+    let stats = if org_settings::public_stats_enabled(org_id) && !moderation::is_quarantined(org_id) {
+        public_stats::get_published(org_id)
+    } else {
+        None
+    };
+    // Organizations that did not opt in look the same as ones that do not exist
+    let stats = match stats {
+        Some(stats) => stats,
+        None => return ApiResponse::error(ApiError::not_found("No public stats for this organization")),
+    };
+
+    let not_modified = known_version == Some(stats.version);
+    ApiResponse::success(PublicBrandStatsResponse {
+        org_id,
+        version: stats.version,
+        not_modified,
+        stats: Some(stats).filter(|_| !not_modified),
+        max_age_seconds: public_stats::PUBLISH_INTERVAL_SECONDS,
+    })
+}
+
+// Resolve which brand a serial number (or its first characters) belongs to, for consumers who do not
+// know the brand yet. An update rather than a query so the lookup budget actually gets spent; the
+// serial itself is never returned, and ambiguous prefixes are refused, so it cannot be used to
+// enumerate codes.
+#[update]
+pub fn lookup_brand_by_code(code_prefix: String) -> ApiResponse<BrandLookupResponse> {
+    let _metrics = endpoint_metrics::track("lookup_brand_by_code");
+    let caller = api::caller();
+    if let Err(e) = brand_lookup::record_lookup(caller, clock::now()) {
+        return ApiResponse::error(e);
+    }
+    let prefix = match brand_lookup::normalize_code_prefix(&code_prefix) {
+        Ok(prefix) => prefix,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    // Two candidates are enough to tell an unambiguous prefix from an ambiguous one
+    let product_id = match serial_store::product_ids_by_text_prefix(&prefix, 2).as_slice() {
+        [] => return ApiResponse::error(ApiError::not_found("No product matches this code")),
+        [product_id] => *product_id,
+        _ => return ApiResponse::error(ApiError::invalid_input("Code matches more than one product; enter more of it")),
+    };
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let organization = match ORGANIZATIONS.with(|orgs| orgs.borrow().get(&product.org_id)) {
+        Some(org) => org,
+        None => return ApiResponse::error(ApiError::not_found("No product matches this code")),
+    };
+
+    let listing = Some(directory::get_listing(product.org_id)).filter(|listing| listing.is_public && !listing.suspended);
+    ApiResponse::success(BrandLookupResponse {
+        organization: OrganizationPublic::from(organization),
+        product_id: product.id,
+        product_name: product.name,
+        logo_url: listing.as_ref().and_then(|listing| listing.logo_url.clone()),
+        website_url: listing.as_ref().and_then(|listing| listing.website_url.clone()),
+        badge: listing.and_then(|listing| listing.badge),
+    })
+}
+
+#[query]
+pub fn get_directory_listing_v2(org_id: Principal) -> ApiResponse<DirectoryListing> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(directory::get_listing(org_id))
+}
+
+#[update]
+pub fn update_directory_listing_v2(request: UpdateDirectoryListingRequest) -> ApiResponse<DirectoryListing> {
+    let _metrics = endpoint_metrics::track("update_directory_listing_v2");
+    let caller = api::caller();
+    log_info!("[update_directory_listing_v2] Called by: {} for org: {}", caller, request.org_id);
+
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    for url in [&request.logo_url, &request.website_url].into_iter().flatten() {
+        if let Err(e) = directory::validate_listing_url(url) {
+            return ApiResponse::error(e);
+        }
+    }
+    if request.tagline.as_ref().is_some_and(|t| t.len() > directory::MAX_TAGLINE_LENGTH) {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Tagline cannot exceed {} characters",
+            directory::MAX_TAGLINE_LENGTH
+        )));
+    }
+
+    let mut listing = directory::get_listing(request.org_id);
+    if let Some(is_public) = request.is_public {
+        listing.is_public = is_public;
+    }
+    if request.logo_url.is_some() {
+        listing.logo_url = request.logo_url;
+    }
+    if request.website_url.is_some() {
+        listing.website_url = request.website_url;
+    }
+    if request.tagline.is_some() {
+        listing.tagline = request.tagline;
+    }
+    if listing.is_public && listing.badge.is_none() {
+        listing.badge = Some(directory::issue_badge(request.org_id));
+    }
+    listing.updated_at = clock::now();
+    listing.updated_by = caller;
+
+    directory::save_listing(listing.clone());
+    ApiResponse::success(listing)
+}
+
+#[update]
+pub fn moderate_directory_listing_v2(request: ModerateDirectoryListingRequest) -> ApiResponse<DirectoryListing> {
+    let _metrics = endpoint_metrics::track("moderate_directory_listing_v2");
+    let caller = api::caller();
+    log_info!("[moderate_directory_listing_v2] Called by: {} for org: {}", caller, request.org_id);
+
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Organization not found"));
+    }
+
+    let mut listing = directory::get_listing(request.org_id);
+    if let Some(featured) = request.featured {
+        listing.featured = featured;
+    }
+    if let Some(suspended) = request.suspended {
+        listing.suspended = suspended;
+        listing.suspension_reason = if suspended { request.suspension_reason } else { None };
+    }
+    listing.updated_at = clock::now();
+    listing.updated_by = caller;
+
+    directory::save_listing(listing.clone());
+    ApiResponse::success(listing)
+}
+
+// ====== Content Moderation ======
+
+// Flagged products, organizations and resellers, oldest first. Filter by Flagged for the review queue.
+#[query]
+pub fn list_moderation_cases_v2(
+    status: Option<ModerationStatus>,
+    pagination: Option<PaginationRequest>,
+) -> ApiResponse<ModerationCasesResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+
+    let (cases, page_info) = paginate(moderation::list_cases(status), &pagination.unwrap_or_default());
+    ApiResponse::success(ModerationCasesResponse { cases, pagination: Some(page_info) })
+}
+
+// Approve flagged text so the record shows again, or reject it so it stays hidden until the owner edits it
+#[update]
+pub fn review_moderation_case_v2(request: ReviewModerationCaseRequest) -> ApiResponse<ModerationCase> {
+    let _metrics = endpoint_metrics::track("review_moderation_case_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+
+    match moderation::review(request.entity_id, request.approve, request.note, caller) {
+        Ok(case) => {
+            log_info!("[review_moderation_case_v2] {:?} {} marked {:?} by {}", case.entity_kind, case.entity_id, case.status, caller);
+            ApiResponse::success(case)
+        }
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// ====== Notification Rules ======
+
+#[update]
+pub fn create_notification_rule_v2(request: CreateNotificationRuleRequest) -> ApiResponse<NotificationRuleResponse> {
+    let _metrics = endpoint_metrics::track("create_notification_rule_v2");
+    let caller = api::caller();
+
+    let webhook_url = request.webhook_url.trim().to_string();
+    if !webhook_url.starts_with("https://") {
+        return ApiResponse::error(ApiError::invalid_input("Webhook URL must start with https://"));
+    }
+
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    if let Err(e) = feature_flags::ensure_enabled(feature_flags::FLAG_WEBHOOKS, Some(product.org_id)) {
+        return ApiResponse::error(e);
+    }
+
+    if let Err(e) = plans::ensure_webhook_capacity(product.org_id) {
+        return ApiResponse::error(e);
+    }
+    if webhooks::list_rules_for_product(product.id).len() >= webhooks::MAX_RULES_PER_PRODUCT {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A product can have at most {} notification rules",
+            webhooks::MAX_RULES_PER_PRODUCT
+        )));
+    }
+
+    let rule = NotificationRule {
+        id: generate_unique_principal(Principal::anonymous()),
+        org_id: product.org_id,
+        product_id: product.id,
+        trigger: request.trigger,
+        min_scan_count: request.min_scan_count,
+        webhook_url,
+        is_active: true,
+        created_at: clock::now(),
+        created_by: caller,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+
+    webhooks::save_rule(rule.clone());
+    log_info!("[create_notification_rule_v2] Rule {} created for product {} by {}", rule.id, rule.product_id, caller);
+
+    ApiResponse::success(NotificationRuleResponse { rule })
+}
+
+#[query]
+pub fn list_notification_rules_v2(product_id: Principal) -> ApiResponse<Vec<NotificationRule>> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(webhooks::list_rules_for_product(product_id))
+}
+
+#[update]
+pub fn delete_notification_rule_v2(rule_id: Principal) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("delete_notification_rule_v2");
+    let caller = api::caller();
+
+    let rule = match webhooks::get_rule(rule_id) {
+        Some(rule) => rule,
+        None => return ApiResponse::error(ApiError::not_found("Notification rule not found")),
+    };
+
+    if let Err(e) = authorize_for_organization(caller, rule.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    webhooks::remove_rule(rule_id);
+    log_info!("[delete_notification_rule_v2] Rule {} deleted by {}", rule_id, caller);
+
+    ApiResponse::success(())
+}
+
+// ====== Organization Settings ======
+
+#[query]
+pub fn get_org_settings_v2(org_id: Principal) -> ApiResponse<OrgSettings> {
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(org_settings::get_org_settings(org_id))
+}
+
+// Options the organization's serial batches and print runs start from, for prefilling forms
+#[query]
+pub fn get_org_defaults_v2(org_id: Principal) -> ApiResponse<SerialBatchDefaults> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(org_settings::serial_batch_defaults(org_id))
+}
+
+#[update]
+pub fn update_org_settings_v2(request: UpdateOrgSettingsRequest) -> ApiResponse<OrgSettings> {
+    let _metrics = endpoint_metrics::track("update_org_settings_v2");
+    let caller = api::caller();
+    log_info!("[update_org_settings_v2] Called by: {} for org: {}", caller, request.org_id);
+
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    if request.certification_validity_seconds == Some(0) {
+        return ApiResponse::error(ApiError::invalid_input("Certification validity must be greater than zero"));
+    }
+    if request.duplicate_scan_threshold == Some(0) {
+        return ApiResponse::error(ApiError::invalid_input("Duplicate scan threshold must be greater than zero"));
+    }
+
+    let mut settings = org_settings::get_org_settings(request.org_id);
+    if request.certification_validity_seconds.is_some() {
+        settings.certification_validity_seconds = request.certification_validity_seconds;
+    }
+    if request.certification_grace_period_seconds.is_some() {
+        settings.certification_grace_period_seconds = request.certification_grace_period_seconds;
+    }
+    if request.duplicate_scan_threshold.is_some() {
+        settings.duplicate_scan_threshold = request.duplicate_scan_threshold;
+    }
+    if request.redemption_approval_threshold.is_some() {
+        settings.redemption_approval_threshold = request.redemption_approval_threshold;
+    }
+    if request.single_use_reseller_codes.is_some() {
+        settings.single_use_reseller_codes = request.single_use_reseller_codes;
+    }
+    if request.verification_privacy.is_some() {
+        settings.verification_privacy = request.verification_privacy;
+    }
+    if let Some(weights) = request.authenticity_weights {
+        if authenticity::is_unset(&weights) {
+            settings.authenticity_weights = None;
+        } else if let Err(e) = authenticity::validate_weights(&weights) {
+            return ApiResponse::error(e);
+        } else {
+            settings.authenticity_weights = Some(weights);
+        }
+    }
+    if let Some(policy) = request.client_version_policy {
+        if policy.min_version.trim().is_empty() {
+            settings.client_version_policy = None;
+        } else if let Err(e) = client_version::validate_policy(&policy) {
+            return ApiResponse::error(e);
+        } else {
+            settings.client_version_policy = Some(policy);
+        }
+    }
+    if let Some(mut defaults) = request.serial_batch_defaults {
+        if defaults.batch_size.is_some_and(|size| size == 0 || size > MAX_SERIAL_BATCH_SIZE) {
+            return ApiResponse::error(ApiError::invalid_input(&format!(
+                "Default batch size must be between 1 and {}",
+                MAX_SERIAL_BATCH_SIZE
+            )));
+        }
+        if defaults
+            .activation_delay_seconds
+            .is_some_and(|delay| delay == 0 || delay.saturating_mul(1_000_000_000) > print_jobs::MAX_ACTIVATION_DELAY_NANOS)
+        {
+            return ApiResponse::error(ApiError::invalid_input("Activation delay must be between 1 second and two years"));
+        }
+        if let Some(template_product_id) = defaults.label_template_product_id {
+            match label_templates::get_template(template_product_id) {
+                Some(template) if template.org_id == request.org_id => {}
+                _ => return ApiResponse::error(ApiError::not_found("No label template of this organization's product was found")),
+            }
+        }
+        defaults.user_serial_prefix = match normalize_user_serial_prefix(defaults.user_serial_prefix.as_deref()) {
+            Ok(prefix) => prefix.map(str::to_string),
+            Err(e) => return ApiResponse::error(e),
+        };
+        settings.serial_batch_defaults = Some(defaults).filter(|d| *d != SerialBatchDefaults::default());
+    }
+    if let Some(enabled) = request.public_stats_enabled {
+        let was_enabled = settings.public_stats_enabled.unwrap_or(false);
+        settings.public_stats_enabled = Some(enabled);
+        if enabled && !was_enabled {
+            public_stats::enable(request.org_id);
+        } else if !enabled && was_enabled {
+            public_stats::disable(request.org_id);
+        }
+    }
+    settings.updated_at = clock::now();
+    settings.updated_by = caller;
+
+    org_settings::save_org_settings(settings.clone());
+    ApiResponse::success(settings)
+}
+
+// ====== Organization Onboarding ======
+
+// Setup progress computed from the organization's data, so it cannot drift from what was actually done
+#[query]
+pub fn get_org_onboarding_status_v2(org_id: Principal) -> ApiResponse<OrgOnboardingStatusResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    let organization = match ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)) {
+        Some(org) => org,
+        None => return ApiResponse::error(ApiError::not_found("Organization not found")),
+    };
+
+    let listing = directory::get_listing(org_id);
+    let branding_set = listing.logo_url.is_some()
+        || listing.tagline.is_some()
+        || organization.metadata.iter().any(|m| m.key.to_lowercase().contains("logo") && !m.value.trim().is_empty());
+
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
+    });
+
+    let serials_printed = product_ids
+        .iter()
+        .any(|product_id| serial_store::list_for_product(*product_id).iter().any(|sn| sn.print_version > 0));
+
+    let first_reseller_certified = RESELLERS.with(|resellers| {
+        resellers
+            .borrow()
+            .iter()
+            .any(|(_, reseller)| reseller.org_id == org_id && reseller.certification_timestamp.is_some())
+    });
+
+    let reward_policy_set = org_settings::get_org_settings(org_id).redemption_approval_threshold.is_some()
+        || product_ids.iter().any(|product_id| rewards::has_special_promotion(*product_id));
+
+    let steps = vec![
+        OnboardingStep { step: OnboardingStepKind::BrandingSet, completed: branding_set },
+        OnboardingStep { step: OnboardingStepKind::FirstProductCreated, completed: !product_ids.is_empty() },
+        OnboardingStep { step: OnboardingStepKind::SerialsPrinted, completed: serials_printed },
+        OnboardingStep { step: OnboardingStepKind::FirstResellerCertified, completed: first_reseller_certified },
+        OnboardingStep { step: OnboardingStepKind::WebhookConfigured, completed: webhooks::has_active_rule_for_org(org_id) },
+        OnboardingStep { step: OnboardingStepKind::RewardPolicySet, completed: reward_policy_set },
+    ];
+
+    ApiResponse::success(OrgOnboardingStatusResponse {
+        org_id,
+        completed_steps: steps.iter().filter(|s| s.completed).count() as u32,
+        total_steps: steps.len() as u32,
+        next_step: steps.iter().find(|s| !s.completed).map(|s| s.step),
+        steps,
+    })
+}
+
+// ====== Brand Dashboard ======
+
+const DASHBOARD_RECENT_VERIFICATIONS: usize = 20;
+const DASHBOARD_NOTIFICATIONS: usize = 10;
+// A serial pool with fewer unprinted serials than this is reported as low on stock
+const LOW_STOCK_SERIAL_THRESHOLD: u64 = 10;
+
+// Everything the brand dashboard renders on load, in one call. Analytics come from the
+// incremental counters and recent verifications from a bounded scan per product.
+#[query]
+pub fn get_brand_dashboard_v2(org_id: Principal) -> ApiResponse<BrandDashboardResponse> {
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let counters = analytics::get_counters(org_id).unwrap_or_else(|| analytics::compute_counters(org_id));
+
+    let products: Vec<Product> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(_, product)| product)
+            .collect()
+    });
+
+    let mut recent: Vec<(ProductVerification, String)> = Vec::new();
+    for product in &products {
+        for verification in verification_store::latest_for_product(product.id, DASHBOARD_RECENT_VERIFICATIONS) {
+            recent.push((verification, product.name.clone()));
+        }
+    }
+    recent.sort_by_key(|a| std::cmp::Reverse(a.0.created_at));
+    recent.truncate(DASHBOARD_RECENT_VERIFICATIONS);
+    let recent_verifications = recent
+        .into_iter()
+        .map(|(verification, product_name)| ProductVerificationDetail {
+            user_email: USERS.with(|users| users.borrow().get(&verification.created_by)).and_then(|user| user.email),
+            product_id: verification.product_id,
+            product_name,
+            serial_no: verification.serial_no,
+            created_at: verification.created_at,
+            status: verification.status,
+        })
+        .collect();
+
+    let mut low_stock_serial_pools = Vec::new();
+    for product in &products {
+        // Keyed by variant; None is the product's own pool, which is always reported on
+        let mut pools: std::collections::BTreeMap<Option<Principal>, (u64, u64)> = std::collections::BTreeMap::new();
+        pools.insert(None, (0, 0));
+        for sn in serial_store::list_for_product(product.id) {
+            let (available, total) = pools.entry(sn.variant_id).or_insert((0, 0));
+            *total += 1;
+            if sn.effective_status() == SerialNumberStatus::Created {
+                *available += 1;
+            }
+        }
+        for (variant_id, (available, total)) in pools {
+            if available < LOW_STOCK_SERIAL_THRESHOLD {
+                low_stock_serial_pools.push(LowStockSerialPool {
+                    product_id: product.id,
+                    product_name: product.name.clone(),
+                    variant_id,
+                    available_serials: available,
+                    total_serials: total,
+                });
+            }
+        }
+    }
+    low_stock_serial_pools.sort_by_key(|pool| pool.available_serials);
+
+    let pending_reseller_applications: Vec<Reseller> =
+        reseller_applications::list_for_org(org_id, Some(ResellerApplicationStatus::Pending))
+            .into_iter()
+            .filter_map(|application| RESELLERS.with(|resellers| resellers.borrow().get(&application.reseller_id)))
+            .collect();
+
+    let mut inbox = notifications::list_for_user(caller);
+    let unread_notifications = inbox.iter().filter(|n| n.read_at.is_none()).count() as u32;
+    inbox.truncate(DASHBOARD_NOTIFICATIONS);
+
+    ApiResponse::success(BrandDashboardResponse {
+        org_id,
+        analytics: organization_analytic_data(&counters),
+        recent_verifications,
+        low_stock_serial_pools,
+        pending_reseller_applications,
+        notifications: inbox,
+        unread_notifications,
+        generated_at: clock::now(),
+    })
+}
+
+// ====== Consumer Activity ======
+
+#[query]
+pub fn get_my_activity_v2(pagination: Option<PaginationRequest>) -> ApiResponse<ConsumerActivityResponse> {
+    let caller = api::caller();
+    log_info!("[get_my_activity_v2] Called by: {}", caller);
+
+    if let Err(e) = check_permission(caller, &Permission::ReadSelf) {
+        return ApiResponse::error(e);
+    }
+
+    // Read from the caller's own index, so only the requested page of their history is loaded
+    let (page_items, page_info) = match paginate_from_store(
+        &pagination.unwrap_or_default(),
+        |(key, _): &(verification_store::UserVerificationKey, ProductVerification)| *key,
+        |after, n| verification_store::list_for_user_after(caller, after, n),
+    ) {
+        Ok(page) => page,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let mut unclaimed: Vec<ProductVerification> = Vec::new();
+    verification_store::for_each_for_user(caller, |verification| {
+        if verification.status == ProductVerificationStatus::FirstVerification && !verification.reward_claimed {
+            unclaimed.push(verification.clone());
+        }
+    });
+
+    // Resolve each product once
+    let mut products: std::collections::HashMap<Principal, Option<Product>> = std::collections::HashMap::new();
+    for verification in page_items.iter().map(|(_, v)| v).chain(unclaimed.iter()) {
+        products
+            .entry(verification.product_id)
+            .or_insert_with(|| PRODUCTS.with(|p| p.borrow().get(&verification.product_id)));
+    }
+    let product_name = |product_id: &Principal| {
+        products
+            .get(product_id)
+            .and_then(|p| p.as_ref().map(|p| p.name.clone()))
+            .unwrap_or_else(|| "Unknown Product".to_string())
+    };
+
+    let unclaimed_rewards: Vec<UnclaimedReward> = unclaimed
+        .iter()
+        .map(|v| UnclaimedReward {
+            verification_id: v.id,
+            product_id: v.product_id,
+            product_name: product_name(&v.product_id),
+            serial_no: v.serial_no,
+            verified_at: v.created_at,
+        })
+        .collect();
+
+    let activities: Vec<ConsumerVerificationActivity> = page_items
+        .iter()
+        .map(|(_, v)| ConsumerVerificationActivity {
+            verification_id: v.id,
+            product_id: v.product_id,
+            product_name: product_name(&v.product_id),
+            org_id: products.get(&v.product_id).and_then(|p| p.as_ref().map(|p| p.org_id)),
+            serial_no: v.serial_no,
+            status: v.status.clone(),
+            created_at: v.created_at,
+            reward_claimed: v.reward_claimed,
+            reward_transaction_id: v.reward_transaction_id.clone(),
+            coupon: coupons::find_for_verification(v.id)
+                .map(|c| RedeemedCoupon { code: c.code, description: c.description }),
+        })
+        .collect();
+
+    let total_verifications = verification_store::count_for_user(caller) as u32;
+    let user_rewards = rewards::get_user_rewards(caller);
+    let now = clock::now();
+    let owned_products = match find_user_by_caller(caller) {
+        Some(user) => owned_products::list_for_user(user.id)
+            .iter()
+            .filter_map(|entry| my_product_entry(entry, now))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    ApiResponse::success(ConsumerActivityResponse {
+        verifications: activities,
+        pagination: page_info,
+        reward_points: user_rewards.as_ref().map_or(0, |r| r.total_points),
+        total_verifications,
+        first_verifications: user_rewards.as_ref().map_or(0, |r| r.first_verifications),
+        unclaimed_rewards,
+        pending_redemptions: redemptions::list_for_user(caller)
+            .into_iter()
+            .filter(|r| r.status == RedemptionApprovalStatus::Pending)
+            .collect(),
+        reward_penalties: rewards::list_penalties(|p| p.user_id == caller),
+        owned_products,
+    })
+}
+
+// ====== Product Reward Stats ======
+
+#[query]
+pub fn get_product_reward_stats_v2(product_id: Principal) -> ApiResponse<ProductRewardStats> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(rewards::get_product_reward_stats(product_id))
+}
+
+// ====== Reward Penalties ======
+
+const MAX_PENALTY_REASON_LENGTH: usize = 500;
+
+// Base points the user earned verifying the organization's products, less what the organization already clawed back
+fn penalizable_points_for_org(user_id: Principal, org_id: Principal) -> u64 {
+    let org_products: std::collections::HashSet<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
+    });
+    let earned: u64 = org_products
+        .iter()
+        .flat_map(|product_id| verification_store::list_for_product(*product_id))
+        .filter(|verification| verification.created_by == user_id)
+        .map(|verification| rewards::base_points_for(&verification.status) as u64)
+        .sum();
+    let already_penalized: u64 = rewards::list_penalties(|p| p.user_id == user_id && p.org_id == Some(org_id))
+        .iter()
+        .map(|p| p.points_deducted as u64)
+        .sum();
+    earned.saturating_sub(already_penalized)
+}
+
+// Claw back points after confirmed fraud or reward farming. Brand owners are limited to points
+// earned on their own products; admins may penalize without an organization.
+#[update]
+pub fn penalize_user_rewards_v2(request: PenalizeUserRewardsRequest) -> ApiResponse<RewardPenalty> {
+    let _metrics = endpoint_metrics::track("penalize_user_rewards_v2");
+    let caller = api::caller();
+
+    let mut validator = Validator::new();
+    let reason = validator.required_text("reason", &request.reason, MAX_PENALTY_REASON_LENGTH);
+    if request.points == 0 {
+        validator.add_error("points", "Points must be greater than zero");
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    if USERS.with(|users| users.borrow().get(&request.user_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("User not found"));
+    }
+
+    match request.org_id {
+        Some(org_id) => {
+            if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+                return ApiResponse::error(e);
+            }
+            // Admins act on behalf of the organization without its cap
+            if ensure_admin(caller).is_err() {
+                let available = penalizable_points_for_org(request.user_id, org_id);
+                if request.points as u64 > available {
+                    return ApiResponse::error(ApiError::invalid_input(&format!(
+                        "Only {} points earned on this organization's products can be penalized",
+                        available
+                    )));
+                }
+            }
+        }
+        None => {
+            if let Err(e) = ensure_admin(caller) {
+                return ApiResponse::error(e);
+            }
+        }
+    }
+
+    let (points_deducted, balance_after) = rewards::deduct_points(request.user_id, request.points);
+    let penalty = RewardPenalty {
+        id: generate_unique_principal(Principal::anonymous()),
+        user_id: request.user_id,
+        org_id: request.org_id,
+        points_requested: request.points,
+        points_deducted,
+        balance_after,
+        reason,
+        penalized_by: caller,
+        created_at: clock::now(),
+    };
+    rewards::save_penalty(penalty.clone());
+
+    log_warn!(
+        "[penalize_user_rewards_v2] {} deducted {} of {} points from {} (org: {:?}, balance now {}): {}",
+        caller,
+        points_deducted,
+        request.points,
+        request.user_id,
+        request.org_id,
+        balance_after,
+        penalty.reason
+    );
+    notifications::notify_user(
+        request.user_id,
+        UserNotificationKind::RewardsPenalized,
+        "Reward points removed",
+        format!("{} points were removed from your balance: {}", points_deducted, penalty.reason),
+        Some(penalty.id),
+    );
+
+    ApiResponse::success(penalty)
+}
+
+#[query]
+pub fn list_reward_penalties_v2(request: ListRewardPenaltiesRequest) -> ApiResponse<RewardPenaltiesListResponse> {
+    let caller = api::caller();
+    let user_filter = request.user_id;
+
+    let matching = match request.org_id {
+        Some(org_id) => {
+            if let Err(e) = authorize_for_organization(caller, org_id, Permission::ReadOrganization) {
+                return ApiResponse::error(e);
+            }
+            rewards::list_penalties(|p| p.org_id == Some(org_id) && user_filter.is_none_or(|u| p.user_id == u))
+        }
+        None => {
+            if let Err(e) = ensure_admin(caller) {
+                return ApiResponse::error(e);
+            }
+            rewards::list_penalties(|p| user_filter.is_none_or(|u| p.user_id == u))
+        }
+    };
+
+    let (page_items, page_info) = paginate(matching, &request.pagination.unwrap_or_default());
+    ApiResponse::success(RewardPenaltiesListResponse {
+        penalties: page_items,
+        pagination: page_info,
+    })
+}
+
+// ====== Reward Pools ======
+
+fn reward_pool_response(pool: RewardPool) -> RewardPoolBalanceResponse {
+    let account = reward_pools::deposit_account(pool.org_id);
+    RewardPoolBalanceResponse {
+        points_covered: pool.balance / pool.units_per_point.max(1),
+        redemptions_require_pool: feature_flags::is_enabled(feature_flags::FLAG_REWARD_POOLS, Some(pool.org_id)),
+        deposit_account_owner: account.owner,
+        deposit_subaccount: account.subaccount.unwrap_or_default(),
+        pool,
+    }
+}
+
+#[query]
+pub fn get_reward_pool_balance_v2(org_id: Principal) -> ApiResponse<RewardPoolBalanceResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(reward_pool_response(reward_pools::get_pool(org_id)))
+}
+
+// ICRC-2 deposit: the caller approves this canister on the ledger first, then calls this to pull the tokens
+#[update]
+pub async fn deposit_reward_pool_v2(request: DepositRewardPoolRequest) -> ApiResponse<RewardPoolBalanceResponse> {
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if request.amount == 0 {
+        return ApiResponse::error(ApiError::invalid_input("Deposit amount must be greater than zero"));
+    }
+
+    match reward_pools::deposit_from(request.org_id, caller, request.amount).await {
+        Ok(pool) => ApiResponse::success(reward_pool_response(pool)),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Deposit notification for plain transfers to the organization's deposit account
+#[update]
+pub async fn notify_reward_pool_deposit_v2(org_id: Principal) -> ApiResponse<RewardPoolBalanceResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    match reward_pools::sync_deposits(org_id).await {
+        Ok(pool) => ApiResponse::success(reward_pool_response(pool)),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[update]
+pub fn set_reward_pool_settings_v2(request: SetRewardPoolSettingsRequest) -> ApiResponse<RewardPoolBalanceResponse> {
+    let _metrics = endpoint_metrics::track("set_reward_pool_settings_v2");
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if request.units_per_point == Some(0) {
+        return ApiResponse::error(ApiError::invalid_input("units_per_point must be greater than zero"));
+    }
+
+    let mut pool = reward_pools::get_pool(request.org_id);
+    if let Some(units_per_point) = request.units_per_point {
+        pool.units_per_point = units_per_point;
+    }
+    if let Some(threshold) = request.low_balance_threshold {
+        pool.low_balance_threshold = threshold;
+        pool.low_balance_alerted = pool.balance < threshold;
+    }
+    reward_pools::save_pool(pool.clone());
+    ApiResponse::success(reward_pool_response(pool))
+}
+
+// ====== Coupon Codes ======
+
+// Stock coupon codes that consumers can take instead of a wallet payout
+#[update]
+pub fn upload_coupon_codes_v2(request: UploadCouponCodesRequest) -> ApiResponse<UploadCouponCodesResponse> {
+    let _metrics = endpoint_metrics::track("upload_coupon_codes_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if let Some(product_id) = request.product_id {
+        if !is_product_owned_by_organization(product_id, request.org_id) {
+            return ApiResponse::error(ApiError::not_found("Product not found in this organization"));
+        }
+    }
+    if request.codes.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one coupon code is required"));
+    }
+    if request.codes.len() > coupons::MAX_COUPON_CODES_PER_UPLOAD {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "At most {} coupon codes can be uploaded at once",
+            coupons::MAX_COUPON_CODES_PER_UPLOAD
+        )));
+    }
+
+    let mut validator = Validator::new();
+    let description = request
+        .description
+        .as_deref()
+        .map(|d| validator.required_text("description", d, coupons::MAX_COUPON_DESCRIPTION_LENGTH));
+    for (i, code) in request.codes.iter().enumerate() {
+        validator.required_text(&format!("codes[{}]", i), code, coupons::MAX_COUPON_CODE_LENGTH);
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    let now = clock::now();
+    let mut added = 0u32;
+    let mut skipped_duplicates = 0u32;
+    for code in &request.codes {
+        let code = code.trim();
+        if coupons::code_exists(request.org_id, code) {
+            skipped_duplicates += 1;
+            continue;
+        }
+        coupons::save_coupon(CouponCode {
+            id: generate_unique_principal(request.org_id),
+            org_id: request.org_id,
+            product_id: request.product_id,
+            code: code.to_string(),
+            description: description.clone(),
+            uploaded_at: now,
+            uploaded_by: caller,
+            redeemed_by: None,
+            redeemed_at: None,
+            verification_id: None,
+        });
+        added += 1;
+    }
+    log_info!("[upload_coupon_codes_v2] {} coupon code(s) added to org {} (product: {:?}) by {}", added, request.org_id, request.product_id, caller);
+
+    ApiResponse::success(UploadCouponCodesResponse {
+        added,
+        skipped_duplicates,
+        available: coupons::count_available(request.org_id, request.product_id),
+    })
+}
+
+// ====== Redemption Approvals ======
+
+#[query]
+pub fn list_pending_redemptions_v2(org_id: Principal) -> ApiResponse<Vec<PendingRedemption>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(redemptions::list_pending_for_org(org_id))
+}
+
+// Load a redemption that is still awaiting a decision and check the caller may decide on it
+fn get_redemption_for_decision(caller: Principal, redemption_id: Principal) -> Result<PendingRedemption, ApiError> {
+    let redemption = redemptions::get_redemption(redemption_id)
+        .ok_or_else(|| ApiError::not_found("Pending redemption not found"))?;
+
+    authorize_for_organization(caller, redemption.org_id, Permission::WriteOrganization)?;
+
+    if redemption.status != RedemptionApprovalStatus::Pending {
+        return Err(ApiError::invalid_input("Redemption has already been decided"));
+    }
+    Ok(redemption)
+}
+
+#[update]
+pub fn approve_redemption_v2(redemption_id: Principal) -> ApiResponse<PendingRedemption> {
+    let _metrics = endpoint_metrics::track("approve_redemption_v2");
+    let caller = api::caller();
+    log_info!("[approve_redemption_v2] Called by: {} for redemption: {}", caller, redemption_id);
+
+    let mut redemption = match get_redemption_for_decision(caller, redemption_id) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let transaction_id = match complete_reward_redemption(
+        redemption.org_id,
+        redemption.product_id,
+        redemption.verification_id,
+        redemption.points,
+        &redemption.wallet_address,
+    ) {
+        Ok(tx) => tx,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    redemption.status = RedemptionApprovalStatus::Approved;
+    redemption.decided_at = Some(clock::now());
+    redemption.decided_by = Some(caller);
+    redemption.transaction_id = Some(transaction_id);
+    redemptions::save_redemption(redemption.clone());
+    notifications::notify_user(
+        redemption.user_id,
+        UserNotificationKind::RedemptionApproved,
+        "Redemption approved",
+        format!("Your redemption of {} points was approved and paid out.", redemption.points),
+        Some(redemption.id),
+    );
+
+    ApiResponse::success(redemption)
+}
+
+#[update]
+pub fn reject_redemption_v2(request: RejectRedemptionRequest) -> ApiResponse<PendingRedemption> {
+    let _metrics = endpoint_metrics::track("reject_redemption_v2");
+    let caller = api::caller();
+    log_info!("[reject_redemption_v2] Called by: {} for redemption: {}", caller, request.redemption_id);
+
+    let mut redemption = match get_redemption_for_decision(caller, request.redemption_id) {
+        Ok(r) => r,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    // The verification stays unclaimed, but a rejected redemption blocks further attempts for it
+    redemption.status = RedemptionApprovalStatus::Rejected;
+    redemption.decided_at = Some(clock::now());
+    redemption.decided_by = Some(caller);
+    redemption.rejection_reason = request.reason;
+    redemptions::save_redemption(redemption.clone());
+    notifications::notify_user(
+        redemption.user_id,
+        UserNotificationKind::RedemptionRejected,
+        "Redemption rejected",
+        match &redemption.rejection_reason {
+            Some(reason) => format!("Your redemption of {} points was rejected: {}", redemption.points, reason),
+            None => format!("Your redemption of {} points was rejected.", redemption.points),
+        },
+        Some(redemption.id),
+    );
+
+    ApiResponse::success(redemption)
+}
+
+// ====== Notification Inbox ======
+
+#[query]
+pub fn list_my_notifications_v2(request: ListNotificationsRequest) -> ApiResponse<NotificationsListResponse> {
+    let caller = api::caller();
+
+    if let Err(e) = check_permission(caller, &Permission::ReadSelf) {
+        return ApiResponse::error(e);
+    }
+
+    let inbox = notifications::list_for_user(caller);
+    let unread_count = inbox.iter().filter(|n| n.read_at.is_none()).count() as u32;
+    let inbox: Vec<UserNotification> = if request.unread_only.unwrap_or(false) {
+        inbox.into_iter().filter(|n| n.read_at.is_none()).collect()
+    } else {
+        inbox
+    };
+    let (page_items, page_info) = paginate(inbox, &request.pagination.unwrap_or_default());
+
+    ApiResponse::success(NotificationsListResponse {
+        notifications: page_items,
+        unread_count,
+        pagination: page_info,
+    })
+}
+
+#[update]
+pub fn mark_notification_read_v2(notification_id: Principal) -> ApiResponse<UserNotification> {
+    let _metrics = endpoint_metrics::track("mark_notification_read_v2");
+    let caller = api::caller();
+
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+
+    let mut notification = match notifications::get_notification(caller, notification_id) {
+        Some(n) => n,
+        _ => return ApiResponse::error(ApiError::not_found("Notification not found")),
+    };
+
+    if notification.read_at.is_none() {
+        notification.read_at = Some(clock::now());
+        notifications::save_notification(notification.clone());
+    }
+    ApiResponse::success(notification)
+}
+
+// ====== Disputes ======
+
+fn validate_dispute_message(message: &str) -> Result<String, ApiError> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(ApiError::invalid_input("Message cannot be empty"));
+    }
+    if message.len() > disputes::MAX_DISPUTE_MESSAGE_LENGTH {
+        return Err(ApiError::invalid_input(&format!(
+            "Message cannot exceed {} characters",
+            disputes::MAX_DISPUTE_MESSAGE_LENGTH
+        )));
+    }
+    Ok(message.to_string())
+}
+
+// Which side of the dispute the caller is on, or an error if they are not involved
+fn dispute_party(caller: Principal, dispute: &Dispute) -> Result<DisputeParty, ApiError> {
+    if caller == dispute.opened_by {
+        return Ok(DisputeParty::Consumer);
+    }
+    if ensure_admin(caller).is_ok() {
+        return Ok(DisputeParty::Admin);
+    }
+    authorize_for_organization(caller, dispute.org_id, Permission::ReadOrganization)?;
+    Ok(DisputeParty::Brand)
+}
+
+#[update]
+pub fn open_dispute_v2(request: OpenDisputeRequest) -> ApiResponse<Dispute> {
+    let _metrics = endpoint_metrics::track("open_dispute_v2");
+    let caller = api::caller();
+    log_info!("[open_dispute_v2] Called by: {} for serial: {}", caller, request.serial_no);
+
+    if let Err(e) = check_permission(caller, &Permission::WriteSelf) {
+        return ApiResponse::error(e);
+    }
+
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let subject = request.subject.trim().to_string();
+    if subject.is_empty() || subject.len() > disputes::MAX_DISPUTE_SUBJECT_LENGTH {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Subject must be between 1 and {} characters",
+            disputes::MAX_DISPUTE_SUBJECT_LENGTH
+        )));
+    }
+    let message = match validate_dispute_message(&request.message) {
+        Ok(m) => m,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    // A referenced verification must be the caller's own scan of this serial
+    if let Some(verification_id) = request.verification_id {
+        let owns_verification = verification_store::find_for_product(request.product_id, |v| {
+            v.id == verification_id && v.created_by == caller && v.serial_no == request.serial_no
+        })
+        .is_some();
+        if !owns_verification {
+            return ApiResponse::error(ApiError::not_found("Verification not found for this product and serial number"));
+        }
+    }
+
+    let now = clock::now();
+    let dispute = Dispute {
+        id: generate_unique_principal(request.serial_no),
+        org_id: product.org_id,
+        product_id: request.product_id,
+        serial_no: request.serial_no,
+        verification_id: request.verification_id,
+        opened_by: caller,
+        subject,
+        status: DisputeStatus::Open,
+        messages: vec![DisputeMessage {
+            author: caller,
+            party: DisputeParty::Consumer,
+            body: message,
+            created_at: now,
+        }],
+        resolution: None,
+        created_at: now,
+        updated_at: now,
+        resolved_at: None,
+        resolved_by: None,
+    };
+    disputes::save_dispute(dispute.clone());
+
+    ApiResponse::success(dispute)
+}
+
+#[query]
+pub fn get_dispute_v2(dispute_id: Principal) -> ApiResponse<Dispute> {
+    let dispute = match disputes::get_dispute(dispute_id) {
+        Some(d) => d,
+        None => return ApiResponse::error(ApiError::not_found("Dispute not found")),
+    };
+
+    if let Err(e) = dispute_party(api::caller(), &dispute) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(dispute)
+}
+
+#[update]
+pub fn add_dispute_message_v2(request: AddDisputeMessageRequest) -> ApiResponse<Dispute> {
+    let _metrics = endpoint_metrics::track("add_dispute_message_v2");
+    let caller = api::caller();
+
+    let mut dispute = match disputes::get_dispute(request.dispute_id) {
+        Some(d) => d,
+        None => return ApiResponse::error(ApiError::not_found("Dispute not found")),
+    };
+    let party = match dispute_party(caller, &dispute) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if dispute.status == DisputeStatus::Resolved {
+        return ApiResponse::error(ApiError::invalid_input("Cannot add messages to a resolved dispute"));
+    }
+    if dispute.messages.len() >= disputes::MAX_MESSAGES_PER_DISPUTE {
+        return ApiResponse::error(ApiError::invalid_input("This dispute has reached its message limit"));
+    }
+    let body = match validate_dispute_message(&request.message) {
+        Ok(m) => m,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let now = clock::now();
+    dispute.messages.push(DisputeMessage {
+        author: caller,
+        party,
+        body,
+        created_at: now,
+    });
+    dispute.updated_at = now;
+    disputes::save_dispute(dispute.clone());
+
+    if party != DisputeParty::Consumer {
+        notifications::notify_user(
+            dispute.opened_by,
+            UserNotificationKind::DisputeUpdated,
+            "New reply on your dispute",
+            format!("The brand replied to your dispute \"{}\".", dispute.subject),
+            Some(dispute.id),
+        );
+    }
+
+    ApiResponse::success(dispute)
+}
+
+#[update]
+pub fn update_dispute_status_v2(request: UpdateDisputeStatusRequest) -> ApiResponse<Dispute> {
+    let _metrics = endpoint_metrics::track("update_dispute_status_v2");
+    let caller = api::caller();
+    log_info!("[update_dispute_status_v2] Called by: {} for dispute: {} -> {:?}", caller, request.dispute_id, request.status);
+
+    let mut dispute = match disputes::get_dispute(request.dispute_id) {
+        Some(d) => d,
+        None => return ApiResponse::error(ApiError::not_found("Dispute not found")),
+    };
+
+    // Only the brand (or an admin) moves a dispute through its lifecycle
+    if ensure_admin(caller).is_err() {
+        if let Err(e) = authorize_for_organization(caller, dispute.org_id, Permission::WriteOrganization) {
+            return ApiResponse::error(e);
+        }
+    }
+
+    if !dispute.status.can_transition_to(request.status) {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Cannot move dispute from {:?} to {:?}",
+            dispute.status, request.status
+        )));
+    }
+
+    let now = clock::now();
+    if request.status == DisputeStatus::Resolved {
+        let resolution = request.resolution.as_deref().map(str::trim).unwrap_or("");
+        if resolution.is_empty() {
+            return ApiResponse::error(ApiError::invalid_input("A resolution is required to resolve a dispute"));
+        }
+        dispute.resolution = Some(resolution.to_string());
+        dispute.resolved_at = Some(now);
+        dispute.resolved_by = Some(caller);
+    }
+    dispute.status = request.status;
+    dispute.updated_at = now;
+    disputes::save_dispute(dispute.clone());
+
+    notifications::notify_user(
+        dispute.opened_by,
+        UserNotificationKind::DisputeUpdated,
+        "Dispute status changed",
+        format!("Your dispute \"{}\" is now {:?}.", dispute.subject, dispute.status),
+        Some(dispute.id),
+    );
+
+    ApiResponse::success(dispute)
+}
+
+#[query]
+pub fn list_my_disputes_v2(pagination: Option<PaginationRequest>) -> ApiResponse<DisputesListResponse> {
+    let caller = api::caller();
+
+    if let Err(e) = check_permission(caller, &Permission::ReadSelf) {
+        return ApiResponse::error(e);
+    }
+
+    let mine = disputes::list_disputes(|d| d.opened_by == caller);
+    let (page_items, page_info) = paginate(mine, &pagination.unwrap_or_default());
+    ApiResponse::success(DisputesListResponse {
+        disputes: page_items,
+        pagination: page_info,
+    })
+}
+
+// Brand view of an organization's disputes; admins may omit org_id to oversee all of them
+#[query]
+pub fn list_disputes_v2(request: ListDisputesRequest) -> ApiResponse<DisputesListResponse> {
+    let caller = api::caller();
+
+    let matching = match request.org_id {
+        Some(org_id) => {
+            if let Err(e) = authorize_for_organization(caller, org_id, Permission::ReadOrganization) {
+                return ApiResponse::error(e);
+            }
+            disputes::list_disputes(|d| d.org_id == org_id && disputes::matches_status(d, request.status))
+        }
+        None => {
+            if let Err(e) = ensure_admin(caller) {
+                return ApiResponse::error(e);
+            }
+            disputes::list_disputes(|d| disputes::matches_status(d, request.status))
+        }
+    };
+
+    let (page_items, page_info) = paginate(matching, &request.pagination.unwrap_or_default());
+    ApiResponse::success(DisputesListResponse {
+        disputes: page_items,
+        pagination: page_info,
+    })
+}
+
+// ====== Reports ======
+
+#[update]
+pub fn create_report_schedule_v2(request: CreateReportScheduleRequest) -> ApiResponse<ReportScheduleResponse> {
+    let _metrics = endpoint_metrics::track("create_report_schedule_v2");
+    let caller = api::caller();
+
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let delivery_url = request.delivery_url.trim().to_string();
+    if !delivery_url.starts_with("https://") {
+        return ApiResponse::error(ApiError::invalid_input("Delivery URL must start with https://"));
+    }
+
+    if request.recipient_emails.iter().any(|email| !email.contains('@')) {
+        return ApiResponse::error(ApiError::invalid_input("Recipient emails must be valid email addresses"));
+    }
+
+    if reports::list_schedules_for_org(request.org_id).len() >= reports::MAX_SCHEDULES_PER_ORG {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "An organization can have at most {} report schedules",
+            reports::MAX_SCHEDULES_PER_ORG
+        )));
+    }
+
+    let now = clock::now();
+    let schedule = ReportSchedule {
+        id: generate_unique_principal(request.org_id),
+        org_id: request.org_id,
+        frequency: request.frequency,
+        delivery_url,
+        recipient_emails: request.recipient_emails,
+        is_active: true,
+        last_run_at: None,
+        next_run_at: now.saturating_add(reports::period_ns(request.frequency)),
+        created_at: now,
+        created_by: caller,
+        updated_at: now,
+        updated_by: caller,
+    };
+
+    reports::save_schedule(schedule.clone());
+    log_info!("[create_report_schedule_v2] Schedule {} ({:?}) created for org {} by {}", schedule.id, schedule.frequency, schedule.org_id, caller);
+
+    ApiResponse::success(ReportScheduleResponse { schedule })
+}
+
+#[query]
+pub fn list_report_schedules_v2(org_id: Principal) -> ApiResponse<Vec<ReportSchedule>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(reports::list_schedules_for_org(org_id))
+}
+
+#[update]
+pub fn delete_report_schedule_v2(schedule_id: Principal) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("delete_report_schedule_v2");
+    let caller = api::caller();
+
+    let schedule = match reports::get_schedule(schedule_id) {
+        Some(s) => s,
+        None => return ApiResponse::error(ApiError::not_found("Report schedule not found")),
+    };
+
+    if let Err(e) = authorize_for_organization(caller, schedule.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    reports::remove_schedule(schedule_id);
+    log_info!("[delete_report_schedule_v2] Schedule {} deleted by {}", schedule_id, caller);
+
+    ApiResponse::success(())
+}
+
+#[query]
+pub fn list_reports_v2(request: ListReportsRequest) -> ApiResponse<ReportsListResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let (reports, pagination) = paginate(
+        reports::list_reports_for_org(request.org_id),
+        &request.pagination.unwrap_or_default(),
+    );
+
+    ApiResponse::success(ReportsListResponse { reports, pagination })
+}
+
+#[query]
+pub fn get_report_v2(report_id: Principal) -> ApiResponse<Report> {
+    let report = match reports::get_report(report_id) {
+        Some(r) => r,
+        None => return ApiResponse::error(ApiError::not_found("Report not found")),
+    };
+
+    if let Err(e) = authorize_for_organization(api::caller(), report.org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(report)
+}
+
+#[update]
+pub fn create_report_share_link_v2(request: CreateReportShareLinkRequest) -> ApiResponse<ReportShareLinkResponse> {
+    let _metrics = endpoint_metrics::track("create_report_share_link_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let ttl_ns = request.ttl_seconds.saturating_mul(NANOS_PER_SECOND);
+    if ttl_ns == 0 || ttl_ns > reports::MAX_SHARE_LINK_TTL_NS {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Link lifetime must be between 1 second and {} days",
+            reports::MAX_SHARE_LINK_TTL_NS / (24 * 60 * 60 * NANOS_PER_SECOND)
+        )));
+    }
+
+    let filters = request.filters;
+    if let (Some(start), Some(end)) = (filters.period_start, filters.period_end) {
+        if start >= end {
+            return ApiResponse::error(ApiError::invalid_input("Report period must end after it starts"));
+        }
+    }
+    if let Some(start) = filters.period_start {
+        if let Err(e) = plans::ensure_within_retention(request.org_id, start, clock::now()) {
+            return ApiResponse::error(e);
+        }
+    }
+    if let Some(product_ids) = &filters.product_ids {
+        if product_ids.is_empty() {
+            return ApiResponse::error(ApiError::invalid_input("Product filter cannot be empty; omit it to include all products"));
+        }
+        for product_id in product_ids {
+            match get_product(product_id) {
+                Ok(product) if product.org_id == request.org_id => {}
+                Ok(_) => {
+                    return ApiResponse::error(ApiError::invalid_input(&format!(
+                        "Product {} does not belong to this organization",
+                        product_id
+                    )))
+                }
+                Err(e) => return ApiResponse::error(e),
+            }
+        }
+    }
+
+    if reports::active_share_links_for_org(request.org_id, clock::now()).len() >= reports::MAX_ACTIVE_SHARE_LINKS_PER_ORG {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "An organization can have at most {} active share links",
+            reports::MAX_ACTIVE_SHARE_LINKS_PER_ORG
+        )));
+    }
+
+    let (link, token) = reports::create_share_link(request.org_id, filters, ttl_ns, caller);
+    log_info!("[create_report_share_link_v2] Share link {} for org {} created by {}, expires at {}", link.id, link.org_id, caller, link.expires_at);
+
+    ApiResponse::success(ReportShareLinkResponse { link, token })
+}
+
+#[query]
+pub fn list_report_share_links_v2(org_id: Principal) -> ApiResponse<Vec<ReportShareLink>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(reports::active_share_links_for_org(org_id, clock::now()))
+}
+
+#[update]
+pub fn revoke_report_share_link_v2(link_id: Principal) -> ApiResponse<ReportShareLink> {
+    let _metrics = endpoint_metrics::track("revoke_report_share_link_v2");
+    let caller = api::caller();
+    let link = match reports::get_share_link(link_id) {
+        Some(l) => l,
+        None => return ApiResponse::error(ApiError::not_found("Share link not found")),
+    };
+    if let Err(e) = authorize_for_organization(caller, link.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    match reports::revoke_share_link(link_id) {
+        Some(link) => {
+            log_info!("[revoke_report_share_link_v2] Share link {} revoked by {}", link_id, caller);
+            ApiResponse::success(link)
+        }
+        None => ApiResponse::error(ApiError::not_found("Share link not found")),
+    }
+}
+
+// Token-authenticated: anyone holding a live token sees the report, no account needed.
+// Expired, revoked and unknown tokens get the same answer.
+#[query]
+pub fn get_shared_report(token: String) -> ApiResponse<SharedReportResponse> {
+    let now = clock::now();
+    let link = match reports::find_usable_share_link(token.trim(), now) {
+        Some(l) => l,
+        None => return ApiResponse::error(ApiError::unauthorized("Share link is invalid or has expired")),
+    };
+    let organization_name = ORGANIZATIONS
+        .with(|orgs| orgs.borrow().get(&link.org_id))
+        .map(|org| org.name)
+        .unwrap_or_default();
+
+    let period_end = link.filters.period_end.unwrap_or(now).min(now);
+    let period_start = link
+        .filters
+        .period_start
+        .unwrap_or_else(|| period_end.saturating_sub(reports::DEFAULT_SHARED_REPORT_PERIOD_NS))
+        // A link outlives plan downgrades, so the period is clamped here as well
+        .max(plans::retention_start(link.org_id, now));
+    let totals = reports::aggregate_verifications(link.org_id, link.filters.product_ids.as_deref(), period_start, period_end);
+
+    ApiResponse::success(SharedReportResponse {
+        organization_name,
+        filters: link.filters,
+        period_start,
+        period_end,
+        total_verifications: totals.total_verifications,
+        first_verifications: totals.first_verifications,
+        multiple_verifications: totals.multiple_verifications,
+        invalid_verifications: totals.invalid_verifications,
+        suspicious_serials: totals.suspicious_serials,
+        top_products: totals.top_products,
+        generated_at: now,
+        expires_at: link.expires_at,
+    })
+}
+
+// ====== BI Export ======
+
+#[update]
+pub fn set_bi_export_config_v2(request: SetBiExportConfigRequest) -> ApiResponse<BiExportConfig> {
+    let _metrics = endpoint_metrics::track("set_bi_export_config_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let endpoint_url = request.endpoint_url.trim().to_string();
+    if !endpoint_url.starts_with("https://") {
+        return ApiResponse::error(ApiError::invalid_input("Endpoint URL must start with https://"));
+    }
+    if let Err(message) = validation::check_url(&endpoint_url) {
+        return ApiResponse::error(ApiError::invalid_input(message));
+    }
+
+    let now = clock::now();
+    let config = match bi_export::get_config(request.org_id) {
+        Some(existing) => BiExportConfig {
+            endpoint_url,
+            is_active: request.is_active,
+            updated_at: now,
+            updated_by: caller,
+            ..existing
+        },
+        None => BiExportConfig {
+            org_id: request.org_id,
+            endpoint_url,
+            is_active: request.is_active,
+            next_push_at: now.saturating_add(bi_export::EXPORT_PERIOD_NS),
+            last_period_end: None,
+            created_at: now,
+            updated_at: now,
+            updated_by: caller,
+        },
+    };
+    bi_export::save_config(config.clone());
+    log_info!("[set_bi_export_config_v2] BI export for org {} updated by {}", request.org_id, caller);
+
+    ApiResponse::success(config)
+}
+
+#[query]
+pub fn get_bi_export_config_v2(org_id: Principal) -> ApiResponse<BiExportConfig> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    match bi_export::get_config(org_id) {
+        Some(config) => ApiResponse::success(config),
+        None => ApiResponse::error(ApiError::not_found("BI export is not configured for this organization")),
+    }
+}
+
+// Push everything since the last export right away instead of waiting for the schedule
+#[update]
+pub fn trigger_bi_export_v2(org_id: Principal) -> ApiResponse<BiExportDelivery> {
+    let _metrics = endpoint_metrics::track("trigger_bi_export_v2");
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let mut config = match bi_export::get_config(org_id) {
+        Some(config) => config,
+        None => return ApiResponse::error(ApiError::not_found("BI export is not configured for this organization")),
+    };
+
+    let now = clock::now();
+    let period_start = config.last_period_end.unwrap_or_else(|| now.saturating_sub(bi_export::EXPORT_PERIOD_NS));
+    let delivery = bi_export::start_export(&config, period_start, now);
+    config.last_period_end = Some(now);
+    config.next_push_at = now.saturating_add(bi_export::EXPORT_PERIOD_NS);
+    bi_export::save_config(config);
+
+    ApiResponse::success(delivery)
+}
+
+#[query]
+pub fn list_bi_export_deliveries_v2(request: ListBiExportDeliveriesRequest) -> ApiResponse<BiExportDeliveriesListResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), request.org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let (deliveries, pagination) = paginate(
+        bi_export::list_deliveries_for_org(request.org_id),
+        &request.pagination.unwrap_or_default(),
+    );
+
+    ApiResponse::success(BiExportDeliveriesListResponse { deliveries, pagination })
+}
+
+// ====== Print Jobs ======
+
+#[update]
+pub fn print_product_serial_numbers_bulk_v2(request: BulkPrintRequest) -> ApiResponse<BulkPrintResponse> {
+    let _metrics = endpoint_metrics::track("print_product_serial_numbers_bulk_v2");
+    let caller = api::caller();
+    log_info!("[print_product_serial_numbers_bulk_v2] Called by: {} for product: {} ({} serials)", caller, request.product_id, request.serial_nos.len());
+
+    if request.serial_nos.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one serial number is required"));
+    }
+    if request.serial_nos.len() > print_jobs::MAX_SERIALS_PER_PRINT_JOB {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A print job can contain at most {} serial numbers",
+            print_jobs::MAX_SERIALS_PER_PRINT_JOB
+        )));
+    }
+    let now = clock::now();
+    if let Some(activation_time) = request.activation_time.filter(|time| *time != 0) {
+        if activation_time <= now {
+            return ApiResponse::error(ApiError::invalid_input("Activation time must be in the future; leave it out to activate right away"));
+        }
+        if activation_time - now > print_jobs::MAX_ACTIVATION_DELAY_NANOS {
+            return ApiResponse::error(ApiError::invalid_input("Activation time can be at most two years ahead"));
+        }
+    }
+
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    // Brand members and capability token holders sign with the brand's key; members of a delegate
+    // manufacturer sign with their own
+    let organization = match authorize_product_operation(
+        caller,
+        &product,
+        Permission::WriteProduct,
+        CapabilityOperation::PrintSerials,
+        request.capability_token.as_deref(),
+    ) {
+        Ok(org) => org,
+        Err(e) if request.capability_token.is_some() => return ApiResponse::error(e),
+        Err(e) => match federation::active_delegation(product.id) {
+            Some(delegation) => match authorize_for_organization(caller, delegation.manufacturer_org_id, Permission::WriteProduct) {
+                Ok(manufacturer) => manufacturer,
+                Err(_) => return ApiResponse::error(e),
+            },
+            None => return ApiResponse::error(e),
+        },
+    };
+
+    // Every serial must belong to the product and be printable before anything is printed: each
+    // reprint below is stored as it goes, and a failure partway would leave earlier labels superseded
+    let requested_serials: std::collections::HashSet<Principal> = request.serial_nos.iter().copied().collect();
+    if requested_serials.len() != request.serial_nos.len() {
+        return ApiResponse::error(ApiError::invalid_input("Serial numbers in a print job must be unique"));
+    }
+    let mut serials = Vec::with_capacity(request.serial_nos.len());
+    for serial_no in &request.serial_nos {
+        match serial_store::get(product.id, *serial_no) {
+            Some(serial) if serial.effective_status() == SerialNumberStatus::Void => {
+                return ApiResponse::error(ApiError::invalid_input(&format!(
+                    "Serial number {} has been voided and cannot be printed",
+                    serial_no
+                )));
+            }
+            Some(serial) => serials.push(serial),
+            None => {
+                return ApiResponse::error(ApiError::not_found(&format!(
+                    "Serial number {} does not belong to product {}",
+                    serial_no, product.id
+                )));
+            }
+        }
+    }
+
+    let defaults = org_settings::serial_batch_defaults(product.org_id);
+    if request.code_policy.or(defaults.code_policy) == Some(PrintCodePolicy::UnprintedOnly) {
+        if let Some(printed) = serials.iter().find(|serial| serial.print_version > 0) {
+            return ApiResponse::error(ApiError::invalid_input(&format!(
+                "Serial number {} was printed before and the code policy only allows unprinted serials",
+                printed.serial_no
+            )));
+        }
+    }
+    // 0 overrides the organization's delay and activates right away
+    let activation_time = match request.activation_time {
+        Some(0) => None,
+        Some(time) => Some(time),
+        None => defaults
+            .activation_delay_seconds
+            .map(|delay| now.saturating_add(delay.saturating_mul(1_000_000_000))),
+    };
+
+    let label_template = label_templates::get_template(product.id)
+        .or_else(|| defaults.label_template_product_id.and_then(label_templates::get_template));
+
+    let signing_key = match keys::org_signing_key(&organization) {
+        Ok(key) => key,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let print_job_id = generate_unique_principal(product.id);
+    let mut codes = Vec::with_capacity(request.serial_nos.len());
+    for serial_no in &request.serial_nos {
+        match generate_and_store_unique_code_for_serial(product.id, *serial_no, &signing_key, Some(print_job_id)) {
+            Ok(record) => codes.push(record),
+            Err(e) => {
+                log_error!("[print_product_serial_numbers_bulk_v2] Failed to print serial {}: {:?}", serial_no, e);
+                return ApiResponse::error(e);
+            }
+        }
+    }
+
+    let print_job = PrintJob {
+        id: print_job_id,
+        org_id: product.org_id,
+        product_id: product.id,
+        entries: codes
+            .iter()
+            .map(|record| PrintJobEntry {
+                serial_no: record.serial_no,
+                print_version: record.print_version,
+            })
+            .collect(),
+        printer_metadata: request.printer_metadata,
+        // Defaults to the version of the product's stored template
+        label_template_version: request
+            .label_template_version
+            .or_else(|| label_template.as_ref().map(|template| template.version.to_string())),
+        activation_time,
+        created_at: now,
+        created_by: caller,
+    };
+    print_jobs::save_print_job(print_job.clone());
+    log_info!("[print_product_serial_numbers_bulk_v2] Print job {} recorded with {} serials", print_job.id, print_job.entries.len());
+
+    ApiResponse::success(BulkPrintResponse { print_job, codes, label_template })
+}
+
+#[query]
+pub fn list_print_jobs_v2(product_id: Principal) -> ApiResponse<Vec<PrintJob>> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(print_jobs::list_print_jobs_for_product(product_id))
+}
+
+// Every printed version of a serial's code, newest first, with who printed it and whether it was revoked
+#[query]
+pub fn list_serial_print_history(product_id: Principal, serial_no: Principal) -> ApiResponse<SerialPrintHistoryResponse> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    let serial = match serial_store::get(product_id, serial_no) {
+        Some(sn) => sn,
+        None => return ApiResponse::error(ApiError::not_found("Serial number not found for this product")),
+    };
+
+    let records = print_history::list_for_serial(serial_no);
+    let versions = (1..=serial.print_version)
+        .rev()
+        .map(|version| {
+            let record = records.iter().find(|r| r.print_version == version);
+            SerialPrintHistoryEntry {
+                print_version: version,
+                printed_at: record.map(|r| r.printed_at),
+                printed_by: record.map(|r| r.printed_by),
+                print_job_id: record.and_then(|r| r.print_job_id),
+                is_current: version == serial.print_version,
+                revocation: serial.revocation_for(version).cloned(),
+            }
+        })
+        .collect();
+
+    ApiResponse::success(SerialPrintHistoryResponse {
+        product_id,
+        serial_no,
+        current_print_version: serial.print_version,
+        versions,
+    })
+}
+
+// Let the codes of a print job verify now instead of at its scheduled activation time
+#[update]
+pub fn activate_print_job_v2(print_job_id: Principal) -> ApiResponse<PrintJob> {
+    let _metrics = endpoint_metrics::track("activate_print_job_v2");
+    let caller = api::caller();
+    let mut print_job = match print_jobs::get_print_job(print_job_id) {
+        Some(job) => job,
+        None => return ApiResponse::error(ApiError::not_found("Print job not found")),
+    };
+    if let Err(e) = authorize_for_organization(caller, print_job.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let now = clock::now();
+    if print_job.activation_time.is_some_and(|activation_time| activation_time > now) {
+        print_job.activation_time = Some(now);
+        print_jobs::save_print_job(print_job.clone());
+        log_info!("[activate_print_job_v2] Print job {} activated early by {}", print_job_id, caller);
+    }
+    ApiResponse::success(print_job)
+}
+
+// ====== Label Templates ======
+
+fn validate_label_template(request: &SetLabelTemplateRequest) -> Result<(), ApiError> {
+    let mut validator = Validator::new();
+    let max = label_templates::MAX_LABEL_DIMENSION_MM;
+    let in_range = |value: f64| value.is_finite() && value > 0.0 && value <= max;
+    if !in_range(request.width_mm) {
+        validator.add_error("width_mm", &format!("Must be greater than 0 and at most {} mm", max));
+    }
+    if !in_range(request.height_mm) {
+        validator.add_error("height_mm", &format!("Must be greater than 0 and at most {} mm", max));
+    }
+    if let Some(version) = request.qr_version {
+        if version == 0 || version > label_templates::MAX_QR_VERSION {
+            validator.add_error("qr_version", &format!("Must be between 1 and {}", label_templates::MAX_QR_VERSION));
+        }
+    }
+    let placement = &request.qr_placement;
+    if !placement.x_mm.is_finite() || !placement.y_mm.is_finite() || placement.x_mm < 0.0 || placement.y_mm < 0.0 {
+        validator.add_error("qr_placement", "Position must not be negative");
+    } else if !in_range(placement.size_mm) {
+        validator.add_error("qr_placement.size_mm", &format!("Must be greater than 0 and at most {} mm", max));
+    } else if placement.x_mm + placement.size_mm > request.width_mm || placement.y_mm + placement.size_mm > request.height_mm {
+        validator.add_error("qr_placement", "QR code must fit within the label");
+    }
+    // An empty artwork URL clears it
+    if let Some(url) = request.artwork_asset_url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        validator.url("artwork_asset_url", url);
+    }
+    validator.finish()
+}
+
+#[update]
+pub fn set_label_template_v2(request: SetLabelTemplateRequest) -> ApiResponse<LabelTemplate> {
+    let _metrics = endpoint_metrics::track("set_label_template_v2");
+    let caller = api::caller();
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    if let Err(e) = validate_label_template(&request) {
+        return ApiResponse::error(e);
+    }
+
+    let now = clock::now();
+    let existing = label_templates::get_template(product.id);
+    let template = LabelTemplate {
+        product_id: product.id,
+        org_id: product.org_id,
+        width_mm: request.width_mm,
+        height_mm: request.height_mm,
+        qr_version: request.qr_version,
+        qr_error_correction: request.qr_error_correction,
+        qr_placement: request.qr_placement,
+        show_human_readable_code: request.show_human_readable_code,
+        artwork_asset_url: request.artwork_asset_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty()),
+        version: existing.as_ref().map_or(1, |t| t.version.saturating_add(1)),
+        created_at: existing.as_ref().map_or(now, |t| t.created_at),
+        created_by: existing.as_ref().map_or(caller, |t| t.created_by),
+        updated_at: now,
+        updated_by: caller,
+    };
+    label_templates::save_template(template.clone());
+    log_info!("[set_label_template_v2] Label template of product {} set to version {} by {}", product.id, template.version, caller);
+
+    ApiResponse::success(template)
+}
+
+#[query]
+pub fn get_label_template_v2(product_id: Principal) -> ApiResponse<LabelTemplate> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    match label_templates::get_template(product_id) {
+        Some(template) => ApiResponse::success(template),
+        None => ApiResponse::error(ApiError::not_found("No label template is set for this product")),
+    }
+}
+
+#[update]
+pub fn delete_label_template_v2(product_id: Principal) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("delete_label_template_v2");
+    let caller = api::caller();
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    match label_templates::remove_template(product_id) {
+        Some(_) => {
+            log_info!("[delete_label_template_v2] Label template of product {} deleted by {}", product_id, caller);
+            ApiResponse::success(())
+        }
+        None => ApiResponse::error(ApiError::not_found("No label template is set for this product")),
+    }
+}
+
+// ====== Verification Delegation ======
+
+// Let a manufacturer organization sign codes for one of the brand's products
+#[update]
+pub fn delegate_verification_v2(request: DelegateVerificationRequest) -> ApiResponse<VerificationDelegation> {
+    let _metrics = endpoint_metrics::track("delegate_verification_v2");
+    let caller = api::caller();
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    if request.manufacturer_org_id == product.org_id {
+        return ApiResponse::error(ApiError::invalid_input("A product cannot be delegated to its own organization"));
+    }
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.manufacturer_org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Manufacturer organization not found"));
+    }
+    if let Some(existing) = federation::active_delegation(product.id) {
+        return ApiResponse::error(ApiError::already_exists(&format!(
+            "Product is already delegated to organization {}; revoke that delegation first",
+            existing.manufacturer_org_id
+        )));
+    }
+
+    let delegation = VerificationDelegation {
+        product_id: product.id,
+        brand_org_id: product.org_id,
+        manufacturer_org_id: request.manufacturer_org_id,
+        status: VerificationDelegationStatus::Active,
+        created_at: clock::now(),
+        created_by: caller,
+        revoked_at: None,
+        revoked_by: None,
+    };
+    federation::save_delegation(delegation.clone());
+    log_info!("[delegate_verification_v2] Product {} delegated to org {} by {}", product.id, request.manufacturer_org_id, caller);
+
+    ApiResponse::success(delegation)
+}
+
+// Revoking stops codes signed by the manufacturer from verifying
+#[update]
+pub fn revoke_verification_delegation_v2(product_id: Principal) -> ApiResponse<VerificationDelegation> {
+    let _metrics = endpoint_metrics::track("revoke_verification_delegation_v2");
+    let caller = api::caller();
+    let mut delegation = match federation::active_delegation(product_id) {
+        Some(d) => d,
+        None => return ApiResponse::error(ApiError::not_found("Product has no active delegation")),
+    };
+    if let Err(e) = authorize_for_organization(caller, delegation.brand_org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    delegation.status = VerificationDelegationStatus::Revoked;
+    delegation.revoked_at = Some(clock::now());
+    delegation.revoked_by = Some(caller);
+    federation::save_delegation(delegation.clone());
+    log_info!("[revoke_verification_delegation_v2] Delegation of product {} to org {} revoked by {}", product_id, delegation.manufacturer_org_id, caller);
+
+    ApiResponse::success(delegation)
+}
+
+// Delegations the organization granted (as brand) or received (as manufacturer)
+#[query]
+pub fn list_verification_delegations_v2(org_id: Principal) -> ApiResponse<Vec<VerificationDelegation>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(federation::list_for_org(org_id))
+}
+
+// ====== Product Variants ======
+
+// Serial numbers of the product that belong to the given variant
+fn variant_serial_numbers(product_id: Principal, variant_id: Principal) -> Vec<ProductSerialNumber> {
+    serial_store::list_for_product(product_id)
+        .into_iter()
+        .filter(|sn| sn.variant_id == Some(variant_id))
+        .collect()
+}
+
+fn validate_variant_sku(product_id: Principal, variant_id: Option<Principal>, sku: &Option<String>) -> Result<(), ApiError> {
+    let sku = match sku {
+        Some(sku) => sku.trim(),
+        None => return Ok(()),
+    };
+    if sku.is_empty() {
+        return Err(ApiError::invalid_input("SKU cannot be empty"));
+    }
+    let taken = variants::list_variants_for_product(product_id)
+        .iter()
+        .any(|v| Some(v.id) != variant_id && v.sku.as_deref() == Some(sku));
+    if taken {
+        return Err(ApiError::already_exists(&format!("SKU '{}' is already used by another variant of this product", sku)));
+    }
+    Ok(())
+}
+
+#[update]
+pub fn create_product_variant_v2(request: CreateProductVariantRequest) -> ApiResponse<ProductVariant> {
+    let _metrics = endpoint_metrics::track("create_product_variant_v2");
+    let caller = api::caller();
+
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("Variant name cannot be empty"));
+    }
+    if variants::list_variants_for_product(product.id).len() >= variants::MAX_VARIANTS_PER_PRODUCT {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A product can have at most {} variants",
+            variants::MAX_VARIANTS_PER_PRODUCT
+        )));
+    }
+    if let Err(e) = validate_variant_sku(product.id, None, &request.sku) {
+        return ApiResponse::error(e);
+    }
+
+    let now = clock::now();
+    let variant = ProductVariant {
+        id: generate_unique_principal(product.id),
+        product_id: product.id,
+        org_id: product.org_id,
+        name,
+        sku: request.sku.map(|sku| sku.trim().to_string()),
+        attributes: request.attributes,
+        created_at: now,
+        created_by: caller,
+        updated_at: now,
+        updated_by: caller,
+    };
+    variants::save_variant(variant.clone());
+    log_info!("[create_product_variant_v2] Variant {} created for product {}", variant.id, product.id);
+
+    ApiResponse::success(variant)
+}
+
+#[update]
+pub fn update_product_variant_v2(request: UpdateProductVariantRequest) -> ApiResponse<ProductVariant> {
+    let _metrics = endpoint_metrics::track("update_product_variant_v2");
+    let caller = api::caller();
+
+    let mut variant = match variants::get_variant(request.variant_id) {
+        Some(v) => v,
+        None => return ApiResponse::error(ApiError::not_found("Product variant not found")),
+    };
+
+    if let Err(e) = authorize_for_organization(caller, variant.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    if let Some(name) = request.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return ApiResponse::error(ApiError::invalid_input("Variant name cannot be empty"));
+        }
+        variant.name = name;
+    }
+    if request.sku.is_some() {
+        if let Err(e) = validate_variant_sku(variant.product_id, Some(variant.id), &request.sku) {
+            return ApiResponse::error(e);
+        }
+        variant.sku = request.sku.map(|sku| sku.trim().to_string());
+    }
+    if let Some(attributes) = request.attributes {
+        variant.attributes = attributes;
+    }
+    variant.updated_at = clock::now();
+    variant.updated_by = caller;
+
+    variants::save_variant(variant.clone());
+    ApiResponse::success(variant)
+}
+
+// Variants that already have serial numbers cannot be deleted, since printed labels refer to them
+#[update]
+pub fn delete_product_variant_v2(variant_id: Principal) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("delete_product_variant_v2");
+    let caller = api::caller();
+
+    let variant = match variants::get_variant(variant_id) {
+        Some(v) => v,
+        None => return ApiResponse::error(ApiError::not_found("Product variant not found")),
+    };
+
+    if let Err(e) = authorize_for_organization(caller, variant.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    if !variant_serial_numbers(variant.product_id, variant_id).is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("Cannot delete a variant that has serial numbers"));
+    }
+
+    variants::remove_variant(variant_id);
+    log_info!("[delete_product_variant_v2] Variant {} deleted by {}", variant_id, caller);
+
+    ApiResponse::success(())
+}
+
+#[query]
+pub fn list_product_variants_v2(product_id: Principal) -> ApiResponse<Vec<ProductVariant>> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(variants::list_variants_for_product(product_id))
+}
+
+#[update]
+pub fn create_variant_serial_number_v2(variant_id: Principal) -> ApiResponse<ProductSerialNumber> {
+    let _metrics = endpoint_metrics::track("create_variant_serial_number_v2");
+    let variant = match variants::get_variant(variant_id) {
+        Some(v) => v,
+        None => return ApiResponse::error(ApiError::not_found("Product variant not found")),
+    };
+
+    match create_serial_number_in_pool(variant.product_id, Some(variant_id)) {
+        Ok(serial_number) => ApiResponse::success(serial_number),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[query]
+pub fn list_variant_serial_numbers_v2(variant_id: Principal) -> ApiResponse<Vec<ProductSerialNumber>> {
+    let variant = match variants::get_variant(variant_id) {
+        Some(v) => v,
+        None => return ApiResponse::error(ApiError::not_found("Product variant not found")),
+    };
+
+    if let Err(e) = authorize_for_organization(api::caller(), variant.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(variant_serial_numbers(variant.product_id, variant_id))
+}
+
+// ====== Serial Ranges ======
+
+const MAX_SERIAL_BATCH_SIZE: u32 = 500;
+const MAX_USER_SERIAL_PREFIX_LENGTH: usize = 16;
+const USER_SERIAL_PAD_WIDTH: usize = 6; // "LOT7-" + 42 -> "LOT7-000042"
+
+// Serials created after this is enabled are numbered 1, 2, 3... per product; existing serials keep no number
+#[update]
+pub fn set_product_sequential_serials_v2(product_id: Principal, enabled: bool) -> ApiResponse<ProductResponse> {
+    let _metrics = endpoint_metrics::track("set_product_sequential_serials_v2");
+    let mut product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    product.sequential_serials = Some(enabled);
+    product.updated_at = clock::now();
+    product.updated_by = api::caller();
+    PRODUCTS.with(|products| products.borrow_mut().insert(product_id, product.clone()));
+    log_info!("Sequential serial numbering {} for product {}.", if enabled { "enabled" } else { "disabled" }, product_id);
+    ApiResponse::success(ProductResponse { product })
+}
+
+// Trimmed prefix for human-readable serials, None when blank
+fn normalize_user_serial_prefix(prefix: Option<&str>) -> Result<Option<&str>, ApiError> {
+    let prefix = prefix.map(str::trim).filter(|p| !p.is_empty());
+    if let Some(prefix) = prefix {
+        if prefix.chars().count() > MAX_USER_SERIAL_PREFIX_LENGTH {
+            return Err(ApiError::invalid_input(&format!(
+                "Serial prefix must be at most {} characters",
+                MAX_USER_SERIAL_PREFIX_LENGTH
+            )));
+        }
+        if !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ApiError::invalid_input("Serial prefix can only contain letters, digits, - and _"));
+        }
+    }
+    Ok(prefix)
+}
+
+// Create up to MAX_SERIAL_BATCH_SIZE serials at once, optionally with human-readable serials
+#[update]
+pub fn create_serial_numbers_batch_v2(request: CreateSerialNumbersBatchRequest) -> ApiResponse<SerialNumbersBatchResponse> {
+    let _metrics = endpoint_metrics::track("create_serial_numbers_batch_v2");
+    let caller = api::caller();
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_product_operation(
+        caller,
+        &product,
+        Permission::WriteProduct,
+        CapabilityOperation::CreateSerials,
+        request.capability_token.as_deref(),
+    ) {
+        return ApiResponse::error(e);
+    }
+    let defaults = org_settings::serial_batch_defaults(product.org_id);
+    let count = match request.count.or(defaults.batch_size) {
+        Some(count) => count,
+        None => return ApiResponse::error(ApiError::invalid_input("Count is required; the organization has no default batch size")),
+    };
+    if count == 0 || count > MAX_SERIAL_BATCH_SIZE {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Count must be between 1 and {}",
+            MAX_SERIAL_BATCH_SIZE
+        )));
+    }
+    if let Some(variant_id) = request.variant_id {
+        if variants::get_variant(variant_id).is_none_or(|v| v.product_id != product.id) {
+            return ApiResponse::error(ApiError::not_found("Product variant not found"));
+        }
+    }
+    let prefix = match normalize_user_serial_prefix(request.user_serial_prefix.as_deref().or(defaults.user_serial_prefix.as_deref())) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    if let Err(e) = quotas::ensure_storage_available(product.org_id) {
+        return ApiResponse::error(e);
+    }
+    if let Err(e) = quotas::consume_serials(product.org_id, count) {
+        return ApiResponse::error(e);
+    }
+
+    let mut serial_numbers = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        match store_new_serial_number(&product, request.variant_id, prefix, caller) {
+            Ok(serial) => serial_numbers.push(serial),
+            Err(e) => {
+                log_error!("[create_serial_numbers_batch_v2] Stopped after {} of {} serials for product {}: {:?}", serial_numbers.len(), count, product.id, e);
+                return ApiResponse::error(e);
+            }
+        }
+    }
+    log_info!("[create_serial_numbers_batch_v2] {} serial(s) created for product {} by {}", serial_numbers.len(), product.id, caller);
+
+    ApiResponse::success(SerialNumbersBatchResponse { serial_numbers })
+}
+
+#[query]
+pub fn find_serial_by_user_serial(product_id: Principal, user_serial_no: String) -> ApiResponse<ProductSerialNumber> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    match serial_store::find_by_user_serial(product_id, user_serial_no.trim()) {
+        Some(serial) => ApiResponse::success(serial),
+        None => ApiResponse::error(ApiError::not_found("No serial with this number exists for the product")),
+    }
+}
+
+// How much of a printed range has been scanned, as a sell-through estimate for the lot
+#[query]
+pub fn get_stats_for_serial_range(product_id: Principal, from_seq: u64, to_seq: u64) -> ApiResponse<SerialRangeStatsResponse> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    if from_seq > to_seq {
+        return ApiResponse::error(ApiError::invalid_input("from_seq must not be greater than to_seq"));
+    }
+
+    let in_range: std::collections::HashSet<Principal> = serial_store::list_sequence_range(product_id, from_seq, to_seq)
+        .into_iter()
+        .map(|sn| sn.serial_no)
+        .collect();
+    let mut scanned: std::collections::HashSet<Principal> = std::collections::HashSet::new();
+    let mut total_verifications = 0u64;
+    let mut first_scan_at: Option<u64> = None;
+    let mut last_scan_at: Option<u64> = None;
+    for verification in verification_store::list_for_product(product_id) {
+        if !in_range.contains(&verification.serial_no) {
+            continue;
+        }
+        scanned.insert(verification.serial_no);
+        total_verifications += 1;
+        first_scan_at = Some(first_scan_at.map_or(verification.created_at, |t| t.min(verification.created_at)));
+        last_scan_at = Some(last_scan_at.map_or(verification.created_at, |t| t.max(verification.created_at)));
+    }
+
+    let serials_in_range = in_range.len() as u64;
+    let scanned_serials = scanned.len() as u64;
+    let coverage_percent = if serials_in_range == 0 {
+        0.0
+    } else {
+        scanned_serials as f64 * 100.0 / serials_in_range as f64
+    };
+    ApiResponse::success(SerialRangeStatsResponse {
+        product_id,
+        from_seq,
+        to_seq,
+        serials_in_range,
+        scanned_serials,
+        coverage_percent,
+        total_verifications,
+        first_scan_at,
+        last_scan_at,
+    })
+}
+
+// ====== Serial Number Lifecycle ======
+
+// Move a set of a product's serials to `target`. All transitions are validated before any is applied.
+fn transition_serial_numbers(
+    caller: Principal,
+    request: UpdateSerialStatusRequest,
+    target: SerialNumberStatus,
+) -> ApiResponse<SerialStatusUpdateResponse> {
+    if request.serial_nos.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one serial number is required"));
+    }
+
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let authorized = match request.capability_token.as_deref() {
+        Some(_) if target == SerialNumberStatus::Void => {
+            Err(ApiError::unauthorized("Capability tokens cannot void serial numbers"))
+        }
+        token => authorize_product_operation(caller, &product, Permission::WriteProduct, CapabilityOperation::UpdateSerialStatus, token),
+    };
+    if let Err(e) = authorized {
+        return ApiResponse::error(e);
+    }
+
+    let mut to_update: Vec<ProductSerialNumber> = Vec::with_capacity(request.serial_nos.len());
+    for serial_no in &request.serial_nos {
+        let sn = match serial_store::get(product.id, *serial_no) {
+            Some(sn) => sn,
+            None => return ApiResponse::error(ApiError::not_found(&format!(
+                "Serial number {} does not belong to product {}",
+                serial_no, product.id
+            ))),
+        };
+        let current = sn.effective_status();
+        if !current.can_transition_to(target) {
+            return ApiResponse::error(ApiError::invalid_input(&format!(
+                "Serial number {} cannot move from {:?} to {:?}",
+                serial_no, current, target
+            )));
+        }
+        if !to_update.iter().any(|queued| queued.serial_no == sn.serial_no) {
+            to_update.push(sn);
+        }
+    }
+
+    let now = clock::now();
+    let mut updated = Vec::with_capacity(to_update.len());
+    for mut sn in to_update {
+        sn.status = Some(target);
+        if let Some(reason) = &request.reason {
+            sn.metadata.push(Metadata {
+                key: format!("status_reason_{:?}", target).to_lowercase(),
+                value: reason.clone(),
+            });
+        }
+        sn.updated_at = now;
+        sn.updated_by = caller;
+        serial_store::insert(sn.clone());
+        updated.push(sn);
+    }
+    log_info!("[transition_serial_numbers] {} serial(s) of product {} moved to {:?} by {}", updated.len(), product.id, target, caller);
+
+    ApiResponse::success(SerialStatusUpdateResponse { serial_numbers: updated })
+}
+
+#[update]
+pub fn mark_serials_shipped(request: UpdateSerialStatusRequest) -> ApiResponse<SerialStatusUpdateResponse> {
+    let _metrics = endpoint_metrics::track("mark_serials_shipped");
+    transition_serial_numbers(api::caller(), request, SerialNumberStatus::Shipped)
+}
+
+#[update]
+pub fn mark_serials_sold(request: UpdateSerialStatusRequest) -> ApiResponse<SerialStatusUpdateResponse> {
+    let _metrics = endpoint_metrics::track("mark_serials_sold");
+    transition_serial_numbers(api::caller(), request, SerialNumberStatus::Sold)
+}
+
+#[update]
+pub fn void_serial_numbers(request: UpdateSerialStatusRequest) -> ApiResponse<SerialStatusUpdateResponse> {
+    let _metrics = endpoint_metrics::track("void_serial_numbers");
+    transition_serial_numbers(api::caller(), request, SerialNumberStatus::Void)
+}
+
+// Kill the currently printed code of each serial, e.g. after a label roll is stolen. All serials are
+// checked before any is revoked. Reprinting a serial afterwards issues a new, valid code.
+#[update]
+pub fn revoke_unique_codes_v2(
+    product_id: Principal,
+    serial_nos: Vec<Principal>,
+    reason: CodeRevocationReason,
+) -> ApiResponse<SerialStatusUpdateResponse> {
+    let _metrics = endpoint_metrics::track("revoke_unique_codes_v2");
+    let caller = api::caller();
+    if serial_nos.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one serial number is required"));
+    }
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let mut to_revoke: Vec<ProductSerialNumber> = Vec::with_capacity(serial_nos.len());
+    for serial_no in &serial_nos {
+        let sn = match serial_store::get(product_id, *serial_no) {
+            Some(sn) => sn,
+            None => return ApiResponse::error(ApiError::not_found(&format!("Serial number {} not found for this product", serial_no))),
+        };
+        if sn.print_version == 0 {
+            return ApiResponse::error(ApiError::invalid_input(&format!("Serial number {} has not been printed", serial_no)));
+        }
+        if sn.revocation_for(sn.print_version).is_some() {
+            return ApiResponse::error(ApiError::invalid_input(&format!(
+                "Code version {} of serial number {} is already revoked",
+                sn.print_version, serial_no
+            )));
+        }
+        if !to_revoke.iter().any(|queued| queued.serial_no == sn.serial_no) {
+            to_revoke.push(sn);
+        }
+    }
+
+    let now = clock::now();
+    let mut revoked = Vec::with_capacity(to_revoke.len());
+    for mut sn in to_revoke {
+        sn.revoked_codes.get_or_insert_with(Vec::new).push(CodeRevocation {
+            print_version: sn.print_version,
+            reason,
+            revoked_at: now,
+            revoked_by: caller,
+        });
+        sn.updated_at = now;
+        sn.updated_by = caller;
+        serial_store::insert(sn.clone());
+        revoked.push(sn);
+    }
+
+    for sn in &revoked {
+        audit_log::record(AuditLogEntry {
+            user_id: caller,
+            action: "RevokeUniqueCode".to_string(),
+            resource_type: "Product".to_string(),
+            resource_id: product_id,
+            timestamp: now,
+            metadata: vec![
+                Metadata { key: "serial_no".to_string(), value: sn.serial_no.to_string() },
+                Metadata { key: "print_version".to_string(), value: sn.print_version.to_string() },
+                Metadata { key: "reason".to_string(), value: format!("{:?}", reason) },
+            ],
+            success: true,
+        });
+    }
+    analytics::on_codes_revoked(product.org_id, revoked.len() as u64);
+    log_info!("[revoke_unique_codes_v2] {} code(s) of product {} revoked by {} ({:?})", revoked.len(), product_id, caller, reason);
+
+    ApiResponse::success(SerialStatusUpdateResponse { serial_numbers: revoked })
+}
+
+// Recorded changes to a product (such as code revocations), newest first
+#[query]
+pub fn list_product_audit_log_v2(
+    product_id: Principal,
+    pagination: Option<PaginationRequest>,
+) -> ApiResponse<AuditLogListResponse> {
+    let product = match get_product(&product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), product.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+
+    let entries = audit_log::list_for_resource("Product", product_id);
+    let (entries, page_info) = paginate(entries, &pagination.unwrap_or_default());
+    ApiResponse::success(AuditLogListResponse { entries, pagination: Some(page_info) })
+}
+
+// Lightweight alternative to verify_product_v2 for high-frequency polling: no auth, rewards or writes.
+// An unknown serial is a successful answer with `exists: false`. Being a query it cannot count lookups,
+// but principals banned for probing get no answer.
+#[query]
+pub fn check_serial_status(serial_no: Principal) -> ApiResponse<SerialStatusCheckResponse> {
+    if let Err(e) = probe_guard::ensure_not_banned(api::caller(), clock::now()) {
+        return ApiResponse::error(e);
+    }
+    let (product_id, serial) = match find_serial_number(serial_no) {
+        Ok(found) => found,
+        Err(_) => {
+            return ApiResponse::success(SerialStatusCheckResponse {
+                serial_no,
+                exists: false,
+                print_version: None,
+                status: None,
+                voided: false,
+                code_revoked: false,
+                suspected_cloned: false,
+                discontinued: false,
+                product_id: None,
+                product_name: None,
+                org_id: None,
+                org_name: None,
+            })
+        }
+    };
+    let product = PRODUCTS.with(|products| products.borrow().get(&product_id));
+    let org_name = product
+        .as_ref()
+        .and_then(|p| ORGANIZATIONS.with(|orgs| orgs.borrow().get(&p.org_id)))
+        .map(|org| org.name);
+
+    let status = serial.effective_status();
+    let custody_visible = product
+        .as_ref()
+        .is_none_or(|p| org_settings::verification_privacy(p.org_id).show_custody_chain);
+    ApiResponse::success(SerialStatusCheckResponse {
+        serial_no,
+        exists: true,
+        print_version: Some(serial.print_version),
+        status: Some(status).filter(|_| custody_visible),
+        voided: status == SerialNumberStatus::Void,
+        code_revoked: serial.revocation_for(serial.print_version).is_some(),
+        suspected_cloned: serial.suspected_cloned.unwrap_or(false),
+        discontinued: product.as_ref().is_some_and(|p| p.discontinued_at.is_some()),
+        product_id: Some(product_id),
+        product_name: product.as_ref().map(|p| p.name.clone()),
+        org_id: product.as_ref().map(|p| p.org_id),
+        org_name,
+    })
+}
+
+// ====== Suspicious Activity ======
+
+// Serials flagged as cloned and lookups of the brand's serials by principals guessing serial numbers
+#[query]
+pub fn get_suspicious_activity_v2(org_id: Principal, pagination: Option<PaginationRequest>) -> ApiResponse<SuspiciousActivityResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
+    });
+    let suspected: Vec<ProductSerialNumber> = product_ids
+        .into_iter()
+        .flat_map(serial_store::list_for_product)
+        .filter(|sn| sn.suspected_cloned == Some(true))
+        .collect();
+    let (suspected_cloned_serials, page_info) = paginate(suspected, &pagination.unwrap_or_default());
+
+    ApiResponse::success(SuspiciousActivityResponse {
+        suspected_cloned_serials,
+        probe_activity: probe_guard::org_activity(org_id),
+        pagination: Some(page_info),
+    })
+}
+
+#[query]
+pub fn list_probe_bans_v2() -> ApiResponse<Vec<ProbeRecord>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(probe_guard::list_banned(clock::now()))
+}
+
+#[update]
+pub fn lift_probe_ban_v2(principal: Principal) -> ApiResponse<ProbeRecord> {
+    let _metrics = endpoint_metrics::track("lift_probe_ban_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    match probe_guard::lift_ban(principal, clock::now()) {
+        Some(record) => {
+            log_info!("[lift_probe_ban_v2] {} lifted the probe ban of {}", caller, principal);
+            ApiResponse::success(record)
+        }
+        None => ApiResponse::error(ApiError::not_found("No probe record for this principal")),
+    }
+}
+
+// ====== Metadata Schemas ======
+
+// Define or replace the metadata rules of a product category. Products created or updated in the
+// category, and serial metadata set through set_serial_metadata_v2, are validated against them.
+#[update]
+pub fn set_metadata_schema_v2(request: SetMetadataSchemaRequest) -> ApiResponse<MetadataSchema> {
+    let _metrics = endpoint_metrics::track("set_metadata_schema_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    let mut validator = Validator::new();
+    let category = validator.required_text("category", &request.category, validation::MAX_NAME_LENGTH);
+    metadata_schemas::check_definition(&mut validator, "product_fields", &request.product_fields);
+    metadata_schemas::check_definition(&mut validator, "serial_fields", &request.serial_fields);
+    if metadata_schemas::category_key(&category).is_empty() && !category.is_empty() {
+        validator.add_error("category", "Must contain at least one letter or digit");
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    let trim_keys = |fields: Vec<MetadataFieldSchema>| -> Vec<MetadataFieldSchema> {
+        fields
+            .into_iter()
+            .map(|field| MetadataFieldSchema { key: field.key.trim().to_string(), ..field })
+            .collect()
+    };
+    let now = clock::now();
+    let schema = MetadataSchema {
+        org_id: request.org_id,
+        category,
+        product_fields: trim_keys(request.product_fields),
+        serial_fields: trim_keys(request.serial_fields),
+        updated_at: now,
+        updated_by: caller,
+    };
+    metadata_schemas::save_schema(schema.clone());
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "SetMetadataSchema".to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: request.org_id,
+        timestamp: now,
+        metadata: vec![Metadata { key: "category".to_string(), value: schema.category.clone() }],
+        success: true,
+    });
+    ApiResponse::success(schema)
+}
+
+#[query]
+pub fn list_metadata_schemas_v2(org_id: Principal) -> ApiResponse<Vec<MetadataSchema>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(metadata_schemas::list_for_org(org_id))
+}
+
+// Existing products and serials keep their metadata; the category simply stops being validated
+#[update]
+pub fn delete_metadata_schema_v2(org_id: Principal, category: String) -> ApiResponse<()> {
+    let _metrics = endpoint_metrics::track("delete_metadata_schema_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if metadata_schemas::remove_schema(org_id, &category).is_none() {
+        return ApiResponse::error(ApiError::not_found("No metadata schema for this category"));
+    }
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "DeleteMetadataSchema".to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: org_id,
+        timestamp: clock::now(),
+        metadata: vec![Metadata { key: "category".to_string(), value: category }],
+        success: true,
+    });
+    ApiResponse::success(())
+}
+
+// Entries replace those of the serial with the same key; other keys are kept. The result must
+// satisfy the serial rules of the product category's schema. Serials are created with empty
+// metadata, so required serial fields are only enforced from their first metadata update on.
+#[update]
+pub fn set_serial_metadata_v2(request: SetSerialMetadataRequest) -> ApiResponse<ProductSerialNumber> {
+    let _metrics = endpoint_metrics::track("set_serial_metadata_v2");
+    let caller = api::caller();
+    let product = match get_product(&request.product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    let serial = match serial_store::get(product.id, request.serial_no) {
+        Some(sn) => sn,
+        None => return ApiResponse::error(ApiError::not_found("Serial number not found for this product")),
+    };
+    let mut validator = Validator::new();
+    validator.metadata("metadata", &request.metadata);
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+
+    let mut metadata = serial.metadata;
+    for entry in request.metadata {
+        let entry = Metadata { key: entry.key.trim().to_string(), value: entry.value };
+        match metadata.iter_mut().find(|m| m.key == entry.key) {
+            Some(existing) => existing.value = entry.value,
+            None => metadata.push(entry),
+        }
+    }
+    if let Err(e) = metadata_schemas::validate_serial_metadata(product.org_id, &product.category, &metadata) {
+        return ApiResponse::error(e);
+    }
+
+    match serial_store::update(product.id, request.serial_no, |sn| {
+        sn.metadata = metadata;
+        sn.updated_at = clock::now();
+        sn.updated_by = caller;
+    }) {
+        Some(updated) => ApiResponse::success(updated),
+        None => ApiResponse::error(ApiError::not_found("Serial number not found for this product")),
+    }
+}
+
+// ====== Product Bundles ======
+
+// Pack units into a multipack: verifying the outer code then reports the validity of every unit
+#[update]
+pub fn create_bundle_v2(request: CreateBundleRequest) -> ApiResponse<BundleContentsResponse> {
+    let _metrics = endpoint_metrics::track("create_bundle_v2");
+    let caller = api::caller();
+    let outer_product = match get_product(&request.outer_product_id) {
+        Ok(p) => p,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = authorize_for_organization(caller, outer_product.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    let outer_serial_no = request.outer_serial_no;
+    match serial_store::get(outer_product.id, outer_serial_no) {
+        Some(sn) if sn.effective_status() == SerialNumberStatus::Void => {
+            return ApiResponse::error(ApiError::invalid_input("A voided serial number cannot be a bundle"))
+        }
+        Some(_) => {}
+        None => return ApiResponse::error(ApiError::not_found("Outer serial number not found for this product")),
+    }
+    if request.member_serial_nos.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("A bundle needs at least one unit"));
+    }
+    if request.member_serial_nos.len() > bundles::MAX_BUNDLE_MEMBERS {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A bundle can contain at most {} units",
+            bundles::MAX_BUNDLE_MEMBERS
+        )));
+    }
+    if bundles::get_bundle(outer_serial_no).is_some() {
+        return ApiResponse::error(ApiError::already_exists("This serial number is already a bundle"));
+    }
+    if bundles::bundle_of_member(outer_serial_no).is_some() {
+        return ApiResponse::error(ApiError::invalid_input("A unit inside a bundle cannot be a bundle itself"));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut members = Vec::with_capacity(request.member_serial_nos.len());
+    for serial_no in request.member_serial_nos {
+        if serial_no == outer_serial_no {
+            return ApiResponse::error(ApiError::invalid_input("The outer serial number cannot be one of its units"));
+        }
+        if !seen.insert(serial_no) {
+            return ApiResponse::error(ApiError::invalid_input(&format!("Serial number {} is listed twice", serial_no)));
+        }
+        let (product_id, sn) = match serial_store::find(serial_no) {
+            Some(found) => found,
+            None => return ApiResponse::error(ApiError::not_found(&format!("Serial number {} not found", serial_no))),
+        };
+        match get_product(&product_id) {
+            Ok(product) if product.org_id == outer_product.org_id => {}
+            Ok(_) => {
+                return ApiResponse::error(ApiError::unauthorized(&format!(
+                    "Serial number {} belongs to another organization",
+                    serial_no
+                )))
+            }
+            Err(e) => return ApiResponse::error(e),
+        }
+        if sn.effective_status() == SerialNumberStatus::Void {
+            return ApiResponse::error(ApiError::invalid_input(&format!("Serial number {} is voided", serial_no)));
+        }
+        if bundles::bundle_of_member(serial_no).is_some() {
+            return ApiResponse::error(ApiError::already_exists(&format!("Serial number {} is already in a bundle", serial_no)));
+        }
+        if bundles::get_bundle(serial_no).is_some() {
+            return ApiResponse::error(ApiError::invalid_input(&format!("Serial number {} is itself a bundle", serial_no)));
+        }
+        members.push(BundleMember { product_id, serial_no });
+    }
+
+    let bundle = Bundle {
+        outer_product_id: outer_product.id,
+        outer_serial_no,
+        org_id: outer_product.org_id,
+        members,
+        created_at: clock::now(),
+        created_by: caller,
+    };
+    bundles::save_bundle(bundle.clone());
+    log_info!("Bundle {} created with {} unit(s) by {}.", outer_serial_no, bundle.members.len(), caller);
+    ApiResponse::success(BundleContentsResponse {
+        summary: bundles::summarize(&bundle),
+        bundle,
+    })
+}
+
+// Units packed under an outer code, with their current validity
+#[query]
+pub fn get_bundle_contents(serial_no: Principal) -> ApiResponse<BundleContentsResponse> {
+    match bundles::get_bundle(serial_no) {
+        Some(bundle) => ApiResponse::success(BundleContentsResponse {
+            summary: bundles::summarize(&bundle),
+            bundle,
+        }),
+        None => ApiResponse::error(ApiError::not_found("No bundle has this outer serial number")),
+    }
+}
+
+// ====== User Administration ======
+
+fn matches_user_filter(user: &User, filter: &UserListFilter, query: Option<&str>) -> bool {
+    if filter.role.as_ref().is_some_and(|role| user.user_role.as_ref() != Some(role)) {
+        return false;
+    }
+    if filter.is_enabled.is_some_and(|enabled| user.is_enabled != enabled) {
+        return false;
+    }
+    if filter.org_id.is_some_and(|org_id| !user.org_ids.contains(&org_id)) {
+        return false;
+    }
+    query.is_none_or(|query| {
+        [&user.first_name, &user.last_name, &user.email]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(query))
+            || user.id.to_text().contains(query)
+    })
+}
+
+#[query]
+pub fn list_users_v2(filter: Option<UserListFilter>, pagination: Option<PaginationRequest>) -> ApiResponse<UsersListResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+
+    let filter = filter.unwrap_or_default();
+    let query = filter.query.as_deref().map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+    let mut users: Vec<User> = USERS.with(|users| {
+        users
+            .borrow()
+            .iter()
+            .map(|(_, user)| user)
+            .filter(|user| matches_user_filter(user, &filter, query.as_deref()))
+            .collect()
+    });
+    users.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+    let (page_items, page_info) = paginate(users, &pagination.unwrap_or_default());
+    ApiResponse::success(UsersListResponse {
+        users: page_items,
+        pagination: page_info,
+    })
+}
+
+// Apply an admin change to another user's account and record it in the audit log
+fn admin_update_user<F>(action: &str, user_id: Principal, detail: Metadata, update: F) -> ApiResponse<User>
+where
+    F: FnOnce(&mut User),
+{
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    if user_id == caller {
+        return ApiResponse::error(ApiError::invalid_input("Admins cannot change their own account this way"));
+    }
+    let mut user = match USERS.with(|users| users.borrow().get(&user_id)) {
+        Some(user) => user,
+        None => return ApiResponse::error(ApiError::not_found("User not found")),
+    };
+
+    let now = clock::now();
+    update(&mut user);
+    user.updated_at = now;
+    user.updated_by = caller;
+    USERS.with(|users| users.borrow_mut().insert(user_id, user.clone()));
+
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: action.to_string(),
+        resource_type: "User".to_string(),
+        resource_id: user_id,
+        timestamp: now,
+        metadata: vec![detail],
+        success: true,
+    });
+    log_info!("[admin_update_user] {} applied to user {} by {}", action, user_id, caller);
+
+    ApiResponse::success(user)
+}
+
+// Disabled users keep their data but fail every permission check, including verifying as themselves
+#[update]
+pub fn disable_user_v2(user_id: Principal) -> ApiResponse<User> {
+    let _metrics = endpoint_metrics::track("disable_user_v2");
+    admin_update_user(
+        "DisableUser",
+        user_id,
+        Metadata { key: "is_enabled".to_string(), value: "false".to_string() },
+        |user| user.is_enabled = false,
+    )
+}
+
+#[update]
+pub fn enable_user_v2(user_id: Principal) -> ApiResponse<User> {
+    let _metrics = endpoint_metrics::track("enable_user_v2");
+    admin_update_user(
+        "EnableUser",
+        user_id,
+        Metadata { key: "is_enabled".to_string(), value: "true".to_string() },
+        |user| user.is_enabled = true,
+    )
+}
+
+#[update]
+pub fn set_user_role_admin_v2(user_id: Principal, role: UserRole) -> ApiResponse<User> {
+    let _metrics = endpoint_metrics::track("set_user_role_admin_v2");
+    admin_update_user(
+        "SetUserRole",
+        user_id,
+        Metadata { key: "user_role".to_string(), value: format!("{:?}", role) },
+        |user| user.user_role = Some(role),
+    )
+}
+
+// ====== Feature Flags ======
+
+#[update]
+pub fn set_feature_flag_v2(request: SetFeatureFlagRequest) -> ApiResponse<FeatureFlagStatus> {
+    let _metrics = endpoint_metrics::track("set_feature_flag_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+
+    let flag = match feature_flags::normalize_flag_name(&request.flag) {
+        Ok(flag) => flag,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    match request.org_id {
+        Some(org_id) => {
+            if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)).is_none() {
+                return ApiResponse::error(ApiError::not_found("Organization not found"));
+            }
+            feature_flags::set_org_override(org_id, &flag, request.enabled);
+        }
+        None => feature_flags::set_global_flag(&flag, request.enabled),
+    }
+    log_info!("[set_feature_flag_v2] Flag '{}' set to {} (org: {:?}) by {}", flag, request.enabled, request.org_id, caller);
+
+    ApiResponse::success(feature_flag_status(&flag, request.org_id))
+}
+
+#[update]
+pub fn clear_feature_flag_override_v2(flag: String, org_id: Principal) -> ApiResponse<FeatureFlagStatus> {
+    let _metrics = endpoint_metrics::track("clear_feature_flag_override_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+
+    let flag = match feature_flags::normalize_flag_name(&flag) {
+        Ok(flag) => flag,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if feature_flags::clear_org_override(org_id, &flag).is_none() {
+        return ApiResponse::error(ApiError::not_found("No override set for this organization"));
+    }
+    log_info!("[clear_feature_flag_override_v2] Override for '{}' on org {} cleared by {}", flag, org_id, caller);
+
+    ApiResponse::success(feature_flag_status(&flag, Some(org_id)))
+}
+
+// Admins see every flag; organization members may list the flags in effect for their organization
+#[query]
+pub fn list_feature_flags_v2(org_id: Option<Principal>) -> ApiResponse<Vec<FeatureFlagStatus>> {
+    let caller = api::caller();
+    let authorized = match org_id {
+        Some(org) => authorize_for_organization(caller, org, Permission::ReadOrganization).map(|_| ()),
+        None => ensure_admin(caller),
+    };
+    if let Err(e) = authorized {
+        return ApiResponse::error(e);
+    }
+
+    let mut names: Vec<String> = feature_flags::list_global_flags().into_iter().map(|f| f.name).collect();
+    if let Some(org) = org_id {
+        names.extend(feature_flags::list_org_overrides(org).into_iter().map(|f| f.name));
+    }
+    names.sort();
+    names.dedup();
+
+    ApiResponse::success(names.iter().map(|name| feature_flag_status(name, org_id)).collect())
+}
+
+fn feature_flag_status(flag: &str, org_id: Option<Principal>) -> FeatureFlagStatus {
+    FeatureFlagStatus {
+        name: flag.to_string(),
+        global_enabled: feature_flags::get_global_flag(flag),
+        org_override: org_id.and_then(|org| feature_flags::get_org_override(org, flag)),
+        effective: feature_flags::is_enabled(flag, org_id),
+    }
+}
+
+// ====== Organization Quotas ======
+
+#[update]
+pub fn set_org_quota_v2(request: SetOrgQuotaRequest) -> ApiResponse<OrgQuota> {
+    let _metrics = endpoint_metrics::track("set_org_quota_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Organization not found"));
+    }
+
+    let quota = OrgQuota {
+        org_id: request.org_id,
+        max_outcalls_per_day: request.max_outcalls_per_day,
+        max_serials_per_month: request.max_serials_per_month,
+        max_storage_bytes: request.max_storage_bytes,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+    quotas::save_quota(quota.clone());
+    log_info!("[set_org_quota_v2] Quota for org {} updated by {}", request.org_id, caller);
+
+    ApiResponse::success(quota)
+}
+
+#[query]
+pub fn get_org_usage_v2(org_id: Principal) -> ApiResponse<OrgUsageResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+
+    ApiResponse::success(quotas::get_usage(org_id))
+}
+
+// ====== Subscription Plans ======
+
+#[update]
+pub fn change_org_plan_v2(org_id: Principal, tier: PlanTier) -> ApiResponse<OrgPlan> {
+    let _metrics = endpoint_metrics::track("change_org_plan_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Organization not found"));
+    }
+    let previous = plans::get_plan(org_id).tier;
+    let result = plans::change_tier(org_id, tier, caller);
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "ChangeOrgPlan".to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: org_id,
+        timestamp: clock::now(),
+        metadata: vec![
+            Metadata { key: "from".to_string(), value: format!("{:?}", previous) },
+            Metadata { key: "to".to_string(), value: format!("{:?}", tier) },
+        ],
+        success: result.is_ok(),
+    });
+    match result {
+        Ok(plan) => ApiResponse::success(plan),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Billing state of the plan; see PlanStatus for the allowed transitions
+#[update]
+pub fn set_org_plan_status_v2(org_id: Principal, status: PlanStatus) -> ApiResponse<OrgPlan> {
+    let _metrics = endpoint_metrics::track("set_org_plan_status_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)).is_none() {
+        return ApiResponse::error(ApiError::not_found("Organization not found"));
+    }
+    let previous = plans::get_plan(org_id).status;
+    let result = plans::change_status(org_id, status, caller);
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "SetOrgPlanStatus".to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: org_id,
+        timestamp: clock::now(),
+        metadata: vec![
+            Metadata { key: "from".to_string(), value: format!("{:?}", previous) },
+            Metadata { key: "to".to_string(), value: format!("{:?}", status) },
+        ],
+        success: result.is_ok(),
     });
+    match result {
+        Ok(plan) => ApiResponse::success(plan),
+        Err(e) => ApiResponse::error(e),
+    }
+}
 
-    let mut all_verification_details = Vec::new();
+// Plan of one of the caller's organizations with its usage against the limits
+#[query]
+pub fn get_my_plan_v2(org_id: Principal) -> ApiResponse<PlanUsageResponse> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(plans::get_usage(org_id))
+}
 
-    // Pre-fetch user emails into a HashMap to avoid multiple reads inside the loop
-    let user_emails: std::collections::HashMap<Principal, Option<String>> = USERS.with(|users_store| {
-        users_store
-            .borrow()
-            .iter()
-            .map(|(id, user)| (id, user.email.clone()))
-            .collect()
-    });
+// ====== Verification Retention ======
 
-    PRODUCT_VERIFICATIONS.with(|verifications_store| {
-        let store = verifications_store.borrow();
-        for (product_id, product) in products_in_org {
-            if let Some(serialized_verifications) = store.get(&product_id) {
-                let decoded_verifications = decode_product_verifications(&serialized_verifications);
-                
-                for verification in decoded_verifications {
-                    // Find the user who created the verification using the pre-fetched map
-                    // .cloned() on Option<&V> (where V=Option<String>) gives Option<Option<String>>
-                    // .flatten() on Option<Option<String>> gives Option<String>
-                    let user_email = user_emails.get(&verification.created_by).cloned().flatten();
-
-                    let detail = ProductVerificationDetail {
-                        user_email,
-                        product_id: verification.product_id,
-                        product_name: product.name.clone(), // Use product name from fetched products
-                        serial_no: verification.serial_no,
-                        created_at: verification.created_at,
-                        status: verification.status.clone(), // Populate the new status field
-                    };
-                    all_verification_details.push(detail);
+#[query]
+pub fn get_verification_retention_policy_v2(org_id: Principal) -> ApiResponse<VerificationRetentionPolicy> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(verification_retention::get_policy(org_id))
+}
+
+// Raw verifications can be kept for at most as long as the plan retains analytics
+#[update]
+pub fn set_verification_retention_policy_v2(request: SetVerificationRetentionRequest) -> ApiResponse<VerificationRetentionPolicy> {
+    let _metrics = endpoint_metrics::track("set_verification_retention_policy_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if let Some(months) = request.retention_months {
+        let max_months = verification_retention::max_retention_months(request.org_id);
+        if months == 0 || months > max_months {
+            return ApiResponse::error(ApiError::invalid_input(&format!(
+                "Retention must be between 1 and {} months on the organization's plan",
+                max_months
+            )));
+        }
+    }
+
+    let policy = VerificationRetentionPolicy {
+        org_id: request.org_id,
+        retention_months: request.retention_months,
+        handling: request.handling,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+    verification_retention::save_policy(policy.clone());
+    log_info!("[set_verification_retention_policy_v2] Retention policy of org {} updated by {}", request.org_id, caller);
+    ApiResponse::success(policy)
+}
+
+fn is_valid_month(month: u32) -> bool {
+    (1..=12).contains(&(month % 100)) && month / 100 >= 1970
+}
+
+// Monthly totals of verifications that were rolled up out of the raw history
+#[query]
+pub fn get_archived_verification_aggregates_v2(query: ArchivedAggregatesQuery) -> ApiResponse<Vec<VerificationMonthlyAggregate>> {
+    if let Err(e) = authorize_for_organization(api::caller(), query.org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    for month in [query.from_month, query.to_month].into_iter().flatten() {
+        if !is_valid_month(month) {
+            return ApiResponse::error(ApiError::invalid_input("Months must be given as YYYYMM"));
+        }
+    }
+    if let (Some(from), Some(to)) = (query.from_month, query.to_month) {
+        if from > to {
+            return ApiResponse::error(ApiError::invalid_input("from_month must not be after to_month"));
+        }
+    }
+    ApiResponse::success(verification_retention::list_aggregates(
+        query.org_id,
+        query.product_id,
+        query.from_month,
+        query.to_month,
+    ))
+}
+
+// Run the daily rollup now, e.g. right after a plan downgrade
+#[update]
+pub fn run_verification_rollup_v2() -> ApiResponse<VerificationRollupResult> {
+    let _metrics = endpoint_metrics::track("run_verification_rollup_v2");
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(verification_retention::run_rollup(clock::now()))
+}
+
+// ====== Background Jobs ======
+
+fn job_progress_percent(job: &Job) -> u8 {
+    if job.total_units == 0 {
+        return 100;
+    }
+    (job.processed_units.saturating_mul(100) / job.total_units).min(100) as u8
+}
+
+// What a caller needs on the organization to submit (and so to cancel) a job
+fn job_permission(spec: &JobSpec) -> Permission {
+    match spec {
+        JobSpec::SerialImport { .. } => Permission::WriteProduct,
+        JobSpec::VerificationExport { .. } => Permission::ReadProduct,
+    }
+}
+
+fn job_status_response(job: Job) -> JobStatusResponse {
+    JobStatusResponse { progress_percent: job_progress_percent(&job), job }
+}
+
+// Queue work too large for a single call. It runs in steps after this call returns;
+// poll get_job_status_v2 for progress and read the output with get_job_results_v2.
+#[update]
+pub fn submit_job_v2(request: SubmitJobRequest) -> ApiResponse<JobStatusResponse> {
+    let _metrics = endpoint_metrics::track("submit_job_v2");
+    let caller = api::caller();
+    let org_id = request.org_id;
+    let spec = match request.job {
+        JobRequest::SerialImport { product_id, variant_id, user_serial_prefix, count } => {
+            if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteProduct) {
+                return ApiResponse::error(e);
+            }
+            match get_product(&product_id) {
+                Ok(product) if product.org_id == org_id => {}
+                Ok(_) => return ApiResponse::error(ApiError::not_found("Product not found")),
+                Err(e) => return ApiResponse::error(e),
+            }
+            if count == 0 || count > jobs::MAX_SERIALS_PER_IMPORT {
+                return ApiResponse::error(ApiError::invalid_input(&format!(
+                    "Count must be between 1 and {}",
+                    jobs::MAX_SERIALS_PER_IMPORT
+                )));
+            }
+            if let Some(variant_id) = variant_id {
+                if variants::get_variant(variant_id).is_none_or(|v| v.product_id != product_id) {
+                    return ApiResponse::error(ApiError::not_found("Product variant not found"));
                 }
             }
+            let defaults = org_settings::serial_batch_defaults(org_id);
+            let prefix = match normalize_user_serial_prefix(user_serial_prefix.as_deref().or(defaults.user_serial_prefix.as_deref())) {
+                Ok(p) => p.map(str::to_string),
+                Err(e) => return ApiResponse::error(e),
+            };
+            JobSpec::SerialImport { product_id, variant_id, user_serial_prefix: prefix, count }
         }
-    });
+        JobRequest::VerificationExport { product_ids, period_start, period_end } => {
+            if let Err(e) = authorize_for_organization(caller, org_id, Permission::ReadProduct) {
+                return ApiResponse::error(e);
+            }
+            let now = clock::now();
+            let period_end = period_end.unwrap_or(now);
+            let period_start = period_start.unwrap_or_else(|| plans::retention_start(org_id, now));
+            if period_start >= period_end {
+                return ApiResponse::error(ApiError::invalid_input("period_start must be before period_end"));
+            }
+            if let Err(e) = plans::ensure_within_retention(org_id, period_start, now) {
+                return ApiResponse::error(e);
+            }
+            let product_ids = match product_ids {
+                Some(mut ids) => {
+                    ids.sort();
+                    ids.dedup();
+                    for id in &ids {
+                        match get_product(id) {
+                            Ok(product) if product.org_id == org_id => {}
+                            _ => return ApiResponse::error(ApiError::not_found(&format!("Product {} not found", id))),
+                        }
+                    }
+                    ids
+                }
+                None => PRODUCTS.with(|products| {
+                    products
+                        .borrow()
+                        .iter()
+                        .filter(|(_, p)| p.org_id == org_id && !p.is_test.unwrap_or(false))
+                        .map(|(id, _)| id)
+                        .collect()
+                }),
+            };
+            JobSpec::VerificationExport { product_ids, period_start, period_end }
+        }
+    };
 
-    // Optionally sort the results, e.g., by creation date descending
-    all_verification_details.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    if jobs::active_count_for_org(org_id) >= jobs::MAX_ACTIVE_JOBS_PER_ORG {
+        return ApiResponse::error(ApiError::quota_exceeded(&format!(
+            "An organization can run at most {} jobs at a time",
+            jobs::MAX_ACTIVE_JOBS_PER_ORG
+        )));
+    }
+    let job = jobs::submit(org_id, spec, caller);
+    log_info!("[submit_job_v2] Job {} queued for org {} by {}", job.id, org_id, caller);
+    ApiResponse::success(job_status_response(job))
+}
 
-    all_verification_details
+#[query]
+pub fn get_job_status_v2(job_id: Principal) -> ApiResponse<JobStatusResponse> {
+    let job = match jobs::get_job(job_id) {
+        Some(j) => j,
+        None => return ApiResponse::error(ApiError::not_found("Job not found")),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), job.org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(job_status_response(job))
+}
+
+// Jobs of the organization, newest first; finished jobs are kept for a week
+#[query]
+pub fn list_jobs_v2(org_id: Principal) -> ApiResponse<Vec<Job>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(jobs::list_jobs_for_org(org_id))
+}
+
+// One chunk of a job's output. Chunks can be read while the job is still running.
+#[query]
+pub fn get_job_results_v2(job_id: Principal, chunk_index: u32) -> ApiResponse<JobResultChunkResponse> {
+    let job = match jobs::get_job(job_id) {
+        Some(j) => j,
+        None => return ApiResponse::error(ApiError::not_found("Job not found")),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), job.org_id, job_permission(&job.spec)) {
+        return ApiResponse::error(e);
+    }
+    let chunk = match jobs::get_result_chunk(job_id, chunk_index) {
+        Some(c) => c,
+        None => {
+            return ApiResponse::error(ApiError::not_found(&format!(
+                "The job has written {} result chunk(s) so far",
+                job.result_chunks
+            )))
+        }
+    };
+    ApiResponse::success(JobResultChunkResponse {
+        job_id,
+        chunk_index,
+        columns: job.result_columns.clone(),
+        rows: chunk.rows,
+        total_chunks: job.result_chunks,
+        is_last: !jobs::is_active(&job) && chunk_index + 1 == job.result_chunks,
+    })
 }
 
+// Stop a queued or running job. Serials an import already created are kept.
 #[update]
-pub fn reset_all_stable_storage() -> ApiResponse<ResetStorageResponse> {
-    ic_cdk::print("🚨 WARNING: Resetting all stable storage initiated.");
+pub fn cancel_job_v2(job_id: Principal) -> ApiResponse<JobStatusResponse> {
+    let _metrics = endpoint_metrics::track("cancel_job_v2");
+    let caller = api::caller();
+    let job = match jobs::get_job(job_id) {
+        Some(j) => j,
+        None => return ApiResponse::error(ApiError::not_found("Job not found")),
+    };
+    if let Err(e) = authorize_for_organization(caller, job.org_id, job_permission(&job.spec)) {
+        return ApiResponse::error(e);
+    }
+    match jobs::cancel(job_id, clock::now()) {
+        Some(job) => {
+            log_info!("[cancel_job_v2] Job {} of org {} cancelled by {}", job_id, job.org_id, caller);
+            ApiResponse::success(job_status_response(job))
+        }
+        None => ApiResponse::error(ApiError::invalid_input("The job has already finished")),
+    }
+}
 
-    // Clear StableBTreeMaps by iterating and removing
-    ORGANIZATIONS.with(|orgs| {
-        let mut orgs_mut = orgs.borrow_mut();
-        let keys: Vec<_> = orgs_mut.iter().map(|(k, _)| k).collect();
-        for key in keys {
-            orgs_mut.remove(&key);
+// ====== Verification Domains ======
+
+fn verification_domain_response(domain: VerificationDomain) -> VerificationDomainResponse {
+    VerificationDomainResponse {
+        proof_record_name: verification_domains::proof_record_name(&domain.domain),
+        proof_record_value: verification_domains::proof_record_value(&domain.proof_token),
+        domain,
+    }
+}
+
+// Register the domain the organization's QR codes should link to. It is used once
+// check_verification_domain_v2 finds the returned TXT record in DNS.
+#[update]
+pub fn register_verification_domain_v2(request: RegisterVerificationDomainRequest) -> ApiResponse<VerificationDomainResponse> {
+    let _metrics = endpoint_metrics::track("register_verification_domain_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    match verification_domains::register(request.org_id, &request.domain, caller, clock::now()) {
+        Ok(domain) => {
+            log_info!("[register_verification_domain_v2] Org {} registered domain {} by {}", request.org_id, domain.domain, caller);
+            ApiResponse::success(verification_domain_response(domain))
         }
-    });
-    PRODUCTS.with(|prods| {
-        let mut prods_mut = prods.borrow_mut();
-        let keys: Vec<_> = prods_mut.iter().map(|(k, _)| k).collect();
-        for key in keys {
-            prods_mut.remove(&key);
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Look up the TXT proof of the organization's domain. Each check is one outcall against the quota.
+#[update]
+pub async fn check_verification_domain_v2(org_id: Principal) -> ApiResponse<VerificationDomainResponse> {
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    if verification_domains::get_domain(org_id).is_none() {
+        return ApiResponse::error(ApiError::not_found("No verification domain is registered for this organization"));
+    }
+    if let Err(e) = circuit_breakers::check(ExternalService::DnsResolver) {
+        return ApiResponse::error(e);
+    }
+    if let Err(e) = quotas::consume_outcalls(org_id, 1) {
+        return ApiResponse::error(e);
+    }
+    match verification_domains::check(org_id, clock::now()).await {
+        Ok(domain) => {
+            log_info!("[check_verification_domain_v2] Domain {} of org {} is {:?}", domain.domain, org_id, domain.status);
+            ApiResponse::success(verification_domain_response(domain))
         }
-    });
-    USERS.with(|users| {
-        let mut users_mut = users.borrow_mut();
-        let keys: Vec<_> = users_mut.iter().map(|(k, _)| k).collect();
-        for key in keys {
-            users_mut.remove(&key);
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+#[query]
+pub fn get_verification_domain_v2(org_id: Principal) -> ApiResponse<Option<VerificationDomainResponse>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadOrganization) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(verification_domains::get_domain(org_id).map(verification_domain_response))
+}
+
+// Codes printed from now on carry no domain link; codes already printed keep theirs
+#[update]
+pub fn remove_verification_domain_v2(org_id: Principal) -> ApiResponse<VerificationDomain> {
+    let _metrics = endpoint_metrics::track("remove_verification_domain_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    match verification_domains::remove_domain(org_id) {
+        Some(domain) => {
+            log_info!("[remove_verification_domain_v2] Org {} removed domain {} by {}", org_id, domain.domain, caller);
+            ApiResponse::success(domain)
         }
-    });
-    RESELLERS.with(|resellers| {
-        let mut resellers_mut = resellers.borrow_mut();
-        let keys: Vec<_> = resellers_mut.iter().map(|(k, _)| k).collect();
-        for key in keys {
-            resellers_mut.remove(&key);
+        None => ApiResponse::error(ApiError::not_found("No verification domain is registered for this organization")),
+    }
+}
+
+// ====== Capability Tokens ======
+
+// Mint a short-lived token that lets `holder` run the listed operations on the listed products of
+// the organization, without being a member. The token is returned once; only its claims are kept.
+#[update]
+pub fn mint_capability_token_v2(request: MintCapabilityTokenRequest) -> ApiResponse<MintCapabilityTokenResponse> {
+    let _metrics = endpoint_metrics::track("mint_capability_token_v2");
+    let caller = api::caller();
+    let organization = match authorize_for_organization(caller, request.org_id, Permission::WriteProduct) {
+        Ok(org) => org,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if request.holder == Principal::anonymous() {
+        return ApiResponse::error(ApiError::invalid_input("A capability token must be issued to a signed-in principal"));
+    }
+    if request.ttl_seconds == 0 || request.ttl_seconds > capability_tokens::MAX_TTL_SECONDS {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "ttl_seconds must be between 1 and {}",
+            capability_tokens::MAX_TTL_SECONDS
+        )));
+    }
+    let mut operations: Vec<CapabilityOperation> = Vec::new();
+    for operation in request.operations {
+        if !operations.contains(&operation) {
+            operations.push(operation);
         }
-    });
-    PRODUCT_SERIAL_NUMBERS.with(|sns| {
-        let mut sns_mut = sns.borrow_mut();
-        let keys: Vec<_> = sns_mut.iter().map(|(k, _)| k).collect();
-        for key in keys {
-            sns_mut.remove(&key);
+    }
+    if operations.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("At least one operation is required"));
+    }
+    let mut product_ids = request.product_ids;
+    product_ids.sort();
+    product_ids.dedup();
+    if product_ids.is_empty() || product_ids.len() > capability_tokens::MAX_PRODUCTS_PER_TOKEN {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "A capability token must name between 1 and {} products",
+            capability_tokens::MAX_PRODUCTS_PER_TOKEN
+        )));
+    }
+    for product_id in &product_ids {
+        match get_product(product_id) {
+            Ok(product) if product.org_id == request.org_id => {}
+            _ => return ApiResponse::error(ApiError::not_found(&format!("Product {} not found", product_id))),
         }
+    }
+    let now = clock::now();
+    if capability_tokens::active_count_for_org(request.org_id, now) >= capability_tokens::MAX_ACTIVE_PER_ORG {
+        return ApiResponse::error(ApiError::quota_exceeded(&format!(
+            "An organization can have at most {} active capability tokens",
+            capability_tokens::MAX_ACTIVE_PER_ORG
+        )));
+    }
+
+    let claims = CapabilityClaims {
+        token_id: generate_unique_principal(request.org_id),
+        org_id: request.org_id,
+        holder: request.holder,
+        operations,
+        product_ids,
+        issued_at: now,
+        expires_at: now.saturating_add(request.ttl_seconds.saturating_mul(1_000_000_000)),
+    };
+    let token = match capability_tokens::encode_token(&claims, &organization) {
+        Ok(t) => t,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let record = CapabilityToken {
+        claims,
+        issued_by: caller,
+        revoked_at: None,
+        revoked_by: None,
+        use_count: 0,
+        last_used_at: None,
+    };
+    capability_tokens::save(record.clone());
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "MintCapabilityToken".to_string(),
+        resource_type: "CapabilityToken".to_string(),
+        resource_id: record.claims.token_id,
+        timestamp: now,
+        metadata: vec![
+            Metadata { key: "holder".to_string(), value: record.claims.holder.to_string() },
+            Metadata { key: "operations".to_string(), value: format!("{:?}", record.claims.operations) },
+            Metadata { key: "expires_at".to_string(), value: record.claims.expires_at.to_string() },
+        ],
+        success: true,
     });
-    PRODUCT_VERIFICATIONS.with(|vers| {
-        let mut vers_mut = vers.borrow_mut();
-        let keys: Vec<_> = vers_mut.iter().map(|(k, _)| k).collect();
-        for key in keys {
-            vers_mut.remove(&key);
-        }
+    log_info!("[mint_capability_token_v2] Token {} for {} minted in org {} by {}", record.claims.token_id, record.claims.holder, request.org_id, caller);
+
+    ApiResponse::success(MintCapabilityTokenResponse { token, record })
+}
+
+// Tokens of the organization, newest first, expired and revoked ones included
+#[query]
+pub fn list_capability_tokens_v2(org_id: Principal) -> ApiResponse<Vec<CapabilityToken>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(capability_tokens::list_for_org(org_id))
+}
+
+#[update]
+pub fn revoke_capability_token_v2(token_id: Principal) -> ApiResponse<CapabilityToken> {
+    let _metrics = endpoint_metrics::track("revoke_capability_token_v2");
+    let caller = api::caller();
+    let mut record = match capability_tokens::get(token_id) {
+        Some(t) => t,
+        None => return ApiResponse::error(ApiError::not_found("Capability token not found")),
+    };
+    if let Err(e) = authorize_for_organization(caller, record.claims.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    if record.revoked_at.is_some() {
+        return ApiResponse::error(ApiError::invalid_input("The capability token is already revoked"));
+    }
+    let now = clock::now();
+    record.revoked_at = Some(now);
+    record.revoked_by = Some(caller);
+    capability_tokens::save(record.clone());
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "RevokeCapabilityToken".to_string(),
+        resource_type: "CapabilityToken".to_string(),
+        resource_id: token_id,
+        timestamp: now,
+        metadata: vec![],
+        success: true,
     });
+    log_info!("[revoke_capability_token_v2] Token {} of org {} revoked by {}", token_id, record.claims.org_id, caller);
+    ApiResponse::success(record)
+}
+
+// Minting, revocation and every use of the token, denied attempts included, newest first
+#[query]
+pub fn list_capability_token_audit_log_v2(
+    token_id: Principal,
+    pagination: Option<PaginationRequest>,
+) -> ApiResponse<AuditLogListResponse> {
+    let record = match capability_tokens::get(token_id) {
+        Some(t) => t,
+        None => return ApiResponse::error(ApiError::not_found("Capability token not found")),
+    };
+    if let Err(e) = authorize_for_organization(api::caller(), record.claims.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
+    }
+    let entries = audit_log::list_for_resource("CapabilityToken", token_id);
+    let (entries, page_info) = paginate(entries, &pagination.unwrap_or_default());
+    ApiResponse::success(AuditLogListResponse { entries, pagination: Some(page_info) })
+}
 
-    // Clear StableCells by setting them to default
-    match CONFIG_OPENAI_API_KEY.with(|cell| cell.borrow_mut().set(StorableString::default())) {
-        Ok(_) => ic_cdk::print("Cleared OpenAI API Key config."),
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to reset OpenAI API Key config: {:?}", e));
-            return ApiResponse::error(ApiError::internal_error("Failed to reset OpenAI key config"));
-        }
+// ====== Post-Verification Messages ======
+
+#[update]
+pub fn set_post_verification_message_v2(request: SetPostVerificationMessageRequest) -> ApiResponse<PostVerificationMessage> {
+    let _metrics = endpoint_metrics::track("set_post_verification_message_v2");
+    let caller = api::caller();
+
+    if let Err(e) = authorize_for_organization(caller, request.org_id, Permission::WriteProduct) {
+        return ApiResponse::error(e);
     }
-    match CONFIG_SCRAPER_URL.with(|cell| cell.borrow_mut().set(StorableString::default())) {
-        Ok(_) => ic_cdk::print("Cleared Scraper URL config."),
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to reset Scraper URL config: {:?}", e));
-            return ApiResponse::error(ApiError::internal_error("Failed to reset scraper URL config"));
+    if let Some(product_id) = request.product_id {
+        match get_product(&product_id) {
+            Ok(product) if product.org_id == request.org_id => {}
+            Ok(_) => return ApiResponse::error(ApiError::unauthorized("Product does not belong to this organization")),
+            Err(e) => return ApiResponse::error(e),
         }
     }
 
-    // Consider clearing rate limiter and rewards storage if they use stable memory too
-    rate_limiter::reset_rate_limits();
-    rewards::reset_rewards_storage();
+    let mut validator = Validator::new();
+    if let Err(message) = verification_messages::validate_template(&request.template) {
+        validator.add_error("template", &message);
+    }
+    let cta_label = request.cta_label.map(|label| label.trim().to_string()).filter(|label| !label.is_empty());
+    if let Some(label) = &cta_label {
+        validator.required_text("cta_label", label, verification_messages::MAX_CTA_LABEL_LENGTH);
+    }
+    let cta_url = request.cta_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if let Some(url) = &cta_url {
+        validator.url("cta_url", url);
+    }
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
 
-    ic_cdk::print("✅ All stable storage reset successfully.");
+    let message = PostVerificationMessage {
+        org_id: request.org_id,
+        product_id: request.product_id,
+        template: request.template.trim().to_string(),
+        cta_label,
+        cta_url,
+        is_active: request.is_active,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+    verification_messages::save_message(message.clone());
+    log_info!("[set_post_verification_message_v2] Message for org {} (product {:?}) updated by {}", request.org_id, request.product_id, caller);
 
-    ApiResponse::success(ResetStorageResponse {
-        message: "All stable storage has been successfully reset.".to_string(),
-    })
+    ApiResponse::success(message)
 }
 
 #[query]
-pub fn check_reseller_verification(org_id: Principal) -> ApiResponse<bool> {
-    let caller = api::caller(); 
-    
-    // Fetch the user based on the caller's principal
-    match USERS.with(|users| users.borrow().get(&caller).clone()) {
-        Some(user) => {
-            // Check if the user has the Reseller role
-            if let Some(UserRole::Reseller) = user.user_role {
-                // Check if the user is associated with the provided organization ID
-                if user.org_ids.contains(&org_id) {
-                    // Reseller role and associated with the correct org
-                    ApiResponse::success(true) 
-                } else {
-                    // Reseller role, but not associated with this org
-                    ic_cdk::print(format!("ℹ️ User {} is a Reseller but not associated with org {}", caller, org_id));
-                    ApiResponse::success(false)
-                }
-            } else {
-                // User exists but is not a Reseller
-                ic_cdk::print(format!("ℹ️ User {} is not a Reseller.", caller));
-                ApiResponse::success(false)
-            }
-        }
-        None => {
-            // User not found
-            ic_cdk::print(format!("ℹ️ User {} not found.", caller));
-            // Return false to align with previous behaviour on user not found.
-            // Alternatively, return an error:
-            // ApiResponse::error(ApiError::not_found("User not found"))
-            ApiResponse::success(false)
-        }
+pub fn list_post_verification_messages_v2(org_id: Principal) -> ApiResponse<Vec<PostVerificationMessage>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::ReadProduct) {
+        return ApiResponse::error(e);
     }
+
+    ApiResponse::success(verification_messages::list_messages_for_org(org_id))
 }
 
-// ====== Phase 1: Core Authentication & Context ======
+// ====== Status Messages ======
 
+// Human-readable text for a verification status, so thin clients need no mapping of their own
 #[query]
-pub fn get_available_roles() -> ApiResponse<Vec<UserRole>> {
-    ApiResponse::success(vec![UserRole::BrandOwner, UserRole::Reseller])
+pub fn describe_status(status: ProductVerificationStatus, locale: Option<String>) -> ApiResponse<StatusDescriptionResponse> {
+    if let Some(Err(message)) = locale.as_deref().map(status_messages::normalize_locale) {
+        return ApiResponse::error(ApiError::invalid_input(&message));
+    }
+    let (message, resolved_locale) = status_messages::describe(&status, locale.as_deref());
+    ApiResponse::success(StatusDescriptionResponse {
+        status,
+        locale: resolved_locale,
+        message,
+    })
 }
 
 #[update]
-pub fn initialize_user_session(selected_role: Option<UserRole>) -> ApiResponse<AuthContextResponse> {
-    let session_principal = api::caller(); 
-    let user_principal_key = session_principal;
+pub fn set_status_message_v2(request: SetStatusMessageRequest) -> ApiResponse<StatusMessage> {
+    let _metrics = endpoint_metrics::track("set_status_message_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
 
-    ic_cdk::print(format!("ℹ️ [initialize_user_session] Called by session_principal: {} with role: {:?}", session_principal, selected_role));
+    let mut validator = Validator::new();
+    let locale = match status_messages::normalize_locale(&request.locale) {
+        Ok(locale) => locale,
+        Err(message) => {
+            validator.add_error("locale", &message);
+            String::new()
+        }
+    };
+    let text = validator.required_text("text", &request.text, status_messages::MAX_MESSAGE_LENGTH);
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
 
-    // Corrected AGAIN: Use .clone() on Option<&User> to get Option<User>
-    let user_record_opt = USERS.with(|users| users.borrow().get(&user_principal_key).clone());
+    let message = StatusMessage {
+        status: request.status,
+        locale,
+        text,
+        updated_at: clock::now(),
+        updated_by: caller,
+    };
+    status_messages::save_message(message.clone());
+    log_info!("[set_status_message_v2] Message for {:?} in {} updated by {}", message.status, message.locale, caller);
 
-    let final_user_state: User = match user_record_opt {
-        Some(mut user) => { // User exists
-            ic_cdk::print(format!("ℹ️ [initialize_user_session] Existing user {} found: {:?}", user_principal_key, user));
-            
-            if user.user_role.is_none() {
-                if let Some(role_to_assign) = selected_role {
-                    user.user_role = Some(role_to_assign);
-                    ic_cdk::print(format!("ℹ️ [initialize_user_session] Assigned role {:?} to existing user {} who had no role.", role_to_assign, user.id));
-                } else {
-                    // This case should ideally not be hit if frontend always sends a role (including Customer)
-                    ic_cdk::print(format!("⚠️ [initialize_user_session] Role selection was None for existing user {} who had no role. This is unexpected.", user_principal_key));
-                    return ApiResponse::error(ApiError::invalid_input(
-                        "A role must be selected to complete registration for an unassigned user.",
-                    ));
-                }
-            } else if let Some(new_role_selected) = selected_role {
-                 // User has an existing role, check if the selected role matches
-                 if user.user_role != Some(new_role_selected) {
-                     ic_cdk::print(format!("⚠️ [initialize_user_session] User {} attempted to change role from {:?} to {:?}", user.id, user.user_role, new_role_selected));
-                     return ApiResponse::error(ApiError::unauthorized(
-                         "User role has already been set and cannot be changed through this flow.",
-                     ));
-                 }
-                 // If roles match, it's fine, proceed to session key update
-                 ic_cdk::print(format!("ℹ️ [initialize_user_session] User {} already has role {:?}, which matches selection.", user.id, user.user_role));
-            } else {
-                // User has an existing role, but no role was selected in this session init (e.g. subsequent logins)
-                // This is fine, just proceed with the existing role.
-                ic_cdk::print(format!("ℹ️ [initialize_user_session] User {} has existing role {:?}. No new role selected in this session.", user.id, user.user_role));
-            }
+    ApiResponse::success(message)
+}
 
-            // ALWAYS add the current session_principal to session_keys if not already present
-            if !user.session_keys.contains(&session_principal) {
-                ic_cdk::print(format!("ℹ️ [initialize_user_session] Adding session key {} for user {}", session_principal, user.id));
-                user.session_keys.push(session_principal);
-                user.updated_at = api::time();
-                user.updated_by = session_principal;
-                // Save the updated user record
-                USERS.with(|users| users.borrow_mut().insert(user.id, user.clone()));
-            } else {
-                 ic_cdk::print(format!("ℹ️ [initialize_user_session] Session key {} already exists for user {}", session_principal, user.id));
-            }
-            user // Return potentially modified user
-        }
-        None => { // New user
-            ic_cdk::print(format!("ℹ️ [initialize_user_session] New user: {}. Creating record.", user_principal_key));
-            match selected_role {
-                Some(role) => {
-                    // Create user with the calling principal as ID and also add it as the first session key
-                    let new_user = User {
-                        id: user_principal_key, // User ID is the principal that called this
-                        user_role: Some(role), // Assign the selected role (e.g., Customer)
-                        session_keys: vec![session_principal], // Always add the session key used for creation
-                        created_by: user_principal_key, // Created by the root identity (same as caller here)
-                        updated_by: session_principal, // Updated by the session identity during this call
-                        ..Default::default()
-                    };
-                    USERS.with(|users| users.borrow_mut().insert(user_principal_key, new_user.clone()));
-                    ic_cdk::print(format!("ℹ️ [initialize_user_session] Created new user {} with role {:?} and initial session key {}", user_principal_key, role, session_principal));
-                    new_user
-                }
-                None => {
-                    // This case should ideally not be hit if frontend always sends a role for new users (including Customer)
-                    ic_cdk::print(format!("⚠️ [initialize_user_session] Role selection was None for new user {}. This is unexpected if FE sends Customer role.", user_principal_key));
-                    return ApiResponse::error(ApiError::invalid_input(
-                        "A role must be selected for new user registration.",
-                    ));
-                }
-            }
-        }
+// Remove a catalog entry; the status falls back to the next locale in line
+#[update]
+pub fn remove_status_message_v2(request: RemoveStatusMessageRequest) -> ApiResponse<StatusMessage> {
+    let _metrics = endpoint_metrics::track("remove_status_message_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    let locale = match status_messages::normalize_locale(&request.locale) {
+        Ok(locale) => locale,
+        Err(message) => return ApiResponse::error(ApiError::invalid_input(&message)),
     };
 
-    // Construct AuthContextResponse using the final helper
-    let auth_context = build_auth_context_response(&final_user_state);
-    ApiResponse::success(auth_context)
+    match status_messages::remove_message(&request.status, &locale) {
+        Some(message) => {
+            log_info!("[remove_status_message_v2] Message for {:?} in {} removed by {}", message.status, message.locale, caller);
+            ApiResponse::success(message)
+        }
+        None => ApiResponse::error(ApiError::not_found("No message is set for this status and locale")),
+    }
 }
 
-// Final version of build_auth_context_response incorporating all phases
-fn build_auth_context_response(user: &User) -> AuthContextResponse {
-    let user_public = UserPublic {
-        id: user.id,
-        first_name: user.first_name.clone(),
-        last_name: user.last_name.clone(),
-        email: user.email.clone(),
-        created_at: user.created_at,
-    };
+// The whole catalog, for clients that cache it; statuses without an entry use the built-in English text
+#[query]
+pub fn list_status_messages_v2() -> ApiResponse<Vec<StatusMessage>> {
+    ApiResponse::success(status_messages::list_messages())
+}
 
-    let mut brand_owner_details: Option<BrandOwnerContextDetails> = None;
-    if user.user_role == Some(UserRole::BrandOwner) {
-        let mut org_public_list = Vec::new();
-        let mut active_org_public: Option<OrganizationPublic> = None;
-        ORGANIZATIONS.with(|orgs_map| {
-            let orgs_ref = orgs_map.borrow();
-            for org_id_principal in &user.org_ids {
-                if let Some(org_record) = orgs_ref.get(org_id_principal) {
-                    org_public_list.push(OrganizationPublic::from(org_record.clone()));
-                }
-            }
-            if let Some(active_org_id_principal) = user.active_org_id {
-                if let Some(active_org_record) = orgs_ref.get(&active_org_id_principal) {
-                    active_org_public = Some(OrganizationPublic::from(active_org_record.clone()));
-                }
-            }
-        });
-        brand_owner_details = Some(BrandOwnerContextDetails {
-            has_organizations: !org_public_list.is_empty(),
-            organizations: if org_public_list.is_empty() { None } else { Some(org_public_list) },
-            active_organization: active_org_public,
-        });
+// ====== Store Integrity ======
+
+#[query]
+pub fn scan_store_integrity(store: IntegrityStore) -> ApiResponse<IntegrityScanReport> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
     }
 
-    let mut reseller_details_ctx: Option<ResellerContextDetails> = None;
-    if user.user_role == Some(UserRole::Reseller) {
-        if let Some(reseller_record) = get_reseller_by_user_id(user.id) { // Assuming get_reseller_by_user_id exists
-            let associated_org_public = ORGANIZATIONS.with(|orgs_map| {
-                orgs_map.borrow().get(&reseller_record.org_id).map(|org| OrganizationPublic::from(org.clone()))
-            });
+    ApiResponse::success(integrity::scan_store(store))
+}
 
-            reseller_details_ctx = Some(ResellerContextDetails {
-                is_profile_complete_and_verified: reseller_record.is_verified,
-                associated_organization: associated_org_public,
-                certification_code: reseller_record.certification_code.clone(),
-                certification_timestamp: reseller_record.certification_timestamp,
-            });
-        } else {
-            reseller_details_ctx = Some(ResellerContextDetails {
-                is_profile_complete_and_verified: false,
-                associated_organization: None,
-                certification_code: None,
-                certification_timestamp: None,
-            });
-        }
+#[update]
+pub fn quarantine_entry(store: IntegrityStore, key: Principal, sub_key: Option<IntegritySubKey>) -> ApiResponse<QuarantinedEntry> {
+    let _metrics = endpoint_metrics::track("quarantine_entry");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
     }
 
-    AuthContextResponse {
-        user: Some(user_public),
-        is_registered: true,
-        role: user.user_role,
-        brand_owner_details,
-        reseller_details: reseller_details_ctx,
+    match integrity::quarantine_entry(store, key, sub_key, caller) {
+        Ok(entry) => ApiResponse::success(entry),
+        Err(e) => ApiResponse::error(e),
     }
 }
 
-// Final version of get_auth_context
 #[query]
-pub fn get_auth_context() -> ApiResponse<AuthContextResponse> {
-    let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [get_auth_context] Called by: {}", caller));
+pub fn list_quarantined_entries() -> ApiResponse<Vec<QuarantinedEntry>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
 
-    match USERS.with(|users| users.borrow().get(&caller).clone()) { // Cloned here
-        Some(user) => {
-            ic_cdk::print(format!("ℹ️ [get_auth_context] Found user: {:?}", user));
-            let auth_context = build_auth_context_response(&user);
-            ApiResponse::success(auth_context)
-        }
-        None => {
-            ic_cdk::print(format!("ℹ️ [get_auth_context] User not found: {}. Returning not registered.", caller));
-            ApiResponse::success(AuthContextResponse {
-                user: None,
-                is_registered: false,
-                role: None,
-                brand_owner_details: None,
-                reseller_details: None,
-            })
-        }
+    ApiResponse::success(integrity::list_quarantined())
+}
+
+// Referential integrity across organizations, products, serials, resellers and users.
+// Also runs after every upgrade, with violations written to the log.
+#[query]
+pub fn run_invariant_checks_v2() -> ApiResponse<InvariantReport> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
     }
+
+    ApiResponse::success(invariants::run_checks())
 }
 
+// ====== Signing Service ======
+
+// Signs a SHA-256 digest with the organization's key so external label printers can produce
+// unique codes without holding the key. Every signature is audited.
 #[update]
-pub fn logout_user() -> ApiResponse<LogoutResponse> {
+pub fn sign_payload_for_org(org_id: Principal, payload_hash: String) -> ApiResponse<SignPayloadResponse> {
+    let _metrics = endpoint_metrics::track("sign_payload_for_org");
     let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [logout_user] User {} attempting to log out.", caller));
-    ApiResponse::success(LogoutResponse {
-        message: "Successfully logged out.".to_string(),
-        redirect_url: None, 
-    })
+    let organization = match authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        Ok(org) => org,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let hash_bytes = match signing::parse_payload_hash(&payload_hash) {
+        Ok(bytes) => bytes,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let now = clock::now();
+    if let Err(e) = signing::record_signature(org_id, now) {
+        log_warn!("[sign_payload_for_org] Rate limit hit for org {} by {}", org_id, caller);
+        return ApiResponse::error(e);
+    }
+
+    let result = signing::sign_payload_hash(&organization, &hash_bytes);
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: "SignPayload".to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: org_id,
+        timestamp: now,
+        metadata: vec![Metadata { key: "payload_hash".to_string(), value: hex::encode(&hash_bytes) }],
+        success: result.is_ok(),
+    });
+
+    match result {
+        Ok((signature, public_key)) => ApiResponse::success(SignPayloadResponse {
+            signature,
+            public_key,
+            signed_at: now,
+        }),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// ====== Read-only Principals ======
+
+// A principal the BI tool generated for itself; it must not already be in use anywhere
+fn check_new_readonly_principal(principal: Principal, caller: Principal) -> Result<(), ApiError> {
+    if principal == Principal::anonymous() || principal == caller {
+        return Err(ApiError::invalid_input("Use a principal dedicated to the BI tool"));
+    }
+    if find_user_by_caller(principal).is_some() {
+        return Err(ApiError::already_exists("This principal belongs to a registered user"));
+    }
+    if readonly_principals::get(principal).is_some() {
+        return Err(ApiError::already_exists("This principal was already registered as read-only"));
+    }
+    Ok(())
 }
 
-// ====== Phase 2: Brand Owner Flow ======
+fn record_readonly_principal_audit(caller: Principal, action: &str, entry: &ReadonlyPrincipal) {
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: action.to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: entry.org_id,
+        timestamp: clock::now(),
+        metadata: vec![Metadata { key: "principal".to_string(), value: entry.principal.to_string() }],
+        success: true,
+    });
+}
 
+// Register a service principal that may run the organization's analytics and list queries, and nothing else
 #[update]
-pub fn create_organization_for_owner(request: CreateOrganizationWithOwnerContextRequest) -> ApiResponse<OrganizationContextResponse> {
+pub fn create_readonly_principal_v2(org_id: Principal, principal: Principal, label: Option<String>) -> ApiResponse<ReadonlyPrincipal> {
+    let _metrics = endpoint_metrics::track("create_readonly_principal_v2");
     let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [create_organization_for_owner] Called by: {} with request: {:?}", caller, request));
-
-    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
-    if user_opt.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
     }
-    let mut user = user_opt.unwrap();
-
-    if user.user_role != Some(UserRole::BrandOwner) {
-        return ApiResponse::error(ApiError::unauthorized("Only Brand Owners can create organizations."));
+    if let Err(e) = check_new_readonly_principal(principal, caller) {
+        return ApiResponse::error(e);
+    }
+    let mut validator = Validator::new();
+    let label = label
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| validator.required_text("label", &l, readonly_principals::MAX_LABEL_LENGTH));
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+    if readonly_principals::active_count_for_org(org_id) >= readonly_principals::MAX_ACTIVE_PER_ORG {
+        return ApiResponse::error(ApiError::quota_exceeded(&format!(
+            "An organization can have at most {} active read-only principals",
+            readonly_principals::MAX_ACTIVE_PER_ORG
+        )));
     }
 
-    let org_id = generate_unique_principal(Principal::anonymous());
-    let mut rng = StdRng::from_entropy(); 
-    let signing_key = SigningKey::random(&mut rng);
-
-    let new_organization = Organization {
-        id: org_id,
-        name: request.name,
-        description: request.description,
-        private_key: hex::encode(&signing_key.to_bytes()),
-        metadata: request.metadata,
-        created_at: api::time(),
+    let entry = ReadonlyPrincipal {
+        principal,
+        org_id,
+        label,
+        created_at: clock::now(),
         created_by: caller,
-        updated_at: api::time(),
-        updated_by: caller,
+        revoked_at: None,
+        revoked_by: None,
+        replaced_by: None,
     };
+    readonly_principals::save(entry.clone());
+    record_readonly_principal_audit(caller, "CreateReadonlyPrincipal", &entry);
+    ApiResponse::success(entry)
+}
 
-    ORGANIZATIONS.with(|orgs| {
-        orgs.borrow_mut().insert(org_id, new_organization.clone());
-    });
-    ic_cdk::print(format!("ℹ️ [create_organization_for_owner] Organization {} created.", org_id));
-
-    if !user.org_ids.contains(&org_id) {
-        user.org_ids.push(org_id);
+#[query]
+pub fn list_readonly_principals_v2(org_id: Principal) -> ApiResponse<Vec<ReadonlyPrincipal>> {
+    if let Err(e) = authorize_for_organization(api::caller(), org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
     }
-    user.active_org_id = Some(org_id);
-    user.updated_at = api::time();
-    user.updated_by = caller;
+    ApiResponse::success(readonly_principals::list_for_org(org_id))
+}
 
-    USERS.with(|users| {
-        users.borrow_mut().insert(caller, user.clone());
-    });
-    ic_cdk::print(format!("ℹ️ [create_organization_for_owner] User {} updated with new org {} and active org set.", caller, org_id));
+fn find_active_readonly_principal(org_id: Principal, principal: Principal) -> Result<ReadonlyPrincipal, ApiError> {
+    match readonly_principals::get(principal) {
+        Some(entry) if entry.org_id == org_id && entry.revoked_at.is_none() => Ok(entry),
+        Some(entry) if entry.org_id == org_id => Err(ApiError::invalid_input("This read-only principal was already revoked")),
+        _ => Err(ApiError::not_found("Read-only principal not found in this organization")),
+    }
+}
 
-    let org_public = OrganizationPublic::from(new_organization);
-    let updated_auth_context = build_auth_context_response(&user); 
+// Swap the principal for a new one with the same label; the old one stops working immediately
+#[update]
+pub fn rotate_readonly_principal_v2(org_id: Principal, principal: Principal, new_principal: Principal) -> ApiResponse<ReadonlyPrincipal> {
+    let _metrics = endpoint_metrics::track("rotate_readonly_principal_v2");
+    let caller = api::caller();
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
+    }
+    let mut old_entry = match find_active_readonly_principal(org_id, principal) {
+        Ok(entry) => entry,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if let Err(e) = check_new_readonly_principal(new_principal, caller) {
+        return ApiResponse::error(e);
+    }
 
-    ApiResponse::success(OrganizationContextResponse {
-        organization: org_public,
-        user_auth_context: updated_auth_context,
-    })
+    let now = clock::now();
+    let new_entry = ReadonlyPrincipal {
+        principal: new_principal,
+        org_id,
+        label: old_entry.label.clone(),
+        created_at: now,
+        created_by: caller,
+        revoked_at: None,
+        revoked_by: None,
+        replaced_by: None,
+    };
+    old_entry.revoked_at = Some(now);
+    old_entry.revoked_by = Some(caller);
+    old_entry.replaced_by = Some(new_principal);
+    readonly_principals::save(old_entry.clone());
+    readonly_principals::save(new_entry.clone());
+    record_readonly_principal_audit(caller, "RotateReadonlyPrincipal", &old_entry);
+    ApiResponse::success(new_entry)
 }
 
 #[update]
-pub fn select_active_organization(org_id: Principal) -> ApiResponse<AuthContextResponse> {
+pub fn revoke_readonly_principal_v2(org_id: Principal, principal: Principal) -> ApiResponse<ReadonlyPrincipal> {
+    let _metrics = endpoint_metrics::track("revoke_readonly_principal_v2");
     let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [select_active_organization] Called by: {} to select org: {}", caller, org_id));
-
-    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
-    if user_opt.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    if let Err(e) = authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        return ApiResponse::error(e);
     }
-    let mut user = user_opt.unwrap();
+    let mut entry = match find_active_readonly_principal(org_id, principal) {
+        Ok(entry) => entry,
+        Err(e) => return ApiResponse::error(e),
+    };
+    entry.revoked_at = Some(clock::now());
+    entry.revoked_by = Some(caller);
+    readonly_principals::save(entry.clone());
+    record_readonly_principal_audit(caller, "RevokeReadonlyPrincipal", &entry);
+    ApiResponse::success(entry)
+}
 
-    if user.user_role != Some(UserRole::BrandOwner) {
-        return ApiResponse::error(ApiError::unauthorized("Only Brand Owners can select an active organization."));
-    }
+// ====== Key Escrow ======
 
-    if !user.org_ids.contains(&org_id) {
-        return ApiResponse::error(ApiError::unauthorized("User is not associated with this organization."));
-    }
-    
-    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)).is_none() {
-        return ApiResponse::error(ApiError::not_found("Organization not found."));
-    }
+const MAX_KEY_ESCROW_REASON_LENGTH: usize = 500;
 
-    user.active_org_id = Some(org_id);
-    user.updated_at = api::time();
-    user.updated_by = caller;
+// Owners and admins may see and cancel an organization's export requests
+fn authorize_key_escrow_access(caller: Principal, org_id: Principal) -> Result<(), ApiError> {
+    if ensure_admin(caller).is_ok() {
+        return Ok(());
+    }
+    authorize_for_organization(caller, org_id, Permission::WriteOrganization).map(|_| ())
+}
 
-    USERS.with(|users| {
-        users.borrow_mut().insert(caller, user.clone());
+fn record_key_escrow_audit(caller: Principal, action: &str, request: &KeyEscrowRequest) {
+    audit_log::record(AuditLogEntry {
+        user_id: caller,
+        action: action.to_string(),
+        resource_type: "Organization".to_string(),
+        resource_id: request.org_id,
+        timestamp: clock::now(),
+        metadata: vec![Metadata { key: "key_escrow_request_id".to_string(), value: request.id.to_string() }],
+        success: true,
     });
-    ic_cdk::print(format!("ℹ️ [select_active_organization] User {} set active org to {}.", caller, org_id));
-
-    let updated_auth_context = build_auth_context_response(&user); 
-    ApiResponse::success(updated_auth_context)
 }
 
-#[query]
-pub fn get_my_organizations() -> ApiResponse<Vec<OrganizationPublic>> {
+// Step 1: a brand owner asks to export the organization's signing key, encrypted to their own key
+#[update]
+pub fn request_org_key_escrow_export_v2(request: RequestKeyEscrowExportRequest) -> ApiResponse<KeyEscrowRequest> {
+    let _metrics = endpoint_metrics::track("request_org_key_escrow_export_v2");
     let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [get_my_organizations] Called by: {}", caller));
-
-    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
-    if user_opt.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    let organization = match authorize_for_organization(caller, request.org_id, Permission::WriteOrganization) {
+        Ok(org) => org,
+        Err(e) => return ApiResponse::error(e),
+    };
+    if organization.private_key.is_empty() {
+        return ApiResponse::error(ApiError::invalid_input("This organization has no exportable signing key"));
     }
-    let user = user_opt.unwrap();
-
-    if user.user_role != Some(UserRole::BrandOwner) {
-        return ApiResponse::error(ApiError::unauthorized("Only Brand Owners can list their organizations."));
+    if let Err(e) = key_escrow::parse_recipient_key(&request.recipient_public_key) {
+        return ApiResponse::error(e);
+    }
+    let reason = request.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    if reason.as_ref().is_some_and(|r| r.chars().count() > MAX_KEY_ESCROW_REASON_LENGTH) {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Reason must be at most {} characters",
+            MAX_KEY_ESCROW_REASON_LENGTH
+        )));
+    }
+    let now = clock::now();
+    if key_escrow::open_request_for_org(request.org_id, now).is_some() {
+        return ApiResponse::error(ApiError::already_exists("This organization already has an open key export request"));
     }
 
-    let mut org_public_list = Vec::new();
-    ORGANIZATIONS.with(|orgs_map| {
-        let orgs_ref = orgs_map.borrow();
-        for org_id_principal in &user.org_ids {
-            if let Some(org_record) = orgs_ref.get(org_id_principal) {
-                org_public_list.push(OrganizationPublic::from(org_record.clone()));
-            }
-        }
-    });
-
-    ApiResponse::success(org_public_list)
+    let escrow_request = KeyEscrowRequest {
+        id: generate_unique_principal(request.org_id),
+        org_id: request.org_id,
+        recipient_public_key: request.recipient_public_key.trim().to_string(),
+        reason,
+        status: KeyEscrowStatus::PendingApproval,
+        requested_by: caller,
+        requested_at: now,
+        approved_by: None,
+        approved_at: None,
+        confirmable_at: None,
+        expires_at: None,
+        exported_at: None,
+        cancelled_by: None,
+        cancelled_at: None,
+    };
+    key_escrow::save_request(escrow_request.clone());
+    record_key_escrow_audit(caller, "RequestKeyEscrowExport", &escrow_request);
+    log_warn!("[request_org_key_escrow_export_v2] Key export requested for org {} by {}", request.org_id, caller);
+    ApiResponse::success(escrow_request)
 }
 
-// ====== Phase 3: Reseller Flow ======
+// Starts the confirmation delay of a pending key export. Only run as an approved ApproveKeyExport admin operation.
+fn approve_key_escrow_request(request_id: Principal, approved_by: Principal) -> Result<KeyEscrowRequest, ApiError> {
+    let mut escrow_request = key_escrow::get_request(request_id).ok_or_else(|| ApiError::not_found("Key export request not found"))?;
+    if escrow_request.status != KeyEscrowStatus::PendingApproval {
+        return Err(ApiError::invalid_input("Only pending key export requests can be approved"));
+    }
 
-// Helper to get Reseller record by user_id
-fn get_reseller_by_user_id(user_id_principal: Principal) -> Option<Reseller> {
-    RESELLERS.with(|resellers_map| {
-        resellers_map
-            .borrow()
-            .iter()
-            .find(|(_, reseller_val)| reseller_val.user_id == user_id_principal)
-            .map(|(_, reseller_val)| reseller_val.clone())
-    })
+    let now = clock::now();
+    let confirmable_at = now.saturating_add(key_escrow::CONFIRMATION_DELAY_NS);
+    escrow_request.status = KeyEscrowStatus::Approved;
+    escrow_request.approved_by = Some(approved_by);
+    escrow_request.approved_at = Some(now);
+    escrow_request.confirmable_at = Some(confirmable_at);
+    escrow_request.expires_at = Some(confirmable_at.saturating_add(key_escrow::CONFIRMATION_WINDOW_NS));
+    key_escrow::save_request(escrow_request.clone());
+    key_escrow::schedule_ready_notification(&escrow_request);
+    record_key_escrow_audit(approved_by, "ApproveKeyEscrowExport", &escrow_request);
+    log_warn!("[approve_key_escrow_request] Key export {} for org {} approved by {}", request_id, escrow_request.org_id, approved_by);
+    Ok(escrow_request)
 }
 
+// Step 2: admins other than the requester approve. The first call proposes an ApproveKeyExport admin
+// operation and the next admin's call completes it, which starts the confirmation delay.
 #[update]
-pub fn complete_reseller_profile(request: CompleteResellerProfileRequest) -> ApiResponse<AuthContextResponse> {
+pub fn approve_org_key_escrow_export_v2(request_id: Principal) -> ApiResponse<KeyEscrowRequest> {
+    let _metrics = endpoint_metrics::track("approve_org_key_escrow_export_v2");
     let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [complete_reseller_profile] Called by: {} with request: {:?}", caller, request));
-
-    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
-    if user_opt.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+    let kind = AdminOperationKind::ApproveKeyExport { request_id };
+    let outcome = match approvals::open_for_kind(&kind, clock::now()) {
+        Some(operation) => approve_admin_operation(caller, operation.id, None),
+        None => propose_admin_operation(caller, kind, None),
+    };
+    match outcome {
+        Ok(operation) if operation.status == AdminOperationStatus::Failed => {
+            ApiResponse::error(ApiError::internal_error(operation.result.as_deref().unwrap_or("Key export approval failed")))
+        }
+        Ok(_) => match key_escrow::get_request(request_id) {
+            Some(escrow_request) => ApiResponse::success(escrow_request),
+            None => ApiResponse::error(ApiError::not_found("Key export request not found")),
+        },
+        Err(e) => ApiResponse::error(e),
     }
-    let mut user = user_opt.unwrap();
+}
 
-    if user.user_role != Some(UserRole::Reseller) {
-        return ApiResponse::error(ApiError::unauthorized("Only Resellers can complete this profile."));
+// Step 3: once the delay has passed, the requester collects the encrypted key. Unlike
+// get_organization_private_key, the key never leaves the canister in the clear.
+#[update]
+pub fn export_org_key_escrow(org_id: Principal) -> ApiResponse<KeyEscrowExport> {
+    let _metrics = endpoint_metrics::track("export_org_key_escrow");
+    let caller = api::caller();
+    let organization = match authorize_for_organization(caller, org_id, Permission::WriteOrganization) {
+        Ok(org) => org,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let now = clock::now();
+    let mut escrow_request = match key_escrow::open_request_for_org(org_id, now) {
+        Some(r) if r.status == KeyEscrowStatus::Approved => r,
+        Some(_) => return ApiResponse::error(ApiError::invalid_input("The key export request has not been approved yet")),
+        None => return ApiResponse::error(ApiError::not_found("No approved key export request for this organization")),
+    };
+    if escrow_request.requested_by != caller {
+        return ApiResponse::error(ApiError::unauthorized("Only the user who requested the export can collect it"));
     }
-
-    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.target_organization_id.clone())).is_none() {
-        return ApiResponse::error(ApiError::not_found("Target organization not found."));
+    if let Some(confirmable_at) = escrow_request.confirmable_at.filter(|at| now < *at) {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "The export can be collected in {} minute(s)",
+            (confirmable_at - now) / (60 * NANOS_PER_SECOND) + 1
+        )));
     }
 
-    let org_opt = ORGANIZATIONS.with(|orgs| orgs.borrow().get(&request.target_organization_id)).unwrap();
-    let private_key_bytes = match hex::decode(&org_opt.private_key) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to decode private key for org {}: {}", org_opt.id, e));
-            return ApiResponse::error(ApiError::internal_error(
-                "Failed to process organization secret key",
-            ));
-        }
-    };
-
-    let private_key = match SecretKey::from_slice(&private_key_bytes) { // Note: Using SecretKey, assuming this is correct for Reseller key generation
+    let recipient = match key_escrow::parse_recipient_key(&escrow_request.recipient_public_key) {
         Ok(key) => key,
-        Err(e) => {
-            ic_cdk::print(format!("❌ ERROR: Failed to create secret key from slice for org {}: {}", org_opt.id, e));
-            return ApiResponse::error(ApiError::internal_error(
-                "Malformed secret key for organization",
-            ));
-        }
+        Err(e) => return ApiResponse::error(e),
     };
-    let public_key = private_key.public_key();
-    let public_key_hex = hex::encode(public_key.to_encoded_point(false).as_bytes());
-    let existing_reseller_opt = get_reseller_by_user_id(caller);
-    let reseller_id = existing_reseller_opt.as_ref().map_or_else(
-        || generate_unique_principal(Principal::anonymous()), 
-        |r| r.id
-    );
-    
-    let cert_code = format!("CERT-{}-{}", request.target_organization_id.to_string().chars().take(5).collect::<String>(), reseller_id.to_string().chars().take(5).collect::<String>());
-    let cert_timestamp = api::time();
-
-    let reseller_record = Reseller {
-        id: reseller_id,
-        user_id: caller,
-        org_id: request.target_organization_id,
-        name: request.reseller_name,
-        contact_email: request.contact_email,
-        contact_phone: request.contact_phone,
-        ecommerce_urls: request.ecommerce_urls,
-        additional_metadata: request.additional_metadata,
-        is_verified: true, 
-        certification_code: Some(cert_code),
-        certification_timestamp: Some(cert_timestamp),
-        created_by: caller,
-        updated_by: caller,
-        date_joined: existing_reseller_opt.as_ref().map_or(api::time(), |r| r.date_joined),
-        metadata: existing_reseller_opt.as_ref().map_or(Vec::new(), |r| r.metadata.clone()), 
-        public_key: public_key_hex,
-        created_at: existing_reseller_opt.as_ref().map_or(api::time(), |r| r.created_at),
-        updated_at: api::time(), 
+    let export = match key_escrow::build_payload(&organization, now)
+        .and_then(|payload| key_escrow::encrypt_for_recipient(&escrow_request, &recipient, &payload))
+    {
+        Ok(export) => export,
+        Err(e) => return ApiResponse::error(e),
     };
 
-    RESELLERS.with(|resellers| {
-        resellers.borrow_mut().insert(reseller_id, reseller_record.clone());
-    });
-    ic_cdk::print(format!("ℹ️ [complete_reseller_profile] Reseller record {} for user {} processed.", reseller_id, caller));
+    escrow_request.status = KeyEscrowStatus::Exported;
+    escrow_request.exported_at = Some(now);
+    key_escrow::save_request(escrow_request.clone());
+    record_key_escrow_audit(caller, "ExportKeyEscrow", &escrow_request);
+    log_warn!("[export_org_key_escrow] Signing key of org {} exported by {}", org_id, caller);
+    ApiResponse::success(export)
+}
 
-    user.org_ids = vec![request.target_organization_id];
-    user.updated_at = api::time();
-    user.updated_by = caller;
-    USERS.with(|users| {
-        users.borrow_mut().insert(caller, user.clone());
-    });
-    ic_cdk::print(format!("ℹ️ [complete_reseller_profile] User {} updated with org_id {}.", caller, request.target_organization_id));
+#[update]
+pub fn cancel_org_key_escrow_export_v2(request_id: Principal) -> ApiResponse<KeyEscrowRequest> {
+    let _metrics = endpoint_metrics::track("cancel_org_key_escrow_export_v2");
+    let caller = api::caller();
+    let mut escrow_request = match key_escrow::get_request(request_id) {
+        Some(r) => r,
+        None => return ApiResponse::error(ApiError::not_found("Key export request not found")),
+    };
+    if let Err(e) = authorize_key_escrow_access(caller, escrow_request.org_id) {
+        return ApiResponse::error(e);
+    }
+    if !key_escrow::is_open(&escrow_request, clock::now()) {
+        return ApiResponse::error(ApiError::invalid_input("This key export request is no longer open"));
+    }
 
-    let updated_auth_context = build_auth_context_response(&user); 
-    ApiResponse::success(updated_auth_context)
+    escrow_request.status = KeyEscrowStatus::Cancelled;
+    escrow_request.cancelled_by = Some(caller);
+    escrow_request.cancelled_at = Some(clock::now());
+    key_escrow::save_request(escrow_request.clone());
+    record_key_escrow_audit(caller, "CancelKeyEscrowExport", &escrow_request);
+    ApiResponse::success(escrow_request)
 }
 
 #[query]
-pub fn get_my_reseller_certification() -> ApiResponse<ResellerCertificationPageContext> {
-    let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [get_my_reseller_certification] Called by: {}", caller));
-
-    let user_opt = USERS.with(|users| users.borrow().get(&caller).clone()); // Cloned here
-    if user_opt.is_none() {
-        return ApiResponse::error(ApiError::unauthorized("User not registered."));
+pub fn list_org_key_escrow_requests_v2(org_id: Principal) -> ApiResponse<Vec<KeyEscrowRequest>> {
+    if let Err(e) = authorize_key_escrow_access(api::caller(), org_id) {
+        return ApiResponse::error(e);
     }
-    let user = user_opt.unwrap();
+    ApiResponse::success(key_escrow::list_for_org(org_id))
+}
 
-    if user.user_role != Some(UserRole::Reseller) {
-        return ApiResponse::error(ApiError::unauthorized("Only Resellers can access certification details."));
+// ====== Admin Approvals ======
+
+// User ID of the calling admin, so approvals count people rather than session keys
+fn admin_user_id(caller: Principal) -> Result<Principal, ApiError> {
+    ensure_admin(caller)?;
+    find_user_by_caller(caller)
+        .map(|user| user.id)
+        .ok_or_else(|| ApiError::not_found("User not found"))
+}
+
+fn record_admin_operation_audit(admin_id: Principal, action: &str, operation: &AdminOperation, success: bool) {
+    audit_log::record(AuditLogEntry {
+        user_id: admin_id,
+        action: action.to_string(),
+        resource_type: "AdminOperation".to_string(),
+        resource_id: operation.id,
+        timestamp: clock::now(),
+        metadata: vec![
+            Metadata { key: "kind".to_string(), value: format!("{:?}", operation.kind) },
+            Metadata { key: "status".to_string(), value: format!("{:?}", operation.status) },
+        ],
+        success,
+    });
+}
+
+// Whether the operation's target still allows it, and whether this admin may approve it
+fn validate_admin_operation(kind: &AdminOperationKind, admin_id: Principal) -> Result<(), ApiError> {
+    match kind {
+        AdminOperationKind::ResetStorage => Ok(()),
+        AdminOperationKind::SetRequiredApprovals { required } => approvals::validate_required_approvals(*required),
+        AdminOperationKind::DeleteOrganization { org_id } => ORGANIZATIONS
+            .with(|orgs| orgs.borrow().get(org_id))
+            .map(|_| ())
+            .ok_or_else(|| ApiError::not_found("Organization not found")),
+        AdminOperationKind::ApproveKeyExport { request_id } => {
+            let escrow_request = key_escrow::get_request(*request_id)
+                .ok_or_else(|| ApiError::not_found("Key export request not found"))?;
+            if escrow_request.status != KeyEscrowStatus::PendingApproval {
+                return Err(ApiError::invalid_input("Only pending key export requests can be approved"));
+            }
+            let requester = find_user_by_caller(escrow_request.requested_by).map_or(escrow_request.requested_by, |user| user.id);
+            if requester == admin_id {
+                return Err(ApiError::unauthorized("A key export must be approved by admins other than the requester"));
+            }
+            Ok(())
+        }
     }
+}
 
-    let reseller_record_opt = get_reseller_by_user_id(caller);
-    if reseller_record_opt.is_none() || !reseller_record_opt.as_ref().unwrap().is_verified {
-        return ApiResponse::error(ApiError::unauthorized("Reseller profile is not complete or verified."));
+// Removes an organization with its products, their serial numbers and verifications, its resellers,
+// its verification domain and public stats, the records the feature modules keep for it, and its
+// users' memberships. Only run as an approved DeleteOrganization admin operation.
+fn delete_organization_records(org_id: Principal) -> Result<String, ApiError> {
+    if ORGANIZATIONS.with(|orgs| orgs.borrow().get(&org_id)).is_none() {
+        return Err(ApiError::not_found("Organization not found"));
     }
-    let reseller_record = reseller_record_opt.unwrap(); 
 
-    let associated_org_public_opt = ORGANIZATIONS.with(|orgs_map| {
-        orgs_map.borrow().get(&reseller_record.org_id).map(|org| OrganizationPublic::from(org.clone()))
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
     });
-    if associated_org_public_opt.is_none() {
-        return ApiResponse::error(ApiError::internal_error("Associated organization not found for reseller."));
+    let mut serial_count = 0;
+    for product_id in &product_ids {
+        for serial in serial_store::list_for_product(*product_id) {
+            serial_store::remove(*product_id, serial.serial_no);
+            serial_count += 1;
+        }
+        verification_store::take_recorded_before(*product_id, u64::MAX, usize::MAX);
+        PRODUCT_SERIAL_NUMBERS.with(|sns| sns.borrow_mut().remove(product_id));
+        PRODUCT_VERIFICATIONS.with(|vers| vers.borrow_mut().remove(product_id));
+        PRODUCTS.with(|products| products.borrow_mut().remove(product_id));
     }
-    let associated_organization = associated_org_public_opt.unwrap();
 
-    let reseller_public = ResellerPublic {
-        id: reseller_record.id,
-        user_id: reseller_record.user_id,
-        organization_id: reseller_record.org_id,
-        name: reseller_record.name.clone(),
-        public_key: reseller_record.public_key.clone(),
-        contact_email: reseller_record.contact_email.clone(),
-        contact_phone: reseller_record.contact_phone.clone(),
-        ecommerce_urls: reseller_record.ecommerce_urls.clone(),
-        additional_metadata: reseller_record.additional_metadata.clone(),
-        is_verified: reseller_record.is_verified,
-        certification_code: reseller_record.certification_code.clone(),
-        certification_timestamp: reseller_record.certification_timestamp,
-        created_at: reseller_record.created_at,
-        updated_at: reseller_record.updated_at,
-    };
+    let reseller_ids: Vec<Principal> = RESELLERS.with(|resellers| {
+        resellers
+            .borrow()
+            .iter()
+            .filter(|(_, reseller)| reseller.org_id == org_id)
+            .map(|(id, _)| id)
+            .collect()
+    });
+    RESELLERS.with(|resellers| {
+        let mut resellers_mut = resellers.borrow_mut();
+        for reseller_id in &reseller_ids {
+            resellers_mut.remove(reseller_id);
+        }
+    });
 
-    let user_details_public = UserPublic {
-        id: user.id,
-        first_name: user.first_name.clone(),
-        last_name: user.last_name.clone(),
-        email: user.email.clone(),
-        created_at: user.created_at,
-    };
-    
-    if reseller_public.certification_code.is_none() || reseller_public.certification_timestamp.is_none() {
-        ic_cdk::print(format!("❌ ERROR [get_my_reseller_certification] Missing cert code or timestamp for verified reseller {}", reseller_public.id));
-        return ApiResponse::error(ApiError::internal_error("Certification details missing for verified reseller."));
+    let members: Vec<User> = USERS.with(|users| {
+        users
+            .borrow()
+            .iter()
+            .map(|(_, user)| user)
+            .filter(|user| user.org_ids.contains(&org_id))
+            .collect()
+    });
+    let member_count = members.len();
+    for mut user in members {
+        user.org_ids.retain(|id| *id != org_id);
+        if user.active_org_id == Some(org_id) {
+            user.active_org_id = user.org_ids.first().copied();
+        }
+        USERS.with(|users| users.borrow_mut().insert(user.id, user));
     }
 
-    ApiResponse::success(ResellerCertificationPageContext {
-        reseller_profile: reseller_public.clone(),
-        associated_organization,
-        certification_code: reseller_public.certification_code.unwrap(), 
-        certification_timestamp: reseller_public.certification_timestamp.unwrap(), 
-        user_details: user_details_public,
-    })
+    public_stats::disable(org_id);
+    verification_domains::remove_domain(org_id);
+    let feature_records = variants::delete_for_org(org_id)
+        + disputes::delete_for_org(org_id)
+        + webhooks::delete_for_org(org_id)
+        + reports::delete_for_org(org_id)
+        + quotas::delete_for_org(org_id)
+        + org_settings::delete_for_org(org_id)
+        + print_jobs::delete_for_org(org_id)
+        + key_escrow::delete_for_org(org_id)
+        + capability_tokens::delete_for_org(org_id)
+        + jobs::delete_for_org(org_id)
+        + outbox::delete_for_org(org_id)
+        + plans::delete_for_org(org_id);
+    ORGANIZATIONS.with(|orgs| orgs.borrow_mut().remove(&org_id));
+
+    Ok(format!(
+        "Deleted organization {} with {} product(s), {} serial number(s), {} reseller(s) and {} other record(s); {} user(s) lost access",
+        org_id,
+        product_ids.len(),
+        serial_count,
+        reseller_ids.len(),
+        feature_records,
+        member_count
+    ))
 }
 
-// ====== Phase 4: Profile and Navigation ======
+fn execute_admin_operation(kind: &AdminOperationKind, caller: Principal) -> Result<String, ApiError> {
+    match kind {
+        AdminOperationKind::ResetStorage => reset_all_storage().map(|_| "All stable storage was reset".to_string()),
+        AdminOperationKind::ApproveKeyExport { request_id } => approve_key_escrow_request(*request_id, caller)
+            .map(|r| format!("Key export {} for organization {} approved", r.id, r.org_id)),
+        AdminOperationKind::DeleteOrganization { org_id } => delete_organization_records(*org_id),
+        AdminOperationKind::SetRequiredApprovals { required } => {
+            let mut canister_config = config::get_config();
+            canister_config.required_admin_approvals = Some(*required);
+            canister_config.updated_by = caller;
+            config::set_config(canister_config)?;
+            Ok(format!(
+                "Admin operations now need {} of the {} enabled admin(s) to approve",
+                required,
+                approvals::enabled_admin_count()
+            ))
+        }
+    }
+}
 
-#[query]
-pub fn get_navigation_context() -> ApiResponse<NavigationContextResponse> {
-    let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [get_navigation_context] Called by: {}", caller));
+fn propose_admin_operation(caller: Principal, kind: AdminOperationKind, reason: Option<String>) -> Result<AdminOperation, ApiError> {
+    let admin_id = admin_user_id(caller)?;
+    validate_admin_operation(&kind, admin_id)?;
+    let operation = approvals::propose(generate_unique_principal(admin_id), kind, reason, admin_id, clock::now())?;
+    record_admin_operation_audit(admin_id, "ProposeAdminOperation", &operation, true);
+    log_warn!("[propose_admin_operation] {:?} proposed as operation {} by {}", operation.kind, operation.id, admin_id);
+    // With a threshold of one, the proposer's own approval is enough
+    if approvals::is_ready(&operation) {
+        return Ok(execute_approved_operation(operation, admin_id, caller));
+    }
+    Ok(operation)
+}
 
-    match USERS.with(|users| users.borrow().get(&caller).clone()) { // Cloned here
-        Some(user) => {
-            let display_name = user.first_name.as_ref().map_or_else(
-                || user.email.as_ref().map_or_else(|| user.id.to_string(), |e| e.clone()),
-                |f_name| f_name.clone()
-            );
+// Run an operation that has all its approvals and record how it went
+fn execute_approved_operation(operation: AdminOperation, admin_id: Principal, caller: Principal) -> AdminOperation {
+    let outcome = execute_admin_operation(&operation.kind, caller);
+    // A storage reset wipes the approvals and audit log; both are written again after it
+    let operation = approvals::finish(operation, &outcome, clock::now());
+    record_admin_operation_audit(admin_id, "ExecuteAdminOperation", &operation, outcome.is_ok());
+    log_warn!("[execute_approved_operation] Operation {} ({:?}) ran with status {:?}", operation.id, operation.kind, operation.status);
+    operation
+}
 
-            let mut current_org_name: Option<String> = None;
+// Add the caller's approval, running the operation once it has all the approvals it needs
+fn approve_admin_operation(caller: Principal, operation_id: Principal, note: Option<String>) -> Result<AdminOperation, ApiError> {
+    let admin_id = admin_user_id(caller)?;
+    let now = clock::now();
+    let pending = approvals::get(operation_id, now).ok_or_else(|| ApiError::not_found("Admin operation not found"))?;
+    validate_admin_operation(&pending.kind, admin_id)?;
+
+    let (operation, ready) = approvals::approve(operation_id, admin_id, note, now)?;
+    record_admin_operation_audit(admin_id, "ApproveAdminOperation", &operation, true);
+    if !ready {
+        return Ok(operation);
+    }
+    Ok(execute_approved_operation(operation, admin_id, caller))
+}
 
-            if user.user_role == Some(UserRole::BrandOwner) {
-                if let Some(active_org_id) = user.active_org_id {
-                    current_org_name = ORGANIZATIONS.with(|orgs| 
-                        orgs.borrow().get(&active_org_id).map(|org| org.name.clone())
-                    );
-                }
-            } else if user.user_role == Some(UserRole::Reseller) {
-                if let Some(reseller_record) = get_reseller_by_user_id(user.id) {
-                    current_org_name = ORGANIZATIONS.with(|orgs| 
-                        orgs.borrow().get(&reseller_record.org_id).map(|org| org.name.clone())
-                    );
-                }
-            }
+// Propose a destructive operation. It runs once other admins bring it to the required approvals
+// within the approval window. The number required is itself changed by a SetRequiredApprovals operation.
+#[update]
+pub fn propose_admin_operation_v2(request: ProposeAdminOperationRequest) -> ApiResponse<AdminOperation> {
+    let _metrics = endpoint_metrics::track("propose_admin_operation_v2");
+    match propose_admin_operation(api::caller(), request.kind, request.reason) {
+        Ok(operation) => ApiResponse::success(operation),
+        Err(e) => ApiResponse::error(e),
+    }
+}
 
-            ApiResponse::success(NavigationContextResponse {
-                user_display_name: display_name,
-                user_avatar_id: None, 
-                current_organization_name: current_org_name,
-            })
-        }
-        None => {
-            ic_cdk::print(format!("ℹ️ [get_navigation_context] User {} not found.", caller));
-            ApiResponse::error(ApiError::unauthorized("User not authenticated.")) 
+// Approve a pending operation. The approval that completes it runs the operation; check status and
+// result of the returned record for the outcome.
+#[update]
+pub fn approve_admin_operation_v2(request: AdminOperationDecisionRequest) -> ApiResponse<AdminOperation> {
+    let _metrics = endpoint_metrics::track("approve_admin_operation_v2");
+    match approve_admin_operation(api::caller(), request.operation_id, request.note) {
+        Ok(operation) => ApiResponse::success(operation),
+        Err(e) => ApiResponse::error(e),
+    }
+}
+
+// Turn a pending operation down. One admin's rejection is enough.
+#[update]
+pub fn reject_admin_operation_v2(request: AdminOperationDecisionRequest) -> ApiResponse<AdminOperation> {
+    let _metrics = endpoint_metrics::track("reject_admin_operation_v2");
+    let admin_id = match admin_user_id(api::caller()) {
+        Ok(id) => id,
+        Err(e) => return ApiResponse::error(e),
+    };
+    match approvals::reject(request.operation_id, admin_id, request.note, clock::now()) {
+        Ok(operation) => {
+            record_admin_operation_audit(admin_id, "RejectAdminOperation", &operation, true);
+            log_warn!("[reject_admin_operation_v2] Operation {} ({:?}) rejected by {}", operation.id, operation.kind, admin_id);
+            ApiResponse::success(operation)
         }
+        Err(e) => ApiResponse::error(e),
     }
 }
 
-// ====== Phase 5: Reward Redemption (New Endpoint) ======
+// Admin operations, newest first. Filter by Pending for the ones waiting on an approval.
+#[query]
+pub fn list_admin_operations_v2(
+    status: Option<AdminOperationStatus>,
+    pagination: Option<PaginationRequest>,
+) -> ApiResponse<AdminOperationsResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+
+    let (operations, page_info) = paginate(approvals::list(status, clock::now()), &pagination.unwrap_or_default());
+    ApiResponse::success(AdminOperationsResponse { operations, pagination: Some(page_info) })
+}
+
+// Who proposed, approved, rejected and ran an admin operation, and when
+#[query]
+pub fn list_admin_operation_audit_log_v2(
+    operation_id: Principal,
+    pagination: Option<PaginationRequest>,
+) -> ApiResponse<AuditLogListResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    if approvals::get(operation_id, clock::now()).is_none() {
+        return ApiResponse::error(ApiError::not_found("Admin operation not found"));
+    }
+    let entries = audit_log::list_for_resource("AdminOperation", operation_id);
+    let (entries, page_info) = paginate(entries, &pagination.unwrap_or_default());
+    ApiResponse::success(AuditLogListResponse { entries, pagination: Some(page_info) })
+}
+
+// ====== Terms and Consent ======
 
+// Publish a new terms version. Users have to accept it before their next reward-earning action.
 #[update]
-pub fn redeem_product_reward(request: RedeemRewardRequest) -> ApiResponse<RedeemRewardResponse> {
+pub fn publish_terms_v2(request: PublishTermsRequest) -> ApiResponse<TermsVersion> {
+    let _metrics = endpoint_metrics::track("publish_terms_v2");
     let caller = api::caller();
-    ic_cdk::print(format!("ℹ️ [redeem_product_reward] Called by: {} for serial: {}", caller, request.serial_no));
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
 
-    // --- 1. Re-verify the original verification request to ensure legitimacy & get product_id/print_version --- 
-    let mut found_product_id: Option<Principal> = None;
-    let mut found_product_sn_record: Option<ProductSerialNumber> = None;
+    let mut validator = Validator::new();
+    let version = validator.required_text("version", &request.version, terms::MAX_TERMS_VERSION_LENGTH);
+    let url = request.url.trim().to_string();
+    validator.url("url", &url);
+    if let Err(e) = validator.finish() {
+        return ApiResponse::error(e);
+    }
+    if terms::version_exists(&version) {
+        return ApiResponse::error(ApiError::already_exists("This terms version has already been published"));
+    }
 
-    PRODUCT_SERIAL_NUMBERS.with(|serial_numbers_map_ref| {
-        let serial_numbers_map = serial_numbers_map_ref.borrow();
-        for (p_id, storable_bytes) in serial_numbers_map.iter() {
-            let sn_vec = decode_product_serial_numbers(&storable_bytes);
-            if let Some(matching_sn) = sn_vec.iter().find(|sn| sn.serial_no == request.serial_no) {
-                found_product_id = Some(p_id);
-                found_product_sn_record = Some(matching_sn.clone());
-                break; 
-            }
-        }
+    let published = terms::publish(TermsVersion {
+        seq: 0, // Assigned by the store
+        version,
+        url,
+        published_at: clock::now(),
+        published_by: caller,
     });
+    log_info!("[publish_terms_v2] Terms version {} published by {}", published.version, caller);
+    ApiResponse::success(published)
+}
 
-    let product_id = match found_product_id {
-        Some(id) => id,
-        None => return ApiResponse::error(ApiError::invalid_input("Serial number not found or invalid for redemption.")),
-    };
+#[query]
+pub fn list_terms_versions_v2() -> ApiResponse<Vec<TermsVersion>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(terms::list_versions())
+}
 
-    let product_sn_record = match found_product_sn_record {
-        Some(psn) => psn,
-        None => return ApiResponse::error(ApiError::internal_error("Inconsistent serial number data during redemption.")), 
-    };
+// Current terms and whether the caller has accepted them
+#[query]
+pub fn get_terms_status_v2() -> ApiResponse<TermsStatusResponse> {
+    let caller = api::caller();
+    let current = terms::current_terms();
+    let accepted = current.as_ref().is_none_or(|t| terms::has_accepted(caller, t.seq));
+    ApiResponse::success(TermsStatusResponse {
+        current,
+        accepted,
+        acceptances: terms::list_for_user(caller),
+    })
+}
 
-    let product_opt = PRODUCTS.with(|products| products.borrow().get(&product_id).map(|p| p.clone()));
-    if product_opt.is_none() {
-        return ApiResponse::error(ApiError::internal_error("Product data inconsistent: Product not found for existing serial number during redemption."));
+// Only the current version can be accepted, so clients cannot consent to terms the user was not shown
+#[update]
+pub fn accept_terms_v2(version: String) -> ApiResponse<TermsAcceptance> {
+    let _metrics = endpoint_metrics::track("accept_terms_v2");
+    let caller = api::caller();
+    if caller == Principal::anonymous() {
+        return ApiResponse::error(ApiError::unauthorized("Sign in to accept the terms"));
     }
-    let product = product_opt.unwrap();
-    let print_version_from_storage = product_sn_record.print_version;
-
-    // Verify signature again to ensure this request is for the same valid code
-    let public_key_bytes = match hex::decode(&product.public_key) {
-        Ok(bytes) => bytes,
-        Err(_) => return ApiResponse::error(ApiError::internal_error("Malformed public key during redemption.")),
-    };
-    let public_key_encoded_point = match EncodedPoint::from_bytes(public_key_bytes) {
-        Ok(point) => point,
-        Err(_) => return ApiResponse::error(ApiError::internal_error("Malformed public key during redemption.")),
-    };
-    let public_key = match VerifyingKey::from_encoded_point(&public_key_encoded_point) {
-        Ok(key) => key,
-        Err(_) => return ApiResponse::error(ApiError::internal_error("Malformed public key during redemption.")),
-    };
-    let msg_to_verify = format!(
-        "{}_{}_{}",
-        product_id.to_string(),
-        request.serial_no.to_string(),
-        print_version_from_storage
-    );
-    let mut hasher = Sha256::new();
-    hasher.update(msg_to_verify);
-    let hashed_message = hasher.finalize();
-    let decoded_code = match hex::decode(&request.unique_code) {
-        Ok(bytes) => bytes,
-        Err(_) => return ApiResponse::error(ApiError::invalid_input("Malformed unique code during redemption.")),
-    };
-    let signature = match Signature::from_slice(decoded_code.as_slice()) {
-        Ok(sig) => sig,
-        Err(_) => return ApiResponse::error(ApiError::invalid_input("Invalid signature format during redemption.")),
+    if let Err(e) = ensure_caller_enabled(caller) {
+        return ApiResponse::error(e);
+    }
+    let current = match terms::current_terms() {
+        Some(terms) => terms,
+        None => return ApiResponse::error(ApiError::not_found("No terms have been published")),
     };
-    if public_key.verify(&hashed_message, &signature).is_err() {
-        return ApiResponse::error(ApiError::invalid_input("Unique code verification failed during redemption attempt."));
+    if current.version != version.trim() {
+        return ApiResponse::error(ApiError::invalid_input(&format!(
+            "Version {} is not the current terms version ({})",
+            version.trim(),
+            current.version
+        )));
     }
 
-    // --- 2. Find the specific verification record for this user, product, serial, and version --- 
-    let mut target_verification_opt: Option<ProductVerification> = None;
-    let mut target_verification_index: Option<usize> = None;
-
-    PRODUCT_VERIFICATIONS.with(|verifications_map| {
-        if let Some(verifications_bytes) = verifications_map.borrow().get(&product_id) {
-            let verifications = decode_product_verifications(&verifications_bytes);
-            for (index, verification) in verifications.iter().enumerate() {
-                if verification.created_by == caller 
-                    && verification.serial_no == request.serial_no 
-                    && verification.print_version == print_version_from_storage 
-                {
-                    target_verification_opt = Some(verification.clone());
-                    target_verification_index = Some(index);
-                    break;
-                }
-            }
-        }
-    });
+    let acceptance = TermsAcceptance {
+        user_id: caller,
+        terms_seq: current.seq,
+        version: current.version,
+        accepted_at: clock::now(),
+    };
+    terms::record_acceptance(acceptance.clone());
+    log_info!("[accept_terms_v2] {} accepted terms version {}", caller, acceptance.version);
+    ApiResponse::success(acceptance)
+}
 
-    if target_verification_opt.is_none() {
-        ic_cdk::print(format!("⚠️ [redeem_product_reward] No matching verification found for user {}, serial {}, version {}", caller, request.serial_no, print_version_from_storage));
-        return ApiResponse::error(ApiError::not_found("No eligible verification record found for this redemption request."));
-    }
+// ====== Platform Monitoring ======
 
-    let mut verification_to_update = target_verification_opt.unwrap();
-    let verification_index = target_verification_index.unwrap();
+const DEFAULT_CYCLES_HISTORY_LIMIT: usize = 24;
 
-    // --- 3. Check if reward was already claimed or if it wasn't a first verification --- 
-    if verification_to_update.reward_claimed {
-        return ApiResponse::success(RedeemRewardResponse {
-            success: false,
-            transaction_id: verification_to_update.reward_transaction_id.clone(),
-            message: "Reward for this verification has already been claimed.".to_string(),
-        });
+#[query]
+pub fn get_platform_metrics_v2() -> ApiResponse<PlatformMetricsResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
     }
+    ApiResponse::success(PlatformMetricsResponse {
+        cycles: cycles_monitor::current_status(),
+        organizations: ORGANIZATIONS.with(|orgs| orgs.borrow().len()),
+        products: PRODUCTS.with(|products| products.borrow().len()),
+        users: USERS.with(|users| users.borrow().len()),
+        generated_at: clock::now(),
+    })
+}
 
-    if verification_to_update.status != ProductVerificationStatus::FirstVerification {
-        return ApiResponse::success(RedeemRewardResponse {
-            success: false,
-            transaction_id: None,
-            message: "Reward can only be claimed for the first verification.".to_string(),
-        });
+// Hourly balance samples, newest first
+#[query]
+pub fn get_cycles_history_v2(limit: Option<u32>) -> ApiResponse<Vec<CyclesSample>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
     }
+    let limit = limit.map_or(DEFAULT_CYCLES_HISTORY_LIMIT, |l| l as usize);
+    ApiResponse::success(cycles_monitor::history(limit))
+}
 
-    // --- 4. Calculate expected reward points (optional, could be stored in verification metadata) ---
-    let rewards = rewards::calculate_verification_rewards(caller, product_id, &verification_to_update.status);
-    if rewards.points == 0 {
-        // This case might happen if reward logic changes or there was an issue during initial calculation
-        // Mark as claimed anyway to prevent future attempts
-        verification_to_update.reward_claimed = true;
-        // Persist the change
-        PRODUCT_VERIFICATIONS.with(|verifications_map| {
-            let mut map_mut = verifications_map.borrow_mut();
-            if let Some(verifications_bytes) = map_mut.get(&product_id) {
-                let mut verifications = decode_product_verifications(&verifications_bytes);
-                if verification_index < verifications.len() {
-                    verifications[verification_index] = verification_to_update.clone();
-                    map_mut.insert(product_id, encode_product_verifications(&verifications));
-                }
-            }
-        });
-        return ApiResponse::success(RedeemRewardResponse {
-            success: false,
-            transaction_id: None,
-            message: "No points were associated with this verification.".to_string(),
-        });
+// Calls, errors by code and latency of each update endpoint, the most failing first
+#[query]
+pub fn get_endpoint_metrics_v2() -> ApiResponse<Vec<EndpointMetricsSummary>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
     }
 
-    // --- 5. Simulate Reward Transfer (TODO: Replace with actual ledger interaction) --- 
-    ic_cdk::print(format!(
-        "✅ [redeem_product_reward] SIMULATING transfer of {} points to wallet {} for user {} verification {}",
-        rewards.points,
-        request.wallet_address,
-        caller,
-        verification_to_update.id
-    ));
-
-    // Simulate success and generate a fake transaction ID
-    let simulated_tx_id = format!("simulated-tx-{}", verification_to_update.id);
-    let redemption_successful = true; // Assume simulation success for now
-
-    // --- 6. Update Verification Record --- 
-    if redemption_successful {
-        verification_to_update.reward_claimed = true;
-        verification_to_update.reward_transaction_id = Some(simulated_tx_id.clone());
-
-        // Persist the updated verification record
-        PRODUCT_VERIFICATIONS.with(|verifications_map| {
-            let mut map_mut = verifications_map.borrow_mut();
-            // Re-fetch the vector in case it was modified concurrently (unlikely in IC but good practice)
-            if let Some(verifications_bytes) = map_mut.get(&product_id) {
-                let mut verifications = decode_product_verifications(&verifications_bytes);
-                // Ensure index is still valid before updating
-                if verification_index < verifications.len() && verifications[verification_index].id == verification_to_update.id {
-                    verifications[verification_index] = verification_to_update.clone();
-                    map_mut.insert(product_id, encode_product_verifications(&verifications));
-                    ic_cdk::print(format!("ℹ️ [redeem_product_reward] Marked verification {} as claimed.", verification_to_update.id));
-                } else {
-                    ic_cdk::print(format!("❌ ERROR [redeem_product_reward] Verification record index {} mismatch for verification {}. Claim status not updated.", verification_index, verification_to_update.id));
-                    // Decide how to handle this: maybe return an internal error? For now, log and proceed.
-                }
-            } else {
-                 ic_cdk::print(format!("❌ ERROR [redeem_product_reward] Could not find verification vector for product {} while trying to update claim status.", product_id));
-                 // Decide how to handle this. For now, log and proceed.
+    let mut summaries: Vec<EndpointMetricsSummary> = endpoint_metrics::list()
+        .into_iter()
+        .map(|metrics| {
+            let mut error_codes = metrics.error_codes;
+            error_codes.sort_by_key(|a| std::cmp::Reverse(a.count));
+            EndpointMetricsSummary {
+                error_rate_percent: if metrics.calls == 0 { 0.0 } else { metrics.errors as f64 * 100.0 / metrics.calls as f64 },
+                p50_instructions: endpoint_metrics::percentile(&metrics.recent_instructions, 50),
+                p95_instructions: endpoint_metrics::percentile(&metrics.recent_instructions, 95),
+                mean_instructions: if metrics.calls == 0 { 0 } else { (metrics.total_instructions / metrics.calls as u128) as u64 },
+                max_instructions: metrics.max_instructions,
+                sampled_calls: metrics.recent_instructions.len() as u32,
+                endpoint: metrics.endpoint,
+                calls: metrics.calls,
+                errors: metrics.errors,
+                error_codes,
+                since: metrics.since,
+                last_called_at: metrics.last_called_at,
             }
-        });
-
-        ApiResponse::success(RedeemRewardResponse {
-            success: true,
-            transaction_id: Some(simulated_tx_id),
-            message: format!("Successfully redeemed {} points.", rewards.points),
         })
-    } else {
-        // Handle simulated failure (or real failure from ledger)
-        ApiResponse::error(ApiError::external_api_error("Failed to process reward transaction."))
-    }
+        .collect();
+    summaries.sort_by(|a, b| b.errors.cmp(&a.errors).then_with(|| b.calls.cmp(&a.calls)));
+    ApiResponse::success(summaries)
 }
 
-// Make sure to export the new types if they are in a different module and used by Candid.
-// However, these are directly in models.rs which is part of the crate.
+// Start a new measurement period
+#[update]
+pub fn reset_endpoint_metrics_v2() -> ApiResponse<bool> {
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    endpoint_metrics::reset_endpoint_metrics();
+    log_info!("[reset_endpoint_metrics_v2] Endpoint metrics reset by {}", caller);
+    ApiResponse::success(true)
+}
 
+// State of the circuit breaker of each external integration
 #[query]
-pub fn get_organization_analytic(request: GetOrganizationAnalyticRequest) -> ApiResponse<OrganizationAnalyticData> {
+pub fn get_circuit_breakers_v2() -> ApiResponse<Vec<CircuitBreaker>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
+    ApiResponse::success(circuit_breakers::ALL_SERVICES.iter().map(|service| circuit_breakers::get(*service)).collect())
+}
+
+// Close a breaker without waiting for its probe, e.g. once the service is known to be back
+#[update]
+pub fn reset_circuit_breaker_v2(service: ExternalService) -> ApiResponse<CircuitBreaker> {
+    let _metrics = endpoint_metrics::track("reset_circuit_breaker_v2");
     let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
+    let breaker = circuit_breakers::reset(service);
+    log_info!("[reset_circuit_breaker_v2] {} circuit breaker closed by {}", circuit_breakers::service_name(service), caller);
+    ApiResponse::success(breaker)
+}
 
-    // Authorize user
-    match authorize_for_organization(caller, request.org_id, Permission::ReadOrganization) {
-        Ok(_) => {
-            // Calculate total products
-            let total_products = PRODUCTS.with(|products_map| {
-                products_map
-                    .borrow()
-                    .iter()
-                    .filter(|(_, product)| product.org_id == request.org_id)
-                    .count() as u64
-            });
+// ====== Webhook Outbox ======
 
-            // Calculate active resellers (assuming active means is_verified = true)
-            let active_resellers = RESELLERS.with(|resellers_map| {
-                resellers_map
-                    .borrow()
-                    .iter()
-                    .filter(|(_, reseller)| reseller.org_id == request.org_id && reseller.is_verified)
-                    .count() as u64
-            });
+// Outgoing webhooks still waiting for delivery, oldest first. Filter by status to find dead letters.
+#[query]
+pub fn list_outbox_deliveries_v2(
+    status: Option<OutboxDeliveryStatus>,
+    pagination: Option<PaginationRequest>,
+) -> ApiResponse<OutboxDeliveriesResponse> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
+    }
 
-            // Calculate verifications in the last 30 days
-            const THIRTY_DAYS_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
-            let thirty_days_ago_ns = api::time().saturating_sub(THIRTY_DAYS_NS);
-
-            let mut verifications_this_month: u64 = 0;
-            let products_in_org_ids = PRODUCTS.with(|p_store| {
-                p_store
-                    .borrow()
-                    .iter()
-                    .filter(|(_, p)| p.org_id == request.org_id)
-                    .map(|(p_id, _)| p_id)
-                    .collect::<Vec<Principal>>()
-            });
+    let (deliveries, page_info) = paginate(outbox::list_deliveries(status), &pagination.unwrap_or_default());
+    ApiResponse::success(OutboxDeliveriesResponse { deliveries, pagination: Some(page_info) })
+}
 
-            PRODUCT_VERIFICATIONS.with(|pv_store| {
-                let store = pv_store.borrow();
-                for product_id in products_in_org_ids {
-                    if let Some(serialized_verifications) = store.get(&product_id) {
-                        let decoded_verifications = decode_product_verifications(&serialized_verifications);
-                        for verification in decoded_verifications {
-                            if verification.created_at >= thirty_days_ago_ns {
-                                verifications_this_month += 1;
-                            }
-                        }
-                    }
-                }
-            });
+// Retry a dead-lettered delivery, e.g. after the receiving endpoint was fixed
+#[update]
+pub fn requeue_outbox_delivery_v2(delivery_id: u64) -> ApiResponse<OutboxDelivery> {
+    let _metrics = endpoint_metrics::track("requeue_outbox_delivery_v2");
+    let caller = api::caller();
+    if let Err(e) = ensure_admin(caller) {
+        return ApiResponse::error(e);
+    }
 
-            let analytic_data = OrganizationAnalyticData {
-                total_products,
-                active_resellers,
-                verifications_this_month,
-            };
-            ApiResponse::success(analytic_data)
+    match outbox::requeue(delivery_id) {
+        Some(delivery) => {
+            log_info!("[requeue_outbox_delivery_v2] Delivery {} requeued by {}", delivery_id, caller);
+            ApiResponse::success(delivery)
         }
-        Err(e) => ApiResponse::error(e),
+        None => ApiResponse::error(ApiError::not_found("No dead-lettered delivery with this ID")),
+    }
+}
+
+// ====== Logs ======
+
+const DEFAULT_LOG_LIMIT: u32 = 100;
+const MAX_LOG_LIMIT: u32 = 1_000;
+
+// Most recent buffered log entries, newest first. `level` is the minimum level (default Info) and
+// `module` matches any module whose name contains it, e.g. "rewards".
+#[query]
+pub fn get_recent_logs_v2(level: Option<LogLevel>, module: Option<String>, limit: Option<u32>) -> ApiResponse<Vec<LogEntry>> {
+    if let Err(e) = ensure_admin(api::caller()) {
+        return ApiResponse::error(e);
     }
+
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT) as usize;
+    let module = module.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+    ApiResponse::success(logging::recent_logs(level.unwrap_or(LogLevel::Info), module.as_deref(), limit))
 }
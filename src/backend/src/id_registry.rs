@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, ORGANIZATIONS, PRODUCTS, RESELLERS};
+use crate::models::{IdAllocation, IdEntityKind};
+use crate::serial_store;
+use crate::utils::generate_unique_principal;
+
+// A collision needs a 29-byte hash clash; more than one retry means something is badly wrong
+const MAX_ALLOCATION_ATTEMPTS: u32 = 8;
+
+// Define a unique MemoryId for this structure
+const ID_REGISTRY_MEM_ID: MemoryId = MemoryId::new(44);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Every organization, product, serial number and reseller ID, across entity types
+    static ID_REGISTRY: RefCell<StableBTreeMap<Principal, IdAllocation, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ID_REGISTRY_MEM_ID))
+        )
+    );
+}
+
+pub fn get_allocation(id: Principal) -> Option<IdAllocation> {
+    ID_REGISTRY.with(|registry| registry.borrow().get(&id))
+}
+
+fn register(id: Principal, entity: IdEntityKind) {
+    ID_REGISTRY.with(|registry| {
+        registry.borrow_mut().insert(id, IdAllocation { entity, created_at: clock::now() });
+    });
+}
+
+// Generate an ID for a new entity, retrying while it clashes with the registry or with `is_taken`
+// (the entity's own map, which also covers records created before the registry existed).
+pub fn allocate_id<F>(entity: IdEntityKind, seed: Principal, is_taken: F) -> Result<Principal, ApiError>
+where
+    F: Fn(&Principal) -> bool,
+{
+    for attempt in 1..=MAX_ALLOCATION_ATTEMPTS {
+        let candidate = generate_unique_principal(seed);
+        if let Some(existing) = get_allocation(candidate) {
+            log_warn!("[allocate_id] {:?} ID {} collides with a registered {:?} (attempt {})", entity, candidate, existing.entity, attempt);
+            continue;
+        }
+        if is_taken(&candidate) {
+            log_warn!("[allocate_id] {:?} ID {} is already in use (attempt {})", entity, candidate, attempt);
+            continue;
+        }
+        register(candidate, entity);
+        return Ok(candidate);
+    }
+    log_error!("[allocate_id] Gave up allocating a {:?} ID after {} attempts", entity, MAX_ALLOCATION_ATTEMPTS);
+    Err(ApiError::internal_error("Could not allocate a unique ID, please try again"))
+}
+
+// Forget an allocated ID whose entity was never stored
+pub fn release_id(id: Principal) {
+    ID_REGISTRY.with(|registry| {
+        registry.borrow_mut().remove(&id);
+    });
+}
+
+pub fn allocate_organization_id() -> Result<Principal, ApiError> {
+    allocate_id(IdEntityKind::Organization, Principal::anonymous(), |id| {
+        ORGANIZATIONS.with(|orgs| orgs.borrow().contains_key(id))
+    })
+}
+
+pub fn allocate_product_id() -> Result<Principal, ApiError> {
+    allocate_id(IdEntityKind::Product, Principal::anonymous(), |id| {
+        PRODUCTS.with(|products| products.borrow().contains_key(id))
+    })
+}
+
+pub fn allocate_reseller_id() -> Result<Principal, ApiError> {
+    allocate_id(IdEntityKind::Reseller, Principal::anonymous(), |id| {
+        RESELLERS.with(|resellers| resellers.borrow().contains_key(id))
+    })
+}
+
+// Serials live in per-product vectors, so the registry is the only global index to check
+pub fn allocate_serial_no(product_id: Principal) -> Result<Principal, ApiError> {
+    allocate_id(IdEntityKind::SerialNumber, product_id, |_| false)
+}
+
+// Register IDs created before the registry existed. Serial numbers are nested per product and
+// rely on the registry alone for collision checks, so they must all be present.
+pub fn backfill_registry() -> u32 {
+    let mut existing: Vec<(Principal, IdEntityKind)> = Vec::new();
+    ORGANIZATIONS.with(|orgs| existing.extend(orgs.borrow().iter().map(|(id, _)| (id, IdEntityKind::Organization))));
+    PRODUCTS.with(|products| existing.extend(products.borrow().iter().map(|(id, _)| (id, IdEntityKind::Product))));
+    RESELLERS.with(|resellers| existing.extend(resellers.borrow().iter().map(|(id, _)| (id, IdEntityKind::Reseller))));
+    serial_store::for_each(|sn| existing.push((sn.serial_no, IdEntityKind::SerialNumber)));
+
+    let mut registered = 0;
+    for (id, entity) in existing {
+        if get_allocation(id).is_none() {
+            register(id, entity);
+            registered += 1;
+        }
+    }
+    registered
+}
+
+// Reset the ID registry (use with caution)
+pub fn reset_id_registry() {
+    ID_REGISTRY.with(|registry| {
+        let mut registry_mut = registry.borrow_mut();
+        let keys: Vec<_> = registry_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            registry_mut.remove(&key);
+        }
+    });
+    log_info!("The ID registry has been reset.");
+}
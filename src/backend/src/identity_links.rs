@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use k256::sha2::{Digest, Sha256};
+use rand::prelude::StdRng;
+
+use crate::api::IdentityLinkResponse;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, USERS};
+use crate::models::{IdentityLinkCode, User, UserRole};
+use crate::owned_products;
+use crate::rewards;
+use crate::verification_store;
+
+// How long a link code can be redeemed
+pub const LINK_CODE_TTL_NS: u64 = 10 * 60 * 1_000_000_000;
+const LINK_CODE_LENGTH: usize = 10;
+// No 0/O or 1/I, as codes are typed by hand on the other device
+const LINK_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+// Define a unique MemoryId for this structure
+const IDENTITY_LINK_CODES_MEM_ID: MemoryId = MemoryId::new(76);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by the primary user; a new code replaces the previous one
+    static IDENTITY_LINK_CODES: RefCell<StableBTreeMap<Principal, IdentityLinkCode, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(IDENTITY_LINK_CODES_MEM_ID))
+        )
+    );
+}
+
+fn hash_code(code: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(code.trim().to_uppercase().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// Issue a fresh code for the user, returning it in the clear together with its record
+pub fn issue_code(user_id: Principal, now: u64) -> (String, IdentityLinkCode) {
+    let mut bytes = [0u8; LINK_CODE_LENGTH];
+    StdRng::from_entropy().fill_bytes(&mut bytes);
+    let code: String = bytes
+        .iter()
+        .map(|b| LINK_CODE_ALPHABET[*b as usize % LINK_CODE_ALPHABET.len()] as char)
+        .collect();
+
+    let record = IdentityLinkCode {
+        user_id,
+        code_hash: hash_code(&code),
+        created_at: now,
+        expires_at: now.saturating_add(LINK_CODE_TTL_NS),
+    };
+    IDENTITY_LINK_CODES.with(|codes| codes.borrow_mut().insert(user_id, record.clone()));
+    (code, record)
+}
+
+// Consume a code, returning the record it belonged to if it has not expired
+pub fn take_code(code: &str, now: u64) -> Option<IdentityLinkCode> {
+    let code_hash = hash_code(code);
+    let record = IDENTITY_LINK_CODES.with(|codes| {
+        codes
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .find(|c| c.code_hash == code_hash)
+    })?;
+    IDENTITY_LINK_CODES.with(|codes| codes.borrow_mut().remove(&record.user_id));
+    Some(record).filter(|c| c.expires_at > now)
+}
+
+// Role of the merged account. A customer (or role-less) identity folds into any account; otherwise
+// the roles must agree. Admin accounts and reseller identities are never merged away, since
+// other records are tied to them.
+pub fn merged_role(primary: &Option<UserRole>, secondary: &Option<UserRole>) -> Result<Option<UserRole>, ApiError> {
+    if matches!(primary, Some(UserRole::Admin)) || matches!(secondary, Some(UserRole::Admin)) {
+        return Err(ApiError::invalid_input("Admin accounts cannot be linked"));
+    }
+    if matches!(secondary, Some(UserRole::Reseller)) {
+        return Err(ApiError::invalid_input(
+            "A reseller account cannot be merged into another account; start the link from the reseller account instead",
+        ));
+    }
+    match (primary, secondary) {
+        (_, None) | (_, Some(UserRole::Customer)) => Ok(*primary),
+        (None, _) | (Some(UserRole::Customer), _) => Ok(*secondary),
+        (Some(p), Some(s)) if p == s => Ok(*primary),
+        _ => Err(ApiError::invalid_input("The two accounts have conflicting roles and cannot be merged")),
+    }
+}
+
+// Fold `secondary` into `primary`: the primary's profile wins, the secondary fills its gaps, and
+// organizations, rewards, verification history and the product shelf move over. The secondary's user record is
+// removed and its principals become session keys of the primary, so they sign in to it from now on.
+// Other records keyed by the secondary principal (notifications, wallets, disputes) stay where they are.
+pub fn merge_accounts(primary: User, secondary: User, now: u64) -> Result<IdentityLinkResponse, ApiError> {
+    let role = merged_role(&primary.user_role, &secondary.user_role)?;
+    let mut merged = primary;
+
+    let mut org_ids_added = 0;
+    for org_id in &secondary.org_ids {
+        if !merged.org_ids.contains(org_id) {
+            merged.org_ids.push(*org_id);
+            org_ids_added += 1;
+        }
+    }
+    merged.user_role = role;
+    merged.active_org_id = merged.active_org_id.or(secondary.active_org_id);
+    merged.first_name = merged.first_name.or(secondary.first_name);
+    merged.last_name = merged.last_name.or(secondary.last_name);
+    merged.phone_no = merged.phone_no.or(secondary.phone_no);
+    merged.email = merged.email.or(secondary.email);
+    for entry in secondary.detail_meta {
+        if !merged.detail_meta.iter().any(|m| m.key == entry.key) {
+            merged.detail_meta.push(entry);
+        }
+    }
+    for principal in std::iter::once(secondary.id).chain(secondary.session_keys) {
+        if principal != merged.id && !merged.session_keys.contains(&principal) {
+            merged.session_keys.push(principal);
+        }
+    }
+    merged.updated_at = now;
+    merged.updated_by = secondary.id;
+
+    let points_transferred = rewards::merge_user_rewards(secondary.id, merged.id);
+    for product_id in rewards::take_verified_products(secondary.id) {
+        rewards::record_product_verification(merged.id, product_id);
+    }
+    let verifications_transferred = verification_store::reassign_creator(secondary.id, merged.id);
+    owned_products::reassign_owner(secondary.id, merged.id);
+
+    USERS.with(|users| {
+        let mut users_mut = users.borrow_mut();
+        users_mut.remove(&secondary.id);
+        users_mut.insert(merged.id, merged.clone());
+    });
+
+    Ok(IdentityLinkResponse {
+        user_id: merged.id,
+        linked_principal: secondary.id,
+        merged_account: true,
+        org_ids_added,
+        points_transferred,
+        verifications_transferred,
+    })
+}
+
+// Reset ALL outstanding identity link codes (use with caution)
+pub fn reset_identity_link_codes() {
+    IDENTITY_LINK_CODES.with(|codes| {
+        let mut codes_mut = codes.borrow_mut();
+        let keys: Vec<_> = codes_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            codes_mut.remove(&key);
+        }
+    });
+    log_info!("All identity link codes have been reset.");
+}
@@ -0,0 +1,241 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+use crate::api::{IntegrityFailure, IntegrityScanReport};
+use crate::clock;
+use crate::error::ApiError;
+use crate::serial_store::{self, SerialKey};
+use crate::verification_store::{self, VerificationKey};
+// Import the shared memory manager
+use crate::global_state::{
+    StorableBytes, MEMORY_MANAGER, ORGANIZATIONS, ORGANIZATION_MEM_ID, PRODUCTS, PRODUCT_MEM_ID,
+    PRODUCT_SERIAL_NUMBERS, PRODUCT_SERIAL_NUMBER_MEM_ID, PRODUCT_VERIFICATIONS, PRODUCT_VERIFICATION_MEM_ID,
+    RESELLERS, RESELLER_MEM_ID, USERS, USER_MEM_ID,
+};
+use crate::models::{
+    IntegritySubKey, IntegrityStore, Organization, Product, ProductSerialNumber, ProductVerification, QuarantinedEntry, Reseller, User,
+};
+
+// Define a unique MemoryId for this structure
+const QUARANTINE_MEM_ID: MemoryId = MemoryId::new(34);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// Key for quarantined entries: the store they were removed from and their key there
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct QuarantineKey {
+    pub store: IntegrityStore,
+    pub key: Principal,
+    pub sub_key: Option<IntegritySubKey>,
+}
+
+impl Storable for QuarantineKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+thread_local! {
+    static QUARANTINE: RefCell<StableBTreeMap<QuarantineKey, QuarantinedEntry, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(QUARANTINE_MEM_ID))
+        )
+    );
+}
+
+fn memory_id(store: IntegrityStore) -> MemoryId {
+    match store {
+        IntegrityStore::Organizations => ORGANIZATION_MEM_ID,
+        IntegrityStore::Products => PRODUCT_MEM_ID,
+        IntegrityStore::Users => USER_MEM_ID,
+        IntegrityStore::Resellers => RESELLER_MEM_ID,
+        IntegrityStore::ProductSerialNumbers => PRODUCT_SERIAL_NUMBER_MEM_ID,
+        IntegrityStore::ProductVerifications => PRODUCT_VERIFICATION_MEM_ID,
+        IntegrityStore::VerificationRecords => verification_store::VERIFICATION_RECORDS_MEM_ID,
+        IntegrityStore::SerialRecords => serial_store::SERIAL_RECORDS_MEM_ID,
+    }
+}
+
+// Key of an entry in its store's own key type
+enum StoreKey {
+    Principal(Principal),
+    Verification(VerificationKey),
+    Serial(SerialKey),
+}
+
+fn store_key(store: IntegrityStore, key: Principal, sub_key: &Option<IntegritySubKey>) -> Result<StoreKey, ApiError> {
+    match (store, sub_key) {
+        (IntegrityStore::VerificationRecords, Some(IntegritySubKey::Seq(seq))) => Ok(StoreKey::Verification((key, *seq))),
+        (IntegrityStore::SerialRecords, Some(IntegritySubKey::SerialNo(serial_no))) => Ok(StoreKey::Serial((key, *serial_no))),
+        (IntegrityStore::VerificationRecords | IntegrityStore::SerialRecords, _) => {
+            Err(ApiError::invalid_input("Sub-key does not match the store's key type"))
+        }
+        (_, None) => Ok(StoreKey::Principal(key)),
+        (_, Some(_)) => Err(ApiError::invalid_input("This store is keyed by a principal alone")),
+    }
+}
+
+// The same stable memory opened with undecoded values, so reading it can never trap on a bad record
+fn raw_view<K: Storable + Ord + Clone>(store: IntegrityStore) -> StableBTreeMap<K, StorableBytes, Memory> {
+    StableBTreeMap::init(MEMORY_MANAGER.with(|m| m.borrow().get(memory_id(store))))
+}
+
+// Visit every entry of the store with its raw value, composite keys split into key and sub-key
+fn for_each_raw<F>(store: IntegrityStore, mut visit: F)
+where
+    F: FnMut(Principal, Option<IntegritySubKey>, StorableBytes),
+{
+    match store {
+        IntegrityStore::VerificationRecords => {
+            for ((product_id, seq), bytes) in raw_view::<VerificationKey>(store).iter() {
+                visit(product_id, Some(IntegritySubKey::Seq(seq)), bytes);
+            }
+        }
+        IntegrityStore::SerialRecords => {
+            for ((product_id, serial_no), bytes) in raw_view::<SerialKey>(store).iter() {
+                visit(product_id, Some(IntegritySubKey::SerialNo(serial_no)), bytes);
+            }
+        }
+        _ => {
+            for (key, bytes) in raw_view::<Principal>(store).iter() {
+                visit(key, None, bytes);
+            }
+        }
+    }
+}
+
+fn try_decode(store: IntegrityStore, bytes: &[u8]) -> Result<(), String> {
+    let result = match store {
+        IntegrityStore::Organizations => decode_one::<Organization>(bytes).map(|_| ()),
+        IntegrityStore::Products => decode_one::<Product>(bytes).map(|_| ()),
+        IntegrityStore::Users => decode_one::<User>(bytes).map(|_| ()),
+        IntegrityStore::Resellers => decode_one::<Reseller>(bytes).map(|_| ()),
+        IntegrityStore::ProductSerialNumbers => decode_one::<Vec<ProductSerialNumber>>(bytes).map(|_| ()),
+        IntegrityStore::ProductVerifications => decode_one::<Vec<ProductVerification>>(bytes).map(|_| ()),
+        IntegrityStore::VerificationRecords => decode_one::<ProductVerification>(bytes).map(|_| ()),
+        IntegrityStore::SerialRecords => decode_one::<ProductSerialNumber>(bytes).map(|_| ()),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+// The typed maps cache their root and length, so they are re-opened after the raw view has written
+fn reload_store(store: IntegrityStore) {
+    let memory = MEMORY_MANAGER.with(|m| m.borrow().get(memory_id(store)));
+    match store {
+        IntegrityStore::Organizations => ORGANIZATIONS.with(|map| *map.borrow_mut() = StableBTreeMap::init(memory)),
+        IntegrityStore::Products => PRODUCTS.with(|map| *map.borrow_mut() = StableBTreeMap::init(memory)),
+        IntegrityStore::Users => USERS.with(|map| *map.borrow_mut() = StableBTreeMap::init(memory)),
+        IntegrityStore::Resellers => RESELLERS.with(|map| *map.borrow_mut() = StableBTreeMap::init(memory)),
+        IntegrityStore::ProductSerialNumbers => {
+            PRODUCT_SERIAL_NUMBERS.with(|map| *map.borrow_mut() = StableBTreeMap::init(memory))
+        }
+        IntegrityStore::ProductVerifications => {
+            PRODUCT_VERIFICATIONS.with(|map| *map.borrow_mut() = StableBTreeMap::init(memory))
+        }
+        IntegrityStore::VerificationRecords => verification_store::reload_records(),
+        IntegrityStore::SerialRecords => serial_store::reload_records(),
+    }
+}
+
+// Attempt to decode every value in the store, collecting failures instead of trapping
+pub fn scan_store(store: IntegrityStore) -> IntegrityScanReport {
+    let mut scanned = 0u64;
+    let mut failures = Vec::new();
+    for_each_raw(store, |key, sub_key, bytes| {
+        scanned += 1;
+        if let Err(error) = try_decode(store, &bytes.0) {
+            failures.push(IntegrityFailure {
+                key,
+                sub_key,
+                size_bytes: bytes.0.len() as u64,
+                error,
+            });
+        }
+    });
+    IntegrityScanReport { store, scanned, failures }
+}
+
+// Every key in a store keyed by principal; keys are stored apart from values and never need decoding
+pub fn store_keys(store: IntegrityStore) -> HashSet<Principal> {
+    raw_view::<Principal>(store).iter().map(|(key, _)| key).collect()
+}
+
+// Visit every value of a store keyed by principal that decodes as T, skipping the entries scan_store would report
+pub fn for_each_decoded<T, F>(store: IntegrityStore, mut visit: F)
+where
+    T: CandidType + for<'de> Deserialize<'de>,
+    F: FnMut(Principal, T),
+{
+    for (key, bytes) in raw_view::<Principal>(store).iter() {
+        if let Ok(value) = decode_one::<T>(&bytes.0) {
+            visit(key, value);
+        }
+    }
+}
+
+// Move an undecodable entry out of its store into the quarantine map. Entries that decode cleanly are refused.
+pub fn quarantine_entry(
+    store: IntegrityStore,
+    key: Principal,
+    sub_key: Option<IntegritySubKey>,
+    caller: Principal,
+) -> Result<QuarantinedEntry, ApiError> {
+    let store_key = store_key(store, key, &sub_key)?;
+    let bytes = match &store_key {
+        StoreKey::Principal(k) => raw_view::<Principal>(store).get(k),
+        StoreKey::Verification(k) => raw_view::<VerificationKey>(store).get(k),
+        StoreKey::Serial(k) => raw_view::<SerialKey>(store).get(k),
+    }
+    .ok_or_else(|| ApiError::not_found("Entry not found in store"))?;
+    let decode_error = match try_decode(store, &bytes.0) {
+        Ok(()) => return Err(ApiError::invalid_input("Entry decodes successfully and does not need quarantine")),
+        Err(e) => e,
+    };
+
+    match &store_key {
+        StoreKey::Principal(k) => raw_view::<Principal>(store).remove(k),
+        StoreKey::Verification(k) => raw_view::<VerificationKey>(store).remove(k),
+        StoreKey::Serial(k) => raw_view::<SerialKey>(store).remove(k),
+    };
+    reload_store(store);
+
+    let entry = QuarantinedEntry {
+        store,
+        key,
+        sub_key: sub_key.clone(),
+        bytes: bytes.0,
+        decode_error,
+        quarantined_at: clock::now(),
+        quarantined_by: caller,
+    };
+    QUARANTINE.with(|quarantine| quarantine.borrow_mut().insert(QuarantineKey { store, key, sub_key: sub_key.clone() }, entry.clone()));
+    log_warn!("[quarantine_entry] Entry {} ({:?}) moved out of {:?} by {}", key, sub_key, store, caller);
+    Ok(entry)
+}
+
+pub fn list_quarantined() -> Vec<QuarantinedEntry> {
+    QUARANTINE.with(|quarantine| quarantine.borrow().iter().map(|(_, entry)| entry).collect())
+}
+
+// Reset ALL quarantined entries (use with caution)
+pub fn reset_quarantine() {
+    QUARANTINE.with(|quarantine| {
+        let mut quarantine_mut = quarantine.borrow_mut();
+        let keys: Vec<_> = quarantine_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            quarantine_mut.remove(&key);
+        }
+    });
+    log_info!("All quarantined entries have been reset.");
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use candid::Principal;
+
+use crate::api::{InvariantKind, InvariantReport, InvariantViolation};
+use crate::clock;
+use crate::integrity;
+use crate::serial_store;
+use crate::verification_store;
+use crate::models::{IntegrityStore, Product, Reseller, User};
+
+// Violations beyond this are counted but not listed
+const MAX_REPORTED_VIOLATIONS: usize = 500;
+
+struct ViolationCollector {
+    violation_count: u64,
+    violations: Vec<InvariantViolation>,
+}
+
+impl ViolationCollector {
+    fn record(&mut self, kind: InvariantKind, entity_id: Principal, referenced_id: Principal, detail: String) {
+        self.violation_count += 1;
+        if self.violations.len() < MAX_REPORTED_VIOLATIONS {
+            self.violations.push(InvariantViolation { kind, entity_id, referenced_id, detail });
+        }
+    }
+}
+
+// Check referential integrity across the core stores. Reads go through the integrity tooling's raw
+// views, so an undecodable record is skipped (scan_store_integrity reports those) rather than trapping.
+pub fn run_checks() -> InvariantReport {
+    let org_ids = integrity::store_keys(IntegrityStore::Organizations);
+    let product_ids = integrity::store_keys(IntegrityStore::Products);
+    let user_ids = integrity::store_keys(IntegrityStore::Users);
+    let mut collector = ViolationCollector { violation_count: 0, violations: Vec::new() };
+
+    integrity::for_each_decoded(IntegrityStore::Products, |product_id, product: Product| {
+        if !org_ids.contains(&product.org_id) {
+            collector.record(
+                InvariantKind::ProductOrganizationMissing,
+                product_id,
+                product.org_id,
+                format!("Product '{}' belongs to a missing organization", product.name),
+            );
+        }
+    });
+
+    for product_id in serial_store::product_ids() {
+        let serials = serial_store::list_for_product(product_id);
+        if !product_ids.contains(&product_id) {
+            collector.record(
+                InvariantKind::SerialsProductMissing,
+                product_id,
+                product_id,
+                format!("{} serial number(s) stored for a missing product", serials.len()),
+            );
+        }
+        for serial in serials.iter().filter(|sn| sn.product_id != product_id) {
+            collector.record(
+                InvariantKind::SerialProductMismatch,
+                serial.serial_no,
+                serial.product_id,
+                format!("Serial is stored under product {}", product_id),
+            );
+        }
+    }
+
+    for product_id in verification_store::product_ids() {
+        if !product_ids.contains(&product_id) {
+            collector.record(
+                InvariantKind::VerificationsProductMissing,
+                product_id,
+                product_id,
+                "Verification history stored for a missing product".to_string(),
+            );
+        }
+    }
+
+    integrity::for_each_decoded(IntegrityStore::Resellers, |reseller_id, reseller: Reseller| {
+        if !org_ids.contains(&reseller.org_id) {
+            collector.record(
+                InvariantKind::ResellerOrganizationMissing,
+                reseller_id,
+                reseller.org_id,
+                format!("Reseller '{}' belongs to a missing organization", reseller.name),
+            );
+        }
+        if !user_ids.contains(&reseller.user_id) {
+            collector.record(
+                InvariantKind::ResellerUserMissing,
+                reseller_id,
+                reseller.user_id,
+                format!("Reseller '{}' has no user account", reseller.name),
+            );
+        }
+    });
+
+    // Callers are resolved to users by session key, so each key must lead to exactly one user
+    let mut session_owners: HashMap<Principal, Principal> = HashMap::new();
+    integrity::for_each_decoded(IntegrityStore::Users, |user_id, user: User| {
+        for org_id in user.org_ids.iter().filter(|org_id| !org_ids.contains(*org_id)) {
+            collector.record(
+                InvariantKind::UserOrganizationMissing,
+                user_id,
+                *org_id,
+                "User is a member of a missing organization".to_string(),
+            );
+        }
+        for session_key in &user.session_keys {
+            if *session_key != user_id && user_ids.contains(session_key) {
+                collector.record(
+                    InvariantKind::SessionKeyConflict,
+                    user_id,
+                    *session_key,
+                    "Session key is another user's ID".to_string(),
+                );
+            }
+            if let Some(owner) = session_owners.insert(*session_key, user_id) {
+                if owner != user_id {
+                    collector.record(
+                        InvariantKind::SessionKeyConflict,
+                        user_id,
+                        *session_key,
+                        format!("Session key is also held by user {}", owner),
+                    );
+                }
+            }
+        }
+    });
+
+    InvariantReport {
+        checked_at: clock::now(),
+        violation_count: collector.violation_count,
+        violations: collector.violations,
+    }
+}
+
+// Run from post_upgrade: violations are logged for operators, never fatal to the upgrade
+pub fn check_after_upgrade() {
+    let report = run_checks();
+    if report.violation_count == 0 {
+        log_info!("[check_after_upgrade] All data invariants hold.");
+        return;
+    }
+    log_warn!("[check_after_upgrade] {} data invariant violation(s) found.", report.violation_count);
+    for violation in &report.violations {
+        log_warn!(
+            "[check_after_upgrade] {:?}: {} -> {} ({})",
+            violation.kind,
+            violation.entity_id,
+            violation.referenced_id,
+            violation.detail
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk_timers::set_timer;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::icp::store_new_serial_number;
+use crate::models::{Job, JobCursor, JobResultChunk, JobSpec, JobStatus, ProductVerification};
+use crate::quotas;
+use crate::utils::generate_unique_principal;
+use crate::verification_store;
+
+pub const MAX_ACTIVE_JOBS_PER_ORG: usize = 3;
+pub const MAX_SERIALS_PER_IMPORT: u32 = 100_000;
+// Work done by one step, sized to stay well within a single message's instruction limit
+const SERIALS_PER_STEP: u32 = 500;
+const VERIFICATIONS_PER_STEP: usize = 5_000; // Records read, whether or not they fall in the period
+// Finished jobs and their results are dropped after this long
+const FINISHED_JOB_RETENTION_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+const SERIAL_IMPORT_COLUMNS: [&str; 2] = ["serial_no", "user_serial_no"];
+const VERIFICATION_EXPORT_COLUMNS: [&str; 6] =
+    ["verification_id", "product_id", "serial_no", "status", "created_at", "country_code"];
+
+// Define unique Memory IDs for the structures in this module
+const JOBS_MEM_ID: MemoryId = MemoryId::new(77);
+const JOB_RESULTS_MEM_ID: MemoryId = MemoryId::new(78);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static JOBS: RefCell<StableBTreeMap<Principal, Job, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(JOBS_MEM_ID))
+        )
+    );
+
+    // Keyed by (job, chunk index); every step that produces rows appends one chunk
+    static JOB_RESULTS: RefCell<StableBTreeMap<(Principal, u32), JobResultChunk, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(JOB_RESULTS_MEM_ID))
+        )
+    );
+
+    static STEP_SCHEDULED: RefCell<bool> = const { RefCell::new(false) };
+}
+
+pub fn is_active(job: &Job) -> bool {
+    matches!(job.status, JobStatus::Queued | JobStatus::Running)
+}
+
+fn save_job(job: Job) {
+    JOBS.with(|jobs| {
+        jobs.borrow_mut().insert(job.id, job);
+    });
+}
+
+pub fn get_job(job_id: Principal) -> Option<Job> {
+    JOBS.with(|jobs| jobs.borrow().get(&job_id))
+}
+
+// Jobs of an organization, newest first
+pub fn list_jobs_for_org(org_id: Principal) -> Vec<Job> {
+    let mut jobs: Vec<Job> = JOBS.with(|jobs| {
+        jobs.borrow()
+            .iter()
+            .map(|(_, job)| job)
+            .filter(|job| job.org_id == org_id)
+            .collect()
+    });
+    jobs.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    jobs
+}
+
+pub fn active_count_for_org(org_id: Principal) -> usize {
+    JOBS.with(|jobs| {
+        jobs.borrow()
+            .iter()
+            .filter(|(_, job)| job.org_id == org_id && is_active(job))
+            .count()
+    })
+}
+
+// Queue a job; its first step runs right after the current message
+pub fn submit(org_id: Principal, spec: JobSpec, created_by: Principal) -> Job {
+    let now = clock::now();
+    prune_finished(now);
+    let (total_units, columns): (u64, &[&str]) = match &spec {
+        JobSpec::SerialImport { count, .. } => (*count as u64, &SERIAL_IMPORT_COLUMNS),
+        JobSpec::VerificationExport { product_ids, .. } => (product_ids.len() as u64, &VERIFICATION_EXPORT_COLUMNS),
+    };
+    let job = Job {
+        id: generate_unique_principal(org_id),
+        org_id,
+        spec,
+        status: JobStatus::Queued,
+        total_units,
+        processed_units: 0,
+        cursor: JobCursor::default(),
+        result_columns: columns.iter().map(|c| c.to_string()).collect(),
+        result_chunks: 0,
+        result_rows: 0,
+        error: None,
+        created_at: now,
+        created_by,
+        started_at: None,
+        finished_at: None,
+        updated_at: now,
+    };
+    save_job(job.clone());
+    schedule_step();
+    job
+}
+
+// Stop a queued or running job. Work already done stays done, and its results stay readable.
+pub fn cancel(job_id: Principal, now: u64) -> Option<Job> {
+    let mut job = get_job(job_id).filter(is_active)?;
+    job.status = JobStatus::Cancelled;
+    job.finished_at = Some(now);
+    job.updated_at = now;
+    save_job(job.clone());
+    Some(job)
+}
+
+pub fn get_result_chunk(job_id: Principal, chunk_index: u32) -> Option<JobResultChunk> {
+    JOB_RESULTS.with(|results| results.borrow().get(&(job_id, chunk_index)))
+}
+
+// Pick up jobs an upgrade interrupted. Timers do not survive upgrades, so this runs from post_upgrade.
+pub fn resume_jobs() {
+    if JOBS.with(|jobs| jobs.borrow().iter().any(|(_, job)| is_active(&job))) {
+        schedule_step();
+    }
+}
+
+fn schedule_step() {
+    let already_scheduled = STEP_SCHEDULED.with(|scheduled| scheduled.replace(true));
+    if already_scheduled {
+        return;
+    }
+    set_timer(Duration::ZERO, run_next_step);
+}
+
+// Advance the job that has gone longest without progress by one step, so jobs take turns, and
+// schedule the next step while any job is left
+fn run_next_step() {
+    STEP_SCHEDULED.with(|scheduled| *scheduled.borrow_mut() = false);
+    let now = clock::now();
+    prune_finished(now);
+
+    let next = JOBS.with(|jobs| {
+        jobs.borrow()
+            .iter()
+            .map(|(_, job)| job)
+            .filter(is_active)
+            .min_by_key(|job| job.updated_at)
+    });
+    let mut job = match next {
+        Some(job) => job,
+        None => return,
+    };
+    if job.status == JobStatus::Queued {
+        job.status = JobStatus::Running;
+        job.started_at = Some(now);
+    }
+
+    let mut rows = Vec::new();
+    let outcome = match job.spec.clone() {
+        JobSpec::SerialImport { product_id, variant_id, user_serial_prefix, .. } => {
+            import_step(&mut job, product_id, variant_id, user_serial_prefix.as_deref(), &mut rows)
+        }
+        JobSpec::VerificationExport { product_ids, period_start, period_end } => {
+            export_step(&mut job, &product_ids, period_start, period_end, &mut rows);
+            Ok(())
+        }
+    };
+
+    if !rows.is_empty() {
+        job.result_rows += rows.len() as u64;
+        JOB_RESULTS.with(|results| results.borrow_mut().insert((job.id, job.result_chunks), JobResultChunk { rows }));
+        job.result_chunks += 1;
+    }
+    match outcome {
+        Err(e) => {
+            log_warn!("[run_next_step] Job {} of org {} failed after {} of {} units: {:?}", job.id, job.org_id, job.processed_units, job.total_units, e);
+            job.status = JobStatus::Failed;
+            job.error = Some(format!("{:?}", e));
+            job.finished_at = Some(now);
+        }
+        Ok(()) if job.processed_units >= job.total_units => {
+            log_info!("[run_next_step] Job {} of org {} completed with {} result row(s)", job.id, job.org_id, job.result_rows);
+            job.status = JobStatus::Completed;
+            job.finished_at = Some(now);
+        }
+        Ok(()) => {}
+    }
+    job.updated_at = now;
+    save_job(job);
+
+    resume_jobs();
+}
+
+fn import_step(
+    job: &mut Job,
+    product_id: Principal,
+    variant_id: Option<Principal>,
+    user_serial_prefix: Option<&str>,
+    rows: &mut Vec<String>,
+) -> Result<(), ApiError> {
+    let product = PRODUCTS
+        .with(|products| products.borrow().get(&product_id))
+        .ok_or_else(|| ApiError::not_found("The product was deleted"))?;
+    let batch = job.total_units.saturating_sub(job.processed_units).min(SERIALS_PER_STEP as u64) as u32;
+    quotas::ensure_storage_available(job.org_id)?;
+    quotas::consume_serials(job.org_id, batch)?;
+
+    for stored in 0..batch {
+        let serial = match store_new_serial_number(&product, variant_id, user_serial_prefix, job.created_by) {
+            Ok(serial) => serial,
+            Err(e) => {
+                // Only the serials actually stored count against the quota
+                quotas::release_serials(job.org_id, batch - stored);
+                return Err(e);
+            }
+        };
+        rows.push(format!("{},{}", serial.serial_no, serial.user_serial_no.unwrap_or_default()));
+        job.processed_units += 1;
+        job.cursor.position = job.processed_units;
+    }
+    Ok(())
+}
+
+fn export_row(verification: &ProductVerification) -> String {
+    // Reported by the client, so anything but letters and digits is dropped to keep the row intact
+    let country_code: String = verification
+        .country_code
+        .as_deref()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    format!(
+        "{},{},{},{:?},{},{}",
+        verification.id, verification.product_id, verification.serial_no, verification.status, verification.created_at, country_code
+    )
+}
+
+fn export_step(job: &mut Job, product_ids: &[Principal], period_start: u64, period_end: u64, rows: &mut Vec<String>) {
+    let mut budget = VERIFICATIONS_PER_STEP;
+    while budget > 0 {
+        let product_id = match product_ids.get(job.cursor.position as usize) {
+            Some(id) => *id,
+            None => break,
+        };
+        let batch = verification_store::list_for_product_from(product_id, job.cursor.seq, budget);
+        let product_done = batch.len() < budget;
+        budget -= batch.len();
+        for (seq, verification) in batch {
+            job.cursor.seq = seq + 1;
+            if verification.created_at >= period_start && verification.created_at < period_end {
+                rows.push(export_row(&verification));
+            }
+        }
+        if product_done {
+            job.cursor.position += 1;
+            job.cursor.seq = 0;
+            job.processed_units += 1;
+        }
+    }
+}
+
+fn remove_results(job_id: Principal) {
+    JOB_RESULTS.with(|results| {
+        let mut results_mut = results.borrow_mut();
+        let keys: Vec<_> = results_mut.range((job_id, 0)..=(job_id, u32::MAX)).map(|(k, _)| k).collect();
+        for key in keys {
+            results_mut.remove(&key);
+        }
+    });
+}
+
+fn prune_finished(now: u64) {
+    let stale: Vec<Principal> = JOBS.with(|jobs| {
+        jobs.borrow()
+            .iter()
+            .filter(|(_, job)| job.finished_at.is_some_and(|at| now >= at.saturating_add(FINISHED_JOB_RETENTION_NS)))
+            .map(|(id, _)| id)
+            .collect()
+    });
+    for job_id in stale {
+        remove_results(job_id);
+        JOBS.with(|jobs| jobs.borrow_mut().remove(&job_id));
+    }
+}
+
+// Remove the organization's jobs and their results when it is deleted, returning how many jobs were removed.
+// Steps run one at a time, so a running job just gets no further step.
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    let job_ids: Vec<Principal> =
+        JOBS.with(|jobs| jobs.borrow().iter().filter(|(_, job)| job.org_id == org_id).map(|(id, _)| id).collect());
+    for job_id in &job_ids {
+        JOBS.with(|jobs| jobs.borrow_mut().remove(job_id));
+        remove_results(*job_id);
+    }
+    job_ids.len() as u32
+}
+
+// Reset ALL jobs and their results (use with caution)
+pub fn reset_jobs() {
+    JOBS.with(|jobs| {
+        let mut jobs_mut = jobs.borrow_mut();
+        let keys: Vec<_> = jobs_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            jobs_mut.remove(&key);
+        }
+    });
+    JOB_RESULTS.with(|results| {
+        let mut results_mut = results.borrow_mut();
+        let keys: Vec<_> = results_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            results_mut.remove(&key);
+        }
+    });
+    log_info!("All background jobs have been reset.");
+}
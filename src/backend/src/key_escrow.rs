@@ -0,0 +1,206 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use candid::Principal;
+use ic_cdk_timers::set_timer;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::ecdh::EphemeralSecret;
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::sha2::{Digest, Sha256};
+use k256::PublicKey;
+use rand::prelude::StdRng;
+use serde::Serialize;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::api::KeyEscrowExport;
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::keys;
+use crate::models::{KeyEscrowRequest, KeyEscrowStatus, Organization, UserNotificationKind};
+use crate::notifications;
+
+// Time between admin approval and the moment the brand can collect the export, so a compromised
+// owner or admin account cannot take the keys in one sitting
+pub const CONFIRMATION_DELAY_NS: u64 = 48 * 60 * 60 * 1_000_000_000;
+// An approved export that is not collected within this window after it matures has to be requested again
+pub const CONFIRMATION_WINDOW_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+// ECDH over secp256k1 with an ephemeral key, SHA-256(shared x || ephemeral public key) as the
+// AES-256-GCM key. The recipient needs the ephemeral public key, nonce and ciphertext to decrypt.
+pub const ENCRYPTION_ALGORITHM: &str = "ECIES-secp256k1-SHA256-AES256GCM";
+
+// Define a unique MemoryId for this structure
+const KEY_ESCROW_REQUESTS_MEM_ID: MemoryId = MemoryId::new(62);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// Plaintext of an export, as JSON
+#[derive(Serialize)]
+struct KeyEscrowPayload {
+    org_id: String,
+    org_name: String,
+    private_key: String, // Hex scalar, as used to sign the organization's codes
+    public_key: String, // Hex uncompressed SEC1 point, for checking the key against printed labels
+    exported_at: u64,
+}
+
+impl Drop for KeyEscrowPayload {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+thread_local! {
+    static KEY_ESCROW_REQUESTS: RefCell<StableBTreeMap<Principal, KeyEscrowRequest, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(KEY_ESCROW_REQUESTS_MEM_ID))
+        )
+    );
+}
+
+pub fn save_request(request: KeyEscrowRequest) {
+    KEY_ESCROW_REQUESTS.with(|requests| {
+        requests.borrow_mut().insert(request.id, request);
+    });
+}
+
+pub fn get_request(request_id: Principal) -> Option<KeyEscrowRequest> {
+    KEY_ESCROW_REQUESTS.with(|requests| requests.borrow().get(&request_id))
+}
+
+// The organization's requests, newest first
+pub fn list_for_org(org_id: Principal) -> Vec<KeyEscrowRequest> {
+    let mut requests: Vec<KeyEscrowRequest> = KEY_ESCROW_REQUESTS.with(|requests| {
+        requests
+            .borrow()
+            .iter()
+            .filter(|(_, request)| request.org_id == org_id)
+            .map(|(_, request)| request)
+            .collect()
+    });
+    requests.sort_by_key(|a| std::cmp::Reverse(a.requested_at));
+    requests
+}
+
+// A request that is waiting for approval, or approved and not yet collected or expired
+pub fn is_open(request: &KeyEscrowRequest, now: u64) -> bool {
+    match request.status {
+        KeyEscrowStatus::PendingApproval => true,
+        KeyEscrowStatus::Approved => request.expires_at.is_none_or(|expires_at| now < expires_at),
+        KeyEscrowStatus::Exported | KeyEscrowStatus::Cancelled => false,
+    }
+}
+
+pub fn open_request_for_org(org_id: Principal, now: u64) -> Option<KeyEscrowRequest> {
+    list_for_org(org_id).into_iter().find(|request| is_open(request, now))
+}
+
+// Hex-encoded SEC1 secp256k1 public key, compressed or not
+pub fn parse_recipient_key(hex_key: &str) -> Result<PublicKey, ApiError> {
+    let bytes = hex::decode(hex_key.trim())
+        .map_err(|_| ApiError::invalid_input("Recipient public key must be hex encoded"))?;
+    PublicKey::from_sec1_bytes(&bytes)
+        .map_err(|_| ApiError::invalid_input("Recipient public key is not a valid secp256k1 public key"))
+}
+
+// The plaintext holds the private key, so it is wiped once the caller has encrypted it
+pub fn build_payload(organization: &Organization, now: u64) -> Result<Zeroizing<Vec<u8>>, ApiError> {
+    let signing_key = keys::org_signing_key(organization)
+        .map_err(|_| ApiError::internal_error("The organization's signing key is malformed"))?;
+    let payload = KeyEscrowPayload {
+        org_id: organization.id.to_string(),
+        org_name: organization.name.clone(),
+        private_key: signing_key.to_hex(),
+        public_key: signing_key.verifying_key().to_hex(),
+        exported_at: now,
+    };
+    serde_json::to_vec(&payload)
+        .map(Zeroizing::new)
+        .map_err(|_| ApiError::internal_error("Failed to prepare the export"))
+}
+
+// Encrypt the export so only the holder of the recipient's private key can read it
+pub fn encrypt_for_recipient(
+    request: &KeyEscrowRequest,
+    recipient: &PublicKey,
+    plaintext: &[u8],
+) -> Result<KeyEscrowExport, ApiError> {
+    let mut rng = StdRng::from_entropy();
+    let ephemeral = EphemeralSecret::random(&mut rng);
+    let ephemeral_public = ephemeral.public_key().to_encoded_point(false);
+    let shared_secret = ephemeral.diffie_hellman(recipient);
+
+    let mut hasher = Sha256::new();
+    hasher.update(shared_secret.raw_secret_bytes());
+    hasher.update(ephemeral_public.as_bytes());
+    let key = hasher.finalize();
+
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| ApiError::internal_error("Failed to prepare the export"))?;
+    let mut nonce = [0u8; 12];
+    rng.fill_bytes(&mut nonce);
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext).map_err(|_| {
+        log_error!("[encrypt_for_recipient] Encryption failed for key escrow request {}", request.id);
+        ApiError::internal_error("Failed to encrypt the export")
+    })?;
+
+    Ok(KeyEscrowExport {
+        request_id: request.id,
+        org_id: request.org_id,
+        algorithm: ENCRYPTION_ALGORITHM.to_string(),
+        ephemeral_public_key: hex::encode(ephemeral_public.as_bytes()),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+// Tell the requester once the delay has passed. Timers do not survive upgrades, so the confirmation
+// step checks the time itself and this is only a courtesy.
+pub fn schedule_ready_notification(request: &KeyEscrowRequest) {
+    let confirmable_at = match request.confirmable_at {
+        Some(at) => at,
+        None => return,
+    };
+    let request_id = request.id;
+    let delay = Duration::from_nanos(confirmable_at.saturating_sub(clock::now()));
+    set_timer(delay, move || {
+        if let Some(request) = get_request(request_id).filter(|r| r.status == KeyEscrowStatus::Approved) {
+            notifications::notify_user(
+                request.requested_by,
+                UserNotificationKind::KeyEscrowReady,
+                "Key export ready",
+                "Your organization's key export can now be collected with export_org_key_escrow.".to_string(),
+                Some(request.id),
+            );
+        }
+    });
+}
+
+// Remove the organization's key escrow requests when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    KEY_ESCROW_REQUESTS.with(|requests| {
+        let mut requests_mut = requests.borrow_mut();
+        let keys: Vec<_> = requests_mut.iter().filter(|(_, request)| request.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            requests_mut.remove(key);
+        }
+        keys.len() as u32
+    })
+}
+
+// Reset ALL key escrow requests (use with caution)
+pub fn reset_key_escrow_requests() {
+    KEY_ESCROW_REQUESTS.with(|requests| {
+        let mut requests_mut = requests.borrow_mut();
+        let keys: Vec<_> = requests_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            requests_mut.remove(&key);
+        }
+    });
+    log_info!("All key escrow requests have been reset.");
+}
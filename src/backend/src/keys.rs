@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use k256::ecdsa::{
+    signature::{Signer, Verifier},
+    Signature, SigningKey, VerifyingKey,
+};
+use k256::elliptic_curve::rand_core::SeedableRng;
+use k256::sha2::{Digest, Sha256};
+use rand::prelude::StdRng;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::error::ApiError;
+use crate::models::Organization;
+
+// Parsed keys kept per kind. Deriving a public key or decompressing a point costs far more than
+// looking one up, and code generation and verification do it on every call.
+const KEY_CACHE_CAPACITY: usize = 256;
+
+// An organization's secp256k1 signing key. Stored on the organization as a hex scalar; use this type
+// everywhere else, so the hex is decoded in one place and the scalar is wiped when the key is dropped.
+#[derive(Clone)]
+pub struct OrgSigningKey(SigningKey);
+
+// SigningKey zeroizes its scalar on drop
+impl ZeroizeOnDrop for OrgSigningKey {}
+
+impl fmt::Debug for OrgSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OrgSigningKey(..)")
+    }
+}
+
+impl OrgSigningKey {
+    pub fn generate() -> Self {
+        let mut rng = StdRng::from_entropy();
+        OrgSigningKey(SigningKey::random(&mut rng))
+    }
+
+    pub fn from_hex(key_hex: &str) -> Result<Self, ApiError> {
+        let bytes = Zeroizing::new(
+            hex::decode(key_hex).map_err(|_| ApiError::internal_error("Malformed secret key for organization"))?,
+        );
+        SigningKey::from_slice(&bytes)
+            .map(OrgSigningKey)
+            .map_err(|_| ApiError::internal_error("Invalid secret key for organization"))
+    }
+
+    // Hex scalar, the form kept on the organization record
+    pub fn to_hex(&self) -> String {
+        let mut bytes = self.0.to_bytes();
+        let key_hex = hex::encode(bytes.as_slice());
+        bytes.as_mut_slice().zeroize();
+        key_hex
+    }
+
+    pub fn verifying_key(&self) -> OrgVerifyingKey {
+        OrgVerifyingKey(*self.0.verifying_key())
+    }
+
+    pub fn sign(&self, data: &[u8]) -> Signature {
+        self.0.sign(data)
+    }
+
+    // Hex signature over the SHA-256 of `msg`, the way unique codes and reseller codes are signed
+    pub fn sign_message(&self, msg: &str) -> String {
+        let signature = self.sign(&Sha256::digest(msg.as_bytes()));
+        hex::encode(signature.to_bytes().as_slice())
+    }
+}
+
+// Public half of an organization's key, also stored on each of its products
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrgVerifyingKey(VerifyingKey);
+
+impl OrgVerifyingKey {
+    // Hex SEC1 encoding, compressed or not
+    pub fn from_hex(key_hex: &str) -> Result<Self, ApiError> {
+        let bytes = hex::decode(key_hex).map_err(|_| ApiError::internal_error("Malformed public key"))?;
+        VerifyingKey::from_sec1_bytes(&bytes)
+            .map(OrgVerifyingKey)
+            .map_err(|_| ApiError::internal_error("Malformed public key"))
+    }
+
+    // Hex, uncompressed SEC1 encoding
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0.to_encoded_point(false).as_bytes())
+    }
+
+    pub fn verify(&self, data: &[u8], signature: &Signature) -> bool {
+        self.0.verify(data, signature).is_ok()
+    }
+
+    // Checks a hex signature made by OrgSigningKey::sign_message. Ok(false) means the signature is
+    // well-formed but was not made over `msg` with this key.
+    pub fn verify_message(&self, msg: &str, signature_hex: &str) -> Result<bool, ApiError> {
+        let signature_bytes = hex::decode(signature_hex).map_err(|_| ApiError::invalid_input("Malformed unique code"))?;
+        let signature =
+            Signature::from_slice(&signature_bytes).map_err(|_| ApiError::invalid_input("Invalid signature format"))?;
+        Ok(self.verify(&Sha256::digest(msg.as_bytes()), &signature))
+    }
+}
+
+// Least recently used entries go first once full. Entries are found by the SHA-256 of the hex they
+// were parsed from, so the cache holds no copy of a secret in its keys and a changed key is a miss.
+struct KeyCache<V> {
+    entries: HashMap<[u8; 32], (V, u64)>,
+    tick: u64,
+}
+
+impl<V: Clone> KeyCache<V> {
+    fn new() -> Self {
+        KeyCache {
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get_or_parse<F>(&mut self, key_hex: &str, parse: F) -> Result<V, ApiError>
+    where
+        F: FnOnce(&str) -> Result<V, ApiError>,
+    {
+        self.tick += 1;
+        let tag: [u8; 32] = Sha256::digest(key_hex.as_bytes()).into();
+        if let Some((value, last_used)) = self.entries.get_mut(&tag) {
+            *last_used = self.tick;
+            return Ok(value.clone());
+        }
+
+        let value = parse(key_hex)?;
+        if self.entries.len() >= KEY_CACHE_CAPACITY {
+            if let Some(oldest) = self.entries.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(tag, _)| *tag) {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(tag, (value.clone(), self.tick));
+        Ok(value)
+    }
+}
+
+thread_local! {
+    // Heap only and rebuilt on demand. A query's additions are dropped with the rest of its state.
+    static SIGNING_KEYS: RefCell<KeyCache<OrgSigningKey>> = RefCell::new(KeyCache::new());
+    static VERIFYING_KEYS: RefCell<KeyCache<OrgVerifyingKey>> = RefCell::new(KeyCache::new());
+}
+
+pub fn org_signing_key(organization: &Organization) -> Result<OrgSigningKey, ApiError> {
+    SIGNING_KEYS.with(|cache| cache.borrow_mut().get_or_parse(&organization.private_key, OrgSigningKey::from_hex))
+}
+
+pub fn org_verifying_key(organization: &Organization) -> Result<OrgVerifyingKey, ApiError> {
+    org_signing_key(organization).map(|key| key.verifying_key())
+}
+
+// A stored public key, e.g. a product's
+pub fn verifying_key(key_hex: &str) -> Result<OrgVerifyingKey, ApiError> {
+    VERIFYING_KEYS.with(|cache| cache.borrow_mut().get_or_parse(key_hex, OrgVerifyingKey::from_hex))
+}
@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::LabelTemplate;
+
+// Bounds on label geometry, in millimetres
+pub const MAX_LABEL_DIMENSION_MM: f64 = 500.0;
+// QR versions defined by ISO/IEC 18004
+pub const MAX_QR_VERSION: u8 = 40;
+
+// Define a unique MemoryId for this structure
+const LABEL_TEMPLATES_MEM_ID: MemoryId = MemoryId::new(53);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by product ID, one template per product
+    static LABEL_TEMPLATES: RefCell<StableBTreeMap<Principal, LabelTemplate, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(LABEL_TEMPLATES_MEM_ID))
+        )
+    );
+}
+
+pub fn save_template(template: LabelTemplate) {
+    LABEL_TEMPLATES.with(|templates| {
+        templates.borrow_mut().insert(template.product_id, template);
+    });
+}
+
+pub fn get_template(product_id: Principal) -> Option<LabelTemplate> {
+    LABEL_TEMPLATES.with(|templates| templates.borrow().get(&product_id))
+}
+
+pub fn remove_template(product_id: Principal) -> Option<LabelTemplate> {
+    LABEL_TEMPLATES.with(|templates| templates.borrow_mut().remove(&product_id))
+}
+
+// Reset ALL label templates (use with caution)
+pub fn reset_label_templates() {
+    LABEL_TEMPLATES.with(|templates| {
+        let mut templates_mut = templates.borrow_mut();
+        let keys: Vec<_> = templates_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            templates_mut.remove(&key);
+        }
+    });
+    log_info!("All label templates have been reset.");
+}
@@ -1,12 +1,83 @@
+#[macro_use]
+pub mod logging;
 pub mod global_state;
 pub mod models;
 pub mod utils;
+pub mod clock;
 pub mod icp;
 pub mod error;
 pub mod auth;
 pub mod api;
 pub mod rate_limiter;
 pub mod rewards;
+pub mod webhooks;
+pub mod org_settings;
+pub mod migrations;
+pub mod reports;
+pub mod print_jobs;
+pub mod config;
+pub mod feature_flags;
+pub mod quotas;
+pub mod redemptions;
+pub mod notifications;
+pub mod disputes;
+pub mod variants;
+pub mod reseller_codes;
+pub mod analytics;
+pub mod directory;
+pub mod validation;
+pub mod guest_sessions;
+pub mod verification_messages;
+pub mod integrity;
+pub mod bi_export;
+pub mod catalog;
+pub mod challenges;
+pub mod wallets;
+pub mod reward_pools;
+pub mod reseller_products;
+pub mod id_registry;
+pub mod invariants;
+pub mod audit_log;
+pub mod verification_store;
+pub mod serial_store;
+pub mod reseller_applications;
+pub mod reseller_email;
+pub mod label_templates;
+pub mod brand_lookup;
+pub mod response_privacy;
+pub mod federation;
+pub mod coupons;
+pub mod client_version;
+pub mod outbox;
+pub mod terms;
+pub mod key_escrow;
+pub mod signing;
+pub mod heatmap;
+pub mod bundles;
+pub mod probe_guard;
+pub mod cycles_monitor;
+pub mod metadata_schemas;
+pub mod readonly_principals;
+pub mod plans;
+pub mod verification_retention;
+pub mod identity_links;
+pub mod authenticity;
+pub mod jobs;
+pub mod outcall_transforms;
+pub mod verification_domains;
+pub mod capability_tokens;
+pub mod owned_products;
+pub mod recall_campaigns;
+pub mod status_messages;
+pub mod endpoint_metrics;
+pub mod print_history;
+pub mod unit_of_work;
+pub mod circuit_breakers;
+pub mod moderation;
+pub mod public_stats;
+pub mod live_events;
+pub mod keys;
+pub mod approvals;
 
 use crate::api::*;
 use crate::error::ApiError;
@@ -17,4 +88,17 @@ use ic_cdk::api::management_canister::http_request::{
     TransformArgs,
 };
 
-ic_cdk::export_candid!();
\ No newline at end of file
+ic_cdk::export_candid!();
+#[cfg(test)]
+mod tests {
+    // dfx serves backend.did and src/declarations is generated from it, so it has to change together
+    // with the endpoints and their types
+    #[test]
+    fn backend_did_matches_the_exported_interface() {
+        assert_eq!(
+            super::__export_service().trim_end(),
+            include_str!("../backend.did").trim_end(),
+            "backend.did is out of date: regenerate it with candid-extractor, then run `dfx generate TrustOrigin_backend`"
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use candid::Principal;
+
+use crate::api::{LiveEvent, LiveTopic, WsOutgoingMessage};
+use crate::clock;
+use crate::config;
+use crate::error::ApiError;
+
+pub const MAX_CONNECTIONS_PER_ORG: usize = 50;
+pub const MAX_CONNECTIONS_PER_CLIENT: usize = 5;
+pub const MAX_MESSAGES_PER_POLL: usize = 100;
+// A connection that has not sent a message for this long is dropped
+pub const IDLE_TIMEOUT_SECONDS: u64 = 5 * 60;
+// Messages kept per gateway for it to poll; past this the oldest are dropped
+const MAX_QUEUED_PER_GATEWAY: usize = 1_000;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+struct Connection {
+    client: Principal,
+    gateway: Principal,
+    org_id: Principal,
+    topics: Vec<LiveTopic>, // Empty follows every topic
+    last_seen_at: u64,
+}
+
+struct QueuedMessage {
+    org_id: Principal, // Checked against the client's access when the gateway polls
+    message: WsOutgoingMessage,
+}
+
+struct GatewayQueue {
+    next_seq: u64,
+    messages: VecDeque<QueuedMessage>,
+}
+
+thread_local! {
+    // Heap only: an upgrade drops every connection, and clients reconnect through their gateway.
+    // IDs and sequence numbers start from the time, so they keep increasing across upgrades.
+    static CONNECTIONS: RefCell<BTreeMap<u64, Connection>> = const { RefCell::new(BTreeMap::new()) };
+    static NEXT_CONNECTION_ID: Cell<u64> = const { Cell::new(0) };
+    static GATEWAY_QUEUES: RefCell<HashMap<Principal, GatewayQueue>> = RefCell::new(HashMap::new());
+}
+
+fn next_connection_id(now: u64) -> u64 {
+    NEXT_CONNECTION_ID.with(|next| {
+        let id = next.get().max(now);
+        next.set(id + 1);
+        id
+    })
+}
+
+fn follows(connection: &Connection, topic: LiveTopic) -> bool {
+    connection.topics.is_empty() || connection.topics.contains(&topic)
+}
+
+fn normalize_topics(topics: Vec<LiveTopic>) -> Vec<LiveTopic> {
+    let mut distinct = Vec::new();
+    for topic in topics {
+        if !distinct.contains(&topic) {
+            distinct.push(topic);
+        }
+    }
+    distinct
+}
+
+fn enqueue(gateway: Principal, org_id: Principal, connection_id: u64, client: Principal, event: LiveEvent, now: u64) {
+    GATEWAY_QUEUES.with(|queues| {
+        let mut queues = queues.borrow_mut();
+        let queue = queues.entry(gateway).or_insert_with(|| GatewayQueue {
+            next_seq: now,
+            messages: VecDeque::new(),
+        });
+        queue.messages.push_back(QueuedMessage {
+            org_id,
+            message: WsOutgoingMessage {
+                seq: queue.next_seq,
+                connection_id,
+                client,
+                event,
+                sent_at: now,
+            },
+        });
+        queue.next_seq += 1;
+        while queue.messages.len() > MAX_QUEUED_PER_GATEWAY {
+            queue.messages.pop_front();
+        }
+    });
+}
+
+// Drop connections that went quiet, telling their gateway
+fn prune_idle(now: u64) {
+    let cutoff = now.saturating_sub(IDLE_TIMEOUT_SECONDS * NANOS_PER_SECOND);
+    let idle: Vec<(u64, Principal, Principal, Principal)> = CONNECTIONS.with(|connections| {
+        connections
+            .borrow()
+            .iter()
+            .filter(|(_, c)| c.last_seen_at < cutoff)
+            .map(|(id, c)| (*id, c.gateway, c.org_id, c.client))
+            .collect()
+    });
+    for (connection_id, gateway, org_id, client) in idle {
+        CONNECTIONS.with(|connections| connections.borrow_mut().remove(&connection_id));
+        enqueue(gateway, org_id, connection_id, client, LiveEvent::Closed { reason: "Idle timeout".to_string() }, now);
+    }
+}
+
+pub fn open(client: Principal, gateway: Principal, org_id: Principal, topics: Vec<LiveTopic>, now: u64) -> Result<(u64, Vec<LiveTopic>), ApiError> {
+    if client == Principal::anonymous() {
+        return Err(ApiError::unauthorized("Sign in to open a live connection"));
+    }
+    if gateway == Principal::anonymous() {
+        return Err(ApiError::invalid_input("A gateway principal is required"));
+    }
+    if !config::is_live_gateway(gateway) {
+        return Err(ApiError::invalid_input("The gateway is not one the canister accepts"));
+    }
+    prune_idle(now);
+
+    let (org_connections, client_connections) = CONNECTIONS.with(|connections| {
+        let connections = connections.borrow();
+        (
+            connections.values().filter(|c| c.org_id == org_id).count(),
+            connections.values().filter(|c| c.client == client).count(),
+        )
+    });
+    if org_connections >= MAX_CONNECTIONS_PER_ORG {
+        return Err(ApiError::quota_exceeded(&format!(
+            "The organization already has {} live connections",
+            MAX_CONNECTIONS_PER_ORG
+        )));
+    }
+    if client_connections >= MAX_CONNECTIONS_PER_CLIENT {
+        return Err(ApiError::quota_exceeded(&format!(
+            "At most {} live connections per user",
+            MAX_CONNECTIONS_PER_CLIENT
+        )));
+    }
+
+    let topics = normalize_topics(topics);
+    let connection_id = next_connection_id(now);
+    CONNECTIONS.with(|connections| {
+        connections.borrow_mut().insert(
+            connection_id,
+            Connection {
+                client,
+                gateway,
+                org_id,
+                topics: topics.clone(),
+                last_seen_at: now,
+            },
+        )
+    });
+    enqueue(gateway, org_id, connection_id, client, LiveEvent::Subscribed { topics: topics.clone() }, now);
+    Ok((connection_id, topics))
+}
+
+// Organization a client's open connection belongs to
+pub fn connection_org(connection_id: u64, client: Principal) -> Result<Principal, ApiError> {
+    CONNECTIONS.with(|connections| {
+        connections
+            .borrow()
+            .get(&connection_id)
+            .filter(|c| c.client == client)
+            .map(|c| c.org_id)
+            .ok_or_else(|| ApiError::not_found("Live connection not found"))
+    })
+}
+
+// Record a message from the client, replacing its topics if it sent new ones
+pub fn on_client_message(connection_id: u64, client: Principal, topics: Option<Vec<LiveTopic>>, now: u64) -> Result<(), ApiError> {
+    let org_id = connection_org(connection_id, client)?;
+    let subscribed = CONNECTIONS.with(|connections| {
+        let mut connections = connections.borrow_mut();
+        let connection = connections.get_mut(&connection_id)?;
+        connection.last_seen_at = now;
+        let topics = topics.map(normalize_topics)?;
+        connection.topics = topics.clone();
+        Some((connection.gateway, topics))
+    });
+    if let Some((gateway, topics)) = subscribed {
+        enqueue(gateway, org_id, connection_id, client, LiveEvent::Subscribed { topics }, now);
+    }
+    prune_idle(now);
+    Ok(())
+}
+
+// Close a connection, either by its client or by the gateway relaying it
+pub fn close(connection_id: u64, caller: Principal, reason: &str, now: u64) -> Result<(), ApiError> {
+    let closed = CONNECTIONS.with(|connections| {
+        let mut connections = connections.borrow_mut();
+        match connections.get(&connection_id) {
+            Some(c) if c.client == caller || c.gateway == caller => connections.remove(&connection_id),
+            _ => None,
+        }
+    });
+    match closed {
+        Some(connection) => {
+            enqueue(
+                connection.gateway,
+                connection.org_id,
+                connection_id,
+                connection.client,
+                LiveEvent::Closed { reason: reason.to_string() },
+                now,
+            );
+            Ok(())
+        }
+        None => Err(ApiError::not_found("Live connection not found")),
+    }
+}
+
+// Queue an event of the organization for each of its connections following the topic
+pub fn publish(org_id: Principal, topic: LiveTopic, event: LiveEvent) {
+    let now = clock::now();
+    prune_idle(now);
+    let recipients: Vec<(u64, Principal, Principal)> = CONNECTIONS.with(|connections| {
+        connections
+            .borrow()
+            .iter()
+            .filter(|(_, c)| c.org_id == org_id && follows(c, topic))
+            .map(|(id, c)| (*id, c.gateway, c.client))
+            .collect()
+    });
+    for (connection_id, gateway, client) in recipients {
+        enqueue(gateway, org_id, connection_id, client, event.clone(), now);
+    }
+}
+
+// Messages queued for the gateway from sequence number `from_seq` on. Polling does not remove them;
+// they age out of the queue as newer ones arrive. Messages for a client `can_read` no longer lets read
+// the organization are skipped, apart from Closed events.
+pub fn messages_for_gateway<F>(gateway: Principal, from_seq: u64, can_read: F) -> (Vec<WsOutgoingMessage>, u64)
+where
+    F: Fn(Principal, Principal) -> bool,
+{
+    GATEWAY_QUEUES.with(|queues| match queues.borrow().get(&gateway) {
+        Some(queue) => {
+            let polled: Vec<&QueuedMessage> =
+                queue.messages.iter().filter(|q| q.message.seq >= from_seq).take(MAX_MESSAGES_PER_POLL).collect();
+            let next_seq = polled.last().map_or(from_seq, |q| q.message.seq + 1);
+            let messages = polled
+                .into_iter()
+                .filter(|q| matches!(q.message.event, LiveEvent::Closed { .. }) || can_read(q.message.client, q.org_id))
+                .map(|q| q.message.clone())
+                .collect();
+            (messages, next_seq)
+        }
+        None => (Vec::new(), from_seq),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::models::CanisterConfig;
+
+    #[test]
+    fn idle_connection_is_closed_once_the_timeout_passes() {
+        let mock = Rc::new(MockClock::new(1_700_000_000_000_000_000));
+        clock::set_clock(mock.clone());
+        let client = Principal::from_slice(&[1]);
+        let gateway = Principal::from_slice(&[2]);
+        let org_id = Principal::from_slice(&[3]);
+        config::set_config(CanisterConfig {
+            live_gateways: Some(vec![gateway]),
+            ..config::get_config()
+        })
+        .unwrap();
+
+        let (connection_id, _) = open(client, gateway, org_id, Vec::new(), clock::now()).unwrap();
+        let closed = LiveEvent::Closed { reason: "test".to_string() };
+
+        mock.advance(IDLE_TIMEOUT_SECONDS * NANOS_PER_SECOND);
+        publish(org_id, LiveTopic::Verifications, closed.clone());
+        assert_eq!(connection_org(connection_id, client).ok(), Some(org_id));
+
+        mock.advance(1);
+        publish(org_id, LiveTopic::Verifications, closed);
+        assert!(connection_org(connection_id, client).is_err());
+
+        let (messages, _) = messages_for_gateway(gateway, 0, |_, _| true);
+        assert!(matches!(
+            messages.last().map(|m| &m.event),
+            Some(LiveEvent::Closed { reason }) if reason == "Idle timeout"
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{LogEntry, LogLevel};
+
+// Oldest entries beyond this are dropped from the ring buffer
+pub const LOG_BUFFER_CAPACITY: u64 = 5_000;
+// Debug output goes to the replica log only
+const MIN_STORED_LEVEL: LogLevel = LogLevel::Info;
+
+// Define a unique MemoryId for this structure
+const LOGS_MEM_ID: MemoryId = MemoryId::new(35);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by a monotonically increasing sequence number
+    static LOGS: RefCell<StableBTreeMap<u64, LogEntry, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(LOGS_MEM_ID))
+        )
+    );
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { $crate::logging::log($crate::models::LogLevel::Debug, module_path!(), format!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => { $crate::logging::log($crate::models::LogLevel::Info, module_path!(), format!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { $crate::logging::log($crate::models::LogLevel::Warn, module_path!(), format!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::logging::log($crate::models::LogLevel::Error, module_path!(), format!($($arg)*)) };
+}
+
+#[cfg(target_arch = "wasm32")]
+fn level_prefix(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Debug => "🔍",
+        LogLevel::Info => "ℹ️",
+        LogLevel::Warn => "⚠️",
+        LogLevel::Error => "❌ ERROR",
+    }
+}
+
+// Print to the replica log and, from Info up, append to the ring buffer. Use the log_* macros rather than calling this directly.
+pub fn log(level: LogLevel, module_path: &str, message: String) {
+    // "backend::icp" -> "icp"
+    let module = module_path.rsplit("::").next().unwrap_or(module_path);
+    // The debug print only exists inside a canister; native unit tests keep just the stored entries
+    #[cfg(target_arch = "wasm32")]
+    ic_cdk::print(format!("{} [{}] {}", level_prefix(level), module, message));
+
+    if level < MIN_STORED_LEVEL {
+        return;
+    }
+    LOGS.with(|logs| {
+        let mut logs_mut = logs.borrow_mut();
+        let seq = logs_mut.last_key_value().map_or(0, |(seq, _)| seq + 1);
+        logs_mut.insert(
+            seq,
+            LogEntry {
+                seq,
+                timestamp: clock::now(),
+                level,
+                module: module.to_string(),
+                message,
+            },
+        );
+        while logs_mut.len() > LOG_BUFFER_CAPACITY {
+            match logs_mut.first_key_value() {
+                Some((oldest, _)) => {
+                    logs_mut.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    });
+}
+
+// Newest first, keeping entries at or above `min_level` whose module contains `module`
+pub fn recent_logs(min_level: LogLevel, module: Option<&str>, limit: usize) -> Vec<LogEntry> {
+    LOGS.with(|logs| {
+        let logs_ref = logs.borrow();
+        let (first, last) = match (logs_ref.first_key_value(), logs_ref.last_key_value()) {
+            (Some((first, _)), Some((last, _))) => (first, last),
+            _ => return Vec::new(),
+        };
+
+        let mut entries = Vec::new();
+        for seq in (first..=last).rev() {
+            if entries.len() >= limit {
+                break;
+            }
+            if let Some(entry) = logs_ref.get(&seq) {
+                if entry.level >= min_level && module.is_none_or(|m| entry.module.contains(m)) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries
+    })
+}
+
+// Reset the log buffer (use with caution)
+pub fn reset_logs() {
+    LOGS.with(|logs| {
+        let mut logs_mut = logs.borrow_mut();
+        let keys: Vec<_> = logs_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            logs_mut.remove(&key);
+        }
+    });
+    log_info!("All logs have been reset.");
+}
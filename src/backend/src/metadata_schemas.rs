@@ -0,0 +1,224 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+use crate::catalog;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{Metadata, MetadataFieldSchema, MetadataSchema, MetadataValueType};
+use crate::validation::{self, Validator, MAX_METADATA_ENTRIES, MAX_METADATA_KEY_LENGTH, MAX_METADATA_VALUE_LENGTH};
+
+pub const MAX_ALLOWED_VALUES: usize = 100;
+
+// Define a unique MemoryId for this structure
+const METADATA_SCHEMAS_MEM_ID: MemoryId = MemoryId::new(70);
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SchemaKey {
+    org_id: Principal,
+    category: String, // category_key of the schema's category
+}
+
+impl Storable for SchemaKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static METADATA_SCHEMAS: RefCell<StableBTreeMap<SchemaKey, MetadataSchema, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(METADATA_SCHEMAS_MEM_ID))
+        )
+    );
+}
+
+// Categories differing only in case, punctuation or spacing share a schema
+pub fn category_key(category: &str) -> String {
+    catalog::normalize_for_comparison(category)
+}
+
+fn schema_key(org_id: Principal, category: &str) -> SchemaKey {
+    SchemaKey { org_id, category: category_key(category) }
+}
+
+pub fn save_schema(schema: MetadataSchema) {
+    METADATA_SCHEMAS.with(|schemas| {
+        schemas.borrow_mut().insert(schema_key(schema.org_id, &schema.category), schema);
+    });
+}
+
+pub fn get_schema(org_id: Principal, category: &str) -> Option<MetadataSchema> {
+    METADATA_SCHEMAS.with(|schemas| schemas.borrow().get(&schema_key(org_id, category)))
+}
+
+pub fn remove_schema(org_id: Principal, category: &str) -> Option<MetadataSchema> {
+    METADATA_SCHEMAS.with(|schemas| schemas.borrow_mut().remove(&schema_key(org_id, category)))
+}
+
+pub fn list_for_org(org_id: Principal) -> Vec<MetadataSchema> {
+    METADATA_SCHEMAS.with(|schemas| {
+        schemas
+            .borrow()
+            .iter()
+            .filter(|(key, _)| key.org_id == org_id)
+            .map(|(_, schema)| schema)
+            .collect()
+    })
+}
+
+fn check_type(value_type: MetadataValueType, value: &str) -> Result<(), &'static str> {
+    let value = value.trim();
+    match value_type {
+        MetadataValueType::Text => Ok(()),
+        MetadataValueType::Integer => value.parse::<i64>().map(|_| ()).map_err(|_| "Must be a whole number"),
+        MetadataValueType::Decimal => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(()),
+            _ => Err("Must be a number"),
+        },
+        MetadataValueType::Boolean => match value {
+            "true" | "false" => Ok(()),
+            _ => Err("Must be true or false"),
+        },
+        MetadataValueType::Url => validation::check_url(value),
+        MetadataValueType::Date => check_date(value),
+    }
+}
+
+// Calendar date as YYYY-MM-DD; day-of-month is checked against the month, leap years included
+fn check_date(value: &str) -> Result<(), &'static str> {
+    const MESSAGE: &str = "Must be a date in YYYY-MM-DD format";
+    let parts: Vec<&str> = value.split('-').collect();
+    if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2 {
+        return Err(MESSAGE);
+    }
+    let (year, month, day) = match (parts[0].parse::<u32>(), parts[1].parse::<u32>(), parts[2].parse::<u32>()) {
+        (Ok(year), Ok(month), Ok(day)) => (year, month, day),
+        _ => return Err(MESSAGE),
+    };
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return Err(MESSAGE),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(MESSAGE);
+    }
+    Ok(())
+}
+
+// Check the field rules of a schema being saved
+pub fn check_definition(validator: &mut Validator, field: &str, rules: &[MetadataFieldSchema]) {
+    if rules.len() > MAX_METADATA_ENTRIES {
+        validator.add_error(field, &format!("At most {} fields are allowed", MAX_METADATA_ENTRIES));
+    }
+    let mut seen = HashSet::new();
+    for (i, rule) in rules.iter().enumerate() {
+        let key = rule.key.trim();
+        if key.is_empty() {
+            validator.add_error(&format!("{}[{}].key", field, i), "Key cannot be empty");
+        } else if key.chars().count() > MAX_METADATA_KEY_LENGTH {
+            validator.add_error(&format!("{}[{}].key", field, i), &format!("Must be at most {} characters", MAX_METADATA_KEY_LENGTH));
+        } else if !seen.insert(key.to_string()) {
+            validator.add_error(&format!("{}[{}].key", field, i), "Key is defined more than once");
+        }
+        if let Some(allowed) = &rule.allowed_values {
+            let allowed_field = format!("{}[{}].allowed_values", field, i);
+            if allowed.is_empty() {
+                validator.add_error(&allowed_field, "Must list at least one value, or be omitted");
+            } else if allowed.len() > MAX_ALLOWED_VALUES {
+                validator.add_error(&allowed_field, &format!("At most {} values are allowed", MAX_ALLOWED_VALUES));
+            }
+            for (j, value) in allowed.iter().enumerate() {
+                if value.chars().count() > MAX_METADATA_VALUE_LENGTH {
+                    validator.add_error(&format!("{}[{}]", allowed_field, j), &format!("Must be at most {} characters", MAX_METADATA_VALUE_LENGTH));
+                } else if let Err(message) = check_type(rule.value_type, value) {
+                    validator.add_error(&format!("{}[{}]", allowed_field, j), message);
+                }
+            }
+        }
+    }
+}
+
+// Report metadata entries that break the rules: missing required keys, values of the wrong
+// type and values outside the allowed list. Only the first entry of a repeated key is checked.
+pub fn check_entries(validator: &mut Validator, field: &str, rules: &[MetadataFieldSchema], entries: &[Metadata]) {
+    for rule in rules {
+        let key = rule.key.trim();
+        let found = entries.iter().enumerate().find(|(_, entry)| entry.key.trim() == key);
+        let (i, entry) = match found {
+            Some(found) => found,
+            None => {
+                if rule.required {
+                    validator.add_error(&format!("{}.{}", field, key), "This field is required");
+                }
+                continue;
+            }
+        };
+        let value = entry.value.trim();
+        let value_field = format!("{}[{}].value", field, i);
+        if value.is_empty() {
+            if rule.required {
+                validator.add_error(&value_field, "This field is required");
+            }
+            continue;
+        }
+        if let Err(message) = check_type(rule.value_type, value) {
+            validator.add_error(&value_field, message);
+        } else if let Some(allowed) = &rule.allowed_values {
+            if !allowed.iter().any(|a| a.trim() == value) {
+                validator.add_error(&value_field, &format!("Must be one of: {}", allowed.join(", ")));
+            }
+        }
+    }
+}
+
+// Product metadata against the schema of the organization's category, if it has one
+pub fn validate_product_metadata(org_id: Principal, category: &str, entries: &[Metadata]) -> Result<(), ApiError> {
+    let schema = match get_schema(org_id, category) {
+        Some(schema) => schema,
+        None => return Ok(()),
+    };
+    let mut validator = Validator::new();
+    check_entries(&mut validator, "metadata", &schema.product_fields, entries);
+    validator.finish()
+}
+
+// Serial metadata against the serial rules of the product's category schema, if it has one
+pub fn validate_serial_metadata(org_id: Principal, category: &str, entries: &[Metadata]) -> Result<(), ApiError> {
+    let schema = match get_schema(org_id, category) {
+        Some(schema) => schema,
+        None => return Ok(()),
+    };
+    let mut validator = Validator::new();
+    check_entries(&mut validator, "metadata", &schema.serial_fields, entries);
+    validator.finish()
+}
+
+// Reset ALL metadata schemas (use with caution)
+pub fn reset_metadata_schemas() {
+    METADATA_SCHEMAS.with(|schemas| {
+        let mut schemas_mut = schemas.borrow_mut();
+        let keys: Vec<_> = schemas_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            schemas_mut.remove(&key);
+        }
+    });
+    log_info!("All metadata schemas have been reset.");
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use candid::Principal;
+use ic_cdk::api;
+
+use crate::analytics;
+use crate::catalog;
+use crate::clock;
+use crate::config;
+use crate::disputes;
+use crate::feature_flags;
+use crate::heatmap;
+use crate::id_registry;
+use crate::global_state::{CONFIG_OPENAI_API_KEY, CONFIG_SCRAPER_URL, USERS};
+use crate::models::UserRole;
+use crate::notifications;
+use crate::owned_products;
+use crate::plans;
+use crate::serial_store;
+use crate::verification_store;
+
+// Runs on every upgrade. Each migration must be idempotent.
+pub fn run_post_upgrade_migrations() {
+    // First, so every later step reads verifications from the new layout
+    let moved = verification_store::migrate_legacy_vectors();
+    log_info!("[run_post_upgrade_migrations] Moved verifications of {} product(s) to the keyed store.", moved);
+    let moved = serial_store::migrate_legacy_vectors();
+    log_info!("[run_post_upgrade_migrations] Moved serial numbers of {} product(s) to the keyed store.", moved);
+    let indexed = verification_store::backfill_user_index();
+    log_info!("[run_post_upgrade_migrations] Indexed {} verification(s) under their user.", indexed);
+    let indexed = verification_store::backfill_serial_index();
+    log_info!("[run_post_upgrade_migrations] Indexed {} verification(s) under their serial.", indexed);
+    let indexed = disputes::backfill_open_serial_index();
+    log_info!("[run_post_upgrade_migrations] Indexed {} open dispute(s) under their serial.", indexed);
+    let indexed = owned_products::backfill_owner_index();
+    log_info!("[run_post_upgrade_migrations] Indexed {} shelf registration(s) under their owner.", indexed);
+    let moved = notifications::migrate_legacy_notifications();
+    log_info!("[run_post_upgrade_migrations] Moved {} notification(s) under their user.", moved);
+    migrate_legacy_config_cells();
+    migrate_config_feature_flags();
+    let migrated = migrate_roleless_users_to_customer();
+    log_info!("[run_post_upgrade_migrations] Assigned Customer role to {} role-less user(s).", migrated);
+    let seeded = analytics::rebuild_missing_counters();
+    log_info!("[run_post_upgrade_migrations] Seeded analytics counters for {} organization(s).", seeded);
+    let seeded = heatmap::backfill_missing();
+    log_info!("[run_post_upgrade_migrations] Seeded verification heatmaps for {} organization(s).", seeded);
+    let numbered = catalog::backfill_product_numbers();
+    log_info!("[run_post_upgrade_migrations] Assigned product numbers to {} product(s).", numbered);
+    let registered = id_registry::backfill_registry();
+    log_info!("[run_post_upgrade_migrations] Registered {} existing ID(s).", registered);
+    let seeded = plans::backfill_missing();
+    log_info!("[run_post_upgrade_migrations] Put {} existing organization(s) on the Pro plan.", seeded);
+}
+
+// Users who verified products before the Customer role existed were left without a role.
+// Anyone with verification history and no role becomes a Customer.
+pub fn migrate_roleless_users_to_customer() -> u32 {
+    let mut verifiers: HashSet<Principal> = HashSet::new();
+    verification_store::for_each(|verification| {
+        verifiers.insert(verification.created_by);
+    });
+
+    USERS.with(|users| {
+        let mut users_mut = users.borrow_mut();
+        let to_migrate: Vec<_> = users_mut
+            .iter()
+            .filter(|(id, user)| user.user_role.is_none() && verifiers.contains(id))
+            .map(|(_, user)| user)
+            .collect();
+
+        let migrated = to_migrate.len() as u32;
+        let now = clock::now();
+        for mut user in to_migrate {
+            user.user_role = Some(UserRole::Customer);
+            user.updated_at = now;
+            user.updated_by = api::id();
+            users_mut.insert(user.id, user);
+        }
+        migrated
+    })
+}
+
+// The OpenAI key and scraper URL used to live in their own cells. Copy them into the
+// consolidated canister config unless it already has values of its own.
+pub fn migrate_legacy_config_cells() {
+    let legacy_api_key = CONFIG_OPENAI_API_KEY.with(|cell| cell.borrow().get().0.clone());
+    let legacy_scraper_url = CONFIG_SCRAPER_URL.with(|cell| cell.borrow().get().0.clone());
+
+    let mut canister_config = config::get_config();
+    let mut changed = false;
+    if canister_config.llm.api_key.is_empty() && !legacy_api_key.is_empty() {
+        canister_config.llm.api_key = legacy_api_key;
+        changed = true;
+    }
+    if canister_config.scraper_url.is_empty() && !legacy_scraper_url.is_empty() {
+        canister_config.scraper_url = legacy_scraper_url;
+        changed = true;
+    }
+
+    if changed {
+        canister_config.updated_by = api::id();
+        match config::set_config(canister_config) {
+            Ok(_) => log_info!("[migrate_legacy_config_cells] Copied legacy config cells into canister config."),
+            Err(e) => log_error!("[migrate_legacy_config_cells] {:?}", e),
+        }
+    }
+}
+
+// Global feature flags briefly lived inside the canister config; move them to the flag store
+pub fn migrate_config_feature_flags() {
+    let mut canister_config = config::get_config();
+    if canister_config.feature_flags.is_empty() {
+        return;
+    }
+
+    for flag in canister_config.feature_flags.drain(..) {
+        if feature_flags::get_global_flag(&flag.name).is_none() {
+            feature_flags::set_global_flag(&flag.name, flag.enabled);
+        }
+    }
+    if let Err(e) = config::set_config(canister_config) {
+        log_error!("[migrate_config_feature_flags] {:?}", e);
+    }
+}
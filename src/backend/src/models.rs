@@ -11,11 +11,12 @@ use crate::{
     },
     utils::generate_unique_principal
 };
+use crate::clock;
 
 macro_rules! impl_storable_for_candid_type {
     ($type:ty) => {
         impl Storable for $type {
-            fn to_bytes(&self) -> Cow<[u8]> {
+            fn to_bytes(&self) -> Cow<'_, [u8]> {
                 Cow::Owned(encode_one(self).expect("Failed to encode"))
             }
 
@@ -63,21 +64,22 @@ impl Default for Organization {
             description: String::new(),
             private_key: String::new(),
             metadata: Vec::new(),
-            created_at: api::time(),
+            created_at: clock::now(),
             created_by: api::caller(), // Default value for Principal
-            updated_at: api::time(),
+            updated_at: clock::now(),
             updated_by: api::caller(), // Default value for Principal
         }
     }
 }
 
+// The signing key stays out of logs
 impl fmt::Debug for Organization {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Organization")
         .field("id", &self.id)
         .field("name", &self.name)
         .field("description", &self.description)
-        .field("private_key", &self.private_key)
+        .field("private_key", &"<redacted>")
         .field("metadata", &self.metadata)
         .field("created_at", &self.created_at)
         .field("created_by", &self.created_by)
@@ -147,6 +149,16 @@ pub struct Product {
     pub description: String,
     pub metadata: Vec<Metadata>,
     pub public_key: String,
+    pub product_number: Option<u64>, // Per-organization sequence; None only until the backfill migration runs
+    pub sku: Option<String>,         // Unique within the organization
+    pub challenge_required: Option<bool>, // High-value goods verify through a signed challenge instead of a static code
+    pub discontinued_at: Option<u64>, // End of life; discontinued products still verify
+    pub successor_product_id: Option<Principal>, // Replacement shown to consumers scanning old stock
+    pub allowed_markets: Option<Vec<String>>, // ISO 3166-1 alpha-2 codes; None or empty means sold everywhere
+    pub sequential_serials: Option<bool>, // New serials get a per-product sequence number, so printed lots can be tracked as ranges
+    pub is_test: Option<bool>, // Sandbox product: verifications credit no points and are left out of analytics and exports
+    pub warranty_days: Option<u32>, // Counted from when an owner registers the item; None when no warranty is offered
+    pub recall: Option<ProductRecall>, // Active recall, shown to registered owners
     pub created_at: u64,
     pub created_by: Principal,
     pub updated_at: u64,
@@ -164,9 +176,19 @@ impl Default for Product {
             category: String::new(),
             metadata: Vec::new(),
             public_key: String::new(),
-            created_at: api::time(),
+            product_number: None,
+            sku: None,
+            challenge_required: None,
+            discontinued_at: None,
+            successor_product_id: None,
+            allowed_markets: None,
+            sequential_serials: None,
+            is_test: None,
+            warranty_days: None,
+            recall: None,
+            created_at: clock::now(),
             created_by: api::caller(), // Default value for Principal
-            updated_at: api::time(),
+            updated_at: clock::now(),
             updated_by: api::caller(), // Default value for Principal
         }
     }
@@ -181,12 +203,30 @@ impl fmt::Debug for Product {
         .field("category", &self.category)
         .field("metadata", &self.metadata)
         .field("public_key", &self.public_key)
+        .field("product_number", &self.product_number)
+        .field("sku", &self.sku)
+        .field("challenge_required", &self.challenge_required)
+        .field("discontinued_at", &self.discontinued_at)
+        .field("successor_product_id", &self.successor_product_id)
+        .field("allowed_markets", &self.allowed_markets)
+        .field("sequential_serials", &self.sequential_serials)
+        .field("is_test", &self.is_test)
+        .field("warranty_days", &self.warranty_days)
+        .field("recall", &self.recall)
         .field("created_at", &self.created_at)
         .field("updated_at", &self.created_at)
         .finish()
     }
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ProductRecall {
+    pub reason: String,
+    pub instructions: Option<String>, // What owners should do, e.g. stop using it or where to return it
+    pub issued_at: u64,
+    pub issued_by: Principal,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Debug, Clone)]
 pub enum ProductResult {
     #[serde(rename = "product")]
@@ -204,6 +244,7 @@ pub struct ProductInput {
     pub category: String,
     pub description: String,
     pub metadata: Vec<Metadata>,
+    pub sku: Option<String>, // On update, None keeps the current SKU and an empty string clears it
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone)]
@@ -212,6 +253,13 @@ pub struct ProductSerialNumber {
     pub serial_no: Principal,
     pub print_version: u8,
     pub metadata: Vec<Metadata>,
+    pub last_print_job_id: Option<Principal>, // Print job that produced the current print_version
+    pub status: Option<SerialNumberStatus>, // None for serials created before statuses were tracked
+    pub suspected_cloned: Option<bool>,
+    pub variant_id: Option<Principal>, // Variant pool the serial belongs to; None for the parent product itself
+    pub revoked_codes: Option<Vec<CodeRevocation>>, // Printed versions the brand has killed, e.g. a stolen label roll
+    pub sequence_no: Option<u64>, // Set when the product numbers its serials sequentially; starts at 1
+    pub user_serial_no: Option<String>, // Human-readable serial, e.g. "LOT7-000042"; unique within the product
     pub created_at: u64,
     pub created_by: Principal,
     pub updated_at: u64,
@@ -219,6 +267,64 @@ pub struct ProductSerialNumber {
 }
 impl_storable_for_candid_type!(ProductSerialNumber);
 
+impl ProductSerialNumber {
+    // Stored status, or the one implied by print_version for legacy serials
+    pub fn effective_status(&self) -> SerialNumberStatus {
+        match self.status {
+            Some(status) => status,
+            None if self.print_version > 0 => SerialNumberStatus::Printed,
+            None => SerialNumberStatus::Created,
+        }
+    }
+
+    pub fn revocation_for(&self, print_version: u8) -> Option<&CodeRevocation> {
+        self.revoked_codes.as_ref()?.iter().find(|r| r.print_version == print_version)
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeRevocationReason {
+    Stolen,
+    Lost,
+    Misprinted,
+    Other,
+}
+
+// A single printed code (serial at one print_version) that no longer verifies. Reprinting the
+// serial issues a new version, which is unaffected.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CodeRevocation {
+    pub print_version: u8,
+    pub reason: CodeRevocationReason,
+    pub revoked_at: u64,
+    pub revoked_by: Principal,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SerialNumberStatus {
+    Created,
+    Printed,
+    Shipped,
+    Sold,
+    Void,
+}
+
+impl SerialNumberStatus {
+    // Lifecycle: Created -> Printed -> Shipped -> Sold. Any state except Void can be voided, Void is terminal.
+    pub fn can_transition_to(&self, next: SerialNumberStatus) -> bool {
+        matches!(
+            (self, next),
+            (SerialNumberStatus::Created, SerialNumberStatus::Printed)
+                | (SerialNumberStatus::Printed, SerialNumberStatus::Shipped)
+                | (SerialNumberStatus::Shipped, SerialNumberStatus::Sold)
+                | (SerialNumberStatus::Created, SerialNumberStatus::Void)
+                | (SerialNumberStatus::Printed, SerialNumberStatus::Void)
+                | (SerialNumberStatus::Shipped, SerialNumberStatus::Void)
+                | (SerialNumberStatus::Sold, SerialNumberStatus::Void)
+        )
+    }
+}
+
 impl Default for ProductSerialNumber {
     fn default() -> Self {
         ProductSerialNumber { 
@@ -226,9 +332,16 @@ impl Default for ProductSerialNumber {
             serial_no: generate_unique_principal(Principal::anonymous()),
             print_version: 0,
             metadata: Vec::new(),
-            created_at: api::time(),
+            last_print_job_id: None,
+            status: Some(SerialNumberStatus::Created),
+            suspected_cloned: None,
+            variant_id: None,
+            revoked_codes: None,
+            sequence_no: None,
+            user_serial_no: None,
+            created_at: clock::now(),
             created_by: api::caller(), // Default value for Principal
-            updated_at: api::time(),
+            updated_at: clock::now(),
             updated_by: api::caller(), // Default value for Principal
         }
     }
@@ -239,6 +352,7 @@ pub struct ProductVerification {
     pub id: Principal,
     pub product_id: Principal,
     pub serial_no: Principal,
+    pub variant_id: Option<Principal>,
     pub print_version: u8,
     pub metadata: Vec<Metadata>,
     pub created_at: u64,
@@ -246,22 +360,35 @@ pub struct ProductVerification {
     pub status: ProductVerificationStatus,
     pub reward_claimed: bool,
     pub reward_transaction_id: Option<String>,
+    pub country_code: Option<String>, // Where the scan happened, as reported by the client
+    pub annotations: Option<Vec<VerificationAnnotation>>,
+    pub is_test: Option<bool>, // Recorded against a test product
 }
 impl_storable_for_candid_type!(ProductVerification);
 
+// Observations attached to an otherwise genuine verification
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationAnnotation {
+    GreyMarketSuspected, // Scanned outside the product's allowed markets
+}
+
 impl Default for ProductVerification {
     fn default() -> Self {
         ProductVerification {
             id: generate_unique_principal(Principal::anonymous()),
             product_id: Principal::anonymous(),
             serial_no: Principal::anonymous(),
+            variant_id: None,
             print_version: 0,
             metadata: Vec::new(),
-            created_at: api::time(),
+            created_at: clock::now(),
             created_by: api::caller(), // Default value for Principal
             status: ProductVerificationStatus::FirstVerification,
             reward_claimed: false,
             reward_transaction_id: None,
+            country_code: None,
+            annotations: None,
+            is_test: None,
         }
     }
 }
@@ -310,9 +437,9 @@ impl Default for User {
             email: None,
             detail_meta: Vec::new(),
             session_keys: Vec::new(),
-            created_at: api::time(),
+            created_at: clock::now(),
             created_by: api::caller(),
-            updated_at: api::time(),
+            updated_at: clock::now(),
             updated_by: api::caller(),
         }
     }
@@ -387,6 +514,8 @@ pub struct Reseller {
     pub is_verified: bool,
     pub certification_code: Option<String>,
     pub certification_timestamp: Option<u64>,
+    pub certification_expires_at: Option<u64>,
+    pub email_verified: Option<bool>, // None without a contact email, or for resellers certified before confirmation was required
     pub date_joined: u64,
     pub metadata: Vec<Metadata>,
     pub public_key: String,
@@ -397,6 +526,33 @@ pub struct Reseller {
 }
 impl_storable_for_candid_type!(Reseller);
 
+// The certification code proves the reseller is certified, so it stays out of logs
+impl fmt::Debug for Reseller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reseller")
+        .field("id", &self.id)
+        .field("user_id", &self.user_id)
+        .field("org_id", &self.org_id)
+        .field("name", &self.name)
+        .field("contact_email", &self.contact_email)
+        .field("contact_phone", &self.contact_phone)
+        .field("ecommerce_urls", &self.ecommerce_urls)
+        .field("additional_metadata", &self.additional_metadata)
+        .field("is_verified", &self.is_verified)
+        .field("certification_code", &self.certification_code.as_ref().map(|_| "<redacted>"))
+        .field("certification_timestamp", &self.certification_timestamp)
+        .field("certification_expires_at", &self.certification_expires_at)
+        .field("date_joined", &self.date_joined)
+        .field("metadata", &self.metadata)
+        .field("public_key", &self.public_key)
+        .field("created_at", &self.created_at)
+        .field("created_by", &self.created_by)
+        .field("updated_at", &self.updated_at)
+        .field("updated_by", &self.updated_by)
+        .finish()
+    }
+}
+
 impl Default for Reseller {
     fn default() -> Self {
         Reseller {
@@ -411,17 +567,78 @@ impl Default for Reseller {
             is_verified: false,
             certification_code: None,
             certification_timestamp: None,
-            date_joined: api::time(),
+            certification_expires_at: None,
+            email_verified: None,
+            date_joined: clock::now(),
             metadata: Vec::new(),
             public_key: String::new(),
-            created_at: api::time(),
+            created_at: clock::now(),
             created_by: api::caller(),
-            updated_at: api::time(),
+            updated_at: clock::now(),
             updated_by: api::caller(),
         }
     }
 }
 
+// Explicit list of products a reseller may sell. Resellers without one are authorized org-wide.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AuthorizedProducts {
+    pub reseller_id: Principal,
+    pub org_id: Principal,
+    pub product_ids: Vec<Principal>,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(AuthorizedProducts);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResellerApplicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+// A reseller's request to represent an organization. The certification code is only issued once
+// a brand owner or admin approves it.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ResellerApplication {
+    pub id: Principal,
+    pub reseller_id: Principal,
+    pub user_id: Principal,
+    pub org_id: Principal,
+    pub status: ResellerApplicationStatus,
+    pub submitted_at: u64,
+    pub decided_at: Option<u64>,
+    pub decided_by: Option<Principal>,
+    pub rejection_reason: Option<String>,
+}
+impl_storable_for_candid_type!(ResellerApplication);
+
+// Distributor a brand approved up front. A reseller applying to the brand with this confirmed
+// contact email is certified without manual review.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ResellerPreapproval {
+    pub org_id: Principal,
+    pub email: String, // Lower-cased
+    pub name: String,
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub used_by_reseller_id: Option<Principal>, // Set once it certified a reseller
+    pub used_at: Option<u64>,
+}
+impl_storable_for_candid_type!(ResellerPreapproval);
+
+// Outstanding contact email confirmation of a reseller. Only the token's hash is stored.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ResellerEmailConfirmation {
+    pub reseller_id: Principal,
+    pub email: String,
+    pub token_hash: String,
+    pub expires_at: u64,
+    pub created_at: u64,
+}
+impl_storable_for_candid_type!(ResellerEmailConfirmation);
+
 #[derive(CandidType, Deserialize)]
 pub struct ResellerInput {
     pub org_id: Principal,
@@ -442,7 +659,11 @@ pub enum UniqueCodeResult {
 pub enum ProductVerificationStatus {
     FirstVerification,
     MultipleVerification,
-    Invalid
+    Invalid,
+    VoidedSerial,
+    CodeRevoked, // Genuine code whose printed version the brand revoked
+    NotYetActive, // Genuine code from a print job whose activation time has not come yet
+    SupersededCode, // Genuine code of an older print version, replaced by a reprint of the label
 }
 
 #[derive(CandidType, Deserialize)]
@@ -489,6 +710,7 @@ pub struct ProductUniqueCodeResultRecord {
     pub product_id: Principal,
     pub serial_no: Principal,
     pub created_at: u64,
+    pub verification_url: Option<String>, // Deep link on the brand's verified domain, for the QR payload
 }
 
 #[derive(CandidType, Deserialize)]
@@ -525,6 +747,7 @@ pub struct ResellerContextDetails {
     pub associated_organization: Option<OrganizationPublic>,
     pub certification_code: Option<String>,
     pub certification_timestamp: Option<u64>,
+    pub certification_expires_at: Option<u64>,
 }
 impl_storable_for_candid_type!(ResellerContextDetails);
 
@@ -578,6 +801,7 @@ pub struct ResellerPublic { // Sanitized Reseller details
     pub public_key: String,
     pub certification_code: Option<String>,
     pub certification_timestamp: Option<u64>,
+    pub certification_expires_at: Option<u64>,
     pub created_at: u64,
     pub updated_at: u64,
 }
@@ -594,12 +818,24 @@ pub struct CompleteResellerProfileRequest {
 }
 impl_storable_for_candid_type!(CompleteResellerProfileRequest);
 
+// Renewal re-confirms the profile details captured when the reseller was first certified
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RenewResellerCertificationRequest {
+    pub reseller_name: String,
+    pub contact_email: Option<String>,
+    pub contact_phone: Option<String>,
+    pub ecommerce_urls: Vec<Metadata>,
+    pub additional_metadata: Option<Vec<Metadata>>,
+}
+impl_storable_for_candid_type!(RenewResellerCertificationRequest);
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct ResellerCertificationPageContext {
     pub reseller_profile: ResellerPublic,
     pub associated_organization: OrganizationPublic,
     pub certification_code: String, // Assuming it will always be present if this context is fetched
     pub certification_timestamp: u64, // Assuming it will always be present
+    pub certification_expires_at: Option<u64>,
     pub user_details: UserPublic,
 }
 impl_storable_for_candid_type!(ResellerCertificationPageContext);
@@ -614,3 +850,1559 @@ pub struct NavigationContextResponse {
 }
 impl_storable_for_candid_type!(NavigationContextResponse);
 
+
+// ====== Notification Rules ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationTrigger {
+    AnyVerification,
+    Invalid,
+    MultipleVerification,
+    SuspectedClone,
+    GreyMarket,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct NotificationRule {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub product_id: Principal,
+    pub trigger: NotificationTrigger,
+    pub min_scan_count: Option<u32>, // Only notify once a serial has been scanned more than this many times
+    pub webhook_url: String,
+    pub is_active: bool,
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(NotificationRule);
+
+// ====== Organization Settings ======
+
+// Per-organization configuration. Unset fields fall back to the platform defaults.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgSettings {
+    pub org_id: Principal,
+    pub certification_validity_seconds: Option<u64>,
+    pub certification_grace_period_seconds: Option<u64>,
+    pub duplicate_scan_threshold: Option<u32>, // Distinct scanners of one serial before it is flagged as cloned
+    pub redemption_approval_threshold: Option<u32>, // Reward points above which a redemption needs approval
+    pub single_use_reseller_codes: Option<bool>, // Reject reseller codes that were already verified once
+    pub verification_privacy: Option<VerificationPrivacyPolicy>, // None shows everything
+    pub client_version_policy: Option<ClientVersionPolicy>, // Applied on top of the platform-wide minimum
+    pub authenticity_weights: Option<AuthenticityWeights>, // None uses authenticity::DEFAULT_WEIGHTS
+    pub serial_batch_defaults: Option<SerialBatchDefaults>, // None leaves every batch option to the request
+    pub public_stats_enabled: Option<bool>, // Opt-in to get_public_brand_stats for embeddable counters
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(OrgSettings);
+
+// Whether a print run may issue a new code for a serial that was printed before, superseding its old label
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrintCodePolicy {
+    ReprintAllowed,
+    UnprintedOnly,
+}
+
+// Options serial batches and print runs of the organization start from; a value set on the request wins
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SerialBatchDefaults {
+    pub batch_size: Option<u32>, // Serials created by create_serial_numbers_batch_v2 when the request gives no count
+    pub code_policy: Option<PrintCodePolicy>, // None allows reprints
+    pub label_template_product_id: Option<Principal>, // Product whose label template is used for products without their own
+    pub activation_delay_seconds: Option<u64>, // Print runs activate this long after printing
+    pub user_serial_prefix: Option<String>, // Human-readable serial prefix for new serials
+}
+
+// Which optional details consumer-facing verification responses may reveal
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerificationPrivacyPolicy {
+    pub show_scan_count: bool,
+    pub show_first_scan_date: bool,
+    pub show_custody_chain: bool, // The serial's lifecycle status (printed, shipped, sold)
+    pub show_reseller_info: bool,
+}
+
+// Relative weight of each signal in the authenticity score; only the ratios matter
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuthenticityWeights {
+    pub signature: u32,
+    pub scan_velocity: u32,
+    pub geography: u32,
+    pub custody: u32,
+    pub reports: u32,
+}
+
+// When the canister's cycle balance counts as low, and where operators are alerted
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CyclesAlertConfig {
+    pub warning_threshold: u128,
+    pub critical_threshold: u128, // Below warning_threshold
+    pub webhook_url: Option<String>, // HTTPS; None records levels without alerting
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CyclesLevel {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CyclesSample {
+    pub timestamp: u64,
+    pub balance: u128,
+    pub level: CyclesLevel,
+    pub alerted: bool, // This check sent an alert
+}
+impl_storable_for_candid_type!(CyclesSample);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CyclesStatus {
+    pub balance: u128,
+    pub level: CyclesLevel,
+    pub warning_threshold: u128,
+    pub critical_threshold: u128,
+    pub alert_webhook_configured: bool,
+    pub last_checked_at: Option<u64>,
+    pub last_alert_at: Option<u64>,
+}
+
+// Oldest scanning app allowed to verify, and where outdated clients are sent to upgrade
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClientVersionPolicy {
+    pub min_version: String, // Dotted number, e.g. "2.4.0"
+    pub download_url: Option<String>,
+}
+
+impl Default for VerificationPrivacyPolicy {
+    fn default() -> Self {
+        VerificationPrivacyPolicy {
+            show_scan_count: true,
+            show_first_scan_date: true,
+            show_custody_chain: true,
+            show_reseller_info: true,
+        }
+    }
+}
+
+impl Default for OrgSettings {
+    fn default() -> Self {
+        OrgSettings {
+            org_id: Principal::anonymous(),
+            certification_validity_seconds: None,
+            certification_grace_period_seconds: None,
+            duplicate_scan_threshold: None,
+            redemption_approval_threshold: None,
+            single_use_reseller_codes: None,
+            verification_privacy: None,
+            client_version_policy: None,
+            authenticity_weights: None,
+            serial_batch_defaults: None,
+            public_stats_enabled: None,
+            updated_at: clock::now(),
+            updated_by: api::caller(),
+        }
+    }
+}
+
+// ====== Reports ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFrequency {
+    Weekly,
+    Monthly,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ReportSchedule {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub frequency: ReportFrequency,
+    pub delivery_url: String, // Email relay or webhook receiving the digest as JSON
+    pub recipient_emails: Vec<String>,
+    pub is_active: bool,
+    pub last_run_at: Option<u64>,
+    pub next_run_at: u64,
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(ReportSchedule);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ReportProductStat {
+    pub product_id: Principal,
+    pub product_name: String,
+    pub verification_count: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Report {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub schedule_id: Principal,
+    pub frequency: ReportFrequency,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub total_verifications: u64,
+    pub first_verifications: u64,
+    pub multiple_verifications: u64,
+    pub invalid_verifications: u64,
+    pub suspicious_serials: u64, // Serials verified by more than one distinct user within the period
+    pub top_products: Vec<ReportProductStat>,
+    pub generated_at: u64,
+    pub delivered_at: Option<u64>,
+    pub delivery_error: Option<String>,
+}
+impl_storable_for_candid_type!(Report);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ReportFilters {
+    pub product_ids: Option<Vec<Principal>>, // None covers every product of the organization
+    pub period_start: Option<u64>, // None: a month before the report is viewed
+    pub period_end: Option<u64>, // None: the moment the report is viewed
+}
+
+// Capability link letting someone without an account view a live, filtered report
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ReportShareLink {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub token_hash: String, // Hex SHA-256 of the token; the token itself is only returned at creation
+    pub filters: ReportFilters,
+    pub expires_at: u64,
+    pub revoked_at: Option<u64>,
+    pub created_at: u64,
+    pub created_by: Principal,
+}
+impl_storable_for_candid_type!(ReportShareLink);
+
+// ====== Print Jobs ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PrintJobEntry {
+    pub serial_no: Principal,
+    pub print_version: u8,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PrintJob {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub product_id: Principal,
+    pub entries: Vec<PrintJobEntry>, // Serials printed by this job with the print_version they received
+    pub printer_metadata: Vec<Metadata>,
+    pub label_template_version: Option<String>,
+    pub activation_time: Option<u64>, // Codes of the job verify from this time on; None for right away
+    pub created_at: u64,
+    pub created_by: Principal,
+}
+impl_storable_for_candid_type!(PrintJob);
+
+// One printing of a serial's label; each reprint gets the next print_version and a new code
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PrintRecord {
+    pub serial_no: Principal,
+    pub product_id: Principal,
+    pub print_version: u8,
+    pub print_job_id: Option<Principal>, // None when printed one at a time
+    pub printed_at: u64,
+    pub printed_by: Principal,
+}
+impl_storable_for_candid_type!(PrintRecord);
+
+// ====== Verification Delegation ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationDelegationStatus {
+    Active,
+    Revoked,
+}
+
+// A brand lets a (white-label) manufacturer sign codes for one of its products. Codes signed with
+// the manufacturer's key verify only while the delegation is active.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationDelegation {
+    pub product_id: Principal,
+    pub brand_org_id: Principal, // Owner of the product
+    pub manufacturer_org_id: Principal, // Signs codes for the product with its own key
+    pub status: VerificationDelegationStatus,
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub revoked_at: Option<u64>,
+    pub revoked_by: Option<Principal>,
+}
+impl_storable_for_candid_type!(VerificationDelegation);
+
+// ====== Label Templates ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QrErrorCorrection {
+    L, // ~7% of the code can be restored
+    M, // ~15%
+    Q, // ~25%
+    H, // ~30%
+}
+
+// Where the QR code sits on the label, in millimetres from the top-left corner
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct QrPlacement {
+    pub x_mm: f64,
+    pub y_mm: f64,
+    pub size_mm: f64,
+}
+
+// Print spec handed to print shops along with a product's codes
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct LabelTemplate {
+    pub product_id: Principal,
+    pub org_id: Principal,
+    pub width_mm: f64,
+    pub height_mm: f64,
+    pub qr_version: Option<u8>, // None lets the printer pick the smallest version that fits
+    pub qr_error_correction: QrErrorCorrection,
+    pub qr_placement: QrPlacement,
+    pub show_human_readable_code: bool, // Print the serial number next to the QR code
+    pub artwork_asset_url: Option<String>,
+    pub version: u32, // Incremented on every change; recorded on print jobs
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(LabelTemplate);
+
+// ====== Canister Configuration ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct LlmProviderConfig {
+    pub provider: String,
+    pub api_host: String,
+    pub model: String,
+    pub api_key: String, // Redacted by get_canister_config_v2
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RateLimitConfig {
+    pub max_attempts_per_window: u32,
+    pub window_duration_seconds: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub enabled: bool,
+}
+
+// Largest response accepted from each outcall integration, in bytes, headers included.
+// Also what the cycles attached to each outcall are budgeted for.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OutcallResponseLimits {
+    pub openai_max_response_bytes: u64,
+    pub scraper_max_response_bytes: u64,
+    pub webhook_max_response_bytes: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CanisterConfig {
+    pub llm: LlmProviderConfig,
+    pub scraper_url: String,
+    pub ledger_canister_id: Option<Principal>,
+    pub email_relay_url: Option<String>, // HTTPS endpoint that delivers the canister's transactional emails
+    pub rate_limit: RateLimitConfig,
+    pub feature_flags: Vec<FeatureFlag>, // View of the global flags, which are stored by the feature_flags module
+    pub client_version_policy: Option<ClientVersionPolicy>, // Platform-wide minimum client version
+    pub cycles_alerts: Option<CyclesAlertConfig>, // None uses the cycles_monitor defaults
+    pub outcall_limits: Option<OutcallResponseLimits>, // None uses the outcall_transforms defaults
+    pub moderation: Option<ModerationConfig>, // None checks nothing
+    pub live_gateways: Option<Vec<Principal>>, // WebSocket gateways allowed to relay live connections; None allows none
+    pub required_admin_approvals: Option<u32>, // None uses the approvals default; only changed by an approved admin operation
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(CanisterConfig);
+
+impl Default for CanisterConfig {
+    fn default() -> Self {
+        CanisterConfig {
+            llm: LlmProviderConfig {
+                provider: crate::config::DEFAULT_LLM_PROVIDER.to_string(),
+                api_host: crate::config::DEFAULT_LLM_API_HOST.to_string(),
+                model: crate::config::DEFAULT_LLM_MODEL.to_string(),
+                api_key: String::new(),
+            },
+            scraper_url: String::new(),
+            ledger_canister_id: None,
+            email_relay_url: None,
+            rate_limit: RateLimitConfig {
+                max_attempts_per_window: crate::config::DEFAULT_MAX_ATTEMPTS_PER_WINDOW,
+                window_duration_seconds: crate::config::DEFAULT_WINDOW_DURATION_SECONDS,
+            },
+            feature_flags: Vec::new(),
+            client_version_policy: None,
+            cycles_alerts: None,
+            outcall_limits: None,
+            moderation: None,
+            live_gateways: None,
+            required_admin_approvals: None,
+            updated_at: 0,
+            updated_by: Principal::anonymous(),
+        }
+    }
+}
+
+// ====== Organization Quotas ======
+
+// Admin-managed limits. Unset fields fall back to the platform defaults.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgQuota {
+    pub org_id: Principal,
+    pub max_outcalls_per_day: Option<u32>,
+    pub max_serials_per_month: Option<u32>,
+    pub max_storage_bytes: Option<u64>,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(OrgQuota);
+
+// Usage counters for the current day/month windows
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgUsage {
+    pub org_id: Principal,
+    pub outcalls_window_start: u64,
+    pub outcalls_in_window: u32,
+    pub oversized_responses_in_window: Option<u32>, // Outcalls rejected for exceeding max_response_bytes, same window
+    pub serials_window_start: u64,
+    pub serials_in_window: u32,
+}
+impl_storable_for_candid_type!(OrgUsage);
+
+// ====== Subscription Plans ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanTier {
+    Free,
+    Pro,
+    Enterprise,
+}
+
+// Active -> PastDue -> Active, and either -> Cancelled -> Active. A past-due plan keeps its
+// limits; a cancelled one is held to Free limits until reactivated.
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanStatus {
+    Active,
+    PastDue,
+    Cancelled,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgPlan {
+    pub org_id: Principal,
+    pub tier: PlanTier,
+    pub status: PlanStatus,
+    pub tier_changed_at: u64,
+    pub status_changed_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(OrgPlan);
+
+// ====== Verification Retention ======
+
+// What happens to raw verifications once they are rolled up into monthly aggregates
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetiredVerificationHandling {
+    Archive, // Moved to the archive store
+    Delete,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationRetentionPolicy {
+    pub org_id: Principal,
+    pub retention_months: Option<u32>, // None follows the plan's analytics retention
+    pub handling: RetiredVerificationHandling,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(VerificationRetentionPolicy);
+
+// Verifications of one product in one calendar month (UTC), test scans excluded
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationMonthlyAggregate {
+    pub org_id: Principal,
+    pub product_id: Principal,
+    pub month: u32, // YYYYMM
+    pub total_verifications: u64,
+    pub first_verifications: u64,
+    pub multiple_verifications: u64,
+    pub invalid_verifications: u64, // Invalid, voided-serial and revoked-code scans
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(VerificationMonthlyAggregate);
+
+// ====== Background Jobs ======
+
+// Work a job was submitted for, fixed at submission
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum JobSpec {
+    SerialImport {
+        product_id: Principal,
+        variant_id: Option<Principal>,
+        user_serial_prefix: Option<String>,
+        count: u32,
+    },
+    VerificationExport {
+        product_ids: Vec<Principal>,
+        period_start: u64,
+        period_end: u64,
+    },
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// Where the next step resumes
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct JobCursor {
+    pub position: u64, // Serials created by an import; index into product_ids for an export
+    pub seq: u64, // Next verification sequence number of the product being exported
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Job {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub spec: JobSpec,
+    pub status: JobStatus,
+    pub total_units: u64, // Serials to create, or products to export
+    pub processed_units: u64,
+    pub cursor: JobCursor,
+    pub result_columns: Vec<String>, // Header of the comma-separated result rows
+    pub result_chunks: u32,
+    pub result_rows: u64,
+    pub error: Option<String>, // Why the job failed
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(Job);
+
+// Result rows written by one step of a job
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct JobResultChunk {
+    pub rows: Vec<String>,
+}
+impl_storable_for_candid_type!(JobResultChunk);
+
+// ====== Verification Domains ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationDomainStatus {
+    Pending,  // Waiting for the DNS TXT record to be found
+    Verified,
+    Failed,   // The last check did not find the record
+}
+
+// Domain an organization serves verification pages on. QR payloads use it once the TXT proof checks out.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationDomain {
+    pub org_id: Principal,
+    pub domain: String, // Lowercase, without scheme or trailing dot
+    pub proof_token: String, // Expected in a TXT record on the proof record name
+    pub status: VerificationDomainStatus,
+    pub last_checked_at: Option<u64>,
+    pub last_error: Option<String>, // Why the last check failed
+    pub verified_at: Option<u64>,
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(VerificationDomain);
+
+// ====== Capability Tokens ======
+
+// What a capability token lets its holder do, always on the products the token names
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapabilityOperation {
+    CreateSerials,
+    PrintSerials,
+    UpdateSerialStatus, // Mark serials shipped or sold; voiding stays with the organization
+}
+
+// The signed part of a capability token
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CapabilityClaims {
+    pub token_id: Principal,
+    pub org_id: Principal,
+    pub holder: Principal, // Only calls from this principal can use the token
+    pub operations: Vec<CapabilityOperation>,
+    pub product_ids: Vec<Principal>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CapabilityToken {
+    pub claims: CapabilityClaims,
+    pub issued_by: Principal,
+    pub revoked_at: Option<u64>,
+    pub revoked_by: Option<Principal>,
+    pub use_count: u64,
+    pub last_used_at: Option<u64>,
+}
+impl_storable_for_candid_type!(CapabilityToken);
+
+// ====== Owned Products ======
+
+// A serial a consumer added to their product shelf. A serial is on at most one shelf.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OwnedProduct {
+    pub serial_no: Principal,
+    pub user_id: Principal,
+    pub product_id: Principal,
+    pub registered_at: u64, // Also when the warranty starts
+}
+impl_storable_for_candid_type!(OwnedProduct);
+
+// ====== Recall Campaigns ======
+
+// Which serials of the product a recall covers
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RecallScope {
+    Product, // Every serial; also sets the product's recall
+    PrintJob { print_job_id: Principal }, // The serials one print job produced, i.e. a batch
+    Serials { serial_nos: Vec<Principal> },
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecallCampaignStatus {
+    Sending, // Owners are still being notified
+    Active,
+    Closed,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RecallCampaign {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub product_id: Principal,
+    pub scope: RecallScope,
+    pub recall: ProductRecall,
+    pub status: RecallCampaignStatus,
+    pub affected_owners: u64, // Users with a covered serial on their shelf when the campaign started
+    pub notices_sent: u64,
+    pub created_at: u64,
+    pub closed_at: Option<u64>,
+    pub closed_by: Option<Principal>,
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(RecallCampaign);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecallRelayStatus {
+    Skipped, // No email on file, no relay configured, or the outcall quota was used up
+    Queued,
+    Delivered,
+    Failed, // Dead-lettered by the outbox
+}
+
+// What one owner was sent for a campaign
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RecallNotice {
+    pub campaign_id: Principal,
+    pub user_id: Principal,
+    pub serial_nos: Vec<Principal>, // The owner's covered serials
+    pub notification_id: Option<Principal>, // Inbox entry, once sent
+    pub relay_delivery_id: Option<u64>, // Outbox entry of the email relay message
+    pub relay_queued_at: Option<u64>, // Tells the entry apart from a later one reusing its ID
+    pub relay_status: Option<RecallRelayStatus>, // None until the notice is sent
+    pub sent_at: Option<u64>,
+}
+impl_storable_for_candid_type!(RecallNotice);
+
+// ====== Status Messages ======
+
+// Admin-edited text shown for a verification status in one locale
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct StatusMessage {
+    pub status: ProductVerificationStatus,
+    pub locale: String, // e.g. "en", "pt-BR"
+    pub text: String,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(StatusMessage);
+
+// ====== Redemption Approvals ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedemptionApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+// Reward redemption above the organization's approval threshold, held until a brand owner or admin decides
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PendingRedemption {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub product_id: Principal,
+    pub verification_id: Principal,
+    pub serial_no: Principal,
+    pub user_id: Principal,
+    pub wallet_address: String,
+    pub points: u32,
+    pub status: RedemptionApprovalStatus,
+    pub requested_at: u64,
+    pub decided_at: Option<u64>,
+    pub decided_by: Option<Principal>,
+    pub rejection_reason: Option<String>,
+    pub transaction_id: Option<String>,
+}
+impl_storable_for_candid_type!(PendingRedemption);
+
+// Gift card or coupon code a brand stocked for consumers without a wallet. Redeemed once, in
+// exchange for a verification's reward.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CouponCode {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub product_id: Option<Principal>, // None: usable for any of the organization's products
+    pub code: String,
+    pub description: Option<String>, // Shown to the consumer, e.g. "10% off your next order"
+    pub uploaded_at: u64,
+    pub uploaded_by: Principal,
+    pub redeemed_by: Option<Principal>,
+    pub redeemed_at: Option<u64>,
+    pub verification_id: Option<Principal>, // Verification whose reward was exchanged for this code
+}
+impl_storable_for_candid_type!(CouponCode);
+
+// ====== Key Escrow ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyEscrowStatus {
+    PendingApproval,
+    Approved, // Collectable between confirmable_at and expires_at
+    Exported,
+    Cancelled,
+}
+
+// A brand's request to take its signing key off the platform, encrypted to a key it supplies
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct KeyEscrowRequest {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub recipient_public_key: String, // Hex SEC1 secp256k1 key the export is encrypted to
+    pub reason: Option<String>,
+    pub status: KeyEscrowStatus,
+    pub requested_by: Principal,
+    pub requested_at: u64,
+    pub approved_by: Option<Principal>,
+    pub approved_at: Option<u64>,
+    pub confirmable_at: Option<u64>,
+    pub expires_at: Option<u64>,
+    pub exported_at: Option<u64>,
+    pub cancelled_by: Option<Principal>,
+    pub cancelled_at: Option<u64>,
+}
+impl_storable_for_candid_type!(KeyEscrowRequest);
+
+// ====== Read-only Principals ======
+
+// Service identity of a BI tool, limited to read queries of one organization. A rotated principal
+// is revoked and points at its replacement.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ReadonlyPrincipal {
+    pub principal: Principal,
+    pub org_id: Principal,
+    pub label: Option<String>,
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub revoked_at: Option<u64>,
+    pub revoked_by: Option<Principal>,
+    pub replaced_by: Option<Principal>, // Set when revoked by a rotation
+}
+impl_storable_for_candid_type!(ReadonlyPrincipal);
+
+// ====== Terms and Consent ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct TermsVersion {
+    pub seq: u64, // Publication order
+    pub version: String, // e.g. "2024-06"
+    pub url: String, // Where the full text is published
+    pub published_at: u64,
+    pub published_by: Principal,
+}
+impl_storable_for_candid_type!(TermsVersion);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct TermsAcceptance {
+    pub user_id: Principal,
+    pub terms_seq: u64,
+    pub version: String,
+    pub accepted_at: u64,
+}
+impl_storable_for_candid_type!(TermsAcceptance);
+
+// ====== Webhook Outbox ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutboxDeliveryStatus {
+    Pending,
+    DeadLettered, // Gave up after the maximum number of attempts; an admin can requeue it
+}
+
+// An outgoing webhook, kept in stable memory until it is delivered
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OutboxDelivery {
+    pub id: u64,
+    pub org_id: Option<Principal>, // Organization the delivery is sent for; None for platform deliveries
+    pub url: String,
+    pub body: String,
+    pub status: OutboxDeliveryStatus,
+    pub attempts: u32, // Failed attempts so far
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(OutboxDelivery);
+
+// ====== User Notifications ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserNotificationKind {
+    RewardGranted,
+    RedemptionPending,
+    RedemptionApproved,
+    RedemptionRejected,
+    CertificationIssued,
+    CertificationRenewed,
+    DisputeUpdated,
+    RewardsPenalized,
+    RewardPoolLow,
+    ResellerApplicationRejected,
+    KeyEscrowReady, // An approved key export can be collected
+    ProductRecalled, // A product on the user's shelf was recalled
+}
+
+// Inbox entry written by the canister for a single user
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct UserNotification {
+    pub id: Principal,
+    pub user_id: Principal,
+    pub kind: UserNotificationKind,
+    pub title: String,
+    pub message: String,
+    pub reference_id: Option<Principal>, // Verification, redemption or reseller the notification is about
+    pub created_at: u64,
+    pub read_at: Option<u64>,
+}
+impl_storable_for_candid_type!(UserNotification);
+
+// ====== Disputes ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisputeStatus {
+    Open,
+    InReview,
+    Resolved,
+}
+
+impl DisputeStatus {
+    // Open -> InReview -> Resolved; an open dispute can also be resolved directly. Resolved is terminal.
+    pub fn can_transition_to(&self, next: DisputeStatus) -> bool {
+        matches!(
+            (self, next),
+            (DisputeStatus::Open, DisputeStatus::InReview)
+                | (DisputeStatus::Open, DisputeStatus::Resolved)
+                | (DisputeStatus::InReview, DisputeStatus::Resolved)
+        )
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisputeParty {
+    Consumer,
+    Brand,
+    Admin,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct DisputeMessage {
+    pub author: Principal,
+    pub party: DisputeParty,
+    pub body: String,
+    pub created_at: u64,
+}
+
+// Consumer ticket contesting a verification result, e.g. a genuine product that showed Invalid
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Dispute {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub verification_id: Option<Principal>, // None for failed attempts, which are not recorded as verifications
+    pub opened_by: Principal,
+    pub subject: String,
+    pub status: DisputeStatus,
+    pub messages: Vec<DisputeMessage>,
+    pub resolution: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub resolved_at: Option<u64>,
+    pub resolved_by: Option<Principal>,
+}
+impl_storable_for_candid_type!(Dispute);
+
+// ====== Product Variants ======
+
+// Size/color style SKU under a parent product. Variant serials live in the parent's serial pool,
+// tagged with the variant id, so product-level analytics include them.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ProductVariant {
+    pub id: Principal,
+    pub product_id: Principal,
+    pub org_id: Principal,
+    pub name: String,
+    pub sku: Option<String>,
+    pub attributes: Vec<Metadata>, // e.g. size=XL, color=Red
+    pub created_at: u64,
+    pub created_by: Principal,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(ProductVariant);
+
+// ====== Product Bundles ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BundleMember {
+    pub product_id: Principal,
+    pub serial_no: Principal,
+}
+
+// Multipack whose outer code attests to the units packed inside it
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Bundle {
+    pub outer_product_id: Principal,
+    pub outer_serial_no: Principal,
+    pub org_id: Principal,
+    pub members: Vec<BundleMember>,
+    pub created_at: u64,
+    pub created_by: Principal,
+}
+impl_storable_for_candid_type!(Bundle);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BundleUnit {
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub user_serial_no: Option<String>,
+    pub valid: bool, // Neither voided nor revoked
+    pub voided: bool,
+    pub revoked: bool, // The printed version on the unit was revoked
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BundleSummary {
+    pub outer_serial_no: Principal,
+    pub total_units: u32,
+    pub valid_units: u32,
+    pub units: Vec<BundleUnit>,
+}
+
+// ====== Metadata Schemas ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataValueType {
+    Text,
+    Integer,
+    Decimal,
+    Boolean, // "true" or "false"
+    Url, // http(s)
+    Date, // YYYY-MM-DD
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MetadataFieldSchema {
+    pub key: String,
+    pub value_type: MetadataValueType,
+    pub required: bool,
+    pub allowed_values: Option<Vec<String>>, // None accepts any value of the type
+}
+
+// Metadata rules for one product category of an organization. Keys not listed are accepted as-is.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MetadataSchema {
+    pub org_id: Principal,
+    pub category: String, // As given; matching is on metadata_schemas::category_key
+    pub product_fields: Vec<MetadataFieldSchema>,
+    pub serial_fields: Vec<MetadataFieldSchema>,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(MetadataSchema);
+
+// ====== Product Reward Stats ======
+
+// Running reward totals for a product. Counting starts when tracking was introduced;
+// earlier issuance is not reflected.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ProductRewardStats {
+    pub product_id: Principal,
+    pub points_issued: u64,
+    pub points_redeemed: u64,
+    pub promotion_points_issued: u64, // Portion of points_issued paid for by promotions
+    pub unique_rewarded_users: u64,
+    pub redemption_count: u64,
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(ProductRewardStats);
+
+impl Default for ProductRewardStats {
+    fn default() -> Self {
+        ProductRewardStats {
+            product_id: Principal::anonymous(),
+            points_issued: 0,
+            points_redeemed: 0,
+            promotion_points_issued: 0,
+            unique_rewarded_users: 0,
+            redemption_count: 0,
+            updated_at: 0,
+        }
+    }
+}
+
+// ====== Serial Probing ======
+
+// Serial lookups of one principal in the current window
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ProbeRecord {
+    pub principal: Principal,
+    pub window_start: u64,
+    pub probes: u32,
+    pub misses: u32, // Lookups of serials that do not exist
+    pub banned_until: Option<u64>,
+    pub ban_count: u32, // Bans so far; each one doubles the next
+}
+impl_storable_for_candid_type!(ProbeRecord);
+
+// Lookups of a brand's serials by principals that mostly look up serials that do not exist
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgProbeActivity {
+    pub org_id: Principal,
+    pub suspicious_hits: u64,
+    pub recent_probers: Vec<Principal>, // Most recent first
+    pub first_seen_at: u64,
+    pub last_seen_at: u64,
+}
+impl_storable_for_candid_type!(OrgProbeActivity);
+
+// ====== Organization Analytics ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct DailyVerificationCount {
+    pub day: u64, // Days since the Unix epoch
+    pub count: u64,
+}
+
+// Counters maintained at write time so analytics do not need to scan every verification
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct OrgAnalyticsCounters {
+    pub org_id: Principal,
+    pub total_products: u64,
+    pub active_resellers: u64,
+    pub daily_verifications: Vec<DailyVerificationCount>, // Ring buffer indexed by day modulo the window
+    pub grey_market_verifications: Option<u64>, // All-time scans outside a product's allowed markets
+    pub reseller_product_checks: Option<u64>, // Reseller verifications that named a product
+    pub unauthorized_reseller_product_checks: Option<u64>, // ...of which the reseller was not authorized for it
+    pub revoked_codes: Option<u64>, // Printed codes revoked by the brand
+    pub revoked_code_scans: Option<u64>, // Verification attempts on revoked codes
+    pub registered_owners: Option<u64>, // Serials consumers have added to their product shelf
+    pub last_refreshed: u64,
+}
+impl_storable_for_candid_type!(OrgAnalyticsCounters);
+
+// Verification counts of an organization for one UTC day, each indexed by hour
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationHeatmapDay {
+    pub all: Vec<u64>, // Genuine verifications
+    pub first: Vec<u64>, // ...of which first verifications
+    pub invalid: Vec<u64>, // Scans whose code did not verify
+}
+impl_storable_for_candid_type!(VerificationHeatmapDay);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum VerificationHeatmapKind {
+    All,
+    FirstOnly,
+    InvalidOnly,
+}
+
+// ====== Public Brand Directory ======
+
+// Canister-signed attestation that an organization is a listed brand. The signature covers
+// SHA-256("verified-brand_{org_id}_{issued_at}") and verifies against public_key.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerifiedBrandBadge {
+    pub org_id: Principal,
+    pub issued_at: u64,
+    pub signature: String, // Hex
+    pub public_key: String, // Hex, uncompressed SEC1
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct DirectoryListing {
+    pub org_id: Principal,
+    pub is_public: bool, // Brand opt-in
+    pub featured: bool, // Set by admins
+    pub suspended: bool, // Set by admins; hides the listing regardless of is_public
+    pub suspension_reason: Option<String>,
+    pub logo_url: Option<String>,
+    pub website_url: Option<String>,
+    pub tagline: Option<String>,
+    pub badge: Option<VerifiedBrandBadge>, // Issued the first time the brand opts in
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(DirectoryListing);
+
+impl Default for DirectoryListing {
+    fn default() -> Self {
+        DirectoryListing {
+            org_id: Principal::anonymous(),
+            is_public: false,
+            featured: false,
+            suspended: false,
+            suspension_reason: None,
+            logo_url: None,
+            website_url: None,
+            tagline: None,
+            badge: None,
+            updated_at: 0,
+            updated_by: Principal::anonymous(),
+        }
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct GuestVerificationRef {
+    pub verification_id: Principal,
+    pub product_id: Principal,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct GuestSession {
+    pub id: Principal, // Stands in for the anonymous caller as created_by and rate-limit key
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub verifications: Vec<GuestVerificationRef>,
+    pub claimed_by: Option<Principal>,
+    pub claimed_at: Option<u64>,
+}
+impl_storable_for_candid_type!(GuestSession);
+
+// One-time code a user issues so another of their identities can be merged into the account
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct IdentityLinkCode {
+    pub user_id: Principal, // The primary account
+    pub code_hash: String, // SHA-256 of the code, hex
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+impl_storable_for_candid_type!(IdentityLinkCode);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PostVerificationMessage {
+    pub org_id: Principal,
+    pub product_id: Option<Principal>, // None for the organization-wide default
+    pub template: String,              // May use {product_name}, {brand_name}, {points} and {total_points}
+    pub cta_label: Option<String>,
+    pub cta_url: Option<String>,
+    pub is_active: bool,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(PostVerificationMessage);
+
+// Entity stores covered by the integrity tooling
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IntegrityStore {
+    Organizations,
+    Products,
+    Users,
+    Resellers,
+    ProductSerialNumbers,
+    ProductVerifications,
+    VerificationRecords, // Keyed by (product_id, seq)
+    SerialRecords, // Keyed by (product_id, serial_no)
+}
+
+// Second part of the key of an entry in a store with composite keys
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IntegritySubKey {
+    Seq(u64),
+    SerialNo(Principal),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct QuarantinedEntry {
+    pub store: IntegrityStore,
+    pub key: Principal,
+    pub sub_key: Option<IntegritySubKey>, // Set for stores with composite keys
+    pub bytes: Vec<u8>, // Raw value as it was found in the store
+    pub decode_error: String,
+    pub quarantined_at: u64,
+    pub quarantined_by: Principal,
+}
+impl_storable_for_candid_type!(QuarantinedEntry);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct LogEntry {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub level: LogLevel,
+    pub module: String,
+    pub message: String,
+}
+impl_storable_for_candid_type!(LogEntry);
+
+// ====== Circuit Breakers ======
+
+// External integrations guarded by a circuit breaker
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExternalService {
+    OpenAi,
+    Scraper,
+    DnsResolver,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed, // Calls go through
+    Open, // Calls fail fast until the cooldown ends
+    HalfOpen, // One probe call goes through; its outcome closes or reopens the breaker
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CircuitBreaker {
+    pub service: ExternalService,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub consecutive_opens: u32, // Times reopened without a success in between; doubles the cooldown
+    pub opened_at: Option<u64>,
+    pub half_open_at: Option<u64>, // When an open breaker lets a probe through
+    pub probe_started_at: Option<u64>, // Set while the half-open probe is in flight
+    pub last_failure: Option<String>,
+    pub last_failure_at: Option<u64>,
+    pub last_success_at: Option<u64>,
+    pub total_short_circuited: u64, // Calls refused while open
+}
+impl_storable_for_candid_type!(CircuitBreaker);
+
+// ====== Content Moderation ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModerationConfig {
+    pub blocklist: Vec<String>, // Words or phrases, matched case-insensitively on whole words
+    pub llm_moderation: bool, // Also send new and changed text to the LLM provider's moderation endpoint
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModeratedEntityKind {
+    Product,
+    Organization,
+    Reseller,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModerationSource {
+    Blocklist,
+    Llm,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModerationStatus {
+    Flagged, // Quarantined until an admin reviews it
+    Approved, // Shown again; the same text is not flagged again
+    Rejected, // Stays hidden until the owner changes the text
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ModeratedField {
+    pub field: String,
+    pub text: String,
+}
+
+// Flagged public text of a product, organization or reseller. Keyed by the record's ID; changing the
+// text clears the case and checks the new text.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ModerationCase {
+    pub entity_id: Principal,
+    pub entity_kind: ModeratedEntityKind,
+    pub org_id: Principal,
+    pub fields: Vec<ModeratedField>, // The text as it was when flagged
+    pub source: ModerationSource,
+    pub matched: Vec<String>, // Blocklist terms or moderation categories that flagged the text
+    pub status: ModerationStatus,
+    pub flagged_at: u64,
+    pub submitted_by: Principal,
+    pub reviewed_at: Option<u64>,
+    pub reviewed_by: Option<Principal>,
+    pub review_note: Option<String>,
+}
+impl_storable_for_candid_type!(ModerationCase);
+
+// ====== Public Brand Stats ======
+
+// Aggregate numbers a brand may show on its own site; nothing in them points at a consumer or a serial
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PublicBrandStats {
+    pub total_verifications: u64,
+    pub distinct_products: u64, // Products verified at least once
+    pub countries_reached: u64, // Countries verifications were reported from
+    pub version: u64, // Changes whenever one of the numbers does
+    pub published_at: u64,
+}
+
+// Running tallies of an opted-in organization, and the stats last published from them
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct PublicStatsTally {
+    pub org_id: Principal,
+    pub total_verifications: u64,
+    pub country_codes: Vec<String>, // Sorted, distinct
+    pub dirty: bool, // Tallies moved since the last publish
+    pub published: Option<PublicBrandStats>,
+}
+impl_storable_for_candid_type!(PublicStatsTally);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorCodeCount {
+    pub code: String, // ApiError variant, e.g. "NotFound"
+    pub count: u64,
+}
+
+// Counters of one update endpoint since the metrics were last reset
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct EndpointMetrics {
+    pub endpoint: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_codes: Vec<ErrorCodeCount>,
+    pub total_instructions: u128,
+    pub max_instructions: u64,
+    pub recent_instructions: Vec<u64>, // Ring buffer of the latest calls, for percentiles
+    pub next_sample: u32, // Slot the next call overwrites once the buffer is full
+    pub since: u64,
+    pub last_called_at: u64,
+}
+impl_storable_for_candid_type!(EndpointMetrics);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BiExportConfig {
+    pub org_id: Principal,
+    pub endpoint_url: String, // HTTPS ingestion URL of the organization's data warehouse
+    pub is_active: bool,
+    pub next_push_at: u64,
+    pub last_period_end: Option<u64>, // End of the last exported period; the next export starts here
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub updated_by: Principal,
+}
+impl_storable_for_candid_type!(BiExportConfig);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BiExportDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BiExportDelivery {
+    pub id: Principal,
+    pub org_id: Principal,
+    pub schema_version: u32,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub status: BiExportDeliveryStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub payload_bytes: u64,
+    pub created_at: u64,
+    pub delivered_at: Option<u64>,
+}
+impl_storable_for_candid_type!(BiExportDelivery);
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationChallengeStatus {
+    Pending,
+    Verified,
+    InvalidResponse,
+    VoidedSerial,
+    CodeRevoked,
+    NotYetActive,
+    Expired,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct VerificationChallenge {
+    pub id: Principal,
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub nonce: String, // Hex; the tag signs "{product_id}_{serial_no}_{nonce}" with the product key
+    pub requested_by: Principal, // Caller or guest session id; only they can complete the challenge
+    pub status: VerificationChallengeStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub completed_at: Option<u64>,
+}
+impl_storable_for_candid_type!(VerificationChallenge);
+
+// Audit record of points clawed back from a user for fraud or reward farming
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RewardPenalty {
+    pub id: Principal,
+    pub user_id: Principal,
+    pub org_id: Option<Principal>, // Brand that imposed it; None for admin penalties outside any organization
+    pub points_requested: u32,
+    pub points_deducted: u32, // Less than requested when the balance was lower
+    pub balance_after: u32,
+    pub reason: String,
+    pub penalized_by: Principal,
+    pub created_at: u64,
+}
+impl_storable_for_candid_type!(RewardPenalty);
+
+// ====== Wallet Address Book ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalletChain {
+    InternetComputer, // Principal text or 64-character hex account identifier
+    Ethereum,
+    Bitcoin,
+    Solana,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SavedWallet {
+    pub id: Principal,
+    pub label: String,
+    pub chain: WalletChain,
+    pub address: String,
+    pub is_default: bool,
+    pub created_at: u64,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WalletBook {
+    pub wallets: Vec<SavedWallet>,
+}
+impl_storable_for_candid_type!(WalletBook);
+
+// ====== Reward Pools ======
+
+// Tokens an organization has escrowed with the canister to back its reward points.
+// Amounts are in the ledger's base units.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RewardPool {
+    pub org_id: Principal,
+    pub balance: u64, // Available for payouts
+    pub ledger_balance_seen: u64, // Deposit subaccount balance at the last sync; new deposits are the difference
+    pub total_deposited: u64,
+    pub total_paid_out: u64,
+    pub units_per_point: u64,
+    pub low_balance_threshold: u64, // Brand owners are alerted once the balance falls below this
+    pub low_balance_alerted: bool, // Cleared when a deposit lifts the balance back over the threshold
+    pub updated_at: u64,
+}
+impl_storable_for_candid_type!(RewardPool);
+
+// ====== ID Registry ======
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdEntityKind {
+    Organization,
+    Product,
+    SerialNumber,
+    Reseller,
+}
+
+// Record of an ID handed out by id_registry::allocate_id (or registered by the upgrade backfill)
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct IdAllocation {
+    pub entity: IdEntityKind,
+    pub created_at: u64,
+}
+impl_storable_for_candid_type!(IdAllocation);
+
+// ====== Admin Approvals ======
+
+// Destructive admin operations. Each runs only once enough admins have approved it.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AdminOperationKind {
+    ResetStorage,
+    ApproveKeyExport { request_id: Principal }, // Approves a pending key escrow export request
+    DeleteOrganization { org_id: Principal },
+    SetRequiredApprovals { required: u32 }, // How many admins must approve an operation, the proposer included
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminOperationStatus {
+    Pending,
+    Executed,
+    Failed, // Reached its approvals but could not run; see result
+    Rejected,
+    Expired, // Not approved within the window
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AdminApproval {
+    pub admin_id: Principal, // User ID rather than caller, so an admin's session keys count once
+    pub approved_at: u64,
+    pub note: Option<String>,
+}
+
+// A destructive operation waiting for approvals. Proposing it counts as the first approval.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct AdminOperation {
+    pub id: Principal,
+    pub kind: AdminOperationKind,
+    pub reason: Option<String>,
+    pub status: AdminOperationStatus,
+    pub required_approvals: u32,
+    pub approvals: Vec<AdminApproval>,
+    pub proposed_by: Principal,
+    pub proposed_at: u64,
+    pub expires_at: u64,
+    pub rejected_by: Option<Principal>,
+    pub rejected_at: Option<u64>,
+    pub rejection_note: Option<String>,
+    pub executed_at: Option<u64>,
+    pub result: Option<String>, // What the operation did, or why it failed
+}
+impl_storable_for_candid_type!(AdminOperation);
@@ -0,0 +1,353 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod,
+};
+use ic_cdk_timers::set_timer;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::circuit_breakers;
+use crate::clock;
+use crate::config;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, ORGANIZATIONS, PRODUCTS, RESELLERS};
+use crate::models::{
+    ExternalService, ModeratedEntityKind, ModeratedField, ModerationCase, ModerationConfig, ModerationSource,
+    ModerationStatus, Organization, Product, Reseller,
+};
+use crate::outcall_transforms::{self, OutcallKind};
+use crate::quotas;
+
+pub const MAX_BLOCKLIST_TERMS: usize = 1000;
+pub const MAX_BLOCKLIST_TERM_LENGTH: usize = 100;
+
+// Define a unique MemoryId for this structure
+const MODERATION_CASES_MEM_ID: MemoryId = MemoryId::new(88);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by the ID of the product, organization or reseller; at most one case per record
+    static MODERATION_CASES: RefCell<StableBTreeMap<Principal, ModerationCase, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MODERATION_CASES_MEM_ID))
+        )
+    );
+}
+
+pub fn get_case(entity_id: Principal) -> Option<ModerationCase> {
+    MODERATION_CASES.with(|cases| cases.borrow().get(&entity_id))
+}
+
+pub fn save_case(case: ModerationCase) {
+    MODERATION_CASES.with(|cases| {
+        cases.borrow_mut().insert(case.entity_id, case);
+    });
+}
+
+fn remove_case(entity_id: Principal) -> Option<ModerationCase> {
+    MODERATION_CASES.with(|cases| cases.borrow_mut().remove(&entity_id))
+}
+
+// Cases with the given status, or all of them, oldest first
+pub fn list_cases(status: Option<ModerationStatus>) -> Vec<ModerationCase> {
+    let mut cases: Vec<ModerationCase> = MODERATION_CASES.with(|cases| {
+        cases
+            .borrow()
+            .iter()
+            .map(|(_, case)| case)
+            .filter(|case| status.is_none_or(|s| case.status == s))
+            .collect()
+    });
+    cases.sort_by_key(|case| case.flagged_at);
+    cases
+}
+
+// Whether the record's public text is withheld: flagged and not yet reviewed, or rejected
+pub fn is_quarantined(entity_id: Principal) -> bool {
+    get_case(entity_id).is_some_and(|case| case.status != ModerationStatus::Approved)
+}
+
+// Lowercase words separated by single spaces, padded so terms can be matched on whole words
+fn normalize(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+// Trimmed, lowercased and deduplicated terms, for storing in the config
+pub fn normalize_config(mut moderation: ModerationConfig) -> Result<ModerationConfig, ApiError> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in &moderation.blocklist {
+        let term = normalize(term).trim().to_string();
+        if term.len() > MAX_BLOCKLIST_TERM_LENGTH {
+            return Err(ApiError::invalid_input(&format!(
+                "Blocklist terms must be at most {} characters",
+                MAX_BLOCKLIST_TERM_LENGTH
+            )));
+        }
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    if terms.len() > MAX_BLOCKLIST_TERMS {
+        return Err(ApiError::invalid_input(&format!("The blocklist can hold at most {} terms", MAX_BLOCKLIST_TERMS)));
+    }
+    moderation.blocklist = terms;
+    Ok(moderation)
+}
+
+fn blocklist_matches(blocklist: &[String], fields: &[ModeratedField]) -> Vec<String> {
+    let texts: Vec<String> = fields.iter().map(|f| normalize(&f.text)).collect();
+    blocklist
+        .iter()
+        .filter(|term| {
+            let padded = format!(" {} ", term);
+            texts.iter().any(|text| text.contains(&padded))
+        })
+        .cloned()
+        .collect()
+}
+
+fn product_fields(product: &Product) -> Vec<ModeratedField> {
+    vec![
+        ModeratedField { field: "name".to_string(), text: product.name.clone() },
+        ModeratedField { field: "description".to_string(), text: product.description.clone() },
+    ]
+}
+
+fn organization_fields(organization: &Organization) -> Vec<ModeratedField> {
+    vec![
+        ModeratedField { field: "name".to_string(), text: organization.name.clone() },
+        ModeratedField { field: "description".to_string(), text: organization.description.clone() },
+    ]
+}
+
+fn reseller_fields(reseller: &Reseller) -> Vec<ModeratedField> {
+    vec![ModeratedField { field: "name".to_string(), text: reseller.name.clone() }]
+}
+
+// Public text of the record as it is stored now
+fn current_fields(kind: ModeratedEntityKind, entity_id: Principal) -> Option<Vec<ModeratedField>> {
+    match kind {
+        ModeratedEntityKind::Product => PRODUCTS.with(|p| p.borrow().get(&entity_id)).map(|p| product_fields(&p)),
+        ModeratedEntityKind::Organization => ORGANIZATIONS.with(|o| o.borrow().get(&entity_id)).map(|o| organization_fields(&o)),
+        ModeratedEntityKind::Reseller => RESELLERS.with(|r| r.borrow().get(&entity_id)).map(|r| reseller_fields(&r)),
+    }
+}
+
+pub fn on_product_saved(product: &Product, submitted_by: Principal) -> Option<ModerationCase> {
+    moderate(ModeratedEntityKind::Product, product.id, product.org_id, product_fields(product), submitted_by)
+}
+
+pub fn on_organization_saved(organization: &Organization, submitted_by: Principal) -> Option<ModerationCase> {
+    moderate(ModeratedEntityKind::Organization, organization.id, organization.id, organization_fields(organization), submitted_by)
+}
+
+pub fn on_reseller_saved(reseller: &Reseller, submitted_by: Principal) -> Option<ModerationCase> {
+    moderate(ModeratedEntityKind::Reseller, reseller.id, reseller.org_id, reseller_fields(reseller), submitted_by)
+}
+
+// Check text that was just written. A blocklist match quarantines the record right away and is returned;
+// the LLM check, when enabled, runs after the call and may quarantine it later.
+fn moderate(
+    kind: ModeratedEntityKind,
+    entity_id: Principal,
+    org_id: Principal,
+    fields: Vec<ModeratedField>,
+    submitted_by: Principal,
+) -> Option<ModerationCase> {
+    let existing = get_case(entity_id);
+    if existing.as_ref().is_some_and(|case| case.fields == fields) {
+        // Unchanged text keeps its review, whichever way it went
+        return None;
+    }
+    // Changed text no longer carries what got the record flagged
+    if existing.is_some() {
+        remove_case(entity_id);
+    }
+
+    let moderation = config::moderation_config();
+    let matched = blocklist_matches(&moderation.blocklist, &fields);
+    if !matched.is_empty() {
+        return Some(flag(kind, entity_id, org_id, fields, ModerationSource::Blocklist, matched, submitted_by));
+    }
+    if moderation.llm_moderation {
+        set_timer(Duration::ZERO, move || {
+            ic_cdk::spawn(check_with_llm(kind, entity_id, org_id, fields, submitted_by))
+        });
+    }
+    None
+}
+
+fn flag(
+    kind: ModeratedEntityKind,
+    entity_id: Principal,
+    org_id: Principal,
+    fields: Vec<ModeratedField>,
+    source: ModerationSource,
+    matched: Vec<String>,
+    submitted_by: Principal,
+) -> ModerationCase {
+    let case = ModerationCase {
+        entity_id,
+        entity_kind: kind,
+        org_id,
+        fields,
+        source,
+        matched,
+        status: ModerationStatus::Flagged,
+        flagged_at: clock::now(),
+        submitted_by,
+        reviewed_at: None,
+        reviewed_by: None,
+        review_note: None,
+    };
+    log_warn!("[moderation] {:?} {} quarantined by {:?}: {:?}", kind, entity_id, source, case.matched);
+    save_case(case.clone());
+    case
+}
+
+// Outcome of an admin review
+pub fn review(entity_id: Principal, approve: bool, note: Option<String>, reviewer: Principal) -> Result<ModerationCase, ApiError> {
+    let mut case = get_case(entity_id).ok_or_else(|| ApiError::not_found("No moderation case for this record"))?;
+    case.status = if approve { ModerationStatus::Approved } else { ModerationStatus::Rejected };
+    case.reviewed_at = Some(clock::now());
+    case.reviewed_by = Some(reviewer);
+    case.review_note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    save_case(case.clone());
+    Ok(case)
+}
+
+// Optional second pass through the LLM provider. It fails open: when the service, the quota or the
+// breaker is in the way, the text stays up with only the blocklist check behind it.
+async fn check_with_llm(
+    kind: ModeratedEntityKind,
+    entity_id: Principal,
+    org_id: Principal,
+    fields: Vec<ModeratedField>,
+    submitted_by: Principal,
+) {
+    if let Err(e) = circuit_breakers::check(ExternalService::OpenAi).and_then(|_| quotas::consume_outcalls(org_id, 1)) {
+        log_warn!("[moderation] LLM check of {:?} {} skipped: {:?}", kind, entity_id, e);
+        return;
+    }
+    let text = fields
+        .iter()
+        .map(|f| f.text.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let categories = match request_llm_moderation(org_id, &text).await {
+        Ok(categories) => categories,
+        Err(e) => {
+            log_warn!("[moderation] LLM check of {:?} {} failed: {:?}", kind, entity_id, e);
+            return;
+        }
+    };
+    if categories.is_empty() {
+        return;
+    }
+    // The text may have changed, or been flagged and reviewed, while the outcall was in flight
+    if current_fields(kind, entity_id).as_ref() != Some(&fields) || get_case(entity_id).is_some() {
+        return;
+    }
+    flag(kind, entity_id, org_id, fields, ModerationSource::Llm, categories, submitted_by);
+}
+
+// Body of a moderation endpoint request
+#[derive(Serialize)]
+struct LlmModerationRequest<'a> {
+    input: &'a str,
+}
+
+// Categories the provider's moderation endpoint flagged the text for; empty when it passed
+async fn request_llm_moderation(org_id: Principal, text: &str) -> Result<Vec<String>, ApiError> {
+    let llm = config::llm_config();
+    let body = serde_json::to_string(&LlmModerationRequest { input: text })
+        .map_err(|_| ApiError::internal_error("Failed to serialize moderation request"))?;
+    let request = CanisterHttpRequestArgument {
+        url: format!("https://{}/v1/moderations", llm.api_host),
+        method: HttpMethod::POST,
+        body: Some(body.into_bytes()),
+        max_response_bytes: Some(outcall_transforms::max_response_bytes(OutcallKind::OpenAi)),
+        transform: Some(outcall_transforms::transform_context(OutcallKind::OpenAi)),
+        headers: vec![
+            HttpHeader {
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            },
+            HttpHeader {
+                name: "Authorization".to_string(),
+                value: format!("Bearer {}", llm.api_key),
+            },
+        ],
+    };
+
+    circuit_breakers::begin_call(ExternalService::OpenAi)?;
+    let cycles = outcall_transforms::request_cycles(&request);
+    let response = match http_request(request, cycles).await {
+        Ok((response,)) => response,
+        Err((rejection_code, message)) => {
+            let error_message = format!("Moderation request failed. RejectionCode: {:?}, Error: {}", rejection_code, message);
+            if outcall_transforms::is_oversized_rejection(&message) {
+                quotas::record_oversized_response(org_id);
+                circuit_breakers::record_success(ExternalService::OpenAi);
+            } else {
+                circuit_breakers::record_failure(ExternalService::OpenAi, &error_message);
+            }
+            return Err(ApiError::external_api_error(&error_message));
+        }
+    };
+    let status_code: u64 = response.status.0.try_into().unwrap_or(0);
+    if status_code >= 500 || status_code == 0 {
+        circuit_breakers::record_failure(ExternalService::OpenAi, &format!("status {}", status_code));
+    } else {
+        circuit_breakers::record_success(ExternalService::OpenAi);
+    }
+    if !(200..300).contains(&status_code) {
+        return Err(ApiError::external_api_error(&format!("Moderation endpoint returned status {}", status_code)));
+    }
+
+    let parsed: Value = serde_json::from_slice(&response.body)
+        .map_err(|_| ApiError::external_api_error("Moderation endpoint returned an unreadable answer"))?;
+    let result = &parsed["results"][0];
+    if !result["flagged"].as_bool().unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+    let mut categories: Vec<String> = result["categories"]
+        .as_object()
+        .map(|categories| {
+            categories
+                .iter()
+                .filter(|(_, flagged)| flagged.as_bool().unwrap_or(false))
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    if categories.is_empty() {
+        categories.push("flagged".to_string());
+    }
+    Ok(categories)
+}
+
+// Reset ALL moderation cases (use with caution)
+pub fn reset_moderation_cases() {
+    MODERATION_CASES.with(|cases| {
+        let mut cases_mut = cases.borrow_mut();
+        let keys: Vec<_> = cases_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            cases_mut.remove(&key);
+        }
+    });
+    log_info!("All moderation cases have been reset.");
+}
@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{UserNotification, UserNotificationKind};
+use crate::utils::generate_unique_principal;
+
+// Oldest notifications beyond this are dropped from a user's inbox
+pub const MAX_NOTIFICATIONS_PER_USER: usize = 200;
+
+// Define unique Memory IDs for the structures in this module
+const LEGACY_NOTIFICATIONS_MEM_ID: MemoryId = MemoryId::new(23);
+const NOTIFICATIONS_MEM_ID: MemoryId = MemoryId::new(91);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// (user_id, notification_id): a user's inbox sits in one key range, so writing and listing it
+// never touch other users' notifications
+type NotificationKey = (Principal, Principal);
+
+thread_local! {
+    // Keyed by notification ID alone; emptied into NOTIFICATIONS by migrate_legacy_notifications
+    static LEGACY_NOTIFICATIONS: RefCell<StableBTreeMap<Principal, UserNotification, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(LEGACY_NOTIFICATIONS_MEM_ID))
+        )
+    );
+
+    static NOTIFICATIONS: RefCell<StableBTreeMap<NotificationKey, UserNotification, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(NOTIFICATIONS_MEM_ID))
+        )
+    );
+}
+
+// Smallest principal, so a range starting at (user_id, it) begins at the user's first notification
+fn user_range_start(user_id: Principal) -> NotificationKey {
+    (user_id, Principal::from_slice(&[]))
+}
+
+// The user's notifications with their IDs, in ID order
+fn inbox_entries(notifications: &StableBTreeMap<NotificationKey, UserNotification, Memory>, user_id: Principal) -> Vec<(Principal, UserNotification)> {
+    notifications
+        .range(user_range_start(user_id)..)
+        .take_while(|((owner, _), _)| *owner == user_id)
+        .map(|((_, id), n)| (id, n))
+        .collect()
+}
+
+// Write a notification to the user's inbox, returning its ID
+pub fn notify_user(
+    user_id: Principal,
+    kind: UserNotificationKind,
+    title: &str,
+    message: String,
+    reference_id: Option<Principal>,
+) -> Principal {
+    let notification = UserNotification {
+        id: generate_unique_principal(user_id),
+        user_id,
+        kind,
+        title: title.to_string(),
+        message,
+        reference_id,
+        created_at: clock::now(),
+        read_at: None,
+    };
+    let notification_id = notification.id;
+
+    NOTIFICATIONS.with(|notifications| {
+        let mut notifications_mut = notifications.borrow_mut();
+        notifications_mut.insert((user_id, notification.id), notification);
+
+        let mut inbox = inbox_entries(&notifications_mut, user_id);
+        if inbox.len() > MAX_NOTIFICATIONS_PER_USER {
+            inbox.sort_by_key(|(_, n)| n.created_at);
+            let excess = inbox.len() - MAX_NOTIFICATIONS_PER_USER;
+            for (id, _) in inbox.into_iter().take(excess) {
+                notifications_mut.remove(&(user_id, id));
+            }
+        }
+    });
+    notification_id
+}
+
+pub fn get_notification(user_id: Principal, notification_id: Principal) -> Option<UserNotification> {
+    NOTIFICATIONS.with(|notifications| notifications.borrow().get(&(user_id, notification_id)))
+}
+
+pub fn save_notification(notification: UserNotification) {
+    NOTIFICATIONS.with(|notifications| {
+        notifications.borrow_mut().insert((notification.user_id, notification.id), notification);
+    });
+}
+
+// Notifications of a user, newest first
+pub fn list_for_user(user_id: Principal) -> Vec<UserNotification> {
+    let mut inbox: Vec<UserNotification> = NOTIFICATIONS.with(|notifications| {
+        inbox_entries(&notifications.borrow(), user_id).into_iter().map(|(_, n)| n).collect()
+    });
+    inbox.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    inbox
+}
+
+// Move notifications of the old ID-keyed layout under their user. Idempotent: a moved entry is
+// removed from the old store.
+pub fn migrate_legacy_notifications() -> u32 {
+    let legacy: Vec<(Principal, UserNotification)> =
+        LEGACY_NOTIFICATIONS.with(|legacy| legacy.borrow().iter().collect());
+    for (id, notification) in &legacy {
+        save_notification(notification.clone());
+        LEGACY_NOTIFICATIONS.with(|legacy| legacy.borrow_mut().remove(id));
+    }
+    legacy.len() as u32
+}
+
+// Reset ALL user notifications (use with caution)
+pub fn reset_notifications() {
+    LEGACY_NOTIFICATIONS.with(|legacy| {
+        let mut legacy_mut = legacy.borrow_mut();
+        let keys: Vec<_> = legacy_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            legacy_mut.remove(&key);
+        }
+    });
+    NOTIFICATIONS.with(|notifications| {
+        let mut notifications_mut = notifications.borrow_mut();
+        let keys: Vec<_> = notifications_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            notifications_mut.remove(&key);
+        }
+    });
+    log_info!("All user notifications have been reset.");
+}
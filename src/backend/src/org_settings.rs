@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{OrgSettings, Reseller, SerialBatchDefaults, VerificationPrivacyPolicy};
+
+// Platform defaults used when an organization has not configured its own values
+pub const DEFAULT_CERTIFICATION_VALIDITY_SECONDS: u64 = 365 * 24 * 60 * 60; // 1 year
+pub const DEFAULT_CERTIFICATION_GRACE_PERIOD_SECONDS: u64 = 14 * 24 * 60 * 60; // 14 days
+pub const DEFAULT_DUPLICATE_SCAN_THRESHOLD: u32 = 50;
+pub const DEFAULT_REDEMPTION_APPROVAL_THRESHOLD: u32 = 100; // Points; a first verification plus a promotion exceeds it
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const ORG_SETTINGS_MEM_ID: MemoryId = MemoryId::new(13);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static ORG_SETTINGS: RefCell<StableBTreeMap<Principal, OrgSettings, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_SETTINGS_MEM_ID))
+        )
+    );
+}
+
+// Stored settings for the organization, or an empty record (all defaults) if none were saved
+pub fn get_org_settings(org_id: Principal) -> OrgSettings {
+    ORG_SETTINGS
+        .with(|settings| settings.borrow().get(&org_id))
+        .unwrap_or_else(|| OrgSettings {
+            org_id,
+            ..Default::default()
+        })
+}
+
+pub fn save_org_settings(settings: OrgSettings) {
+    ORG_SETTINGS.with(|store| {
+        store.borrow_mut().insert(settings.org_id, settings);
+    });
+}
+
+pub fn certification_validity_ns(org_id: Principal) -> u64 {
+    get_org_settings(org_id)
+        .certification_validity_seconds
+        .unwrap_or(DEFAULT_CERTIFICATION_VALIDITY_SECONDS)
+        .saturating_mul(NANOS_PER_SECOND)
+}
+
+pub fn certification_grace_period_ns(org_id: Principal) -> u64 {
+    get_org_settings(org_id)
+        .certification_grace_period_seconds
+        .unwrap_or(DEFAULT_CERTIFICATION_GRACE_PERIOD_SECONDS)
+        .saturating_mul(NANOS_PER_SECOND)
+}
+
+pub fn duplicate_scan_threshold(org_id: Principal) -> u32 {
+    get_org_settings(org_id)
+        .duplicate_scan_threshold
+        .unwrap_or(DEFAULT_DUPLICATE_SCAN_THRESHOLD)
+}
+
+pub fn redemption_approval_threshold(org_id: Principal) -> u32 {
+    get_org_settings(org_id)
+        .redemption_approval_threshold
+        .unwrap_or(DEFAULT_REDEMPTION_APPROVAL_THRESHOLD)
+}
+
+// Reseller codes are reusable until they expire unless the organization opts into single use
+pub fn single_use_reseller_codes(org_id: Principal) -> bool {
+    get_org_settings(org_id).single_use_reseller_codes.unwrap_or(false)
+}
+
+pub fn verification_privacy(org_id: Principal) -> VerificationPrivacyPolicy {
+    get_org_settings(org_id).verification_privacy.unwrap_or_default()
+}
+
+pub fn serial_batch_defaults(org_id: Principal) -> SerialBatchDefaults {
+    get_org_settings(org_id).serial_batch_defaults.unwrap_or_default()
+}
+
+pub fn public_stats_enabled(org_id: Principal) -> bool {
+    get_org_settings(org_id).public_stats_enabled.unwrap_or(false)
+}
+
+// Effective expiry of a reseller's certification. Records certified before expiry was tracked
+// derive it from the certification timestamp and the organization's current validity period.
+pub fn certification_expiry(reseller: &Reseller) -> Option<u64> {
+    reseller.certification_expires_at.or_else(|| {
+        reseller
+            .certification_timestamp
+            .map(|ts| ts.saturating_add(certification_validity_ns(reseller.org_id)))
+    })
+}
+
+// True once the certification is past its expiry plus the organization's grace period
+pub fn is_certification_expired(reseller: &Reseller, now: u64) -> bool {
+    match certification_expiry(reseller) {
+        Some(expires_at) => now > expires_at.saturating_add(certification_grace_period_ns(reseller.org_id)),
+        None => false,
+    }
+}
+
+// Remove the organization's settings when it is deleted
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    ORG_SETTINGS.with(|settings| settings.borrow_mut().remove(&org_id)).is_some() as u32
+}
+
+// Reset ALL organization settings (use with caution)
+pub fn reset_org_settings() {
+    ORG_SETTINGS.with(|settings| {
+        let mut settings_mut = settings.borrow_mut();
+        let keys: Vec<_> = settings_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            settings_mut.remove(&key);
+        }
+    });
+    log_info!("All organization settings have been reset.");
+}
@@ -0,0 +1,192 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk_timers::{set_timer, set_timer_interval};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{OutboxDelivery, OutboxDeliveryStatus};
+use crate::webhooks;
+
+// A delivery is dead-lettered after this many failed attempts
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 8;
+// Delay before the first retry; doubled after every further failure, up to MAX_RETRY_DELAY_SECONDS
+const INITIAL_RETRY_DELAY_SECONDS: u64 = 30;
+const MAX_RETRY_DELAY_SECONDS: u64 = 60 * 60;
+// How long a delivery being sent is hidden from other dispatcher runs. Covers a dispatcher that never
+// finished, e.g. because the canister was upgraded while the outcall was in flight.
+const IN_FLIGHT_LEASE_SECONDS: u64 = 5 * 60;
+const OUTBOX_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_ERROR_LENGTH: usize = 500;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const OUTBOX_MEM_ID: MemoryId = MemoryId::new(59);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by a sequence number, so deliveries go out in the order they were queued. Delivered
+    // entries are removed; dead-lettered ones stay until an admin requeues them.
+    static OUTBOX: RefCell<StableBTreeMap<u64, OutboxDelivery, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(OUTBOX_MEM_ID))
+        )
+    );
+
+    static DISPATCH_SCHEDULED: RefCell<bool> = const { RefCell::new(false) };
+}
+
+// Register the recurring dispatcher. Timers do not survive upgrades, so this runs from both init and post_upgrade.
+pub fn start_outbox_dispatcher() {
+    set_timer_interval(OUTBOX_CHECK_INTERVAL, || ic_cdk::spawn(dispatch_due_deliveries()));
+}
+
+// Persist a JSON body for delivery and send it as soon as possible
+pub fn enqueue(url: String, body: String, org_id: Option<Principal>) -> OutboxDelivery {
+    let now = clock::now();
+    let delivery = OUTBOX.with(|outbox| {
+        let mut outbox_mut = outbox.borrow_mut();
+        let id = outbox_mut.last_key_value().map_or(0, |(id, _)| id + 1);
+        let delivery = OutboxDelivery {
+            id,
+            org_id,
+            url,
+            body,
+            status: OutboxDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        outbox_mut.insert(id, delivery.clone());
+        delivery
+    });
+    schedule_dispatch();
+    delivery
+}
+
+pub fn get_delivery(id: u64) -> Option<OutboxDelivery> {
+    OUTBOX.with(|outbox| outbox.borrow().get(&id))
+}
+
+// Deliveries in queue order, optionally only those with the given status
+pub fn list_deliveries(status: Option<OutboxDeliveryStatus>) -> Vec<OutboxDelivery> {
+    OUTBOX.with(|outbox| {
+        outbox
+            .borrow()
+            .iter()
+            .map(|(_, delivery)| delivery)
+            .filter(|delivery| status.is_none_or(|s| delivery.status == s))
+            .collect()
+    })
+}
+
+// Give a dead-lettered delivery a fresh set of attempts, starting now
+pub fn requeue(id: u64) -> Option<OutboxDelivery> {
+    let mut delivery = get_delivery(id).filter(|d| d.status == OutboxDeliveryStatus::DeadLettered)?;
+    let now = clock::now();
+    delivery.status = OutboxDeliveryStatus::Pending;
+    delivery.attempts = 0;
+    delivery.next_attempt_at = now;
+    delivery.updated_at = now;
+    OUTBOX.with(|outbox| outbox.borrow_mut().insert(id, delivery.clone()));
+    schedule_dispatch();
+    Some(delivery)
+}
+
+fn retry_delay_ns(attempts: u32) -> u64 {
+    let delay = INITIAL_RETRY_DELAY_SECONDS.saturating_mul(1u64 << attempts.saturating_sub(1).min(16));
+    delay.min(MAX_RETRY_DELAY_SECONDS).saturating_mul(NANOS_PER_SECOND)
+}
+
+fn schedule_dispatch() {
+    let already_scheduled = DISPATCH_SCHEDULED.with(|scheduled| scheduled.replace(true));
+    if already_scheduled {
+        return;
+    }
+    set_timer(Duration::ZERO, || ic_cdk::spawn(dispatch_due_deliveries()));
+}
+
+async fn dispatch_due_deliveries() {
+    DISPATCH_SCHEDULED.with(|scheduled| *scheduled.borrow_mut() = false);
+
+    // Lease the due deliveries before the first await, so an overlapping run skips them
+    let now = clock::now();
+    let lease_until = now.saturating_add(IN_FLIGHT_LEASE_SECONDS * NANOS_PER_SECOND);
+    let due: Vec<OutboxDelivery> = OUTBOX.with(|outbox| {
+        let mut outbox_mut = outbox.borrow_mut();
+        let due: Vec<OutboxDelivery> = outbox_mut
+            .iter()
+            .map(|(_, delivery)| delivery)
+            .filter(|delivery| delivery.status == OutboxDeliveryStatus::Pending && delivery.next_attempt_at <= now)
+            .collect();
+        for delivery in &due {
+            let mut leased = delivery.clone();
+            leased.next_attempt_at = lease_until;
+            outbox_mut.insert(leased.id, leased);
+        }
+        due
+    });
+    if due.is_empty() {
+        return;
+    }
+    log_info!("[dispatch_due_deliveries] Dispatching {} delivery(ies)", due.len());
+
+    for mut delivery in due {
+        let result = webhooks::post_json(&delivery.url, delivery.body.clone(), delivery.org_id).await;
+        // Requeued or reset while in flight: leave the record as it is now
+        if get_delivery(delivery.id).is_none_or(|current| current.next_attempt_at != lease_until) {
+            continue;
+        }
+        match result {
+            Ok(()) => {
+                OUTBOX.with(|outbox| outbox.borrow_mut().remove(&delivery.id));
+            }
+            Err(e) => {
+                let now = clock::now();
+                delivery.attempts += 1;
+                delivery.last_error = Some(format!("{:?}", e).chars().take(MAX_ERROR_LENGTH).collect());
+                delivery.updated_at = now;
+                if delivery.attempts >= MAX_DELIVERY_ATTEMPTS {
+                    delivery.status = OutboxDeliveryStatus::DeadLettered;
+                    log_error!("[dispatch_due_deliveries] Delivery {} to {} dead-lettered after {} attempts: {:?}", delivery.id, delivery.url, delivery.attempts, e);
+                } else {
+                    delivery.next_attempt_at = now.saturating_add(retry_delay_ns(delivery.attempts));
+                    log_warn!("[dispatch_due_deliveries] Delivery {} to {} failed (attempt {}), retrying later: {:?}", delivery.id, delivery.url, delivery.attempts, e);
+                }
+                OUTBOX.with(|outbox| outbox.borrow_mut().insert(delivery.id, delivery));
+            }
+        }
+    }
+}
+
+// Remove the organization's queued and dead-lettered deliveries when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    OUTBOX.with(|outbox| {
+        let mut outbox_mut = outbox.borrow_mut();
+        let keys: Vec<_> = outbox_mut.iter().filter(|(_, delivery)| delivery.org_id == Some(org_id)).map(|(k, _)| k).collect();
+        for key in &keys {
+            outbox_mut.remove(key);
+        }
+        keys.len() as u32
+    })
+}
+
+// Reset the outbox, dropping undelivered and dead-lettered deliveries (use with caution)
+pub fn reset_outbox() {
+    OUTBOX.with(|outbox| {
+        let mut outbox_mut = outbox.borrow_mut();
+        let keys: Vec<_> = outbox_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            outbox_mut.remove(&key);
+        }
+    });
+    log_info!("The webhook outbox has been reset.");
+}
@@ -0,0 +1,226 @@
+use ic_cdk::api;
+use ic_cdk::api::management_canister::http_request::{
+    CanisterHttpRequestArgument, HttpHeader, HttpResponse, TransformArgs, TransformContext, TransformFunc,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config;
+use crate::error::ApiError;
+use crate::models::OutcallResponseLimits;
+
+// Integrations the canister makes HTTPS outcalls to. Each has its own transform query, so only
+// these methods are ever named as outcall transforms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutcallKind {
+    OpenAi,
+    Scraper,
+    Webhook,
+    DnsLookup, // DNS-over-HTTPS queries for verification domain proofs
+}
+
+// Bounds for configured response limits. The lower one leaves room for the headers.
+pub const MIN_RESPONSE_BYTES: u64 = 4 * 1024;
+pub const MAX_RESPONSE_BYTES: u64 = 2_000_000; // The IC's limit for an outcall response
+
+// Outcall fees grow with the subnet's size; budgeting for the largest subnets is safe, as unused cycles are refunded
+const COST_SUBNET_NODES: u128 = 34;
+
+// Response headers kept; the rest (Date, request ids, cookies, rate-limit counters) differ between replicas
+const KEPT_HEADERS: [&str; 1] = ["content-type"];
+
+// Top-level JSON fields that differ between replicas answering the same request
+const OPENAI_VOLATILE_FIELDS: [&str; 3] = ["id", "created", "system_fingerprint"];
+const SCRAPER_VOLATILE_FIELDS: [&str; 6] = ["timestamp", "date", "generated_at", "scraped_at", "request_id", "requestId"];
+const WEBHOOK_VOLATILE_FIELDS: [&str; 6] = ["timestamp", "date", "time", "request_id", "requestId", "trace_id"];
+
+impl OutcallKind {
+    fn transform_method(self) -> &'static str {
+        match self {
+            OutcallKind::OpenAi => "transform_openai",
+            OutcallKind::Scraper => "transform_scraper",
+            OutcallKind::Webhook => "transform_webhook",
+            OutcallKind::DnsLookup => "transform_dns",
+        }
+    }
+
+    pub fn default_max_response_bytes(self) -> u64 {
+        match self {
+            OutcallKind::OpenAi => 64 * 1024,
+            OutcallKind::Scraper => 256 * 1024,
+            OutcallKind::Webhook => 16 * 1024, // The body is only quoted in delivery errors
+            OutcallKind::DnsLookup => 8 * 1024,
+        }
+    }
+
+    fn volatile_fields(self) -> &'static [&'static str] {
+        match self {
+            OutcallKind::OpenAi => &OPENAI_VOLATILE_FIELDS,
+            OutcallKind::Scraper => &SCRAPER_VOLATILE_FIELDS,
+            OutcallKind::Webhook => &WEBHOOK_VOLATILE_FIELDS,
+            OutcallKind::DnsLookup => &[], // The body is reduced to its TXT values instead
+        }
+    }
+}
+
+// Configured limit of the integration, or its default. DNS answers are small, so their limit is fixed.
+pub fn max_response_bytes(kind: OutcallKind) -> u64 {
+    match config::outcall_limits() {
+        Some(limits) => match kind {
+            OutcallKind::OpenAi => limits.openai_max_response_bytes,
+            OutcallKind::Scraper => limits.scraper_max_response_bytes,
+            OutcallKind::Webhook => limits.webhook_max_response_bytes,
+            OutcallKind::DnsLookup => kind.default_max_response_bytes(),
+        },
+        None => kind.default_max_response_bytes(),
+    }
+}
+
+pub fn validate_limits(limits: &OutcallResponseLimits) -> Result<(), ApiError> {
+    let all = [
+        limits.openai_max_response_bytes,
+        limits.scraper_max_response_bytes,
+        limits.webhook_max_response_bytes,
+    ];
+    if all.iter().any(|bytes| *bytes < MIN_RESPONSE_BYTES || *bytes > MAX_RESPONSE_BYTES) {
+        return Err(ApiError::invalid_input(&format!(
+            "Outcall response limits must be between {} and {} bytes",
+            MIN_RESPONSE_BYTES, MAX_RESPONSE_BYTES
+        )));
+    }
+    Ok(())
+}
+
+// Transform to attach to an outcall of this kind. The body limit travels in the context.
+pub fn transform_context(kind: OutcallKind) -> TransformContext {
+    TransformContext {
+        function: TransformFunc(candid::Func {
+            principal: api::id(),
+            method: kind.transform_method().to_string(),
+        }),
+        context: max_response_bytes(kind).to_le_bytes().to_vec(),
+    }
+}
+
+// Cycles to attach to an outcall, from the IC's fee schedule: a base fee plus a fee per request
+// byte and per byte of the response limit
+pub fn request_cycles(request: &CanisterHttpRequestArgument) -> u128 {
+    let request_bytes = request.url.len()
+        + request.body.as_ref().map_or(0, |body| body.len())
+        + request.headers.iter().map(|h| h.name.len() + h.value.len()).sum::<usize>();
+    let response_bytes = request.max_response_bytes.unwrap_or(MAX_RESPONSE_BYTES);
+    let n = COST_SUBNET_NODES;
+    (3_000_000 + 60_000 * n) * n + 400 * n * request_bytes as u128 + 800 * n * response_bytes as u128
+}
+
+// Whether a rejected outcall failed because the response was larger than its max_response_bytes
+pub fn is_oversized_rejection(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("size limit") || message.contains("exceeds limit")
+}
+
+fn body_limit(kind: OutcallKind, context: &[u8]) -> usize {
+    let limit = match <[u8; 8]>::try_from(context) {
+        Ok(bytes) => u64::from_le_bytes(bytes),
+        Err(_) => kind.default_max_response_bytes(),
+    };
+    limit.min(usize::MAX as u64) as usize
+}
+
+// Drop the volatile top-level fields of a JSON object body; any other body is returned as it is
+fn strip_volatile_fields(body: Vec<u8>, fields: &[&str]) -> Vec<u8> {
+    let mut value: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(_) => return body,
+    };
+    match value.as_object_mut() {
+        Some(object) => {
+            for field in fields {
+                object.remove(*field);
+            }
+        }
+        None => return body,
+    }
+    serde_json::to_vec(&value).unwrap_or(body)
+}
+
+// Text of a TXT record as a DNS-over-HTTPS JSON answer gives it: quoted strings, which long records split
+// into several, e.g. "\"part one\" \"part two\""
+fn txt_record_text(data: &str) -> String {
+    if !data.contains('"') {
+        return data.to_string();
+    }
+    data.split('"').skip(1).step_by(2).collect()
+}
+
+// What transform_dns keeps of a resolver answer; read back by verification_domains
+#[derive(Serialize)]
+struct ReducedDnsAnswer {
+    status: Option<u64>,
+    txt: Vec<String>,
+}
+
+// Reduce a DNS-over-HTTPS JSON answer to the DNS status and the sorted TXT values, dropping the TTLs
+// and resolver comments that differ between replicas. Any other body is returned as it is.
+fn reduce_dns_answer(body: Vec<u8>) -> Vec<u8> {
+    let value: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(_) => return body,
+    };
+    let status = value.get("Status").and_then(Value::as_u64);
+    let mut txt: Vec<String> = value
+        .get("Answer")
+        .and_then(Value::as_array)
+        .map(|answers| {
+            answers
+                .iter()
+                .filter(|answer| answer.get("type").and_then(Value::as_u64) == Some(16))
+                .filter_map(|answer| answer.get("data").and_then(Value::as_str))
+                .map(txt_record_text)
+                .collect()
+        })
+        .unwrap_or_default();
+    txt.sort();
+    txt.dedup();
+    serde_json::to_vec(&ReducedDnsAnswer { status, txt }).unwrap_or(body)
+}
+
+// Cut the body to `limit` bytes without splitting a UTF-8 sequence
+fn truncate_body(body: &mut Vec<u8>, limit: usize) {
+    if body.len() <= limit {
+        return;
+    }
+    let mut end = limit;
+    while end > 0 && body[end] & 0xC0 == 0x80 {
+        end -= 1;
+    }
+    body.truncate(end);
+}
+
+// Reduce a raw response to what every replica sees the same: the status, the kept headers with
+// lowercase names in a fixed order, and the body without volatile fields, cut to the limit
+pub fn normalize(kind: OutcallKind, raw: TransformArgs) -> HttpResponse {
+    let response = raw.response;
+    let mut headers: Vec<HttpHeader> = response
+        .headers
+        .into_iter()
+        .map(|h| HttpHeader {
+            name: h.name.to_lowercase(),
+            value: h.value.trim().to_string(),
+        })
+        .filter(|h| KEPT_HEADERS.contains(&h.name.as_str()))
+        .collect();
+    headers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.value.cmp(&b.value)));
+
+    let mut body = match kind {
+        OutcallKind::DnsLookup => reduce_dns_answer(response.body),
+        _ => strip_volatile_fields(response.body, kind.volatile_fields()),
+    };
+    truncate_body(&mut body, body_limit(kind, &raw.context));
+
+    HttpResponse {
+        status: response.status,
+        headers,
+        body,
+    }
+}
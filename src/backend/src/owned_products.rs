@@ -0,0 +1,141 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::OwnedProduct;
+
+pub const MAX_OWNED_PER_USER: usize = 500;
+
+// Define unique Memory IDs for the structures in this module
+const OWNED_PRODUCTS_MEM_ID: MemoryId = MemoryId::new(81);
+const OWNER_INDEX_MEM_ID: MemoryId = MemoryId::new(93);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by serial number, so a serial is owned by at most one user
+    static OWNED_PRODUCTS: RefCell<StableBTreeMap<Principal, OwnedProduct, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(OWNED_PRODUCTS_MEM_ID))
+        )
+    );
+
+    // (user_id, serial_no), so a user's shelf is a range scan
+    static OWNER_INDEX: RefCell<StableBTreeMap<(Principal, Principal), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(OWNER_INDEX_MEM_ID))
+        )
+    );
+}
+
+pub fn get(serial_no: Principal) -> Option<OwnedProduct> {
+    OWNED_PRODUCTS.with(|owned| owned.borrow().get(&serial_no))
+}
+
+pub fn insert(entry: OwnedProduct) {
+    let key = (entry.user_id, entry.serial_no);
+    let previous = OWNED_PRODUCTS.with(|owned| owned.borrow_mut().insert(entry.serial_no, entry));
+    OWNER_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        if let Some(previous) = previous {
+            index_mut.remove(&(previous.user_id, previous.serial_no));
+        }
+        index_mut.insert(key, ());
+    });
+}
+
+pub fn remove(serial_no: Principal) -> Option<OwnedProduct> {
+    let removed = OWNED_PRODUCTS.with(|owned| owned.borrow_mut().remove(&serial_no))?;
+    OWNER_INDEX.with(|index| index.borrow_mut().remove(&(removed.user_id, removed.serial_no)));
+    Some(removed)
+}
+
+// The user's shelf, most recently added first
+pub fn list_for_user(user_id: Principal) -> Vec<OwnedProduct> {
+    let serial_nos: Vec<Principal> = OWNER_INDEX.with(|index| {
+        index
+            .borrow()
+            .range((user_id, Principal::from_slice(&[]))..=(user_id, Principal::from_slice(&[0xFF; 29])))
+            .map(|((_, serial_no), _)| serial_no)
+            .collect()
+    });
+    let mut entries: Vec<OwnedProduct> = serial_nos.into_iter().filter_map(get).collect();
+    entries.sort_by_key(|a| std::cmp::Reverse(a.registered_at));
+    entries
+}
+
+pub fn list_for_product(product_id: Principal) -> Vec<OwnedProduct> {
+    OWNED_PRODUCTS.with(|owned| {
+        owned
+            .borrow()
+            .iter()
+            .map(|(_, entry)| entry)
+            .filter(|entry| entry.product_id == product_id)
+            .collect()
+    })
+}
+
+// Registered serials of each of the products that has any. Counts only; owners are not exposed to brands.
+pub fn counts_for_products(product_ids: &[Principal]) -> Vec<(Principal, u64)> {
+    let mut counts: Vec<(Principal, u64)> = Vec::new();
+    OWNED_PRODUCTS.with(|owned| {
+        for (_, entry) in owned.borrow().iter().filter(|(_, entry)| product_ids.contains(&entry.product_id)) {
+            match counts.iter_mut().find(|(product_id, _)| *product_id == entry.product_id) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((entry.product_id, 1)),
+            }
+        }
+    });
+    counts
+}
+
+// Move a user's shelf to another user, e.g. when two accounts are merged. Returns the entries moved.
+pub fn reassign_owner(from: Principal, to: Principal) -> u32 {
+    let mut moved = 0;
+    for mut entry in list_for_user(from) {
+        entry.user_id = to;
+        insert(entry);
+        moved += 1;
+    }
+    moved
+}
+
+// Index the registrations made before the owner index existed. Only runs while the index is empty.
+pub fn backfill_owner_index() -> u64 {
+    if OWNER_INDEX.with(|index| !index.borrow().is_empty()) {
+        return 0;
+    }
+    let keys: Vec<(Principal, Principal)> =
+        OWNED_PRODUCTS.with(|owned| owned.borrow().iter().map(|(serial_no, entry)| (entry.user_id, serial_no)).collect());
+    let indexed = keys.len() as u64;
+    OWNER_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        for key in keys {
+            index_mut.insert(key, ());
+        }
+    });
+    indexed
+}
+
+// Reset ALL owned product registrations (use with caution)
+pub fn reset_owned_products() {
+    OWNED_PRODUCTS.with(|owned| {
+        let mut owned_mut = owned.borrow_mut();
+        let keys: Vec<_> = owned_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            owned_mut.remove(&key);
+        }
+    });
+    OWNER_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    log_info!("All owned product registrations have been reset.");
+}
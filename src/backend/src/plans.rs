@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_cdk::api;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::api::{PlanLimits, PlanUsageResponse};
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, ORGANIZATIONS, PRODUCTS};
+use crate::models::{OrgPlan, PlanStatus, PlanTier};
+use crate::quotas;
+use crate::webhooks;
+
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const ORG_PLANS_MEM_ID: MemoryId = MemoryId::new(72);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static ORG_PLANS: RefCell<StableBTreeMap<Principal, OrgPlan, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_PLANS_MEM_ID))
+        )
+    );
+}
+
+pub fn tier_limits(tier: PlanTier) -> PlanLimits {
+    match tier {
+        PlanTier::Free => PlanLimits {
+            max_products: Some(10),
+            max_serials_per_month: 1_000,
+            analytics_retention_days: 30,
+            max_webhooks: Some(1),
+        },
+        PlanTier::Pro => PlanLimits {
+            max_products: Some(500),
+            max_serials_per_month: 100_000,
+            analytics_retention_days: 365,
+            max_webhooks: Some(20),
+        },
+        PlanTier::Enterprise => PlanLimits {
+            max_products: None,
+            max_serials_per_month: 1_000_000,
+            analytics_retention_days: 5 * 365,
+            max_webhooks: None,
+        },
+    }
+}
+
+fn save_plan(plan: OrgPlan) {
+    ORG_PLANS.with(|plans| {
+        plans.borrow_mut().insert(plan.org_id, plan);
+    });
+}
+
+// Stored plan, or Free for an organization that never had one
+pub fn get_plan(org_id: Principal) -> OrgPlan {
+    ORG_PLANS.with(|plans| plans.borrow().get(&org_id)).unwrap_or(OrgPlan {
+        org_id,
+        tier: PlanTier::Free,
+        status: PlanStatus::Active,
+        tier_changed_at: 0,
+        status_changed_at: 0,
+        updated_by: Principal::anonymous(),
+    })
+}
+
+pub fn effective_tier(plan: &OrgPlan) -> PlanTier {
+    match plan.status {
+        PlanStatus::Cancelled => PlanTier::Free,
+        PlanStatus::Active | PlanStatus::PastDue => plan.tier,
+    }
+}
+
+pub fn limits(org_id: Principal) -> PlanLimits {
+    tier_limits(effective_tier(&get_plan(org_id)))
+}
+
+// New organizations start on Free; stored explicitly so the upgrade backfill leaves them alone
+pub fn start_free_plan(org_id: Principal, created_by: Principal) {
+    let now = clock::now();
+    save_plan(OrgPlan {
+        org_id,
+        tier: PlanTier::Free,
+        status: PlanStatus::Active,
+        tier_changed_at: now,
+        status_changed_at: now,
+        updated_by: created_by,
+    });
+}
+
+// Organizations created before plans existed keep their previous allowance, which matches Pro.
+// Idempotent: organizations with a stored plan are skipped.
+pub fn backfill_missing() -> u32 {
+    let org_ids: Vec<Principal> = ORGANIZATIONS.with(|orgs| orgs.borrow().iter().map(|(id, _)| id).collect());
+    let now = clock::now();
+    let mut seeded = 0;
+    for org_id in org_ids {
+        if ORG_PLANS.with(|plans| plans.borrow().contains_key(&org_id)) {
+            continue;
+        }
+        save_plan(OrgPlan {
+            org_id,
+            tier: PlanTier::Pro,
+            status: PlanStatus::Active,
+            tier_changed_at: now,
+            status_changed_at: now,
+            updated_by: api::id(),
+        });
+        seeded += 1;
+    }
+    seeded
+}
+
+pub fn product_count(org_id: Principal) -> u64 {
+    PRODUCTS.with(|products| products.borrow().iter().filter(|(_, p)| p.org_id == org_id).count() as u64)
+}
+
+// Room for `count` more products under the organization's plan
+pub fn ensure_product_capacity(org_id: Principal, count: u64) -> Result<(), ApiError> {
+    if let Some(max) = limits(org_id).max_products {
+        if product_count(org_id).saturating_add(count) > max {
+            return Err(ApiError::quota_exceeded(&format!(
+                "The organization's plan allows at most {} products",
+                max
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn ensure_webhook_capacity(org_id: Principal) -> Result<(), ApiError> {
+    if let Some(max) = limits(org_id).max_webhooks {
+        if webhooks::count_rules_for_org(org_id) >= max as usize {
+            return Err(ApiError::quota_exceeded(&format!(
+                "The organization's plan allows at most {} notification rules",
+                max
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Oldest timestamp the organization's analytics may reach back to
+pub fn retention_start(org_id: Principal, now: u64) -> u64 {
+    now.saturating_sub(limits(org_id).analytics_retention_days as u64 * DAY_NS)
+}
+
+pub fn ensure_within_retention(org_id: Principal, period_start: u64, now: u64) -> Result<(), ApiError> {
+    if period_start < retention_start(org_id, now) {
+        return Err(ApiError::quota_exceeded(&format!(
+            "The organization's plan keeps {} days of analytics",
+            limits(org_id).analytics_retention_days
+        )));
+    }
+    Ok(())
+}
+
+// Move to another tier. A cancelled plan must be reactivated first, and a downgrade is refused
+// while the organization holds more products or notification rules than the new tier allows.
+pub fn change_tier(org_id: Principal, tier: PlanTier, changed_by: Principal) -> Result<OrgPlan, ApiError> {
+    let mut plan = get_plan(org_id);
+    if plan.status == PlanStatus::Cancelled {
+        return Err(ApiError::invalid_input("Reactivate the plan before changing its tier"));
+    }
+    if plan.tier == tier {
+        return Err(ApiError::invalid_input("The organization is already on this tier"));
+    }
+    let target = tier_limits(tier);
+    if let Some(max) = target.max_products {
+        let products = product_count(org_id);
+        if products > max {
+            return Err(ApiError::invalid_input(&format!(
+                "The organization has {} products; the {:?} tier allows {}",
+                products, tier, max
+            )));
+        }
+    }
+    if let Some(max) = target.max_webhooks {
+        let rules = webhooks::count_rules_for_org(org_id);
+        if rules > max as usize {
+            return Err(ApiError::invalid_input(&format!(
+                "The organization has {} notification rules; the {:?} tier allows {}",
+                rules, tier, max
+            )));
+        }
+    }
+
+    plan.tier = tier;
+    plan.tier_changed_at = clock::now();
+    plan.updated_by = changed_by;
+    save_plan(plan.clone());
+    Ok(plan)
+}
+
+fn transition_allowed(from: PlanStatus, to: PlanStatus) -> bool {
+    matches!(
+        (from, to),
+        (PlanStatus::Active, PlanStatus::PastDue)
+            | (PlanStatus::PastDue, PlanStatus::Active)
+            | (PlanStatus::Active, PlanStatus::Cancelled)
+            | (PlanStatus::PastDue, PlanStatus::Cancelled)
+            | (PlanStatus::Cancelled, PlanStatus::Active)
+    )
+}
+
+pub fn change_status(org_id: Principal, status: PlanStatus, changed_by: Principal) -> Result<OrgPlan, ApiError> {
+    let mut plan = get_plan(org_id);
+    if !transition_allowed(plan.status, status) {
+        return Err(ApiError::invalid_input(&format!(
+            "A {:?} plan cannot become {:?}",
+            plan.status, status
+        )));
+    }
+    plan.status = status;
+    plan.status_changed_at = clock::now();
+    plan.updated_by = changed_by;
+    save_plan(plan.clone());
+    Ok(plan)
+}
+
+pub fn get_usage(org_id: Principal) -> PlanUsageResponse {
+    let plan = get_plan(org_id);
+    let effective_tier = effective_tier(&plan);
+    let serials = quotas::get_usage(org_id);
+    PlanUsageResponse {
+        plan,
+        effective_tier,
+        limits: tier_limits(effective_tier),
+        products: product_count(org_id),
+        serials_this_month: serials.serials_this_month,
+        serials_limit: serials.serials_limit,
+        serials_window_resets_at: serials.serials_window_resets_at,
+        webhooks: webhooks::count_rules_for_org(org_id) as u32,
+    }
+}
+
+// Remove the organization's plan when it is deleted
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    ORG_PLANS.with(|plans| plans.borrow_mut().remove(&org_id)).is_some() as u32
+}
+
+// Reset ALL organization plans (use with caution)
+pub fn reset_org_plans() {
+    ORG_PLANS.with(|plans| {
+        let mut plans_mut = plans.borrow_mut();
+        let keys: Vec<_> = plans_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            plans_mut.remove(&key);
+        }
+    });
+    log_info!("All organization plans have been reset.");
+}
@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::PrintRecord;
+
+// Older versions a verification tries before calling a code invalid; each try is a signature check
+pub const MAX_SUPERSEDED_VERSIONS_CHECKED: u8 = 16;
+
+// Define a unique MemoryId for this structure
+const PRINT_HISTORY_MEM_ID: MemoryId = MemoryId::new(86);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by (serial number, print version); versions printed before this store existed have no record
+    static PRINT_HISTORY: RefCell<StableBTreeMap<(Principal, u8), PrintRecord, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PRINT_HISTORY_MEM_ID))
+        )
+    );
+}
+
+pub fn record_print(record: PrintRecord) {
+    PRINT_HISTORY.with(|history| {
+        history.borrow_mut().insert((record.serial_no, record.print_version), record);
+    });
+}
+
+pub fn remove_for_serial(serial_no: Principal) {
+    PRINT_HISTORY.with(|history| {
+        let mut history_mut = history.borrow_mut();
+        let keys: Vec<_> = history_mut.range((serial_no, 0)..=(serial_no, u8::MAX)).map(|(k, _)| k).collect();
+        for key in keys {
+            history_mut.remove(&key);
+        }
+    });
+}
+
+// Recorded prints of a serial, oldest first
+pub fn list_for_serial(serial_no: Principal) -> Vec<PrintRecord> {
+    PRINT_HISTORY.with(|history| {
+        history
+            .borrow()
+            .range((serial_no, 0)..=(serial_no, u8::MAX))
+            .map(|(_, record)| record)
+            .collect()
+    })
+}
+
+// Reset ALL print history (use with caution)
+pub fn reset_print_history() {
+    PRINT_HISTORY.with(|history| {
+        let mut history_mut = history.borrow_mut();
+        let keys: Vec<_> = history_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            history_mut.remove(&key);
+        }
+    });
+    log_info!("All print history has been reset.");
+}
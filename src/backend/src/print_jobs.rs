@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::PrintJob;
+
+// Upper bound on serials printed by one bulk request, keeps the call within instruction limits
+pub const MAX_SERIALS_PER_PRINT_JOB: usize = 500;
+// Latest activation time a print job may be scheduled for, counted from printing
+pub const MAX_ACTIVATION_DELAY_NANOS: u64 = 2 * 365 * 24 * 60 * 60 * 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const PRINT_JOBS_MEM_ID: MemoryId = MemoryId::new(16);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static PRINT_JOBS: RefCell<StableBTreeMap<Principal, PrintJob, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PRINT_JOBS_MEM_ID))
+        )
+    );
+}
+
+pub fn save_print_job(job: PrintJob) {
+    PRINT_JOBS.with(|jobs| {
+        jobs.borrow_mut().insert(job.id, job);
+    });
+}
+
+pub fn get_print_job(job_id: Principal) -> Option<PrintJob> {
+    PRINT_JOBS.with(|jobs| jobs.borrow().get(&job_id))
+}
+
+// Whether codes of the print job verify at `now`. Serials printed outside a print job always do.
+pub fn is_activated(print_job_id: Option<Principal>, now: u64) -> bool {
+    print_job_id
+        .and_then(get_print_job)
+        .and_then(|job| job.activation_time)
+        .is_none_or(|activation_time| activation_time <= now)
+}
+
+// Print jobs of a product, newest first
+pub fn list_print_jobs_for_product(product_id: Principal) -> Vec<PrintJob> {
+    let mut jobs: Vec<PrintJob> = PRINT_JOBS.with(|jobs| {
+        jobs.borrow()
+            .iter()
+            .filter(|(_, job)| job.product_id == product_id)
+            .map(|(_, job)| job)
+            .collect()
+    });
+    jobs.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    jobs
+}
+
+// Remove the organization's print jobs when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    PRINT_JOBS.with(|jobs| {
+        let mut jobs_mut = jobs.borrow_mut();
+        let keys: Vec<_> = jobs_mut.iter().filter(|(_, job)| job.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            jobs_mut.remove(key);
+        }
+        keys.len() as u32
+    })
+}
+
+// Reset ALL print jobs (use with caution)
+pub fn reset_print_jobs() {
+    PRINT_JOBS.with(|jobs| {
+        let mut jobs_mut = jobs.borrow_mut();
+        let keys: Vec<_> = jobs_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            jobs_mut.remove(&key);
+        }
+    });
+    log_info!("All print jobs have been reset.");
+}
@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{OrgProbeActivity, ProbeRecord};
+
+// Serial lookups a principal may make per window, across all products
+const MAX_PROBES_PER_WINDOW: u32 = 120;
+const PROBE_WINDOW_NS: u64 = 60 * 60 * 1_000_000_000;
+
+// A principal whose lookups mostly miss is guessing serials. Past FLAG_* it is reported to the brands
+// whose serials it hits; past BAN_* it is banned for a while, twice as long each time it happens again.
+const FLAG_MIN_PROBES: u32 = 10;
+const FLAG_MISS_RATIO_PERCENT: u32 = 50;
+const BAN_MIN_PROBES: u32 = 20;
+const BAN_MISS_RATIO_PERCENT: u32 = 80;
+const BASE_BAN_NS: u64 = 60 * 60 * 1_000_000_000;
+const MAX_BAN_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+// Probing principals kept per brand summary
+const MAX_RECENT_PROBERS: usize = 20;
+
+// Define unique Memory IDs for the structures in this module
+const PROBE_RECORDS_MEM_ID: MemoryId = MemoryId::new(67);
+const ORG_PROBE_ACTIVITY_MEM_ID: MemoryId = MemoryId::new(68);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static PROBE_RECORDS: RefCell<StableBTreeMap<Principal, ProbeRecord, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PROBE_RECORDS_MEM_ID))
+        )
+    );
+
+    static ORG_PROBE_ACTIVITY: RefCell<StableBTreeMap<Principal, OrgProbeActivity, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_PROBE_ACTIVITY_MEM_ID))
+        )
+    );
+}
+
+fn misses_exceed(record: &ProbeRecord, min_probes: u32, ratio_percent: u32) -> bool {
+    record.probes >= min_probes && record.misses as u64 * 100 >= record.probes as u64 * ratio_percent as u64
+}
+
+fn banned_error(banned_until: u64) -> ApiError {
+    ApiError::unauthorized(&format!("Too many lookups of unknown serial numbers. Try again after {}", banned_until))
+}
+
+fn get_record(principal: Principal) -> Option<ProbeRecord> {
+    PROBE_RECORDS.with(|records| records.borrow().get(&principal))
+}
+
+// Fails while the principal serves a ban. For query endpoints, which cannot record lookups.
+pub fn ensure_not_banned(principal: Principal, now: u64) -> Result<(), ApiError> {
+    match get_record(principal).and_then(|r| r.banned_until).filter(|until| *until > now) {
+        Some(until) => Err(banned_error(until)),
+        None => Ok(()),
+    }
+}
+
+// Count a serial lookup against the principal's budget. `found_org_id` is the organization owning
+// the serial, or None if it does not exist. The lookup that triggers a ban is refused as well.
+pub fn record_probe(principal: Principal, found_org_id: Option<Principal>, now: u64) -> Result<(), ApiError> {
+    let mut record = get_record(principal).unwrap_or(ProbeRecord {
+        principal,
+        window_start: now,
+        probes: 0,
+        misses: 0,
+        banned_until: None,
+        ban_count: 0,
+    });
+    if let Some(until) = record.banned_until.filter(|until| *until > now) {
+        return Err(banned_error(until));
+    }
+    if now >= record.window_start.saturating_add(PROBE_WINDOW_NS) {
+        record.window_start = now;
+        record.probes = 0;
+        record.misses = 0;
+    }
+    if record.probes >= MAX_PROBES_PER_WINDOW {
+        return Err(ApiError::invalid_input(&format!(
+            "Lookup limit exceeded. Try again after {}",
+            record.window_start + PROBE_WINDOW_NS
+        )));
+    }
+
+    record.probes += 1;
+    if found_org_id.is_none() {
+        record.misses += 1;
+    }
+    let banned = misses_exceed(&record, BAN_MIN_PROBES, BAN_MISS_RATIO_PERCENT);
+    if banned {
+        let ban = BASE_BAN_NS.saturating_mul(1u64 << record.ban_count.min(32)).min(MAX_BAN_NS);
+        record.banned_until = Some(now.saturating_add(ban));
+        record.ban_count = record.ban_count.saturating_add(1);
+        // The next window starts after the ban, with a clean slate
+        record.window_start = now.saturating_add(ban);
+        log_warn!("[probe_guard] {} banned until {} after {} misses in {} lookups", principal, now + ban, record.misses, record.probes);
+    }
+    if let Some(org_id) = found_org_id {
+        if banned || misses_exceed(&record, FLAG_MIN_PROBES, FLAG_MISS_RATIO_PERCENT) {
+            record_org_activity(org_id, principal, now);
+        }
+    }
+    let banned_until = record.banned_until;
+    PROBE_RECORDS.with(|records| records.borrow_mut().insert(principal, record));
+
+    match banned_until.filter(|_| banned) {
+        Some(until) => Err(banned_error(until)),
+        None => Ok(()),
+    }
+}
+
+// A principal that looks like it is guessing serials hit one of the organization's
+fn record_org_activity(org_id: Principal, principal: Principal, now: u64) {
+    ORG_PROBE_ACTIVITY.with(|activity| {
+        let mut activity_mut = activity.borrow_mut();
+        let mut summary = activity_mut.get(&org_id).unwrap_or(OrgProbeActivity {
+            org_id,
+            suspicious_hits: 0,
+            recent_probers: Vec::new(),
+            first_seen_at: now,
+            last_seen_at: now,
+        });
+        summary.suspicious_hits += 1;
+        summary.last_seen_at = now;
+        summary.recent_probers.retain(|p| *p != principal);
+        summary.recent_probers.insert(0, principal);
+        summary.recent_probers.truncate(MAX_RECENT_PROBERS);
+        activity_mut.insert(org_id, summary);
+    });
+}
+
+pub fn org_activity(org_id: Principal) -> Option<OrgProbeActivity> {
+    ORG_PROBE_ACTIVITY.with(|activity| activity.borrow().get(&org_id))
+}
+
+// Principals currently serving a ban
+pub fn list_banned(now: u64) -> Vec<ProbeRecord> {
+    PROBE_RECORDS.with(|records| {
+        records
+            .borrow()
+            .iter()
+            .map(|(_, r)| r)
+            .filter(|r| r.banned_until.is_some_and(|until| until > now))
+            .collect()
+    })
+}
+
+// Lift a ban early; the principal's ban history is kept so a relapse escalates as usual
+pub fn lift_ban(principal: Principal, now: u64) -> Option<ProbeRecord> {
+    PROBE_RECORDS.with(|records| {
+        let mut records_mut = records.borrow_mut();
+        let mut record = records_mut.get(&principal)?;
+        record.banned_until = None;
+        record.window_start = now;
+        record.probes = 0;
+        record.misses = 0;
+        records_mut.insert(principal, record.clone());
+        Some(record)
+    })
+}
+
+// Reset ALL probe records and brand summaries (use with caution)
+pub fn reset_probe_guard() {
+    PROBE_RECORDS.with(|records| {
+        let mut records_mut = records.borrow_mut();
+        let keys: Vec<_> = records_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            records_mut.remove(&key);
+        }
+    });
+    ORG_PROBE_ACTIVITY.with(|activity| {
+        let mut activity_mut = activity.borrow_mut();
+        let keys: Vec<_> = activity_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            activity_mut.remove(&key);
+        }
+    });
+    log_info!("All serial probe records have been reset.");
+}
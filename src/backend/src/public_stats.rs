@@ -0,0 +1,183 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk_timers::set_timer;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::{ProductVerification, PublicBrandStats, PublicStatsTally};
+use crate::org_settings;
+use crate::verification_store;
+
+// Published stats change at most this often, so embedders may cache a response for as long
+pub const PUBLISH_INTERVAL_SECONDS: u64 = 5 * 60;
+
+// Define a unique MemoryId for this structure
+const PUBLIC_STATS_MEM_ID: MemoryId = MemoryId::new(89);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Only opted-in organizations have a tally; opting out removes it
+    static PUBLIC_STATS: RefCell<StableBTreeMap<Principal, PublicStatsTally, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PUBLIC_STATS_MEM_ID))
+        )
+    );
+
+    // Organizations with a publish timer set; heap only, resume_publishing sets them again after an upgrade
+    static PENDING_PUBLISH: RefCell<HashSet<Principal>> = RefCell::new(HashSet::new());
+}
+
+fn get(org_id: Principal) -> Option<PublicStatsTally> {
+    PUBLIC_STATS.with(|stats| stats.borrow().get(&org_id))
+}
+
+fn save(tally: PublicStatsTally) {
+    PUBLIC_STATS.with(|stats| {
+        stats.borrow_mut().insert(tally.org_id, tally);
+    });
+}
+
+pub fn get_published(org_id: Principal) -> Option<PublicBrandStats> {
+    get(org_id).and_then(|tally| tally.published)
+}
+
+fn add_verification(tally: &mut PublicStatsTally, verification: &ProductVerification) {
+    tally.total_verifications += 1;
+    if let Some(country) = verification.country_code.as_ref() {
+        if let Err(index) = tally.country_codes.binary_search(country) {
+            tally.country_codes.insert(index, country.clone());
+        }
+    }
+}
+
+// Non-test products of the organization that have at least one stored verification
+fn verified_product_count(org_id: Principal) -> u64 {
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id && product.is_test != Some(true))
+            .map(|(id, _)| id)
+            .collect()
+    });
+    product_ids.into_iter().filter(|id| verification_store::has_verifications(*id)).count() as u64
+}
+
+// Publish the organization's current tallies. The version only moves when a number does.
+fn publish(org_id: Principal) -> Option<PublicBrandStats> {
+    let mut tally = get(org_id)?;
+    let now = clock::now();
+    let total_verifications = tally.total_verifications;
+    let distinct_products = verified_product_count(org_id);
+    let countries_reached = tally.country_codes.len() as u64;
+    let version = match tally.published.as_ref() {
+        Some(previous)
+            if previous.total_verifications == total_verifications
+                && previous.distinct_products == distinct_products
+                && previous.countries_reached == countries_reached =>
+        {
+            previous.version
+        }
+        Some(previous) => now.max(previous.version + 1),
+        None => now,
+    };
+    let stats = PublicBrandStats {
+        total_verifications,
+        distinct_products,
+        countries_reached,
+        version,
+        published_at: now,
+    };
+    tally.published = Some(stats.clone());
+    tally.dirty = false;
+    save(tally);
+    Some(stats)
+}
+
+fn schedule_publish(org_id: Principal) {
+    let newly_pending = PENDING_PUBLISH.with(|pending| pending.borrow_mut().insert(org_id));
+    if !newly_pending {
+        return;
+    }
+    set_timer(Duration::from_secs(PUBLISH_INTERVAL_SECONDS), move || {
+        PENDING_PUBLISH.with(|pending| pending.borrow_mut().remove(&org_id));
+        publish(org_id);
+    });
+}
+
+// A genuine, non-test verification was recorded for the organization
+pub fn on_verification_recorded(org_id: Principal, verification: &ProductVerification) {
+    if !org_settings::public_stats_enabled(org_id) {
+        return;
+    }
+    let mut tally = match get(org_id) {
+        Some(tally) => tally,
+        None => return,
+    };
+    add_verification(&mut tally, verification);
+    tally.dirty = true;
+    save(tally);
+    schedule_publish(org_id);
+}
+
+// Start tallying for an organization that opted in, counting the verifications already stored, and
+// publish right away so the widget works from the start
+pub fn enable(org_id: Principal) -> Option<PublicBrandStats> {
+    let mut tally = PublicStatsTally {
+        org_id,
+        total_verifications: 0,
+        country_codes: Vec::new(),
+        dirty: false,
+        published: get_published(org_id),
+    };
+    let product_ids: Vec<Principal> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id && product.is_test != Some(true))
+            .map(|(id, _)| id)
+            .collect()
+    });
+    for product_id in product_ids {
+        for verification in verification_store::list_for_product(product_id) {
+            if verification.is_test != Some(true) {
+                add_verification(&mut tally, &verification);
+            }
+        }
+    }
+    save(tally);
+    publish(org_id)
+}
+
+pub fn disable(org_id: Principal) {
+    PUBLIC_STATS.with(|stats| stats.borrow_mut().remove(&org_id));
+}
+
+// Set the publish timers lost in an upgrade
+pub fn resume_publishing() {
+    let dirty: Vec<Principal> = PUBLIC_STATS.with(|stats| {
+        stats.borrow().iter().filter(|(_, tally)| tally.dirty).map(|(org_id, _)| org_id).collect()
+    });
+    for org_id in dirty {
+        schedule_publish(org_id);
+    }
+}
+
+// Reset ALL public brand stats (use with caution)
+pub fn reset_public_stats() {
+    PUBLIC_STATS.with(|stats| {
+        let mut stats_mut = stats.borrow_mut();
+        let keys: Vec<_> = stats_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            stats_mut.remove(&key);
+        }
+    });
+    log_info!("All public brand stats have been reset.");
+}
@@ -0,0 +1,225 @@
+use std::cell::RefCell;
+
+use candid::{encode_one, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::api::OrgUsageResponse;
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::{OrgQuota, OrgUsage};
+use crate::plans;
+use crate::serial_store;
+use crate::verification_store;
+
+// Platform defaults used when an admin has not set a quota for the organization.
+// Serial numbers default to the allowance of the organization's plan instead.
+pub const DEFAULT_MAX_OUTCALLS_PER_DAY: u32 = 500;
+pub const DEFAULT_MAX_STORAGE_BYTES: u64 = 256 * 1024 * 1024; // 256 MiB
+
+// Usage windows (in nanoseconds, matching clock::now())
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MONTH_NS: u64 = 30 * DAY_NS;
+
+// Define unique Memory IDs for the structures in this module
+const ORG_USAGE_MEM_ID: MemoryId = MemoryId::new(20);
+const ORG_QUOTAS_MEM_ID: MemoryId = MemoryId::new(21);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static ORG_USAGE: RefCell<StableBTreeMap<Principal, OrgUsage, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_USAGE_MEM_ID))
+        )
+    );
+
+    static ORG_QUOTAS: RefCell<StableBTreeMap<Principal, OrgQuota, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ORG_QUOTAS_MEM_ID))
+        )
+    );
+}
+
+pub fn get_quota(org_id: Principal) -> Option<OrgQuota> {
+    ORG_QUOTAS.with(|quotas| quotas.borrow().get(&org_id))
+}
+
+pub fn save_quota(quota: OrgQuota) {
+    ORG_QUOTAS.with(|quotas| {
+        quotas.borrow_mut().insert(quota.org_id, quota);
+    });
+}
+
+fn outcall_limit(org_id: Principal) -> u32 {
+    get_quota(org_id)
+        .and_then(|q| q.max_outcalls_per_day)
+        .unwrap_or(DEFAULT_MAX_OUTCALLS_PER_DAY)
+}
+
+fn serial_limit(org_id: Principal) -> u32 {
+    get_quota(org_id)
+        .and_then(|q| q.max_serials_per_month)
+        .unwrap_or_else(|| plans::limits(org_id).max_serials_per_month)
+}
+
+fn storage_limit(org_id: Principal) -> u64 {
+    get_quota(org_id)
+        .and_then(|q| q.max_storage_bytes)
+        .unwrap_or(DEFAULT_MAX_STORAGE_BYTES)
+}
+
+// Current usage with expired windows rolled over (not persisted)
+fn current_usage(org_id: Principal, now: u64) -> OrgUsage {
+    let mut usage = ORG_USAGE
+        .with(|usage| usage.borrow().get(&org_id))
+        .unwrap_or(OrgUsage {
+            org_id,
+            outcalls_window_start: now,
+            outcalls_in_window: 0,
+            oversized_responses_in_window: None,
+            serials_window_start: now,
+            serials_in_window: 0,
+        });
+
+    if now >= usage.outcalls_window_start.saturating_add(DAY_NS) {
+        usage.outcalls_window_start = now;
+        usage.outcalls_in_window = 0;
+        usage.oversized_responses_in_window = None;
+    }
+    if now >= usage.serials_window_start.saturating_add(MONTH_NS) {
+        usage.serials_window_start = now;
+        usage.serials_in_window = 0;
+    }
+    usage
+}
+
+fn save_usage(usage: OrgUsage) {
+    ORG_USAGE.with(|store| {
+        store.borrow_mut().insert(usage.org_id, usage);
+    });
+}
+
+// Reserve `count` HTTPS outcalls for the organization, failing without side effects if the daily quota would be exceeded
+pub fn consume_outcalls(org_id: Principal, count: u32) -> Result<(), ApiError> {
+    let mut usage = current_usage(org_id, clock::now());
+    let limit = outcall_limit(org_id);
+    if usage.outcalls_in_window.saturating_add(count) > limit {
+        log_warn!("[consume_outcalls] Org {} reached its outcall quota ({}/{})", org_id, usage.outcalls_in_window, limit);
+        return Err(ApiError::quota_exceeded(&format!(
+            "Daily outcall quota of {} reached for this organization",
+            limit
+        )));
+    }
+    usage.outcalls_in_window += count;
+    save_usage(usage);
+    Ok(())
+}
+
+// Reserve `count` new serial numbers against the monthly quota
+pub fn consume_serials(org_id: Principal, count: u32) -> Result<(), ApiError> {
+    let mut usage = current_usage(org_id, clock::now());
+    let limit = serial_limit(org_id);
+    if usage.serials_in_window.saturating_add(count) > limit {
+        log_warn!("[consume_serials] Org {} reached its serial quota ({}/{})", org_id, usage.serials_in_window, limit);
+        return Err(ApiError::quota_exceeded(&format!(
+            "Monthly serial number quota of {} reached for this organization",
+            limit
+        )));
+    }
+    usage.serials_in_window += count;
+    save_usage(usage);
+    Ok(())
+}
+
+// Give back serials reserved by consume_serials for an operation that was undone
+pub fn release_serials(org_id: Principal, count: u32) {
+    let mut usage = current_usage(org_id, clock::now());
+    usage.serials_in_window = usage.serials_in_window.saturating_sub(count);
+    save_usage(usage);
+}
+
+// Count an outcall of the organization that was rejected because its response was too large
+pub fn record_oversized_response(org_id: Principal) {
+    let mut usage = current_usage(org_id, clock::now());
+    usage.oversized_responses_in_window = Some(usage.oversized_responses_in_window.unwrap_or(0).saturating_add(1));
+    save_usage(usage);
+}
+
+// Approximate stable storage held by the organization: its products plus their serial and verification records
+pub fn estimate_storage_bytes(org_id: Principal) -> u64 {
+    let products: Vec<_> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(_, product)| product.org_id == org_id)
+            .map(|(_, product)| product)
+            .collect()
+    });
+
+    products
+        .iter()
+        .map(|product| {
+            let product_bytes = encode_one(product).map(|b| b.len() as u64).unwrap_or(0);
+            let serial_bytes = serial_store::stored_bytes_for_product(product.id);
+            let verification_bytes = verification_store::stored_bytes_for_product(product.id);
+            product_bytes + serial_bytes + verification_bytes
+        })
+        .sum()
+}
+
+pub fn ensure_storage_available(org_id: Principal) -> Result<(), ApiError> {
+    let used = estimate_storage_bytes(org_id);
+    let limit = storage_limit(org_id);
+    if used >= limit {
+        return Err(ApiError::quota_exceeded(&format!(
+            "Storage quota of {} bytes reached for this organization",
+            limit
+        )));
+    }
+    Ok(())
+}
+
+pub fn get_usage(org_id: Principal) -> OrgUsageResponse {
+    let usage = current_usage(org_id, clock::now());
+    OrgUsageResponse {
+        org_id,
+        outcalls_today: usage.outcalls_in_window,
+        outcalls_limit: outcall_limit(org_id),
+        outcalls_window_resets_at: usage.outcalls_window_start.saturating_add(DAY_NS),
+        oversized_responses_today: usage.oversized_responses_in_window.unwrap_or(0),
+        serials_this_month: usage.serials_in_window,
+        serials_limit: serial_limit(org_id),
+        serials_window_resets_at: usage.serials_window_start.saturating_add(MONTH_NS),
+        storage_bytes: estimate_storage_bytes(org_id),
+        storage_limit: storage_limit(org_id),
+    }
+}
+
+// Remove the organization's quota and usage counters when it is deleted
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    let quota = ORG_QUOTAS.with(|quotas| quotas.borrow_mut().remove(&org_id)).is_some() as u32;
+    let usage = ORG_USAGE.with(|usage| usage.borrow_mut().remove(&org_id)).is_some() as u32;
+    quota + usage
+}
+
+// Reset ALL quotas and usage counters (use with caution)
+pub fn reset_quotas() {
+    ORG_USAGE.with(|usage| {
+        let mut usage_mut = usage.borrow_mut();
+        let keys: Vec<_> = usage_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            usage_mut.remove(&key);
+        }
+    });
+    ORG_QUOTAS.with(|quotas| {
+        let mut quotas_mut = quotas.borrow_mut();
+        let keys: Vec<_> = quotas_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            quotas_mut.remove(&key);
+        }
+    });
+    log_info!("All organization quotas and usage counters have been reset.");
+}
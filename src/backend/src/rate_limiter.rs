@@ -7,29 +7,57 @@ use ic_cdk::api;
 use ic_stable_structures::{DefaultMemoryImpl, Storable, StableBTreeMap, memory_manager::{MemoryId, MemoryManager, VirtualMemory}};
 
 use crate::api::RateLimitInfo;
+use crate::clock;
+use crate::config;
 use crate::error::ApiError;
 // Import the shared memory manager
 use crate::global_state::MEMORY_MANAGER;
 
-// Default values for rate limiting
-const MAX_ATTEMPTS_PER_WINDOW: u32 = 5;
-const WINDOW_DURATION_SECONDS: u64 = 60 * 5; // 5 minutes
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
 
-// Define a unique MemoryId for this structure
+// Lockouts start at one window and double per repeated exhaustion, up to this cap
+const MAX_LOCKOUT_NS: u64 = 24 * 60 * 60 * NANOS_PER_SECOND;
+// A principal's penalty level is forgiven after this long without exhausting a limit
+const PENALTY_DECAY_NS: u64 = 24 * 60 * 60 * NANOS_PER_SECOND;
+
+// Define unique Memory IDs for the structures in this module
 const RATE_LIMIT_MEM_ID: MemoryId = MemoryId::new(6);
+const RATE_LIMIT_PENALTIES_MEM_ID: MemoryId = MemoryId::new(33);
 
 // Type definitions for rate limiting
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub struct RateLimitEntry {
     pub principal_id: Principal,
     pub product_id: Principal, 
-    pub attempts: u32,
+    pub attempts: u32, // Attempts in the window starting at window_start
     pub window_start: u64,
     pub last_attempt: u64,
+    pub previous_window_attempts: Option<u32>, // None for entries written before the sliding window
 }
 
 impl Storable for RateLimitEntry {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// Escalating lockout state, tracked per principal across all products
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct RateLimitPenalty {
+    pub principal_id: Principal,
+    pub level: u32, // Number of recent exhaustions; each one doubles the next lockout
+    pub locked_until: u64,
+    pub last_violation_at: u64,
+}
+
+impl Storable for RateLimitPenalty {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Owned(encode_one(self).expect("Failed to encode"))
     }
 
@@ -48,7 +76,7 @@ pub struct RateLimitKey {
 }
 
 impl Storable for RateLimitKey {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Owned(encode_one(self).expect("Failed to encode"))
     }
 
@@ -69,6 +97,21 @@ thread_local! {
             MEMORY_MANAGER.with(|m| m.borrow().get(RATE_LIMIT_MEM_ID))
         )
     );
+
+    static RATE_LIMIT_PENALTIES: RefCell<StableBTreeMap<Principal, RateLimitPenalty, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(RATE_LIMIT_PENALTIES_MEM_ID))
+        )
+    );
+}
+
+// Attempts per window and window length (in nanoseconds, matching clock::now()) from the canister config
+fn current_limits() -> (u32, u64) {
+    let limits = config::rate_limit_config();
+    (
+        limits.max_attempts_per_window,
+        limits.window_duration_seconds.saturating_mul(NANOS_PER_SECOND).max(1),
+    )
 }
 
 // Helper function to create a rate limit key
@@ -79,89 +122,131 @@ fn create_rate_limit_key(user_id: Principal, product_id: Principal) -> RateLimit
     }
 }
 
+fn new_entry(user_id: Principal, product_id: Principal, current_time: u64) -> RateLimitEntry {
+    RateLimitEntry {
+        principal_id: user_id,
+        product_id,
+        attempts: 0,
+        window_start: current_time,
+        last_attempt: 0,
+        previous_window_attempts: Some(0),
+    }
+}
+
+// Advance the entry to the window containing current_time, carrying the last window's count over
+fn roll_window(entry: &mut RateLimitEntry, current_time: u64, window_duration: u64) {
+    let elapsed_windows = current_time.saturating_sub(entry.window_start) / window_duration;
+    if elapsed_windows == 0 {
+        return;
+    }
+    entry.previous_window_attempts = Some(if elapsed_windows == 1 { entry.attempts } else { 0 });
+    entry.attempts = 0;
+    entry.window_start = entry.window_start.saturating_add(elapsed_windows.saturating_mul(window_duration));
+}
+
+// Sliding-window estimate: the previous window's attempts weighted by how much of it still overlaps
+// the trailing window, plus the attempts in the current one
+fn estimated_attempts(entry: &RateLimitEntry, current_time: u64, window_duration: u64) -> u64 {
+    let elapsed = current_time.saturating_sub(entry.window_start).min(window_duration);
+    let previous = entry.previous_window_attempts.unwrap_or(0) as u64;
+    let carried = (previous * (window_duration - elapsed)).div_ceil(window_duration);
+    carried + entry.attempts as u64
+}
+
+fn get_penalty(user_id: Principal) -> Option<RateLimitPenalty> {
+    RATE_LIMIT_PENALTIES.with(|penalties| penalties.borrow().get(&user_id))
+}
+
+// Level still in effect, i.e. not yet forgiven by PENALTY_DECAY_NS of good behaviour
+fn active_penalty_level(penalty: &RateLimitPenalty, current_time: u64) -> u32 {
+    if current_time >= penalty.locked_until.saturating_add(PENALTY_DECAY_NS) {
+        0
+    } else {
+        penalty.level
+    }
+}
+
+// Lock the principal out for window_duration * 2^level and escalate its level
+fn apply_penalty(user_id: Principal, current_time: u64, window_duration: u64) -> RateLimitPenalty {
+    let level = get_penalty(user_id).map_or(0, |p| active_penalty_level(&p, current_time));
+    let lockout = window_duration
+        .saturating_mul(1u64 << level.min(32))
+        .min(MAX_LOCKOUT_NS);
+    let penalty = RateLimitPenalty {
+        principal_id: user_id,
+        level: level.saturating_add(1),
+        locked_until: current_time.saturating_add(lockout),
+        last_violation_at: current_time,
+    };
+    RATE_LIMIT_PENALTIES.with(|penalties| penalties.borrow_mut().insert(user_id, penalty.clone()));
+    log_warn!("[rate_limiter] {} locked out until {} (penalty level {})", user_id, penalty.locked_until, penalty.level);
+    penalty
+}
+
+fn build_info(
+    entry: &RateLimitEntry,
+    penalty: Option<&RateLimitPenalty>,
+    current_time: u64,
+    max_attempts: u32,
+    window_duration: u64,
+) -> RateLimitInfo {
+    let locked_until = penalty.map(|p| p.locked_until).filter(|until| *until > current_time);
+    let estimated = estimated_attempts(entry, current_time, window_duration);
+    let remaining_attempts = if locked_until.is_some() {
+        0
+    } else {
+        (max_attempts as u64).saturating_sub(estimated) as u32
+    };
+
+    RateLimitInfo {
+        remaining_attempts,
+        reset_time: locked_until.unwrap_or(entry.window_start + window_duration),
+        current_window_start: entry.window_start,
+        penalty_level: penalty.map_or(0, |p| active_penalty_level(p, current_time)),
+        locked_until,
+    }
+}
+
 // Check if a user is rate limited for verifying a specific product
 pub fn check_rate_limit(user_id: Principal, product_id: Principal) -> Result<RateLimitInfo, ApiError> {
+    let (max_attempts, window_duration) = current_limits();
     let key = create_rate_limit_key(user_id, product_id);
-    let current_time = api::time();
+    let current_time = clock::now();
 
-    RATE_LIMITS.with(|rate_limits| {
-        let rate_limits_ref = rate_limits.borrow();
-        
-        let entry = match rate_limits_ref.get(&key) {
-            Some(mut entry) => {
-                if current_time > entry.window_start + WINDOW_DURATION_SECONDS {
-                    RateLimitEntry {
-                        attempts: 0,
-                        window_start: current_time,
-                        ..entry.clone()
-                    }
-                } else {
-                    entry.clone()
-                }
-            },
-            None => {
-                RateLimitEntry {
-                    principal_id: user_id,
-                    product_id,
-                    attempts: 0,
-                    window_start: current_time,
-                    last_attempt: 0,
-                }
-            }
-        };
-
-        let remaining_attempts = if entry.attempts >= MAX_ATTEMPTS_PER_WINDOW {
-            0
-        } else {
-            MAX_ATTEMPTS_PER_WINDOW - entry.attempts
-        };
-
-        let reset_time = entry.window_start + WINDOW_DURATION_SECONDS;
-
-        Ok(RateLimitInfo {
-            remaining_attempts,
-            reset_time,
-            current_window_start: entry.window_start,
-        })
-    })
+    let mut entry = RATE_LIMITS
+        .with(|rate_limits| rate_limits.borrow().get(&key))
+        .unwrap_or_else(|| new_entry(user_id, product_id, current_time));
+    roll_window(&mut entry, current_time, window_duration);
+
+    let penalty = get_penalty(user_id);
+    Ok(build_info(&entry, penalty.as_ref(), current_time, max_attempts, window_duration))
 }
 
-// Record an attempt and check if rate limited
+// Record an attempt and check if rate limited. Exhausting the limit locks the principal out of all
+// verifications, for longer each time it happens again before the penalty decays.
 pub fn record_verification_attempt(user_id: Principal, product_id: Principal) -> Result<RateLimitInfo, ApiError> {
+    let (max_attempts, window_duration) = current_limits();
     let key = create_rate_limit_key(user_id, product_id);
-    let current_time = api::time();
+    let current_time = clock::now();
+
+    let penalty = get_penalty(user_id);
+    if let Some(p) = penalty.as_ref().filter(|p| p.locked_until > current_time) {
+        return Err(ApiError::rate_limited(p.locked_until));
+    }
 
     RATE_LIMITS.with(|rate_limits| {
         let mut rate_limits_mut = rate_limits.borrow_mut();
         
         // Get or create rate limit entry
-        let mut entry = match rate_limits_mut.get(&key) {
-            Some(mut entry) => {
-                // Check if window has expired and reset if needed
-                if current_time > entry.window_start + WINDOW_DURATION_SECONDS {
-                    // Reset the window
-                    entry.window_start = current_time;
-                    entry.attempts = 0;
-                }
-                entry
-            },
-            None => {
-                // Create new entry
-                RateLimitEntry {
-                    principal_id: user_id,
-                    product_id,
-                    attempts: 0,
-                    window_start: current_time,
-                    last_attempt: 0,
-                }
-            }
-        };
+        let mut entry = rate_limits_mut
+            .get(&key)
+            .unwrap_or_else(|| new_entry(user_id, product_id, current_time));
+        roll_window(&mut entry, current_time, window_duration);
 
         // Check if rate limited
-        if entry.attempts >= MAX_ATTEMPTS_PER_WINDOW {
-            return Err(ApiError::invalid_input(
-                &format!("Rate limit exceeded. Try again after {}", entry.window_start + WINDOW_DURATION_SECONDS)
-            ));
+        if estimated_attempts(&entry, current_time, window_duration) >= max_attempts as u64 {
+            let penalty = apply_penalty(user_id, current_time, window_duration);
+            return Err(ApiError::rate_limited(penalty.locked_until));
         }
 
         // Increment attempts and update last attempt time
@@ -171,26 +256,20 @@ pub fn record_verification_attempt(user_id: Principal, product_id: Principal) ->
         // Update entry
         rate_limits_mut.insert(key, entry.clone());
 
-        let remaining_attempts = if entry.attempts >= MAX_ATTEMPTS_PER_WINDOW {
-            0
-        } else {
-            MAX_ATTEMPTS_PER_WINDOW - entry.attempts
-        };
-
-        let reset_time = entry.window_start + WINDOW_DURATION_SECONDS;
-
-        Ok(RateLimitInfo {
-            remaining_attempts,
-            reset_time,
-            current_window_start: entry.window_start,
-        })
+        Ok(build_info(&entry, penalty.as_ref(), current_time, max_attempts, window_duration))
     })
 }
 
+// A batch verification counts as a single attempt in a per-user bucket keyed by the canister's own id,
+// so warehouse audits neither consume nor depend on per-product limits
+pub fn record_batch_verification_attempt(user_id: Principal) -> Result<RateLimitInfo, ApiError> {
+    record_verification_attempt(user_id, api::id())
+}
+
 // Record a successful verification attempt
 pub fn record_successful_verification(user_id: Principal, product_id: Principal) {
     let key = create_rate_limit_key(user_id, product_id);
-    let current_time = api::time();
+    let current_time = clock::now();
 
     RATE_LIMITS.with(|rate_limits| {
         let mut rate_limits_mut = rate_limits.borrow_mut();
@@ -206,13 +285,14 @@ pub fn record_successful_verification(user_id: Principal, product_id: Principal)
     });
 }
 
-// Reset rate limit for a user and product
+// Reset rate limit for a user and product, lifting any lockout the user is serving
 pub fn reset_rate_limit(user_id: Principal, product_id: Principal) {
     let key = create_rate_limit_key(user_id, product_id);
     RATE_LIMITS.with(|rate_limits| {
         let mut rate_limits_mut = rate_limits.borrow_mut();
         rate_limits_mut.remove(&key);
     });
+    RATE_LIMIT_PENALTIES.with(|penalties| penalties.borrow_mut().remove(&user_id));
 }
 
 // Reset ALL rate limits (use with caution)
@@ -224,5 +304,68 @@ pub fn reset_rate_limits() {
             rate_limits_mut.remove(&key);
         }
     });
-    ic_cdk::print("ℹ️ All rate limits have been reset.");
-} 
\ No newline at end of file
+    RATE_LIMIT_PENALTIES.with(|penalties| {
+        let mut penalties_mut = penalties.borrow_mut();
+        let keys: Vec<_> = penalties_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            penalties_mut.remove(&key);
+        }
+    });
+    log_info!("All rate limits have been reset.");
+} 
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    const START: u64 = 1_700_000_000 * NANOS_PER_SECOND;
+
+    fn mock_clock() -> Rc<MockClock> {
+        let mock = Rc::new(MockClock::new(START));
+        clock::set_clock(mock.clone());
+        mock
+    }
+
+    #[test]
+    fn exhausting_the_window_locks_the_user_out_of_every_product() {
+        let mock = mock_clock();
+        let user = Principal::from_slice(&[1]);
+        let product = Principal::from_slice(&[2]);
+        let other_product = Principal::from_slice(&[3]);
+        let (max_attempts, window_duration) = current_limits();
+
+        for _ in 0..max_attempts {
+            assert!(record_verification_attempt(user, product).is_ok());
+        }
+        assert!(matches!(record_verification_attempt(user, product), Err(ApiError::RateLimited { .. })));
+
+        mock.advance(window_duration - 1);
+        assert!(matches!(record_verification_attempt(user, other_product), Err(ApiError::RateLimited { .. })));
+
+        // Past the lockout, the sliding window still counts the attempts that caused it until it rolls off
+        mock.set(START + 2 * window_duration);
+        let info = record_verification_attempt(user, product).unwrap();
+        assert_eq!(info.penalty_level, 1);
+        assert_eq!(info.locked_until, None);
+    }
+
+    #[test]
+    fn penalty_level_decays_after_a_quiet_day() {
+        let mock = mock_clock();
+        let user = Principal::from_slice(&[4]);
+        let product = Principal::from_slice(&[5]);
+        let (max_attempts, window_duration) = current_limits();
+
+        for _ in 0..=max_attempts {
+            let _ = record_verification_attempt(user, product);
+        }
+        assert_eq!(check_rate_limit(user, product).unwrap().penalty_level, 1);
+
+        mock.set(START + window_duration + PENALTY_DECAY_NS - 1);
+        assert_eq!(check_rate_limit(user, product).unwrap().penalty_level, 1);
+        mock.advance(1);
+        assert_eq!(check_rate_limit(user, product).unwrap().penalty_level, 0);
+    }
+}
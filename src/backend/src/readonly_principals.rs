@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::ReadonlyPrincipal;
+
+pub const MAX_ACTIVE_PER_ORG: usize = 10;
+pub const MAX_LABEL_LENGTH: usize = 100;
+
+// Define a unique MemoryId for this structure
+const READONLY_PRINCIPALS_MEM_ID: MemoryId = MemoryId::new(71);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by the service principal; revoked entries are kept so a principal is never reused
+    static READONLY_PRINCIPALS: RefCell<StableBTreeMap<Principal, ReadonlyPrincipal, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(READONLY_PRINCIPALS_MEM_ID))
+        )
+    );
+}
+
+pub fn save(entry: ReadonlyPrincipal) {
+    READONLY_PRINCIPALS.with(|entries| {
+        entries.borrow_mut().insert(entry.principal, entry);
+    });
+}
+
+pub fn get(principal: Principal) -> Option<ReadonlyPrincipal> {
+    READONLY_PRINCIPALS.with(|entries| entries.borrow().get(&principal))
+}
+
+// The grant the caller holds, unless it was revoked
+pub fn get_active(principal: Principal) -> Option<ReadonlyPrincipal> {
+    get(principal).filter(|entry| entry.revoked_at.is_none())
+}
+
+// All principals of the organization, revoked ones included, newest first
+pub fn list_for_org(org_id: Principal) -> Vec<ReadonlyPrincipal> {
+    let mut entries: Vec<ReadonlyPrincipal> = READONLY_PRINCIPALS.with(|entries| {
+        entries
+            .borrow()
+            .iter()
+            .map(|(_, entry)| entry)
+            .filter(|entry| entry.org_id == org_id)
+            .collect()
+    });
+    entries.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    entries
+}
+
+pub fn active_count_for_org(org_id: Principal) -> usize {
+    list_for_org(org_id).iter().filter(|entry| entry.revoked_at.is_none()).count()
+}
+
+// Reset ALL read-only principals (use with caution)
+pub fn reset_readonly_principals() {
+    READONLY_PRINCIPALS.with(|entries| {
+        let mut entries_mut = entries.borrow_mut();
+        let keys: Vec<_> = entries_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            entries_mut.remove(&key);
+        }
+    });
+    log_info!("All read-only principals have been reset.");
+}
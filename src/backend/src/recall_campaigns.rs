@@ -0,0 +1,324 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk_timers::set_timer;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use serde::Serialize;
+
+use crate::clock;
+use crate::config;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS, USERS};
+use crate::models::{
+    OutboxDeliveryStatus, ProductRecall, RecallCampaign, RecallCampaignStatus, RecallNotice, RecallRelayStatus, RecallScope,
+    UserNotificationKind,
+};
+use crate::notifications;
+use crate::outbox;
+use crate::owned_products;
+use crate::print_jobs;
+use crate::quotas;
+use crate::utils::generate_unique_principal;
+use crate::webhooks;
+
+// Notices sent by one step; each writes an inbox entry and may queue a relay message
+const NOTICES_PER_STEP: usize = 100;
+
+// Define unique Memory IDs for the structures in this module
+const RECALL_CAMPAIGNS_MEM_ID: MemoryId = MemoryId::new(82);
+const RECALL_NOTICES_MEM_ID: MemoryId = MemoryId::new(83);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// Body posted to the email relay, which renders and sends the recall email
+#[derive(Serialize, Clone, Debug)]
+struct RecallEmailPayload {
+    event: String,
+    to: String,
+    campaign_id: String,
+    product_name: String,
+    serial_nos: Vec<String>,
+    reason: String,
+    instructions: Option<String>,
+    issued_at: u64,
+}
+
+thread_local! {
+    static RECALL_CAMPAIGNS: RefCell<StableBTreeMap<Principal, RecallCampaign, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(RECALL_CAMPAIGNS_MEM_ID))
+        )
+    );
+
+    // Keyed by (campaign, owner); one notice per owner, however many covered serials they hold
+    static RECALL_NOTICES: RefCell<StableBTreeMap<(Principal, Principal), RecallNotice, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(RECALL_NOTICES_MEM_ID))
+        )
+    );
+
+    static STEP_SCHEDULED: RefCell<bool> = const { RefCell::new(false) };
+}
+
+fn save_campaign(campaign: RecallCampaign) {
+    RECALL_CAMPAIGNS.with(|campaigns| {
+        campaigns.borrow_mut().insert(campaign.id, campaign);
+    });
+}
+
+pub fn get_campaign(campaign_id: Principal) -> Option<RecallCampaign> {
+    RECALL_CAMPAIGNS.with(|campaigns| campaigns.borrow().get(&campaign_id))
+}
+
+// Campaigns of an organization, newest first
+pub fn list_for_org(org_id: Principal) -> Vec<RecallCampaign> {
+    let mut campaigns: Vec<RecallCampaign> = RECALL_CAMPAIGNS.with(|campaigns| {
+        campaigns
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .filter(|c| c.org_id == org_id)
+            .collect()
+    });
+    campaigns.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    campaigns
+}
+
+pub fn list_notices(campaign_id: Principal) -> Vec<RecallNotice> {
+    RECALL_NOTICES.with(|notices| {
+        notices
+            .borrow()
+            .range((campaign_id, Principal::management_canister())..)
+            .take_while(|((id, _), _)| *id == campaign_id)
+            .map(|(_, notice)| notice)
+            .collect()
+    })
+}
+
+// Whether the campaign's scope covers the serial
+pub fn covers(campaign: &RecallCampaign, serial_no: Principal) -> bool {
+    match &campaign.scope {
+        RecallScope::Product => true,
+        RecallScope::PrintJob { print_job_id } => print_jobs::get_print_job(*print_job_id)
+            .is_some_and(|job| job.entries.iter().any(|entry| entry.serial_no == serial_no)),
+        RecallScope::Serials { serial_nos } => serial_nos.contains(&serial_no),
+    }
+}
+
+// The recall an open campaign holds on the serial, newest campaign first
+pub fn active_recall_for_serial(product_id: Principal, serial_no: Principal) -> Option<ProductRecall> {
+    let mut campaigns: Vec<RecallCampaign> = RECALL_CAMPAIGNS.with(|campaigns| {
+        campaigns
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .filter(|c| c.product_id == product_id && c.status != RecallCampaignStatus::Closed)
+            .collect()
+    });
+    campaigns.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    campaigns.into_iter().find(|c| covers(c, serial_no)).map(|c| c.recall)
+}
+
+// Start a campaign: record one pending notice per owner of a covered serial and send them in steps
+// after this call returns
+pub fn start(org_id: Principal, product_id: Principal, scope: RecallScope, recall: ProductRecall) -> RecallCampaign {
+    let now = recall.issued_at;
+    let mut campaign = RecallCampaign {
+        id: generate_unique_principal(product_id),
+        org_id,
+        product_id,
+        scope,
+        recall,
+        status: RecallCampaignStatus::Sending,
+        affected_owners: 0,
+        notices_sent: 0,
+        created_at: now,
+        closed_at: None,
+        closed_by: None,
+        updated_at: now,
+    };
+
+    let mut notices: Vec<RecallNotice> = Vec::new();
+    for entry in owned_products::list_for_product(product_id) {
+        if !covers(&campaign, entry.serial_no) {
+            continue;
+        }
+        match notices.iter_mut().find(|n| n.user_id == entry.user_id) {
+            Some(notice) => notice.serial_nos.push(entry.serial_no),
+            None => notices.push(RecallNotice {
+                campaign_id: campaign.id,
+                user_id: entry.user_id,
+                serial_nos: vec![entry.serial_no],
+                notification_id: None,
+                relay_delivery_id: None,
+                relay_queued_at: None,
+                relay_status: None,
+                sent_at: None,
+            }),
+        }
+    }
+    campaign.affected_owners = notices.len() as u64;
+    if notices.is_empty() {
+        campaign.status = RecallCampaignStatus::Active;
+    }
+    RECALL_NOTICES.with(|stored| {
+        let mut stored_mut = stored.borrow_mut();
+        for notice in notices {
+            stored_mut.insert((notice.campaign_id, notice.user_id), notice);
+        }
+    });
+    save_campaign(campaign.clone());
+    schedule_step();
+    campaign
+}
+
+pub fn close(campaign_id: Principal, closed_by: Principal, now: u64) -> Option<RecallCampaign> {
+    let mut campaign = get_campaign(campaign_id).filter(|c| c.status != RecallCampaignStatus::Closed)?;
+    campaign.status = RecallCampaignStatus::Closed;
+    campaign.closed_at = Some(now);
+    campaign.closed_by = Some(closed_by);
+    campaign.updated_at = now;
+    save_campaign(campaign.clone());
+    Some(campaign)
+}
+
+// Relay status of a sent notice. The outbox drops entries once they are delivered and can then
+// hand their ID to a new entry, so only an entry queued at the recorded time is still ours.
+pub fn relay_status(notice: &RecallNotice) -> Option<RecallRelayStatus> {
+    match (notice.relay_status, notice.relay_delivery_id) {
+        (Some(RecallRelayStatus::Queued), Some(delivery_id)) => Some(match outbox::get_delivery(delivery_id)
+            .filter(|delivery| Some(delivery.created_at) == notice.relay_queued_at)
+        {
+            None => RecallRelayStatus::Delivered,
+            Some(delivery) if delivery.status == OutboxDeliveryStatus::DeadLettered => RecallRelayStatus::Failed,
+            Some(_) => RecallRelayStatus::Queued,
+        }),
+        (status, _) => status,
+    }
+}
+
+// Whether the owner opened the inbox notice. Notices pushed out of a full inbox count as unread.
+pub fn is_read(notice: &RecallNotice) -> bool {
+    notice
+        .notification_id
+        .and_then(|notification_id| notifications::get_notification(notice.user_id, notification_id))
+        .is_some_and(|n| n.read_at.is_some())
+}
+
+// Pick up campaigns an upgrade interrupted. Timers do not survive upgrades, so this runs from post_upgrade.
+pub fn resume_campaigns() {
+    let sending = RECALL_CAMPAIGNS.with(|campaigns| {
+        campaigns
+            .borrow()
+            .iter()
+            .any(|(_, c)| c.status == RecallCampaignStatus::Sending)
+    });
+    if sending {
+        schedule_step();
+    }
+}
+
+fn schedule_step() {
+    let already_scheduled = STEP_SCHEDULED.with(|scheduled| scheduled.replace(true));
+    if already_scheduled {
+        return;
+    }
+    set_timer(Duration::ZERO, send_next_notices);
+}
+
+// Send the next batch of notices of the oldest campaign still sending, and schedule another step while any is left
+fn send_next_notices() {
+    STEP_SCHEDULED.with(|scheduled| *scheduled.borrow_mut() = false);
+    let now = clock::now();
+
+    let next = RECALL_CAMPAIGNS.with(|campaigns| {
+        campaigns
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .filter(|c| c.status == RecallCampaignStatus::Sending)
+            .min_by_key(|c| c.created_at)
+    });
+    let mut campaign = match next {
+        Some(c) => c,
+        None => return,
+    };
+    let product_name = PRODUCTS
+        .with(|products| products.borrow().get(&campaign.product_id))
+        .map(|p| p.name)
+        .unwrap_or_default();
+    let relay_url = config::email_relay_url();
+
+    let pending: Vec<RecallNotice> = list_notices(campaign.id)
+        .into_iter()
+        .filter(|n| n.sent_at.is_none())
+        .take(NOTICES_PER_STEP)
+        .collect();
+    for mut notice in pending {
+        notice.notification_id = Some(notifications::notify_user(
+            notice.user_id,
+            UserNotificationKind::ProductRecalled,
+            "Product recall",
+            format!("{} has been recalled: {}", product_name, campaign.recall.reason),
+            Some(campaign.product_id),
+        ));
+        notice.relay_status = Some(RecallRelayStatus::Skipped);
+        let email = USERS.with(|users| users.borrow().get(&notice.user_id)).and_then(|u| u.email);
+        if let (Some(url), Some(to)) = (relay_url.clone(), email) {
+            if quotas::consume_outcalls(campaign.org_id, 1).is_ok() {
+                let payload = RecallEmailPayload {
+                    event: "product_recall".to_string(),
+                    to,
+                    campaign_id: campaign.id.to_string(),
+                    product_name: product_name.clone(),
+                    serial_nos: notice.serial_nos.iter().map(|sn| sn.to_string()).collect(),
+                    reason: campaign.recall.reason.clone(),
+                    instructions: campaign.recall.instructions.clone(),
+                    issued_at: campaign.recall.issued_at,
+                };
+                match serde_json::to_string(&payload) {
+                    Ok(body) => {
+                        let delivery = webhooks::enqueue_webhook(url, body, Some(campaign.org_id));
+                        notice.relay_delivery_id = Some(delivery.id);
+                        notice.relay_queued_at = Some(delivery.created_at);
+                        notice.relay_status = Some(RecallRelayStatus::Queued);
+                    }
+                    Err(e) => log_error!("[send_next_notices] Failed to serialize recall email for campaign {}: {:?}", campaign.id, e),
+                }
+            }
+        }
+        notice.sent_at = Some(now);
+        RECALL_NOTICES.with(|notices| notices.borrow_mut().insert((notice.campaign_id, notice.user_id), notice));
+        campaign.notices_sent += 1;
+    }
+
+    if campaign.notices_sent >= campaign.affected_owners {
+        log_info!("[send_next_notices] Recall campaign {} notified {} owner(s)", campaign.id, campaign.notices_sent);
+        campaign.status = RecallCampaignStatus::Active;
+    }
+    campaign.updated_at = now;
+    save_campaign(campaign);
+
+    resume_campaigns();
+}
+
+// Reset ALL recall campaigns and their notices (use with caution)
+pub fn reset_recall_campaigns() {
+    RECALL_CAMPAIGNS.with(|campaigns| {
+        let mut campaigns_mut = campaigns.borrow_mut();
+        let keys: Vec<_> = campaigns_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            campaigns_mut.remove(&key);
+        }
+    });
+    RECALL_NOTICES.with(|notices| {
+        let mut notices_mut = notices.borrow_mut();
+        let keys: Vec<_> = notices_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            notices_mut.remove(&key);
+        }
+    });
+    log_info!("All recall campaigns have been reset.");
+}
@@ -0,0 +1,81 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{PendingRedemption, RedemptionApprovalStatus};
+
+// Define a unique MemoryId for this structure
+const PENDING_REDEMPTIONS_MEM_ID: MemoryId = MemoryId::new(22);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static PENDING_REDEMPTIONS: RefCell<StableBTreeMap<Principal, PendingRedemption, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PENDING_REDEMPTIONS_MEM_ID))
+        )
+    );
+}
+
+pub fn save_redemption(redemption: PendingRedemption) {
+    PENDING_REDEMPTIONS.with(|redemptions| {
+        redemptions.borrow_mut().insert(redemption.id, redemption);
+    });
+}
+
+pub fn get_redemption(redemption_id: Principal) -> Option<PendingRedemption> {
+    PENDING_REDEMPTIONS.with(|redemptions| redemptions.borrow().get(&redemption_id))
+}
+
+// Latest approval request raised for a verification, if any
+pub fn find_for_verification(verification_id: Principal) -> Option<PendingRedemption> {
+    PENDING_REDEMPTIONS.with(|redemptions| {
+        redemptions
+            .borrow()
+            .iter()
+            .filter(|(_, r)| r.verification_id == verification_id)
+            .map(|(_, r)| r)
+            .max_by_key(|r| r.requested_at)
+    })
+}
+
+// Redemptions of an organization awaiting a decision, oldest first
+pub fn list_pending_for_org(org_id: Principal) -> Vec<PendingRedemption> {
+    let mut pending: Vec<PendingRedemption> = PENDING_REDEMPTIONS.with(|redemptions| {
+        redemptions
+            .borrow()
+            .iter()
+            .filter(|(_, r)| r.org_id == org_id && r.status == RedemptionApprovalStatus::Pending)
+            .map(|(_, r)| r)
+            .collect()
+    });
+    pending.sort_by_key(|a| a.requested_at);
+    pending
+}
+
+pub fn list_for_user(user_id: Principal) -> Vec<PendingRedemption> {
+    PENDING_REDEMPTIONS.with(|redemptions| {
+        redemptions
+            .borrow()
+            .iter()
+            .filter(|(_, r)| r.user_id == user_id)
+            .map(|(_, r)| r)
+            .collect()
+    })
+}
+
+// Reset ALL redemption approval requests (use with caution)
+pub fn reset_pending_redemptions() {
+    PENDING_REDEMPTIONS.with(|redemptions| {
+        let mut redemptions_mut = redemptions.borrow_mut();
+        let keys: Vec<_> = redemptions_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            redemptions_mut.remove(&key);
+        }
+    });
+    log_info!("All pending redemptions have been reset.");
+}
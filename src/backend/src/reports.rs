@@ -0,0 +1,451 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use candid::Principal;
+use ic_cdk_timers::set_timer_interval;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use k256::sha2::{Digest, Sha256};
+use rand::prelude::StdRng;
+use serde::Serialize;
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::{
+    ProductVerificationStatus, Report, ReportFilters, ReportFrequency, ReportProductStat, ReportSchedule, ReportShareLink,
+};
+use crate::utils::generate_unique_principal;
+use crate::quotas;
+use crate::verification_store;
+use crate::webhooks;
+
+// Upper bound on schedules per organization
+pub const MAX_SCHEDULES_PER_ORG: usize = 10;
+
+// How often the scheduler looks for due schedules
+const REPORT_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+// Report periods (in nanoseconds, matching clock::now())
+const WEEK_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+const MONTH_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
+
+// Number of products listed in a report's top products section
+const TOP_PRODUCTS_LIMIT: usize = 5;
+
+// Share link limits
+pub const MAX_ACTIVE_SHARE_LINKS_PER_ORG: usize = 20;
+pub const MAX_SHARE_LINK_TTL_NS: u64 = MONTH_NS;
+pub const DEFAULT_SHARED_REPORT_PERIOD_NS: u64 = MONTH_NS; // When the link's filters give no start
+const SHARE_LINK_RETENTION_NS: u64 = WEEK_NS; // Closed links stay listed this long
+
+// Define unique Memory IDs for the structures in this module
+const REPORT_SCHEDULES_MEM_ID: MemoryId = MemoryId::new(14);
+const REPORTS_MEM_ID: MemoryId = MemoryId::new(15);
+const REPORT_SHARE_LINKS_MEM_ID: MemoryId = MemoryId::new(46);
+
+// Body posted to the schedule's delivery URL
+#[derive(Serialize)]
+struct ReportDigestPayload<'a> {
+    event: String,
+    recipients: &'a [String],
+    report: &'a Report,
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static REPORT_SCHEDULES: RefCell<StableBTreeMap<Principal, ReportSchedule, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(REPORT_SCHEDULES_MEM_ID))
+        )
+    );
+
+    static REPORTS: RefCell<StableBTreeMap<Principal, Report, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(REPORTS_MEM_ID))
+        )
+    );
+
+    static REPORT_SHARE_LINKS: RefCell<StableBTreeMap<Principal, ReportShareLink, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(REPORT_SHARE_LINKS_MEM_ID))
+        )
+    );
+}
+
+pub fn period_ns(frequency: ReportFrequency) -> u64 {
+    match frequency {
+        ReportFrequency::Weekly => WEEK_NS,
+        ReportFrequency::Monthly => MONTH_NS,
+    }
+}
+
+pub fn save_schedule(schedule: ReportSchedule) {
+    REPORT_SCHEDULES.with(|schedules| {
+        schedules.borrow_mut().insert(schedule.id, schedule);
+    });
+}
+
+pub fn get_schedule(schedule_id: Principal) -> Option<ReportSchedule> {
+    REPORT_SCHEDULES.with(|schedules| schedules.borrow().get(&schedule_id))
+}
+
+pub fn remove_schedule(schedule_id: Principal) -> Option<ReportSchedule> {
+    REPORT_SCHEDULES.with(|schedules| schedules.borrow_mut().remove(&schedule_id))
+}
+
+pub fn list_schedules_for_org(org_id: Principal) -> Vec<ReportSchedule> {
+    REPORT_SCHEDULES.with(|schedules| {
+        schedules
+            .borrow()
+            .iter()
+            .filter(|(_, schedule)| schedule.org_id == org_id)
+            .map(|(_, schedule)| schedule)
+            .collect()
+    })
+}
+
+pub fn get_report(report_id: Principal) -> Option<Report> {
+    REPORTS.with(|reports| reports.borrow().get(&report_id))
+}
+
+// Reports of an organization, newest first
+pub fn list_reports_for_org(org_id: Principal) -> Vec<Report> {
+    let mut reports: Vec<Report> = REPORTS.with(|reports| {
+        reports
+            .borrow()
+            .iter()
+            .filter(|(_, report)| report.org_id == org_id)
+            .map(|(_, report)| report)
+            .collect()
+    });
+    reports.sort_by_key(|a| std::cmp::Reverse(a.generated_at));
+    reports
+}
+
+// Register the recurring scheduler. Timers do not survive upgrades, so this runs from both init and post_upgrade.
+pub fn start_report_scheduler() {
+    set_timer_interval(REPORT_CHECK_INTERVAL, run_due_schedules);
+}
+
+fn run_due_schedules() {
+    let now = clock::now();
+    let due: Vec<ReportSchedule> = REPORT_SCHEDULES.with(|schedules| {
+        schedules
+            .borrow()
+            .iter()
+            .filter(|(_, schedule)| schedule.is_active && schedule.next_run_at <= now)
+            .map(|(_, schedule)| schedule)
+            .collect()
+    });
+
+    if due.is_empty() {
+        return;
+    }
+    log_info!("[run_due_schedules] {} report schedule(s) due", due.len());
+
+    for mut schedule in due {
+        let period_start = schedule.last_run_at.unwrap_or_else(|| now.saturating_sub(period_ns(schedule.frequency)));
+        let report = compile_report(&schedule, period_start, now);
+        REPORTS.with(|reports| reports.borrow_mut().insert(report.id, report.clone()));
+
+        schedule.last_run_at = Some(now);
+        schedule.next_run_at = now.saturating_add(period_ns(schedule.frequency));
+        save_schedule(schedule.clone());
+
+        let payload = ReportDigestPayload {
+            event: "report_digest".to_string(),
+            recipients: &schedule.recipient_emails,
+            report: &report,
+        };
+        if let Err(e) = quotas::consume_outcalls(schedule.org_id, 1) {
+            record_delivery(report.id, Err(format!("{:?}", e)));
+            continue;
+        }
+
+        match serde_json::to_string(&payload) {
+            Ok(body) => ic_cdk::spawn(deliver_report(report.id, report.org_id, schedule.delivery_url.clone(), body)),
+            Err(e) => {
+                log_error!("[run_due_schedules] Failed to serialize report {}: {:?}", report.id, e);
+                record_delivery(report.id, Err(format!("Serialization failed: {:?}", e)));
+            }
+        }
+    }
+}
+
+async fn deliver_report(report_id: Principal, org_id: Principal, url: String, body: String) {
+    let result = webhooks::post_json(&url, body, Some(org_id)).await.map_err(|e| format!("{:?}", e));
+    if let Err(e) = &result {
+        log_error!("[deliver_report] Delivery of report {} to {} failed: {}", report_id, url, e);
+    }
+    record_delivery(report_id, result);
+}
+
+fn record_delivery(report_id: Principal, result: Result<(), String>) {
+    REPORTS.with(|reports| {
+        let mut reports_mut = reports.borrow_mut();
+        if let Some(mut report) = reports_mut.get(&report_id) {
+            match result {
+                Ok(()) => {
+                    report.delivered_at = Some(clock::now());
+                    report.delivery_error = None;
+                }
+                Err(e) => report.delivery_error = Some(e),
+            }
+            reports_mut.insert(report_id, report);
+        }
+    });
+}
+
+// Verification totals behind both scheduled and shared reports
+pub struct ReportTotals {
+    pub total_verifications: u64,
+    pub first_verifications: u64,
+    pub multiple_verifications: u64,
+    pub invalid_verifications: u64,
+    pub suspicious_serials: u64, // Serials verified by more than one distinct user within the period
+    pub top_products: Vec<ReportProductStat>,
+}
+
+// Aggregate the organization's verifications within [period_start, period_end), optionally limited to some products.
+// Test products are left out unless the filter names them.
+pub fn aggregate_verifications(
+    org_id: Principal,
+    product_filter: Option<&[Principal]>,
+    period_start: u64,
+    period_end: u64,
+) -> ReportTotals {
+    let product_names: HashMap<Principal, String> = PRODUCTS.with(|products| {
+        products
+            .borrow()
+            .iter()
+            .filter(|(id, product)| {
+                product.org_id == org_id
+                    && match product_filter {
+                        Some(ids) => ids.contains(id),
+                        None => !product.is_test.unwrap_or(false),
+                    }
+            })
+            .map(|(id, product)| (id, product.name))
+            .collect()
+    });
+
+    let mut total_verifications = 0u64;
+    let mut first_verifications = 0u64;
+    let mut multiple_verifications = 0u64;
+    let mut invalid_verifications = 0u64;
+    let mut per_product: HashMap<Principal, u64> = HashMap::new();
+    let mut verifiers_per_serial: HashMap<Principal, HashSet<Principal>> = HashMap::new();
+
+    for product_id in product_names.keys() {
+        for verification in verification_store::list_for_product(*product_id)
+            .iter()
+            .filter(|v| v.created_at >= period_start && v.created_at < period_end)
+        {
+            total_verifications += 1;
+            match verification.status {
+                ProductVerificationStatus::FirstVerification => first_verifications += 1,
+                ProductVerificationStatus::MultipleVerification => multiple_verifications += 1,
+                ProductVerificationStatus::Invalid
+                | ProductVerificationStatus::VoidedSerial
+                | ProductVerificationStatus::CodeRevoked
+                | ProductVerificationStatus::NotYetActive
+                | ProductVerificationStatus::SupersededCode => invalid_verifications += 1,
+            }
+            *per_product.entry(*product_id).or_insert(0) += 1;
+            verifiers_per_serial
+                .entry(verification.serial_no)
+                .or_default()
+                .insert(verification.created_by);
+        }
+    }
+
+    let mut top_products: Vec<ReportProductStat> = per_product
+        .into_iter()
+        .map(|(product_id, verification_count)| ReportProductStat {
+            product_id,
+            product_name: product_names.get(&product_id).cloned().unwrap_or_default(),
+            verification_count,
+        })
+        .collect();
+    top_products.sort_by_key(|a| std::cmp::Reverse(a.verification_count));
+    top_products.truncate(TOP_PRODUCTS_LIMIT);
+
+    ReportTotals {
+        total_verifications,
+        first_verifications,
+        multiple_verifications,
+        invalid_verifications,
+        suspicious_serials: verifiers_per_serial.values().filter(|users| users.len() > 1).count() as u64,
+        top_products,
+    }
+}
+
+// Build the scheduled report for [period_start, period_end)
+pub fn compile_report(schedule: &ReportSchedule, period_start: u64, period_end: u64) -> Report {
+    let totals = aggregate_verifications(schedule.org_id, None, period_start, period_end);
+    Report {
+        id: generate_unique_principal(schedule.id),
+        org_id: schedule.org_id,
+        schedule_id: schedule.id,
+        frequency: schedule.frequency,
+        period_start,
+        period_end,
+        total_verifications: totals.total_verifications,
+        first_verifications: totals.first_verifications,
+        multiple_verifications: totals.multiple_verifications,
+        invalid_verifications: totals.invalid_verifications,
+        suspicious_serials: totals.suspicious_serials,
+        top_products: totals.top_products,
+        generated_at: clock::now(),
+        delivered_at: None,
+        delivery_error: None,
+    }
+}
+
+// ====== Share Links ======
+
+fn hash_share_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// Drop links that expired or were revoked more than a retention period ago
+fn prune_share_links(now: u64) {
+    REPORT_SHARE_LINKS.with(|links| {
+        let mut links_mut = links.borrow_mut();
+        let stale: Vec<Principal> = links_mut
+            .iter()
+            .filter(|(_, link)| {
+                let closed_at = link.revoked_at.unwrap_or(link.expires_at).min(link.expires_at);
+                now >= closed_at.saturating_add(SHARE_LINK_RETENTION_NS)
+            })
+            .map(|(k, _)| k)
+            .collect();
+        for key in stale {
+            links_mut.remove(&key);
+        }
+    });
+}
+
+// Links of an organization that can still be used
+pub fn active_share_links_for_org(org_id: Principal, now: u64) -> Vec<ReportShareLink> {
+    REPORT_SHARE_LINKS.with(|links| {
+        links
+            .borrow()
+            .iter()
+            .map(|(_, link)| link)
+            .filter(|link| link.org_id == org_id && link.revoked_at.is_none() && link.expires_at > now)
+            .collect()
+    })
+}
+
+// Create a link and return it with its token. Only the token's hash is stored, so it cannot be shown again.
+pub fn create_share_link(org_id: Principal, filters: ReportFilters, ttl_ns: u64, created_by: Principal) -> (ReportShareLink, String) {
+    let now = clock::now();
+    prune_share_links(now);
+
+    let mut token_bytes = [0u8; 32];
+    StdRng::from_entropy().fill_bytes(&mut token_bytes);
+    let token = hex::encode(token_bytes);
+
+    let link = ReportShareLink {
+        id: generate_unique_principal(org_id),
+        org_id,
+        token_hash: hash_share_token(&token),
+        filters,
+        expires_at: now.saturating_add(ttl_ns),
+        revoked_at: None,
+        created_at: now,
+        created_by,
+    };
+    REPORT_SHARE_LINKS.with(|links| links.borrow_mut().insert(link.id, link.clone()));
+    (link, token)
+}
+
+pub fn get_share_link(link_id: Principal) -> Option<ReportShareLink> {
+    REPORT_SHARE_LINKS.with(|links| links.borrow().get(&link_id))
+}
+
+pub fn revoke_share_link(link_id: Principal) -> Option<ReportShareLink> {
+    REPORT_SHARE_LINKS.with(|links| {
+        let mut links_mut = links.borrow_mut();
+        let mut link = links_mut.get(&link_id)?;
+        link.revoked_at.get_or_insert(clock::now());
+        links_mut.insert(link_id, link.clone());
+        Some(link)
+    })
+}
+
+// The link a token belongs to, if it is neither expired nor revoked
+pub fn find_usable_share_link(token: &str, now: u64) -> Option<ReportShareLink> {
+    let token_hash = hash_share_token(token);
+    REPORT_SHARE_LINKS.with(|links| {
+        links
+            .borrow()
+            .iter()
+            .map(|(_, link)| link)
+            .find(|link| link.token_hash == token_hash)
+    })
+    .filter(|link| link.revoked_at.is_none() && link.expires_at > now)
+}
+
+// Remove the organization's schedules, generated reports and share links when it is deleted,
+// returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    let schedules = REPORT_SCHEDULES.with(|schedules| {
+        let mut schedules_mut = schedules.borrow_mut();
+        let keys: Vec<_> = schedules_mut.iter().filter(|(_, schedule)| schedule.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            schedules_mut.remove(key);
+        }
+        keys.len() as u32
+    });
+    let reports = REPORTS.with(|reports| {
+        let mut reports_mut = reports.borrow_mut();
+        let keys: Vec<_> = reports_mut.iter().filter(|(_, report)| report.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            reports_mut.remove(key);
+        }
+        keys.len() as u32
+    });
+    let links = REPORT_SHARE_LINKS.with(|links| {
+        let mut links_mut = links.borrow_mut();
+        let keys: Vec<_> = links_mut.iter().filter(|(_, link)| link.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            links_mut.remove(key);
+        }
+        keys.len() as u32
+    });
+    schedules + reports + links
+}
+
+// Reset ALL report schedules, generated reports and share links (use with caution)
+pub fn reset_reports_storage() {
+    REPORT_SCHEDULES.with(|schedules| {
+        let mut schedules_mut = schedules.borrow_mut();
+        let keys: Vec<_> = schedules_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            schedules_mut.remove(&key);
+        }
+    });
+    REPORTS.with(|reports| {
+        let mut reports_mut = reports.borrow_mut();
+        let keys: Vec<_> = reports_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            reports_mut.remove(&key);
+        }
+    });
+    REPORT_SHARE_LINKS.with(|links| {
+        let mut links_mut = links.borrow_mut();
+        let keys: Vec<_> = links_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            links_mut.remove(&key);
+        }
+    });
+    log_info!("All report schedules, reports and share links have been reset.");
+}
@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{ResellerApplication, ResellerApplicationStatus, ResellerPreapproval};
+
+pub const MAX_REJECTION_REASON_LENGTH: usize = 500;
+pub const MAX_PREAPPROVALS_PER_UPLOAD: usize = 1_000;
+
+// Define unique Memory IDs for the structures in this module
+const RESELLER_APPLICATIONS_MEM_ID: MemoryId = MemoryId::new(51);
+const RESELLER_PREAPPROVALS_MEM_ID: MemoryId = MemoryId::new(66);
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct PreapprovalKey {
+    org_id: Principal,
+    email: String,
+}
+
+impl Storable for PreapprovalKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static RESELLER_APPLICATIONS: RefCell<StableBTreeMap<Principal, ResellerApplication, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(RESELLER_APPLICATIONS_MEM_ID))
+        )
+    );
+
+    static RESELLER_PREAPPROVALS: RefCell<StableBTreeMap<PreapprovalKey, ResellerPreapproval, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(RESELLER_PREAPPROVALS_MEM_ID))
+        )
+    );
+}
+
+pub fn save_application(application: ResellerApplication) {
+    RESELLER_APPLICATIONS.with(|applications| {
+        applications.borrow_mut().insert(application.id, application);
+    });
+}
+
+pub fn get_application(application_id: Principal) -> Option<ResellerApplication> {
+    RESELLER_APPLICATIONS.with(|applications| applications.borrow().get(&application_id))
+}
+
+// The reseller's application still awaiting a decision, if any
+pub fn find_pending_for_reseller(reseller_id: Principal) -> Option<ResellerApplication> {
+    RESELLER_APPLICATIONS.with(|applications| {
+        applications
+            .borrow()
+            .iter()
+            .map(|(_, a)| a)
+            .find(|a| a.reseller_id == reseller_id && a.status == ResellerApplicationStatus::Pending)
+    })
+}
+
+// Applications to an organization, optionally of one status, oldest first
+pub fn list_for_org(org_id: Principal, status: Option<ResellerApplicationStatus>) -> Vec<ResellerApplication> {
+    let mut listed: Vec<ResellerApplication> = RESELLER_APPLICATIONS.with(|applications| {
+        applications
+            .borrow()
+            .iter()
+            .map(|(_, a)| a)
+            .filter(|a| a.org_id == org_id && status.is_none_or(|s| a.status == s))
+            .collect()
+    });
+    listed.sort_by_key(|a| a.submitted_at);
+    listed
+}
+
+pub fn save_preapproval(preapproval: ResellerPreapproval) {
+    RESELLER_PREAPPROVALS.with(|preapprovals| {
+        let key = PreapprovalKey { org_id: preapproval.org_id, email: preapproval.email.clone() };
+        preapprovals.borrow_mut().insert(key, preapproval);
+    });
+}
+
+// `email` must already be lower-cased
+pub fn get_preapproval(org_id: Principal, email: &str) -> Option<ResellerPreapproval> {
+    RESELLER_PREAPPROVALS.with(|preapprovals| {
+        preapprovals.borrow().get(&PreapprovalKey { org_id, email: email.to_string() })
+    })
+}
+
+pub fn remove_preapproval(org_id: Principal, email: &str) -> Option<ResellerPreapproval> {
+    RESELLER_PREAPPROVALS.with(|preapprovals| {
+        preapprovals.borrow_mut().remove(&PreapprovalKey { org_id, email: email.to_string() })
+    })
+}
+
+pub fn list_preapprovals(org_id: Principal) -> Vec<ResellerPreapproval> {
+    RESELLER_PREAPPROVALS.with(|preapprovals| {
+        preapprovals
+            .borrow()
+            .iter()
+            .filter(|(key, _)| key.org_id == org_id)
+            .map(|(_, p)| p)
+            .collect()
+    })
+}
+
+// Reset ALL reseller applications and pre-approvals (use with caution)
+pub fn reset_reseller_applications() {
+    RESELLER_APPLICATIONS.with(|applications| {
+        let mut applications_mut = applications.borrow_mut();
+        let keys: Vec<_> = applications_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            applications_mut.remove(&key);
+        }
+    });
+    RESELLER_PREAPPROVALS.with(|preapprovals| {
+        let mut preapprovals_mut = preapprovals.borrow_mut();
+        let keys: Vec<_> = preapprovals_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            preapprovals_mut.remove(&key);
+        }
+    });
+    log_info!("All reseller applications have been reset.");
+}
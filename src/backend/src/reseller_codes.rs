@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+
+// Define a unique MemoryId for this structure
+const CONSUMED_RESELLER_CODES_MEM_ID: MemoryId = MemoryId::new(27);
+
+// Identifies one generated reseller code
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConsumedCodeKey {
+    pub reseller_id: Principal,
+    pub timestamp: u64,
+    pub signature_hash: String, // Hex SHA-256 of the code's signature bytes
+}
+
+impl Storable for ConsumedCodeKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Consumed codes mapped to the time after which they would be rejected as expired anyway
+    static CONSUMED_RESELLER_CODES: RefCell<StableBTreeMap<ConsumedCodeKey, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(CONSUMED_RESELLER_CODES_MEM_ID))
+        )
+    );
+}
+
+pub fn is_consumed(key: &ConsumedCodeKey) -> bool {
+    CONSUMED_RESELLER_CODES.with(|codes| codes.borrow().contains_key(key))
+}
+
+// Record a code as used. Entries past their expiry are pruned here, since expired codes
+// are rejected before the replay check and no longer need to be remembered.
+pub fn consume(key: ConsumedCodeKey, expires_at: u64, now: u64) {
+    CONSUMED_RESELLER_CODES.with(|codes| {
+        let mut codes_mut = codes.borrow_mut();
+        let expired: Vec<ConsumedCodeKey> = codes_mut
+            .iter()
+            .filter(|(_, code_expires_at)| *code_expires_at < now)
+            .map(|(k, _)| k)
+            .collect();
+        for expired_key in expired {
+            codes_mut.remove(&expired_key);
+        }
+        codes_mut.insert(key, expires_at);
+    });
+}
+
+// Reset ALL consumed reseller codes (use with caution)
+pub fn reset_consumed_codes() {
+    CONSUMED_RESELLER_CODES.with(|codes| {
+        let mut codes_mut = codes.borrow_mut();
+        let keys: Vec<_> = codes_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            codes_mut.remove(&key);
+        }
+    });
+    log_info!("All consumed reseller codes have been reset.");
+}
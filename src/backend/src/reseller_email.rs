@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use k256::sha2::{Digest, Sha256};
+use rand::prelude::StdRng;
+use serde::Serialize;
+
+use crate::clock;
+use crate::config;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{Reseller, ResellerEmailConfirmation};
+use crate::quotas;
+use crate::webhooks;
+
+// How long a confirmation link stays valid
+pub const CONFIRMATION_TTL_NS: u64 = 48 * 60 * 60 * 1_000_000_000;
+// Minimum time between two confirmation emails to the same reseller
+pub const RESEND_INTERVAL_NS: u64 = 60 * 1_000_000_000;
+
+// Define a unique MemoryId for this structure
+const EMAIL_CONFIRMATIONS_MEM_ID: MemoryId = MemoryId::new(52);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// Body posted to the email relay, which renders and sends the confirmation link
+#[derive(Serialize, Clone, Debug)]
+struct EmailConfirmationPayload {
+    event: String,
+    to: String,
+    reseller_name: String,
+    org_id: String,
+    token: String,
+    expires_at: u64,
+}
+
+thread_local! {
+    // Keyed by reseller ID; a new confirmation replaces the previous one
+    static EMAIL_CONFIRMATIONS: RefCell<StableBTreeMap<Principal, ResellerEmailConfirmation, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(EMAIL_CONFIRMATIONS_MEM_ID))
+        )
+    );
+}
+
+fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// Create a confirmation for the reseller's contact email and queue it for the relay.
+// Fails without side effects if no relay is configured or one was sent too recently.
+pub fn send_confirmation(reseller: &Reseller) -> Result<ResellerEmailConfirmation, ApiError> {
+    let email = reseller
+        .contact_email
+        .clone()
+        .ok_or_else(|| ApiError::invalid_input("Reseller has no contact email"))?;
+    let relay_url = config::email_relay_url()
+        .ok_or_else(|| ApiError::internal_error("Email relay is not configured"))?;
+
+    let now = clock::now();
+    if let Some(previous) = EMAIL_CONFIRMATIONS.with(|confirmations| confirmations.borrow().get(&reseller.id)) {
+        if previous.email == email && now < previous.created_at.saturating_add(RESEND_INTERVAL_NS) {
+            return Err(ApiError::invalid_input("A confirmation email was sent moments ago"));
+        }
+    }
+    quotas::consume_outcalls(reseller.org_id, 1)?;
+
+    let mut token_bytes = [0u8; 32];
+    StdRng::from_entropy().fill_bytes(&mut token_bytes);
+    let token = hex::encode(token_bytes);
+
+    let confirmation = ResellerEmailConfirmation {
+        reseller_id: reseller.id,
+        email: email.clone(),
+        token_hash: hash_token(&token),
+        expires_at: now.saturating_add(CONFIRMATION_TTL_NS),
+        created_at: now,
+    };
+    let payload = EmailConfirmationPayload {
+        event: "reseller_email_confirmation".to_string(),
+        to: email,
+        reseller_name: reseller.name.clone(),
+        org_id: reseller.org_id.to_string(),
+        token,
+        expires_at: confirmation.expires_at,
+    };
+    let body = serde_json::to_string(&payload).map_err(|e| {
+        log_error!("[send_confirmation] Failed to serialize payload for reseller {}: {:?}", reseller.id, e);
+        ApiError::internal_error("Failed to prepare confirmation email")
+    })?;
+
+    EMAIL_CONFIRMATIONS.with(|confirmations| {
+        confirmations.borrow_mut().insert(reseller.id, confirmation.clone());
+    });
+    webhooks::enqueue_webhook(relay_url, body, Some(reseller.org_id));
+    Ok(confirmation)
+}
+
+// Consume a token, returning the confirmation it belonged to if it has not expired
+pub fn take_confirmation(token: &str, now: u64) -> Option<ResellerEmailConfirmation> {
+    let token_hash = hash_token(token);
+    let confirmation = EMAIL_CONFIRMATIONS.with(|confirmations| {
+        confirmations
+            .borrow()
+            .iter()
+            .map(|(_, c)| c)
+            .find(|c| c.token_hash == token_hash)
+    })?;
+    EMAIL_CONFIRMATIONS.with(|confirmations| confirmations.borrow_mut().remove(&confirmation.reseller_id));
+    Some(confirmation).filter(|c| c.expires_at > now)
+}
+
+// Drop the outstanding confirmation of a reseller, e.g. after its email changed
+pub fn cancel_confirmation(reseller_id: Principal) {
+    EMAIL_CONFIRMATIONS.with(|confirmations| confirmations.borrow_mut().remove(&reseller_id));
+}
+
+// Reset ALL pending email confirmations (use with caution)
+pub fn reset_email_confirmations() {
+    EMAIL_CONFIRMATIONS.with(|confirmations| {
+        let mut confirmations_mut = confirmations.borrow_mut();
+        let keys: Vec<_> = confirmations_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            confirmations_mut.remove(&key);
+        }
+    });
+    log_info!("All reseller email confirmations have been reset.");
+}
@@ -0,0 +1,90 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{AuthorizedProducts, Reseller};
+
+// Define a unique MemoryId for this structure
+const AUTHORIZED_PRODUCTS_MEM_ID: MemoryId = MemoryId::new(43);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by reseller ID. A reseller without an entry is authorized for every product of its organization.
+    static AUTHORIZED_PRODUCTS: RefCell<StableBTreeMap<Principal, AuthorizedProducts, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(AUTHORIZED_PRODUCTS_MEM_ID))
+        )
+    );
+}
+
+pub fn get_authorization(reseller_id: Principal) -> Option<AuthorizedProducts> {
+    AUTHORIZED_PRODUCTS.with(|authorized| authorized.borrow().get(&reseller_id))
+}
+
+pub fn is_authorized(reseller_id: Principal, product_id: Principal) -> bool {
+    get_authorization(reseller_id).is_none_or(|entry| entry.product_ids.contains(&product_id))
+}
+
+fn update_authorization<F>(reseller: &Reseller, updated_by: Principal, update: F) -> AuthorizedProducts
+where
+    F: FnOnce(&mut Vec<Principal>),
+{
+    AUTHORIZED_PRODUCTS.with(|authorized| {
+        let mut authorized_mut = authorized.borrow_mut();
+        let mut entry = authorized_mut.get(&reseller.id).unwrap_or_else(|| AuthorizedProducts {
+            reseller_id: reseller.id,
+            org_id: reseller.org_id,
+            product_ids: Vec::new(),
+            updated_at: 0,
+            updated_by,
+        });
+        update(&mut entry.product_ids);
+        entry.updated_at = clock::now();
+        entry.updated_by = updated_by;
+        authorized_mut.insert(reseller.id, entry.clone());
+        entry
+    })
+}
+
+// Add products to the reseller's list. The first grant switches the reseller from implicit
+// org-wide authorization to the explicit list.
+pub fn grant(reseller: &Reseller, product_ids: &[Principal], granted_by: Principal) -> AuthorizedProducts {
+    update_authorization(reseller, granted_by, |authorized| {
+        for product_id in product_ids {
+            if !authorized.contains(product_id) {
+                authorized.push(*product_id);
+            }
+        }
+    })
+}
+
+// Remove products from the reseller's list. Revoking from a reseller with implicit authorization
+// leaves it with an explicit, empty list.
+pub fn revoke(reseller: &Reseller, product_ids: &[Principal], revoked_by: Principal) -> AuthorizedProducts {
+    update_authorization(reseller, revoked_by, |authorized| {
+        authorized.retain(|product_id| !product_ids.contains(product_id));
+    })
+}
+
+// Return the reseller to implicit authorization for the whole organization
+pub fn clear(reseller_id: Principal) -> Option<AuthorizedProducts> {
+    AUTHORIZED_PRODUCTS.with(|authorized| authorized.borrow_mut().remove(&reseller_id))
+}
+
+// Reset ALL reseller product authorizations (use with caution)
+pub fn reset_authorized_products() {
+    AUTHORIZED_PRODUCTS.with(|authorized| {
+        let mut authorized_mut = authorized.borrow_mut();
+        let keys: Vec<_> = authorized_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            authorized_mut.remove(&key);
+        }
+    });
+    log_info!("All reseller product authorizations have been reset.");
+}
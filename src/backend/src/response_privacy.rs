@@ -0,0 +1,43 @@
+use candid::Principal;
+
+use crate::api::{ProductVerificationEnhancedResponse, ResellerVerificationResponse};
+use crate::moderation;
+use crate::org_settings;
+
+// Every consumer-facing verification response passes through here, so a brand's privacy policy
+// only has to be enforced in one place.
+
+// Strip the details the organization's policy hides from a genuine verification
+pub fn shape_verification_response(
+    org_id: Principal,
+    mut response: ProductVerificationEnhancedResponse,
+) -> ProductVerificationEnhancedResponse {
+    let policy = org_settings::verification_privacy(org_id);
+    if !policy.show_scan_count {
+        response.scan_count = None;
+    }
+    if !policy.show_first_scan_date {
+        response.first_scanned_at = None;
+    }
+    if !policy.show_custody_chain {
+        response.custody_status = None;
+    }
+    response
+}
+
+// Reseller details are dropped when the reseller's organization hides them; the status still tells
+// the consumer whether the code is valid. Quarantined names are dropped the same way.
+pub fn shape_reseller_verification_response(mut response: ResellerVerificationResponse) -> ResellerVerificationResponse {
+    if let Some(org_id) = response.reseller.as_ref().map(|reseller| reseller.org_id) {
+        if !org_settings::verification_privacy(org_id).show_reseller_info {
+            response.reseller = None;
+        }
+    }
+    if response.reseller.as_ref().is_some_and(|reseller| moderation::is_quarantined(reseller.id)) {
+        response.reseller = None;
+    }
+    if response.organization.as_ref().is_some_and(|organization| moderation::is_quarantined(organization.id)) {
+        response.organization = None;
+    }
+    response
+}
@@ -0,0 +1,231 @@
+use std::cell::RefCell;
+
+use candid::{CandidType, Deserialize, Nat, Principal};
+use ic_cdk::api;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::sha2::{Digest, Sha256};
+
+use crate::clock;
+use crate::config;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, USERS};
+use crate::models::{RewardPool, UserNotificationKind, UserRole};
+use crate::notifications;
+
+// 1 point = 0.0001 tokens on an 8-decimal ledger, until the brand sets its own rate
+pub const DEFAULT_UNITS_PER_POINT: u64 = 10_000;
+
+// Define a unique MemoryId for this structure
+const REWARD_POOLS_MEM_ID: MemoryId = MemoryId::new(42);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static REWARD_POOLS: RefCell<StableBTreeMap<Principal, RewardPool, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(REWARD_POOLS_MEM_ID))
+        )
+    );
+}
+
+// ICRC-1/ICRC-2 ledger types, limited to what deposits need
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Account {
+    pub owner: Principal,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+struct TransferFromArgs {
+    spender_subaccount: Option<Vec<u8>>,
+    from: Account,
+    to: Account,
+    amount: Nat,
+    fee: Option<Nat>,
+    memo: Option<Vec<u8>>,
+    created_at_time: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+enum TransferFromError {
+    BadFee { expected_fee: Nat },
+    BadBurn { min_burn_amount: Nat },
+    InsufficientFunds { balance: Nat },
+    InsufficientAllowance { allowance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: Nat },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+fn nat_to_u64(value: &Nat) -> u64 {
+    match value.0.to_u64_digits().as_slice() {
+        [] => 0,
+        [low] => *low,
+        _ => u64::MAX,
+    }
+}
+
+fn ledger_id() -> Result<Principal, ApiError> {
+    config::get_config()
+        .ledger_canister_id
+        .ok_or_else(|| ApiError::invalid_input("No ledger canister is configured for reward pools"))
+}
+
+// Each organization deposits into its own subaccount of the canister, derived from the org ID
+pub fn deposit_account(org_id: Principal) -> Account {
+    let mut hasher = Sha256::new();
+    hasher.update(b"reward-pool");
+    hasher.update(org_id.as_slice());
+    Account {
+        owner: api::id(),
+        subaccount: Some(hasher.finalize().to_vec()),
+    }
+}
+
+pub fn get_pool(org_id: Principal) -> RewardPool {
+    REWARD_POOLS
+        .with(|pools| pools.borrow().get(&org_id))
+        .unwrap_or_else(|| RewardPool {
+            org_id,
+            balance: 0,
+            ledger_balance_seen: 0,
+            total_deposited: 0,
+            total_paid_out: 0,
+            units_per_point: DEFAULT_UNITS_PER_POINT,
+            low_balance_threshold: 0,
+            low_balance_alerted: false,
+            updated_at: clock::now(),
+        })
+}
+
+pub fn save_pool(mut pool: RewardPool) {
+    pool.updated_at = clock::now();
+    REWARD_POOLS.with(|pools| pools.borrow_mut().insert(pool.org_id, pool));
+}
+
+// Ledger units needed to pay out `points`
+pub fn cost_of(pool: &RewardPool, points: u32) -> u64 {
+    (points as u64).saturating_mul(pool.units_per_point)
+}
+
+// Pull an ICRC-2 approved amount from the caller into the organization's deposit subaccount
+pub async fn deposit_from(org_id: Principal, from: Principal, amount: u64) -> Result<RewardPool, ApiError> {
+    let ledger = ledger_id()?;
+    let args = TransferFromArgs {
+        spender_subaccount: None,
+        from: Account { owner: from, subaccount: None },
+        to: deposit_account(org_id),
+        amount: Nat::from(amount),
+        fee: None,
+        memo: None,
+        created_at_time: None,
+    };
+    let (result,): (Result<Nat, TransferFromError>,) = ic_cdk::call(ledger, "icrc2_transfer_from", (args,))
+        .await
+        .map_err(|(code, message)| ApiError::external_api_error(&format!("Ledger call failed: {:?} {}", code, message)))?;
+    if let Err(e) = result {
+        return Err(ApiError::external_api_error(&format!("Deposit rejected by the ledger: {:?}", e)));
+    }
+    log_info!("[reward_pools] {} deposited {} units into the reward pool of {}", from, amount, org_id);
+    sync_deposits(org_id).await
+}
+
+// Credit whatever arrived in the deposit subaccount since the last sync. Covers ICRC-2 deposits and
+// plain transfers alike, so a transfer can never be credited twice.
+pub async fn sync_deposits(org_id: Principal) -> Result<RewardPool, ApiError> {
+    let ledger = ledger_id()?;
+    let (ledger_balance,): (Nat,) = ic_cdk::call(ledger, "icrc1_balance_of", (deposit_account(org_id),))
+        .await
+        .map_err(|(code, message)| ApiError::external_api_error(&format!("Ledger call failed: {:?} {}", code, message)))?;
+    let ledger_balance = nat_to_u64(&ledger_balance);
+
+    // Read the pool after the await; a stale, lower balance from an overlapping call credits nothing
+    let mut pool = get_pool(org_id);
+    if ledger_balance > pool.ledger_balance_seen {
+        let deposited = ledger_balance - pool.ledger_balance_seen;
+        pool.ledger_balance_seen = ledger_balance;
+        pool.balance = pool.balance.saturating_add(deposited);
+        pool.total_deposited = pool.total_deposited.saturating_add(deposited);
+        if pool.balance >= pool.low_balance_threshold {
+            pool.low_balance_alerted = false;
+        }
+        save_pool(pool.clone());
+        log_info!("[reward_pools] Credited {} units to the reward pool of {}", deposited, org_id);
+    }
+    Ok(pool)
+}
+
+// Take the cost of a payout from the organization's pool, alerting brand owners when it runs low
+pub fn debit_for_redemption(org_id: Principal, points: u32) -> Result<RewardPool, ApiError> {
+    let mut pool = get_pool(org_id);
+    let cost = cost_of(&pool, points);
+    if pool.balance < cost {
+        return Err(ApiError::invalid_input(
+            "The brand's reward pool cannot cover this redemption right now. Please try again later.",
+        ));
+    }
+
+    pool.balance -= cost;
+    pool.total_paid_out = pool.total_paid_out.saturating_add(cost);
+    let alert = pool.balance < pool.low_balance_threshold && !pool.low_balance_alerted;
+    if alert {
+        pool.low_balance_alerted = true;
+    }
+    save_pool(pool.clone());
+
+    if alert {
+        notify_low_balance(&pool);
+    }
+    Ok(pool)
+}
+
+// Put back what debit_for_redemption took for a payout that did not go through
+pub fn refund_redemption(org_id: Principal, points: u32) {
+    let mut pool = get_pool(org_id);
+    let cost = cost_of(&pool, points);
+    pool.balance = pool.balance.saturating_add(cost);
+    pool.total_paid_out = pool.total_paid_out.saturating_sub(cost);
+    save_pool(pool);
+    log_info!("[reward_pools] Refunded {} units to the reward pool of {}", cost, org_id);
+}
+
+fn notify_low_balance(pool: &RewardPool) {
+    log_warn!("[reward_pools] Reward pool of {} is low: {} units left", pool.org_id, pool.balance);
+    let owners: Vec<Principal> = USERS.with(|users| {
+        users
+            .borrow()
+            .iter()
+            .filter(|(_, user)| user.user_role == Some(UserRole::BrandOwner) && user.org_ids.contains(&pool.org_id))
+            .map(|(id, _)| id)
+            .collect()
+    });
+    for owner in owners {
+        notifications::notify_user(
+            owner,
+            UserNotificationKind::RewardPoolLow,
+            "Reward pool running low",
+            format!(
+                "Your reward pool has {} units left, below your alert threshold of {}. Top it up to keep redemptions flowing.",
+                pool.balance, pool.low_balance_threshold
+            ),
+            Some(pool.org_id),
+        );
+    }
+}
+
+// Reset ALL reward pools (use with caution). Tokens stay in the deposit subaccounts and are
+// credited again by the next sync.
+pub fn reset_reward_pools() {
+    REWARD_POOLS.with(|pools| {
+        let mut pools_mut = pools.borrow_mut();
+        let keys: Vec<_> = pools_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            pools_mut.remove(&key);
+        }
+    });
+    log_info!("All reward pools have been reset.");
+}
@@ -2,13 +2,13 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 
 use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
-use ic_cdk::api;
 use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
 
 use crate::api::VerificationRewards;
+use crate::clock;
 // Import the shared memory manager
 use crate::global_state::MEMORY_MANAGER;
-use crate::models::{Metadata, ProductVerificationStatus};
+use crate::models::{Metadata, ProductRewardStats, ProductVerificationStatus, RewardPenalty};
 
 // Points awarded for different verification types
 const FIRST_VERIFICATION_POINTS: u32 = 100;
@@ -22,6 +22,8 @@ const REWARDS_EXPIRATION_TIME: u64 = 86400 * 30; // 30 days
 const USER_REWARDS_MEM_ID: MemoryId = MemoryId::new(7);
 const USER_VERIFIED_PRODUCTS_MEM_ID: MemoryId = MemoryId::new(8);
 const PROMOTIONS_MEM_ID: MemoryId = MemoryId::new(9);
+const PRODUCT_REWARD_STATS_MEM_ID: MemoryId = MemoryId::new(26);
+const REWARD_PENALTIES_MEM_ID: MemoryId = MemoryId::new(40);
 
 // Type definitions for rewards
 #[derive(CandidType, Deserialize, Clone, Debug)]
@@ -35,7 +37,7 @@ pub struct UserRewards {
 }
 
 impl Storable for UserRewards {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Owned(encode_one(self).expect("Failed to encode"))
     }
 
@@ -54,7 +56,7 @@ pub struct UserVerifiedProducts {
 }
 
 impl Storable for UserVerifiedProducts {
-    fn to_bytes(&self) -> Cow<[u8]> {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
         Cow::Owned(encode_one(self).expect("Failed to encode"))
     }
 
@@ -87,6 +89,20 @@ thread_local! {
             MEMORY_MANAGER.with(|m| m.borrow().get(PROMOTIONS_MEM_ID))
         )
     );
+
+    // Per-product reward totals, updated as points are issued and redeemed
+    static PRODUCT_REWARD_STATS: RefCell<StableBTreeMap<Principal, ProductRewardStats, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PRODUCT_REWARD_STATS_MEM_ID))
+        )
+    );
+
+    // Audit trail of clawed-back points, keyed by penalty ID
+    static REWARD_PENALTIES: RefCell<StableBTreeMap<Principal, RewardPenalty, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(REWARD_PENALTIES_MEM_ID))
+        )
+    );
 }
 
 // Check if this is the first time a user has verified this product
@@ -127,6 +143,37 @@ pub fn record_product_verification(user_id: Principal, product_id: Principal) {
     });
 }
 
+// Remove and return the products a (guest) principal has verified, used when its history moves to an account
+pub fn take_verified_products(user_id: Principal) -> Vec<Principal> {
+    USER_VERIFIED_PRODUCTS.with(|verified_products| {
+        verified_products
+            .borrow_mut()
+            .remove(&user_id)
+            .map(|record| record.verified_products)
+            .unwrap_or_default()
+    })
+}
+
+// Points a verification earns before any promotion
+pub fn base_points_for(verification_status: &ProductVerificationStatus) -> u32 {
+    match verification_status {
+        ProductVerificationStatus::FirstVerification => FIRST_VERIFICATION_POINTS,
+        ProductVerificationStatus::MultipleVerification => MULTIPLE_VERIFICATION_POINTS,
+        ProductVerificationStatus::Invalid
+        | ProductVerificationStatus::VoidedSerial
+        | ProductVerificationStatus::CodeRevoked
+        | ProductVerificationStatus::NotYetActive
+        | ProductVerificationStatus::SupersededCode => 0,
+    }
+}
+
+// Points a verification of this product is worth, including any active promotion
+pub fn reward_points_for(product_id: Principal, verification_status: &ProductVerificationStatus) -> u32 {
+    let base_points = base_points_for(verification_status);
+    let promotion_points = if get_special_promotion(product_id).is_some() { SPECIAL_PROMOTION_POINTS } else { 0 };
+    base_points + promotion_points
+}
+
 // Calculate rewards for a verification
 pub fn calculate_verification_rewards(
     user_id: Principal, 
@@ -134,29 +181,29 @@ pub fn calculate_verification_rewards(
     verification_status: &ProductVerificationStatus
 ) -> VerificationRewards {
     let is_first_verification = is_first_verification_for_user(user_id, product_id);
-    api::time();
-    
-    // Calculate points based on verification type
-    let base_points = match verification_status {
-        ProductVerificationStatus::FirstVerification => FIRST_VERIFICATION_POINTS,
-        ProductVerificationStatus::MultipleVerification => MULTIPLE_VERIFICATION_POINTS,
-        ProductVerificationStatus::Invalid => 0,
-    };
     
     // Check for special promotions
     let special_reward = get_special_promotion(product_id);
     let promotion_points = if special_reward.is_some() { SPECIAL_PROMOTION_POINTS } else { 0 };
     
     // Record the verification if valid
-    if *verification_status != ProductVerificationStatus::Invalid {
+    if !matches!(
+        verification_status,
+        ProductVerificationStatus::Invalid
+            | ProductVerificationStatus::VoidedSerial
+            | ProductVerificationStatus::CodeRevoked
+            | ProductVerificationStatus::NotYetActive
+            | ProductVerificationStatus::SupersededCode
+    ) {
         record_product_verification(user_id, product_id);
     }
     
     // Update user rewards
-    let total_points = base_points + promotion_points;
+    let total_points = reward_points_for(product_id, verification_status);
     
     if total_points > 0 {
         update_user_rewards(user_id, total_points, is_first_verification);
+        record_points_issued(product_id, total_points, promotion_points, is_first_verification);
     }
     
     VerificationRewards {
@@ -167,6 +214,23 @@ pub fn calculate_verification_rewards(
     }
 }
 
+// What a verification of a test product would earn. The product still counts as verified by the
+// user, so repeat scans are reported as such, but no points are credited or issued.
+pub fn simulate_verification_rewards(
+    user_id: Principal,
+    product_id: Principal,
+    verification_status: &ProductVerificationStatus,
+) -> VerificationRewards {
+    let is_first_verification = is_first_verification_for_user(user_id, product_id);
+    record_product_verification(user_id, product_id);
+    VerificationRewards {
+        points: reward_points_for(product_id, verification_status),
+        is_first_verification,
+        special_reward: None,
+        reward_description: Some("Test product: points are not credited".to_string()),
+    }
+}
+
 // Update user rewards
 fn update_user_rewards(user_id: Principal, points: u32, is_first_verification: bool) {
     USER_REWARDS.with(|rewards| {
@@ -180,7 +244,7 @@ fn update_user_rewards(user_id: Principal, points: u32, is_first_verification: b
                 if is_first_verification {
                     updated.first_verifications += 1;
                 }
-                updated.last_reward_time = api::time();
+                updated.last_reward_time = clock::now();
                 
                 rewards_mut.insert(user_id, updated);
             },
@@ -191,7 +255,7 @@ fn update_user_rewards(user_id: Principal, points: u32, is_first_verification: b
                     total_points: points,
                     verification_count: 1,
                     first_verifications: if is_first_verification { 1 } else { 0 },
-                    last_reward_time: api::time(),
+                    last_reward_time: clock::now(),
                     metadata: Vec::new(),
                 };
                 
@@ -208,6 +272,10 @@ fn get_special_promotion(product_id: Principal) -> Option<Metadata> {
     })
 }
 
+pub fn has_special_promotion(product_id: Principal) -> bool {
+    get_special_promotion(product_id).is_some()
+}
+
 // Add a special promotion for a product
 pub fn add_special_promotion(product_id: Principal, promotion_name: &str, promotion_value: &str) {
     let metadata = Metadata {
@@ -234,6 +302,119 @@ pub fn get_user_rewards(user_id: Principal) -> Option<UserRewards> {
     })
 }
 
+// Move `from`'s reward balance and counters onto `into`, returning the points moved
+pub fn merge_user_rewards(from: Principal, into: Principal) -> u32 {
+    USER_REWARDS.with(|rewards| {
+        let mut rewards_mut = rewards.borrow_mut();
+        let source = match rewards_mut.remove(&from) {
+            Some(source) => source,
+            None => return 0,
+        };
+        let mut target = rewards_mut.get(&into).unwrap_or(UserRewards {
+            user_id: into,
+            total_points: 0,
+            verification_count: 0,
+            first_verifications: 0,
+            last_reward_time: 0,
+            metadata: Vec::new(),
+        });
+        target.total_points = target.total_points.saturating_add(source.total_points);
+        target.verification_count = target.verification_count.saturating_add(source.verification_count);
+        target.first_verifications = target.first_verifications.saturating_add(source.first_verifications);
+        target.last_reward_time = target.last_reward_time.max(source.last_reward_time);
+        for entry in source.metadata {
+            if !target.metadata.iter().any(|m| m.key == entry.key) {
+                target.metadata.push(entry);
+            }
+        }
+        rewards_mut.insert(into, target);
+        source.total_points
+    })
+}
+
+// Take up to `points` from the user's balance, never below zero. Returns (deducted, balance after).
+pub fn deduct_points(user_id: Principal, points: u32) -> (u32, u32) {
+    USER_REWARDS.with(|rewards| {
+        let mut rewards_mut = rewards.borrow_mut();
+        match rewards_mut.get(&user_id) {
+            Some(mut user_rewards) => {
+                let deducted = points.min(user_rewards.total_points);
+                user_rewards.total_points -= deducted;
+                let balance = user_rewards.total_points;
+                rewards_mut.insert(user_id, user_rewards);
+                (deducted, balance)
+            }
+            None => (0, 0),
+        }
+    })
+}
+
+pub fn save_penalty(penalty: RewardPenalty) {
+    REWARD_PENALTIES.with(|penalties| {
+        penalties.borrow_mut().insert(penalty.id, penalty);
+    });
+}
+
+// Penalties matching the filter, newest first
+pub fn list_penalties<F>(filter: F) -> Vec<RewardPenalty>
+where
+    F: Fn(&RewardPenalty) -> bool,
+{
+    let mut matching: Vec<RewardPenalty> = REWARD_PENALTIES.with(|penalties| {
+        penalties
+            .borrow()
+            .iter()
+            .map(|(_, penalty)| penalty)
+            .filter(|penalty| filter(penalty))
+            .collect()
+    });
+    matching.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    matching
+}
+
+fn update_product_reward_stats<F>(product_id: Principal, update: F)
+where
+    F: FnOnce(&mut ProductRewardStats),
+{
+    PRODUCT_REWARD_STATS.with(|stats| {
+        let mut stats_mut = stats.borrow_mut();
+        let mut product_stats = stats_mut.get(&product_id).unwrap_or_else(|| ProductRewardStats {
+            product_id,
+            ..Default::default()
+        });
+        update(&mut product_stats);
+        product_stats.updated_at = clock::now();
+        stats_mut.insert(product_id, product_stats);
+    });
+}
+
+// A user's first rewarded verification of the product also counts them as a rewarded user
+fn record_points_issued(product_id: Principal, points: u32, promotion_points: u32, is_new_user: bool) {
+    update_product_reward_stats(product_id, |stats| {
+        stats.points_issued += points as u64;
+        stats.promotion_points_issued += promotion_points as u64;
+        if is_new_user {
+            stats.unique_rewarded_users += 1;
+        }
+    });
+}
+
+pub fn record_points_redeemed(product_id: Principal, points: u32) {
+    update_product_reward_stats(product_id, |stats| {
+        stats.points_redeemed += points as u64;
+        stats.redemption_count += 1;
+    });
+}
+
+pub fn get_product_reward_stats(product_id: Principal) -> ProductRewardStats {
+    PRODUCT_REWARD_STATS
+        .with(|stats| stats.borrow().get(&product_id))
+        .unwrap_or_else(|| ProductRewardStats {
+            product_id,
+            ..Default::default()
+        })
+}
+
 // Reset ALL rewards-related stable storage (use with caution)
 pub fn reset_rewards_storage() {
     USER_REWARDS.with(|rewards| {
@@ -257,5 +438,19 @@ pub fn reset_rewards_storage() {
             promos_mut.remove(&key);
         }
     });
-    ic_cdk::print("ℹ️ All rewards-related stable storage has been reset.");
+    PRODUCT_REWARD_STATS.with(|stats| {
+        let mut stats_mut = stats.borrow_mut();
+        let keys: Vec<_> = stats_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            stats_mut.remove(&key);
+        }
+    });
+    REWARD_PENALTIES.with(|penalties| {
+        let mut penalties_mut = penalties.borrow_mut();
+        let keys: Vec<_> = penalties_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            penalties_mut.remove(&key);
+        }
+    });
+    log_info!("All rewards-related stable storage has been reset.");
 }
\ No newline at end of file
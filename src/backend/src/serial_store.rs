@@ -0,0 +1,376 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ops::Bound;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCT_SERIAL_NUMBERS};
+use crate::models::ProductSerialNumber;
+
+// Define unique Memory IDs for the structures in this module
+pub const SERIAL_RECORDS_MEM_ID: MemoryId = MemoryId::new(49);
+const SERIAL_INDEX_MEM_ID: MemoryId = MemoryId::new(50);
+const SERIAL_SEQUENCES_MEM_ID: MemoryId = MemoryId::new(56);
+const SERIAL_SEQUENCE_INDEX_MEM_ID: MemoryId = MemoryId::new(57);
+const USER_SERIAL_INDEX_MEM_ID: MemoryId = MemoryId::new(58);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// (product_id, serial_no): creating or printing a serial rewrites one record instead of the
+// product's whole serial list.
+pub type SerialKey = (Principal, Principal);
+
+// Human-readable serials are only unique within their product
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UserSerialKey {
+    pub product_id: Principal,
+    pub user_serial_no: String,
+}
+
+impl Storable for UserSerialKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+thread_local! {
+    static SERIAL_RECORDS: RefCell<StableBTreeMap<SerialKey, ProductSerialNumber, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(SERIAL_RECORDS_MEM_ID))
+        )
+    );
+
+    // serial_no -> product_id, for lookups that only have the serial
+    static SERIAL_INDEX: RefCell<StableBTreeMap<Principal, Principal, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(SERIAL_INDEX_MEM_ID))
+        )
+    );
+
+    // Last sequence number handed out per product, for products with sequential serials
+    static SERIAL_SEQUENCES: RefCell<StableBTreeMap<Principal, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(SERIAL_SEQUENCES_MEM_ID))
+        )
+    );
+
+    // (product_id, sequence_no) -> serial_no, so a printed range is a range query
+    static SERIAL_SEQUENCE_INDEX: RefCell<StableBTreeMap<(Principal, u64), Principal, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(SERIAL_SEQUENCE_INDEX_MEM_ID))
+        )
+    );
+
+    // (product_id, user_serial_no) -> serial_no
+    static USER_SERIAL_INDEX: RefCell<StableBTreeMap<UserSerialKey, Principal, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(USER_SERIAL_INDEX_MEM_ID))
+        )
+    );
+}
+
+// Re-open the records map after its memory was written through another handle
+pub fn reload_records() {
+    SERIAL_RECORDS.with(|records| {
+        *records.borrow_mut() = StableBTreeMap::init(MEMORY_MANAGER.with(|m| m.borrow().get(SERIAL_RECORDS_MEM_ID)))
+    });
+}
+
+fn product_range(product_id: Principal) -> std::ops::RangeInclusive<SerialKey> {
+    (product_id, Principal::from_slice(&[]))..=(product_id, Principal::from_slice(&[0xFF; 29]))
+}
+
+// Serials of a product still stored as one Candid vector in the old layout. None if the product
+// has been migrated, or its vector does not decode.
+fn legacy_vector(product_id: Principal) -> Option<Vec<ProductSerialNumber>> {
+    let bytes = PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().get(&product_id))?;
+    match decode_one(&bytes.0) {
+        Ok(serials) => Some(serials),
+        Err(e) => {
+            log_error!("[legacy_vector] Serial numbers of product {} do not decode: {}", product_id, e);
+            None
+        }
+    }
+}
+
+// Move one product's legacy vector into this store. Returns false if there was nothing to move.
+fn migrate_product(product_id: Principal) -> bool {
+    let serials = match legacy_vector(product_id) {
+        Some(serials) => serials,
+        None => return false,
+    };
+    for serial in serials {
+        // Stored under the key it was filed under, even if the record's own product_id disagrees
+        SERIAL_INDEX.with(|index| index.borrow_mut().insert(serial.serial_no, product_id));
+        SERIAL_RECORDS.with(|records| records.borrow_mut().insert((product_id, serial.serial_no), serial));
+    }
+    PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow_mut().remove(&product_id));
+    true
+}
+
+pub fn get(product_id: Principal, serial_no: Principal) -> Option<ProductSerialNumber> {
+    if let Some(legacy) = legacy_vector(product_id) {
+        return legacy.into_iter().find(|sn| sn.serial_no == serial_no);
+    }
+    SERIAL_RECORDS.with(|records| records.borrow().get(&(product_id, serial_no)))
+}
+
+// Look a serial up without knowing its product, returning the product it is stored under
+pub fn find(serial_no: Principal) -> Option<(Principal, ProductSerialNumber)> {
+    if let Some(product_id) = SERIAL_INDEX.with(|index| index.borrow().get(&serial_no)) {
+        return get(product_id, serial_no).map(|sn| (product_id, sn));
+    }
+    // Not indexed yet: search the products still in the old layout
+    let legacy: Vec<Principal> = PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().iter().map(|(k, _)| k).collect());
+    legacy.into_iter().find_map(|product_id| {
+        legacy_vector(product_id)?
+            .into_iter()
+            .find(|sn| sn.serial_no == serial_no)
+            .map(|sn| (product_id, sn))
+    })
+}
+
+// All serials of a product
+pub fn list_for_product(product_id: Principal) -> Vec<ProductSerialNumber> {
+    if let Some(legacy) = legacy_vector(product_id) {
+        return legacy;
+    }
+    SERIAL_RECORDS.with(|records| records.borrow().range(product_range(product_id)).map(|(_, sn)| sn).collect())
+}
+
+// Serials of a product in serial number order, those after `after` when it is set, at most `limit` of them
+pub fn list_for_product_after(product_id: Principal, after: Option<Principal>, limit: usize) -> Vec<ProductSerialNumber> {
+    if let Some(mut legacy) = legacy_vector(product_id) {
+        legacy.sort_by_key(|sn| sn.serial_no);
+        return legacy
+            .into_iter()
+            .filter(|sn| after.is_none_or(|after| sn.serial_no > after))
+            .take(limit)
+            .collect();
+    }
+    let range = product_range(product_id);
+    let start = match after {
+        Some(after) => Bound::Excluded((product_id, after)),
+        None => Bound::Included(*range.start()),
+    };
+    SERIAL_RECORDS.with(|records| {
+        records
+            .borrow()
+            .range((start, Bound::Included(*range.end())))
+            .take(limit)
+            .map(|(_, sn)| sn)
+            .collect()
+    })
+}
+
+pub fn count_for_product(product_id: Principal) -> u64 {
+    if let Some(legacy) = legacy_vector(product_id) {
+        return legacy.len() as u64;
+    }
+    SERIAL_RECORDS.with(|records| records.borrow().range(product_range(product_id)).count() as u64)
+}
+
+// Encoded size of a product's serials, for storage quotas
+pub fn stored_bytes_for_product(product_id: Principal) -> u64 {
+    if let Some(bytes) = PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().get(&product_id)) {
+        return bytes.0.len() as u64;
+    }
+    SERIAL_RECORDS.with(|records| {
+        records
+            .borrow()
+            .range(product_range(product_id))
+            .map(|(_, sn)| sn.to_bytes().len() as u64)
+            .sum()
+    })
+}
+
+// Products owning a serial whose text form starts with `prefix`, at most `limit` of them. Principal
+// text leads with a checksum, so this is a scan rather than a range query.
+pub fn product_ids_by_text_prefix(prefix: &str, limit: usize) -> Vec<Principal> {
+    let mut product_ids: Vec<Principal> = Vec::new();
+    let legacy: Vec<Principal> = PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().iter().map(|(k, _)| k).collect());
+    for product_id in legacy {
+        if product_ids.len() >= limit {
+            return product_ids;
+        }
+        let serials = legacy_vector(product_id).unwrap_or_default();
+        if serials.iter().any(|sn| sn.serial_no.to_text().starts_with(prefix)) {
+            product_ids.push(product_id);
+        }
+    }
+    SERIAL_INDEX.with(|index| {
+        for (serial_no, product_id) in index.borrow().iter() {
+            if product_ids.len() >= limit {
+                break;
+            }
+            if !product_ids.contains(&product_id) && serial_no.to_text().starts_with(prefix) {
+                product_ids.push(product_id);
+            }
+        }
+    });
+    product_ids
+}
+
+// Products with at least one stored serial, in either layout
+pub fn product_ids() -> Vec<Principal> {
+    let mut product_ids: Vec<Principal> =
+        PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().iter().map(|(k, _)| k).collect());
+    SERIAL_RECORDS.with(|records| {
+        for ((product_id, _), _) in records.borrow().iter() {
+            // Records are grouped by product, and a migrated product has no legacy vector left
+            if product_ids.last() != Some(&product_id) {
+                product_ids.push(product_id);
+            }
+        }
+    });
+    product_ids
+}
+
+// Visit every stored serial, grouped by product
+pub fn for_each<F>(mut visit: F)
+where
+    F: FnMut(&ProductSerialNumber),
+{
+    for product_id in PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().iter().map(|(k, _)| k).collect::<Vec<_>>()) {
+        for serial in legacy_vector(product_id).unwrap_or_default() {
+            visit(&serial);
+        }
+    }
+    SERIAL_RECORDS.with(|records| {
+        for (_, serial) in records.borrow().iter() {
+            visit(&serial);
+        }
+    });
+}
+
+// Store a serial, replacing any existing record with the same product and serial number
+pub fn insert(serial: ProductSerialNumber) {
+    migrate_product(serial.product_id);
+    SERIAL_INDEX.with(|index| index.borrow_mut().insert(serial.serial_no, serial.product_id));
+    if let Some(sequence_no) = serial.sequence_no {
+        SERIAL_SEQUENCE_INDEX.with(|index| index.borrow_mut().insert((serial.product_id, sequence_no), serial.serial_no));
+    }
+    if let Some(user_serial_no) = &serial.user_serial_no {
+        let key = UserSerialKey { product_id: serial.product_id, user_serial_no: user_serial_no.clone() };
+        USER_SERIAL_INDEX.with(|index| index.borrow_mut().insert(key, serial.serial_no));
+    }
+    SERIAL_RECORDS.with(|records| records.borrow_mut().insert((serial.product_id, serial.serial_no), serial));
+}
+
+// Remove a serial and its index entries, e.g. when the operation that created it is undone
+pub fn remove(product_id: Principal, serial_no: Principal) -> Option<ProductSerialNumber> {
+    migrate_product(product_id);
+    let serial = SERIAL_RECORDS.with(|records| records.borrow_mut().remove(&(product_id, serial_no)))?;
+    SERIAL_INDEX.with(|index| index.borrow_mut().remove(&serial_no));
+    if let Some(sequence_no) = serial.sequence_no {
+        SERIAL_SEQUENCE_INDEX.with(|index| index.borrow_mut().remove(&(product_id, sequence_no)));
+    }
+    if let Some(user_serial_no) = &serial.user_serial_no {
+        let key = UserSerialKey { product_id, user_serial_no: user_serial_no.clone() };
+        USER_SERIAL_INDEX.with(|index| index.borrow_mut().remove(&key));
+    }
+    Some(serial)
+}
+
+// Allocate the next sequence number (starting at 1) for a serial of the product. Never reused,
+// even if the serial it was given is never stored.
+pub fn next_sequence_no(product_id: Principal) -> u64 {
+    SERIAL_SEQUENCES.with(|sequences| {
+        let mut sequences_mut = sequences.borrow_mut();
+        let next = sequences_mut.get(&product_id).unwrap_or(0) + 1;
+        sequences_mut.insert(product_id, next);
+        next
+    })
+}
+
+// Serials of the product numbered from `from_seq` to `to_seq` inclusive, in sequence order
+pub fn list_sequence_range(product_id: Principal, from_seq: u64, to_seq: u64) -> Vec<ProductSerialNumber> {
+    let serial_nos: Vec<Principal> = SERIAL_SEQUENCE_INDEX.with(|index| {
+        index
+            .borrow()
+            .range((product_id, from_seq)..=(product_id, to_seq))
+            .map(|(_, serial_no)| serial_no)
+            .collect()
+    });
+    serial_nos.into_iter().filter_map(|serial_no| get(product_id, serial_no)).collect()
+}
+
+// Serial of the product with the given human-readable serial number
+pub fn find_by_user_serial(product_id: Principal, user_serial_no: &str) -> Option<ProductSerialNumber> {
+    let key = UserSerialKey { product_id, user_serial_no: user_serial_no.to_string() };
+    let serial_no = USER_SERIAL_INDEX.with(|index| index.borrow().get(&key))?;
+    get(product_id, serial_no)
+}
+
+pub fn user_serial_taken(product_id: Principal, user_serial_no: &str) -> bool {
+    let key = UserSerialKey { product_id, user_serial_no: user_serial_no.to_string() };
+    USER_SERIAL_INDEX.with(|index| index.borrow().contains_key(&key))
+}
+
+// Apply `update` to a stored serial and write it back
+pub fn update<F>(product_id: Principal, serial_no: Principal, update: F) -> Option<ProductSerialNumber>
+where
+    F: FnOnce(&mut ProductSerialNumber),
+{
+    migrate_product(product_id);
+    let mut serial = SERIAL_RECORDS.with(|records| records.borrow().get(&(product_id, serial_no)))?;
+    update(&mut serial);
+    SERIAL_RECORDS.with(|records| records.borrow_mut().insert((product_id, serial_no), serial.clone()));
+    Some(serial)
+}
+
+// Move every decodable legacy vector into this store. Vectors that fail to decode are left where
+// they are, for the integrity tooling to quarantine; reads treat them as empty.
+pub fn migrate_legacy_vectors() -> u32 {
+    let legacy: Vec<Principal> = PRODUCT_SERIAL_NUMBERS.with(|map| map.borrow().iter().map(|(k, _)| k).collect());
+    legacy.into_iter().filter(|product_id| migrate_product(*product_id)).count() as u32
+}
+
+// Reset ALL serial records, the serial index and sequence numbers (use with caution)
+pub fn reset_serial_records() {
+    SERIAL_RECORDS.with(|records| {
+        let mut records_mut = records.borrow_mut();
+        let keys: Vec<_> = records_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            records_mut.remove(&key);
+        }
+    });
+    SERIAL_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    SERIAL_SEQUENCES.with(|sequences| {
+        let mut sequences_mut = sequences.borrow_mut();
+        let keys: Vec<_> = sequences_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            sequences_mut.remove(&key);
+        }
+    });
+    SERIAL_SEQUENCE_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    USER_SERIAL_INDEX.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    log_info!("All serial records have been reset.");
+}
@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use candid::Principal;
+use crate::error::ApiError;
+use crate::keys;
+use crate::models::Organization;
+
+// Label printers sign in bulk, so the budget is per organization rather than per printer
+const MAX_SIGNATURES_PER_ORG: u32 = 10_000;
+const SIGNATURE_WINDOW_NS: u64 = 60 * 60 * 1_000_000_000;
+
+thread_local! {
+    // org_id -> (window_start, signatures in window); heap only, so an upgrade simply opens new windows
+    static SIGNATURE_WINDOWS: RefCell<HashMap<Principal, (u64, u32)>> = RefCell::new(HashMap::new());
+}
+
+// Count a signature against the organization's budget, failing once it is used up for the current window
+pub fn record_signature(org_id: Principal, now: u64) -> Result<(), ApiError> {
+    SIGNATURE_WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        let window = windows.entry(org_id).or_insert((now, 0));
+        if now >= window.0.saturating_add(SIGNATURE_WINDOW_NS) {
+            *window = (now, 0);
+        }
+        if window.1 >= MAX_SIGNATURES_PER_ORG {
+            return Err(ApiError::invalid_input(&format!(
+                "Signing rate limit exceeded. Try again after {}",
+                window.0 + SIGNATURE_WINDOW_NS
+            )));
+        }
+        window.1 += 1;
+        Ok(())
+    })
+}
+
+// Decode a hex SHA-256 digest
+pub fn parse_payload_hash(payload_hash: &str) -> Result<Vec<u8>, ApiError> {
+    let bytes = hex::decode(payload_hash.trim())
+        .map_err(|_| ApiError::invalid_input("Payload hash must be hex encoded"))?;
+    if bytes.len() != 32 {
+        return Err(ApiError::invalid_input("Payload hash must be a 32-byte SHA-256 digest"));
+    }
+    Ok(bytes)
+}
+
+// Sign the digest the same way unique codes are signed, so a printer that submits
+// SHA-256(msg) gets the code the canister would have generated for msg.
+// Returns the hex signature and the hex, uncompressed SEC1 public key to verify it with.
+pub fn sign_payload_hash(organization: &Organization, payload_hash: &[u8]) -> Result<(String, String), ApiError> {
+    let signing_key = keys::org_signing_key(organization).map_err(|e| {
+        log_error!("[sign_payload_hash] Failed to parse private key for org {}: {:?}", organization.id, e);
+        e
+    })?;
+
+    let signature = signing_key.sign(payload_hash);
+    Ok((hex::encode(signature.to_bytes()), signing_key.verifying_key().to_hex()))
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{ProductVerificationStatus, StatusMessage};
+
+pub const DEFAULT_LOCALE: &str = "en";
+pub const MAX_MESSAGE_LENGTH: usize = 300;
+
+// Define a unique MemoryId for this structure
+const STATUS_MESSAGES_MEM_ID: MemoryId = MemoryId::new(84);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by "<status>:<locale>", e.g. "Invalid:pt-BR"
+    static STATUS_MESSAGES: RefCell<StableBTreeMap<String, StatusMessage, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(STATUS_MESSAGES_MEM_ID))
+        )
+    );
+}
+
+fn status_key(status: &ProductVerificationStatus) -> &'static str {
+    match status {
+        ProductVerificationStatus::FirstVerification => "FirstVerification",
+        ProductVerificationStatus::MultipleVerification => "MultipleVerification",
+        ProductVerificationStatus::Invalid => "Invalid",
+        ProductVerificationStatus::VoidedSerial => "VoidedSerial",
+        ProductVerificationStatus::CodeRevoked => "CodeRevoked",
+        ProductVerificationStatus::NotYetActive => "NotYetActive",
+        ProductVerificationStatus::SupersededCode => "SupersededCode",
+    }
+}
+
+fn catalog_key(status: &ProductVerificationStatus, locale: &str) -> String {
+    format!("{}:{}", status_key(status), locale)
+}
+
+// Text used when the catalog has no entry for the status, not even in the default locale
+pub fn built_in_message(status: &ProductVerificationStatus) -> &'static str {
+    match status {
+        ProductVerificationStatus::FirstVerification => "Genuine product. This is the first time this code has been verified.",
+        ProductVerificationStatus::MultipleVerification => "Genuine product. This code has been verified before.",
+        ProductVerificationStatus::Invalid => "This code could not be verified. The product may not be genuine.",
+        ProductVerificationStatus::VoidedSerial => "This label has been voided by the brand and is no longer valid.",
+        ProductVerificationStatus::CodeRevoked => "This code has been revoked by the brand. Contact the brand before relying on this product.",
+        ProductVerificationStatus::NotYetActive => "This code is genuine but not active yet. Try again once the product has launched.",
+        ProductVerificationStatus::SupersededCode => "This label was replaced by a newer one. Scan the current label, or contact the brand if this is the only one.",
+    }
+}
+
+// Canonical form of a language tag: a 2-3 letter language, optionally followed by a 2 letter or
+// 3 digit region, e.g. "pt_br" becomes "pt-BR"
+pub fn normalize_locale(locale: &str) -> Result<String, String> {
+    let invalid = || format!("'{}' is not a supported locale; use a language code such as 'en' or 'pt-BR'", locale.trim());
+    let mut parts = locale.trim().split(['-', '_']);
+    let language = parts.next().unwrap_or("");
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid());
+    }
+    let mut normalized = language.to_ascii_lowercase();
+    if let Some(region) = parts.next() {
+        let valid_region = (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+            || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit()));
+        if !valid_region {
+            return Err(invalid());
+        }
+        normalized.push('-');
+        normalized.push_str(&region.to_ascii_uppercase());
+    }
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(normalized)
+}
+
+pub fn get_message(status: &ProductVerificationStatus, locale: &str) -> Option<StatusMessage> {
+    STATUS_MESSAGES.with(|messages| messages.borrow().get(&catalog_key(status, locale)))
+}
+
+pub fn save_message(message: StatusMessage) {
+    STATUS_MESSAGES.with(|messages| {
+        messages.borrow_mut().insert(catalog_key(&message.status, &message.locale), message);
+    });
+}
+
+pub fn remove_message(status: &ProductVerificationStatus, locale: &str) -> Option<StatusMessage> {
+    STATUS_MESSAGES.with(|messages| messages.borrow_mut().remove(&catalog_key(status, locale)))
+}
+
+pub fn list_messages() -> Vec<StatusMessage> {
+    STATUS_MESSAGES.with(|messages| messages.borrow().iter().map(|(_, message)| message).collect())
+}
+
+// Text for the status in the closest locale the catalog has: the exact locale, then its language,
+// then the default locale, then the built-in English text. Returns the locale the text is in.
+pub fn describe(status: &ProductVerificationStatus, locale: Option<&str>) -> (String, String) {
+    let mut candidates: Vec<String> = Vec::new();
+    if let Some(locale) = locale.and_then(|l| normalize_locale(l).ok()) {
+        if let Some((language, _)) = locale.split_once('-') {
+            let language = language.to_string();
+            candidates.push(locale);
+            candidates.push(language);
+        } else {
+            candidates.push(locale);
+        }
+    }
+    candidates.push(DEFAULT_LOCALE.to_string());
+
+    candidates
+        .into_iter()
+        .find_map(|candidate| get_message(status, &candidate).map(|message| (message.text, candidate)))
+        .unwrap_or_else(|| (built_in_message(status).to_string(), DEFAULT_LOCALE.to_string()))
+}
+
+// Text only, for embedding in verification responses
+pub fn message_for(status: &ProductVerificationStatus, locale: Option<&str>) -> String {
+    describe(status, locale).0
+}
+
+// Reset ALL status message overrides (use with caution); the built-in texts remain
+pub fn reset_status_messages() {
+    STATUS_MESSAGES.with(|messages| {
+        let mut messages_mut = messages.borrow_mut();
+        let keys: Vec<_> = messages_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            messages_mut.remove(&key);
+        }
+    });
+    log_info!("All status messages have been reset.");
+}
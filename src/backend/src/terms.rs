@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{TermsAcceptance, TermsVersion};
+
+pub const MAX_TERMS_VERSION_LENGTH: usize = 32;
+
+// Define unique Memory IDs for the structures in this module
+const TERMS_VERSIONS_MEM_ID: MemoryId = MemoryId::new(60);
+const TERMS_ACCEPTANCES_MEM_ID: MemoryId = MemoryId::new(61);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// (user_id, seq of the accepted terms version)
+type AcceptanceKey = (Principal, u64);
+
+thread_local! {
+    // Keyed by publication order; the last entry is the version users must accept
+    static TERMS_VERSIONS: RefCell<StableBTreeMap<u64, TermsVersion, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(TERMS_VERSIONS_MEM_ID))
+        )
+    );
+
+    // Every acceptance is kept, so a user's consent history can be shown for any past version
+    static TERMS_ACCEPTANCES: RefCell<StableBTreeMap<AcceptanceKey, TermsAcceptance, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(TERMS_ACCEPTANCES_MEM_ID))
+        )
+    );
+}
+
+pub fn current_terms() -> Option<TermsVersion> {
+    TERMS_VERSIONS.with(|versions| versions.borrow().last_key_value().map(|(_, terms)| terms))
+}
+
+pub fn version_exists(version: &str) -> bool {
+    TERMS_VERSIONS.with(|versions| versions.borrow().iter().any(|(_, terms)| terms.version == version))
+}
+
+// Publish a new version; from now on it is the one users must accept
+pub fn publish(mut terms: TermsVersion) -> TermsVersion {
+    TERMS_VERSIONS.with(|versions| {
+        let mut versions_mut = versions.borrow_mut();
+        terms.seq = versions_mut.last_key_value().map_or(0, |(seq, _)| seq + 1);
+        versions_mut.insert(terms.seq, terms.clone());
+    });
+    terms
+}
+
+pub fn list_versions() -> Vec<TermsVersion> {
+    TERMS_VERSIONS.with(|versions| versions.borrow().iter().map(|(_, terms)| terms).collect())
+}
+
+pub fn record_acceptance(acceptance: TermsAcceptance) {
+    TERMS_ACCEPTANCES.with(|acceptances| {
+        acceptances.borrow_mut().insert((acceptance.user_id, acceptance.terms_seq), acceptance);
+    });
+}
+
+pub fn has_accepted(user_id: Principal, terms_seq: u64) -> bool {
+    TERMS_ACCEPTANCES.with(|acceptances| acceptances.borrow().contains_key(&(user_id, terms_seq)))
+}
+
+// The user's acceptances, oldest version first
+pub fn list_for_user(user_id: Principal) -> Vec<TermsAcceptance> {
+    TERMS_ACCEPTANCES.with(|acceptances| {
+        acceptances
+            .borrow()
+            .range((user_id, 0)..=(user_id, u64::MAX))
+            .map(|(_, acceptance)| acceptance)
+            .collect()
+    })
+}
+
+// Reward-earning actions need the current terms accepted. Passes while no terms have been published.
+pub fn ensure_terms_accepted(user_id: Principal) -> Result<(), ApiError> {
+    match current_terms() {
+        Some(terms) if !has_accepted(user_id, terms.seq) => Err(ApiError::terms_not_accepted(&terms.version, &terms.url)),
+        _ => Ok(()),
+    }
+}
+
+// Reset ALL terms versions and acceptances (use with caution)
+pub fn reset_terms() {
+    TERMS_VERSIONS.with(|versions| {
+        let mut versions_mut = versions.borrow_mut();
+        let keys: Vec<_> = versions_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            versions_mut.remove(&key);
+        }
+    });
+    TERMS_ACCEPTANCES.with(|acceptances| {
+        let mut acceptances_mut = acceptances.borrow_mut();
+        let keys: Vec<_> = acceptances_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            acceptances_mut.remove(&key);
+        }
+    });
+    log_info!("All terms versions and acceptances have been reset.");
+}
@@ -0,0 +1,41 @@
+// Writes of one operation that must land together. Stable memory has no transactions, and returning
+// an error does not roll a call back (only a trap does), so each write registers how to undo it.
+// Dropping the unit without commit(), e.g. on an early `return` or `?`, runs the undo actions newest
+// first, leaving no half-created entities behind.
+pub struct UnitOfWork {
+    operation: &'static str,
+    undo_actions: Vec<Box<dyn FnOnce()>>,
+    committed: bool,
+}
+
+impl UnitOfWork {
+    pub fn new(operation: &'static str) -> Self {
+        UnitOfWork {
+            operation,
+            undo_actions: Vec::new(),
+            committed: false,
+        }
+    }
+
+    // Register how to undo a write that has just been made
+    pub fn on_rollback(&mut self, undo: impl FnOnce() + 'static) {
+        self.undo_actions.push(Box::new(undo));
+    }
+
+    // Keep every write made so far
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for UnitOfWork {
+    fn drop(&mut self) {
+        if self.committed || self.undo_actions.is_empty() {
+            return;
+        }
+        log_warn!("[{}] Failed part way; undoing {} write(s)", self.operation, self.undo_actions.len());
+        while let Some(undo) = self.undo_actions.pop() {
+            undo();
+        }
+    }
+}
@@ -1,14 +1,26 @@
 use candid::Principal;
-use ic_cdk::api::time;
+use crate::clock;
 use sha2::{Sha256, Digest};
+use std::cell::Cell;
 use std::time::Duration;
 use futures::channel::oneshot;
 use ic_cdk_timers::set_timer;
 
 
+thread_local! {
+    // Bumped on every call, since the time does not advance within a single message
+    static ID_NONCE: Cell<u64> = const { Cell::new(0) };
+}
+
 pub fn generate_unique_principal(principal: Principal) -> Principal {
-    // Combine the principal text and the current time
-    let input = format!("{}-{}", principal.to_text(), time());
+    let nonce = ID_NONCE.with(|n| {
+        let value = n.get();
+        n.set(value.wrapping_add(1));
+        value
+    });
+
+    // Combine the principal text, the current time and the nonce
+    let input = format!("{}-{}-{}", principal.to_text(), clock::now(), nonce);
 
     // Hash the combined input using SHA-256
     let mut hasher = Sha256::new();
@@ -25,7 +37,7 @@ pub fn generate_unique_principal(principal: Principal) -> Principal {
 /// Uses a oneshot channel and `ic_cdk_timers::set_timer`.
 pub async fn async_delay(duration: Duration) {
     let (tx, rx) = oneshot::channel::<()>();
-    ic_cdk::print(format!("⏱️ Setting timer for {:?}", duration)); // Optional: Log timer setting
+    log_info!("Setting timer for {:?}", duration); // Optional: Log timer setting
     set_timer(duration, move || {
         let _ = tx.send(()); // Signal completion, ignore result
     });
@@ -33,7 +45,7 @@ pub async fn async_delay(duration: Duration) {
         Ok(_) => { /* Timer completed successfully */ }
         Err(e) => {
             // This should ideally not happen in canister environment unless timer logic fails
-            ic_cdk::print(format!("❌ ERROR: Timer future cancelled: {:?}", e));
+            log_error!("Timer future cancelled: {:?}", e);
         }
     }
 }
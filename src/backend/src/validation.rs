@@ -0,0 +1,200 @@
+use crate::error::{ApiError, ValidationError};
+use crate::models::Metadata;
+
+// Length caps on profile input
+pub const MAX_NAME_LENGTH: usize = 100;
+pub const MAX_EMAIL_LENGTH: usize = 254;
+pub const MAX_URL_LENGTH: usize = 2048;
+pub const MAX_METADATA_ENTRIES: usize = 50;
+pub const MAX_METADATA_KEY_LENGTH: usize = 64;
+pub const MAX_METADATA_VALUE_LENGTH: usize = 1024;
+
+// E.164 allows at most 15 digits including the country code
+const MIN_PHONE_DIGITS: usize = 8;
+const MAX_PHONE_DIGITS: usize = 15;
+
+// Collects field-level errors so a request reports every problem at once
+#[derive(Default)]
+pub struct Validator {
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_error(&mut self, field: &str, message: &str) {
+        self.errors.push(ValidationError {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    // Trimmed value, which must be non-empty and within `max` characters
+    pub fn required_text(&mut self, field: &str, value: &str, max: usize) -> String {
+        let value = value.trim();
+        if value.is_empty() {
+            self.add_error(field, "This field is required");
+        } else if value.chars().count() > max {
+            self.add_error(field, &format!("Must be at most {} characters", max));
+        }
+        value.to_string()
+    }
+
+    // Trimmed, lower-cased email; an empty value is treated as not provided
+    pub fn email(&mut self, field: &str, value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        if let Err(message) = check_email(value) {
+            self.add_error(field, message);
+        }
+        Some(value.to_lowercase())
+    }
+
+    // Phone number normalized to E.164 (+<country code><number>); an empty value is treated as not provided
+    pub fn phone(&mut self, field: &str, value: &str) -> Option<String> {
+        if value.trim().is_empty() {
+            return None;
+        }
+        match normalize_phone(value) {
+            Ok(normalized) => Some(normalized),
+            Err(message) => {
+                self.add_error(field, message);
+                Some(value.trim().to_string())
+            }
+        }
+    }
+
+    pub fn url(&mut self, field: &str, value: &str) {
+        if let Err(message) = check_url(value.trim()) {
+            self.add_error(field, message);
+        }
+    }
+
+    pub fn metadata(&mut self, field: &str, entries: &[Metadata]) {
+        if entries.len() > MAX_METADATA_ENTRIES {
+            self.add_error(field, &format!("At most {} entries are allowed", MAX_METADATA_ENTRIES));
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            if entry.key.trim().is_empty() {
+                self.add_error(&format!("{}[{}].key", field, i), "Key cannot be empty");
+            } else if entry.key.chars().count() > MAX_METADATA_KEY_LENGTH {
+                self.add_error(&format!("{}[{}].key", field, i), &format!("Must be at most {} characters", MAX_METADATA_KEY_LENGTH));
+            }
+            if entry.value.chars().count() > MAX_METADATA_VALUE_LENGTH {
+                self.add_error(&format!("{}[{}].value", field, i), &format!("Must be at most {} characters", MAX_METADATA_VALUE_LENGTH));
+            }
+        }
+    }
+
+    // Metadata whose values must be http(s) URLs, e.g. ecommerce_urls
+    pub fn url_metadata(&mut self, field: &str, entries: &[Metadata]) {
+        self.metadata(field, entries);
+        for (i, entry) in entries.iter().enumerate() {
+            if let Err(message) = check_url(entry.value.trim()) {
+                self.add_error(&format!("{}[{}].value", field, i), message);
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::validation_failed(self.errors))
+        }
+    }
+}
+
+// Syntax check only. Unicode letters are accepted in both parts to allow internationalized addresses.
+pub fn check_email(email: &str) -> Result<(), &'static str> {
+    if email.chars().count() > MAX_EMAIL_LENGTH {
+        return Err("Email address is too long");
+    }
+    let (local, domain) = match email.rsplit_once('@') {
+        Some(parts) => parts,
+        None => return Err("Email address must contain '@'"),
+    };
+
+    let local_ok = !local.is_empty()
+        && local.chars().count() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || "!#$%&'*+/=?^_`{|}~.-".contains(c));
+    if !local_ok {
+        return Err("Email address has an invalid local part");
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.chars().count() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+        && labels.last().is_some_and(|tld| tld.chars().count() >= 2 && tld.chars().all(|c| c.is_alphabetic()));
+    if !domain_ok {
+        return Err("Email address has an invalid domain");
+    }
+    Ok(())
+}
+
+// Accepts common formatting (spaces, dashes, dots, parentheses) and a "+" or "00" international prefix
+pub fn normalize_phone(phone: &str) -> Result<String, &'static str> {
+    let compact: String = phone
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+
+    let digits = if let Some(rest) = compact.strip_prefix('+') {
+        rest
+    } else if let Some(rest) = compact.strip_prefix("00") {
+        rest
+    } else {
+        return Err("Phone number must include the country code, e.g. +62...");
+    };
+
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Phone number can only contain digits");
+    }
+    if digits.starts_with('0') {
+        return Err("Country code cannot start with 0");
+    }
+    if digits.len() < MIN_PHONE_DIGITS || digits.len() > MAX_PHONE_DIGITS {
+        return Err("Phone number must have between 8 and 15 digits");
+    }
+    Ok(format!("+{}", digits))
+}
+
+pub fn check_url(url: &str) -> Result<(), &'static str> {
+    if url.len() > MAX_URL_LENGTH {
+        return Err("URL is too long");
+    }
+    let rest = match url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) {
+        Some(rest) => rest,
+        None => return Err("URL must start with http:// or https://"),
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if host.is_empty() || host.contains(char::is_whitespace) {
+        return Err("URL must include a host");
+    }
+    Ok(())
+}
+
+// ISO 3166-1 alpha-2 code, upper-cased ("de" -> "DE")
+pub fn normalize_country_code(code: &str) -> Result<String, &'static str> {
+    let code = code.trim();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("Country code must be a two-letter ISO 3166-1 code");
+    }
+    Ok(code.to_ascii_uppercase())
+}
@@ -0,0 +1,77 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::ProductVariant;
+
+// Upper bound on variants under one product
+pub const MAX_VARIANTS_PER_PRODUCT: usize = 100;
+
+// Define a unique MemoryId for this structure
+const PRODUCT_VARIANTS_MEM_ID: MemoryId = MemoryId::new(25);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static PRODUCT_VARIANTS: RefCell<StableBTreeMap<Principal, ProductVariant, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(PRODUCT_VARIANTS_MEM_ID))
+        )
+    );
+}
+
+pub fn save_variant(variant: ProductVariant) {
+    PRODUCT_VARIANTS.with(|variants| {
+        variants.borrow_mut().insert(variant.id, variant);
+    });
+}
+
+pub fn get_variant(variant_id: Principal) -> Option<ProductVariant> {
+    PRODUCT_VARIANTS.with(|variants| variants.borrow().get(&variant_id))
+}
+
+pub fn remove_variant(variant_id: Principal) -> Option<ProductVariant> {
+    PRODUCT_VARIANTS.with(|variants| variants.borrow_mut().remove(&variant_id))
+}
+
+// Variants of a product, oldest first
+pub fn list_variants_for_product(product_id: Principal) -> Vec<ProductVariant> {
+    let mut variants: Vec<ProductVariant> = PRODUCT_VARIANTS.with(|variants| {
+        variants
+            .borrow()
+            .iter()
+            .filter(|(_, variant)| variant.product_id == product_id)
+            .map(|(_, variant)| variant)
+            .collect()
+    });
+    variants.sort_by_key(|a| a.created_at);
+    variants
+}
+
+// Remove the organization's variants of its products when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    PRODUCT_VARIANTS.with(|variants| {
+        let mut variants_mut = variants.borrow_mut();
+        let keys: Vec<_> = variants_mut.iter().filter(|(_, variant)| variant.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            variants_mut.remove(key);
+        }
+        keys.len() as u32
+    })
+}
+
+// Reset ALL product variants (use with caution)
+pub fn reset_product_variants() {
+    PRODUCT_VARIANTS.with(|variants| {
+        let mut variants_mut = variants.borrow_mut();
+        let keys: Vec<_> = variants_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            variants_mut.remove(&key);
+        }
+    });
+    log_info!("All product variants have been reset.");
+}
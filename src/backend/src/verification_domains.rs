@@ -0,0 +1,270 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod,
+};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use k256::elliptic_curve::rand_core::{RngCore, SeedableRng};
+use rand::prelude::StdRng;
+use serde::Deserialize;
+
+use crate::circuit_breakers;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{ExternalService, VerificationDomain, VerificationDomainStatus};
+use crate::outcall_transforms::{self, OutcallKind};
+use crate::quotas;
+
+// Public DNS-over-HTTPS resolver with a JSON API, since outcalls can only speak HTTPS
+const DNS_RESOLVER_URL: &str = "https://dns.google/resolve";
+// The proof lives on its own label, so it does not clash with other TXT records of the domain
+const PROOF_RECORD_LABEL: &str = "_trueorigin-verification";
+const PROOF_VALUE_PREFIX: &str = "trueorigin-verification=";
+const PROOF_TOKEN_BYTES: usize = 16;
+const MAX_DOMAIN_LENGTH: usize = 253;
+const MAX_LABEL_LENGTH: usize = 63;
+// Path of the verification page the deep links open
+const VERIFY_PATH: &str = "/verify";
+
+// Define a unique MemoryId for this structure
+const VERIFICATION_DOMAINS_MEM_ID: MemoryId = MemoryId::new(79);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // One domain per organization; registering another replaces it
+    static VERIFICATION_DOMAINS: RefCell<StableBTreeMap<Principal, VerificationDomain, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_DOMAINS_MEM_ID))
+        )
+    );
+}
+
+// Answer of the transform_dns transform, see outcall_transforms
+#[derive(Deserialize)]
+struct DnsTxtAnswer {
+    status: Option<u64>,
+    txt: Vec<String>,
+}
+
+// Lowercase host name without scheme, path, port or trailing dot, made of valid DNS labels
+pub fn normalize_domain(domain: &str) -> Result<String, ApiError> {
+    let domain = domain.trim().trim_end_matches('.').to_lowercase();
+    if domain.is_empty() || domain.len() > MAX_DOMAIN_LENGTH {
+        return Err(ApiError::invalid_input(&format!(
+            "Domain must be between 1 and {} characters",
+            MAX_DOMAIN_LENGTH
+        )));
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return Err(ApiError::invalid_input("Domain must include a top-level domain, e.g. verify.brand.com"));
+    }
+    for label in &labels {
+        let valid = !label.is_empty()
+            && label.len() <= MAX_LABEL_LENGTH
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !label.starts_with('-')
+            && !label.ends_with('-');
+        if !valid {
+            return Err(ApiError::invalid_input(&format!(
+                "'{}' is not a valid domain; give the host name only, without scheme, port or path",
+                domain
+            )));
+        }
+    }
+    Ok(domain)
+}
+
+pub fn proof_record_name(domain: &str) -> String {
+    format!("{}.{}", PROOF_RECORD_LABEL, domain)
+}
+
+pub fn proof_record_value(proof_token: &str) -> String {
+    format!("{}{}", PROOF_VALUE_PREFIX, proof_token)
+}
+
+pub fn get_domain(org_id: Principal) -> Option<VerificationDomain> {
+    VERIFICATION_DOMAINS.with(|domains| domains.borrow().get(&org_id))
+}
+
+fn save_domain(domain: VerificationDomain) {
+    VERIFICATION_DOMAINS.with(|domains| {
+        domains.borrow_mut().insert(domain.org_id, domain);
+    });
+}
+
+pub fn remove_domain(org_id: Principal) -> Option<VerificationDomain> {
+    VERIFICATION_DOMAINS.with(|domains| domains.borrow_mut().remove(&org_id))
+}
+
+// Organization other than `org_id` that has verified `domain`, if any
+fn verified_by_other(org_id: Principal, domain: &str) -> Option<Principal> {
+    VERIFICATION_DOMAINS.with(|domains| {
+        domains
+            .borrow()
+            .iter()
+            .map(|(_, d)| d)
+            .find(|d| d.org_id != org_id && d.domain == domain && d.status == VerificationDomainStatus::Verified)
+            .map(|d| d.org_id)
+    })
+}
+
+// Register a domain for the organization, pending until its TXT proof is checked. Registering the
+// same domain again keeps its token, so a record already published stays valid.
+pub fn register(org_id: Principal, domain: &str, caller: Principal, now: u64) -> Result<VerificationDomain, ApiError> {
+    let domain = normalize_domain(domain)?;
+    if verified_by_other(org_id, &domain).is_some() {
+        return Err(ApiError::already_exists(&format!(
+            "Domain {} is already verified by another organization",
+            domain
+        )));
+    }
+    if let Some(existing) = get_domain(org_id).filter(|d| d.domain == domain) {
+        return Ok(existing);
+    }
+
+    let mut token = [0u8; PROOF_TOKEN_BYTES];
+    StdRng::from_entropy().fill_bytes(&mut token);
+    let record = VerificationDomain {
+        org_id,
+        domain,
+        proof_token: hex::encode(token),
+        status: VerificationDomainStatus::Pending,
+        last_checked_at: None,
+        last_error: None,
+        verified_at: None,
+        created_at: now,
+        created_by: caller,
+        updated_at: now,
+    };
+    save_domain(record.clone());
+    Ok(record)
+}
+
+// TXT values published on `name`
+async fn lookup_txt(name: &str, org_id: Principal) -> Result<Vec<String>, ApiError> {
+    let request = CanisterHttpRequestArgument {
+        url: format!("{}?name={}&type=TXT", DNS_RESOLVER_URL, name),
+        method: HttpMethod::GET,
+        body: None,
+        max_response_bytes: Some(outcall_transforms::max_response_bytes(OutcallKind::DnsLookup)),
+        transform: Some(outcall_transforms::transform_context(OutcallKind::DnsLookup)),
+        headers: vec![HttpHeader {
+            name: "Accept".to_string(),
+            value: "application/dns-json".to_string(),
+        }],
+    };
+
+    circuit_breakers::begin_call(ExternalService::DnsResolver)?;
+    let cycles = outcall_transforms::request_cycles(&request);
+    let response = match http_request(request, cycles).await {
+        Ok((response,)) => response,
+        Err((rejection_code, message)) => {
+            let error_message = format!("DNS lookup failed. RejectionCode: {:?}, Error: {}", rejection_code, message);
+            // An oversized answer says nothing about the resolver's health
+            if outcall_transforms::is_oversized_rejection(&message) {
+                quotas::record_oversized_response(org_id);
+                circuit_breakers::record_success(ExternalService::DnsResolver);
+            } else {
+                circuit_breakers::record_failure(ExternalService::DnsResolver, &error_message);
+            }
+            return Err(ApiError::external_api_error(&error_message));
+        }
+    };
+    let status_code: u64 = response.status.0.try_into().unwrap_or(0);
+    if status_code >= 500 || status_code == 0 {
+        circuit_breakers::record_failure(ExternalService::DnsResolver, &format!("status {}", status_code));
+    } else {
+        circuit_breakers::record_success(ExternalService::DnsResolver);
+    }
+    if !(200..300).contains(&status_code) {
+        return Err(ApiError::external_api_error(&format!("DNS resolver returned status {}", status_code)));
+    }
+    let answer: DnsTxtAnswer = serde_json::from_slice(&response.body)
+        .map_err(|_| ApiError::external_api_error("DNS resolver returned an unreadable answer"))?;
+    // Status 3 is NXDOMAIN, which only means the record is not published yet
+    match answer.status {
+        Some(0) | Some(3) => Ok(answer.txt),
+        Some(status) => Err(ApiError::external_api_error(&format!("DNS lookup failed with DNS status {}", status))),
+        None => Err(ApiError::external_api_error("DNS resolver returned an unreadable answer")),
+    }
+}
+
+// Look up the organization's proof record and record the outcome. A lookup that could not be made
+// leaves a verified domain verified; a lookup that finds no proof marks it failed.
+pub async fn check(org_id: Principal, now: u64) -> Result<VerificationDomain, ApiError> {
+    let record = get_domain(org_id)
+        .ok_or_else(|| ApiError::not_found("No verification domain is registered for this organization"))?;
+    let expected = proof_record_value(&record.proof_token);
+    let lookup = lookup_txt(&proof_record_name(&record.domain), org_id).await;
+
+    // The record may have changed while the outcall was in flight
+    let mut record = match get_domain(org_id).filter(|d| d.domain == record.domain && d.proof_token == record.proof_token) {
+        Some(current) => current,
+        None => return Err(ApiError::invalid_input("The verification domain was changed during the check")),
+    };
+    record.last_checked_at = Some(now);
+    record.updated_at = now;
+    match lookup {
+        Ok(values) if values.iter().any(|v| v.trim() == expected) => {
+            if let Some(other) = verified_by_other(org_id, &record.domain) {
+                record.status = VerificationDomainStatus::Failed;
+                record.last_error = Some(format!("The domain was verified by organization {} first", other));
+            } else {
+                if record.status != VerificationDomainStatus::Verified {
+                    record.verified_at = Some(now);
+                }
+                record.status = VerificationDomainStatus::Verified;
+                record.last_error = None;
+            }
+        }
+        Ok(_) => {
+            record.status = VerificationDomainStatus::Failed;
+            record.verified_at = None;
+            record.last_error = Some(format!(
+                "No TXT record '{}' found on {}",
+                expected,
+                proof_record_name(&record.domain)
+            ));
+        }
+        Err(e) => {
+            log_warn!("[check] DNS lookup for org {} domain {} failed: {:?}", org_id, record.domain, e);
+            record.last_error = Some(format!("{:?}", e));
+            save_domain(record);
+            return Err(e);
+        }
+    }
+    save_domain(record.clone());
+    Ok(record)
+}
+
+// The organization's domain, once verified
+pub fn verified_domain(org_id: Principal) -> Option<String> {
+    get_domain(org_id)
+        .filter(|d| d.status == VerificationDomainStatus::Verified)
+        .map(|d| d.domain)
+}
+
+// Deep link to the verification page on the brand's verified domain, carrying what a scan verifies;
+// the page still verifies against the canister
+pub fn verification_url(org_id: Principal, serial_no: Principal, unique_code: &str) -> Option<String> {
+    verified_domain(org_id).map(|domain| {
+        format!("https://{}{}?serial_no={}&code={}", domain, VERIFY_PATH, serial_no, unique_code)
+    })
+}
+
+// Reset ALL verification domains (use with caution)
+pub fn reset_verification_domains() {
+    VERIFICATION_DOMAINS.with(|domains| {
+        let mut domains_mut = domains.borrow_mut();
+        let keys: Vec<_> = domains_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            domains_mut.remove(&key);
+        }
+    });
+    log_info!("All verification domains have been reset.");
+}
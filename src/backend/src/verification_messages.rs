@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::PostVerificationMessage;
+
+pub const MAX_TEMPLATE_LENGTH: usize = 500;
+pub const MAX_CTA_LABEL_LENGTH: usize = 40;
+
+// Placeholders a template may use
+pub const PLACEHOLDERS: [&str; 4] = ["{product_name}", "{brand_name}", "{points}", "{total_points}"];
+
+// Define a unique MemoryId for this structure
+const VERIFICATION_MESSAGES_MEM_ID: MemoryId = MemoryId::new(32);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by product ID for product messages and by organization ID for the organization-wide default
+    static VERIFICATION_MESSAGES: RefCell<StableBTreeMap<Principal, PostVerificationMessage, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_MESSAGES_MEM_ID))
+        )
+    );
+}
+
+// Values substituted into a template
+pub struct MessageContext<'a> {
+    pub product_name: &'a str,
+    pub brand_name: &'a str,
+    pub points: u32,
+    pub total_points: u32,
+}
+
+fn target_id(message: &PostVerificationMessage) -> Principal {
+    message.product_id.unwrap_or(message.org_id)
+}
+
+pub fn save_message(message: PostVerificationMessage) {
+    VERIFICATION_MESSAGES.with(|messages| {
+        messages.borrow_mut().insert(target_id(&message), message);
+    });
+}
+
+// Message stored for a product, or the organization default when `target_id` is an organization
+pub fn get_message(target_id: Principal) -> Option<PostVerificationMessage> {
+    VERIFICATION_MESSAGES.with(|messages| messages.borrow().get(&target_id))
+}
+
+pub fn list_messages_for_org(org_id: Principal) -> Vec<PostVerificationMessage> {
+    VERIFICATION_MESSAGES.with(|messages| {
+        messages
+            .borrow()
+            .iter()
+            .filter(|(_, message)| message.org_id == org_id)
+            .map(|(_, message)| message)
+            .collect()
+    })
+}
+
+// Active message for a verified product: its own message first, then the organization default
+pub fn resolve_message(product_id: Principal, org_id: Principal) -> Option<PostVerificationMessage> {
+    get_message(product_id)
+        .filter(|message| message.is_active)
+        .or_else(|| get_message(org_id).filter(|message| message.is_active && message.product_id.is_none()))
+}
+
+// Rejects empty or overlong templates and any `{...}` token that is not a known placeholder
+pub fn validate_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Message template cannot be empty".to_string());
+    }
+    if template.chars().count() > MAX_TEMPLATE_LENGTH {
+        return Err(format!("Message template must be at most {} characters", MAX_TEMPLATE_LENGTH));
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(offset) => start + offset,
+            None => return Err("Message template has an unclosed placeholder".to_string()),
+        };
+        let placeholder = &rest[start..=end];
+        if !PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!(
+                "Unknown placeholder {}; supported placeholders are {}",
+                placeholder,
+                PLACEHOLDERS.join(", ")
+            ));
+        }
+        rest = &rest[end + 1..];
+    }
+    Ok(())
+}
+
+// Single pass, so placeholder-like text inside substituted values is left as is
+pub fn render(template: &str, context: &MessageContext) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let end = match rest[start..].find('}') {
+            Some(offset) => start + offset,
+            None => {
+                rendered.push_str(&rest[start..]);
+                return rendered;
+            }
+        };
+        match &rest[start..=end] {
+            "{product_name}" => rendered.push_str(context.product_name),
+            "{brand_name}" => rendered.push_str(context.brand_name),
+            "{points}" => rendered.push_str(&context.points.to_string()),
+            "{total_points}" => rendered.push_str(&context.total_points.to_string()),
+            other => rendered.push_str(other),
+        }
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+// Reset ALL post-verification messages (use with caution)
+pub fn reset_verification_messages() {
+    VERIFICATION_MESSAGES.with(|messages| {
+        let mut messages_mut = messages.borrow_mut();
+        let keys: Vec<_> = messages_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            messages_mut.remove(&key);
+        }
+    });
+    log_info!("All post-verification messages have been reset.");
+}
@@ -0,0 +1,289 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_cdk_timers::set_timer_interval;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+use crate::api::VerificationRollupResult;
+use crate::clock;
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCTS};
+use crate::models::{
+    ProductVerification, ProductVerificationStatus, RetiredVerificationHandling, VerificationMonthlyAggregate,
+    VerificationRetentionPolicy,
+};
+use crate::plans;
+use crate::verification_store;
+
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+// Raw records handled per run, to stay within the instruction limit; the rest waits for the next run
+const MAX_RECORDS_PER_RUN: usize = 10_000;
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+const DAYS_PER_MONTH: u32 = 30;
+
+// Define unique Memory IDs for the structures in this module
+const RETENTION_POLICIES_MEM_ID: MemoryId = MemoryId::new(73);
+const MONTHLY_AGGREGATES_MEM_ID: MemoryId = MemoryId::new(74);
+const ARCHIVED_VERIFICATIONS_MEM_ID: MemoryId = MemoryId::new(75);
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct AggregateKey {
+    org_id: Principal,
+    month: u32,
+    product_id: Principal,
+}
+
+impl Storable for AggregateKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static RETENTION_POLICIES: RefCell<StableBTreeMap<Principal, VerificationRetentionPolicy, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(RETENTION_POLICIES_MEM_ID))
+        )
+    );
+
+    static MONTHLY_AGGREGATES: RefCell<StableBTreeMap<AggregateKey, VerificationMonthlyAggregate, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MONTHLY_AGGREGATES_MEM_ID))
+        )
+    );
+
+    // Same (product_id, seq) keys the records had in verification_store
+    static ARCHIVED_VERIFICATIONS: RefCell<StableBTreeMap<(Principal, u64), ProductVerification, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(ARCHIVED_VERIFICATIONS_MEM_ID))
+        )
+    );
+}
+
+// Register the daily rollup. Timers do not survive upgrades, so this runs from both init and post_upgrade.
+pub fn start_rollup_scheduler() {
+    set_timer_interval(ROLLUP_INTERVAL, || {
+        run_rollup(clock::now());
+    });
+}
+
+// Stored policy, or archiving after the plan's analytics retention
+pub fn get_policy(org_id: Principal) -> VerificationRetentionPolicy {
+    RETENTION_POLICIES.with(|policies| policies.borrow().get(&org_id)).unwrap_or(VerificationRetentionPolicy {
+        org_id,
+        retention_months: None,
+        handling: RetiredVerificationHandling::Archive,
+        updated_at: 0,
+        updated_by: Principal::anonymous(),
+    })
+}
+
+pub fn save_policy(policy: VerificationRetentionPolicy) {
+    RETENTION_POLICIES.with(|policies| {
+        policies.borrow_mut().insert(policy.org_id, policy);
+    });
+}
+
+// Longest raw retention the organization's plan allows
+pub fn max_retention_months(org_id: Principal) -> u32 {
+    (plans::limits(org_id).analytics_retention_days / DAYS_PER_MONTH).max(1)
+}
+
+fn retention_days(policy: &VerificationRetentionPolicy) -> u32 {
+    let max_months = max_retention_months(policy.org_id);
+    let months = policy.retention_months.unwrap_or(max_months).min(max_months);
+    months * DAYS_PER_MONTH
+}
+
+// (year, month, day) of a day count since 1970-01-01; Howard Hinnant's civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = (if z >= 0 { z } else { z - 146_096 }) / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let yoe = year - era * 400;
+    let mp = (if month > 2 { month - 3 } else { month + 9 }) as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// YYYYMM of a timestamp, in UTC
+pub fn month_of(timestamp: u64) -> u32 {
+    let (year, month, _) = civil_from_days((timestamp / DAY_NS) as i64);
+    year as u32 * 100 + month
+}
+
+fn month_start(timestamp: u64) -> u64 {
+    let (year, month, _) = civil_from_days((timestamp / DAY_NS) as i64);
+    days_from_civil(year, month, 1) as u64 * DAY_NS
+}
+
+// Records before the start of the month the retention window reaches into are rolled up,
+// so every aggregated month is complete
+fn rollup_cutoff(policy: &VerificationRetentionPolicy, now: u64) -> u64 {
+    month_start(now.saturating_sub(retention_days(policy) as u64 * DAY_NS))
+}
+
+fn add_to_aggregate(org_id: Principal, verification: &ProductVerification, now: u64) {
+    let key = AggregateKey {
+        org_id,
+        month: month_of(verification.created_at),
+        product_id: verification.product_id,
+    };
+    MONTHLY_AGGREGATES.with(|aggregates| {
+        let mut aggregates_mut = aggregates.borrow_mut();
+        let mut aggregate = aggregates_mut.get(&key).unwrap_or(VerificationMonthlyAggregate {
+            org_id,
+            product_id: key.product_id,
+            month: key.month,
+            total_verifications: 0,
+            first_verifications: 0,
+            multiple_verifications: 0,
+            invalid_verifications: 0,
+            updated_at: now,
+        });
+        aggregate.total_verifications += 1;
+        match verification.status {
+            ProductVerificationStatus::FirstVerification => aggregate.first_verifications += 1,
+            ProductVerificationStatus::MultipleVerification => aggregate.multiple_verifications += 1,
+            ProductVerificationStatus::Invalid
+            | ProductVerificationStatus::VoidedSerial
+            | ProductVerificationStatus::CodeRevoked
+            | ProductVerificationStatus::NotYetActive
+            | ProductVerificationStatus::SupersededCode => aggregate.invalid_verifications += 1,
+        }
+        aggregate.updated_at = now;
+        aggregates_mut.insert(key, aggregate);
+    });
+}
+
+// Roll up raw verifications that fell out of their organization's retention window. Verifications
+// of deleted products are left alone, as their organization is no longer known.
+pub fn run_rollup(now: u64) -> VerificationRollupResult {
+    let mut result = VerificationRollupResult { rolled_up: 0, archived: 0, deleted: 0, complete: true };
+    let mut policies: HashMap<Principal, (VerificationRetentionPolicy, u64)> = HashMap::new();
+    let mut budget = MAX_RECORDS_PER_RUN;
+
+    for product_id in verification_store::product_ids() {
+        if budget == 0 {
+            break;
+        }
+        let org_id = match PRODUCTS.with(|products| products.borrow().get(&product_id)) {
+            Some(product) => product.org_id,
+            None => continue,
+        };
+        let (policy, cutoff) = policies
+            .entry(org_id)
+            .or_insert_with(|| {
+                let policy = get_policy(org_id);
+                let cutoff = rollup_cutoff(&policy, now);
+                (policy, cutoff)
+            })
+            .clone();
+
+        let taken = verification_store::take_recorded_before(product_id, cutoff, budget);
+        budget -= taken.len();
+        if budget == 0 {
+            // The product may have more; finishing it is left to the next run
+            result.complete = false;
+        }
+        for (seq, verification) in taken {
+            if !verification.is_test.unwrap_or(false) {
+                add_to_aggregate(org_id, &verification, now);
+                result.rolled_up += 1;
+            }
+            match policy.handling {
+                RetiredVerificationHandling::Archive => {
+                    ARCHIVED_VERIFICATIONS.with(|archive| archive.borrow_mut().insert((product_id, seq), verification));
+                    result.archived += 1;
+                }
+                RetiredVerificationHandling::Delete => result.deleted += 1,
+            }
+        }
+    }
+
+    if result.rolled_up > 0 || result.archived > 0 || result.deleted > 0 {
+        log_info!(
+            "[run_rollup] Rolled up {} verification(s): {} archived, {} deleted{}",
+            result.rolled_up,
+            result.archived,
+            result.deleted,
+            if result.complete { "" } else { "; more remain for the next run" }
+        );
+    }
+    result
+}
+
+// Monthly aggregates of the organization, oldest month first
+pub fn list_aggregates(
+    org_id: Principal,
+    product_id: Option<Principal>,
+    from_month: Option<u32>,
+    to_month: Option<u32>,
+) -> Vec<VerificationMonthlyAggregate> {
+    let mut aggregates: Vec<VerificationMonthlyAggregate> = MONTHLY_AGGREGATES.with(|aggregates| {
+        aggregates
+            .borrow()
+            .iter()
+            .filter(|(key, _)| {
+                key.org_id == org_id
+                    && product_id.is_none_or(|id| key.product_id == id)
+                    && from_month.is_none_or(|from| key.month >= from)
+                    && to_month.is_none_or(|to| key.month <= to)
+            })
+            .map(|(_, aggregate)| aggregate)
+            .collect()
+    });
+    aggregates.sort_by(|a, b| a.month.cmp(&b.month).then_with(|| a.product_id.cmp(&b.product_id)));
+    aggregates
+}
+
+// Reset ALL retention policies, monthly aggregates and archived verifications (use with caution)
+pub fn reset_verification_retention() {
+    RETENTION_POLICIES.with(|policies| {
+        let mut policies_mut = policies.borrow_mut();
+        let keys: Vec<_> = policies_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            policies_mut.remove(&key);
+        }
+    });
+    MONTHLY_AGGREGATES.with(|aggregates| {
+        let mut aggregates_mut = aggregates.borrow_mut();
+        let keys: Vec<_> = aggregates_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            aggregates_mut.remove(&key);
+        }
+    });
+    ARCHIVED_VERIFICATIONS.with(|archive| {
+        let mut archive_mut = archive.borrow_mut();
+        let keys: Vec<_> = archive_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            archive_mut.remove(&key);
+        }
+    });
+    log_info!("All verification retention policies, aggregates and archives have been reset.");
+}
@@ -0,0 +1,576 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ops::Bound;
+
+use candid::{decode_one, encode_one, CandidType, Deserialize, Principal};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap, Storable};
+
+// Import the shared memory manager
+use crate::global_state::{MEMORY_MANAGER, PRODUCT_VERIFICATIONS};
+use crate::models::ProductVerification;
+
+// Define unique Memory IDs for the structures in this module
+pub const VERIFICATION_RECORDS_MEM_ID: MemoryId = MemoryId::new(47);
+const VERIFICATION_SEQUENCES_MEM_ID: MemoryId = MemoryId::new(48);
+const USER_VERIFICATIONS_MEM_ID: MemoryId = MemoryId::new(92);
+const SERIAL_VERIFICATIONS_MEM_ID: MemoryId = MemoryId::new(94);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+// (product_id, seq): a product's verifications sit next to each other in recording order,
+// so appending writes one small record and reads are range scans.
+pub type VerificationKey = (Principal, u64);
+
+// A user's verifications sort newest first: `newest_first` is u64::MAX minus the recording time
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UserVerificationKey {
+    pub user_id: Principal,
+    pub newest_first: u64,
+    pub product_id: Principal,
+    pub seq: u64,
+}
+
+impl UserVerificationKey {
+    fn of(product_id: Principal, seq: u64, verification: &ProductVerification) -> Self {
+        UserVerificationKey {
+            user_id: verification.created_by,
+            newest_first: u64::MAX - verification.created_at,
+            product_id,
+            seq,
+        }
+    }
+}
+
+impl Storable for UserVerificationKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// A serial's verifications sort in recording order under its product
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SerialVerificationKey {
+    pub product_id: Principal,
+    pub serial_no: Principal,
+    pub seq: u64,
+}
+
+impl SerialVerificationKey {
+    fn of(product_id: Principal, seq: u64, verification: &ProductVerification) -> Self {
+        SerialVerificationKey { product_id, serial_no: verification.serial_no, seq }
+    }
+}
+
+impl Storable for SerialVerificationKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(encode_one(self).expect("Failed to encode"))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        decode_one(&bytes).expect("Failed to decode")
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+thread_local! {
+    static VERIFICATION_RECORDS: RefCell<StableBTreeMap<VerificationKey, ProductVerification, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_RECORDS_MEM_ID))
+        )
+    );
+
+    // Next sequence number per product. Never decremented, so a seq is never reused.
+    static VERIFICATION_SEQUENCES: RefCell<StableBTreeMap<Principal, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_SEQUENCES_MEM_ID))
+        )
+    );
+
+    // Every record under the user who made it, so a user's history is a range scan
+    static USER_VERIFICATIONS: RefCell<StableBTreeMap<UserVerificationKey, (), Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(USER_VERIFICATIONS_MEM_ID))
+        )
+    );
+
+    // Every record under the serial it verified, so a serial's scan history is a range scan
+    static SERIAL_VERIFICATIONS: RefCell<StableBTreeMap<SerialVerificationKey, (), Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(SERIAL_VERIFICATIONS_MEM_ID))
+        )
+    );
+}
+
+fn index_for_user(product_id: Principal, seq: u64, verification: &ProductVerification) {
+    USER_VERIFICATIONS.with(|index| index.borrow_mut().insert(UserVerificationKey::of(product_id, seq, verification), ()));
+}
+
+fn unindex_for_user(product_id: Principal, seq: u64, verification: &ProductVerification) {
+    USER_VERIFICATIONS.with(|index| index.borrow_mut().remove(&UserVerificationKey::of(product_id, seq, verification)));
+}
+
+fn index_for_serial(product_id: Principal, seq: u64, verification: &ProductVerification) {
+    SERIAL_VERIFICATIONS.with(|index| index.borrow_mut().insert(SerialVerificationKey::of(product_id, seq, verification), ()));
+}
+
+fn unindex_for_serial(product_id: Principal, seq: u64, verification: &ProductVerification) {
+    SERIAL_VERIFICATIONS.with(|index| index.borrow_mut().remove(&SerialVerificationKey::of(product_id, seq, verification)));
+}
+
+// Both indexes of a record are kept in step with it
+fn index(product_id: Principal, seq: u64, verification: &ProductVerification) {
+    index_for_user(product_id, seq, verification);
+    index_for_serial(product_id, seq, verification);
+}
+
+fn unindex(product_id: Principal, seq: u64, verification: &ProductVerification) {
+    unindex_for_user(product_id, seq, verification);
+    unindex_for_serial(product_id, seq, verification);
+}
+
+fn user_range_end(user_id: Principal) -> UserVerificationKey {
+    UserVerificationKey {
+        user_id,
+        newest_first: u64::MAX,
+        product_id: Principal::from_slice(&[0xFF; 29]),
+        seq: u64::MAX,
+    }
+}
+
+fn user_range_start(user_id: Principal) -> UserVerificationKey {
+    UserVerificationKey {
+        user_id,
+        newest_first: 0,
+        product_id: Principal::from_slice(&[]),
+        seq: 0,
+    }
+}
+
+// Re-open the records map after its memory was written through another handle
+pub fn reload_records() {
+    VERIFICATION_RECORDS.with(|records| {
+        *records.borrow_mut() = StableBTreeMap::init(MEMORY_MANAGER.with(|m| m.borrow().get(VERIFICATION_RECORDS_MEM_ID)))
+    });
+}
+
+fn product_range(product_id: Principal) -> std::ops::RangeInclusive<VerificationKey> {
+    (product_id, 0)..=(product_id, u64::MAX)
+}
+
+fn next_seq(product_id: Principal) -> u64 {
+    VERIFICATION_SEQUENCES.with(|sequences| {
+        let mut sequences_mut = sequences.borrow_mut();
+        let seq = sequences_mut.get(&product_id).unwrap_or(0);
+        sequences_mut.insert(product_id, seq + 1);
+        seq
+    })
+}
+
+// Store a new verification under its product, returning its sequence number
+pub fn append(verification: &ProductVerification) -> u64 {
+    let seq = next_seq(verification.product_id);
+    VERIFICATION_RECORDS.with(|records| {
+        records.borrow_mut().insert((verification.product_id, seq), verification.clone());
+    });
+    index(verification.product_id, seq, verification);
+    seq
+}
+
+// The user's verifications newest first, those after `after` when it is set, at most `limit` of them.
+// Index entries whose record was removed outside this module are skipped.
+pub fn list_for_user_after(
+    user_id: Principal,
+    after: Option<UserVerificationKey>,
+    limit: usize,
+) -> Vec<(UserVerificationKey, ProductVerification)> {
+    let start = match after {
+        Some(after) => Bound::Excluded(after),
+        None => Bound::Included(user_range_start(user_id)),
+    };
+    USER_VERIFICATIONS.with(|index| {
+        VERIFICATION_RECORDS.with(|records| {
+            let records = records.borrow();
+            index
+                .borrow()
+                .range((start, Bound::Included(user_range_end(user_id))))
+                .filter_map(|(key, _)| records.get(&(key.product_id, key.seq)).map(|v| (key, v)))
+                .take(limit)
+                .collect()
+        })
+    })
+}
+
+// Visit every verification the user made, newest first
+pub fn for_each_for_user<F>(user_id: Principal, mut visit: F)
+where
+    F: FnMut(&ProductVerification),
+{
+    for (_, verification) in list_for_user_after(user_id, None, usize::MAX) {
+        visit(&verification);
+    }
+}
+
+pub fn count_for_user(user_id: Principal) -> u64 {
+    USER_VERIFICATIONS.with(|index| index.borrow().range(user_range_start(user_id)..=user_range_end(user_id)).count() as u64)
+}
+
+// Verifications of one serial of a product, oldest first. Index entries whose record was removed
+// outside this module are skipped.
+pub fn list_for_serial(product_id: Principal, serial_no: Principal) -> Vec<ProductVerification> {
+    let start = SerialVerificationKey { product_id, serial_no, seq: 0 };
+    let end = SerialVerificationKey { product_id, serial_no, seq: u64::MAX };
+    SERIAL_VERIFICATIONS.with(|index| {
+        VERIFICATION_RECORDS.with(|records| {
+            let records = records.borrow();
+            index
+                .borrow()
+                .range(start..=end)
+                .filter_map(|(key, _)| records.get(&(key.product_id, key.seq)))
+                .collect()
+        })
+    })
+}
+
+// All verifications of a product, oldest first
+pub fn list_for_product(product_id: Principal) -> Vec<ProductVerification> {
+    VERIFICATION_RECORDS.with(|records| {
+        records.borrow().range(product_range(product_id)).map(|(_, v)| v).collect()
+    })
+}
+
+// Verifications of a product from sequence number `from_seq` on, at most `limit` of them
+pub fn list_for_product_from(product_id: Principal, from_seq: u64, limit: usize) -> Vec<(u64, ProductVerification)> {
+    VERIFICATION_RECORDS.with(|records| {
+        records
+            .borrow()
+            .range((product_id, from_seq)..=(product_id, u64::MAX))
+            .take(limit)
+            .map(|((_, seq), v)| (seq, v))
+            .collect()
+    })
+}
+
+// Walk the verifications of `product_ids` in (product, seq) order, starting after `after`, keeping those
+// `keep` accepts. Stops once `limit` are kept or `max_scanned` records were looked at; the returned key is
+// the last record looked at when there are records left, so the walk can resume from it.
+pub fn scan_products<F>(
+    product_ids: &[Principal],
+    after: Option<VerificationKey>,
+    limit: usize,
+    max_scanned: usize,
+    mut keep: F,
+) -> (Vec<ProductVerification>, Option<VerificationKey>)
+where
+    F: FnMut(&ProductVerification) -> bool,
+{
+    let mut product_ids = product_ids.to_vec();
+    product_ids.sort();
+    product_ids.dedup();
+
+    let mut kept = Vec::new();
+    let mut scanned = 0;
+    let mut last_key = None;
+    VERIFICATION_RECORDS.with(|records| {
+        let records = records.borrow();
+        for product_id in product_ids {
+            let from_seq = match after {
+                Some((after_product, _)) if product_id < after_product => continue,
+                Some((after_product, seq)) if product_id == after_product => match seq.checked_add(1) {
+                    Some(from_seq) => from_seq,
+                    None => continue,
+                },
+                _ => 0,
+            };
+            for (key, verification) in records.range((product_id, from_seq)..=(product_id, u64::MAX)) {
+                if kept.len() >= limit || scanned >= max_scanned {
+                    return (kept, last_key);
+                }
+                scanned += 1;
+                last_key = Some(key);
+                if keep(&verification) {
+                    kept.push(verification);
+                }
+            }
+        }
+        (kept, None)
+    })
+}
+
+// The most recent verifications of a product, newest first. Records are only ever removed oldest
+// first, so the last `limit` sequence numbers hold them.
+pub fn latest_for_product(product_id: Principal, limit: usize) -> Vec<ProductVerification> {
+    let next = VERIFICATION_SEQUENCES.with(|sequences| sequences.borrow().get(&product_id).unwrap_or(0));
+    let from_seq = next.saturating_sub(limit as u64);
+    let mut latest: Vec<ProductVerification> = VERIFICATION_RECORDS.with(|records| {
+        records.borrow().range((product_id, from_seq)..=(product_id, u64::MAX)).map(|(_, v)| v).collect()
+    });
+    latest.reverse();
+    latest
+}
+
+// Encoded size of a product's verifications, for storage quotas
+pub fn stored_bytes_for_product(product_id: Principal) -> u64 {
+    VERIFICATION_RECORDS.with(|records| {
+        records
+            .borrow()
+            .range(product_range(product_id))
+            .map(|(_, v)| v.to_bytes().len() as u64)
+            .sum()
+    })
+}
+
+pub fn has_verifications(product_id: Principal) -> bool {
+    VERIFICATION_RECORDS.with(|records| records.borrow().range(product_range(product_id)).next().is_some())
+}
+
+// Products with at least one stored verification
+pub fn product_ids() -> Vec<Principal> {
+    let candidates: Vec<Principal> =
+        VERIFICATION_SEQUENCES.with(|sequences| sequences.borrow().iter().map(|(product_id, _)| product_id).collect());
+    candidates.into_iter().filter(|product_id| has_verifications(*product_id)).collect()
+}
+
+// Visit every stored verification, grouped by product
+pub fn for_each<F>(mut visit: F)
+where
+    F: FnMut(&ProductVerification),
+{
+    VERIFICATION_RECORDS.with(|records| {
+        for (_, verification) in records.borrow().iter() {
+            visit(&verification);
+        }
+    });
+}
+
+// First verification of the product matching `predicate`, with its sequence number
+pub fn find_for_product<P>(product_id: Principal, predicate: P) -> Option<(u64, ProductVerification)>
+where
+    P: Fn(&ProductVerification) -> bool,
+{
+    VERIFICATION_RECORDS.with(|records| {
+        records
+            .borrow()
+            .range(product_range(product_id))
+            .find(|(_, v)| predicate(v))
+            .map(|((_, seq), v)| (seq, v))
+    })
+}
+
+// Overwrite an existing record; returns false if there is none at that position
+pub fn replace(product_id: Principal, seq: u64, verification: ProductVerification) -> bool {
+    VERIFICATION_RECORDS.with(|records| {
+        let mut records_mut = records.borrow_mut();
+        let previous = match records_mut.get(&(product_id, seq)) {
+            Some(previous) => previous,
+            None => return false,
+        };
+        unindex(product_id, seq, &previous);
+        index(product_id, seq, &verification);
+        records_mut.insert((product_id, seq), verification);
+        true
+    })
+}
+
+// Apply `update` to the first verification of the product matching `predicate` and store it
+pub fn update_first<P, F>(product_id: Principal, predicate: P, update: F) -> Option<ProductVerification>
+where
+    P: Fn(&ProductVerification) -> bool,
+    F: FnOnce(&mut ProductVerification),
+{
+    let (seq, mut verification) = find_for_product(product_id, predicate)?;
+    update(&mut verification);
+    replace(product_id, seq, verification.clone());
+    Some(verification)
+}
+
+// Re-attribute every verification recorded by `from` to `to`, returning how many changed
+pub fn reassign_creator(from: Principal, to: Principal) -> u32 {
+    let moved = list_for_user_after(from, None, usize::MAX);
+    let count = moved.len() as u32;
+    for (key, mut verification) in moved {
+        verification.created_by = to;
+        replace(key.product_id, key.seq, verification);
+    }
+    count
+}
+
+// Remove and return the product's oldest verifications recorded before `cutoff`, at most `limit` of them.
+// Records are in recording order, so this stops at the first one at or after the cutoff.
+pub fn take_recorded_before(product_id: Principal, cutoff: u64, limit: usize) -> Vec<(u64, ProductVerification)> {
+    let taken: Vec<(u64, ProductVerification)> = VERIFICATION_RECORDS.with(|records| {
+        records
+            .borrow()
+            .range(product_range(product_id))
+            .take_while(|(_, v)| v.created_at < cutoff)
+            .take(limit)
+            .map(|((_, seq), v)| (seq, v))
+            .collect()
+    });
+    VERIFICATION_RECORDS.with(|records| {
+        let mut records_mut = records.borrow_mut();
+        for (seq, verification) in &taken {
+            records_mut.remove(&(product_id, *seq));
+            unindex(product_id, *seq, verification);
+        }
+    });
+    taken
+}
+
+// Move the per-product Candid vectors of the old layout into this store. Vectors that fail to
+// decode are left where they are, for the integrity tooling to quarantine. Idempotent: a migrated
+// vector is removed from the old store.
+pub fn migrate_legacy_vectors() -> u32 {
+    let legacy: Vec<Principal> = PRODUCT_VERIFICATIONS.with(|map| map.borrow().iter().map(|(k, _)| k).collect());
+    let mut migrated = 0;
+    for product_id in legacy {
+        let bytes = match PRODUCT_VERIFICATIONS.with(|map| map.borrow().get(&product_id)) {
+            Some(bytes) => bytes,
+            None => continue,
+        };
+        let verifications: Vec<ProductVerification> = match decode_one(&bytes.0) {
+            Ok(verifications) => verifications,
+            Err(e) => {
+                log_error!("[migrate_legacy_vectors] Verifications of product {} do not decode, left in place: {}", product_id, e);
+                continue;
+            }
+        };
+        for verification in &verifications {
+            // Stored under the key it was filed under, even if the record's own product_id disagrees
+            let seq = next_seq(product_id);
+            VERIFICATION_RECORDS.with(|records| records.borrow_mut().insert((product_id, seq), verification.clone()));
+            index(product_id, seq, verification);
+        }
+        PRODUCT_VERIFICATIONS.with(|map| map.borrow_mut().remove(&product_id));
+        migrated += 1;
+    }
+    migrated
+}
+
+// Index the records stored before the user index existed. Only runs while the index is empty, so it
+// is a no-op after the first upgrade that has it.
+pub fn backfill_user_index() -> u64 {
+    if USER_VERIFICATIONS.with(|index| !index.borrow().is_empty()) {
+        return 0;
+    }
+    let keys: Vec<UserVerificationKey> = VERIFICATION_RECORDS.with(|records| {
+        records
+            .borrow()
+            .iter()
+            .map(|((product_id, seq), v)| UserVerificationKey::of(product_id, seq, &v))
+            .collect()
+    });
+    let indexed = keys.len() as u64;
+    USER_VERIFICATIONS.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        for key in keys {
+            index_mut.insert(key, ());
+        }
+    });
+    indexed
+}
+
+// Index the records stored before the serial index existed. Like the user index, only runs while
+// the index is empty.
+pub fn backfill_serial_index() -> u64 {
+    if SERIAL_VERIFICATIONS.with(|index| !index.borrow().is_empty()) {
+        return 0;
+    }
+    let keys: Vec<SerialVerificationKey> = VERIFICATION_RECORDS.with(|records| {
+        records
+            .borrow()
+            .iter()
+            .map(|((product_id, seq), v)| SerialVerificationKey::of(product_id, seq, &v))
+            .collect()
+    });
+    let indexed = keys.len() as u64;
+    SERIAL_VERIFICATIONS.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        for key in keys {
+            index_mut.insert(key, ());
+        }
+    });
+    indexed
+}
+
+// Reset ALL verification records and sequence counters (use with caution)
+pub fn reset_verification_records() {
+    VERIFICATION_RECORDS.with(|records| {
+        let mut records_mut = records.borrow_mut();
+        let keys: Vec<_> = records_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            records_mut.remove(&key);
+        }
+    });
+    VERIFICATION_SEQUENCES.with(|sequences| {
+        let mut sequences_mut = sequences.borrow_mut();
+        let keys: Vec<_> = sequences_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            sequences_mut.remove(&key);
+        }
+    });
+    USER_VERIFICATIONS.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    SERIAL_VERIFICATIONS.with(|index| {
+        let mut index_mut = index.borrow_mut();
+        let keys: Vec<_> = index_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            index_mut.remove(&key);
+        }
+    });
+    log_info!("All verification records have been reset.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProductVerificationStatus;
+
+    fn verification(product_id: Principal, serial_no: Principal, created_at: u64) -> ProductVerification {
+        ProductVerification {
+            id: Principal::from_slice(&[created_at as u8]),
+            product_id,
+            serial_no,
+            variant_id: None,
+            print_version: 1,
+            metadata: Vec::new(),
+            created_at,
+            created_by: Principal::from_slice(&[9]),
+            status: ProductVerificationStatus::FirstVerification,
+            reward_claimed: false,
+            reward_transaction_id: None,
+            country_code: None,
+            annotations: None,
+            is_test: None,
+        }
+    }
+
+    #[test]
+    fn serial_history_follows_appends_and_removals() {
+        let product_id = Principal::from_slice(&[1]);
+        let (serial_a, serial_b) = (Principal::from_slice(&[2]), Principal::from_slice(&[3]));
+        append(&verification(product_id, serial_a, 10));
+        append(&verification(product_id, serial_b, 20));
+        append(&verification(product_id, serial_a, 30));
+
+        let scans: Vec<u64> = list_for_serial(product_id, serial_a).iter().map(|v| v.created_at).collect();
+        assert_eq!(scans, vec![10, 30]);
+
+        take_recorded_before(product_id, 25, usize::MAX);
+        let scans: Vec<u64> = list_for_serial(product_id, serial_a).iter().map(|v| v.created_at).collect();
+        assert_eq!(scans, vec![30]);
+        assert!(list_for_serial(product_id, serial_b).is_empty());
+    }
+}
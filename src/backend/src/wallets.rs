@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+
+use crate::clock;
+use crate::error::ApiError;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{SavedWallet, WalletBook, WalletChain};
+use crate::utils::generate_unique_principal;
+
+pub const MAX_WALLETS_PER_USER: usize = 10;
+pub const MAX_WALLET_LABEL_LENGTH: usize = 50;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Define a unique MemoryId for this structure
+const WALLET_BOOKS_MEM_ID: MemoryId = MemoryId::new(41);
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    // Keyed by user ID
+    static WALLET_BOOKS: RefCell<StableBTreeMap<Principal, WalletBook, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(WALLET_BOOKS_MEM_ID))
+        )
+    );
+}
+
+fn is_base58(value: &str) -> bool {
+    value.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+// Format check for the chain's address encoding; checksums are left to the ledger
+pub fn validate_address(chain: WalletChain, address: &str) -> Result<(), &'static str> {
+    let valid = match chain {
+        WalletChain::InternetComputer => {
+            Principal::from_text(address).is_ok()
+                || (address.len() == 64 && address.chars().all(|c| c.is_ascii_hexdigit()))
+        }
+        WalletChain::Ethereum => {
+            address.len() == 42
+                && address.starts_with("0x")
+                && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+        }
+        WalletChain::Bitcoin => {
+            let lower = address.to_ascii_lowercase();
+            if let Some(data) = lower.strip_prefix("bc1") {
+                (14..=74).contains(&address.len())
+                    && (address == lower || address == address.to_ascii_uppercase())
+                    && data.chars().all(|c| c.is_ascii_alphanumeric() && !matches!(c, '1' | 'b' | 'i' | 'o'))
+            } else {
+                (26..=35).contains(&address.len()) && matches!(address.chars().next(), Some('1' | '3')) && is_base58(address)
+            }
+        }
+        WalletChain::Solana => (32..=44).contains(&address.len()) && is_base58(address),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err("Address is not valid for the selected chain")
+    }
+}
+
+pub fn list_wallets(user_id: Principal) -> Vec<SavedWallet> {
+    WALLET_BOOKS
+        .with(|books| books.borrow().get(&user_id))
+        .map(|book| book.wallets)
+        .unwrap_or_default()
+}
+
+pub fn get_wallet(user_id: Principal, wallet_id: Principal) -> Option<SavedWallet> {
+    list_wallets(user_id).into_iter().find(|wallet| wallet.id == wallet_id)
+}
+
+pub fn default_wallet(user_id: Principal) -> Option<SavedWallet> {
+    list_wallets(user_id).into_iter().find(|wallet| wallet.is_default)
+}
+
+fn update_book<F, T>(user_id: Principal, update: F) -> Result<T, ApiError>
+where
+    F: FnOnce(&mut WalletBook) -> Result<T, ApiError>,
+{
+    WALLET_BOOKS.with(|books| {
+        let mut books_mut = books.borrow_mut();
+        let mut book = books_mut.get(&user_id).unwrap_or_default();
+        let result = update(&mut book)?;
+        books_mut.insert(user_id, book);
+        Ok(result)
+    })
+}
+
+// Save a validated wallet. The first wallet saved becomes the default.
+pub fn add_wallet(
+    user_id: Principal,
+    label: String,
+    chain: WalletChain,
+    address: String,
+    make_default: bool,
+) -> Result<SavedWallet, ApiError> {
+    update_book(user_id, |book| {
+        if book.wallets.len() >= MAX_WALLETS_PER_USER {
+            return Err(ApiError::invalid_input(&format!("At most {} wallets can be saved", MAX_WALLETS_PER_USER)));
+        }
+        if book.wallets.iter().any(|w| w.chain == chain && w.address == address) {
+            return Err(ApiError::already_exists("This wallet is already in your address book"));
+        }
+
+        let is_default = make_default || book.wallets.is_empty();
+        if is_default {
+            book.wallets.iter_mut().for_each(|w| w.is_default = false);
+        }
+        let wallet = SavedWallet {
+            id: generate_unique_principal(user_id),
+            label,
+            chain,
+            address,
+            is_default,
+            created_at: clock::now(),
+        };
+        book.wallets.push(wallet.clone());
+        Ok(wallet)
+    })
+}
+
+pub fn set_default_wallet(user_id: Principal, wallet_id: Principal) -> Result<SavedWallet, ApiError> {
+    update_book(user_id, |book| {
+        // The book is only written back on success, so an unknown ID leaves the old default in place
+        let mut selected = None;
+        for wallet in book.wallets.iter_mut() {
+            wallet.is_default = wallet.id == wallet_id;
+            if wallet.is_default {
+                selected = Some(wallet.clone());
+            }
+        }
+        selected.ok_or_else(|| ApiError::not_found("Wallet not found"))
+    })
+}
+
+// Remove a wallet; if it was the default, the oldest remaining wallet takes over
+pub fn remove_wallet(user_id: Principal, wallet_id: Principal) -> Result<SavedWallet, ApiError> {
+    update_book(user_id, |book| {
+        let index = book
+            .wallets
+            .iter()
+            .position(|w| w.id == wallet_id)
+            .ok_or_else(|| ApiError::not_found("Wallet not found"))?;
+        let removed = book.wallets.remove(index);
+        if removed.is_default {
+            if let Some(first) = book.wallets.first_mut() {
+                first.is_default = true;
+            }
+        }
+        Ok(removed)
+    })
+}
+
+// Reset ALL wallet address books (use with caution)
+pub fn reset_wallet_books() {
+    WALLET_BOOKS.with(|books| {
+        let mut books_mut = books.borrow_mut();
+        let keys: Vec<_> = books_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            books_mut.remove(&key);
+        }
+    });
+    log_info!("All wallet address books have been reset.");
+}
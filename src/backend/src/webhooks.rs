@@ -0,0 +1,277 @@
+use std::cell::RefCell;
+
+use candid::Principal;
+use ic_cdk::api::management_canister::http_request::{
+    http_request, CanisterHttpRequestArgument, HttpHeader, HttpMethod,
+};
+use ic_stable_structures::{memory_manager::{MemoryId, VirtualMemory}, DefaultMemoryImpl, StableBTreeMap};
+use serde::Serialize;
+
+use crate::clock;
+use crate::error::ApiError;
+use crate::feature_flags;
+use crate::outbox;
+use crate::outcall_transforms::{self, OutcallKind};
+use crate::quotas;
+// Import the shared memory manager
+use crate::global_state::MEMORY_MANAGER;
+use crate::models::{NotificationRule, NotificationTrigger, OutboxDelivery, ProductVerificationStatus};
+
+// Upper bound on rules per product, keeps evaluation cheap on the verification path
+pub const MAX_RULES_PER_PRODUCT: usize = 20;
+
+// Define a unique MemoryId for this structure
+const NOTIFICATION_RULES_MEM_ID: MemoryId = MemoryId::new(12);
+
+// Body posted to the webhook URL of a matching rule
+#[derive(Serialize, Clone, Debug)]
+pub struct VerificationWebhookPayload {
+    pub event: String,
+    pub rule_id: String,
+    pub org_id: String,
+    pub product_id: String,
+    pub serial_no: String,
+    pub status: String,
+    pub scan_count: u32,
+    pub timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>, // Only sent for grey market events
+}
+
+// Use the standard Memory type alias
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static NOTIFICATION_RULES: RefCell<StableBTreeMap<Principal, NotificationRule, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(NOTIFICATION_RULES_MEM_ID))
+        )
+    );
+}
+
+// Store (insert or replace) a notification rule
+pub fn save_rule(rule: NotificationRule) {
+    NOTIFICATION_RULES.with(|rules| {
+        rules.borrow_mut().insert(rule.id, rule);
+    });
+}
+
+pub fn get_rule(rule_id: Principal) -> Option<NotificationRule> {
+    NOTIFICATION_RULES.with(|rules| rules.borrow().get(&rule_id))
+}
+
+pub fn remove_rule(rule_id: Principal) -> Option<NotificationRule> {
+    NOTIFICATION_RULES.with(|rules| rules.borrow_mut().remove(&rule_id))
+}
+
+pub fn list_rules_for_product(product_id: Principal) -> Vec<NotificationRule> {
+    NOTIFICATION_RULES.with(|rules| {
+        rules
+            .borrow()
+            .iter()
+            .filter(|(_, rule)| rule.product_id == product_id)
+            .map(|(_, rule)| rule)
+            .collect()
+    })
+}
+
+// Rules of every product of the organization, paused ones included
+pub fn count_rules_for_org(org_id: Principal) -> usize {
+    NOTIFICATION_RULES.with(|rules| rules.borrow().iter().filter(|(_, rule)| rule.org_id == org_id).count())
+}
+
+pub fn has_active_rule_for_org(org_id: Principal) -> bool {
+    NOTIFICATION_RULES.with(|rules| rules.borrow().iter().any(|(_, rule)| rule.org_id == org_id && rule.is_active))
+}
+
+fn rule_matches(rule: &NotificationRule, status: &ProductVerificationStatus, scan_count: u32) -> bool {
+    if !rule.is_active {
+        return false;
+    }
+
+    let trigger_matches = match rule.trigger {
+        NotificationTrigger::AnyVerification => true,
+        NotificationTrigger::Invalid => matches!(
+            status,
+            ProductVerificationStatus::Invalid
+                | ProductVerificationStatus::VoidedSerial
+                | ProductVerificationStatus::CodeRevoked
+                | ProductVerificationStatus::SupersededCode
+        ),
+        NotificationTrigger::MultipleVerification => *status == ProductVerificationStatus::MultipleVerification,
+        NotificationTrigger::SuspectedClone => false, // Raised separately by notify_suspected_clone
+        NotificationTrigger::GreyMarket => false, // Raised separately by notify_grey_market
+    };
+
+    trigger_matches && rule.min_scan_count.is_none_or(|min| scan_count > min)
+}
+
+// Queue `payload` for each of the product's rules that `matches` accepts, stamped with the rule's ID.
+// Runs synchronously inside the calling update; delivery happens later from the outbox, and each
+// queued webhook counts against the organization's outcall quota.
+fn dispatch_rules<F>(org_id: Principal, product_id: Principal, matches: F, mut payload: VerificationWebhookPayload)
+where
+    F: Fn(&NotificationRule) -> bool,
+{
+    if !feature_flags::is_enabled(feature_flags::FLAG_WEBHOOKS, Some(org_id)) {
+        return;
+    }
+
+    for rule in list_rules_for_product(product_id).into_iter().filter(|rule| matches(rule)) {
+        if let Err(e) = quotas::consume_outcalls(org_id, 1) {
+            log_warn!("[dispatch_rules] Skipping {} rule {}: {:?}", payload.event, rule.id, e);
+            continue;
+        }
+
+        payload.rule_id = rule.id.to_string();
+        match serde_json::to_string(&payload) {
+            Ok(body) => {
+                enqueue_webhook(rule.webhook_url.clone(), body, Some(org_id));
+            }
+            Err(e) => log_error!("[dispatch_rules] Failed to serialize {} payload for rule {}: {:?}", payload.event, rule.id, e),
+        }
+    }
+}
+
+// Evaluate the product's rules against a verification outcome and queue a webhook for every match
+pub fn evaluate_verification_rules(
+    org_id: Principal,
+    product_id: Principal,
+    serial_no: Principal,
+    status: &ProductVerificationStatus,
+    scan_count: u32,
+) {
+    let payload = VerificationWebhookPayload {
+        event: "product_verification".to_string(),
+        rule_id: String::new(),
+        org_id: org_id.to_string(),
+        product_id: product_id.to_string(),
+        serial_no: serial_no.to_string(),
+        status: format!("{:?}", status),
+        scan_count,
+        timestamp: clock::now(),
+        country_code: None,
+    };
+    dispatch_rules(org_id, product_id, |rule| rule_matches(rule, status, scan_count), payload);
+}
+
+// Notify the product's SuspectedClone rules that a serial crossed the duplicate-scan threshold
+pub fn notify_suspected_clone(org_id: Principal, product_id: Principal, serial_no: Principal, distinct_scanners: u32) {
+    let payload = VerificationWebhookPayload {
+        event: "suspected_clone".to_string(),
+        rule_id: String::new(),
+        org_id: org_id.to_string(),
+        product_id: product_id.to_string(),
+        serial_no: serial_no.to_string(),
+        status: "SuspectedClone".to_string(),
+        scan_count: distinct_scanners,
+        timestamp: clock::now(),
+        country_code: None,
+    };
+    dispatch_rules(
+        org_id,
+        product_id,
+        |rule| rule.is_active && rule.trigger == NotificationTrigger::SuspectedClone,
+        payload,
+    );
+}
+
+// Notify the product's GreyMarket rules that a genuine unit was scanned outside its allowed markets
+pub fn notify_grey_market(org_id: Principal, product_id: Principal, serial_no: Principal, country_code: &str) {
+    let payload = VerificationWebhookPayload {
+        event: "grey_market_suspected".to_string(),
+        rule_id: String::new(),
+        org_id: org_id.to_string(),
+        product_id: product_id.to_string(),
+        serial_no: serial_no.to_string(),
+        status: "GreyMarketSuspected".to_string(),
+        scan_count: 0,
+        timestamp: clock::now(),
+        country_code: Some(country_code.to_string()),
+    };
+    dispatch_rules(
+        org_id,
+        product_id,
+        |rule| rule.is_active && rule.trigger == NotificationTrigger::GreyMarket,
+        payload,
+    );
+}
+
+// Queue a JSON body for delivery. The outbox persists it and retries failed deliveries.
+pub fn enqueue_webhook(url: String, body: String, org_id: Option<Principal>) -> OutboxDelivery {
+    outbox::enqueue(url, body, org_id)
+}
+
+// POST a JSON body to an external endpoint, treating any non-2xx status as an error. An oversized
+// response is counted against `org_id`, when the delivery belongs to an organization.
+pub async fn post_json(url: &str, body: String, org_id: Option<Principal>) -> Result<(), ApiError> {
+    let request = CanisterHttpRequestArgument {
+        url: url.to_string(),
+        method: HttpMethod::POST,
+        body: Some(body.into_bytes()),
+        max_response_bytes: Some(outcall_transforms::max_response_bytes(OutcallKind::Webhook)),
+        transform: Some(outcall_transforms::transform_context(OutcallKind::Webhook)),
+        headers: vec![
+            HttpHeader {
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            },
+            HttpHeader {
+                name: "User-Agent".to_string(),
+                value: "trueorigin_canister".to_string(),
+            },
+        ],
+    };
+
+    let cycles = outcall_transforms::request_cycles(&request);
+    match http_request(request, cycles).await {
+        Ok((response,)) => {
+            let status_code: u64 = response.status.0.try_into().unwrap_or(0);
+            if (200..300).contains(&status_code) {
+                Ok(())
+            } else {
+                Err(ApiError::external_api_error(&format!(
+                    "Webhook endpoint returned status {}: {}",
+                    status_code,
+                    String::from_utf8_lossy(&response.body)
+                )))
+            }
+        }
+        Err((rejection_code, message)) => {
+            if outcall_transforms::is_oversized_rejection(&message) {
+                log_warn!("[post_json] Response from {} exceeded the webhook response limit", url);
+                if let Some(org_id) = org_id {
+                    quotas::record_oversized_response(org_id);
+                }
+            }
+            Err(ApiError::external_api_error(&format!(
+                "Webhook request failed. RejectionCode: {:?}, Error: {}",
+                rejection_code, message
+            )))
+        }
+    }
+}
+
+// Remove the organization's notification rules when it is deleted, returning how many were removed
+pub fn delete_for_org(org_id: Principal) -> u32 {
+    NOTIFICATION_RULES.with(|rules| {
+        let mut rules_mut = rules.borrow_mut();
+        let keys: Vec<_> = rules_mut.iter().filter(|(_, rule)| rule.org_id == org_id).map(|(k, _)| k).collect();
+        for key in &keys {
+            rules_mut.remove(key);
+        }
+        keys.len() as u32
+    })
+}
+
+// Reset ALL notification rules (use with caution)
+pub fn reset_notification_rules() {
+    NOTIFICATION_RULES.with(|rules| {
+        let mut rules_mut = rules.borrow_mut();
+        let keys: Vec<_> = rules_mut.iter().map(|(k, _)| k).collect();
+        for key in keys {
+            rules_mut.remove(&key);
+        }
+    });
+    log_info!("All notification rules have been reset.");
+}
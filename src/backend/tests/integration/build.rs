@@ -0,0 +1,25 @@
+// Generates the Rust mirrors of the backend's candid types from backend.did, the interface dfx serves
+use std::path::PathBuf;
+
+use candid_parser::bindings::rust::{compile, Config};
+use candid_parser::pretty_check_file;
+
+fn main() {
+    let did = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../backend.did");
+    println!("cargo:rerun-if-changed={}", did.display());
+    let (env, actor) = pretty_check_file(&did).unwrap_or_else(|e| panic!("could not read {}: {}", did.display(), e));
+
+    let mut config = Config::new();
+    config.set_type_attributes("#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]".to_string());
+    // Generating from the service orders the types so that only recursive ones are boxed. Just the
+    // types are kept: the header's inner attributes and imports cannot be include!d, so types.rs carries
+    // them, and the tests call the canister through PocketIC rather than the generated client.
+    let types: String = compile(&config, &env, &actor)
+        .lines()
+        .skip_while(|line| line.starts_with("//") || line.starts_with("#![") || line.starts_with("use "))
+        .take_while(|line| !line.starts_with("pub struct Service("))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("backend_types.rs");
+    std::fs::write(&out, types).unwrap_or_else(|e| panic!("could not write {}: {}", out.display(), e));
+}
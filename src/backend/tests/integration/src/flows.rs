@@ -0,0 +1,155 @@
+// Steps the tests chain together, each going through the same endpoints the frontend calls
+use candid::Principal;
+
+use crate::types::*;
+use crate::{Response, TestEnv};
+
+pub struct Brand {
+    pub owner: Principal,
+    pub org_id: Principal,
+}
+
+// A printed code of one serial, as it would appear on a label
+pub struct Label {
+    pub serial_no: Principal,
+    pub unique_code: String,
+}
+
+pub fn sign_in(env: &TestEnv, user: Principal, role: UserRole) -> AuthContextResponse {
+    let (response,): (Response<AuthContextResponse>,) = env.update(user, "initialize_user_session", (Some(role),));
+    response.ok()
+}
+
+// A brand owner signed in with a fresh organization
+pub fn create_brand(env: &TestEnv, owner: Principal, name: &str) -> Brand {
+    sign_in(env, owner, UserRole::BrandOwner);
+    let request = OrganizationInput {
+        name: name.to_string(),
+        description: format!("{} test organization", name),
+        metadata: Vec::new(),
+    };
+    let (response,): (Response<OrganizationContextResponse>,) = env.update(owner, "create_organization_for_owner", (request,));
+    Brand {
+        owner,
+        org_id: response.ok().organization.id,
+    }
+}
+
+pub fn create_product(env: &TestEnv, brand: &Brand, name: &str) -> Product {
+    let request = CreateProductRequest {
+        name: name.to_string(),
+        org_id: brand.org_id,
+        category: "Apparel".to_string(),
+        description: format!("{} test product", name),
+        metadata: Vec::new(),
+        sku: None,
+        force: None,
+    };
+    let (response,): (Response<ProductResponse>,) = env.update(brand.owner, "create_product_v2", (request,));
+    response.ok().product
+}
+
+fn metadata_value<'a>(metadata: &'a [Metadata], key: &str) -> Option<&'a str> {
+    metadata.iter().find(|m| m.key == key).map(|m| m.value.as_str())
+}
+
+// The label printed for the serial every new product starts with
+pub fn initial_label(product: &Product) -> Label {
+    Label {
+        serial_no: metadata_value(&product.metadata, "initial_serial_no")
+            .and_then(|text| Principal::from_text(text).ok())
+            .expect("product has no initial serial number"),
+        unique_code: metadata_value(&product.metadata, "initial_unique_code")
+            .expect("product has no initial code")
+            .to_string(),
+    }
+}
+
+pub fn create_serials(env: &TestEnv, brand: &Brand, product_id: Principal, count: u32) -> Vec<Principal> {
+    let request = CreateSerialNumbersBatchRequest {
+        product_id,
+        count: Some(count),
+        user_serial_prefix: None,
+        capability_token: None,
+        variant_id: None,
+    };
+    let (response,): (Response<SerialNumbersBatchResponse>,) = env.update(brand.owner, "create_serial_numbers_batch_v2", (request,));
+    response.ok().serial_numbers.into_iter().map(|serial| serial.serial_no).collect()
+}
+
+// Print the serials in one job. `activation_time` of 0 activates the codes right away.
+pub fn print_labels(
+    env: &TestEnv,
+    brand: &Brand,
+    product_id: Principal,
+    serial_nos: Vec<Principal>,
+    activation_time: u64,
+) -> BulkPrintResponse {
+    let request = BulkPrintRequest {
+        product_id,
+        serial_nos,
+        printer_metadata: Vec::new(),
+        label_template_version: None,
+        capability_token: None,
+        code_policy: None,
+        activation_time: Some(activation_time),
+    };
+    let (response,): (Response<BulkPrintResponse>,) = env.update(brand.owner, "print_product_serial_numbers_bulk_v2", (request,));
+    response.ok()
+}
+
+pub fn verify(env: &TestEnv, user: Principal, serial_no: Principal, unique_code: &str) -> Response<ProductVerificationEnhancedResponse> {
+    let request = VerifyProductEnhancedRequest {
+        serial_no,
+        unique_code: unique_code.to_string(),
+        locale: None,
+        client_version: None,
+        guest_token: None,
+        country_code: None,
+    };
+    let (response,): (Response<ProductVerificationEnhancedResponse>,) = env.update(user, "verify_product_v2", (request,));
+    response
+}
+
+pub fn verify_label(env: &TestEnv, user: Principal, label: &Label) -> ProductVerificationEnhancedResponse {
+    verify(env, user, label.serial_no, &label.unique_code).ok()
+}
+
+pub fn redeem(env: &TestEnv, user: Principal, label: &Label, wallet_address: &str) -> Response<RedeemRewardResponse> {
+    let request = RedeemRewardRequest {
+        wallet_address: wallet_address.to_string(),
+        serial_no: label.serial_no,
+        unique_code: label.unique_code.clone(),
+        wallet_id: None,
+        redeem_as_coupon: None,
+    };
+    let (response,): (Response<RedeemRewardResponse>,) = env.update(user, "redeem_product_reward", (request,));
+    response
+}
+
+// A reseller who applied to the brand and was approved by its owner; returns the reseller ID
+pub fn certify_reseller(env: &TestEnv, brand: &Brand, reseller_user: Principal, name: &str) -> Principal {
+    sign_in(env, reseller_user, UserRole::Reseller);
+    let profile = CompleteResellerProfileRequest {
+        target_organization_id: brand.org_id,
+        reseller_name: name.to_string(),
+        ecommerce_urls: Vec::new(),
+        contact_email: None,
+        contact_phone: None,
+        additional_metadata: None,
+    };
+    let (applied,): (Response<AuthContextResponse>,) = env.update(reseller_user, "complete_reseller_profile", (profile,));
+    applied.ok();
+
+    let (pending,): (Response<Vec<ResellerApplicationDetail>>,) = env.query(
+        brand.owner,
+        "list_reseller_applications_v2",
+        (brand.org_id, Some(ResellerApplicationStatus::Pending)),
+    );
+    let application = pending.ok().into_iter().next().expect("the reseller's application is not pending").application;
+
+    let (approved,): (Response<ResellerApplicationDetail>,) = env.update(brand.owner, "approve_reseller_application_v2", (application.id,));
+    let approved = approved.ok();
+    assert_eq!(approved.application.status, ResellerApplicationStatus::Approved);
+    approved.application.reseller_id
+}
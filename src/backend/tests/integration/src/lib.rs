@@ -0,0 +1,143 @@
+// End-to-end tests of the backend canister on PocketIC, talking to it over candid like a client would.
+//
+// Running them needs the PocketIC server and a build of the canister:
+//
+//   cargo build --target wasm32-unknown-unknown --release -p TrustOrigin_backend
+//   POCKET_IC_BIN=/path/to/pocket-ic cargo test --manifest-path src/backend/tests/integration/Cargo.toml
+//
+// TRUEORIGIN_BACKEND_WASM points the tests at another build of the canister (plain or gzipped).
+// The candid types in `types` are generated from backend.did, which has to match the build.
+pub mod flows;
+pub mod types;
+
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+use candid::utils::{ArgumentDecoder, ArgumentEncoder};
+use candid::{decode_args, encode_args, CandidType, Deserialize, Principal};
+use pocket_ic::{PocketIc, WasmResult};
+
+use crate::types::{ApiError, ResponseMetadata};
+
+const INITIAL_CYCLES: u128 = 100_000_000_000_000;
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+fn backend_wasm() -> Vec<u8> {
+    let path = std::env::var_os("TRUEORIGIN_BACKEND_WASM").map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../../target/wasm32-unknown-unknown/release/TrustOrigin_backend.wasm")
+    });
+    std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "Could not read the backend wasm at {}: {}. Build it with \
+             `cargo build --target wasm32-unknown-unknown --release -p TrustOrigin_backend` \
+             or set TRUEORIGIN_BACKEND_WASM.",
+            path.display(),
+            e
+        )
+    })
+}
+
+// A distinct, stable self-authenticating principal per seed, standing in for a signed-in user
+pub fn identity(seed: u8) -> Principal {
+    Principal::self_authenticating([seed; 32])
+}
+
+// The envelope every v2 endpoint replies with. backend.did has one copy of it per payload type
+// (ApiResponse, ApiResponse1, ...), numbered in an order that shifts as endpoints are added.
+#[derive(CandidType, Deserialize, Debug)]
+pub struct Response<T> {
+    pub data: Option<T>,
+    pub error: Option<ApiError>,
+    pub metadata: ResponseMetadata,
+}
+
+impl<T> Response<T> {
+    // The data of a successful response; panics with the error otherwise
+    pub fn ok(self) -> T {
+        match (self.data, self.error) {
+            (Some(data), None) => data,
+            (_, Some(error)) => panic!("expected a successful response, got {:?}", error),
+            (None, None) => panic!("expected a successful response, got neither data nor an error"),
+        }
+    }
+
+    // The error of a failed response; panics if the call succeeded
+    pub fn err(self) -> ApiError {
+        match self.error {
+            Some(error) => error,
+            None => panic!("expected an error response"),
+        }
+    }
+}
+
+// A fresh PocketIC instance with the backend installed on it
+pub struct TestEnv {
+    pub pic: PocketIc,
+    pub canister_id: Principal,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let pic = PocketIc::new();
+        let canister_id = pic.create_canister();
+        pic.add_cycles(canister_id, INITIAL_CYCLES);
+        pic.install_canister(canister_id, backend_wasm(), encode_args(()).unwrap(), None);
+        let env = TestEnv { pic, canister_id };
+        // init seeds the canister's RNG from raw_rand on a timer; key generation needs it
+        env.tick(3);
+        env
+    }
+
+    pub fn update<A: ArgumentEncoder, R: for<'a> ArgumentDecoder<'a>>(&self, sender: Principal, method: &str, args: A) -> R {
+        let result = self
+            .pic
+            .update_call(self.canister_id, sender, method, encode_args(args).unwrap())
+            .unwrap_or_else(|e| panic!("update call {} failed: {:?}", method, e));
+        decode_reply(method, result)
+    }
+
+    pub fn query<A: ArgumentEncoder, R: for<'a> ArgumentDecoder<'a>>(&self, sender: Principal, method: &str, args: A) -> R {
+        let result = self
+            .pic
+            .query_call(self.canister_id, sender, method, encode_args(args).unwrap())
+            .unwrap_or_else(|e| panic!("query call {} failed: {:?}", method, e));
+        decode_reply(method, result)
+    }
+
+    // Canister time in nanoseconds since the Unix epoch, as api::time() reports it
+    pub fn now(&self) -> u64 {
+        self.pic.get_time().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64
+    }
+
+    // Move the replica's clock forward, running a round so timers that came due fire
+    pub fn advance_time(&self, duration: Duration) {
+        self.pic.advance_time(duration);
+        self.tick(1);
+    }
+
+    pub fn advance_seconds(&self, seconds: u64) {
+        self.advance_time(Duration::from_nanos(seconds * NANOS_PER_SECOND));
+    }
+
+    pub fn tick(&self, rounds: usize) {
+        for _ in 0..rounds {
+            self.pic.tick();
+        }
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn decode_reply<R: for<'a> ArgumentDecoder<'a>>(method: &str, result: WasmResult) -> R {
+    match result {
+        WasmResult::Reply(bytes) => {
+            decode_args(&bytes).unwrap_or_else(|e| panic!("could not decode the reply of {}: {}", method, e))
+        }
+        WasmResult::Reject(message) => panic!("{} was rejected: {}", method, message),
+    }
+}
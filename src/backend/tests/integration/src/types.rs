@@ -0,0 +1,9 @@
+// The backend's candid types, generated from backend.did by build.rs
+#![allow(dead_code, clippy::all)]
+
+use candid::{CandidType, Deserialize, Principal};
+
+include!(concat!(env!("OUT_DIR"), "/backend_types.rs"));
+
+// backend.did names structurally identical types once, after the first of them
+pub type ResellerApplicationStatus = RedemptionApprovalStatus;
@@ -0,0 +1,125 @@
+use trueorigin_integration_tests::flows::*;
+use trueorigin_integration_tests::types::*;
+use trueorigin_integration_tests::{identity, Response, TestEnv};
+
+#[test]
+fn product_verifies_and_its_first_verification_is_redeemed_once() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let product = create_product(&env, &brand, "Trail Jacket");
+    assert_eq!(product.org_id, brand.org_id);
+    let label = initial_label(&product);
+
+    let customer = identity(2);
+    sign_in(&env, customer, UserRole::Customer);
+
+    let first = verify_label(&env, customer, &label);
+    assert_eq!(first.status, ProductVerificationStatus::FirstVerification);
+    let rewards = first.rewards.expect("a first verification earns rewards");
+    assert!(rewards.is_first_verification);
+    assert!(rewards.points > 0);
+
+    let second = verify_label(&env, customer, &label);
+    assert_eq!(second.status, ProductVerificationStatus::MultipleVerification);
+
+    let redeemed = redeem(&env, customer, &label, "0x52908400098527886E0F7030069857D2E4169EE7").ok();
+    assert!(redeemed.success, "{}", redeemed.message);
+    assert!(redeemed.transaction_id.is_some());
+
+    let again = redeem(&env, customer, &label, "0x52908400098527886E0F7030069857D2E4169EE7").ok();
+    assert!(!again.success);
+}
+
+#[test]
+fn bulk_printed_codes_only_verify_once_their_job_is_active() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let product = create_product(&env, &brand, "Trail Jacket");
+    let serial_nos = create_serials(&env, &brand, product.id, 3);
+    assert_eq!(serial_nos.len(), 3);
+
+    let launch = env.now() + 60 * 60 * 1_000_000_000;
+    let printed = print_labels(&env, &brand, product.id, serial_nos.clone(), launch);
+    assert_eq!(printed.print_job.activation_time, Some(launch));
+    assert_eq!(printed.codes.len(), serial_nos.len());
+    let code = &printed.codes[0];
+    let label = Label {
+        serial_no: code.serial_no,
+        unique_code: code.unique_code.clone(),
+    };
+
+    let customer = identity(2);
+    sign_in(&env, customer, UserRole::Customer);
+    assert_eq!(verify_label(&env, customer, &label).status, ProductVerificationStatus::NotYetActive);
+
+    env.advance_seconds(60 * 60);
+    assert_eq!(verify_label(&env, customer, &label).status, ProductVerificationStatus::FirstVerification);
+
+    // Another serial's code does not verify this one
+    let forged = Label {
+        serial_no: printed.codes[1].serial_no,
+        unique_code: label.unique_code.clone(),
+    };
+    assert_eq!(verify_label(&env, customer, &forged).status, ProductVerificationStatus::Invalid);
+}
+
+#[test]
+fn only_members_of_the_brand_can_print_its_codes() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let other_brand = create_brand(&env, identity(3), "Globex");
+    let product = create_product(&env, &brand, "Trail Jacket");
+    let serial_nos = create_serials(&env, &brand, product.id, 1);
+
+    let request = BulkPrintRequest {
+        product_id: product.id,
+        serial_nos,
+        printer_metadata: Vec::new(),
+        label_template_version: None,
+        capability_token: None,
+        code_policy: None,
+        activation_time: Some(0),
+    };
+    let (response,): (Response<BulkPrintResponse>,) =
+        env.update(other_brand.owner, "print_product_serial_numbers_bulk_v2", (request,));
+    assert!(matches!(response.err(), ApiError::Unauthorized { .. }));
+}
+
+#[test]
+fn reprinting_a_batch_with_a_voided_serial_leaves_the_printed_labels_valid() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let product = create_product(&env, &brand, "Trail Jacket");
+    let serial_nos = create_serials(&env, &brand, product.id, 3);
+    let printed = print_labels(&env, &brand, product.id, serial_nos.clone(), 0);
+
+    let void = UpdateSerialStatusRequest {
+        product_id: product.id,
+        serial_nos: vec![serial_nos[2]],
+        capability_token: None,
+        reason: Some("Damaged label".to_string()),
+    };
+    let (voided,): (Response<SerialNumbersBatchResponse>,) = env.update(brand.owner, "void_serial_numbers", (void,));
+    voided.ok();
+
+    let reprint = BulkPrintRequest {
+        product_id: product.id,
+        serial_nos: serial_nos.clone(),
+        printer_metadata: Vec::new(),
+        label_template_version: None,
+        capability_token: None,
+        code_policy: None,
+        activation_time: Some(0),
+    };
+    let (response,): (Response<BulkPrintResponse>,) = env.update(brand.owner, "print_product_serial_numbers_bulk_v2", (reprint,));
+    assert!(matches!(response.err(), ApiError::InvalidInput { .. }));
+
+    // The rejected job reprinted nothing, so the labels already on the shelf still verify
+    let customer = identity(2);
+    sign_in(&env, customer, UserRole::Customer);
+    let label = Label {
+        serial_no: printed.codes[0].serial_no,
+        unique_code: printed.codes[0].unique_code.clone(),
+    };
+    assert_eq!(verify_label(&env, customer, &label).status, ProductVerificationStatus::FirstVerification);
+}
@@ -0,0 +1,41 @@
+use trueorigin_integration_tests::flows::*;
+use trueorigin_integration_tests::types::*;
+use trueorigin_integration_tests::{identity, TestEnv};
+
+// Defaults of the canister's rate limit config
+const MAX_ATTEMPTS_PER_WINDOW: u32 = 5;
+const WINDOW_SECONDS: u64 = 5 * 60;
+
+#[test]
+fn repeated_bad_codes_lock_the_caller_out_until_the_window_passes() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let product = create_product(&env, &brand, "Trail Jacket");
+    let label = initial_label(&product);
+    // A well-formed code signed for a different serial
+    let forged_code = initial_label(&create_product(&env, &brand, "Rain Shell")).unique_code;
+    let customer = identity(2);
+    sign_in(&env, customer, UserRole::Customer);
+
+    for _ in 0..MAX_ATTEMPTS_PER_WINDOW {
+        let response = verify(&env, customer, label.serial_no, &forged_code).ok();
+        assert_eq!(response.status, ProductVerificationStatus::Invalid);
+    }
+
+    let locked = verify(&env, customer, label.serial_no, &label.unique_code);
+    let locked_until = locked
+        .metadata
+        .rate_limit
+        .as_ref()
+        .and_then(|info| info.locked_until)
+        .expect("the lockout is reported with the error");
+    assert!(locked_until > env.now());
+    match locked.err() {
+        ApiError::RateLimited { retry_after, .. } => assert_eq!(retry_after, locked_until),
+        other => panic!("expected a rate limit error, got {:?}", other),
+    }
+
+    // Past the lockout the attempts that caused it still weigh on the sliding window until it rolls off
+    env.advance_seconds(2 * WINDOW_SECONDS);
+    assert_eq!(verify_label(&env, customer, &label).status, ProductVerificationStatus::FirstVerification);
+}
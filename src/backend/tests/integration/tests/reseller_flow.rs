@@ -0,0 +1,91 @@
+use trueorigin_integration_tests::flows::*;
+use trueorigin_integration_tests::types::*;
+use trueorigin_integration_tests::{identity, Response, TestEnv};
+
+// Reseller codes are valid for five minutes after they are generated
+const RESELLER_CODE_VALIDITY_SECONDS: u64 = 5 * 60;
+
+fn generate_code(env: &TestEnv, reseller_user: candid::Principal, reseller_id: candid::Principal) -> ResellerUniqueCodeResponse {
+    let request = GenerateResellerUniqueCodeRequest {
+        reseller_id,
+        context: Some("storefront".to_string()),
+    };
+    let (response,): (Response<ResellerUniqueCodeResponse>,) =
+        env.update(reseller_user, "generate_reseller_unique_code_v2", (request,));
+    response.ok()
+}
+
+fn verify_code(env: &TestEnv, customer: candid::Principal, code: &ResellerUniqueCodeResponse, unique_code: &str) -> ResellerVerificationResponse {
+    let request = VerifyResellerRequest {
+        reseller_id: code.reseller_id,
+        unique_code: unique_code.to_string(),
+        timestamp: code.timestamp,
+        context: code.context.clone(),
+        product_id: None,
+        client_version: None,
+    };
+    let (response,): (Response<ResellerVerificationResponse>,) = env.query(customer, "verify_reseller_v2", (request,));
+    response.ok()
+}
+
+#[test]
+fn certified_reseller_code_verifies_until_it_expires() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let reseller_user = identity(4);
+    let reseller_id = certify_reseller(&env, &brand, reseller_user, "Corner Store");
+    let customer = identity(2);
+
+    let code = generate_code(&env, reseller_user, reseller_id);
+    let verified = verify_code(&env, customer, &code, &code.unique_code);
+    assert_eq!(verified.status, ResellerVerificationStatus::Success);
+    assert_eq!(verified.organization.map(|org| org.id), Some(brand.org_id));
+
+    let mut tampered = code.unique_code.clone();
+    let last = if tampered.ends_with('0') { "1" } else { "0" };
+    tampered.replace_range(tampered.len() - 1.., last);
+    assert_eq!(verify_code(&env, customer, &code, &tampered).status, ResellerVerificationStatus::InvalidCode);
+
+    env.advance_seconds(RESELLER_CODE_VALIDITY_SECONDS + 1);
+    assert_eq!(verify_code(&env, customer, &code, &code.unique_code).status, ResellerVerificationStatus::ExpiredCode);
+}
+
+#[test]
+fn reseller_stays_uncertified_until_the_brand_approves() {
+    let env = TestEnv::new();
+    let brand = create_brand(&env, identity(1), "Acme");
+    let reseller_user = identity(4);
+    sign_in(&env, reseller_user, UserRole::Reseller);
+    let profile = CompleteResellerProfileRequest {
+        target_organization_id: brand.org_id,
+        reseller_name: "Corner Store".to_string(),
+        ecommerce_urls: Vec::new(),
+        contact_email: None,
+        contact_phone: None,
+        additional_metadata: None,
+    };
+    let (applied,): (Response<AuthContextResponse>,) = env.update(reseller_user, "complete_reseller_profile", (profile,));
+    applied.ok();
+
+    let (pending,): (Response<Vec<ResellerApplicationDetail>>,) = env.query(
+        brand.owner,
+        "list_reseller_applications_v2",
+        (brand.org_id, None::<ResellerApplicationStatus>),
+    );
+    let pending = pending.ok();
+    assert_eq!(pending.len(), 1);
+    let reseller = pending[0].reseller.clone().expect("the application names its reseller");
+    assert!(!reseller.is_verified);
+    assert!(reseller.certification_code.is_none());
+
+    // Approving is the brand's decision, not the applicant's
+    let (self_approved,): (Response<ResellerApplicationDetail>,) =
+        env.update(reseller_user, "approve_reseller_application_v2", (pending[0].application.id,));
+    self_approved.err();
+
+    let (approved,): (Response<ResellerApplicationDetail>,) =
+        env.update(brand.owner, "approve_reseller_application_v2", (pending[0].application.id,));
+    let certified = approved.ok().reseller.expect("the approval returns the certified reseller");
+    assert!(certified.is_verified);
+    assert!(certified.certification_code.is_some());
+}
@@ -2,118 +2,904 @@ import type { Principal } from '@dfinity/principal';
 import type { ActorMethod } from '@dfinity/agent';
 import type { IDL } from '@dfinity/candid';
 
-export type ApiError = { 'InvalidInput' : { 'details' : ErrorDetails } } |
+export interface AddDisputeMessageRequest {
+  'dispute_id' : Principal,
+  'message' : string,
+}
+export interface AddWalletRequest {
+  'make_default' : boolean,
+  'chain' : WalletChain,
+  'label' : string,
+  'address' : string,
+}
+export interface AdminApproval {
+  'admin_id' : Principal,
+  'note' : [] | [string],
+  'approved_at' : bigint,
+}
+export interface AdminOperation {
+  'id' : Principal,
+  'status' : AdminOperationStatus,
+  'result' : [] | [string],
+  'rejected_at' : [] | [bigint],
+  'rejected_by' : [] | [Principal],
+  'executed_at' : [] | [bigint],
+  'kind' : AdminOperationKind,
+  'required_approvals' : number,
+  'rejection_note' : [] | [string],
+  'expires_at' : bigint,
+  'proposed_at' : bigint,
+  'proposed_by' : Principal,
+  'approvals' : Array<AdminApproval>,
+  'reason' : [] | [string],
+}
+export interface AdminOperationDecisionRequest {
+  'note' : [] | [string],
+  'operation_id' : Principal,
+}
+export type AdminOperationKind = {
+    'SetRequiredApprovals' : { 'required' : number }
+  } |
+  { 'ResetStorage' : null } |
+  { 'DeleteOrganization' : GetOrganizationAnalyticRequest } |
+  { 'ApproveKeyExport' : { 'request_id' : Principal } };
+export type AdminOperationStatus = { 'Failed' : null } |
+  { 'Rejected' : null } |
+  { 'Executed' : null } |
+  { 'Expired' : null } |
+  { 'Pending' : null };
+export interface AdminOperationsResponse {
+  'pagination' : [] | [PaginationResponse],
+  'operations' : Array<AdminOperation>,
+}
+export type ApiError = { 'AccountDisabled' : { 'details' : ErrorDetails } } |
+  {
+    'ValidationFailed' : {
+      'errors' : Array<ValidationError>,
+      'details' : ErrorDetails,
+    }
+  } |
+  { 'InvalidInput' : { 'details' : ErrorDetails } } |
   { 'NotFound' : { 'details' : ErrorDetails } } |
   { 'ExternalApiError' : { 'details' : ErrorDetails } } |
   { 'Unauthorized' : { 'details' : ErrorDetails } } |
   { 'AlreadyExists' : { 'details' : ErrorDetails } } |
+  {
+    'ServiceUnavailable' : {
+      'service' : string,
+      'retry_after' : [] | [bigint],
+      'details' : ErrorDetails,
+    }
+  } |
+  { 'RateLimited' : { 'retry_after' : bigint, 'details' : ErrorDetails } } |
   { 'MalformedData' : { 'details' : ErrorDetails } } |
-  { 'InternalError' : { 'details' : ErrorDetails } };
+  {
+    'UpgradeRequired' : {
+      'min_version' : string,
+      'download_url' : [] | [string],
+      'details' : ErrorDetails,
+    }
+  } |
+  { 'FeatureDisabled' : { 'feature' : string, 'details' : ErrorDetails } } |
+  { 'InternalError' : { 'details' : ErrorDetails } } |
+  {
+    'TermsNotAccepted' : {
+      'url' : string,
+      'version' : string,
+      'details' : ErrorDetails,
+    }
+  } |
+  {
+    'PossibleDuplicate' : {
+      'product_ids' : Array<Principal>,
+      'details' : ErrorDetails,
+    }
+  } |
+  { 'QuotaExceeded' : { 'details' : ErrorDetails } };
 export interface ApiResponse {
   'metadata' : ResponseMetadata,
-  'data' : [] | [boolean],
+  'data' : [] | [TermsAcceptance],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_1 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [AuthContextResponse],
+  'data' : [] | [PrintJob],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_10 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [string],
+  'data' : [] | [OrgPlan],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_100 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ProductVariant>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_101 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductVerificationsListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_102 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductsListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_103 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [PublicBrandsListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_104 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<QuarantinedEntry>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_105 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ReadonlyPrincipal>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_106 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<RecallCampaign>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_107 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ReportSchedule>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_108 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ReportShareLink>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_109 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ReportsListResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_11 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [OrganizationAnalyticData],
+  'data' : [] | [boolean],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_110 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ResellerApplicationDetail>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_111 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ResellerPreapproval>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_112 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [RewardPenaltiesListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_113 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SerialPrintHistoryResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_114 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<StatusMessage>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_115 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<DuplicateProductGroup>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_116 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<TermsVersion>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_117 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [UsersListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_118 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ProductSerialNumber>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_119 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<VerificationDelegation>],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_12 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [RateLimitInfo],
+  'data' : [] | [SerialStatusCheckResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_120 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [LogoutResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_121 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BrandLookupResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_122 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [UserNotification],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_123 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [MintCapabilityTokenResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_124 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [RewardPenalty],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_125 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [PreapproveResellersResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_126 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BulkPrintResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_127 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [TermsVersion],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_128 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [QuarantinedEntry],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_129 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [RedeemRewardResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_13 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [OrganizationsListResponse],
+  'data' : [] | [VerificationDomainResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_130 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [UserResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_131 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ResellerPreapproval],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_132 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [StatusMessage],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_133 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [VerificationDomain],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_134 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OutboxDelivery],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_135 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ResetStorageResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_136 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [CircuitBreaker],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_137 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ModerationCase],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_138 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [CapabilityToken],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_139 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ReportShareLink],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_14 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [LogoutResponse],
+  'data' : [] | [ClaimGuestVerificationsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_140 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [InvariantReport],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_141 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [VerificationRollupResult],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_142 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [IntegrityScanReport],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_143 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [MetadataSchema],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_144 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrgQuota],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_145 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [PostVerificationMessage],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_146 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SignPayloadResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_147 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductVerificationEnhancedResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_148 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [GuestSessionResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_149 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [IdentityLinkCodeResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_15 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [RedeemRewardResponse],
+  'data' : [] | [FeatureFlagStatus],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_150 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [VerificationChallengeResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_151 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BiExportDelivery],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_152 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [UploadCouponCodesResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_153 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BatchVerificationResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_154 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ResellerVerificationResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_155 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [WsGetMessagesResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_156 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [WsOpenResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_16 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [UserResponse],
+  'data' : [] | [ProductResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_17 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [ResetStorageResponse],
+  'data' : [] | [ResellerProductsResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_18 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [null],
+  'data' : [] | [RecallCampaign],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_19 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [ProductVerificationEnhancedResponse],
+  'data' : [] | [IdentityLinkResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_2 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [OrganizationContextResponse],
+  'data' : [] | [Dispute],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_20 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [ResellerVerificationResponse],
+  'data' : [] | [AuthContextResponse],
   'error' : [] | [ApiError],
 }
-export interface ApiResponse_3 {
+export interface ApiResponse_21 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ChallengeVerificationResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_22 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [null],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_23 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BundleContentsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_24 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [NotificationRuleResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_25 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrganizationContextResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_26 {
   'metadata' : ResponseMetadata,
   'data' : [] | [OrganizationResponse],
   'error' : [] | [ApiError],
 }
+export interface ApiResponse_27 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductVariant],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_28 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ReadonlyPrincipal],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_29 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ReportScheduleResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_3 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SavedWallet],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_30 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ReportShareLinkResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_31 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SerialNumbersBatchResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_32 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductSerialNumber],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_33 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [VerificationDelegation],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_34 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [RewardPoolBalanceResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_35 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [StatusDescriptionResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_36 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [User],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_37 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [KeyEscrowExport],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_38 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ResellerUniqueCodeResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_39 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<VerificationMonthlyAggregate>],
+  'error' : [] | [ApiError],
+}
 export interface ApiResponse_4 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [ProductResponse],
+  'data' : [] | [MyProductEntry],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_40 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<UserRole>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_41 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BiExportConfig],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_42 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BrandDashboardResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_43 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [CanisterConfig],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_44 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<CircuitBreaker>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_45 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<CyclesSample>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_46 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [DirectoryListing],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_47 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<EndpointMetricsSummary>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_48 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [JobResultChunkResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_49 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [LabelTemplate],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_5 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [ResellerUniqueCodeResponse],
+  'data' : [] | [AdminOperation],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_50 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ConsumerActivityResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_51 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [CapabilitiesResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_52 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<OrganizationPublic>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_53 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [PlanUsageResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_54 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ResellerCertificationPageContext],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_55 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [NavigationContextResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_56 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [string],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_57 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SerialBatchDefaults],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_58 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrgOnboardingStatusResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_59 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrgSettings],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_6 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [Array<UserRole>],
+  'data' : [] | [KeyEscrowRequest],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_60 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrgUsageResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_61 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrganizationAnalyticData],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_62 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OwnershipStatsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_63 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [PlatformMetricsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_64 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductRewardStats],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_65 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [PublicBrandStatsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_66 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [RecallCampaignStatusResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_67 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<LogEntry>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_68 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Report],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_69 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SharedReportResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_7 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [Array<OrganizationPublic>],
+  'data' : [] | [PendingRedemption],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_70 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SerialRangeStatsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_71 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [SuspiciousActivityResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_72 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [TermsStatusResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_73 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [[] | [VerificationDomainResponse]],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_74 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [VerificationHeatmapResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_75 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [RateLimitInfo],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_76 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [VerificationRetentionPolicy],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_77 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProbeRecord],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_78 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [AuditLogListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_79 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [AdminOperationsResponse],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_8 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [ResellerCertificationPageContext],
+  'data' : [] | [ResellerApplicationDetail],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_80 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [BiExportDeliveriesListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_81 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<CapabilityToken>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_82 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [DisputesListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_83 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<FeatureFlagStatus>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_84 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<Job>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_85 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<MetadataSchema>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_86 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ModerationCasesResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_87 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [NotificationsListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_88 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<MyProductEntry>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_89 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<SavedWallet>],
   'error' : [] | [ApiError],
 }
 export interface ApiResponse_9 {
   'metadata' : ResponseMetadata,
-  'data' : [] | [NavigationContextResponse],
+  'data' : [] | [JobStatusResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_90 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<NotificationRule>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_91 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<KeyEscrowRequest>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_92 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrgVerificationsResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_93 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OrganizationsListResponse],
   'error' : [] | [ApiError],
 }
+export interface ApiResponse_94 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [OutboxDeliveriesResponse],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_95 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<PendingRedemption>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_96 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<PostVerificationMessage>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_97 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<PrintJob>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_98 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [Array<ProbeRecord>],
+  'error' : [] | [ApiError],
+}
+export interface ApiResponse_99 {
+  'metadata' : ResponseMetadata,
+  'data' : [] | [ProductSerialNumbersListResponse],
+  'error' : [] | [ApiError],
+}
+export interface ArchivedAggregatesQuery {
+  'from_month' : [] | [number],
+  'product_id' : [] | [Principal],
+  'org_id' : Principal,
+  'to_month' : [] | [number],
+}
+export interface AuditLogEntry {
+  'action' : string,
+  'metadata' : Array<Metadata>,
+  'user_id' : Principal,
+  'resource_type' : string,
+  'timestamp' : bigint,
+  'resource_id' : Principal,
+  'success' : boolean,
+}
+export interface AuditLogListResponse {
+  'pagination' : [] | [PaginationResponse],
+  'entries' : Array<AuditLogEntry>,
+}
 export interface AuthContextResponse {
   'reseller_details' : [] | [ResellerContextDetails],
   'role' : [] | [UserRole],
@@ -121,11 +907,234 @@ export interface AuthContextResponse {
   'brand_owner_details' : [] | [BrandOwnerContextDetails],
   'is_registered' : boolean,
 }
+export interface AuthenticityFactor {
+  'weight' : number,
+  'kind' : AuthenticityFactorKind,
+  'detail' : string,
+  'score' : number,
+}
+export type AuthenticityFactorKind = { 'Geography' : null } |
+  { 'Custody' : null } |
+  { 'Reports' : null } |
+  { 'ScanVelocity' : null } |
+  { 'Signature' : null };
+export interface AuthenticityScore {
+  'score' : number,
+  'factors' : Array<AuthenticityFactor>,
+}
+export interface AuthenticityWeights {
+  'signature' : number,
+  'custody' : number,
+  'geography' : number,
+  'scan_velocity' : number,
+  'reports' : number,
+}
+export interface BatchVerificationItem {
+  'unique_code' : string,
+  'serial_no' : Principal,
+}
+export interface BatchVerificationItemResult {
+  'status' : BatchVerificationItemStatus,
+  'product_id' : [] | [Principal],
+  'message' : [] | [string],
+  'serial_no' : Principal,
+}
+export type BatchVerificationItemStatus = { 'Invalid' : null } |
+  { 'SuspectedClone' : null } |
+  { 'Genuine' : null } |
+  { 'Voided' : null } |
+  { 'Revoked' : null };
+export interface BatchVerificationResponse {
+  'rate_limit' : RateLimitInfo,
+  'results' : Array<BatchVerificationItemResult>,
+  'summary' : BatchVerificationSummary,
+}
+export interface BatchVerificationSummary {
+  'total' : number,
+  'invalid' : number,
+  'genuine' : number,
+  'suspect' : number,
+}
+export interface BiExportConfig {
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'next_push_at' : bigint,
+  'endpoint_url' : string,
+  'last_period_end' : [] | [bigint],
+  'is_active' : boolean,
+}
+export interface BiExportDeliveriesListResponse {
+  'deliveries' : Array<BiExportDelivery>,
+  'pagination' : PaginationResponse,
+}
+export interface BiExportDelivery {
+  'id' : Principal,
+  'last_error' : [] | [string],
+  'status' : BiExportDeliveryStatus,
+  'period_end' : bigint,
+  'org_id' : Principal,
+  'period_start' : bigint,
+  'attempts' : number,
+  'created_at' : bigint,
+  'schema_version' : number,
+  'payload_bytes' : bigint,
+  'delivered_at' : [] | [bigint],
+}
+export type BiExportDeliveryStatus = { 'Failed' : null } |
+  { 'Delivered' : null } |
+  { 'Pending' : null };
+export interface BrandDashboardResponse {
+  'pending_reseller_applications' : Array<Reseller>,
+  'low_stock_serial_pools' : Array<LowStockSerialPool>,
+  'generated_at' : bigint,
+  'notifications' : Array<UserNotification>,
+  'org_id' : Principal,
+  'analytics' : OrganizationAnalyticData,
+  'recent_verifications' : Array<ProductVerificationDetail>,
+  'unread_notifications' : number,
+}
+export interface BrandLookupResponse {
+  'website_url' : [] | [string],
+  'product_id' : Principal,
+  'logo_url' : [] | [string],
+  'product_name' : string,
+  'badge' : [] | [VerifiedBrandBadge],
+  'organization' : OrganizationPublic,
+}
 export interface BrandOwnerContextDetails {
   'active_organization' : [] | [OrganizationPublic],
   'has_organizations' : boolean,
   'organizations' : [] | [Array<OrganizationPublic>],
 }
+export type BreakerState = { 'Open' : null } |
+  { 'Closed' : null } |
+  { 'HalfOpen' : null };
+export interface BulkPrintRequest {
+  'printer_metadata' : Array<Metadata>,
+  'product_id' : Principal,
+  'label_template_version' : [] | [string],
+  'serial_nos' : Array<Principal>,
+  'capability_token' : [] | [string],
+  'code_policy' : [] | [PrintCodePolicy],
+  'activation_time' : [] | [bigint],
+}
+export interface BulkPrintResponse {
+  'codes' : Array<ProductUniqueCodeResultRecord>,
+  'label_template' : [] | [LabelTemplate],
+  'print_job' : PrintJob,
+}
+export interface Bundle {
+  'members' : Array<BundleMember>,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'outer_product_id' : Principal,
+  'outer_serial_no' : Principal,
+}
+export interface BundleContentsResponse {
+  'summary' : BundleSummary,
+  'bundle' : Bundle,
+}
+export interface BundleMember {
+  'product_id' : Principal,
+  'serial_no' : Principal,
+}
+export interface BundleSummary {
+  'total_units' : number,
+  'valid_units' : number,
+  'units' : Array<BundleUnit>,
+  'outer_serial_no' : Principal,
+}
+export interface BundleUnit {
+  'revoked' : boolean,
+  'product_id' : Principal,
+  'valid' : boolean,
+  'voided' : boolean,
+  'user_serial_no' : [] | [string],
+  'serial_no' : Principal,
+}
+export interface CanisterConfig {
+  'llm' : LlmProviderConfig,
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'outcall_limits' : [] | [OutcallResponseLimits],
+  'client_version_policy' : [] | [ClientVersionPolicy],
+  'rate_limit' : RateLimitConfig,
+  'scraper_url' : string,
+  'email_relay_url' : [] | [string],
+  'required_admin_approvals' : [] | [number],
+  'live_gateways' : [] | [Array<Principal>],
+  'cycles_alerts' : [] | [CyclesAlertConfig],
+  'ledger_canister_id' : [] | [Principal],
+  'moderation' : [] | [ModerationConfig],
+  'feature_flags' : Array<FeatureFlag>,
+}
+export interface CapabilitiesResponse {
+  'permissions' : Array<Permission>,
+  'features' : Array<FeatureFlag>,
+  'is_admin' : boolean,
+  'role' : [] | [UserRole],
+  'is_enabled' : boolean,
+  'is_registered' : boolean,
+  'organizations' : Array<OrgCapabilities>,
+}
+export interface CapabilityClaims {
+  'issued_at' : bigint,
+  'token_id' : Principal,
+  'org_id' : Principal,
+  'product_ids' : Array<Principal>,
+  'operations' : Array<CapabilityOperation>,
+  'holder' : Principal,
+  'expires_at' : bigint,
+}
+export type CapabilityOperation = { 'PrintSerials' : null } |
+  { 'UpdateSerialStatus' : null } |
+  { 'CreateSerials' : null };
+export interface CapabilityToken {
+  'claims' : CapabilityClaims,
+  'issued_by' : Principal,
+  'last_used_at' : [] | [bigint],
+  'revoked_at' : [] | [bigint],
+  'revoked_by' : [] | [Principal],
+  'use_count' : bigint,
+}
+export interface ChallengeVerificationResponse {
+  'status' : VerificationChallengeStatus,
+  'result' : [] | [ProductVerificationEnhancedResponse],
+}
+export interface CircuitBreaker {
+  'service' : ExternalService,
+  'half_open_at' : [] | [bigint],
+  'opened_at' : [] | [bigint],
+  'probe_started_at' : [] | [bigint],
+  'total_short_circuited' : bigint,
+  'last_success_at' : [] | [bigint],
+  'state' : BreakerState,
+  'last_failure_at' : [] | [bigint],
+  'consecutive_failures' : number,
+  'last_failure' : [] | [string],
+  'consecutive_opens' : number,
+}
+export interface ClaimGuestVerificationsResponse {
+  'points_awarded' : number,
+  'claimed_verifications' : number,
+}
+export interface ClientVersionPolicy {
+  'min_version' : string,
+  'download_url' : [] | [string],
+}
+export interface CodeRevocation {
+  'revoked_at' : bigint,
+  'revoked_by' : Principal,
+  'print_version' : number,
+  'reason' : CodeRevocationReason,
+}
+export type CodeRevocationReason = { 'Lost' : null } |
+  { 'Stolen' : null } |
+  { 'Misprinted' : null } |
+  { 'Other' : null };
 export interface CompleteResellerProfileRequest {
   'ecommerce_urls' : Array<Metadata>,
   'additional_metadata' : [] | [Array<Metadata>],
@@ -134,10 +1143,197 @@ export interface CompleteResellerProfileRequest {
   'target_organization_id' : Principal,
   'reseller_name' : string,
 }
+export interface CompleteVerificationChallengeRequest {
+  'signature' : string,
+  'locale' : [] | [string],
+  'guest_token' : [] | [string],
+  'country_code' : [] | [string],
+  'challenge_id' : Principal,
+}
+export interface ConsumerActivityResponse {
+  'owned_products' : Array<MyProductEntry>,
+  'pagination' : PaginationResponse,
+  'reward_penalties' : Array<RewardPenalty>,
+  'reward_points' : number,
+  'pending_redemptions' : Array<PendingRedemption>,
+  'unclaimed_rewards' : Array<UnclaimedReward>,
+  'verifications' : Array<ConsumerVerificationActivity>,
+  'first_verifications' : number,
+  'total_verifications' : number,
+}
+export interface ConsumerVerificationActivity {
+  'status' : ProductVerificationStatus,
+  'product_id' : Principal,
+  'reward_claimed' : boolean,
+  'reward_transaction_id' : [] | [string],
+  'org_id' : [] | [Principal],
+  'created_at' : bigint,
+  'product_name' : string,
+  'coupon' : [] | [RedeemedCoupon],
+  'serial_no' : Principal,
+  'verification_id' : Principal,
+}
+export type CounterfeitSignal = { 'GreyMarket' : null } |
+  { 'SuspectedClone' : null } |
+  { 'InvalidCode' : null };
+export interface CreateBundleRequest {
+  'member_serial_nos' : Array<Principal>,
+  'outer_product_id' : Principal,
+  'outer_serial_no' : Principal,
+}
+export interface CreateNotificationRuleRequest {
+  'webhook_url' : string,
+  'trigger' : NotificationTrigger,
+  'product_id' : Principal,
+  'min_scan_count' : [] | [number],
+}
+export interface CreateProductRequest {
+  'sku' : [] | [string],
+  'force' : [] | [boolean],
+  'metadata' : Array<Metadata>,
+  'name' : string,
+  'org_id' : Principal,
+  'description' : string,
+  'category' : string,
+}
+export interface CreateProductVariantRequest {
+  'sku' : [] | [string],
+  'product_id' : Principal,
+  'name' : string,
+  'attributes' : Array<Metadata>,
+}
+export interface CreateReportScheduleRequest {
+  'recipient_emails' : Array<string>,
+  'org_id' : Principal,
+  'delivery_url' : string,
+  'frequency' : ReportFrequency,
+}
+export interface CreateReportShareLinkRequest {
+  'filters' : ReportFilters,
+  'ttl_seconds' : bigint,
+  'org_id' : Principal,
+}
+export interface CreateSerialNumbersBatchRequest {
+  'user_serial_prefix' : [] | [string],
+  'product_id' : Principal,
+  'count' : [] | [number],
+  'capability_token' : [] | [string],
+  'variant_id' : [] | [Principal],
+}
+export interface CyclesAlertConfig {
+  'webhook_url' : [] | [string],
+  'critical_threshold' : bigint,
+  'warning_threshold' : bigint,
+}
+export type CyclesLevel = { 'Healthy' : null } |
+  { 'Critical' : null } |
+  { 'Warning' : null };
+export interface CyclesSample {
+  'balance' : bigint,
+  'alerted' : boolean,
+  'level' : CyclesLevel,
+  'timestamp' : bigint,
+}
+export interface CyclesStatus {
+  'balance' : bigint,
+  'last_alert_at' : [] | [bigint],
+  'alert_webhook_configured' : boolean,
+  'critical_threshold' : bigint,
+  'level' : CyclesLevel,
+  'last_checked_at' : [] | [bigint],
+  'warning_threshold' : bigint,
+}
+export interface DelegateVerificationRequest {
+  'product_id' : Principal,
+  'manufacturer_org_id' : Principal,
+}
+export interface DepositRewardPoolRequest {
+  'org_id' : Principal,
+  'amount' : bigint,
+}
+export interface DirectoryListing {
+  'is_public' : boolean,
+  'website_url' : [] | [string],
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'featured' : boolean,
+  'tagline' : [] | [string],
+  'org_id' : Principal,
+  'logo_url' : [] | [string],
+  'badge' : [] | [VerifiedBrandBadge],
+  'suspended' : boolean,
+  'suspension_reason' : [] | [string],
+}
+export interface Dispute {
+  'id' : Principal,
+  'status' : DisputeStatus,
+  'updated_at' : bigint,
+  'subject' : string,
+  'product_id' : Principal,
+  'messages' : Array<DisputeMessage>,
+  'org_id' : Principal,
+  'opened_by' : Principal,
+  'created_at' : bigint,
+  'resolution' : [] | [string],
+  'resolved_at' : [] | [bigint],
+  'resolved_by' : [] | [Principal],
+  'serial_no' : Principal,
+  'verification_id' : [] | [Principal],
+}
+export interface DisputeMessage {
+  'body' : string,
+  'created_at' : bigint,
+  'author' : Principal,
+  'party' : DisputeParty,
+}
+export type DisputeParty = { 'Brand' : null } |
+  { 'Consumer' : null } |
+  { 'Admin' : null };
+export type DisputeStatus = { 'Open' : null } |
+  { 'InReview' : null } |
+  { 'Resolved' : null };
+export interface DisputesListResponse {
+  'disputes' : Array<Dispute>,
+  'pagination' : PaginationResponse,
+}
+export interface DuplicateProductGroup {
+  'normalized_name' : string,
+  'normalized_category' : string,
+  'products' : Array<Product>,
+}
+export interface EndpointMetricsSummary {
+  'error_codes' : Array<ErrorCodeCount>,
+  'endpoint' : string,
+  'last_called_at' : bigint,
+  'calls' : bigint,
+  'sampled_calls' : number,
+  'errors' : bigint,
+  'since' : bigint,
+  'error_rate_percent' : number,
+  'mean_instructions' : bigint,
+  'p50_instructions' : bigint,
+  'max_instructions' : bigint,
+  'p95_instructions' : bigint,
+}
+export interface ErrorCodeCount { 'code' : string, 'count' : bigint }
 export interface ErrorDetails {
   'message' : string,
   'details' : Array<Metadata>,
 }
+export type ExternalService = { 'OpenAi' : null } |
+  { 'DnsResolver' : null } |
+  { 'Scraper' : null };
+export interface FeatureFlag { 'name' : string, 'enabled' : boolean }
+export interface FeatureFlagStatus {
+  'global_enabled' : [] | [boolean],
+  'effective' : boolean,
+  'name' : string,
+  'org_override' : [] | [boolean],
+}
+export interface FederatedVerification {
+  'manufacturer' : OrganizationPublic,
+  'brand' : OrganizationPublic,
+}
 export interface FindOrganizationsRequest {
   'pagination' : [] | [PaginationRequest],
   'name' : string,
@@ -147,26 +1343,478 @@ export interface GenerateResellerUniqueCodeRequest {
   'reseller_id' : Principal,
 }
 export interface GetOrganizationAnalyticRequest { 'org_id' : Principal }
+export interface GuestSessionResponse {
+  'token' : string,
+  'max_verifications' : number,
+  'session_id' : Principal,
+  'expires_at' : bigint,
+}
 export interface HttpHeader { 'value' : string, 'name' : string }
 export interface HttpResponse {
   'status' : bigint,
   'body' : Uint8Array | number[],
   'headers' : Array<HttpHeader>,
 }
+export interface IdentityLinkCodeResponse {
+  'code' : string,
+  'expires_at' : bigint,
+}
+export interface IdentityLinkResponse {
+  'org_ids_added' : number,
+  'verifications_transferred' : number,
+  'user_id' : Principal,
+  'linked_principal' : Principal,
+  'merged_account' : boolean,
+  'points_transferred' : number,
+}
+export interface IntegrityFailure {
+  'key' : Principal,
+  'sub_key' : [] | [IntegritySubKey],
+  'size_bytes' : bigint,
+  'error' : string,
+}
+export interface IntegrityScanReport {
+  'failures' : Array<IntegrityFailure>,
+  'scanned' : bigint,
+  'store' : IntegrityStore,
+}
+export type IntegrityStore = { 'Users' : null } |
+  { 'Organizations' : null } |
+  { 'ProductVerifications' : null } |
+  { 'VerificationRecords' : null } |
+  { 'ProductSerialNumbers' : null } |
+  { 'Products' : null } |
+  { 'Resellers' : null } |
+  { 'SerialRecords' : null };
+export type IntegritySubKey = { 'Seq' : bigint } |
+  { 'SerialNo' : Principal };
+export type InvariantKind = { 'SerialProductMismatch' : null } |
+  { 'UserOrganizationMissing' : null } |
+  { 'SessionKeyConflict' : null } |
+  { 'VerificationsProductMissing' : null } |
+  { 'ResellerOrganizationMissing' : null } |
+  { 'ResellerUserMissing' : null } |
+  { 'ProductOrganizationMissing' : null } |
+  { 'SerialsProductMissing' : null };
+export interface InvariantReport {
+  'violations' : Array<InvariantViolation>,
+  'checked_at' : bigint,
+  'violation_count' : bigint,
+}
+export interface InvariantViolation {
+  'kind' : InvariantKind,
+  'detail' : string,
+  'referenced_id' : Principal,
+  'entity_id' : Principal,
+}
+export interface Job {
+  'id' : Principal,
+  'status' : JobStatus,
+  'updated_at' : bigint,
+  'result_rows' : bigint,
+  'total_units' : bigint,
+  'cursor' : JobCursor,
+  'org_id' : Principal,
+  'spec' : JobSpec,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'error' : [] | [string],
+  'result_columns' : Array<string>,
+  'result_chunks' : number,
+  'started_at' : [] | [bigint],
+  'processed_units' : bigint,
+  'finished_at' : [] | [bigint],
+}
+export interface JobCursor { 'seq' : bigint, 'position' : bigint }
+export type JobRequest = { 'VerificationExport' : ReportFilters } |
+  {
+    'SerialImport' : {
+      'user_serial_prefix' : [] | [string],
+      'product_id' : Principal,
+      'count' : number,
+      'variant_id' : [] | [Principal],
+    }
+  };
+export interface JobResultChunkResponse {
+  'is_last' : boolean,
+  'chunk_index' : number,
+  'total_chunks' : number,
+  'rows' : Array<string>,
+  'job_id' : Principal,
+  'columns' : Array<string>,
+}
+export type JobSpec = {
+    'VerificationExport' : {
+      'period_end' : bigint,
+      'period_start' : bigint,
+      'product_ids' : Array<Principal>,
+    }
+  } |
+  {
+    'SerialImport' : {
+      'user_serial_prefix' : [] | [string],
+      'product_id' : Principal,
+      'count' : number,
+      'variant_id' : [] | [Principal],
+    }
+  };
+export type JobStatus = { 'Queued' : null } |
+  { 'Failed' : null } |
+  { 'Running' : null } |
+  { 'Cancelled' : null } |
+  { 'Completed' : null };
+export interface JobStatusResponse { 'job' : Job, 'progress_percent' : number }
+export interface KeyEscrowExport {
+  'request_id' : Principal,
+  'algorithm' : string,
+  'ciphertext' : string,
+  'ephemeral_public_key' : string,
+  'org_id' : Principal,
+  'nonce' : string,
+}
+export interface KeyEscrowRequest {
+  'id' : Principal,
+  'status' : KeyEscrowStatus,
+  'cancelled_at' : [] | [bigint],
+  'cancelled_by' : [] | [Principal],
+  'recipient_public_key' : string,
+  'org_id' : Principal,
+  'approved_at' : [] | [bigint],
+  'approved_by' : [] | [Principal],
+  'exported_at' : [] | [bigint],
+  'requested_at' : bigint,
+  'requested_by' : Principal,
+  'confirmable_at' : [] | [bigint],
+  'expires_at' : [] | [bigint],
+  'reason' : [] | [string],
+}
+export type KeyEscrowStatus = { 'Approved' : null } |
+  { 'Cancelled' : null } |
+  { 'PendingApproval' : null } |
+  { 'Exported' : null };
+export interface LabelTemplate {
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'product_id' : Principal,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'qr_error_correction' : QrErrorCorrection,
+  'version' : number,
+  'qr_version' : [] | [number],
+  'height_mm' : number,
+  'width_mm' : number,
+  'show_human_readable_code' : boolean,
+  'artwork_asset_url' : [] | [string],
+  'qr_placement' : QrPlacement,
+}
+export interface ListBiExportDeliveriesRequest {
+  'pagination' : [] | [PaginationRequest],
+  'org_id' : Principal,
+}
+export interface ListDisputesRequest {
+  'status' : [] | [DisputeStatus],
+  'pagination' : [] | [PaginationRequest],
+  'org_id' : [] | [Principal],
+}
+export interface ListNotificationsRequest {
+  'pagination' : [] | [PaginationRequest],
+  'unread_only' : [] | [boolean],
+}
+export interface ListOrgVerificationsRequest {
+  'filters' : [] | [OrgVerificationFilters],
+  'cursor' : [] | [string],
+  'org_id' : Principal,
+  'limit' : [] | [number],
+}
+export interface ListProductSerialNumbersRequest {
+  'product_id' : [] | [Principal],
+  'pagination' : [] | [PaginationRequest],
+  'organization_id' : [] | [Principal],
+}
+export interface ListProductVerificationsRequest {
+  'product_id' : [] | [Principal],
+  'pagination' : [] | [PaginationRequest],
+  'serial_number' : [] | [Principal],
+  'organization_id' : [] | [Principal],
+}
+export interface ListProductsRequest {
+  'pagination' : [] | [PaginationRequest],
+  'org_id' : Principal,
+}
+export interface ListReportsRequest {
+  'pagination' : [] | [PaginationRequest],
+  'org_id' : Principal,
+}
+export interface ListRewardPenaltiesRequest {
+  'pagination' : [] | [PaginationRequest],
+  'org_id' : [] | [Principal],
+  'user_id' : [] | [Principal],
+}
+export type LiveEvent = {
+    'Counterfeit' : {
+      'product_id' : Principal,
+      'detected_at' : bigint,
+      'country_code' : [] | [string],
+      'signal' : CounterfeitSignal,
+      'serial_no' : Principal,
+    }
+  } |
+  { 'Closed' : { 'reason' : string } } |
+  {
+    'Verification' : {
+      'status' : ProductVerificationStatus,
+      'product_id' : Principal,
+      'recorded_at' : bigint,
+      'country_code' : [] | [string],
+      'serial_no' : Principal,
+    }
+  } |
+  { 'Subscribed' : { 'topics' : Array<LiveTopic> } };
+export type LiveTopic = { 'Verifications' : null } |
+  { 'Counterfeits' : null };
+export interface LlmProviderConfig {
+  'model' : string,
+  'provider' : string,
+  'api_key' : string,
+  'api_host' : string,
+}
+export interface LogEntry {
+  'seq' : bigint,
+  'level' : LogLevel,
+  'message' : string,
+  'timestamp' : bigint,
+  'module' : string,
+}
+export type LogLevel = { 'Error' : null } |
+  { 'Info' : null } |
+  { 'Warn' : null } |
+  { 'Debug' : null };
 export interface LogoutResponse {
   'redirect_url' : [] | [string],
   'message' : string,
 }
+export interface LowStockSerialPool {
+  'product_id' : Principal,
+  'variant_id' : [] | [Principal],
+  'product_name' : string,
+  'total_serials' : bigint,
+  'available_serials' : bigint,
+}
 export interface Metadata { 'key' : string, 'value' : string }
+export interface MetadataFieldSchema {
+  'key' : string,
+  'value_type' : MetadataValueType,
+  'allowed_values' : [] | [Array<string>],
+  'required' : boolean,
+}
+export interface MetadataSchema {
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'org_id' : Principal,
+  'product_fields' : Array<MetadataFieldSchema>,
+  'serial_fields' : Array<MetadataFieldSchema>,
+  'category' : string,
+}
+export type MetadataValueType = { 'Url' : null } |
+  { 'Date' : null } |
+  { 'Text' : null } |
+  { 'Boolean' : null } |
+  { 'Decimal' : null } |
+  { 'Integer' : null };
+export interface MintCapabilityTokenRequest {
+  'ttl_seconds' : bigint,
+  'org_id' : Principal,
+  'product_ids' : Array<Principal>,
+  'operations' : Array<CapabilityOperation>,
+  'holder' : Principal,
+}
+export interface MintCapabilityTokenResponse {
+  'token' : string,
+  'record' : CapabilityToken,
+}
+export interface ModerateDirectoryListingRequest {
+  'featured' : [] | [boolean],
+  'org_id' : Principal,
+  'suspended' : [] | [boolean],
+  'suspension_reason' : [] | [string],
+}
+export type ModeratedEntityKind = { 'Reseller' : null } |
+  { 'Organization' : null } |
+  { 'Product' : null };
+export interface ModeratedField { 'field' : string, 'text' : string }
+export interface ModerationCase {
+  'status' : ModerationStatus,
+  'source' : ModerationSource,
+  'org_id' : Principal,
+  'reviewed_at' : [] | [bigint],
+  'reviewed_by' : [] | [Principal],
+  'fields' : Array<ModeratedField>,
+  'matched' : Array<string>,
+  'flagged_at' : bigint,
+  'entity_kind' : ModeratedEntityKind,
+  'entity_id' : Principal,
+  'review_note' : [] | [string],
+  'submitted_by' : Principal,
+}
+export interface ModerationCasesResponse {
+  'cases' : Array<ModerationCase>,
+  'pagination' : [] | [PaginationResponse],
+}
+export interface ModerationConfig {
+  'llm_moderation' : boolean,
+  'blocklist' : Array<string>,
+}
+export type ModerationSource = { 'Llm' : null } |
+  { 'Blocklist' : null };
+export type ModerationStatus = { 'Approved' : null } |
+  { 'Rejected' : null } |
+  { 'Flagged' : null };
+export interface MyProductEntry {
+  'product_id' : Principal,
+  'discontinued' : boolean,
+  'org_id' : Principal,
+  'org_name' : [] | [string],
+  'product_name' : string,
+  'category' : string,
+  'warranty_expires_at' : [] | [bigint],
+  'registered_at' : bigint,
+  'warranty_status' : WarrantyStatus,
+  'user_serial_no' : [] | [string],
+  'serial_no' : Principal,
+  'recall' : [] | [ProductRecall],
+}
 export interface NavigationContextResponse {
   'user_display_name' : string,
   'user_avatar_id' : [] | [string],
   'current_organization_name' : [] | [string],
 }
+export interface NotificationRule {
+  'id' : Principal,
+  'webhook_url' : string,
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'trigger' : NotificationTrigger,
+  'product_id' : Principal,
+  'org_id' : Principal,
+  'min_scan_count' : [] | [number],
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'is_active' : boolean,
+}
+export interface NotificationRuleResponse { 'rule' : NotificationRule }
+export type NotificationTrigger = { 'Invalid' : null } |
+  { 'AnyVerification' : null } |
+  { 'MultipleVerification' : null } |
+  { 'GreyMarket' : null } |
+  { 'SuspectedClone' : null };
+export interface NotificationsListResponse {
+  'notifications' : Array<UserNotification>,
+  'pagination' : PaginationResponse,
+  'unread_count' : number,
+}
+export interface OnboardingStep {
+  'step' : OnboardingStepKind,
+  'completed' : boolean,
+}
+export type OnboardingStepKind = { 'BrandingSet' : null } |
+  { 'FirstProductCreated' : null } |
+  { 'WebhookConfigured' : null } |
+  { 'FirstResellerCertified' : null } |
+  { 'SerialsPrinted' : null } |
+  { 'RewardPolicySet' : null };
+export interface OpenDisputeRequest {
+  'subject' : string,
+  'product_id' : Principal,
+  'message' : string,
+  'serial_no' : Principal,
+  'verification_id' : [] | [Principal],
+}
+export interface OrgCapabilities {
+  'permissions' : Array<Permission>,
+  'features' : Array<FeatureFlag>,
+  'org_id' : Principal,
+}
+export interface OrgOnboardingStatusResponse {
+  'total_steps' : number,
+  'next_step' : [] | [OnboardingStepKind],
+  'org_id' : Principal,
+  'steps' : Array<OnboardingStep>,
+  'completed_steps' : number,
+}
+export interface OrgPlan {
+  'status' : PlanStatus,
+  'updated_by' : Principal,
+  'tier_changed_at' : bigint,
+  'status_changed_at' : bigint,
+  'org_id' : Principal,
+  'tier' : PlanTier,
+}
+export interface OrgProbeActivity {
+  'last_seen_at' : bigint,
+  'org_id' : Principal,
+  'suspicious_hits' : bigint,
+  'first_seen_at' : bigint,
+  'recent_probers' : Array<Principal>,
+}
+export interface OrgQuota {
+  'max_storage_bytes' : [] | [bigint],
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'max_outcalls_per_day' : [] | [number],
+  'org_id' : Principal,
+  'max_serials_per_month' : [] | [number],
+}
+export interface OrgSettings {
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'serial_batch_defaults' : [] | [SerialBatchDefaults],
+  'duplicate_scan_threshold' : [] | [number],
+  'client_version_policy' : [] | [ClientVersionPolicy],
+  'org_id' : Principal,
+  'certification_grace_period_seconds' : [] | [bigint],
+  'public_stats_enabled' : [] | [boolean],
+  'redemption_approval_threshold' : [] | [number],
+  'single_use_reseller_codes' : [] | [boolean],
+  'certification_validity_seconds' : [] | [bigint],
+  'verification_privacy' : [] | [VerificationPrivacyPolicy],
+  'authenticity_weights' : [] | [AuthenticityWeights],
+}
+export interface OrgUsageResponse {
+  'outcalls_limit' : number,
+  'outcalls_window_resets_at' : bigint,
+  'oversized_responses_today' : number,
+  'serials_this_month' : number,
+  'org_id' : Principal,
+  'storage_limit' : bigint,
+  'serials_window_resets_at' : bigint,
+  'outcalls_today' : number,
+  'storage_bytes' : bigint,
+  'serials_limit' : number,
+}
+export interface OrgVerificationFilters {
+  'to' : [] | [bigint],
+  'from' : [] | [bigint],
+  'statuses' : [] | [Array<ProductVerificationStatus>],
+  'product_ids' : [] | [Array<Principal>],
+  'user_email_domain' : [] | [string],
+}
+export interface OrgVerificationsResponse {
+  'next_cursor' : [] | [string],
+  'verifications' : Array<ProductVerificationDetail>,
+  'has_more' : boolean,
+}
 export interface OrganizationAnalyticData {
+  'revoked_codes' : bigint,
+  'grey_market_verifications' : bigint,
   'total_products' : bigint,
+  'unauthorized_reseller_product_checks' : bigint,
   'active_resellers' : bigint,
   'verifications_this_month' : bigint,
+  'registered_owners' : bigint,
+  'revoked_code_scans' : bigint,
+  'reseller_product_checks' : bigint,
+  'last_refreshed' : bigint,
 }
 export interface OrganizationContextResponse {
   'user_auth_context' : AuthContextResponse,
@@ -194,7 +1842,36 @@ export interface OrganizationsListResponse {
   'pagination' : [] | [PaginationResponse],
   'organizations' : Array<OrganizationPublic>,
 }
+export interface OutboxDeliveriesResponse {
+  'deliveries' : Array<OutboxDelivery>,
+  'pagination' : [] | [PaginationResponse],
+}
+export interface OutboxDelivery {
+  'id' : bigint,
+  'url' : string,
+  'last_error' : [] | [string],
+  'status' : OutboxDeliveryStatus,
+  'updated_at' : bigint,
+  'body' : string,
+  'next_attempt_at' : bigint,
+  'org_id' : [] | [Principal],
+  'attempts' : number,
+  'created_at' : bigint,
+}
+export type OutboxDeliveryStatus = { 'DeadLettered' : null } |
+  { 'Pending' : null };
+export interface OutcallResponseLimits {
+  'scraper_max_response_bytes' : bigint,
+  'webhook_max_response_bytes' : bigint,
+  'openai_max_response_bytes' : bigint,
+}
+export interface OwnershipStatsResponse {
+  'total_registered' : bigint,
+  'org_id' : Principal,
+  'products' : Array<ProductOwnershipCount>,
+}
 export interface PaginationRequest {
+  'cursor' : [] | [string],
   'page' : [] | [number],
   'limit' : [] | [number],
 }
@@ -202,67 +1879,234 @@ export interface PaginationResponse {
   'total' : bigint,
   'page' : number,
   'limit' : number,
+  'next_cursor' : [] | [string],
   'has_more' : boolean,
 }
+export interface PenalizeUserRewardsRequest {
+  'org_id' : [] | [Principal],
+  'user_id' : Principal,
+  'points' : number,
+  'reason' : string,
+}
+export interface PendingRedemption {
+  'id' : Principal,
+  'transaction_id' : [] | [string],
+  'status' : RedemptionApprovalStatus,
+  'product_id' : Principal,
+  'org_id' : Principal,
+  'wallet_address' : string,
+  'user_id' : Principal,
+  'requested_at' : bigint,
+  'rejection_reason' : [] | [string],
+  'decided_at' : [] | [bigint],
+  'decided_by' : [] | [Principal],
+  'serial_no' : Principal,
+  'points' : number,
+  'verification_id' : Principal,
+}
+export type Permission = { 'ManageVerifications' : null } |
+  { 'ReadOrganization' : null } |
+  { 'ReadProduct' : null } |
+  { 'AdminAccess' : null } |
+  { 'ReadReseller' : null } |
+  { 'ReadSelf' : null } |
+  { 'ReadUser' : null } |
+  { 'WriteReseller' : null } |
+  { 'VerifyProduct' : null } |
+  { 'WriteProduct' : null } |
+  { 'RedeemRewards' : null } |
+  { 'WriteOrganization' : null } |
+  { 'WriteSelf' : null } |
+  { 'WriteUser' : null };
+export interface PlanLimits {
+  'max_products' : [] | [bigint],
+  'analytics_retention_days' : number,
+  'max_serials_per_month' : number,
+  'max_webhooks' : [] | [number],
+}
+export type PlanStatus = { 'Active' : null } |
+  { 'PastDue' : null } |
+  { 'Cancelled' : null };
+export type PlanTier = { 'Pro' : null } |
+  { 'Enterprise' : null } |
+  { 'Free' : null };
+export interface PlanUsageResponse {
+  'serials_this_month' : number,
+  'plan' : OrgPlan,
+  'webhooks' : number,
+  'serials_window_resets_at' : bigint,
+  'effective_tier' : PlanTier,
+  'serials_limit' : number,
+  'products' : bigint,
+  'limits' : PlanLimits,
+}
+export interface PlatformMetricsResponse {
+  'generated_at' : bigint,
+  'cycles' : CyclesStatus,
+  'users' : bigint,
+  'products' : bigint,
+  'organizations' : bigint,
+}
+export interface PostVerificationMessage {
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'product_id' : [] | [Principal],
+  'org_id' : Principal,
+  'cta_url' : [] | [string],
+  'cta_label' : [] | [string],
+  'template' : string,
+  'is_active' : boolean,
+}
+export interface PreapproveResellersResponse {
+  'added' : number,
+  'updated' : number,
+  'certified' : number,
+}
+export interface PreapprovedResellerRow { 'name' : string, 'email' : string }
+export type PrintCodePolicy = { 'UnprintedOnly' : null } |
+  { 'ReprintAllowed' : null };
+export interface PrintJob {
+  'id' : Principal,
+  'printer_metadata' : Array<Metadata>,
+  'product_id' : Principal,
+  'label_template_version' : [] | [string],
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'entries' : Array<PrintJobEntry>,
+  'activation_time' : [] | [bigint],
+}
+export interface PrintJobEntry {
+  'print_version' : number,
+  'serial_no' : Principal,
+}
 export type PrivateKeyResult = { 'key' : string } |
   { 'error' : ApiError };
+export interface ProbeRecord {
+  'principal' : Principal,
+  'window_start' : bigint,
+  'misses' : number,
+  'ban_count' : number,
+  'probes' : number,
+  'banned_until' : [] | [bigint],
+}
 export interface Product {
   'id' : Principal,
+  'sku' : [] | [string],
+  'is_test' : [] | [boolean],
+  'warranty_days' : [] | [number],
   'updated_at' : bigint,
   'updated_by' : Principal,
   'public_key' : string,
+  'challenge_required' : [] | [boolean],
   'metadata' : Array<Metadata>,
   'name' : string,
   'org_id' : Principal,
+  'allowed_markets' : [] | [Array<string>],
   'description' : string,
+  'discontinued_at' : [] | [bigint],
   'created_at' : bigint,
   'created_by' : Principal,
+  'sequential_serials' : [] | [boolean],
+  'product_number' : [] | [bigint],
+  'successor_product_id' : [] | [Principal],
   'category' : string,
+  'recall' : [] | [ProductRecall],
 }
 export interface ProductInput {
+  'sku' : [] | [string],
   'metadata' : Array<Metadata>,
   'name' : string,
   'org_id' : Principal,
   'description' : string,
   'category' : string,
 }
+export interface ProductOwnershipCount {
+  'product_id' : Principal,
+  'registered_owners' : bigint,
+  'product_name' : string,
+}
+export interface ProductRecall {
+  'issued_at' : bigint,
+  'issued_by' : Principal,
+  'instructions' : [] | [string],
+  'reason' : string,
+}
 export interface ProductResponse { 'product' : Product }
 export type ProductResult = { 'none' : null } |
   { 'error' : ApiError } |
   { 'product' : Product };
+export interface ProductRewardStats {
+  'redemption_count' : bigint,
+  'updated_at' : bigint,
+  'product_id' : Principal,
+  'promotion_points_issued' : bigint,
+  'unique_rewarded_users' : bigint,
+  'points_issued' : bigint,
+  'points_redeemed' : bigint,
+}
 export interface ProductSerialNumber {
+  'status' : [] | [SerialNumberStatus],
+  'revoked_codes' : [] | [Array<CodeRevocation>],
   'updated_at' : bigint,
   'updated_by' : Principal,
   'product_id' : Principal,
   'metadata' : Array<Metadata>,
+  'last_print_job_id' : [] | [Principal],
   'created_at' : bigint,
   'created_by' : Principal,
+  'suspected_cloned' : [] | [boolean],
+  'variant_id' : [] | [Principal],
   'print_version' : number,
+  'sequence_no' : [] | [bigint],
+  'user_serial_no' : [] | [string],
   'serial_no' : Principal,
 }
 export type ProductSerialNumberResult = { 'result' : ProductSerialNumber } |
   { 'error' : ApiError };
+export interface ProductSerialNumbersListResponse {
+  'pagination' : [] | [PaginationResponse],
+  'serial_numbers' : Array<ProductSerialNumber>,
+}
+export interface ProductSuccessor { 'product_id' : Principal, 'name' : string }
 export type ProductUniqueCodeResult = {
     'result' : ProductUniqueCodeResultRecord
   } |
   { 'error' : ApiError };
 export interface ProductUniqueCodeResultRecord {
+  'verification_url' : [] | [string],
   'product_id' : Principal,
   'created_at' : bigint,
   'print_version' : number,
   'unique_code' : string,
   'serial_no' : Principal,
 }
+export interface ProductVariant {
+  'id' : Principal,
+  'sku' : [] | [string],
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'product_id' : Principal,
+  'name' : string,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'attributes' : Array<Metadata>,
+}
 export interface ProductVerification {
   'id' : Principal,
   'status' : ProductVerificationStatus,
+  'is_test' : [] | [boolean],
   'product_id' : Principal,
   'reward_claimed' : boolean,
   'metadata' : Array<Metadata>,
   'reward_transaction_id' : [] | [string],
   'created_at' : bigint,
   'created_by' : Principal,
+  'variant_id' : [] | [Principal],
   'print_version' : number,
+  'annotations' : [] | [Array<VerificationAnnotation>],
+  'country_code' : [] | [string],
   'serial_no' : Principal,
 }
 export interface ProductVerificationDetail {
@@ -274,28 +2118,259 @@ export interface ProductVerificationDetail {
   'serial_no' : Principal,
 }
 export interface ProductVerificationEnhancedResponse {
+  'first_scanned_at' : [] | [bigint],
+  'successor' : [] | [ProductSuccessor],
   'status' : ProductVerificationStatus,
+  'is_test' : boolean,
+  'warning' : [] | [string],
+  'authenticity' : [] | [AuthenticityScore],
+  'discontinued' : boolean,
+  'federation' : [] | [FederatedVerification],
+  'rate_limit' : [] | [RateLimitInfo],
+  'custody_status' : [] | [SerialNumberStatus],
   'expiration' : [] | [bigint],
+  'annotations' : Array<VerificationAnnotation>,
+  'revocation_reason' : [] | [CodeRevocationReason],
+  'message' : [] | [RenderedVerificationMessage],
   'rewards' : [] | [VerificationRewards],
+  'scan_count' : [] | [number],
+  'status_message' : string,
+  'bundle' : [] | [BundleSummary],
+  'variant' : [] | [ProductVariant],
   'verification' : [] | [ProductVerification],
 }
-export type ProductVerificationStatus = { 'Invalid' : null } |
-  { 'MultipleVerification' : null } |
-  { 'FirstVerification' : null };
-export interface RateLimitInfo {
-  'current_window_start' : bigint,
-  'remaining_attempts' : number,
-  'reset_time' : bigint,
+export type ProductVerificationStatus = { 'CodeRevoked' : null } |
+  { 'Invalid' : null } |
+  { 'NotYetActive' : null } |
+  { 'MultipleVerification' : null } |
+  { 'FirstVerification' : null } |
+  { 'VoidedSerial' : null } |
+  { 'SupersededCode' : null };
+export interface ProductVerificationsListResponse {
+  'pagination' : [] | [PaginationResponse],
+  'verifications' : Array<ProductVerification>,
+}
+export interface ProductsListResponse {
+  'pagination' : [] | [PaginationResponse],
+  'products' : Array<Product>,
+}
+export interface ProposeAdminOperationRequest {
+  'kind' : AdminOperationKind,
+  'reason' : [] | [string],
+}
+export interface PublicBrand {
+  'website_url' : [] | [string],
+  'featured' : boolean,
+  'tagline' : [] | [string],
+  'product_count' : bigint,
+  'logo_url' : [] | [string],
+  'badge' : VerifiedBrandBadge,
+  'organization' : OrganizationPublic,
+}
+export interface PublicBrandStats {
+  'countries_reached' : bigint,
+  'published_at' : bigint,
+  'version' : bigint,
+  'distinct_products' : bigint,
+  'total_verifications' : bigint,
+}
+export interface PublicBrandStatsResponse {
+  'org_id' : Principal,
+  'max_age_seconds' : bigint,
+  'version' : bigint,
+  'stats' : [] | [PublicBrandStats],
+  'not_modified' : boolean,
+}
+export interface PublicBrandsListResponse {
+  'brands' : Array<PublicBrand>,
+  'pagination' : PaginationResponse,
+}
+export interface PublishTermsRequest { 'url' : string, 'version' : string }
+export type QrErrorCorrection = { 'H' : null } |
+  { 'L' : null } |
+  { 'M' : null } |
+  { 'Q' : null };
+export interface QrPlacement {
+  'size_mm' : number,
+  'x_mm' : number,
+  'y_mm' : number,
+}
+export interface QuarantinedEntry {
+  'key' : Principal,
+  'sub_key' : [] | [IntegritySubKey],
+  'store' : IntegrityStore,
+  'decode_error' : string,
+  'bytes' : Uint8Array | number[],
+  'quarantined_at' : bigint,
+  'quarantined_by' : Principal,
+}
+export interface RateLimitConfig {
+  'max_attempts_per_window' : number,
+  'window_duration_seconds' : bigint,
+}
+export interface RateLimitInfo {
+  'current_window_start' : bigint,
+  'remaining_attempts' : number,
+  'reset_time' : bigint,
+  'locked_until' : [] | [bigint],
+  'penalty_level' : number,
+}
+export interface ReadonlyPrincipal {
+  'principal' : Principal,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'replaced_by' : [] | [Principal],
+  'label' : [] | [string],
+  'revoked_at' : [] | [bigint],
+  'revoked_by' : [] | [Principal],
+}
+export interface RecallCampaign {
+  'id' : Principal,
+  'status' : RecallCampaignStatus,
+  'updated_at' : bigint,
+  'closed_at' : [] | [bigint],
+  'closed_by' : [] | [Principal],
+  'product_id' : Principal,
+  'notices_sent' : bigint,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'scope' : RecallScope,
+  'affected_owners' : bigint,
+  'recall' : ProductRecall,
+}
+export type RecallCampaignStatus = { 'Closed' : null } |
+  { 'Active' : null } |
+  { 'Sending' : null };
+export interface RecallCampaignStatusResponse {
+  'campaign' : RecallCampaign,
+  'notices_sent' : bigint,
+  'relay_skipped' : bigint,
+  'inbox_read' : bigint,
+  'relay_queued' : bigint,
+  'relay_delivered' : bigint,
+  'relay_failed' : bigint,
+  'owners_reached' : bigint,
+  'reach_percent' : number,
+}
+export type RecallScope = { 'PrintJob' : { 'print_job_id' : Principal } } |
+  { 'Product' : null } |
+  { 'Serials' : { 'serial_nos' : Array<Principal> } };
+export interface RedeemRewardRequest {
+  'redeem_as_coupon' : [] | [boolean],
+  'wallet_address' : string,
+  'unique_code' : string,
+  'wallet_id' : [] | [Principal],
+  'serial_no' : Principal,
+}
+export interface RedeemRewardResponse {
+  'transaction_id' : [] | [string],
+  'pending_redemption_id' : [] | [Principal],
+  'message' : string,
+  'success' : boolean,
+  'coupon' : [] | [RedeemedCoupon],
+}
+export interface RedeemedCoupon {
+  'code' : string,
+  'description' : [] | [string],
+}
+export type RedemptionApprovalStatus = { 'Approved' : null } |
+  { 'Rejected' : null } |
+  { 'Pending' : null };
+export interface RegisterVerificationDomainRequest {
+  'domain' : string,
+  'org_id' : Principal,
+}
+export interface RejectRedemptionRequest {
+  'redemption_id' : Principal,
+  'reason' : [] | [string],
+}
+export interface RejectResellerApplicationRequest {
+  'application_id' : Principal,
+  'reason' : string,
+}
+export interface RemoveStatusMessageRequest {
+  'status' : ProductVerificationStatus,
+  'locale' : string,
+}
+export interface RenderedVerificationMessage {
+  'text' : string,
+  'cta_url' : [] | [string],
+  'cta_label' : [] | [string],
+}
+export interface RenewResellerCertificationRequest {
+  'ecommerce_urls' : Array<Metadata>,
+  'additional_metadata' : [] | [Array<Metadata>],
+  'contact_email' : [] | [string],
+  'contact_phone' : [] | [string],
+  'reseller_name' : string,
+}
+export interface Report {
+  'id' : Principal,
+  'period_end' : bigint,
+  'generated_at' : bigint,
+  'suspicious_serials' : bigint,
+  'org_id' : Principal,
+  'period_start' : bigint,
+  'delivery_error' : [] | [string],
+  'frequency' : ReportFrequency,
+  'invalid_verifications' : bigint,
+  'top_products' : Array<ReportProductStat>,
+  'first_verifications' : bigint,
+  'delivered_at' : [] | [bigint],
+  'multiple_verifications' : bigint,
+  'total_verifications' : bigint,
+  'schedule_id' : Principal,
+}
+export interface ReportFilters {
+  'period_end' : [] | [bigint],
+  'period_start' : [] | [bigint],
+  'product_ids' : [] | [Array<Principal>],
+}
+export type ReportFrequency = { 'Weekly' : null } |
+  { 'Monthly' : null };
+export interface ReportProductStat {
+  'product_id' : Principal,
+  'product_name' : string,
+  'verification_count' : bigint,
+}
+export interface ReportSchedule {
+  'id' : Principal,
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'recipient_emails' : Array<string>,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'delivery_url' : string,
+  'last_run_at' : [] | [bigint],
+  'next_run_at' : bigint,
+  'is_active' : boolean,
+  'frequency' : ReportFrequency,
+}
+export interface ReportScheduleResponse { 'schedule' : ReportSchedule }
+export interface ReportShareLink {
+  'id' : Principal,
+  'filters' : ReportFilters,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'revoked_at' : [] | [bigint],
+  'expires_at' : bigint,
+  'token_hash' : string,
+}
+export interface ReportShareLinkResponse {
+  'token' : string,
+  'link' : ReportShareLink,
 }
-export interface RedeemRewardRequest {
-  'wallet_address' : string,
-  'unique_code' : string,
-  'serial_no' : Principal,
+export interface ReportsListResponse {
+  'pagination' : PaginationResponse,
+  'reports' : Array<Report>,
 }
-export interface RedeemRewardResponse {
-  'transaction_id' : [] | [string],
-  'message' : string,
-  'success' : boolean,
+export interface RequestKeyEscrowExportRequest {
+  'recipient_public_key' : string,
+  'org_id' : Principal,
+  'reason' : [] | [string],
 }
 export interface Reseller {
   'id' : Principal,
@@ -309,6 +2384,7 @@ export interface Reseller {
   'org_id' : Principal,
   'contact_email' : [] | [string],
   'certification_code' : [] | [string],
+  'certification_expires_at' : [] | [bigint],
   'certification_timestamp' : [] | [bigint],
   'date_joined' : bigint,
   'created_at' : bigint,
@@ -316,9 +2392,26 @@ export interface Reseller {
   'user_id' : Principal,
   'is_verified' : boolean,
   'contact_phone' : [] | [string],
+  'email_verified' : [] | [boolean],
+}
+export interface ResellerApplication {
+  'id' : Principal,
+  'status' : RedemptionApprovalStatus,
+  'org_id' : Principal,
+  'user_id' : Principal,
+  'reseller_id' : Principal,
+  'rejection_reason' : [] | [string],
+  'decided_at' : [] | [bigint],
+  'decided_by' : [] | [Principal],
+  'submitted_at' : bigint,
+}
+export interface ResellerApplicationDetail {
+  'application' : ResellerApplication,
+  'reseller' : [] | [Reseller],
 }
 export interface ResellerCertificationPageContext {
   'certification_code' : string,
+  'certification_expires_at' : [] | [bigint],
   'certification_timestamp' : bigint,
   'reseller_profile' : ResellerPublic,
   'user_details' : UserPublic,
@@ -326,6 +2419,7 @@ export interface ResellerCertificationPageContext {
 }
 export interface ResellerContextDetails {
   'certification_code' : [] | [string],
+  'certification_expires_at' : [] | [bigint],
   'certification_timestamp' : [] | [bigint],
   'associated_organization' : [] | [OrganizationPublic],
   'is_profile_complete_and_verified' : boolean,
@@ -336,6 +2430,21 @@ export interface ResellerInput {
   'name' : string,
   'org_id' : Principal,
 }
+export interface ResellerPreapproval {
+  'name' : string,
+  'org_id' : Principal,
+  'used_at' : [] | [bigint],
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'email' : string,
+  'used_by_reseller_id' : [] | [Principal],
+}
+export interface ResellerProductsResponse {
+  'updated_at' : [] | [bigint],
+  'product_ids' : Array<Principal>,
+  'restricted' : boolean,
+  'reseller_id' : Principal,
+}
 export interface ResellerPublic {
   'id' : Principal,
   'updated_at' : bigint,
@@ -345,6 +2454,7 @@ export interface ResellerPublic {
   'name' : string,
   'contact_email' : [] | [string],
   'certification_code' : [] | [string],
+  'certification_expires_at' : [] | [bigint],
   'certification_timestamp' : [] | [bigint],
   'created_at' : bigint,
   'user_id' : Principal,
@@ -362,8 +2472,10 @@ export interface ResellerVerificationResponse {
   'status' : ResellerVerificationStatus,
   'reseller' : [] | [Reseller],
   'organization' : [] | [OrganizationPublic],
+  'product_authorized' : [] | [boolean],
 }
 export type ResellerVerificationStatus = { 'ExpiredCode' : null } |
+  { 'CertificationExpired' : null } |
   { 'Success' : null } |
   { 'ReplayAttackDetected' : null } |
   { 'InvalidCode' : null } |
@@ -373,21 +2485,340 @@ export type ResellerVerificationStatus = { 'ExpiredCode' : null } |
 export interface ResetStorageResponse { 'message' : string }
 export interface ResponseMetadata {
   'request_id' : [] | [string],
+  'rate_limit' : [] | [RateLimitInfo],
   'version' : string,
   'timestamp' : bigint,
 }
 export type Result = { 'Ok' : Array<ProductSerialNumber> } |
   { 'Err' : ApiError };
+export type RetiredVerificationHandling = { 'Archive' : null } |
+  { 'Delete' : null };
+export interface ReviewModerationCaseRequest {
+  'note' : [] | [string],
+  'approve' : boolean,
+  'entity_id' : Principal,
+}
+export interface RewardPenaltiesListResponse {
+  'pagination' : PaginationResponse,
+  'penalties' : Array<RewardPenalty>,
+}
+export interface RewardPenalty {
+  'id' : Principal,
+  'balance_after' : number,
+  'org_id' : [] | [Principal],
+  'created_at' : bigint,
+  'user_id' : Principal,
+  'penalized_by' : Principal,
+  'points_requested' : number,
+  'points_deducted' : number,
+  'reason' : string,
+}
+export interface RewardPool {
+  'updated_at' : bigint,
+  'total_paid_out' : bigint,
+  'ledger_balance_seen' : bigint,
+  'balance' : bigint,
+  'total_deposited' : bigint,
+  'org_id' : Principal,
+  'units_per_point' : bigint,
+  'low_balance_threshold' : bigint,
+  'low_balance_alerted' : boolean,
+}
+export interface RewardPoolBalanceResponse {
+  'pool' : RewardPool,
+  'deposit_account_owner' : Principal,
+  'points_covered' : bigint,
+  'deposit_subaccount' : Uint8Array | number[],
+  'redemptions_require_pool' : boolean,
+}
+export interface SavedWallet {
+  'id' : Principal,
+  'chain' : WalletChain,
+  'created_at' : bigint,
+  'label' : string,
+  'is_default' : boolean,
+  'address' : string,
+}
+export interface SearchProductsRequest {
+  'pagination' : [] | [PaginationRequest],
+  'org_id' : Principal,
+  'query' : [] | [string],
+}
+export interface SerialBatchDefaults {
+  'user_serial_prefix' : [] | [string],
+  'batch_size' : [] | [number],
+  'code_policy' : [] | [PrintCodePolicy],
+  'activation_delay_seconds' : [] | [bigint],
+  'label_template_product_id' : [] | [Principal],
+}
+export type SerialNumberStatus = { 'Sold' : null } |
+  { 'Void' : null } |
+  { 'Printed' : null } |
+  { 'Shipped' : null } |
+  { 'Created' : null };
+export interface SerialNumbersBatchResponse {
+  'serial_numbers' : Array<ProductSerialNumber>,
+}
+export interface SerialPrintHistoryEntry {
+  'printed_at' : [] | [bigint],
+  'printed_by' : [] | [Principal],
+  'print_job_id' : [] | [Principal],
+  'print_version' : number,
+  'is_current' : boolean,
+  'revocation' : [] | [CodeRevocation],
+}
+export interface SerialPrintHistoryResponse {
+  'current_print_version' : number,
+  'product_id' : Principal,
+  'versions' : Array<SerialPrintHistoryEntry>,
+  'serial_no' : Principal,
+}
+export interface SerialRangeStatsResponse {
+  'last_scan_at' : [] | [bigint],
+  'product_id' : Principal,
+  'coverage_percent' : number,
+  'to_seq' : bigint,
+  'from_seq' : bigint,
+  'serials_in_range' : bigint,
+  'scanned_serials' : bigint,
+  'first_scan_at' : [] | [bigint],
+  'total_verifications' : bigint,
+}
+export interface SerialStatusCheckResponse {
+  'status' : [] | [SerialNumberStatus],
+  'product_id' : [] | [Principal],
+  'discontinued' : boolean,
+  'org_id' : [] | [Principal],
+  'voided' : boolean,
+  'org_name' : [] | [string],
+  'suspected_cloned' : boolean,
+  'product_name' : [] | [string],
+  'print_version' : [] | [number],
+  'exists' : boolean,
+  'serial_no' : Principal,
+  'code_revoked' : boolean,
+}
+export interface SetBiExportConfigRequest {
+  'org_id' : Principal,
+  'endpoint_url' : string,
+  'is_active' : boolean,
+}
+export interface SetFeatureFlagRequest {
+  'flag' : string,
+  'org_id' : [] | [Principal],
+  'enabled' : boolean,
+}
+export interface SetLabelTemplateRequest {
+  'product_id' : Principal,
+  'qr_error_correction' : QrErrorCorrection,
+  'qr_version' : [] | [number],
+  'height_mm' : number,
+  'width_mm' : number,
+  'show_human_readable_code' : boolean,
+  'artwork_asset_url' : [] | [string],
+  'qr_placement' : QrPlacement,
+}
+export interface SetMetadataSchemaRequest {
+  'org_id' : Principal,
+  'product_fields' : Array<MetadataFieldSchema>,
+  'serial_fields' : Array<MetadataFieldSchema>,
+  'category' : string,
+}
+export interface SetOrgQuotaRequest {
+  'max_storage_bytes' : [] | [bigint],
+  'max_outcalls_per_day' : [] | [number],
+  'org_id' : Principal,
+  'max_serials_per_month' : [] | [number],
+}
+export interface SetPostVerificationMessageRequest {
+  'product_id' : [] | [Principal],
+  'org_id' : Principal,
+  'cta_url' : [] | [string],
+  'cta_label' : [] | [string],
+  'template' : string,
+  'is_active' : boolean,
+}
+export interface SetProductRecallRequest {
+  'product_id' : Principal,
+  'instructions' : [] | [string],
+  'reason' : string,
+}
+export interface SetRewardPoolSettingsRequest {
+  'org_id' : Principal,
+  'units_per_point' : [] | [bigint],
+  'low_balance_threshold' : [] | [bigint],
+}
+export interface SetSerialMetadataRequest {
+  'product_id' : Principal,
+  'metadata' : Array<Metadata>,
+  'serial_no' : Principal,
+}
+export interface SetStatusMessageRequest {
+  'status' : ProductVerificationStatus,
+  'text' : string,
+  'locale' : string,
+}
+export interface SetVerificationRetentionRequest {
+  'org_id' : Principal,
+  'handling' : RetiredVerificationHandling,
+  'retention_months' : [] | [number],
+}
+export interface SharedReportResponse {
+  'filters' : ReportFilters,
+  'period_end' : bigint,
+  'generated_at' : bigint,
+  'suspicious_serials' : bigint,
+  'period_start' : bigint,
+  'organization_name' : string,
+  'invalid_verifications' : bigint,
+  'expires_at' : bigint,
+  'top_products' : Array<ReportProductStat>,
+  'first_verifications' : bigint,
+  'multiple_verifications' : bigint,
+  'total_verifications' : bigint,
+}
+export interface SignPayloadResponse {
+  'signature' : string,
+  'public_key' : string,
+  'signed_at' : bigint,
+}
+export interface SimulateVerificationRequest {
+  'product_id' : Principal,
+  'locale' : [] | [string],
+  'scenario' : VerificationScenario,
+}
+export interface StartRecallCampaignRequest {
+  'product_id' : Principal,
+  'instructions' : [] | [string],
+  'scope' : RecallScope,
+  'reason' : string,
+}
+export interface StartVerificationChallengeRequest {
+  'client_version' : [] | [string],
+  'guest_token' : [] | [string],
+  'serial_no' : Principal,
+}
+export interface StatusDescriptionResponse {
+  'status' : ProductVerificationStatus,
+  'locale' : string,
+  'message' : string,
+}
+export interface StatusMessage {
+  'status' : ProductVerificationStatus,
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'text' : string,
+  'locale' : string,
+}
+export interface SubmitJobRequest { 'job' : JobRequest, 'org_id' : Principal }
+export interface SuspiciousActivityResponse {
+  'probe_activity' : [] | [OrgProbeActivity],
+  'pagination' : [] | [PaginationResponse],
+  'suspected_cloned_serials' : Array<ProductSerialNumber>,
+}
+export interface TermsAcceptance {
+  'accepted_at' : bigint,
+  'user_id' : Principal,
+  'version' : string,
+  'terms_seq' : bigint,
+}
+export interface TermsStatusResponse {
+  'acceptances' : Array<TermsAcceptance>,
+  'accepted' : boolean,
+  'current' : [] | [TermsVersion],
+}
+export interface TermsVersion {
+  'seq' : bigint,
+  'url' : string,
+  'published_at' : bigint,
+  'published_by' : Principal,
+  'version' : string,
+}
 export interface TransformArgs {
   'context' : Uint8Array | number[],
   'response' : HttpResponse,
 }
+export interface UnclaimedReward {
+  'product_id' : Principal,
+  'product_name' : string,
+  'verified_at' : bigint,
+  'serial_no' : Principal,
+  'verification_id' : Principal,
+}
+export interface UpdateCanisterConfigRequest {
+  'llm_api_host' : [] | [string],
+  'outcall_limits' : [] | [OutcallResponseLimits],
+  'client_version_policy' : [] | [ClientVersionPolicy],
+  'rate_limit' : [] | [RateLimitConfig],
+  'scraper_url' : [] | [string],
+  'llm_provider' : [] | [string],
+  'email_relay_url' : [] | [string],
+  'live_gateways' : [] | [Array<Principal>],
+  'cycles_alerts' : [] | [CyclesAlertConfig],
+  'ledger_canister_id' : [] | [Principal],
+  'llm_api_key' : [] | [string],
+  'moderation' : [] | [ModerationConfig],
+  'llm_model' : [] | [string],
+  'feature_flags' : [] | [Array<FeatureFlag>],
+}
+export interface UpdateDirectoryListingRequest {
+  'is_public' : [] | [boolean],
+  'website_url' : [] | [string],
+  'tagline' : [] | [string],
+  'org_id' : Principal,
+  'logo_url' : [] | [string],
+}
+export interface UpdateDisputeStatusRequest {
+  'status' : DisputeStatus,
+  'dispute_id' : Principal,
+  'resolution' : [] | [string],
+}
+export interface UpdateOrgSettingsRequest {
+  'serial_batch_defaults' : [] | [SerialBatchDefaults],
+  'duplicate_scan_threshold' : [] | [number],
+  'client_version_policy' : [] | [ClientVersionPolicy],
+  'org_id' : Principal,
+  'certification_grace_period_seconds' : [] | [bigint],
+  'public_stats_enabled' : [] | [boolean],
+  'redemption_approval_threshold' : [] | [number],
+  'single_use_reseller_codes' : [] | [boolean],
+  'certification_validity_seconds' : [] | [bigint],
+  'verification_privacy' : [] | [VerificationPrivacyPolicy],
+  'authenticity_weights' : [] | [AuthenticityWeights],
+}
 export interface UpdateOrganizationRequest {
   'id' : Principal,
   'metadata' : Array<Metadata>,
   'name' : string,
   'description' : string,
 }
+export interface UpdateProductVariantRequest {
+  'sku' : [] | [string],
+  'name' : [] | [string],
+  'variant_id' : Principal,
+  'attributes' : [] | [Array<Metadata>],
+}
+export interface UpdateResellerProductsRequest {
+  'product_ids' : Array<Principal>,
+  'reseller_id' : Principal,
+}
+export interface UpdateSerialStatusRequest {
+  'product_id' : Principal,
+  'serial_nos' : Array<Principal>,
+  'capability_token' : [] | [string],
+  'reason' : [] | [string],
+}
+export interface UploadCouponCodesRequest {
+  'product_id' : [] | [Principal],
+  'org_id' : Principal,
+  'codes' : Array<string>,
+  'description' : [] | [string],
+}
+export interface UploadCouponCodesResponse {
+  'added' : number,
+  'skipped_duplicates' : number,
+  'available' : bigint,
+}
 export interface User {
   'id' : Principal,
   'updated_at' : bigint,
@@ -413,6 +2844,34 @@ export interface UserDetailsInput {
   'last_name' : string,
   'phone_no' : string,
 }
+export interface UserListFilter {
+  'org_id' : [] | [Principal],
+  'role' : [] | [UserRole],
+  'is_enabled' : [] | [boolean],
+  'query' : [] | [string],
+}
+export interface UserNotification {
+  'id' : Principal,
+  'read_at' : [] | [bigint],
+  'title' : string,
+  'reference_id' : [] | [Principal],
+  'kind' : UserNotificationKind,
+  'created_at' : bigint,
+  'user_id' : Principal,
+  'message' : string,
+}
+export type UserNotificationKind = { 'RewardsPenalized' : null } |
+  { 'RedemptionPending' : null } |
+  { 'ResellerApplicationRejected' : null } |
+  { 'ProductRecalled' : null } |
+  { 'RewardGranted' : null } |
+  { 'RedemptionApproved' : null } |
+  { 'DisputeUpdated' : null } |
+  { 'CertificationIssued' : null } |
+  { 'CertificationRenewed' : null } |
+  { 'RedemptionRejected' : null } |
+  { 'KeyEscrowReady' : null } |
+  { 'RewardPoolLow' : null };
 export interface UserPublic {
   'id' : Principal,
   'created_at' : bigint,
@@ -428,120 +2887,766 @@ export type UserRole = { 'Customer' : null } |
   { 'Reseller' : null } |
   { 'Admin' : null } |
   { 'BrandOwner' : null };
+export interface UsersListResponse {
+  'pagination' : PaginationResponse,
+  'users' : Array<User>,
+}
+export interface ValidationError { 'field' : string, 'message' : string }
+export type VerificationAnnotation = { 'GreyMarketSuspected' : null };
+export interface VerificationChallengeResponse {
+  'product_id' : Principal,
+  'nonce' : string,
+  'challenge_id' : Principal,
+  'expires_at' : bigint,
+}
+export type VerificationChallengeStatus = { 'CodeRevoked' : null } |
+  { 'NotYetActive' : null } |
+  { 'InvalidResponse' : null } |
+  { 'VoidedSerial' : null } |
+  { 'Verified' : null } |
+  { 'Expired' : null } |
+  { 'Pending' : null };
+export interface VerificationDelegation {
+  'status' : VerificationDelegationStatus,
+  'brand_org_id' : Principal,
+  'product_id' : Principal,
+  'manufacturer_org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'revoked_at' : [] | [bigint],
+  'revoked_by' : [] | [Principal],
+}
+export type VerificationDelegationStatus = { 'Active' : null } |
+  { 'Revoked' : null };
+export interface VerificationDomain {
+  'last_error' : [] | [string],
+  'status' : VerificationDomainStatus,
+  'updated_at' : bigint,
+  'domain' : string,
+  'org_id' : Principal,
+  'created_at' : bigint,
+  'created_by' : Principal,
+  'last_checked_at' : [] | [bigint],
+  'verified_at' : [] | [bigint],
+  'proof_token' : string,
+}
+export interface VerificationDomainResponse {
+  'domain' : VerificationDomain,
+  'proof_record_name' : string,
+  'proof_record_value' : string,
+}
+export type VerificationDomainStatus = { 'Failed' : null } |
+  { 'Verified' : null } |
+  { 'Pending' : null };
+export type VerificationHeatmapKind = { 'All' : null } |
+  { 'InvalidOnly' : null } |
+  { 'FirstOnly' : null };
+export interface VerificationHeatmapRange {
+  'period_end' : [] | [bigint],
+  'kind' : [] | [VerificationHeatmapKind],
+  'period_start' : [] | [bigint],
+  'utc_offset_hours' : [] | [number],
+}
+export interface VerificationHeatmapResponse {
+  'total' : bigint,
+  'period_end' : bigint,
+  'kind' : VerificationHeatmapKind,
+  'period_start' : bigint,
+  'utc_offset_hours' : number,
+  'counts' : Array<BigUint64Array | bigint[]>,
+}
+export interface VerificationMonthlyAggregate {
+  'month' : number,
+  'updated_at' : bigint,
+  'product_id' : Principal,
+  'org_id' : Principal,
+  'invalid_verifications' : bigint,
+  'first_verifications' : bigint,
+  'multiple_verifications' : bigint,
+  'total_verifications' : bigint,
+}
+export interface VerificationPrivacyPolicy {
+  'show_custody_chain' : boolean,
+  'show_reseller_info' : boolean,
+  'show_scan_count' : boolean,
+  'show_first_scan_date' : boolean,
+}
+export interface VerificationRetentionPolicy {
+  'updated_at' : bigint,
+  'updated_by' : Principal,
+  'org_id' : Principal,
+  'handling' : RetiredVerificationHandling,
+  'retention_months' : [] | [number],
+}
 export interface VerificationRewards {
   'special_reward' : [] | [string],
   'reward_description' : [] | [string],
   'is_first_verification' : boolean,
   'points' : number,
 }
+export interface VerificationRollupResult {
+  'deleted' : bigint,
+  'complete' : boolean,
+  'rolled_up' : bigint,
+  'archived' : bigint,
+}
+export type VerificationScenario = { 'CodeRevoked' : null } |
+  { 'Invalid' : null } |
+  { 'Discontinued' : null } |
+  { 'NotYetActive' : null } |
+  { 'MultipleVerification' : null } |
+  { 'GreyMarket' : null } |
+  { 'SuspectedClone' : null } |
+  { 'FirstVerification' : null } |
+  { 'VoidedSerial' : null } |
+  { 'SupersededCode' : null };
+export interface VerifiedBrandBadge {
+  'signature' : string,
+  'issued_at' : bigint,
+  'public_key' : string,
+  'org_id' : Principal,
+}
 export interface VerifyProductEnhancedRequest {
+  'locale' : [] | [string],
+  'client_version' : [] | [string],
+  'guest_token' : [] | [string],
+  'country_code' : [] | [string],
   'unique_code' : string,
   'serial_no' : Principal,
 }
 export interface VerifyResellerRequest {
   'context' : [] | [string],
+  'product_id' : [] | [Principal],
+  'client_version' : [] | [string],
   'unique_code' : string,
   'timestamp' : bigint,
   'reseller_id' : Principal,
 }
+export type WalletChain = { 'InternetComputer' : null } |
+  { 'Ethereum' : null } |
+  { 'Solana' : null } |
+  { 'Bitcoin' : null };
+export type WarrantyStatus = { 'Active' : null } |
+  { 'NotOffered' : null } |
+  { 'Expired' : null };
+export type WsClientMessage = { 'Ping' : null } |
+  { 'Subscribe' : Array<LiveTopic> };
+export interface WsGetMessagesResponse {
+  'next_seq' : bigint,
+  'messages' : Array<WsOutgoingMessage>,
+}
+export interface WsMessageRequest {
+  'message' : WsClientMessage,
+  'connection_id' : bigint,
+}
+export interface WsOpenRequest {
+  'org_id' : Principal,
+  'gateway' : Principal,
+  'topics' : Array<LiveTopic>,
+}
+export interface WsOpenResponse {
+  'idle_timeout_seconds' : bigint,
+  'topics' : Array<LiveTopic>,
+  'connection_id' : bigint,
+}
+export interface WsOutgoingMessage {
+  'seq' : bigint,
+  'client' : Principal,
+  'event' : LiveEvent,
+  'sent_at' : bigint,
+  'connection_id' : bigint,
+}
 export interface _SERVICE {
-  'check_reseller_verification' : ActorMethod<[Principal], ApiResponse>,
+  'accept_terms_v2' : ActorMethod<[string], ApiResponse>,
+  'activate_print_job_v2' : ActorMethod<[Principal], ApiResponse_1>,
+  'add_dispute_message_v2' : ActorMethod<
+    [AddDisputeMessageRequest],
+    ApiResponse_2
+  >,
+  'add_my_wallet_v2' : ActorMethod<[AddWalletRequest], ApiResponse_3>,
+  'add_to_my_products' : ActorMethod<[Principal], ApiResponse_4>,
+  'approve_admin_operation_v2' : ActorMethod<
+    [AdminOperationDecisionRequest],
+    ApiResponse_5
+  >,
+  'approve_org_key_escrow_export_v2' : ActorMethod<[Principal], ApiResponse_6>,
+  'approve_redemption_v2' : ActorMethod<[Principal], ApiResponse_7>,
+  'approve_reseller_application_v2' : ActorMethod<[Principal], ApiResponse_8>,
+  'cancel_job_v2' : ActorMethod<[Principal], ApiResponse_9>,
+  'cancel_org_key_escrow_export_v2' : ActorMethod<[Principal], ApiResponse_6>,
+  'change_org_plan_v2' : ActorMethod<[Principal, PlanTier], ApiResponse_10>,
+  'check_reseller_verification' : ActorMethod<[Principal], ApiResponse_11>,
+  'check_serial_status' : ActorMethod<[Principal], ApiResponse_12>,
+  'check_verification_domain_v2' : ActorMethod<[Principal], ApiResponse_13>,
+  'claim_guest_verifications' : ActorMethod<[string], ApiResponse_14>,
+  'clear_feature_flag_override_v2' : ActorMethod<
+    [string, Principal],
+    ApiResponse_15
+  >,
+  'clear_product_recall_v2' : ActorMethod<[Principal], ApiResponse_16>,
+  'clear_reseller_products_v2' : ActorMethod<[Principal], ApiResponse_17>,
+  'close_recall_campaign_v2' : ActorMethod<[Principal], ApiResponse_18>,
+  'complete_identity_link' : ActorMethod<[string], ApiResponse_19>,
   'complete_reseller_profile' : ActorMethod<
     [CompleteResellerProfileRequest],
-    ApiResponse_1
+    ApiResponse_20
+  >,
+  'complete_verification_challenge' : ActorMethod<
+    [CompleteVerificationChallengeRequest],
+    ApiResponse_21
+  >,
+  'confirm_reseller_email' : ActorMethod<[string], ApiResponse_22>,
+  'create_bundle_v2' : ActorMethod<[CreateBundleRequest], ApiResponse_23>,
+  'create_notification_rule_v2' : ActorMethod<
+    [CreateNotificationRuleRequest],
+    ApiResponse_24
   >,
   'create_organization' : ActorMethod<[OrganizationInput], OrganizationPublic>,
   'create_organization_for_owner' : ActorMethod<
     [OrganizationInput],
-    ApiResponse_2
+    ApiResponse_25
   >,
-  'create_organization_v2' : ActorMethod<[OrganizationInput], ApiResponse_3>,
+  'create_organization_v2' : ActorMethod<[OrganizationInput], ApiResponse_26>,
   'create_product' : ActorMethod<[ProductInput], ProductResult>,
   'create_product_serial_number' : ActorMethod<
     [Principal],
     ProductSerialNumberResult
   >,
+  'create_product_v2' : ActorMethod<[CreateProductRequest], ApiResponse_16>,
+  'create_product_variant_v2' : ActorMethod<
+    [CreateProductVariantRequest],
+    ApiResponse_27
+  >,
+  'create_readonly_principal_v2' : ActorMethod<
+    [Principal, Principal, [] | [string]],
+    ApiResponse_28
+  >,
+  'create_report_schedule_v2' : ActorMethod<
+    [CreateReportScheduleRequest],
+    ApiResponse_29
+  >,
+  'create_report_share_link_v2' : ActorMethod<
+    [CreateReportShareLinkRequest],
+    ApiResponse_30
+  >,
+  'create_serial_numbers_batch_v2' : ActorMethod<
+    [CreateSerialNumbersBatchRequest],
+    ApiResponse_31
+  >,
   'create_user' : ActorMethod<[Principal, UserDetailsInput], UserResult>,
+  'create_variant_serial_number_v2' : ActorMethod<[Principal], ApiResponse_32>,
+  'delegate_verification_v2' : ActorMethod<
+    [DelegateVerificationRequest],
+    ApiResponse_33
+  >,
+  'delete_label_template_v2' : ActorMethod<[Principal], ApiResponse_22>,
+  'delete_metadata_schema_v2' : ActorMethod<
+    [Principal, string],
+    ApiResponse_22
+  >,
+  'delete_notification_rule_v2' : ActorMethod<[Principal], ApiResponse_22>,
+  'delete_product_variant_v2' : ActorMethod<[Principal], ApiResponse_22>,
+  'delete_report_schedule_v2' : ActorMethod<[Principal], ApiResponse_22>,
+  'deposit_reward_pool_v2' : ActorMethod<
+    [DepositRewardPoolRequest],
+    ApiResponse_34
+  >,
+  'describe_status' : ActorMethod<
+    [ProductVerificationStatus, [] | [string]],
+    ApiResponse_35
+  >,
+  'disable_user_v2' : ActorMethod<[Principal], ApiResponse_36>,
+  'discontinue_product_v2' : ActorMethod<
+    [Principal, [] | [Principal]],
+    ApiResponse_16
+  >,
+  'enable_user_v2' : ActorMethod<[Principal], ApiResponse_36>,
+  'export_org_key_escrow' : ActorMethod<[Principal], ApiResponse_37>,
   'find_organizations_by_name' : ActorMethod<
     [string],
     Array<OrganizationPublic>
   >,
   'find_resellers_by_name_or_id' : ActorMethod<[string], Array<Reseller>>,
-  'generate_product_review_v2' : ActorMethod<[Principal], ApiResponse_4>,
+  'find_serial_by_user_serial' : ActorMethod<
+    [Principal, string],
+    ApiResponse_32
+  >,
+  'generate_product_review_v2' : ActorMethod<[Principal], ApiResponse_16>,
   'generate_reseller_unique_code_v2' : ActorMethod<
     [GenerateResellerUniqueCodeRequest],
-    ApiResponse_5
+    ApiResponse_38
+  >,
+  'get_archived_verification_aggregates_v2' : ActorMethod<
+    [ArchivedAggregatesQuery],
+    ApiResponse_39
+  >,
+  'get_auth_context' : ActorMethod<[], ApiResponse_20>,
+  'get_available_roles' : ActorMethod<[], ApiResponse_40>,
+  'get_bi_export_config_v2' : ActorMethod<[Principal], ApiResponse_41>,
+  'get_brand_dashboard_v2' : ActorMethod<[Principal], ApiResponse_42>,
+  'get_bundle_contents' : ActorMethod<[Principal], ApiResponse_23>,
+  'get_canister_config_v2' : ActorMethod<[], ApiResponse_43>,
+  'get_circuit_breakers_v2' : ActorMethod<[], ApiResponse_44>,
+  'get_cycles_history_v2' : ActorMethod<[[] | [number]], ApiResponse_45>,
+  'get_directory_listing_v2' : ActorMethod<[Principal], ApiResponse_46>,
+  'get_dispute_v2' : ActorMethod<[Principal], ApiResponse_2>,
+  'get_endpoint_metrics_v2' : ActorMethod<[], ApiResponse_47>,
+  'get_job_results_v2' : ActorMethod<[Principal, number], ApiResponse_48>,
+  'get_job_status_v2' : ActorMethod<[Principal], ApiResponse_9>,
+  'get_label_template_v2' : ActorMethod<[Principal], ApiResponse_49>,
+  'get_my_activity_v2' : ActorMethod<
+    [[] | [PaginationRequest]],
+    ApiResponse_50
   >,
-  'get_auth_context' : ActorMethod<[], ApiResponse_1>,
-  'get_available_roles' : ActorMethod<[], ApiResponse_6>,
-  'get_my_organizations' : ActorMethod<[], ApiResponse_7>,
-  'get_my_reseller_certification' : ActorMethod<[], ApiResponse_8>,
-  'get_navigation_context' : ActorMethod<[], ApiResponse_9>,
-  'get_openai_api_key' : ActorMethod<[], ApiResponse_10>,
+  'get_my_capabilities_v2' : ActorMethod<[], ApiResponse_51>,
+  'get_my_organizations' : ActorMethod<[], ApiResponse_52>,
+  'get_my_plan_v2' : ActorMethod<[Principal], ApiResponse_53>,
+  'get_my_reseller_certification' : ActorMethod<[], ApiResponse_54>,
+  'get_navigation_context' : ActorMethod<[], ApiResponse_55>,
+  'get_openai_api_key' : ActorMethod<[], ApiResponse_56>,
+  'get_org_defaults_v2' : ActorMethod<[Principal], ApiResponse_57>,
+  'get_org_onboarding_status_v2' : ActorMethod<[Principal], ApiResponse_58>,
+  'get_org_settings_v2' : ActorMethod<[Principal], ApiResponse_59>,
+  'get_org_usage_v2' : ActorMethod<[Principal], ApiResponse_60>,
   'get_organization_analytic' : ActorMethod<
     [GetOrganizationAnalyticRequest],
-    ApiResponse_11
+    ApiResponse_61
   >,
   'get_organization_by_id' : ActorMethod<[Principal], OrganizationResult>,
-  'get_organization_by_id_v2' : ActorMethod<[Principal], ApiResponse_3>,
+  'get_organization_by_id_v2' : ActorMethod<[Principal], ApiResponse_26>,
   'get_organization_private_key' : ActorMethod<[Principal], PrivateKeyResult>,
+  'get_ownership_stats_v2' : ActorMethod<[Principal], ApiResponse_62>,
+  'get_platform_metrics_v2' : ActorMethod<[], ApiResponse_63>,
   'get_product_by_id' : ActorMethod<[Principal], ProductResult>,
-  'get_scraper_url' : ActorMethod<[], ApiResponse_10>,
+  'get_product_reward_stats_v2' : ActorMethod<[Principal], ApiResponse_64>,
+  'get_public_brand_stats' : ActorMethod<
+    [Principal, [] | [bigint]],
+    ApiResponse_65
+  >,
+  'get_recall_campaign_status_v2' : ActorMethod<[Principal], ApiResponse_66>,
+  'get_recent_logs_v2' : ActorMethod<
+    [[] | [LogLevel], [] | [string], [] | [number]],
+    ApiResponse_67
+  >,
+  'get_report_v2' : ActorMethod<[Principal], ApiResponse_68>,
+  'get_reseller_products_v2' : ActorMethod<[Principal], ApiResponse_17>,
+  'get_reward_pool_balance_v2' : ActorMethod<[Principal], ApiResponse_34>,
+  'get_scraper_url' : ActorMethod<[], ApiResponse_56>,
+  'get_shared_report' : ActorMethod<[string], ApiResponse_69>,
+  'get_stats_for_serial_range' : ActorMethod<
+    [Principal, bigint, bigint],
+    ApiResponse_70
+  >,
+  'get_suspicious_activity_v2' : ActorMethod<
+    [Principal, [] | [PaginationRequest]],
+    ApiResponse_71
+  >,
+  'get_terms_status_v2' : ActorMethod<[], ApiResponse_72>,
   'get_user_by_id' : ActorMethod<[Principal], [] | [User]>,
-  'get_verification_rate_limit' : ActorMethod<[Principal], ApiResponse_12>,
+  'get_verification_domain_v2' : ActorMethod<[Principal], ApiResponse_73>,
+  'get_verification_heatmap_v2' : ActorMethod<
+    [Principal, VerificationHeatmapRange],
+    ApiResponse_74
+  >,
+  'get_verification_rate_limit' : ActorMethod<[Principal], ApiResponse_75>,
+  'get_verification_retention_policy_v2' : ActorMethod<
+    [Principal],
+    ApiResponse_76
+  >,
+  'grant_reseller_products_v2' : ActorMethod<
+    [UpdateResellerProductsRequest],
+    ApiResponse_17
+  >,
   'greet' : ActorMethod<[string], string>,
-  'initialize_user_session' : ActorMethod<[[] | [UserRole]], ApiResponse_1>,
+  'initialize_user_session' : ActorMethod<[[] | [UserRole]], ApiResponse_20>,
+  'lift_probe_ban_v2' : ActorMethod<[Principal], ApiResponse_77>,
+  'link_verification_history' : ActorMethod<[string], ApiResponse_14>,
+  'list_admin_operation_audit_log_v2' : ActorMethod<
+    [Principal, [] | [PaginationRequest]],
+    ApiResponse_78
+  >,
+  'list_admin_operations_v2' : ActorMethod<
+    [[] | [AdminOperationStatus], [] | [PaginationRequest]],
+    ApiResponse_79
+  >,
+  'list_bi_export_deliveries_v2' : ActorMethod<
+    [ListBiExportDeliveriesRequest],
+    ApiResponse_80
+  >,
+  'list_capability_token_audit_log_v2' : ActorMethod<
+    [Principal, [] | [PaginationRequest]],
+    ApiResponse_78
+  >,
+  'list_capability_tokens_v2' : ActorMethod<[Principal], ApiResponse_81>,
+  'list_disputes_v2' : ActorMethod<[ListDisputesRequest], ApiResponse_82>,
+  'list_feature_flags_v2' : ActorMethod<[[] | [Principal]], ApiResponse_83>,
+  'list_jobs_v2' : ActorMethod<[Principal], ApiResponse_84>,
+  'list_metadata_schemas_v2' : ActorMethod<[Principal], ApiResponse_85>,
+  'list_moderation_cases_v2' : ActorMethod<
+    [[] | [ModerationStatus], [] | [PaginationRequest]],
+    ApiResponse_86
+  >,
+  'list_my_disputes_v2' : ActorMethod<
+    [[] | [PaginationRequest]],
+    ApiResponse_82
+  >,
+  'list_my_notifications_v2' : ActorMethod<
+    [ListNotificationsRequest],
+    ApiResponse_87
+  >,
+  'list_my_products_v2' : ActorMethod<[], ApiResponse_88>,
+  'list_my_wallets_v2' : ActorMethod<[], ApiResponse_89>,
+  'list_notification_rules_v2' : ActorMethod<[Principal], ApiResponse_90>,
+  'list_org_key_escrow_requests_v2' : ActorMethod<[Principal], ApiResponse_91>,
+  'list_org_verifications_v2' : ActorMethod<
+    [ListOrgVerificationsRequest],
+    ApiResponse_92
+  >,
   'list_organizations_v2' : ActorMethod<
     [FindOrganizationsRequest],
-    ApiResponse_13
+    ApiResponse_93
+  >,
+  'list_outbox_deliveries_v2' : ActorMethod<
+    [[] | [OutboxDeliveryStatus], [] | [PaginationRequest]],
+    ApiResponse_94
+  >,
+  'list_pending_redemptions_v2' : ActorMethod<[Principal], ApiResponse_95>,
+  'list_post_verification_messages_v2' : ActorMethod<
+    [Principal],
+    ApiResponse_96
+  >,
+  'list_print_jobs_v2' : ActorMethod<[Principal], ApiResponse_97>,
+  'list_probe_bans_v2' : ActorMethod<[], ApiResponse_98>,
+  'list_product_audit_log_v2' : ActorMethod<
+    [Principal, [] | [PaginationRequest]],
+    ApiResponse_78
   >,
   'list_product_serial_numbers' : ActorMethod<
     [[] | [Principal], [] | [Principal]],
     Result
   >,
+  'list_product_serial_numbers_v2' : ActorMethod<
+    [ListProductSerialNumbersRequest],
+    ApiResponse_99
+  >,
+  'list_product_variants_v2' : ActorMethod<[Principal], ApiResponse_100>,
   'list_product_verifications_by_org_id' : ActorMethod<
     [Principal],
     Array<ProductVerificationDetail>
   >,
+  'list_product_verifications_v2' : ActorMethod<
+    [ListProductVerificationsRequest],
+    ApiResponse_101
+  >,
   'list_products' : ActorMethod<[Principal], Array<Product>>,
+  'list_products_v2' : ActorMethod<[ListProductsRequest], ApiResponse_102>,
+  'list_public_brands' : ActorMethod<
+    [[] | [PaginationRequest]],
+    ApiResponse_103
+  >,
+  'list_quarantined_entries' : ActorMethod<[], ApiResponse_104>,
+  'list_readonly_principals_v2' : ActorMethod<[Principal], ApiResponse_105>,
+  'list_recall_campaigns_v2' : ActorMethod<[Principal], ApiResponse_106>,
+  'list_report_schedules_v2' : ActorMethod<[Principal], ApiResponse_107>,
+  'list_report_share_links_v2' : ActorMethod<[Principal], ApiResponse_108>,
+  'list_reports_v2' : ActorMethod<[ListReportsRequest], ApiResponse_109>,
+  'list_reseller_applications_v2' : ActorMethod<
+    [Principal, [] | [RedemptionApprovalStatus]],
+    ApiResponse_110
+  >,
+  'list_reseller_preapprovals_v2' : ActorMethod<[Principal], ApiResponse_111>,
   'list_resellers_by_org_id' : ActorMethod<[Principal], Array<Reseller>>,
-  'logout_user' : ActorMethod<[], ApiResponse_14>,
+  'list_reward_penalties_v2' : ActorMethod<
+    [ListRewardPenaltiesRequest],
+    ApiResponse_112
+  >,
+  'list_serial_print_history' : ActorMethod<
+    [Principal, Principal],
+    ApiResponse_113
+  >,
+  'list_status_messages_v2' : ActorMethod<[], ApiResponse_114>,
+  'list_suspected_duplicate_products_v2' : ActorMethod<
+    [Principal],
+    ApiResponse_115
+  >,
+  'list_terms_versions_v2' : ActorMethod<[], ApiResponse_116>,
+  'list_users_v2' : ActorMethod<
+    [[] | [UserListFilter], [] | [PaginationRequest]],
+    ApiResponse_117
+  >,
+  'list_variant_serial_numbers_v2' : ActorMethod<[Principal], ApiResponse_118>,
+  'list_verification_delegations_v2' : ActorMethod<
+    [Principal],
+    ApiResponse_119
+  >,
+  'logout_user' : ActorMethod<[], ApiResponse_120>,
+  'lookup_brand_by_code' : ActorMethod<[string], ApiResponse_121>,
+  'mark_notification_read_v2' : ActorMethod<[Principal], ApiResponse_122>,
+  'mark_serials_shipped' : ActorMethod<
+    [UpdateSerialStatusRequest],
+    ApiResponse_31
+  >,
+  'mark_serials_sold' : ActorMethod<
+    [UpdateSerialStatusRequest],
+    ApiResponse_31
+  >,
+  'mint_capability_token_v2' : ActorMethod<
+    [MintCapabilityTokenRequest],
+    ApiResponse_123
+  >,
+  'moderate_directory_listing_v2' : ActorMethod<
+    [ModerateDirectoryListingRequest],
+    ApiResponse_46
+  >,
+  'notify_reward_pool_deposit_v2' : ActorMethod<[Principal], ApiResponse_34>,
+  'open_dispute_v2' : ActorMethod<[OpenDisputeRequest], ApiResponse_2>,
+  'penalize_user_rewards_v2' : ActorMethod<
+    [PenalizeUserRewardsRequest],
+    ApiResponse_124
+  >,
+  'preapprove_resellers_v2' : ActorMethod<
+    [Principal, Array<PreapprovedResellerRow>],
+    ApiResponse_125
+  >,
   'print_product_serial_number' : ActorMethod<
     [Principal, Principal],
     ProductUniqueCodeResult
   >,
-  'redeem_product_reward' : ActorMethod<[RedeemRewardRequest], ApiResponse_15>,
+  'print_product_serial_numbers_bulk_v2' : ActorMethod<
+    [BulkPrintRequest],
+    ApiResponse_126
+  >,
+  'propose_admin_operation_v2' : ActorMethod<
+    [ProposeAdminOperationRequest],
+    ApiResponse_5
+  >,
+  'publish_terms_v2' : ActorMethod<[PublishTermsRequest], ApiResponse_127>,
+  'quarantine_entry' : ActorMethod<
+    [IntegrityStore, Principal, [] | [IntegritySubKey]],
+    ApiResponse_128
+  >,
+  'redeem_product_reward' : ActorMethod<[RedeemRewardRequest], ApiResponse_129>,
+  'refresh_organization_analytic_v2' : ActorMethod<[Principal], ApiResponse_61>,
   'register' : ActorMethod<[], User>,
   'register_as_organization' : ActorMethod<[OrganizationInput], UserResult>,
-  'register_as_reseller_v2' : ActorMethod<[ResellerInput], ApiResponse_16>,
-  'reset_all_stable_storage' : ActorMethod<[], ApiResponse_17>,
-  'select_active_organization' : ActorMethod<[Principal], ApiResponse_1>,
-  'set_openai_api_key' : ActorMethod<[string], ApiResponse_18>,
-  'set_scraper_url' : ActorMethod<[string], ApiResponse_18>,
+  'register_as_reseller_v2' : ActorMethod<[ResellerInput], ApiResponse_130>,
+  'register_verification_domain_v2' : ActorMethod<
+    [RegisterVerificationDomainRequest],
+    ApiResponse_13
+  >,
+  'reject_admin_operation_v2' : ActorMethod<
+    [AdminOperationDecisionRequest],
+    ApiResponse_5
+  >,
+  'reject_redemption_v2' : ActorMethod<
+    [RejectRedemptionRequest],
+    ApiResponse_7
+  >,
+  'reject_reseller_application_v2' : ActorMethod<
+    [RejectResellerApplicationRequest],
+    ApiResponse_8
+  >,
+  'remove_from_my_products_v2' : ActorMethod<[Principal], ApiResponse_22>,
+  'remove_my_wallet_v2' : ActorMethod<[Principal], ApiResponse_3>,
+  'remove_reseller_preapproval_v2' : ActorMethod<
+    [Principal, string],
+    ApiResponse_131
+  >,
+  'remove_status_message_v2' : ActorMethod<
+    [RemoveStatusMessageRequest],
+    ApiResponse_132
+  >,
+  'remove_verification_domain_v2' : ActorMethod<[Principal], ApiResponse_133>,
+  'renew_reseller_certification_v2' : ActorMethod<
+    [RenewResellerCertificationRequest],
+    ApiResponse_20
+  >,
+  'request_org_key_escrow_export_v2' : ActorMethod<
+    [RequestKeyEscrowExportRequest],
+    ApiResponse_6
+  >,
+  'requeue_outbox_delivery_v2' : ActorMethod<[bigint], ApiResponse_134>,
+  'resend_reseller_email_confirmation_v2' : ActorMethod<[], ApiResponse_22>,
+  'reset_all_stable_storage' : ActorMethod<[], ApiResponse_135>,
+  'reset_circuit_breaker_v2' : ActorMethod<[ExternalService], ApiResponse_136>,
+  'reset_endpoint_metrics_v2' : ActorMethod<[], ApiResponse_11>,
+  'review_moderation_case_v2' : ActorMethod<
+    [ReviewModerationCaseRequest],
+    ApiResponse_137
+  >,
+  'revoke_capability_token_v2' : ActorMethod<[Principal], ApiResponse_138>,
+  'revoke_readonly_principal_v2' : ActorMethod<
+    [Principal, Principal],
+    ApiResponse_28
+  >,
+  'revoke_report_share_link_v2' : ActorMethod<[Principal], ApiResponse_139>,
+  'revoke_reseller_products_v2' : ActorMethod<
+    [UpdateResellerProductsRequest],
+    ApiResponse_17
+  >,
+  'revoke_unique_codes_v2' : ActorMethod<
+    [Principal, Array<Principal>, CodeRevocationReason],
+    ApiResponse_31
+  >,
+  'revoke_verification_delegation_v2' : ActorMethod<
+    [Principal],
+    ApiResponse_33
+  >,
+  'rotate_readonly_principal_v2' : ActorMethod<
+    [Principal, Principal, Principal],
+    ApiResponse_28
+  >,
+  'run_invariant_checks_v2' : ActorMethod<[], ApiResponse_140>,
+  'run_verification_rollup_v2' : ActorMethod<[], ApiResponse_141>,
+  'scan_store_integrity' : ActorMethod<[IntegrityStore], ApiResponse_142>,
+  'search_products_v2' : ActorMethod<[SearchProductsRequest], ApiResponse_102>,
+  'select_active_organization' : ActorMethod<[Principal], ApiResponse_20>,
+  'set_bi_export_config_v2' : ActorMethod<
+    [SetBiExportConfigRequest],
+    ApiResponse_41
+  >,
+  'set_default_wallet_v2' : ActorMethod<[Principal], ApiResponse_3>,
+  'set_feature_flag_v2' : ActorMethod<[SetFeatureFlagRequest], ApiResponse_15>,
+  'set_label_template_v2' : ActorMethod<
+    [SetLabelTemplateRequest],
+    ApiResponse_49
+  >,
+  'set_metadata_schema_v2' : ActorMethod<
+    [SetMetadataSchemaRequest],
+    ApiResponse_143
+  >,
+  'set_openai_api_key' : ActorMethod<[string], ApiResponse_22>,
+  'set_org_plan_status_v2' : ActorMethod<
+    [Principal, PlanStatus],
+    ApiResponse_10
+  >,
+  'set_org_quota_v2' : ActorMethod<[SetOrgQuotaRequest], ApiResponse_144>,
+  'set_post_verification_message_v2' : ActorMethod<
+    [SetPostVerificationMessageRequest],
+    ApiResponse_145
+  >,
+  'set_product_challenge_mode_v2' : ActorMethod<
+    [Principal, boolean],
+    ApiResponse_16
+  >,
+  'set_product_markets_v2' : ActorMethod<
+    [Principal, Array<string>],
+    ApiResponse_16
+  >,
+  'set_product_recall_v2' : ActorMethod<
+    [SetProductRecallRequest],
+    ApiResponse_16
+  >,
+  'set_product_sequential_serials_v2' : ActorMethod<
+    [Principal, boolean],
+    ApiResponse_16
+  >,
+  'set_product_test_mode_v2' : ActorMethod<
+    [Principal, boolean],
+    ApiResponse_16
+  >,
+  'set_product_warranty_v2' : ActorMethod<
+    [Principal, [] | [number]],
+    ApiResponse_16
+  >,
+  'set_reward_pool_settings_v2' : ActorMethod<
+    [SetRewardPoolSettingsRequest],
+    ApiResponse_34
+  >,
+  'set_scraper_url' : ActorMethod<[string], ApiResponse_22>,
   'set_self_role' : ActorMethod<[UserRole], UserResult>,
-  'transform' : ActorMethod<[TransformArgs], HttpResponse>,
+  'set_serial_metadata_v2' : ActorMethod<
+    [SetSerialMetadataRequest],
+    ApiResponse_32
+  >,
+  'set_status_message_v2' : ActorMethod<
+    [SetStatusMessageRequest],
+    ApiResponse_132
+  >,
+  'set_user_role_admin_v2' : ActorMethod<[Principal, UserRole], ApiResponse_36>,
+  'set_verification_retention_policy_v2' : ActorMethod<
+    [SetVerificationRetentionRequest],
+    ApiResponse_76
+  >,
+  'sign_payload_for_org' : ActorMethod<[Principal, string], ApiResponse_146>,
+  'simulate_verification' : ActorMethod<
+    [SimulateVerificationRequest],
+    ApiResponse_147
+  >,
+  'start_guest_session' : ActorMethod<[], ApiResponse_148>,
+  'start_identity_link' : ActorMethod<[], ApiResponse_149>,
+  'start_recall_campaign_v2' : ActorMethod<
+    [StartRecallCampaignRequest],
+    ApiResponse_66
+  >,
+  'start_verification_challenge' : ActorMethod<
+    [StartVerificationChallengeRequest],
+    ApiResponse_150
+  >,
+  'submit_job_v2' : ActorMethod<[SubmitJobRequest], ApiResponse_9>,
+  'transform_dns' : ActorMethod<[TransformArgs], HttpResponse>,
+  'transform_openai' : ActorMethod<[TransformArgs], HttpResponse>,
+  'transform_scraper' : ActorMethod<[TransformArgs], HttpResponse>,
+  'transform_webhook' : ActorMethod<[TransformArgs], HttpResponse>,
+  'trigger_bi_export_v2' : ActorMethod<[Principal], ApiResponse_151>,
+  'update_canister_config_v2' : ActorMethod<
+    [UpdateCanisterConfigRequest],
+    ApiResponse_43
+  >,
+  'update_directory_listing_v2' : ActorMethod<
+    [UpdateDirectoryListingRequest],
+    ApiResponse_46
+  >,
+  'update_dispute_status_v2' : ActorMethod<
+    [UpdateDisputeStatusRequest],
+    ApiResponse_2
+  >,
+  'update_org_settings_v2' : ActorMethod<
+    [UpdateOrgSettingsRequest],
+    ApiResponse_59
+  >,
   'update_organization' : ActorMethod<
     [Principal, OrganizationInput],
     OrganizationResult
   >,
   'update_organization_v2' : ActorMethod<
     [UpdateOrganizationRequest],
-    ApiResponse_3
+    ApiResponse_26
   >,
   'update_product' : ActorMethod<[Principal, ProductInput], ProductResult>,
   'update_product_serial_number' : ActorMethod<
     [Principal, Principal],
     ProductSerialNumberResult
   >,
+  'update_product_variant_v2' : ActorMethod<
+    [UpdateProductVariantRequest],
+    ApiResponse_27
+  >,
   'update_self_details' : ActorMethod<[UserDetailsInput], UserResult>,
   'update_user' : ActorMethod<[Principal, UserDetailsInput], UserResult>,
   'update_user_orgs' : ActorMethod<[Principal, Array<Principal>], UserResult>,
+  'upload_coupon_codes_v2' : ActorMethod<
+    [UploadCouponCodesRequest],
+    ApiResponse_152
+  >,
   'verify_product_v2' : ActorMethod<
     [VerifyProductEnhancedRequest],
-    ApiResponse_19
+    ApiResponse_147
+  >,
+  'verify_products_batch_v2' : ActorMethod<
+    [Array<BatchVerificationItem>],
+    ApiResponse_153
+  >,
+  'verify_reseller_v2' : ActorMethod<[VerifyResellerRequest], ApiResponse_154>,
+  'void_serial_numbers' : ActorMethod<
+    [UpdateSerialStatusRequest],
+    ApiResponse_31
   >,
-  'verify_reseller_v2' : ActorMethod<[VerifyResellerRequest], ApiResponse_20>,
   'whoami' : ActorMethod<[], [] | [User]>,
+  'ws_close' : ActorMethod<[bigint], ApiResponse_22>,
+  'ws_get_messages' : ActorMethod<[bigint], ApiResponse_155>,
+  'ws_message' : ActorMethod<[WsMessageRequest], ApiResponse_22>,
+  'ws_open' : ActorMethod<[WsOpenRequest], ApiResponse_156>,
 }
 export declare const idlFactory: IDL.InterfaceFactory;
 export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
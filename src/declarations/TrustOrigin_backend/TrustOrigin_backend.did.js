@@ -1,172 +1,304 @@
 export const idlFactory = ({ IDL }) => {
+  const RateLimitInfo = IDL.Record({
+    'current_window_start' : IDL.Nat64,
+    'remaining_attempts' : IDL.Nat32,
+    'reset_time' : IDL.Nat64,
+    'locked_until' : IDL.Opt(IDL.Nat64),
+    'penalty_level' : IDL.Nat32,
+  });
   const ResponseMetadata = IDL.Record({
     'request_id' : IDL.Opt(IDL.Text),
+    'rate_limit' : IDL.Opt(RateLimitInfo),
     'version' : IDL.Text,
     'timestamp' : IDL.Nat64,
   });
+  const TermsAcceptance = IDL.Record({
+    'accepted_at' : IDL.Nat64,
+    'user_id' : IDL.Principal,
+    'version' : IDL.Text,
+    'terms_seq' : IDL.Nat64,
+  });
   const Metadata = IDL.Record({ 'key' : IDL.Text, 'value' : IDL.Text });
   const ErrorDetails = IDL.Record({
     'message' : IDL.Text,
     'details' : IDL.Vec(Metadata),
   });
+  const ValidationError = IDL.Record({
+    'field' : IDL.Text,
+    'message' : IDL.Text,
+  });
   const ApiError = IDL.Variant({
+    'AccountDisabled' : IDL.Record({ 'details' : ErrorDetails }),
+    'ValidationFailed' : IDL.Record({
+      'errors' : IDL.Vec(ValidationError),
+      'details' : ErrorDetails,
+    }),
     'InvalidInput' : IDL.Record({ 'details' : ErrorDetails }),
     'NotFound' : IDL.Record({ 'details' : ErrorDetails }),
     'ExternalApiError' : IDL.Record({ 'details' : ErrorDetails }),
     'Unauthorized' : IDL.Record({ 'details' : ErrorDetails }),
     'AlreadyExists' : IDL.Record({ 'details' : ErrorDetails }),
+    'ServiceUnavailable' : IDL.Record({
+      'service' : IDL.Text,
+      'retry_after' : IDL.Opt(IDL.Nat64),
+      'details' : ErrorDetails,
+    }),
+    'RateLimited' : IDL.Record({
+      'retry_after' : IDL.Nat64,
+      'details' : ErrorDetails,
+    }),
     'MalformedData' : IDL.Record({ 'details' : ErrorDetails }),
+    'UpgradeRequired' : IDL.Record({
+      'min_version' : IDL.Text,
+      'download_url' : IDL.Opt(IDL.Text),
+      'details' : ErrorDetails,
+    }),
+    'FeatureDisabled' : IDL.Record({
+      'feature' : IDL.Text,
+      'details' : ErrorDetails,
+    }),
     'InternalError' : IDL.Record({ 'details' : ErrorDetails }),
+    'TermsNotAccepted' : IDL.Record({
+      'url' : IDL.Text,
+      'version' : IDL.Text,
+      'details' : ErrorDetails,
+    }),
+    'PossibleDuplicate' : IDL.Record({
+      'product_ids' : IDL.Vec(IDL.Principal),
+      'details' : ErrorDetails,
+    }),
+    'QuotaExceeded' : IDL.Record({ 'details' : ErrorDetails }),
   });
   const ApiResponse = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(IDL.Bool),
+    'data' : IDL.Opt(TermsAcceptance),
     'error' : IDL.Opt(ApiError),
   });
-  const CompleteResellerProfileRequest = IDL.Record({
-    'ecommerce_urls' : IDL.Vec(Metadata),
-    'additional_metadata' : IDL.Opt(IDL.Vec(Metadata)),
-    'contact_email' : IDL.Opt(IDL.Text),
-    'contact_phone' : IDL.Opt(IDL.Text),
-    'target_organization_id' : IDL.Principal,
-    'reseller_name' : IDL.Text,
+  const PrintJobEntry = IDL.Record({
+    'print_version' : IDL.Nat8,
+    'serial_no' : IDL.Principal,
   });
-  const OrganizationPublic = IDL.Record({
+  const PrintJob = IDL.Record({
     'id' : IDL.Principal,
-    'updated_at' : IDL.Nat64,
-    'updated_by' : IDL.Principal,
-    'metadata' : IDL.Vec(Metadata),
-    'name' : IDL.Text,
-    'description' : IDL.Text,
+    'printer_metadata' : IDL.Vec(Metadata),
+    'product_id' : IDL.Principal,
+    'label_template_version' : IDL.Opt(IDL.Text),
+    'org_id' : IDL.Principal,
     'created_at' : IDL.Nat64,
     'created_by' : IDL.Principal,
+    'entries' : IDL.Vec(PrintJobEntry),
+    'activation_time' : IDL.Opt(IDL.Nat64),
   });
-  const ResellerContextDetails = IDL.Record({
-    'certification_code' : IDL.Opt(IDL.Text),
-    'certification_timestamp' : IDL.Opt(IDL.Nat64),
-    'associated_organization' : IDL.Opt(OrganizationPublic),
-    'is_profile_complete_and_verified' : IDL.Bool,
+  const ApiResponse_1 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PrintJob),
+    'error' : IDL.Opt(ApiError),
   });
-  const UserRole = IDL.Variant({
-    'Customer' : IDL.Null,
-    'Reseller' : IDL.Null,
+  const AddDisputeMessageRequest = IDL.Record({
+    'dispute_id' : IDL.Principal,
+    'message' : IDL.Text,
+  });
+  const DisputeStatus = IDL.Variant({
+    'Open' : IDL.Null,
+    'InReview' : IDL.Null,
+    'Resolved' : IDL.Null,
+  });
+  const DisputeParty = IDL.Variant({
+    'Brand' : IDL.Null,
+    'Consumer' : IDL.Null,
     'Admin' : IDL.Null,
-    'BrandOwner' : IDL.Null,
   });
-  const UserPublic = IDL.Record({
+  const DisputeMessage = IDL.Record({
+    'body' : IDL.Text,
+    'created_at' : IDL.Nat64,
+    'author' : IDL.Principal,
+    'party' : DisputeParty,
+  });
+  const Dispute = IDL.Record({
     'id' : IDL.Principal,
+    'status' : DisputeStatus,
+    'updated_at' : IDL.Nat64,
+    'subject' : IDL.Text,
+    'product_id' : IDL.Principal,
+    'messages' : IDL.Vec(DisputeMessage),
+    'org_id' : IDL.Principal,
+    'opened_by' : IDL.Principal,
     'created_at' : IDL.Nat64,
-    'email' : IDL.Opt(IDL.Text),
-    'first_name' : IDL.Opt(IDL.Text),
-    'last_name' : IDL.Opt(IDL.Text),
+    'resolution' : IDL.Opt(IDL.Text),
+    'resolved_at' : IDL.Opt(IDL.Nat64),
+    'resolved_by' : IDL.Opt(IDL.Principal),
+    'serial_no' : IDL.Principal,
+    'verification_id' : IDL.Opt(IDL.Principal),
   });
-  const BrandOwnerContextDetails = IDL.Record({
-    'active_organization' : IDL.Opt(OrganizationPublic),
-    'has_organizations' : IDL.Bool,
-    'organizations' : IDL.Opt(IDL.Vec(OrganizationPublic)),
+  const ApiResponse_2 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(Dispute),
+    'error' : IDL.Opt(ApiError),
   });
-  const AuthContextResponse = IDL.Record({
-    'reseller_details' : IDL.Opt(ResellerContextDetails),
-    'role' : IDL.Opt(UserRole),
-    'user' : IDL.Opt(UserPublic),
-    'brand_owner_details' : IDL.Opt(BrandOwnerContextDetails),
-    'is_registered' : IDL.Bool,
+  const WalletChain = IDL.Variant({
+    'InternetComputer' : IDL.Null,
+    'Ethereum' : IDL.Null,
+    'Solana' : IDL.Null,
+    'Bitcoin' : IDL.Null,
   });
-  const ApiResponse_1 = IDL.Record({
+  const AddWalletRequest = IDL.Record({
+    'make_default' : IDL.Bool,
+    'chain' : WalletChain,
+    'label' : IDL.Text,
+    'address' : IDL.Text,
+  });
+  const SavedWallet = IDL.Record({
+    'id' : IDL.Principal,
+    'chain' : WalletChain,
+    'created_at' : IDL.Nat64,
+    'label' : IDL.Text,
+    'is_default' : IDL.Bool,
+    'address' : IDL.Text,
+  });
+  const ApiResponse_3 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(AuthContextResponse),
+    'data' : IDL.Opt(SavedWallet),
     'error' : IDL.Opt(ApiError),
   });
-  const OrganizationInput = IDL.Record({
-    'metadata' : IDL.Vec(Metadata),
-    'name' : IDL.Text,
-    'description' : IDL.Text,
+  const WarrantyStatus = IDL.Variant({
+    'Active' : IDL.Null,
+    'NotOffered' : IDL.Null,
+    'Expired' : IDL.Null,
   });
-  const OrganizationContextResponse = IDL.Record({
-    'user_auth_context' : AuthContextResponse,
-    'organization' : OrganizationPublic,
+  const ProductRecall = IDL.Record({
+    'issued_at' : IDL.Nat64,
+    'issued_by' : IDL.Principal,
+    'instructions' : IDL.Opt(IDL.Text),
+    'reason' : IDL.Text,
   });
-  const ApiResponse_2 = IDL.Record({
+  const MyProductEntry = IDL.Record({
+    'product_id' : IDL.Principal,
+    'discontinued' : IDL.Bool,
+    'org_id' : IDL.Principal,
+    'org_name' : IDL.Opt(IDL.Text),
+    'product_name' : IDL.Text,
+    'category' : IDL.Text,
+    'warranty_expires_at' : IDL.Opt(IDL.Nat64),
+    'registered_at' : IDL.Nat64,
+    'warranty_status' : WarrantyStatus,
+    'user_serial_no' : IDL.Opt(IDL.Text),
+    'serial_no' : IDL.Principal,
+    'recall' : IDL.Opt(ProductRecall),
+  });
+  const ApiResponse_4 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(OrganizationContextResponse),
+    'data' : IDL.Opt(MyProductEntry),
     'error' : IDL.Opt(ApiError),
   });
-  const OrganizationResponse = IDL.Record({
-    'organization' : OrganizationPublic,
+  const AdminOperationDecisionRequest = IDL.Record({
+    'note' : IDL.Opt(IDL.Text),
+    'operation_id' : IDL.Principal,
   });
-  const ApiResponse_3 = IDL.Record({
+  const AdminOperationStatus = IDL.Variant({
+    'Failed' : IDL.Null,
+    'Rejected' : IDL.Null,
+    'Executed' : IDL.Null,
+    'Expired' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const GetOrganizationAnalyticRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+  });
+  const AdminOperationKind = IDL.Variant({
+    'SetRequiredApprovals' : IDL.Record({ 'required' : IDL.Nat32 }),
+    'ResetStorage' : IDL.Null,
+    'DeleteOrganization' : GetOrganizationAnalyticRequest,
+    'ApproveKeyExport' : IDL.Record({ 'request_id' : IDL.Principal }),
+  });
+  const AdminApproval = IDL.Record({
+    'admin_id' : IDL.Principal,
+    'note' : IDL.Opt(IDL.Text),
+    'approved_at' : IDL.Nat64,
+  });
+  const AdminOperation = IDL.Record({
+    'id' : IDL.Principal,
+    'status' : AdminOperationStatus,
+    'result' : IDL.Opt(IDL.Text),
+    'rejected_at' : IDL.Opt(IDL.Nat64),
+    'rejected_by' : IDL.Opt(IDL.Principal),
+    'executed_at' : IDL.Opt(IDL.Nat64),
+    'kind' : AdminOperationKind,
+    'required_approvals' : IDL.Nat32,
+    'rejection_note' : IDL.Opt(IDL.Text),
+    'expires_at' : IDL.Nat64,
+    'proposed_at' : IDL.Nat64,
+    'proposed_by' : IDL.Principal,
+    'approvals' : IDL.Vec(AdminApproval),
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const ApiResponse_5 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(OrganizationResponse),
+    'data' : IDL.Opt(AdminOperation),
     'error' : IDL.Opt(ApiError),
   });
-  const ProductInput = IDL.Record({
-    'metadata' : IDL.Vec(Metadata),
-    'name' : IDL.Text,
-    'org_id' : IDL.Principal,
-    'description' : IDL.Text,
-    'category' : IDL.Text,
+  const KeyEscrowStatus = IDL.Variant({
+    'Approved' : IDL.Null,
+    'Cancelled' : IDL.Null,
+    'PendingApproval' : IDL.Null,
+    'Exported' : IDL.Null,
   });
-  const Product = IDL.Record({
+  const KeyEscrowRequest = IDL.Record({
     'id' : IDL.Principal,
-    'updated_at' : IDL.Nat64,
-    'updated_by' : IDL.Principal,
-    'public_key' : IDL.Text,
-    'metadata' : IDL.Vec(Metadata),
-    'name' : IDL.Text,
+    'status' : KeyEscrowStatus,
+    'cancelled_at' : IDL.Opt(IDL.Nat64),
+    'cancelled_by' : IDL.Opt(IDL.Principal),
+    'recipient_public_key' : IDL.Text,
     'org_id' : IDL.Principal,
-    'description' : IDL.Text,
-    'created_at' : IDL.Nat64,
-    'created_by' : IDL.Principal,
-    'category' : IDL.Text,
+    'approved_at' : IDL.Opt(IDL.Nat64),
+    'approved_by' : IDL.Opt(IDL.Principal),
+    'exported_at' : IDL.Opt(IDL.Nat64),
+    'requested_at' : IDL.Nat64,
+    'requested_by' : IDL.Principal,
+    'confirmable_at' : IDL.Opt(IDL.Nat64),
+    'expires_at' : IDL.Opt(IDL.Nat64),
+    'reason' : IDL.Opt(IDL.Text),
   });
-  const ProductResult = IDL.Variant({
-    'none' : IDL.Null,
-    'error' : ApiError,
-    'product' : Product,
+  const ApiResponse_6 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(KeyEscrowRequest),
+    'error' : IDL.Opt(ApiError),
   });
-  const ProductSerialNumber = IDL.Record({
-    'updated_at' : IDL.Nat64,
-    'updated_by' : IDL.Principal,
+  const RedemptionApprovalStatus = IDL.Variant({
+    'Approved' : IDL.Null,
+    'Rejected' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const PendingRedemption = IDL.Record({
+    'id' : IDL.Principal,
+    'transaction_id' : IDL.Opt(IDL.Text),
+    'status' : RedemptionApprovalStatus,
     'product_id' : IDL.Principal,
-    'metadata' : IDL.Vec(Metadata),
-    'created_at' : IDL.Nat64,
-    'created_by' : IDL.Principal,
-    'print_version' : IDL.Nat8,
+    'org_id' : IDL.Principal,
+    'wallet_address' : IDL.Text,
+    'user_id' : IDL.Principal,
+    'requested_at' : IDL.Nat64,
+    'rejection_reason' : IDL.Opt(IDL.Text),
+    'decided_at' : IDL.Opt(IDL.Nat64),
+    'decided_by' : IDL.Opt(IDL.Principal),
     'serial_no' : IDL.Principal,
+    'points' : IDL.Nat32,
+    'verification_id' : IDL.Principal,
   });
-  const ProductSerialNumberResult = IDL.Variant({
-    'result' : ProductSerialNumber,
-    'error' : ApiError,
-  });
-  const UserDetailsInput = IDL.Record({
-    'email' : IDL.Text,
-    'first_name' : IDL.Text,
-    'detail_meta' : IDL.Vec(Metadata),
-    'last_name' : IDL.Text,
-    'phone_no' : IDL.Text,
+  const ApiResponse_7 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PendingRedemption),
+    'error' : IDL.Opt(ApiError),
   });
-  const User = IDL.Record({
+  const ResellerApplication = IDL.Record({
     'id' : IDL.Principal,
-    'updated_at' : IDL.Nat64,
-    'updated_by' : IDL.Principal,
-    'user_role' : IDL.Opt(UserRole),
-    'org_ids' : IDL.Vec(IDL.Principal),
-    'is_principal' : IDL.Bool,
-    'is_enabled' : IDL.Bool,
-    'created_at' : IDL.Nat64,
-    'created_by' : IDL.Principal,
-    'email' : IDL.Opt(IDL.Text),
-    'active_org_id' : IDL.Opt(IDL.Principal),
-    'first_name' : IDL.Opt(IDL.Text),
-    'detail_meta' : IDL.Vec(Metadata),
-    'last_name' : IDL.Opt(IDL.Text),
-    'phone_no' : IDL.Opt(IDL.Text),
-    'session_keys' : IDL.Vec(IDL.Principal),
-  });
-  const UserResult = IDL.Variant({
-    'none' : IDL.Null,
-    'user' : User,
-    'error' : ApiError,
+    'status' : RedemptionApprovalStatus,
+    'org_id' : IDL.Principal,
+    'user_id' : IDL.Principal,
+    'reseller_id' : IDL.Principal,
+    'rejection_reason' : IDL.Opt(IDL.Text),
+    'decided_at' : IDL.Opt(IDL.Nat64),
+    'decided_by' : IDL.Opt(IDL.Principal),
+    'submitted_at' : IDL.Nat64,
   });
   const Reseller = IDL.Record({
     'id' : IDL.Principal,
@@ -180,6 +312,7 @@ export const idlFactory = ({ IDL }) => {
     'org_id' : IDL.Principal,
     'contact_email' : IDL.Opt(IDL.Text),
     'certification_code' : IDL.Opt(IDL.Text),
+    'certification_expires_at' : IDL.Opt(IDL.Nat64),
     'certification_timestamp' : IDL.Opt(IDL.Nat64),
     'date_joined' : IDL.Nat64,
     'created_at' : IDL.Nat64,
@@ -187,162 +320,2257 @@ export const idlFactory = ({ IDL }) => {
     'user_id' : IDL.Principal,
     'is_verified' : IDL.Bool,
     'contact_phone' : IDL.Opt(IDL.Text),
+    'email_verified' : IDL.Opt(IDL.Bool),
   });
-  const ProductResponse = IDL.Record({ 'product' : Product });
-  const ApiResponse_4 = IDL.Record({
+  const ResellerApplicationDetail = IDL.Record({
+    'application' : ResellerApplication,
+    'reseller' : IDL.Opt(Reseller),
+  });
+  const ApiResponse_8 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(ProductResponse),
+    'data' : IDL.Opt(ResellerApplicationDetail),
     'error' : IDL.Opt(ApiError),
   });
-  const GenerateResellerUniqueCodeRequest = IDL.Record({
-    'context' : IDL.Opt(IDL.Text),
-    'reseller_id' : IDL.Principal,
+  const JobStatus = IDL.Variant({
+    'Queued' : IDL.Null,
+    'Failed' : IDL.Null,
+    'Running' : IDL.Null,
+    'Cancelled' : IDL.Null,
+    'Completed' : IDL.Null,
   });
-  const ResellerUniqueCodeResponse = IDL.Record({
-    'context' : IDL.Opt(IDL.Text),
-    'unique_code' : IDL.Text,
-    'timestamp' : IDL.Nat64,
-    'reseller_id' : IDL.Principal,
+  const JobCursor = IDL.Record({ 'seq' : IDL.Nat64, 'position' : IDL.Nat64 });
+  const JobSpec = IDL.Variant({
+    'VerificationExport' : IDL.Record({
+      'period_end' : IDL.Nat64,
+      'period_start' : IDL.Nat64,
+      'product_ids' : IDL.Vec(IDL.Principal),
+    }),
+    'SerialImport' : IDL.Record({
+      'user_serial_prefix' : IDL.Opt(IDL.Text),
+      'product_id' : IDL.Principal,
+      'count' : IDL.Nat32,
+      'variant_id' : IDL.Opt(IDL.Principal),
+    }),
   });
-  const ApiResponse_5 = IDL.Record({
+  const Job = IDL.Record({
+    'id' : IDL.Principal,
+    'status' : JobStatus,
+    'updated_at' : IDL.Nat64,
+    'result_rows' : IDL.Nat64,
+    'total_units' : IDL.Nat64,
+    'cursor' : JobCursor,
+    'org_id' : IDL.Principal,
+    'spec' : JobSpec,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'error' : IDL.Opt(IDL.Text),
+    'result_columns' : IDL.Vec(IDL.Text),
+    'result_chunks' : IDL.Nat32,
+    'started_at' : IDL.Opt(IDL.Nat64),
+    'processed_units' : IDL.Nat64,
+    'finished_at' : IDL.Opt(IDL.Nat64),
+  });
+  const JobStatusResponse = IDL.Record({
+    'job' : Job,
+    'progress_percent' : IDL.Nat8,
+  });
+  const ApiResponse_9 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(ResellerUniqueCodeResponse),
+    'data' : IDL.Opt(JobStatusResponse),
     'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_6 = IDL.Record({
+  const PlanTier = IDL.Variant({
+    'Pro' : IDL.Null,
+    'Enterprise' : IDL.Null,
+    'Free' : IDL.Null,
+  });
+  const PlanStatus = IDL.Variant({
+    'Active' : IDL.Null,
+    'PastDue' : IDL.Null,
+    'Cancelled' : IDL.Null,
+  });
+  const OrgPlan = IDL.Record({
+    'status' : PlanStatus,
+    'updated_by' : IDL.Principal,
+    'tier_changed_at' : IDL.Nat64,
+    'status_changed_at' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'tier' : PlanTier,
+  });
+  const ApiResponse_10 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(IDL.Vec(UserRole)),
+    'data' : IDL.Opt(OrgPlan),
     'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_7 = IDL.Record({
+  const ApiResponse_11 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(IDL.Vec(OrganizationPublic)),
+    'data' : IDL.Opt(IDL.Bool),
     'error' : IDL.Opt(ApiError),
   });
-  const ResellerPublic = IDL.Record({
-    'id' : IDL.Principal,
+  const SerialNumberStatus = IDL.Variant({
+    'Sold' : IDL.Null,
+    'Void' : IDL.Null,
+    'Printed' : IDL.Null,
+    'Shipped' : IDL.Null,
+    'Created' : IDL.Null,
+  });
+  const SerialStatusCheckResponse = IDL.Record({
+    'status' : IDL.Opt(SerialNumberStatus),
+    'product_id' : IDL.Opt(IDL.Principal),
+    'discontinued' : IDL.Bool,
+    'org_id' : IDL.Opt(IDL.Principal),
+    'voided' : IDL.Bool,
+    'org_name' : IDL.Opt(IDL.Text),
+    'suspected_cloned' : IDL.Bool,
+    'product_name' : IDL.Opt(IDL.Text),
+    'print_version' : IDL.Opt(IDL.Nat8),
+    'exists' : IDL.Bool,
+    'serial_no' : IDL.Principal,
+    'code_revoked' : IDL.Bool,
+  });
+  const ApiResponse_12 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SerialStatusCheckResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const VerificationDomainStatus = IDL.Variant({
+    'Failed' : IDL.Null,
+    'Verified' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const VerificationDomain = IDL.Record({
+    'last_error' : IDL.Opt(IDL.Text),
+    'status' : VerificationDomainStatus,
     'updated_at' : IDL.Nat64,
-    'ecommerce_urls' : IDL.Vec(Metadata),
-    'public_key' : IDL.Text,
-    'additional_metadata' : IDL.Opt(IDL.Vec(Metadata)),
-    'name' : IDL.Text,
-    'contact_email' : IDL.Opt(IDL.Text),
-    'certification_code' : IDL.Opt(IDL.Text),
-    'certification_timestamp' : IDL.Opt(IDL.Nat64),
+    'domain' : IDL.Text,
+    'org_id' : IDL.Principal,
     'created_at' : IDL.Nat64,
-    'user_id' : IDL.Principal,
-    'is_verified' : IDL.Bool,
-    'contact_phone' : IDL.Opt(IDL.Text),
-    'organization_id' : IDL.Principal,
+    'created_by' : IDL.Principal,
+    'last_checked_at' : IDL.Opt(IDL.Nat64),
+    'verified_at' : IDL.Opt(IDL.Nat64),
+    'proof_token' : IDL.Text,
   });
-  const ResellerCertificationPageContext = IDL.Record({
-    'certification_code' : IDL.Text,
-    'certification_timestamp' : IDL.Nat64,
-    'reseller_profile' : ResellerPublic,
-    'user_details' : UserPublic,
-    'associated_organization' : OrganizationPublic,
+  const VerificationDomainResponse = IDL.Record({
+    'domain' : VerificationDomain,
+    'proof_record_name' : IDL.Text,
+    'proof_record_value' : IDL.Text,
   });
-  const ApiResponse_8 = IDL.Record({
+  const ApiResponse_13 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(ResellerCertificationPageContext),
+    'data' : IDL.Opt(VerificationDomainResponse),
     'error' : IDL.Opt(ApiError),
   });
-  const NavigationContextResponse = IDL.Record({
-    'user_display_name' : IDL.Text,
-    'user_avatar_id' : IDL.Opt(IDL.Text),
-    'current_organization_name' : IDL.Opt(IDL.Text),
+  const ClaimGuestVerificationsResponse = IDL.Record({
+    'points_awarded' : IDL.Nat32,
+    'claimed_verifications' : IDL.Nat32,
   });
-  const ApiResponse_9 = IDL.Record({
+  const ApiResponse_14 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(NavigationContextResponse),
+    'data' : IDL.Opt(ClaimGuestVerificationsResponse),
     'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_10 = IDL.Record({
+  const FeatureFlagStatus = IDL.Record({
+    'global_enabled' : IDL.Opt(IDL.Bool),
+    'effective' : IDL.Bool,
+    'name' : IDL.Text,
+    'org_override' : IDL.Opt(IDL.Bool),
+  });
+  const ApiResponse_15 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(IDL.Text),
+    'data' : IDL.Opt(FeatureFlagStatus),
     'error' : IDL.Opt(ApiError),
   });
-  const GetOrganizationAnalyticRequest = IDL.Record({
+  const Product = IDL.Record({
+    'id' : IDL.Principal,
+    'sku' : IDL.Opt(IDL.Text),
+    'is_test' : IDL.Opt(IDL.Bool),
+    'warranty_days' : IDL.Opt(IDL.Nat32),
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'public_key' : IDL.Text,
+    'challenge_required' : IDL.Opt(IDL.Bool),
+    'metadata' : IDL.Vec(Metadata),
+    'name' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'allowed_markets' : IDL.Opt(IDL.Vec(IDL.Text)),
+    'description' : IDL.Text,
+    'discontinued_at' : IDL.Opt(IDL.Nat64),
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'sequential_serials' : IDL.Opt(IDL.Bool),
+    'product_number' : IDL.Opt(IDL.Nat64),
+    'successor_product_id' : IDL.Opt(IDL.Principal),
+    'category' : IDL.Text,
+    'recall' : IDL.Opt(ProductRecall),
+  });
+  const ProductResponse = IDL.Record({ 'product' : Product });
+  const ApiResponse_16 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ResellerProductsResponse = IDL.Record({
+    'updated_at' : IDL.Opt(IDL.Nat64),
+    'product_ids' : IDL.Vec(IDL.Principal),
+    'restricted' : IDL.Bool,
+    'reseller_id' : IDL.Principal,
+  });
+  const ApiResponse_17 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ResellerProductsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const RecallCampaignStatus = IDL.Variant({
+    'Closed' : IDL.Null,
+    'Active' : IDL.Null,
+    'Sending' : IDL.Null,
+  });
+  const RecallScope = IDL.Variant({
+    'PrintJob' : IDL.Record({ 'print_job_id' : IDL.Principal }),
+    'Product' : IDL.Null,
+    'Serials' : IDL.Record({ 'serial_nos' : IDL.Vec(IDL.Principal) }),
+  });
+  const RecallCampaign = IDL.Record({
+    'id' : IDL.Principal,
+    'status' : RecallCampaignStatus,
+    'updated_at' : IDL.Nat64,
+    'closed_at' : IDL.Opt(IDL.Nat64),
+    'closed_by' : IDL.Opt(IDL.Principal),
+    'product_id' : IDL.Principal,
+    'notices_sent' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'scope' : RecallScope,
+    'affected_owners' : IDL.Nat64,
+    'recall' : ProductRecall,
+  });
+  const ApiResponse_18 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(RecallCampaign),
+    'error' : IDL.Opt(ApiError),
+  });
+  const IdentityLinkResponse = IDL.Record({
+    'org_ids_added' : IDL.Nat32,
+    'verifications_transferred' : IDL.Nat32,
+    'user_id' : IDL.Principal,
+    'linked_principal' : IDL.Principal,
+    'merged_account' : IDL.Bool,
+    'points_transferred' : IDL.Nat32,
+  });
+  const ApiResponse_19 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IdentityLinkResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CompleteResellerProfileRequest = IDL.Record({
+    'ecommerce_urls' : IDL.Vec(Metadata),
+    'additional_metadata' : IDL.Opt(IDL.Vec(Metadata)),
+    'contact_email' : IDL.Opt(IDL.Text),
+    'contact_phone' : IDL.Opt(IDL.Text),
+    'target_organization_id' : IDL.Principal,
+    'reseller_name' : IDL.Text,
+  });
+  const OrganizationPublic = IDL.Record({
+    'id' : IDL.Principal,
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'metadata' : IDL.Vec(Metadata),
+    'name' : IDL.Text,
+    'description' : IDL.Text,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+  });
+  const ResellerContextDetails = IDL.Record({
+    'certification_code' : IDL.Opt(IDL.Text),
+    'certification_expires_at' : IDL.Opt(IDL.Nat64),
+    'certification_timestamp' : IDL.Opt(IDL.Nat64),
+    'associated_organization' : IDL.Opt(OrganizationPublic),
+    'is_profile_complete_and_verified' : IDL.Bool,
+  });
+  const UserRole = IDL.Variant({
+    'Customer' : IDL.Null,
+    'Reseller' : IDL.Null,
+    'Admin' : IDL.Null,
+    'BrandOwner' : IDL.Null,
+  });
+  const UserPublic = IDL.Record({
+    'id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'email' : IDL.Opt(IDL.Text),
+    'first_name' : IDL.Opt(IDL.Text),
+    'last_name' : IDL.Opt(IDL.Text),
+  });
+  const BrandOwnerContextDetails = IDL.Record({
+    'active_organization' : IDL.Opt(OrganizationPublic),
+    'has_organizations' : IDL.Bool,
+    'organizations' : IDL.Opt(IDL.Vec(OrganizationPublic)),
+  });
+  const AuthContextResponse = IDL.Record({
+    'reseller_details' : IDL.Opt(ResellerContextDetails),
+    'role' : IDL.Opt(UserRole),
+    'user' : IDL.Opt(UserPublic),
+    'brand_owner_details' : IDL.Opt(BrandOwnerContextDetails),
+    'is_registered' : IDL.Bool,
+  });
+  const ApiResponse_20 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(AuthContextResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CompleteVerificationChallengeRequest = IDL.Record({
+    'signature' : IDL.Text,
+    'locale' : IDL.Opt(IDL.Text),
+    'guest_token' : IDL.Opt(IDL.Text),
+    'country_code' : IDL.Opt(IDL.Text),
+    'challenge_id' : IDL.Principal,
+  });
+  const VerificationChallengeStatus = IDL.Variant({
+    'CodeRevoked' : IDL.Null,
+    'NotYetActive' : IDL.Null,
+    'InvalidResponse' : IDL.Null,
+    'VoidedSerial' : IDL.Null,
+    'Verified' : IDL.Null,
+    'Expired' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const ProductSuccessor = IDL.Record({
+    'product_id' : IDL.Principal,
+    'name' : IDL.Text,
+  });
+  const ProductVerificationStatus = IDL.Variant({
+    'CodeRevoked' : IDL.Null,
+    'Invalid' : IDL.Null,
+    'NotYetActive' : IDL.Null,
+    'MultipleVerification' : IDL.Null,
+    'FirstVerification' : IDL.Null,
+    'VoidedSerial' : IDL.Null,
+    'SupersededCode' : IDL.Null,
+  });
+  const AuthenticityFactorKind = IDL.Variant({
+    'Geography' : IDL.Null,
+    'Custody' : IDL.Null,
+    'Reports' : IDL.Null,
+    'ScanVelocity' : IDL.Null,
+    'Signature' : IDL.Null,
+  });
+  const AuthenticityFactor = IDL.Record({
+    'weight' : IDL.Nat32,
+    'kind' : AuthenticityFactorKind,
+    'detail' : IDL.Text,
+    'score' : IDL.Nat8,
+  });
+  const AuthenticityScore = IDL.Record({
+    'score' : IDL.Nat8,
+    'factors' : IDL.Vec(AuthenticityFactor),
+  });
+  const FederatedVerification = IDL.Record({
+    'manufacturer' : OrganizationPublic,
+    'brand' : OrganizationPublic,
+  });
+  const VerificationAnnotation = IDL.Variant({
+    'GreyMarketSuspected' : IDL.Null,
+  });
+  const CodeRevocationReason = IDL.Variant({
+    'Lost' : IDL.Null,
+    'Stolen' : IDL.Null,
+    'Misprinted' : IDL.Null,
+    'Other' : IDL.Null,
+  });
+  const RenderedVerificationMessage = IDL.Record({
+    'text' : IDL.Text,
+    'cta_url' : IDL.Opt(IDL.Text),
+    'cta_label' : IDL.Opt(IDL.Text),
+  });
+  const VerificationRewards = IDL.Record({
+    'special_reward' : IDL.Opt(IDL.Text),
+    'reward_description' : IDL.Opt(IDL.Text),
+    'is_first_verification' : IDL.Bool,
+    'points' : IDL.Nat32,
+  });
+  const BundleUnit = IDL.Record({
+    'revoked' : IDL.Bool,
+    'product_id' : IDL.Principal,
+    'valid' : IDL.Bool,
+    'voided' : IDL.Bool,
+    'user_serial_no' : IDL.Opt(IDL.Text),
+    'serial_no' : IDL.Principal,
+  });
+  const BundleSummary = IDL.Record({
+    'total_units' : IDL.Nat32,
+    'valid_units' : IDL.Nat32,
+    'units' : IDL.Vec(BundleUnit),
+    'outer_serial_no' : IDL.Principal,
+  });
+  const ProductVariant = IDL.Record({
+    'id' : IDL.Principal,
+    'sku' : IDL.Opt(IDL.Text),
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'product_id' : IDL.Principal,
+    'name' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'attributes' : IDL.Vec(Metadata),
+  });
+  const ProductVerification = IDL.Record({
+    'id' : IDL.Principal,
+    'status' : ProductVerificationStatus,
+    'is_test' : IDL.Opt(IDL.Bool),
+    'product_id' : IDL.Principal,
+    'reward_claimed' : IDL.Bool,
+    'metadata' : IDL.Vec(Metadata),
+    'reward_transaction_id' : IDL.Opt(IDL.Text),
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'variant_id' : IDL.Opt(IDL.Principal),
+    'print_version' : IDL.Nat8,
+    'annotations' : IDL.Opt(IDL.Vec(VerificationAnnotation)),
+    'country_code' : IDL.Opt(IDL.Text),
+    'serial_no' : IDL.Principal,
+  });
+  const ProductVerificationEnhancedResponse = IDL.Record({
+    'first_scanned_at' : IDL.Opt(IDL.Nat64),
+    'successor' : IDL.Opt(ProductSuccessor),
+    'status' : ProductVerificationStatus,
+    'is_test' : IDL.Bool,
+    'warning' : IDL.Opt(IDL.Text),
+    'authenticity' : IDL.Opt(AuthenticityScore),
+    'discontinued' : IDL.Bool,
+    'federation' : IDL.Opt(FederatedVerification),
+    'rate_limit' : IDL.Opt(RateLimitInfo),
+    'custody_status' : IDL.Opt(SerialNumberStatus),
+    'expiration' : IDL.Opt(IDL.Nat64),
+    'annotations' : IDL.Vec(VerificationAnnotation),
+    'revocation_reason' : IDL.Opt(CodeRevocationReason),
+    'message' : IDL.Opt(RenderedVerificationMessage),
+    'rewards' : IDL.Opt(VerificationRewards),
+    'scan_count' : IDL.Opt(IDL.Nat32),
+    'status_message' : IDL.Text,
+    'bundle' : IDL.Opt(BundleSummary),
+    'variant' : IDL.Opt(ProductVariant),
+    'verification' : IDL.Opt(ProductVerification),
+  });
+  const ChallengeVerificationResponse = IDL.Record({
+    'status' : VerificationChallengeStatus,
+    'result' : IDL.Opt(ProductVerificationEnhancedResponse),
+  });
+  const ApiResponse_21 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ChallengeVerificationResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_22 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Null),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CreateBundleRequest = IDL.Record({
+    'member_serial_nos' : IDL.Vec(IDL.Principal),
+    'outer_product_id' : IDL.Principal,
+    'outer_serial_no' : IDL.Principal,
+  });
+  const BundleMember = IDL.Record({
+    'product_id' : IDL.Principal,
+    'serial_no' : IDL.Principal,
+  });
+  const Bundle = IDL.Record({
+    'members' : IDL.Vec(BundleMember),
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'outer_product_id' : IDL.Principal,
+    'outer_serial_no' : IDL.Principal,
+  });
+  const BundleContentsResponse = IDL.Record({
+    'summary' : BundleSummary,
+    'bundle' : Bundle,
+  });
+  const ApiResponse_23 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BundleContentsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const NotificationTrigger = IDL.Variant({
+    'Invalid' : IDL.Null,
+    'AnyVerification' : IDL.Null,
+    'MultipleVerification' : IDL.Null,
+    'GreyMarket' : IDL.Null,
+    'SuspectedClone' : IDL.Null,
+  });
+  const CreateNotificationRuleRequest = IDL.Record({
+    'webhook_url' : IDL.Text,
+    'trigger' : NotificationTrigger,
+    'product_id' : IDL.Principal,
+    'min_scan_count' : IDL.Opt(IDL.Nat32),
+  });
+  const NotificationRule = IDL.Record({
+    'id' : IDL.Principal,
+    'webhook_url' : IDL.Text,
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'trigger' : NotificationTrigger,
+    'product_id' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'min_scan_count' : IDL.Opt(IDL.Nat32),
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'is_active' : IDL.Bool,
+  });
+  const NotificationRuleResponse = IDL.Record({ 'rule' : NotificationRule });
+  const ApiResponse_24 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(NotificationRuleResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OrganizationInput = IDL.Record({
+    'metadata' : IDL.Vec(Metadata),
+    'name' : IDL.Text,
+    'description' : IDL.Text,
+  });
+  const OrganizationContextResponse = IDL.Record({
+    'user_auth_context' : AuthContextResponse,
+    'organization' : OrganizationPublic,
+  });
+  const ApiResponse_25 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrganizationContextResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OrganizationResponse = IDL.Record({
+    'organization' : OrganizationPublic,
+  });
+  const ApiResponse_26 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrganizationResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ProductInput = IDL.Record({
+    'sku' : IDL.Opt(IDL.Text),
+    'metadata' : IDL.Vec(Metadata),
+    'name' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'description' : IDL.Text,
+    'category' : IDL.Text,
+  });
+  const ProductResult = IDL.Variant({
+    'none' : IDL.Null,
+    'error' : ApiError,
+    'product' : Product,
+  });
+  const CodeRevocation = IDL.Record({
+    'revoked_at' : IDL.Nat64,
+    'revoked_by' : IDL.Principal,
+    'print_version' : IDL.Nat8,
+    'reason' : CodeRevocationReason,
+  });
+  const ProductSerialNumber = IDL.Record({
+    'status' : IDL.Opt(SerialNumberStatus),
+    'revoked_codes' : IDL.Opt(IDL.Vec(CodeRevocation)),
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'product_id' : IDL.Principal,
+    'metadata' : IDL.Vec(Metadata),
+    'last_print_job_id' : IDL.Opt(IDL.Principal),
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'suspected_cloned' : IDL.Opt(IDL.Bool),
+    'variant_id' : IDL.Opt(IDL.Principal),
+    'print_version' : IDL.Nat8,
+    'sequence_no' : IDL.Opt(IDL.Nat64),
+    'user_serial_no' : IDL.Opt(IDL.Text),
+    'serial_no' : IDL.Principal,
+  });
+  const ProductSerialNumberResult = IDL.Variant({
+    'result' : ProductSerialNumber,
+    'error' : ApiError,
+  });
+  const CreateProductRequest = IDL.Record({
+    'sku' : IDL.Opt(IDL.Text),
+    'force' : IDL.Opt(IDL.Bool),
+    'metadata' : IDL.Vec(Metadata),
+    'name' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'description' : IDL.Text,
+    'category' : IDL.Text,
+  });
+  const CreateProductVariantRequest = IDL.Record({
+    'sku' : IDL.Opt(IDL.Text),
+    'product_id' : IDL.Principal,
+    'name' : IDL.Text,
+    'attributes' : IDL.Vec(Metadata),
+  });
+  const ApiResponse_27 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductVariant),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ReadonlyPrincipal = IDL.Record({
+    'principal' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'replaced_by' : IDL.Opt(IDL.Principal),
+    'label' : IDL.Opt(IDL.Text),
+    'revoked_at' : IDL.Opt(IDL.Nat64),
+    'revoked_by' : IDL.Opt(IDL.Principal),
+  });
+  const ApiResponse_28 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ReadonlyPrincipal),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ReportFrequency = IDL.Variant({
+    'Weekly' : IDL.Null,
+    'Monthly' : IDL.Null,
+  });
+  const CreateReportScheduleRequest = IDL.Record({
+    'recipient_emails' : IDL.Vec(IDL.Text),
+    'org_id' : IDL.Principal,
+    'delivery_url' : IDL.Text,
+    'frequency' : ReportFrequency,
+  });
+  const ReportSchedule = IDL.Record({
+    'id' : IDL.Principal,
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'recipient_emails' : IDL.Vec(IDL.Text),
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'delivery_url' : IDL.Text,
+    'last_run_at' : IDL.Opt(IDL.Nat64),
+    'next_run_at' : IDL.Nat64,
+    'is_active' : IDL.Bool,
+    'frequency' : ReportFrequency,
+  });
+  const ReportScheduleResponse = IDL.Record({ 'schedule' : ReportSchedule });
+  const ApiResponse_29 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ReportScheduleResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ReportFilters = IDL.Record({
+    'period_end' : IDL.Opt(IDL.Nat64),
+    'period_start' : IDL.Opt(IDL.Nat64),
+    'product_ids' : IDL.Opt(IDL.Vec(IDL.Principal)),
+  });
+  const CreateReportShareLinkRequest = IDL.Record({
+    'filters' : ReportFilters,
+    'ttl_seconds' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+  });
+  const ReportShareLink = IDL.Record({
+    'id' : IDL.Principal,
+    'filters' : ReportFilters,
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'revoked_at' : IDL.Opt(IDL.Nat64),
+    'expires_at' : IDL.Nat64,
+    'token_hash' : IDL.Text,
+  });
+  const ReportShareLinkResponse = IDL.Record({
+    'token' : IDL.Text,
+    'link' : ReportShareLink,
+  });
+  const ApiResponse_30 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ReportShareLinkResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CreateSerialNumbersBatchRequest = IDL.Record({
+    'user_serial_prefix' : IDL.Opt(IDL.Text),
+    'product_id' : IDL.Principal,
+    'count' : IDL.Opt(IDL.Nat32),
+    'capability_token' : IDL.Opt(IDL.Text),
+    'variant_id' : IDL.Opt(IDL.Principal),
+  });
+  const SerialNumbersBatchResponse = IDL.Record({
+    'serial_numbers' : IDL.Vec(ProductSerialNumber),
+  });
+  const ApiResponse_31 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SerialNumbersBatchResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const UserDetailsInput = IDL.Record({
+    'email' : IDL.Text,
+    'first_name' : IDL.Text,
+    'detail_meta' : IDL.Vec(Metadata),
+    'last_name' : IDL.Text,
+    'phone_no' : IDL.Text,
+  });
+  const User = IDL.Record({
+    'id' : IDL.Principal,
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'user_role' : IDL.Opt(UserRole),
+    'org_ids' : IDL.Vec(IDL.Principal),
+    'is_principal' : IDL.Bool,
+    'is_enabled' : IDL.Bool,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'email' : IDL.Opt(IDL.Text),
+    'active_org_id' : IDL.Opt(IDL.Principal),
+    'first_name' : IDL.Opt(IDL.Text),
+    'detail_meta' : IDL.Vec(Metadata),
+    'last_name' : IDL.Opt(IDL.Text),
+    'phone_no' : IDL.Opt(IDL.Text),
+    'session_keys' : IDL.Vec(IDL.Principal),
+  });
+  const UserResult = IDL.Variant({
+    'none' : IDL.Null,
+    'user' : User,
+    'error' : ApiError,
+  });
+  const ApiResponse_32 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductSerialNumber),
+    'error' : IDL.Opt(ApiError),
+  });
+  const DelegateVerificationRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'manufacturer_org_id' : IDL.Principal,
+  });
+  const VerificationDelegationStatus = IDL.Variant({
+    'Active' : IDL.Null,
+    'Revoked' : IDL.Null,
+  });
+  const VerificationDelegation = IDL.Record({
+    'status' : VerificationDelegationStatus,
+    'brand_org_id' : IDL.Principal,
+    'product_id' : IDL.Principal,
+    'manufacturer_org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'revoked_at' : IDL.Opt(IDL.Nat64),
+    'revoked_by' : IDL.Opt(IDL.Principal),
+  });
+  const ApiResponse_33 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(VerificationDelegation),
+    'error' : IDL.Opt(ApiError),
+  });
+  const DepositRewardPoolRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+    'amount' : IDL.Nat64,
+  });
+  const RewardPool = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'total_paid_out' : IDL.Nat64,
+    'ledger_balance_seen' : IDL.Nat64,
+    'balance' : IDL.Nat64,
+    'total_deposited' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'units_per_point' : IDL.Nat64,
+    'low_balance_threshold' : IDL.Nat64,
+    'low_balance_alerted' : IDL.Bool,
+  });
+  const RewardPoolBalanceResponse = IDL.Record({
+    'pool' : RewardPool,
+    'deposit_account_owner' : IDL.Principal,
+    'points_covered' : IDL.Nat64,
+    'deposit_subaccount' : IDL.Vec(IDL.Nat8),
+    'redemptions_require_pool' : IDL.Bool,
+  });
+  const ApiResponse_34 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(RewardPoolBalanceResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const StatusDescriptionResponse = IDL.Record({
+    'status' : ProductVerificationStatus,
+    'locale' : IDL.Text,
+    'message' : IDL.Text,
+  });
+  const ApiResponse_35 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(StatusDescriptionResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_36 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(User),
+    'error' : IDL.Opt(ApiError),
+  });
+  const KeyEscrowExport = IDL.Record({
+    'request_id' : IDL.Principal,
+    'algorithm' : IDL.Text,
+    'ciphertext' : IDL.Text,
+    'ephemeral_public_key' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'nonce' : IDL.Text,
+  });
+  const ApiResponse_37 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(KeyEscrowExport),
+    'error' : IDL.Opt(ApiError),
+  });
+  const GenerateResellerUniqueCodeRequest = IDL.Record({
+    'context' : IDL.Opt(IDL.Text),
+    'reseller_id' : IDL.Principal,
+  });
+  const ResellerUniqueCodeResponse = IDL.Record({
+    'context' : IDL.Opt(IDL.Text),
+    'unique_code' : IDL.Text,
+    'timestamp' : IDL.Nat64,
+    'reseller_id' : IDL.Principal,
+  });
+  const ApiResponse_38 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ResellerUniqueCodeResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ArchivedAggregatesQuery = IDL.Record({
+    'from_month' : IDL.Opt(IDL.Nat32),
+    'product_id' : IDL.Opt(IDL.Principal),
+    'org_id' : IDL.Principal,
+    'to_month' : IDL.Opt(IDL.Nat32),
+  });
+  const VerificationMonthlyAggregate = IDL.Record({
+    'month' : IDL.Nat32,
+    'updated_at' : IDL.Nat64,
+    'product_id' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'invalid_verifications' : IDL.Nat64,
+    'first_verifications' : IDL.Nat64,
+    'multiple_verifications' : IDL.Nat64,
+    'total_verifications' : IDL.Nat64,
+  });
+  const ApiResponse_39 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(VerificationMonthlyAggregate)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_40 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(UserRole)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const BiExportConfig = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'next_push_at' : IDL.Nat64,
+    'endpoint_url' : IDL.Text,
+    'last_period_end' : IDL.Opt(IDL.Nat64),
+    'is_active' : IDL.Bool,
+  });
+  const ApiResponse_41 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BiExportConfig),
+    'error' : IDL.Opt(ApiError),
+  });
+  const LowStockSerialPool = IDL.Record({
+    'product_id' : IDL.Principal,
+    'variant_id' : IDL.Opt(IDL.Principal),
+    'product_name' : IDL.Text,
+    'total_serials' : IDL.Nat64,
+    'available_serials' : IDL.Nat64,
+  });
+  const UserNotificationKind = IDL.Variant({
+    'RewardsPenalized' : IDL.Null,
+    'RedemptionPending' : IDL.Null,
+    'ResellerApplicationRejected' : IDL.Null,
+    'ProductRecalled' : IDL.Null,
+    'RewardGranted' : IDL.Null,
+    'RedemptionApproved' : IDL.Null,
+    'DisputeUpdated' : IDL.Null,
+    'CertificationIssued' : IDL.Null,
+    'CertificationRenewed' : IDL.Null,
+    'RedemptionRejected' : IDL.Null,
+    'KeyEscrowReady' : IDL.Null,
+    'RewardPoolLow' : IDL.Null,
+  });
+  const UserNotification = IDL.Record({
+    'id' : IDL.Principal,
+    'read_at' : IDL.Opt(IDL.Nat64),
+    'title' : IDL.Text,
+    'reference_id' : IDL.Opt(IDL.Principal),
+    'kind' : UserNotificationKind,
+    'created_at' : IDL.Nat64,
+    'user_id' : IDL.Principal,
+    'message' : IDL.Text,
+  });
+  const OrganizationAnalyticData = IDL.Record({
+    'revoked_codes' : IDL.Nat64,
+    'grey_market_verifications' : IDL.Nat64,
+    'total_products' : IDL.Nat64,
+    'unauthorized_reseller_product_checks' : IDL.Nat64,
+    'active_resellers' : IDL.Nat64,
+    'verifications_this_month' : IDL.Nat64,
+    'registered_owners' : IDL.Nat64,
+    'revoked_code_scans' : IDL.Nat64,
+    'reseller_product_checks' : IDL.Nat64,
+    'last_refreshed' : IDL.Nat64,
+  });
+  const ProductVerificationDetail = IDL.Record({
+    'status' : ProductVerificationStatus,
+    'user_email' : IDL.Opt(IDL.Text),
+    'product_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'product_name' : IDL.Text,
+    'serial_no' : IDL.Principal,
+  });
+  const BrandDashboardResponse = IDL.Record({
+    'pending_reseller_applications' : IDL.Vec(Reseller),
+    'low_stock_serial_pools' : IDL.Vec(LowStockSerialPool),
+    'generated_at' : IDL.Nat64,
+    'notifications' : IDL.Vec(UserNotification),
+    'org_id' : IDL.Principal,
+    'analytics' : OrganizationAnalyticData,
+    'recent_verifications' : IDL.Vec(ProductVerificationDetail),
+    'unread_notifications' : IDL.Nat32,
+  });
+  const ApiResponse_42 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BrandDashboardResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const LlmProviderConfig = IDL.Record({
+    'model' : IDL.Text,
+    'provider' : IDL.Text,
+    'api_key' : IDL.Text,
+    'api_host' : IDL.Text,
+  });
+  const OutcallResponseLimits = IDL.Record({
+    'scraper_max_response_bytes' : IDL.Nat64,
+    'webhook_max_response_bytes' : IDL.Nat64,
+    'openai_max_response_bytes' : IDL.Nat64,
+  });
+  const ClientVersionPolicy = IDL.Record({
+    'min_version' : IDL.Text,
+    'download_url' : IDL.Opt(IDL.Text),
+  });
+  const RateLimitConfig = IDL.Record({
+    'max_attempts_per_window' : IDL.Nat32,
+    'window_duration_seconds' : IDL.Nat64,
+  });
+  const CyclesAlertConfig = IDL.Record({
+    'webhook_url' : IDL.Opt(IDL.Text),
+    'critical_threshold' : IDL.Nat,
+    'warning_threshold' : IDL.Nat,
+  });
+  const ModerationConfig = IDL.Record({
+    'llm_moderation' : IDL.Bool,
+    'blocklist' : IDL.Vec(IDL.Text),
+  });
+  const FeatureFlag = IDL.Record({ 'name' : IDL.Text, 'enabled' : IDL.Bool });
+  const CanisterConfig = IDL.Record({
+    'llm' : LlmProviderConfig,
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'outcall_limits' : IDL.Opt(OutcallResponseLimits),
+    'client_version_policy' : IDL.Opt(ClientVersionPolicy),
+    'rate_limit' : RateLimitConfig,
+    'scraper_url' : IDL.Text,
+    'email_relay_url' : IDL.Opt(IDL.Text),
+    'required_admin_approvals' : IDL.Opt(IDL.Nat32),
+    'live_gateways' : IDL.Opt(IDL.Vec(IDL.Principal)),
+    'cycles_alerts' : IDL.Opt(CyclesAlertConfig),
+    'ledger_canister_id' : IDL.Opt(IDL.Principal),
+    'moderation' : IDL.Opt(ModerationConfig),
+    'feature_flags' : IDL.Vec(FeatureFlag),
+  });
+  const ApiResponse_43 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(CanisterConfig),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ExternalService = IDL.Variant({
+    'OpenAi' : IDL.Null,
+    'DnsResolver' : IDL.Null,
+    'Scraper' : IDL.Null,
+  });
+  const BreakerState = IDL.Variant({
+    'Open' : IDL.Null,
+    'Closed' : IDL.Null,
+    'HalfOpen' : IDL.Null,
+  });
+  const CircuitBreaker = IDL.Record({
+    'service' : ExternalService,
+    'half_open_at' : IDL.Opt(IDL.Nat64),
+    'opened_at' : IDL.Opt(IDL.Nat64),
+    'probe_started_at' : IDL.Opt(IDL.Nat64),
+    'total_short_circuited' : IDL.Nat64,
+    'last_success_at' : IDL.Opt(IDL.Nat64),
+    'state' : BreakerState,
+    'last_failure_at' : IDL.Opt(IDL.Nat64),
+    'consecutive_failures' : IDL.Nat32,
+    'last_failure' : IDL.Opt(IDL.Text),
+    'consecutive_opens' : IDL.Nat32,
+  });
+  const ApiResponse_44 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(CircuitBreaker)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CyclesLevel = IDL.Variant({
+    'Healthy' : IDL.Null,
+    'Critical' : IDL.Null,
+    'Warning' : IDL.Null,
+  });
+  const CyclesSample = IDL.Record({
+    'balance' : IDL.Nat,
+    'alerted' : IDL.Bool,
+    'level' : CyclesLevel,
+    'timestamp' : IDL.Nat64,
+  });
+  const ApiResponse_45 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(CyclesSample)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const VerifiedBrandBadge = IDL.Record({
+    'signature' : IDL.Text,
+    'issued_at' : IDL.Nat64,
+    'public_key' : IDL.Text,
+    'org_id' : IDL.Principal,
+  });
+  const DirectoryListing = IDL.Record({
+    'is_public' : IDL.Bool,
+    'website_url' : IDL.Opt(IDL.Text),
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'featured' : IDL.Bool,
+    'tagline' : IDL.Opt(IDL.Text),
+    'org_id' : IDL.Principal,
+    'logo_url' : IDL.Opt(IDL.Text),
+    'badge' : IDL.Opt(VerifiedBrandBadge),
+    'suspended' : IDL.Bool,
+    'suspension_reason' : IDL.Opt(IDL.Text),
+  });
+  const ApiResponse_46 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(DirectoryListing),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ErrorCodeCount = IDL.Record({ 'code' : IDL.Text, 'count' : IDL.Nat64 });
+  const EndpointMetricsSummary = IDL.Record({
+    'error_codes' : IDL.Vec(ErrorCodeCount),
+    'endpoint' : IDL.Text,
+    'last_called_at' : IDL.Nat64,
+    'calls' : IDL.Nat64,
+    'sampled_calls' : IDL.Nat32,
+    'errors' : IDL.Nat64,
+    'since' : IDL.Nat64,
+    'error_rate_percent' : IDL.Float64,
+    'mean_instructions' : IDL.Nat64,
+    'p50_instructions' : IDL.Nat64,
+    'max_instructions' : IDL.Nat64,
+    'p95_instructions' : IDL.Nat64,
+  });
+  const ApiResponse_47 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(EndpointMetricsSummary)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const JobResultChunkResponse = IDL.Record({
+    'is_last' : IDL.Bool,
+    'chunk_index' : IDL.Nat32,
+    'total_chunks' : IDL.Nat32,
+    'rows' : IDL.Vec(IDL.Text),
+    'job_id' : IDL.Principal,
+    'columns' : IDL.Vec(IDL.Text),
+  });
+  const ApiResponse_48 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(JobResultChunkResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const QrErrorCorrection = IDL.Variant({
+    'H' : IDL.Null,
+    'L' : IDL.Null,
+    'M' : IDL.Null,
+    'Q' : IDL.Null,
+  });
+  const QrPlacement = IDL.Record({
+    'size_mm' : IDL.Float64,
+    'x_mm' : IDL.Float64,
+    'y_mm' : IDL.Float64,
+  });
+  const LabelTemplate = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'product_id' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'qr_error_correction' : QrErrorCorrection,
+    'version' : IDL.Nat32,
+    'qr_version' : IDL.Opt(IDL.Nat8),
+    'height_mm' : IDL.Float64,
+    'width_mm' : IDL.Float64,
+    'show_human_readable_code' : IDL.Bool,
+    'artwork_asset_url' : IDL.Opt(IDL.Text),
+    'qr_placement' : QrPlacement,
+  });
+  const ApiResponse_49 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(LabelTemplate),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PaginationRequest = IDL.Record({
+    'cursor' : IDL.Opt(IDL.Text),
+    'page' : IDL.Opt(IDL.Nat32),
+    'limit' : IDL.Opt(IDL.Nat32),
+  });
+  const PaginationResponse = IDL.Record({
+    'total' : IDL.Nat64,
+    'page' : IDL.Nat32,
+    'limit' : IDL.Nat32,
+    'next_cursor' : IDL.Opt(IDL.Text),
+    'has_more' : IDL.Bool,
+  });
+  const RewardPenalty = IDL.Record({
+    'id' : IDL.Principal,
+    'balance_after' : IDL.Nat32,
+    'org_id' : IDL.Opt(IDL.Principal),
+    'created_at' : IDL.Nat64,
+    'user_id' : IDL.Principal,
+    'penalized_by' : IDL.Principal,
+    'points_requested' : IDL.Nat32,
+    'points_deducted' : IDL.Nat32,
+    'reason' : IDL.Text,
+  });
+  const UnclaimedReward = IDL.Record({
+    'product_id' : IDL.Principal,
+    'product_name' : IDL.Text,
+    'verified_at' : IDL.Nat64,
+    'serial_no' : IDL.Principal,
+    'verification_id' : IDL.Principal,
+  });
+  const RedeemedCoupon = IDL.Record({
+    'code' : IDL.Text,
+    'description' : IDL.Opt(IDL.Text),
+  });
+  const ConsumerVerificationActivity = IDL.Record({
+    'status' : ProductVerificationStatus,
+    'product_id' : IDL.Principal,
+    'reward_claimed' : IDL.Bool,
+    'reward_transaction_id' : IDL.Opt(IDL.Text),
+    'org_id' : IDL.Opt(IDL.Principal),
+    'created_at' : IDL.Nat64,
+    'product_name' : IDL.Text,
+    'coupon' : IDL.Opt(RedeemedCoupon),
+    'serial_no' : IDL.Principal,
+    'verification_id' : IDL.Principal,
+  });
+  const ConsumerActivityResponse = IDL.Record({
+    'owned_products' : IDL.Vec(MyProductEntry),
+    'pagination' : PaginationResponse,
+    'reward_penalties' : IDL.Vec(RewardPenalty),
+    'reward_points' : IDL.Nat32,
+    'pending_redemptions' : IDL.Vec(PendingRedemption),
+    'unclaimed_rewards' : IDL.Vec(UnclaimedReward),
+    'verifications' : IDL.Vec(ConsumerVerificationActivity),
+    'first_verifications' : IDL.Nat32,
+    'total_verifications' : IDL.Nat32,
+  });
+  const ApiResponse_50 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ConsumerActivityResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const Permission = IDL.Variant({
+    'ManageVerifications' : IDL.Null,
+    'ReadOrganization' : IDL.Null,
+    'ReadProduct' : IDL.Null,
+    'AdminAccess' : IDL.Null,
+    'ReadReseller' : IDL.Null,
+    'ReadSelf' : IDL.Null,
+    'ReadUser' : IDL.Null,
+    'WriteReseller' : IDL.Null,
+    'VerifyProduct' : IDL.Null,
+    'WriteProduct' : IDL.Null,
+    'RedeemRewards' : IDL.Null,
+    'WriteOrganization' : IDL.Null,
+    'WriteSelf' : IDL.Null,
+    'WriteUser' : IDL.Null,
+  });
+  const OrgCapabilities = IDL.Record({
+    'permissions' : IDL.Vec(Permission),
+    'features' : IDL.Vec(FeatureFlag),
+    'org_id' : IDL.Principal,
+  });
+  const CapabilitiesResponse = IDL.Record({
+    'permissions' : IDL.Vec(Permission),
+    'features' : IDL.Vec(FeatureFlag),
+    'is_admin' : IDL.Bool,
+    'role' : IDL.Opt(UserRole),
+    'is_enabled' : IDL.Bool,
+    'is_registered' : IDL.Bool,
+    'organizations' : IDL.Vec(OrgCapabilities),
+  });
+  const ApiResponse_51 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(CapabilitiesResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_52 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(OrganizationPublic)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PlanLimits = IDL.Record({
+    'max_products' : IDL.Opt(IDL.Nat64),
+    'analytics_retention_days' : IDL.Nat32,
+    'max_serials_per_month' : IDL.Nat32,
+    'max_webhooks' : IDL.Opt(IDL.Nat32),
+  });
+  const PlanUsageResponse = IDL.Record({
+    'serials_this_month' : IDL.Nat32,
+    'plan' : OrgPlan,
+    'webhooks' : IDL.Nat32,
+    'serials_window_resets_at' : IDL.Nat64,
+    'effective_tier' : PlanTier,
+    'serials_limit' : IDL.Nat32,
+    'products' : IDL.Nat64,
+    'limits' : PlanLimits,
+  });
+  const ApiResponse_53 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PlanUsageResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ResellerPublic = IDL.Record({
+    'id' : IDL.Principal,
+    'updated_at' : IDL.Nat64,
+    'ecommerce_urls' : IDL.Vec(Metadata),
+    'public_key' : IDL.Text,
+    'additional_metadata' : IDL.Opt(IDL.Vec(Metadata)),
+    'name' : IDL.Text,
+    'contact_email' : IDL.Opt(IDL.Text),
+    'certification_code' : IDL.Opt(IDL.Text),
+    'certification_expires_at' : IDL.Opt(IDL.Nat64),
+    'certification_timestamp' : IDL.Opt(IDL.Nat64),
+    'created_at' : IDL.Nat64,
+    'user_id' : IDL.Principal,
+    'is_verified' : IDL.Bool,
+    'contact_phone' : IDL.Opt(IDL.Text),
+    'organization_id' : IDL.Principal,
+  });
+  const ResellerCertificationPageContext = IDL.Record({
+    'certification_code' : IDL.Text,
+    'certification_expires_at' : IDL.Opt(IDL.Nat64),
+    'certification_timestamp' : IDL.Nat64,
+    'reseller_profile' : ResellerPublic,
+    'user_details' : UserPublic,
+    'associated_organization' : OrganizationPublic,
+  });
+  const ApiResponse_54 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ResellerCertificationPageContext),
+    'error' : IDL.Opt(ApiError),
+  });
+  const NavigationContextResponse = IDL.Record({
+    'user_display_name' : IDL.Text,
+    'user_avatar_id' : IDL.Opt(IDL.Text),
+    'current_organization_name' : IDL.Opt(IDL.Text),
+  });
+  const ApiResponse_55 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(NavigationContextResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_56 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Text),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PrintCodePolicy = IDL.Variant({
+    'UnprintedOnly' : IDL.Null,
+    'ReprintAllowed' : IDL.Null,
+  });
+  const SerialBatchDefaults = IDL.Record({
+    'user_serial_prefix' : IDL.Opt(IDL.Text),
+    'batch_size' : IDL.Opt(IDL.Nat32),
+    'code_policy' : IDL.Opt(PrintCodePolicy),
+    'activation_delay_seconds' : IDL.Opt(IDL.Nat64),
+    'label_template_product_id' : IDL.Opt(IDL.Principal),
+  });
+  const ApiResponse_57 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SerialBatchDefaults),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OnboardingStepKind = IDL.Variant({
+    'BrandingSet' : IDL.Null,
+    'FirstProductCreated' : IDL.Null,
+    'WebhookConfigured' : IDL.Null,
+    'FirstResellerCertified' : IDL.Null,
+    'SerialsPrinted' : IDL.Null,
+    'RewardPolicySet' : IDL.Null,
+  });
+  const OnboardingStep = IDL.Record({
+    'step' : OnboardingStepKind,
+    'completed' : IDL.Bool,
+  });
+  const OrgOnboardingStatusResponse = IDL.Record({
+    'total_steps' : IDL.Nat32,
+    'next_step' : IDL.Opt(OnboardingStepKind),
+    'org_id' : IDL.Principal,
+    'steps' : IDL.Vec(OnboardingStep),
+    'completed_steps' : IDL.Nat32,
+  });
+  const ApiResponse_58 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrgOnboardingStatusResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const VerificationPrivacyPolicy = IDL.Record({
+    'show_custody_chain' : IDL.Bool,
+    'show_reseller_info' : IDL.Bool,
+    'show_scan_count' : IDL.Bool,
+    'show_first_scan_date' : IDL.Bool,
+  });
+  const AuthenticityWeights = IDL.Record({
+    'signature' : IDL.Nat32,
+    'custody' : IDL.Nat32,
+    'geography' : IDL.Nat32,
+    'scan_velocity' : IDL.Nat32,
+    'reports' : IDL.Nat32,
+  });
+  const OrgSettings = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'serial_batch_defaults' : IDL.Opt(SerialBatchDefaults),
+    'duplicate_scan_threshold' : IDL.Opt(IDL.Nat32),
+    'client_version_policy' : IDL.Opt(ClientVersionPolicy),
+    'org_id' : IDL.Principal,
+    'certification_grace_period_seconds' : IDL.Opt(IDL.Nat64),
+    'public_stats_enabled' : IDL.Opt(IDL.Bool),
+    'redemption_approval_threshold' : IDL.Opt(IDL.Nat32),
+    'single_use_reseller_codes' : IDL.Opt(IDL.Bool),
+    'certification_validity_seconds' : IDL.Opt(IDL.Nat64),
+    'verification_privacy' : IDL.Opt(VerificationPrivacyPolicy),
+    'authenticity_weights' : IDL.Opt(AuthenticityWeights),
+  });
+  const ApiResponse_59 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrgSettings),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OrgUsageResponse = IDL.Record({
+    'outcalls_limit' : IDL.Nat32,
+    'outcalls_window_resets_at' : IDL.Nat64,
+    'oversized_responses_today' : IDL.Nat32,
+    'serials_this_month' : IDL.Nat32,
+    'org_id' : IDL.Principal,
+    'storage_limit' : IDL.Nat64,
+    'serials_window_resets_at' : IDL.Nat64,
+    'outcalls_today' : IDL.Nat32,
+    'storage_bytes' : IDL.Nat64,
+    'serials_limit' : IDL.Nat32,
+  });
+  const ApiResponse_60 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrgUsageResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_61 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrganizationAnalyticData),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OrganizationResult = IDL.Variant({
+    'error' : ApiError,
+    'organization' : OrganizationPublic,
+  });
+  const PrivateKeyResult = IDL.Variant({
+    'key' : IDL.Text,
+    'error' : ApiError,
+  });
+  const ProductOwnershipCount = IDL.Record({
+    'product_id' : IDL.Principal,
+    'registered_owners' : IDL.Nat64,
+    'product_name' : IDL.Text,
+  });
+  const OwnershipStatsResponse = IDL.Record({
+    'total_registered' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'products' : IDL.Vec(ProductOwnershipCount),
+  });
+  const ApiResponse_62 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OwnershipStatsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CyclesStatus = IDL.Record({
+    'balance' : IDL.Nat,
+    'last_alert_at' : IDL.Opt(IDL.Nat64),
+    'alert_webhook_configured' : IDL.Bool,
+    'critical_threshold' : IDL.Nat,
+    'level' : CyclesLevel,
+    'last_checked_at' : IDL.Opt(IDL.Nat64),
+    'warning_threshold' : IDL.Nat,
+  });
+  const PlatformMetricsResponse = IDL.Record({
+    'generated_at' : IDL.Nat64,
+    'cycles' : CyclesStatus,
+    'users' : IDL.Nat64,
+    'products' : IDL.Nat64,
+    'organizations' : IDL.Nat64,
+  });
+  const ApiResponse_63 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PlatformMetricsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ProductRewardStats = IDL.Record({
+    'redemption_count' : IDL.Nat64,
+    'updated_at' : IDL.Nat64,
+    'product_id' : IDL.Principal,
+    'promotion_points_issued' : IDL.Nat64,
+    'unique_rewarded_users' : IDL.Nat64,
+    'points_issued' : IDL.Nat64,
+    'points_redeemed' : IDL.Nat64,
+  });
+  const ApiResponse_64 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductRewardStats),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PublicBrandStats = IDL.Record({
+    'countries_reached' : IDL.Nat64,
+    'published_at' : IDL.Nat64,
+    'version' : IDL.Nat64,
+    'distinct_products' : IDL.Nat64,
+    'total_verifications' : IDL.Nat64,
+  });
+  const PublicBrandStatsResponse = IDL.Record({
+    'org_id' : IDL.Principal,
+    'max_age_seconds' : IDL.Nat64,
+    'version' : IDL.Nat64,
+    'stats' : IDL.Opt(PublicBrandStats),
+    'not_modified' : IDL.Bool,
+  });
+  const ApiResponse_65 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PublicBrandStatsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const RecallCampaignStatusResponse = IDL.Record({
+    'campaign' : RecallCampaign,
+    'notices_sent' : IDL.Nat64,
+    'relay_skipped' : IDL.Nat64,
+    'inbox_read' : IDL.Nat64,
+    'relay_queued' : IDL.Nat64,
+    'relay_delivered' : IDL.Nat64,
+    'relay_failed' : IDL.Nat64,
+    'owners_reached' : IDL.Nat64,
+    'reach_percent' : IDL.Nat8,
+  });
+  const ApiResponse_66 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(RecallCampaignStatusResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const LogLevel = IDL.Variant({
+    'Error' : IDL.Null,
+    'Info' : IDL.Null,
+    'Warn' : IDL.Null,
+    'Debug' : IDL.Null,
+  });
+  const LogEntry = IDL.Record({
+    'seq' : IDL.Nat64,
+    'level' : LogLevel,
+    'message' : IDL.Text,
+    'timestamp' : IDL.Nat64,
+    'module' : IDL.Text,
+  });
+  const ApiResponse_67 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(LogEntry)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ReportProductStat = IDL.Record({
+    'product_id' : IDL.Principal,
+    'product_name' : IDL.Text,
+    'verification_count' : IDL.Nat64,
+  });
+  const Report = IDL.Record({
+    'id' : IDL.Principal,
+    'period_end' : IDL.Nat64,
+    'generated_at' : IDL.Nat64,
+    'suspicious_serials' : IDL.Nat64,
     'org_id' : IDL.Principal,
+    'period_start' : IDL.Nat64,
+    'delivery_error' : IDL.Opt(IDL.Text),
+    'frequency' : ReportFrequency,
+    'invalid_verifications' : IDL.Nat64,
+    'top_products' : IDL.Vec(ReportProductStat),
+    'first_verifications' : IDL.Nat64,
+    'delivered_at' : IDL.Opt(IDL.Nat64),
+    'multiple_verifications' : IDL.Nat64,
+    'total_verifications' : IDL.Nat64,
+    'schedule_id' : IDL.Principal,
   });
-  const OrganizationAnalyticData = IDL.Record({
-    'total_products' : IDL.Nat64,
-    'active_resellers' : IDL.Nat64,
-    'verifications_this_month' : IDL.Nat64,
+  const ApiResponse_68 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(Report),
+    'error' : IDL.Opt(ApiError),
+  });
+  const SharedReportResponse = IDL.Record({
+    'filters' : ReportFilters,
+    'period_end' : IDL.Nat64,
+    'generated_at' : IDL.Nat64,
+    'suspicious_serials' : IDL.Nat64,
+    'period_start' : IDL.Nat64,
+    'organization_name' : IDL.Text,
+    'invalid_verifications' : IDL.Nat64,
+    'expires_at' : IDL.Nat64,
+    'top_products' : IDL.Vec(ReportProductStat),
+    'first_verifications' : IDL.Nat64,
+    'multiple_verifications' : IDL.Nat64,
+    'total_verifications' : IDL.Nat64,
+  });
+  const ApiResponse_69 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SharedReportResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const SerialRangeStatsResponse = IDL.Record({
+    'last_scan_at' : IDL.Opt(IDL.Nat64),
+    'product_id' : IDL.Principal,
+    'coverage_percent' : IDL.Float64,
+    'to_seq' : IDL.Nat64,
+    'from_seq' : IDL.Nat64,
+    'serials_in_range' : IDL.Nat64,
+    'scanned_serials' : IDL.Nat64,
+    'first_scan_at' : IDL.Opt(IDL.Nat64),
+    'total_verifications' : IDL.Nat64,
+  });
+  const ApiResponse_70 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SerialRangeStatsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OrgProbeActivity = IDL.Record({
+    'last_seen_at' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'suspicious_hits' : IDL.Nat64,
+    'first_seen_at' : IDL.Nat64,
+    'recent_probers' : IDL.Vec(IDL.Principal),
+  });
+  const SuspiciousActivityResponse = IDL.Record({
+    'probe_activity' : IDL.Opt(OrgProbeActivity),
+    'pagination' : IDL.Opt(PaginationResponse),
+    'suspected_cloned_serials' : IDL.Vec(ProductSerialNumber),
+  });
+  const ApiResponse_71 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SuspiciousActivityResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const TermsVersion = IDL.Record({
+    'seq' : IDL.Nat64,
+    'url' : IDL.Text,
+    'published_at' : IDL.Nat64,
+    'published_by' : IDL.Principal,
+    'version' : IDL.Text,
+  });
+  const TermsStatusResponse = IDL.Record({
+    'acceptances' : IDL.Vec(TermsAcceptance),
+    'accepted' : IDL.Bool,
+    'current' : IDL.Opt(TermsVersion),
+  });
+  const ApiResponse_72 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(TermsStatusResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_73 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Opt(VerificationDomainResponse)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const VerificationHeatmapKind = IDL.Variant({
+    'All' : IDL.Null,
+    'InvalidOnly' : IDL.Null,
+    'FirstOnly' : IDL.Null,
+  });
+  const VerificationHeatmapRange = IDL.Record({
+    'period_end' : IDL.Opt(IDL.Nat64),
+    'kind' : IDL.Opt(VerificationHeatmapKind),
+    'period_start' : IDL.Opt(IDL.Nat64),
+    'utc_offset_hours' : IDL.Opt(IDL.Int8),
+  });
+  const VerificationHeatmapResponse = IDL.Record({
+    'total' : IDL.Nat64,
+    'period_end' : IDL.Nat64,
+    'kind' : VerificationHeatmapKind,
+    'period_start' : IDL.Nat64,
+    'utc_offset_hours' : IDL.Int8,
+    'counts' : IDL.Vec(IDL.Vec(IDL.Nat64)),
+  });
+  const ApiResponse_74 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(VerificationHeatmapResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_75 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(RateLimitInfo),
+    'error' : IDL.Opt(ApiError),
+  });
+  const RetiredVerificationHandling = IDL.Variant({
+    'Archive' : IDL.Null,
+    'Delete' : IDL.Null,
+  });
+  const VerificationRetentionPolicy = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'handling' : RetiredVerificationHandling,
+    'retention_months' : IDL.Opt(IDL.Nat32),
+  });
+  const ApiResponse_76 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(VerificationRetentionPolicy),
+    'error' : IDL.Opt(ApiError),
+  });
+  const UpdateResellerProductsRequest = IDL.Record({
+    'product_ids' : IDL.Vec(IDL.Principal),
+    'reseller_id' : IDL.Principal,
+  });
+  const ProbeRecord = IDL.Record({
+    'principal' : IDL.Principal,
+    'window_start' : IDL.Nat64,
+    'misses' : IDL.Nat32,
+    'ban_count' : IDL.Nat32,
+    'probes' : IDL.Nat32,
+    'banned_until' : IDL.Opt(IDL.Nat64),
+  });
+  const ApiResponse_77 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProbeRecord),
+    'error' : IDL.Opt(ApiError),
+  });
+  const AuditLogEntry = IDL.Record({
+    'action' : IDL.Text,
+    'metadata' : IDL.Vec(Metadata),
+    'user_id' : IDL.Principal,
+    'resource_type' : IDL.Text,
+    'timestamp' : IDL.Nat64,
+    'resource_id' : IDL.Principal,
+    'success' : IDL.Bool,
+  });
+  const AuditLogListResponse = IDL.Record({
+    'pagination' : IDL.Opt(PaginationResponse),
+    'entries' : IDL.Vec(AuditLogEntry),
+  });
+  const ApiResponse_78 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(AuditLogListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const AdminOperationsResponse = IDL.Record({
+    'pagination' : IDL.Opt(PaginationResponse),
+    'operations' : IDL.Vec(AdminOperation),
+  });
+  const ApiResponse_79 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(AdminOperationsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ListBiExportDeliveriesRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'org_id' : IDL.Principal,
+  });
+  const BiExportDeliveryStatus = IDL.Variant({
+    'Failed' : IDL.Null,
+    'Delivered' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const BiExportDelivery = IDL.Record({
+    'id' : IDL.Principal,
+    'last_error' : IDL.Opt(IDL.Text),
+    'status' : BiExportDeliveryStatus,
+    'period_end' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'period_start' : IDL.Nat64,
+    'attempts' : IDL.Nat32,
+    'created_at' : IDL.Nat64,
+    'schema_version' : IDL.Nat32,
+    'payload_bytes' : IDL.Nat64,
+    'delivered_at' : IDL.Opt(IDL.Nat64),
+  });
+  const BiExportDeliveriesListResponse = IDL.Record({
+    'deliveries' : IDL.Vec(BiExportDelivery),
+    'pagination' : PaginationResponse,
+  });
+  const ApiResponse_80 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BiExportDeliveriesListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const CapabilityOperation = IDL.Variant({
+    'PrintSerials' : IDL.Null,
+    'UpdateSerialStatus' : IDL.Null,
+    'CreateSerials' : IDL.Null,
+  });
+  const CapabilityClaims = IDL.Record({
+    'issued_at' : IDL.Nat64,
+    'token_id' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'product_ids' : IDL.Vec(IDL.Principal),
+    'operations' : IDL.Vec(CapabilityOperation),
+    'holder' : IDL.Principal,
+    'expires_at' : IDL.Nat64,
+  });
+  const CapabilityToken = IDL.Record({
+    'claims' : CapabilityClaims,
+    'issued_by' : IDL.Principal,
+    'last_used_at' : IDL.Opt(IDL.Nat64),
+    'revoked_at' : IDL.Opt(IDL.Nat64),
+    'revoked_by' : IDL.Opt(IDL.Principal),
+    'use_count' : IDL.Nat64,
+  });
+  const ApiResponse_81 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(CapabilityToken)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ListDisputesRequest = IDL.Record({
+    'status' : IDL.Opt(DisputeStatus),
+    'pagination' : IDL.Opt(PaginationRequest),
+    'org_id' : IDL.Opt(IDL.Principal),
+  });
+  const DisputesListResponse = IDL.Record({
+    'disputes' : IDL.Vec(Dispute),
+    'pagination' : PaginationResponse,
+  });
+  const ApiResponse_82 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(DisputesListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_83 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(FeatureFlagStatus)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_84 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(Job)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const MetadataValueType = IDL.Variant({
+    'Url' : IDL.Null,
+    'Date' : IDL.Null,
+    'Text' : IDL.Null,
+    'Boolean' : IDL.Null,
+    'Decimal' : IDL.Null,
+    'Integer' : IDL.Null,
+  });
+  const MetadataFieldSchema = IDL.Record({
+    'key' : IDL.Text,
+    'value_type' : MetadataValueType,
+    'allowed_values' : IDL.Opt(IDL.Vec(IDL.Text)),
+    'required' : IDL.Bool,
+  });
+  const MetadataSchema = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'org_id' : IDL.Principal,
+    'product_fields' : IDL.Vec(MetadataFieldSchema),
+    'serial_fields' : IDL.Vec(MetadataFieldSchema),
+    'category' : IDL.Text,
+  });
+  const ApiResponse_85 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(MetadataSchema)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ModerationStatus = IDL.Variant({
+    'Approved' : IDL.Null,
+    'Rejected' : IDL.Null,
+    'Flagged' : IDL.Null,
+  });
+  const ModerationSource = IDL.Variant({
+    'Llm' : IDL.Null,
+    'Blocklist' : IDL.Null,
+  });
+  const ModeratedField = IDL.Record({ 'field' : IDL.Text, 'text' : IDL.Text });
+  const ModeratedEntityKind = IDL.Variant({
+    'Reseller' : IDL.Null,
+    'Organization' : IDL.Null,
+    'Product' : IDL.Null,
+  });
+  const ModerationCase = IDL.Record({
+    'status' : ModerationStatus,
+    'source' : ModerationSource,
+    'org_id' : IDL.Principal,
+    'reviewed_at' : IDL.Opt(IDL.Nat64),
+    'reviewed_by' : IDL.Opt(IDL.Principal),
+    'fields' : IDL.Vec(ModeratedField),
+    'matched' : IDL.Vec(IDL.Text),
+    'flagged_at' : IDL.Nat64,
+    'entity_kind' : ModeratedEntityKind,
+    'entity_id' : IDL.Principal,
+    'review_note' : IDL.Opt(IDL.Text),
+    'submitted_by' : IDL.Principal,
+  });
+  const ModerationCasesResponse = IDL.Record({
+    'cases' : IDL.Vec(ModerationCase),
+    'pagination' : IDL.Opt(PaginationResponse),
+  });
+  const ApiResponse_86 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ModerationCasesResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ListNotificationsRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'unread_only' : IDL.Opt(IDL.Bool),
+  });
+  const NotificationsListResponse = IDL.Record({
+    'notifications' : IDL.Vec(UserNotification),
+    'pagination' : PaginationResponse,
+    'unread_count' : IDL.Nat32,
+  });
+  const ApiResponse_87 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(NotificationsListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_88 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(MyProductEntry)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_89 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(SavedWallet)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_90 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(NotificationRule)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_91 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(KeyEscrowRequest)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OrgVerificationFilters = IDL.Record({
+    'to' : IDL.Opt(IDL.Nat64),
+    'from' : IDL.Opt(IDL.Nat64),
+    'statuses' : IDL.Opt(IDL.Vec(ProductVerificationStatus)),
+    'product_ids' : IDL.Opt(IDL.Vec(IDL.Principal)),
+    'user_email_domain' : IDL.Opt(IDL.Text),
+  });
+  const ListOrgVerificationsRequest = IDL.Record({
+    'filters' : IDL.Opt(OrgVerificationFilters),
+    'cursor' : IDL.Opt(IDL.Text),
+    'org_id' : IDL.Principal,
+    'limit' : IDL.Opt(IDL.Nat32),
+  });
+  const OrgVerificationsResponse = IDL.Record({
+    'next_cursor' : IDL.Opt(IDL.Text),
+    'verifications' : IDL.Vec(ProductVerificationDetail),
+    'has_more' : IDL.Bool,
+  });
+  const ApiResponse_92 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrgVerificationsResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const FindOrganizationsRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'name' : IDL.Text,
+  });
+  const OrganizationsListResponse = IDL.Record({
+    'pagination' : IDL.Opt(PaginationResponse),
+    'organizations' : IDL.Vec(OrganizationPublic),
+  });
+  const ApiResponse_93 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrganizationsListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const OutboxDeliveryStatus = IDL.Variant({
+    'DeadLettered' : IDL.Null,
+    'Pending' : IDL.Null,
+  });
+  const OutboxDelivery = IDL.Record({
+    'id' : IDL.Nat64,
+    'url' : IDL.Text,
+    'last_error' : IDL.Opt(IDL.Text),
+    'status' : OutboxDeliveryStatus,
+    'updated_at' : IDL.Nat64,
+    'body' : IDL.Text,
+    'next_attempt_at' : IDL.Nat64,
+    'org_id' : IDL.Opt(IDL.Principal),
+    'attempts' : IDL.Nat32,
+    'created_at' : IDL.Nat64,
+  });
+  const OutboxDeliveriesResponse = IDL.Record({
+    'deliveries' : IDL.Vec(OutboxDelivery),
+    'pagination' : IDL.Opt(PaginationResponse),
+  });
+  const ApiResponse_94 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OutboxDeliveriesResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_95 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(PendingRedemption)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PostVerificationMessage = IDL.Record({
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'product_id' : IDL.Opt(IDL.Principal),
+    'org_id' : IDL.Principal,
+    'cta_url' : IDL.Opt(IDL.Text),
+    'cta_label' : IDL.Opt(IDL.Text),
+    'template' : IDL.Text,
+    'is_active' : IDL.Bool,
+  });
+  const ApiResponse_96 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(PostVerificationMessage)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_97 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(PrintJob)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_98 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ProbeRecord)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const Result = IDL.Variant({
+    'Ok' : IDL.Vec(ProductSerialNumber),
+    'Err' : ApiError,
+  });
+  const ListProductSerialNumbersRequest = IDL.Record({
+    'product_id' : IDL.Opt(IDL.Principal),
+    'pagination' : IDL.Opt(PaginationRequest),
+    'organization_id' : IDL.Opt(IDL.Principal),
+  });
+  const ProductSerialNumbersListResponse = IDL.Record({
+    'pagination' : IDL.Opt(PaginationResponse),
+    'serial_numbers' : IDL.Vec(ProductSerialNumber),
+  });
+  const ApiResponse_99 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductSerialNumbersListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_100 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ProductVariant)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ListProductVerificationsRequest = IDL.Record({
+    'product_id' : IDL.Opt(IDL.Principal),
+    'pagination' : IDL.Opt(PaginationRequest),
+    'serial_number' : IDL.Opt(IDL.Principal),
+    'organization_id' : IDL.Opt(IDL.Principal),
+  });
+  const ProductVerificationsListResponse = IDL.Record({
+    'pagination' : IDL.Opt(PaginationResponse),
+    'verifications' : IDL.Vec(ProductVerification),
+  });
+  const ApiResponse_101 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductVerificationsListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ListProductsRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'org_id' : IDL.Principal,
+  });
+  const ProductsListResponse = IDL.Record({
+    'pagination' : IDL.Opt(PaginationResponse),
+    'products' : IDL.Vec(Product),
+  });
+  const ApiResponse_102 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductsListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PublicBrand = IDL.Record({
+    'website_url' : IDL.Opt(IDL.Text),
+    'featured' : IDL.Bool,
+    'tagline' : IDL.Opt(IDL.Text),
+    'product_count' : IDL.Nat64,
+    'logo_url' : IDL.Opt(IDL.Text),
+    'badge' : VerifiedBrandBadge,
+    'organization' : OrganizationPublic,
+  });
+  const PublicBrandsListResponse = IDL.Record({
+    'brands' : IDL.Vec(PublicBrand),
+    'pagination' : PaginationResponse,
+  });
+  const ApiResponse_103 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PublicBrandsListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const IntegritySubKey = IDL.Variant({
+    'Seq' : IDL.Nat64,
+    'SerialNo' : IDL.Principal,
+  });
+  const IntegrityStore = IDL.Variant({
+    'Users' : IDL.Null,
+    'Organizations' : IDL.Null,
+    'ProductVerifications' : IDL.Null,
+    'VerificationRecords' : IDL.Null,
+    'ProductSerialNumbers' : IDL.Null,
+    'Products' : IDL.Null,
+    'Resellers' : IDL.Null,
+    'SerialRecords' : IDL.Null,
+  });
+  const QuarantinedEntry = IDL.Record({
+    'key' : IDL.Principal,
+    'sub_key' : IDL.Opt(IntegritySubKey),
+    'store' : IntegrityStore,
+    'decode_error' : IDL.Text,
+    'bytes' : IDL.Vec(IDL.Nat8),
+    'quarantined_at' : IDL.Nat64,
+    'quarantined_by' : IDL.Principal,
+  });
+  const ApiResponse_104 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(QuarantinedEntry)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_105 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ReadonlyPrincipal)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_106 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(RecallCampaign)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_107 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ReportSchedule)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_108 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ReportShareLink)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ListReportsRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'org_id' : IDL.Principal,
+  });
+  const ReportsListResponse = IDL.Record({
+    'pagination' : PaginationResponse,
+    'reports' : IDL.Vec(Report),
+  });
+  const ApiResponse_109 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ReportsListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_110 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ResellerApplicationDetail)),
+    'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_11 = IDL.Record({
+  const ResellerPreapproval = IDL.Record({
+    'name' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'used_at' : IDL.Opt(IDL.Nat64),
+    'created_at' : IDL.Nat64,
+    'created_by' : IDL.Principal,
+    'email' : IDL.Text,
+    'used_by_reseller_id' : IDL.Opt(IDL.Principal),
+  });
+  const ApiResponse_111 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(OrganizationAnalyticData),
+    'data' : IDL.Opt(IDL.Vec(ResellerPreapproval)),
     'error' : IDL.Opt(ApiError),
   });
-  const OrganizationResult = IDL.Variant({
-    'error' : ApiError,
-    'organization' : OrganizationPublic,
+  const ListRewardPenaltiesRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'org_id' : IDL.Opt(IDL.Principal),
+    'user_id' : IDL.Opt(IDL.Principal),
   });
-  const PrivateKeyResult = IDL.Variant({
-    'key' : IDL.Text,
-    'error' : ApiError,
+  const RewardPenaltiesListResponse = IDL.Record({
+    'pagination' : PaginationResponse,
+    'penalties' : IDL.Vec(RewardPenalty),
   });
-  const RateLimitInfo = IDL.Record({
-    'current_window_start' : IDL.Nat64,
-    'remaining_attempts' : IDL.Nat32,
-    'reset_time' : IDL.Nat64,
+  const ApiResponse_112 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(RewardPenaltiesListResponse),
+    'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_12 = IDL.Record({
+  const SerialPrintHistoryEntry = IDL.Record({
+    'printed_at' : IDL.Opt(IDL.Nat64),
+    'printed_by' : IDL.Opt(IDL.Principal),
+    'print_job_id' : IDL.Opt(IDL.Principal),
+    'print_version' : IDL.Nat8,
+    'is_current' : IDL.Bool,
+    'revocation' : IDL.Opt(CodeRevocation),
+  });
+  const SerialPrintHistoryResponse = IDL.Record({
+    'current_print_version' : IDL.Nat8,
+    'product_id' : IDL.Principal,
+    'versions' : IDL.Vec(SerialPrintHistoryEntry),
+    'serial_no' : IDL.Principal,
+  });
+  const ApiResponse_113 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(RateLimitInfo),
+    'data' : IDL.Opt(SerialPrintHistoryResponse),
     'error' : IDL.Opt(ApiError),
   });
-  const PaginationRequest = IDL.Record({
-    'page' : IDL.Opt(IDL.Nat32),
-    'limit' : IDL.Opt(IDL.Nat32),
+  const StatusMessage = IDL.Record({
+    'status' : ProductVerificationStatus,
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'text' : IDL.Text,
+    'locale' : IDL.Text,
   });
-  const FindOrganizationsRequest = IDL.Record({
-    'pagination' : IDL.Opt(PaginationRequest),
-    'name' : IDL.Text,
+  const ApiResponse_114 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(StatusMessage)),
+    'error' : IDL.Opt(ApiError),
   });
-  const PaginationResponse = IDL.Record({
-    'total' : IDL.Nat64,
-    'page' : IDL.Nat32,
-    'limit' : IDL.Nat32,
-    'has_more' : IDL.Bool,
+  const DuplicateProductGroup = IDL.Record({
+    'normalized_name' : IDL.Text,
+    'normalized_category' : IDL.Text,
+    'products' : IDL.Vec(Product),
   });
-  const OrganizationsListResponse = IDL.Record({
-    'pagination' : IDL.Opt(PaginationResponse),
-    'organizations' : IDL.Vec(OrganizationPublic),
+  const ApiResponse_115 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(DuplicateProductGroup)),
+    'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_13 = IDL.Record({
+  const ApiResponse_116 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(OrganizationsListResponse),
+    'data' : IDL.Opt(IDL.Vec(TermsVersion)),
     'error' : IDL.Opt(ApiError),
   });
-  const Result = IDL.Variant({
-    'Ok' : IDL.Vec(ProductSerialNumber),
-    'Err' : ApiError,
+  const UserListFilter = IDL.Record({
+    'org_id' : IDL.Opt(IDL.Principal),
+    'role' : IDL.Opt(UserRole),
+    'is_enabled' : IDL.Opt(IDL.Bool),
+    'query' : IDL.Opt(IDL.Text),
   });
-  const ProductVerificationStatus = IDL.Variant({
-    'Invalid' : IDL.Null,
-    'MultipleVerification' : IDL.Null,
-    'FirstVerification' : IDL.Null,
+  const UsersListResponse = IDL.Record({
+    'pagination' : PaginationResponse,
+    'users' : IDL.Vec(User),
   });
-  const ProductVerificationDetail = IDL.Record({
-    'status' : ProductVerificationStatus,
-    'user_email' : IDL.Opt(IDL.Text),
-    'product_id' : IDL.Principal,
-    'created_at' : IDL.Nat64,
-    'product_name' : IDL.Text,
-    'serial_no' : IDL.Principal,
+  const ApiResponse_117 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(UsersListResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_118 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(ProductSerialNumber)),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_119 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IDL.Vec(VerificationDelegation)),
+    'error' : IDL.Opt(ApiError),
   });
   const LogoutResponse = IDL.Record({
     'redirect_url' : IDL.Opt(IDL.Text),
     'message' : IDL.Text,
   });
-  const ApiResponse_14 = IDL.Record({
+  const ApiResponse_120 = IDL.Record({
     'metadata' : ResponseMetadata,
     'data' : IDL.Opt(LogoutResponse),
     'error' : IDL.Opt(ApiError),
   });
+  const BrandLookupResponse = IDL.Record({
+    'website_url' : IDL.Opt(IDL.Text),
+    'product_id' : IDL.Principal,
+    'logo_url' : IDL.Opt(IDL.Text),
+    'product_name' : IDL.Text,
+    'badge' : IDL.Opt(VerifiedBrandBadge),
+    'organization' : OrganizationPublic,
+  });
+  const ApiResponse_121 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BrandLookupResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_122 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(UserNotification),
+    'error' : IDL.Opt(ApiError),
+  });
+  const UpdateSerialStatusRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'serial_nos' : IDL.Vec(IDL.Principal),
+    'capability_token' : IDL.Opt(IDL.Text),
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const MintCapabilityTokenRequest = IDL.Record({
+    'ttl_seconds' : IDL.Nat64,
+    'org_id' : IDL.Principal,
+    'product_ids' : IDL.Vec(IDL.Principal),
+    'operations' : IDL.Vec(CapabilityOperation),
+    'holder' : IDL.Principal,
+  });
+  const MintCapabilityTokenResponse = IDL.Record({
+    'token' : IDL.Text,
+    'record' : CapabilityToken,
+  });
+  const ApiResponse_123 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(MintCapabilityTokenResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ModerateDirectoryListingRequest = IDL.Record({
+    'featured' : IDL.Opt(IDL.Bool),
+    'org_id' : IDL.Principal,
+    'suspended' : IDL.Opt(IDL.Bool),
+    'suspension_reason' : IDL.Opt(IDL.Text),
+  });
+  const OpenDisputeRequest = IDL.Record({
+    'subject' : IDL.Text,
+    'product_id' : IDL.Principal,
+    'message' : IDL.Text,
+    'serial_no' : IDL.Principal,
+    'verification_id' : IDL.Opt(IDL.Principal),
+  });
+  const PenalizeUserRewardsRequest = IDL.Record({
+    'org_id' : IDL.Opt(IDL.Principal),
+    'user_id' : IDL.Principal,
+    'points' : IDL.Nat32,
+    'reason' : IDL.Text,
+  });
+  const ApiResponse_124 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(RewardPenalty),
+    'error' : IDL.Opt(ApiError),
+  });
+  const PreapprovedResellerRow = IDL.Record({
+    'name' : IDL.Text,
+    'email' : IDL.Text,
+  });
+  const PreapproveResellersResponse = IDL.Record({
+    'added' : IDL.Nat32,
+    'updated' : IDL.Nat32,
+    'certified' : IDL.Nat32,
+  });
+  const ApiResponse_125 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PreapproveResellersResponse),
+    'error' : IDL.Opt(ApiError),
+  });
   const ProductUniqueCodeResultRecord = IDL.Record({
+    'verification_url' : IDL.Opt(IDL.Text),
     'product_id' : IDL.Principal,
     'created_at' : IDL.Nat64,
     'print_version' : IDL.Nat8,
@@ -353,17 +2581,58 @@ export const idlFactory = ({ IDL }) => {
     'result' : ProductUniqueCodeResultRecord,
     'error' : ApiError,
   });
+  const BulkPrintRequest = IDL.Record({
+    'printer_metadata' : IDL.Vec(Metadata),
+    'product_id' : IDL.Principal,
+    'label_template_version' : IDL.Opt(IDL.Text),
+    'serial_nos' : IDL.Vec(IDL.Principal),
+    'capability_token' : IDL.Opt(IDL.Text),
+    'code_policy' : IDL.Opt(PrintCodePolicy),
+    'activation_time' : IDL.Opt(IDL.Nat64),
+  });
+  const BulkPrintResponse = IDL.Record({
+    'codes' : IDL.Vec(ProductUniqueCodeResultRecord),
+    'label_template' : IDL.Opt(LabelTemplate),
+    'print_job' : PrintJob,
+  });
+  const ApiResponse_126 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BulkPrintResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ProposeAdminOperationRequest = IDL.Record({
+    'kind' : AdminOperationKind,
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const PublishTermsRequest = IDL.Record({
+    'url' : IDL.Text,
+    'version' : IDL.Text,
+  });
+  const ApiResponse_127 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(TermsVersion),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_128 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(QuarantinedEntry),
+    'error' : IDL.Opt(ApiError),
+  });
   const RedeemRewardRequest = IDL.Record({
+    'redeem_as_coupon' : IDL.Opt(IDL.Bool),
     'wallet_address' : IDL.Text,
     'unique_code' : IDL.Text,
+    'wallet_id' : IDL.Opt(IDL.Principal),
     'serial_no' : IDL.Principal,
   });
   const RedeemRewardResponse = IDL.Record({
     'transaction_id' : IDL.Opt(IDL.Text),
+    'pending_redemption_id' : IDL.Opt(IDL.Principal),
     'message' : IDL.Text,
     'success' : IDL.Bool,
+    'coupon' : IDL.Opt(RedeemedCoupon),
   });
-  const ApiResponse_15 = IDL.Record({
+  const ApiResponse_129 = IDL.Record({
     'metadata' : ResponseMetadata,
     'data' : IDL.Opt(RedeemRewardResponse),
     'error' : IDL.Opt(ApiError),
@@ -375,22 +2644,323 @@ export const idlFactory = ({ IDL }) => {
     'org_id' : IDL.Principal,
   });
   const UserResponse = IDL.Record({ 'user' : User });
-  const ApiResponse_16 = IDL.Record({
+  const ApiResponse_130 = IDL.Record({
     'metadata' : ResponseMetadata,
     'data' : IDL.Opt(UserResponse),
     'error' : IDL.Opt(ApiError),
   });
+  const RegisterVerificationDomainRequest = IDL.Record({
+    'domain' : IDL.Text,
+    'org_id' : IDL.Principal,
+  });
+  const RejectRedemptionRequest = IDL.Record({
+    'redemption_id' : IDL.Principal,
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const RejectResellerApplicationRequest = IDL.Record({
+    'application_id' : IDL.Principal,
+    'reason' : IDL.Text,
+  });
+  const ApiResponse_131 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ResellerPreapproval),
+    'error' : IDL.Opt(ApiError),
+  });
+  const RemoveStatusMessageRequest = IDL.Record({
+    'status' : ProductVerificationStatus,
+    'locale' : IDL.Text,
+  });
+  const ApiResponse_132 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(StatusMessage),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_133 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(VerificationDomain),
+    'error' : IDL.Opt(ApiError),
+  });
+  const RenewResellerCertificationRequest = IDL.Record({
+    'ecommerce_urls' : IDL.Vec(Metadata),
+    'additional_metadata' : IDL.Opt(IDL.Vec(Metadata)),
+    'contact_email' : IDL.Opt(IDL.Text),
+    'contact_phone' : IDL.Opt(IDL.Text),
+    'reseller_name' : IDL.Text,
+  });
+  const RequestKeyEscrowExportRequest = IDL.Record({
+    'recipient_public_key' : IDL.Text,
+    'org_id' : IDL.Principal,
+    'reason' : IDL.Opt(IDL.Text),
+  });
+  const ApiResponse_134 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OutboxDelivery),
+    'error' : IDL.Opt(ApiError),
+  });
   const ResetStorageResponse = IDL.Record({ 'message' : IDL.Text });
-  const ApiResponse_17 = IDL.Record({
+  const ApiResponse_135 = IDL.Record({
     'metadata' : ResponseMetadata,
     'data' : IDL.Opt(ResetStorageResponse),
     'error' : IDL.Opt(ApiError),
   });
-  const ApiResponse_18 = IDL.Record({
+  const ApiResponse_136 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(IDL.Null),
+    'data' : IDL.Opt(CircuitBreaker),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ReviewModerationCaseRequest = IDL.Record({
+    'note' : IDL.Opt(IDL.Text),
+    'approve' : IDL.Bool,
+    'entity_id' : IDL.Principal,
+  });
+  const ApiResponse_137 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ModerationCase),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_138 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(CapabilityToken),
+    'error' : IDL.Opt(ApiError),
+  });
+  const ApiResponse_139 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ReportShareLink),
+    'error' : IDL.Opt(ApiError),
+  });
+  const InvariantKind = IDL.Variant({
+    'SerialProductMismatch' : IDL.Null,
+    'UserOrganizationMissing' : IDL.Null,
+    'SessionKeyConflict' : IDL.Null,
+    'VerificationsProductMissing' : IDL.Null,
+    'ResellerOrganizationMissing' : IDL.Null,
+    'ResellerUserMissing' : IDL.Null,
+    'ProductOrganizationMissing' : IDL.Null,
+    'SerialsProductMissing' : IDL.Null,
+  });
+  const InvariantViolation = IDL.Record({
+    'kind' : InvariantKind,
+    'detail' : IDL.Text,
+    'referenced_id' : IDL.Principal,
+    'entity_id' : IDL.Principal,
+  });
+  const InvariantReport = IDL.Record({
+    'violations' : IDL.Vec(InvariantViolation),
+    'checked_at' : IDL.Nat64,
+    'violation_count' : IDL.Nat64,
+  });
+  const ApiResponse_140 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(InvariantReport),
+    'error' : IDL.Opt(ApiError),
+  });
+  const VerificationRollupResult = IDL.Record({
+    'deleted' : IDL.Nat64,
+    'complete' : IDL.Bool,
+    'rolled_up' : IDL.Nat64,
+    'archived' : IDL.Nat64,
+  });
+  const ApiResponse_141 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(VerificationRollupResult),
+    'error' : IDL.Opt(ApiError),
+  });
+  const IntegrityFailure = IDL.Record({
+    'key' : IDL.Principal,
+    'sub_key' : IDL.Opt(IntegritySubKey),
+    'size_bytes' : IDL.Nat64,
+    'error' : IDL.Text,
+  });
+  const IntegrityScanReport = IDL.Record({
+    'failures' : IDL.Vec(IntegrityFailure),
+    'scanned' : IDL.Nat64,
+    'store' : IntegrityStore,
+  });
+  const ApiResponse_142 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IntegrityScanReport),
+    'error' : IDL.Opt(ApiError),
+  });
+  const SearchProductsRequest = IDL.Record({
+    'pagination' : IDL.Opt(PaginationRequest),
+    'org_id' : IDL.Principal,
+    'query' : IDL.Opt(IDL.Text),
+  });
+  const SetBiExportConfigRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+    'endpoint_url' : IDL.Text,
+    'is_active' : IDL.Bool,
+  });
+  const SetFeatureFlagRequest = IDL.Record({
+    'flag' : IDL.Text,
+    'org_id' : IDL.Opt(IDL.Principal),
+    'enabled' : IDL.Bool,
+  });
+  const SetLabelTemplateRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'qr_error_correction' : QrErrorCorrection,
+    'qr_version' : IDL.Opt(IDL.Nat8),
+    'height_mm' : IDL.Float64,
+    'width_mm' : IDL.Float64,
+    'show_human_readable_code' : IDL.Bool,
+    'artwork_asset_url' : IDL.Opt(IDL.Text),
+    'qr_placement' : QrPlacement,
+  });
+  const SetMetadataSchemaRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+    'product_fields' : IDL.Vec(MetadataFieldSchema),
+    'serial_fields' : IDL.Vec(MetadataFieldSchema),
+    'category' : IDL.Text,
+  });
+  const ApiResponse_143 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(MetadataSchema),
+    'error' : IDL.Opt(ApiError),
+  });
+  const SetOrgQuotaRequest = IDL.Record({
+    'max_storage_bytes' : IDL.Opt(IDL.Nat64),
+    'max_outcalls_per_day' : IDL.Opt(IDL.Nat32),
+    'org_id' : IDL.Principal,
+    'max_serials_per_month' : IDL.Opt(IDL.Nat32),
+  });
+  const OrgQuota = IDL.Record({
+    'max_storage_bytes' : IDL.Opt(IDL.Nat64),
+    'updated_at' : IDL.Nat64,
+    'updated_by' : IDL.Principal,
+    'max_outcalls_per_day' : IDL.Opt(IDL.Nat32),
+    'org_id' : IDL.Principal,
+    'max_serials_per_month' : IDL.Opt(IDL.Nat32),
+  });
+  const ApiResponse_144 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(OrgQuota),
+    'error' : IDL.Opt(ApiError),
+  });
+  const SetPostVerificationMessageRequest = IDL.Record({
+    'product_id' : IDL.Opt(IDL.Principal),
+    'org_id' : IDL.Principal,
+    'cta_url' : IDL.Opt(IDL.Text),
+    'cta_label' : IDL.Opt(IDL.Text),
+    'template' : IDL.Text,
+    'is_active' : IDL.Bool,
+  });
+  const ApiResponse_145 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(PostVerificationMessage),
+    'error' : IDL.Opt(ApiError),
+  });
+  const SetProductRecallRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'instructions' : IDL.Opt(IDL.Text),
+    'reason' : IDL.Text,
+  });
+  const SetRewardPoolSettingsRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+    'units_per_point' : IDL.Opt(IDL.Nat64),
+    'low_balance_threshold' : IDL.Opt(IDL.Nat64),
+  });
+  const SetSerialMetadataRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'metadata' : IDL.Vec(Metadata),
+    'serial_no' : IDL.Principal,
+  });
+  const SetStatusMessageRequest = IDL.Record({
+    'status' : ProductVerificationStatus,
+    'text' : IDL.Text,
+    'locale' : IDL.Text,
+  });
+  const SetVerificationRetentionRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+    'handling' : RetiredVerificationHandling,
+    'retention_months' : IDL.Opt(IDL.Nat32),
+  });
+  const SignPayloadResponse = IDL.Record({
+    'signature' : IDL.Text,
+    'public_key' : IDL.Text,
+    'signed_at' : IDL.Nat64,
+  });
+  const ApiResponse_146 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(SignPayloadResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const VerificationScenario = IDL.Variant({
+    'CodeRevoked' : IDL.Null,
+    'Invalid' : IDL.Null,
+    'Discontinued' : IDL.Null,
+    'NotYetActive' : IDL.Null,
+    'MultipleVerification' : IDL.Null,
+    'GreyMarket' : IDL.Null,
+    'SuspectedClone' : IDL.Null,
+    'FirstVerification' : IDL.Null,
+    'VoidedSerial' : IDL.Null,
+    'SupersededCode' : IDL.Null,
+  });
+  const SimulateVerificationRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'locale' : IDL.Opt(IDL.Text),
+    'scenario' : VerificationScenario,
+  });
+  const ApiResponse_147 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(ProductVerificationEnhancedResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const GuestSessionResponse = IDL.Record({
+    'token' : IDL.Text,
+    'max_verifications' : IDL.Nat32,
+    'session_id' : IDL.Principal,
+    'expires_at' : IDL.Nat64,
+  });
+  const ApiResponse_148 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(GuestSessionResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const IdentityLinkCodeResponse = IDL.Record({
+    'code' : IDL.Text,
+    'expires_at' : IDL.Nat64,
+  });
+  const ApiResponse_149 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(IdentityLinkCodeResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const StartRecallCampaignRequest = IDL.Record({
+    'product_id' : IDL.Principal,
+    'instructions' : IDL.Opt(IDL.Text),
+    'scope' : RecallScope,
+    'reason' : IDL.Text,
+  });
+  const StartVerificationChallengeRequest = IDL.Record({
+    'client_version' : IDL.Opt(IDL.Text),
+    'guest_token' : IDL.Opt(IDL.Text),
+    'serial_no' : IDL.Principal,
+  });
+  const VerificationChallengeResponse = IDL.Record({
+    'product_id' : IDL.Principal,
+    'nonce' : IDL.Text,
+    'challenge_id' : IDL.Principal,
+    'expires_at' : IDL.Nat64,
+  });
+  const ApiResponse_150 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(VerificationChallengeResponse),
     'error' : IDL.Opt(ApiError),
   });
+  const JobRequest = IDL.Variant({
+    'VerificationExport' : ReportFilters,
+    'SerialImport' : IDL.Record({
+      'user_serial_prefix' : IDL.Opt(IDL.Text),
+      'product_id' : IDL.Principal,
+      'count' : IDL.Nat32,
+      'variant_id' : IDL.Opt(IDL.Principal),
+    }),
+  });
+  const SubmitJobRequest = IDL.Record({
+    'job' : JobRequest,
+    'org_id' : IDL.Principal,
+  });
   const HttpHeader = IDL.Record({ 'value' : IDL.Text, 'name' : IDL.Text });
   const HttpResponse = IDL.Record({
     'status' : IDL.Nat,
@@ -401,53 +2971,132 @@ export const idlFactory = ({ IDL }) => {
     'context' : IDL.Vec(IDL.Nat8),
     'response' : HttpResponse,
   });
+  const ApiResponse_151 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(BiExportDelivery),
+    'error' : IDL.Opt(ApiError),
+  });
+  const UpdateCanisterConfigRequest = IDL.Record({
+    'llm_api_host' : IDL.Opt(IDL.Text),
+    'outcall_limits' : IDL.Opt(OutcallResponseLimits),
+    'client_version_policy' : IDL.Opt(ClientVersionPolicy),
+    'rate_limit' : IDL.Opt(RateLimitConfig),
+    'scraper_url' : IDL.Opt(IDL.Text),
+    'llm_provider' : IDL.Opt(IDL.Text),
+    'email_relay_url' : IDL.Opt(IDL.Text),
+    'live_gateways' : IDL.Opt(IDL.Vec(IDL.Principal)),
+    'cycles_alerts' : IDL.Opt(CyclesAlertConfig),
+    'ledger_canister_id' : IDL.Opt(IDL.Principal),
+    'llm_api_key' : IDL.Opt(IDL.Text),
+    'moderation' : IDL.Opt(ModerationConfig),
+    'llm_model' : IDL.Opt(IDL.Text),
+    'feature_flags' : IDL.Opt(IDL.Vec(FeatureFlag)),
+  });
+  const UpdateDirectoryListingRequest = IDL.Record({
+    'is_public' : IDL.Opt(IDL.Bool),
+    'website_url' : IDL.Opt(IDL.Text),
+    'tagline' : IDL.Opt(IDL.Text),
+    'org_id' : IDL.Principal,
+    'logo_url' : IDL.Opt(IDL.Text),
+  });
+  const UpdateDisputeStatusRequest = IDL.Record({
+    'status' : DisputeStatus,
+    'dispute_id' : IDL.Principal,
+    'resolution' : IDL.Opt(IDL.Text),
+  });
+  const UpdateOrgSettingsRequest = IDL.Record({
+    'serial_batch_defaults' : IDL.Opt(SerialBatchDefaults),
+    'duplicate_scan_threshold' : IDL.Opt(IDL.Nat32),
+    'client_version_policy' : IDL.Opt(ClientVersionPolicy),
+    'org_id' : IDL.Principal,
+    'certification_grace_period_seconds' : IDL.Opt(IDL.Nat64),
+    'public_stats_enabled' : IDL.Opt(IDL.Bool),
+    'redemption_approval_threshold' : IDL.Opt(IDL.Nat32),
+    'single_use_reseller_codes' : IDL.Opt(IDL.Bool),
+    'certification_validity_seconds' : IDL.Opt(IDL.Nat64),
+    'verification_privacy' : IDL.Opt(VerificationPrivacyPolicy),
+    'authenticity_weights' : IDL.Opt(AuthenticityWeights),
+  });
   const UpdateOrganizationRequest = IDL.Record({
     'id' : IDL.Principal,
     'metadata' : IDL.Vec(Metadata),
     'name' : IDL.Text,
     'description' : IDL.Text,
   });
+  const UpdateProductVariantRequest = IDL.Record({
+    'sku' : IDL.Opt(IDL.Text),
+    'name' : IDL.Opt(IDL.Text),
+    'variant_id' : IDL.Principal,
+    'attributes' : IDL.Opt(IDL.Vec(Metadata)),
+  });
+  const UploadCouponCodesRequest = IDL.Record({
+    'product_id' : IDL.Opt(IDL.Principal),
+    'org_id' : IDL.Principal,
+    'codes' : IDL.Vec(IDL.Text),
+    'description' : IDL.Opt(IDL.Text),
+  });
+  const UploadCouponCodesResponse = IDL.Record({
+    'added' : IDL.Nat32,
+    'skipped_duplicates' : IDL.Nat32,
+    'available' : IDL.Nat64,
+  });
+  const ApiResponse_152 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(UploadCouponCodesResponse),
+    'error' : IDL.Opt(ApiError),
+  });
   const VerifyProductEnhancedRequest = IDL.Record({
+    'locale' : IDL.Opt(IDL.Text),
+    'client_version' : IDL.Opt(IDL.Text),
+    'guest_token' : IDL.Opt(IDL.Text),
+    'country_code' : IDL.Opt(IDL.Text),
     'unique_code' : IDL.Text,
     'serial_no' : IDL.Principal,
   });
-  const VerificationRewards = IDL.Record({
-    'special_reward' : IDL.Opt(IDL.Text),
-    'reward_description' : IDL.Opt(IDL.Text),
-    'is_first_verification' : IDL.Bool,
-    'points' : IDL.Nat32,
+  const BatchVerificationItem = IDL.Record({
+    'unique_code' : IDL.Text,
+    'serial_no' : IDL.Principal,
   });
-  const ProductVerification = IDL.Record({
-    'id' : IDL.Principal,
-    'status' : ProductVerificationStatus,
-    'product_id' : IDL.Principal,
-    'reward_claimed' : IDL.Bool,
-    'metadata' : IDL.Vec(Metadata),
-    'reward_transaction_id' : IDL.Opt(IDL.Text),
-    'created_at' : IDL.Nat64,
-    'created_by' : IDL.Principal,
-    'print_version' : IDL.Nat8,
+  const BatchVerificationItemStatus = IDL.Variant({
+    'Invalid' : IDL.Null,
+    'SuspectedClone' : IDL.Null,
+    'Genuine' : IDL.Null,
+    'Voided' : IDL.Null,
+    'Revoked' : IDL.Null,
+  });
+  const BatchVerificationItemResult = IDL.Record({
+    'status' : BatchVerificationItemStatus,
+    'product_id' : IDL.Opt(IDL.Principal),
+    'message' : IDL.Opt(IDL.Text),
     'serial_no' : IDL.Principal,
   });
-  const ProductVerificationEnhancedResponse = IDL.Record({
-    'status' : ProductVerificationStatus,
-    'expiration' : IDL.Opt(IDL.Nat64),
-    'rewards' : IDL.Opt(VerificationRewards),
-    'verification' : IDL.Opt(ProductVerification),
+  const BatchVerificationSummary = IDL.Record({
+    'total' : IDL.Nat32,
+    'invalid' : IDL.Nat32,
+    'genuine' : IDL.Nat32,
+    'suspect' : IDL.Nat32,
   });
-  const ApiResponse_19 = IDL.Record({
+  const BatchVerificationResponse = IDL.Record({
+    'rate_limit' : RateLimitInfo,
+    'results' : IDL.Vec(BatchVerificationItemResult),
+    'summary' : BatchVerificationSummary,
+  });
+  const ApiResponse_153 = IDL.Record({
     'metadata' : ResponseMetadata,
-    'data' : IDL.Opt(ProductVerificationEnhancedResponse),
+    'data' : IDL.Opt(BatchVerificationResponse),
     'error' : IDL.Opt(ApiError),
   });
   const VerifyResellerRequest = IDL.Record({
     'context' : IDL.Opt(IDL.Text),
+    'product_id' : IDL.Opt(IDL.Principal),
+    'client_version' : IDL.Opt(IDL.Text),
     'unique_code' : IDL.Text,
     'timestamp' : IDL.Nat64,
     'reseller_id' : IDL.Principal,
   });
   const ResellerVerificationStatus = IDL.Variant({
     'ExpiredCode' : IDL.Null,
+    'CertificationExpired' : IDL.Null,
     'Success' : IDL.Null,
     'ReplayAttackDetected' : IDL.Null,
     'InvalidCode' : IDL.Null,
@@ -459,21 +3108,164 @@ export const idlFactory = ({ IDL }) => {
     'status' : ResellerVerificationStatus,
     'reseller' : IDL.Opt(Reseller),
     'organization' : IDL.Opt(OrganizationPublic),
+    'product_authorized' : IDL.Opt(IDL.Bool),
   });
-  const ApiResponse_20 = IDL.Record({
+  const ApiResponse_154 = IDL.Record({
     'metadata' : ResponseMetadata,
     'data' : IDL.Opt(ResellerVerificationResponse),
     'error' : IDL.Opt(ApiError),
   });
+  const CounterfeitSignal = IDL.Variant({
+    'GreyMarket' : IDL.Null,
+    'SuspectedClone' : IDL.Null,
+    'InvalidCode' : IDL.Null,
+  });
+  const LiveTopic = IDL.Variant({
+    'Verifications' : IDL.Null,
+    'Counterfeits' : IDL.Null,
+  });
+  const LiveEvent = IDL.Variant({
+    'Counterfeit' : IDL.Record({
+      'product_id' : IDL.Principal,
+      'detected_at' : IDL.Nat64,
+      'country_code' : IDL.Opt(IDL.Text),
+      'signal' : CounterfeitSignal,
+      'serial_no' : IDL.Principal,
+    }),
+    'Closed' : IDL.Record({ 'reason' : IDL.Text }),
+    'Verification' : IDL.Record({
+      'status' : ProductVerificationStatus,
+      'product_id' : IDL.Principal,
+      'recorded_at' : IDL.Nat64,
+      'country_code' : IDL.Opt(IDL.Text),
+      'serial_no' : IDL.Principal,
+    }),
+    'Subscribed' : IDL.Record({ 'topics' : IDL.Vec(LiveTopic) }),
+  });
+  const WsOutgoingMessage = IDL.Record({
+    'seq' : IDL.Nat64,
+    'client' : IDL.Principal,
+    'event' : LiveEvent,
+    'sent_at' : IDL.Nat64,
+    'connection_id' : IDL.Nat64,
+  });
+  const WsGetMessagesResponse = IDL.Record({
+    'next_seq' : IDL.Nat64,
+    'messages' : IDL.Vec(WsOutgoingMessage),
+  });
+  const ApiResponse_155 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(WsGetMessagesResponse),
+    'error' : IDL.Opt(ApiError),
+  });
+  const WsClientMessage = IDL.Variant({
+    'Ping' : IDL.Null,
+    'Subscribe' : IDL.Vec(LiveTopic),
+  });
+  const WsMessageRequest = IDL.Record({
+    'message' : WsClientMessage,
+    'connection_id' : IDL.Nat64,
+  });
+  const WsOpenRequest = IDL.Record({
+    'org_id' : IDL.Principal,
+    'gateway' : IDL.Principal,
+    'topics' : IDL.Vec(LiveTopic),
+  });
+  const WsOpenResponse = IDL.Record({
+    'idle_timeout_seconds' : IDL.Nat64,
+    'topics' : IDL.Vec(LiveTopic),
+    'connection_id' : IDL.Nat64,
+  });
+  const ApiResponse_156 = IDL.Record({
+    'metadata' : ResponseMetadata,
+    'data' : IDL.Opt(WsOpenResponse),
+    'error' : IDL.Opt(ApiError),
+  });
   return IDL.Service({
+    'accept_terms_v2' : IDL.Func([IDL.Text], [ApiResponse], []),
+    'activate_print_job_v2' : IDL.Func([IDL.Principal], [ApiResponse_1], []),
+    'add_dispute_message_v2' : IDL.Func(
+        [AddDisputeMessageRequest],
+        [ApiResponse_2],
+        [],
+      ),
+    'add_my_wallet_v2' : IDL.Func([AddWalletRequest], [ApiResponse_3], []),
+    'add_to_my_products' : IDL.Func([IDL.Principal], [ApiResponse_4], []),
+    'approve_admin_operation_v2' : IDL.Func(
+        [AdminOperationDecisionRequest],
+        [ApiResponse_5],
+        [],
+      ),
+    'approve_org_key_escrow_export_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_6],
+        [],
+      ),
+    'approve_redemption_v2' : IDL.Func([IDL.Principal], [ApiResponse_7], []),
+    'approve_reseller_application_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_8],
+        [],
+      ),
+    'cancel_job_v2' : IDL.Func([IDL.Principal], [ApiResponse_9], []),
+    'cancel_org_key_escrow_export_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_6],
+        [],
+      ),
+    'change_org_plan_v2' : IDL.Func(
+        [IDL.Principal, PlanTier],
+        [ApiResponse_10],
+        [],
+      ),
     'check_reseller_verification' : IDL.Func(
         [IDL.Principal],
-        [ApiResponse],
+        [ApiResponse_11],
+        ['query'],
+      ),
+    'check_serial_status' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_12],
         ['query'],
       ),
+    'check_verification_domain_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_13],
+        [],
+      ),
+    'claim_guest_verifications' : IDL.Func([IDL.Text], [ApiResponse_14], []),
+    'clear_feature_flag_override_v2' : IDL.Func(
+        [IDL.Text, IDL.Principal],
+        [ApiResponse_15],
+        [],
+      ),
+    'clear_product_recall_v2' : IDL.Func([IDL.Principal], [ApiResponse_16], []),
+    'clear_reseller_products_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_17],
+        [],
+      ),
+    'close_recall_campaign_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_18],
+        [],
+      ),
+    'complete_identity_link' : IDL.Func([IDL.Text], [ApiResponse_19], []),
     'complete_reseller_profile' : IDL.Func(
         [CompleteResellerProfileRequest],
-        [ApiResponse_1],
+        [ApiResponse_20],
+        [],
+      ),
+    'complete_verification_challenge' : IDL.Func(
+        [CompleteVerificationChallengeRequest],
+        [ApiResponse_21],
+        [],
+      ),
+    'confirm_reseller_email' : IDL.Func([IDL.Text], [ApiResponse_22], []),
+    'create_bundle_v2' : IDL.Func([CreateBundleRequest], [ApiResponse_23], []),
+    'create_notification_rule_v2' : IDL.Func(
+        [CreateNotificationRuleRequest],
+        [ApiResponse_24],
         [],
       ),
     'create_organization' : IDL.Func(
@@ -483,12 +3275,12 @@ export const idlFactory = ({ IDL }) => {
       ),
     'create_organization_for_owner' : IDL.Func(
         [OrganizationInput],
-        [ApiResponse_2],
+        [ApiResponse_25],
         [],
       ),
     'create_organization_v2' : IDL.Func(
         [OrganizationInput],
-        [ApiResponse_3],
+        [ApiResponse_26],
         [],
       ),
     'create_product' : IDL.Func([ProductInput], [ProductResult], []),
@@ -497,101 +3289,583 @@ export const idlFactory = ({ IDL }) => {
         [ProductSerialNumberResult],
         [],
       ),
-    'create_user' : IDL.Func(
-        [IDL.Principal, UserDetailsInput],
-        [UserResult],
+    'create_product_v2' : IDL.Func(
+        [CreateProductRequest],
+        [ApiResponse_16],
+        [],
+      ),
+    'create_product_variant_v2' : IDL.Func(
+        [CreateProductVariantRequest],
+        [ApiResponse_27],
+        [],
+      ),
+    'create_readonly_principal_v2' : IDL.Func(
+        [IDL.Principal, IDL.Principal, IDL.Opt(IDL.Text)],
+        [ApiResponse_28],
+        [],
+      ),
+    'create_report_schedule_v2' : IDL.Func(
+        [CreateReportScheduleRequest],
+        [ApiResponse_29],
+        [],
+      ),
+    'create_report_share_link_v2' : IDL.Func(
+        [CreateReportShareLinkRequest],
+        [ApiResponse_30],
+        [],
+      ),
+    'create_serial_numbers_batch_v2' : IDL.Func(
+        [CreateSerialNumbersBatchRequest],
+        [ApiResponse_31],
+        [],
+      ),
+    'create_user' : IDL.Func(
+        [IDL.Principal, UserDetailsInput],
+        [UserResult],
+        [],
+      ),
+    'create_variant_serial_number_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_32],
+        [],
+      ),
+    'delegate_verification_v2' : IDL.Func(
+        [DelegateVerificationRequest],
+        [ApiResponse_33],
+        [],
+      ),
+    'delete_label_template_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_22],
+        [],
+      ),
+    'delete_metadata_schema_v2' : IDL.Func(
+        [IDL.Principal, IDL.Text],
+        [ApiResponse_22],
+        [],
+      ),
+    'delete_notification_rule_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_22],
+        [],
+      ),
+    'delete_product_variant_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_22],
+        [],
+      ),
+    'delete_report_schedule_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_22],
+        [],
+      ),
+    'deposit_reward_pool_v2' : IDL.Func(
+        [DepositRewardPoolRequest],
+        [ApiResponse_34],
+        [],
+      ),
+    'describe_status' : IDL.Func(
+        [ProductVerificationStatus, IDL.Opt(IDL.Text)],
+        [ApiResponse_35],
+        ['query'],
+      ),
+    'disable_user_v2' : IDL.Func([IDL.Principal], [ApiResponse_36], []),
+    'discontinue_product_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(IDL.Principal)],
+        [ApiResponse_16],
+        [],
+      ),
+    'enable_user_v2' : IDL.Func([IDL.Principal], [ApiResponse_36], []),
+    'export_org_key_escrow' : IDL.Func([IDL.Principal], [ApiResponse_37], []),
+    'find_organizations_by_name' : IDL.Func(
+        [IDL.Text],
+        [IDL.Vec(OrganizationPublic)],
+        ['query'],
+      ),
+    'find_resellers_by_name_or_id' : IDL.Func(
+        [IDL.Text],
+        [IDL.Vec(Reseller)],
+        ['query'],
+      ),
+    'find_serial_by_user_serial' : IDL.Func(
+        [IDL.Principal, IDL.Text],
+        [ApiResponse_32],
+        ['query'],
+      ),
+    'generate_product_review_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_16],
+        [],
+      ),
+    'generate_reseller_unique_code_v2' : IDL.Func(
+        [GenerateResellerUniqueCodeRequest],
+        [ApiResponse_38],
+        [],
+      ),
+    'get_archived_verification_aggregates_v2' : IDL.Func(
+        [ArchivedAggregatesQuery],
+        [ApiResponse_39],
+        ['query'],
+      ),
+    'get_auth_context' : IDL.Func([], [ApiResponse_20], ['query']),
+    'get_available_roles' : IDL.Func([], [ApiResponse_40], ['query']),
+    'get_bi_export_config_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_41],
+        ['query'],
+      ),
+    'get_brand_dashboard_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_42],
+        ['query'],
+      ),
+    'get_bundle_contents' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_23],
+        ['query'],
+      ),
+    'get_canister_config_v2' : IDL.Func([], [ApiResponse_43], ['query']),
+    'get_circuit_breakers_v2' : IDL.Func([], [ApiResponse_44], ['query']),
+    'get_cycles_history_v2' : IDL.Func(
+        [IDL.Opt(IDL.Nat32)],
+        [ApiResponse_45],
+        ['query'],
+      ),
+    'get_directory_listing_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_46],
+        ['query'],
+      ),
+    'get_dispute_v2' : IDL.Func([IDL.Principal], [ApiResponse_2], ['query']),
+    'get_endpoint_metrics_v2' : IDL.Func([], [ApiResponse_47], ['query']),
+    'get_job_results_v2' : IDL.Func(
+        [IDL.Principal, IDL.Nat32],
+        [ApiResponse_48],
+        ['query'],
+      ),
+    'get_job_status_v2' : IDL.Func([IDL.Principal], [ApiResponse_9], ['query']),
+    'get_label_template_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_49],
+        ['query'],
+      ),
+    'get_my_activity_v2' : IDL.Func(
+        [IDL.Opt(PaginationRequest)],
+        [ApiResponse_50],
+        ['query'],
+      ),
+    'get_my_capabilities_v2' : IDL.Func([], [ApiResponse_51], ['query']),
+    'get_my_organizations' : IDL.Func([], [ApiResponse_52], ['query']),
+    'get_my_plan_v2' : IDL.Func([IDL.Principal], [ApiResponse_53], ['query']),
+    'get_my_reseller_certification' : IDL.Func([], [ApiResponse_54], ['query']),
+    'get_navigation_context' : IDL.Func([], [ApiResponse_55], ['query']),
+    'get_openai_api_key' : IDL.Func([], [ApiResponse_56], ['query']),
+    'get_org_defaults_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_57],
+        ['query'],
+      ),
+    'get_org_onboarding_status_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_58],
+        ['query'],
+      ),
+    'get_org_settings_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_59],
+        ['query'],
+      ),
+    'get_org_usage_v2' : IDL.Func([IDL.Principal], [ApiResponse_60], ['query']),
+    'get_organization_analytic' : IDL.Func(
+        [GetOrganizationAnalyticRequest],
+        [ApiResponse_61],
+        ['query'],
+      ),
+    'get_organization_by_id' : IDL.Func(
+        [IDL.Principal],
+        [OrganizationResult],
+        ['query'],
+      ),
+    'get_organization_by_id_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_26],
+        ['query'],
+      ),
+    'get_organization_private_key' : IDL.Func(
+        [IDL.Principal],
+        [PrivateKeyResult],
+        ['query'],
+      ),
+    'get_ownership_stats_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_62],
+        ['query'],
+      ),
+    'get_platform_metrics_v2' : IDL.Func([], [ApiResponse_63], ['query']),
+    'get_product_by_id' : IDL.Func([IDL.Principal], [ProductResult], ['query']),
+    'get_product_reward_stats_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_64],
+        ['query'],
+      ),
+    'get_public_brand_stats' : IDL.Func(
+        [IDL.Principal, IDL.Opt(IDL.Nat64)],
+        [ApiResponse_65],
+        ['query'],
+      ),
+    'get_recall_campaign_status_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_66],
+        ['query'],
+      ),
+    'get_recent_logs_v2' : IDL.Func(
+        [IDL.Opt(LogLevel), IDL.Opt(IDL.Text), IDL.Opt(IDL.Nat32)],
+        [ApiResponse_67],
+        ['query'],
+      ),
+    'get_report_v2' : IDL.Func([IDL.Principal], [ApiResponse_68], ['query']),
+    'get_reseller_products_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_17],
+        ['query'],
+      ),
+    'get_reward_pool_balance_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_34],
+        ['query'],
+      ),
+    'get_scraper_url' : IDL.Func([], [ApiResponse_56], ['query']),
+    'get_shared_report' : IDL.Func([IDL.Text], [ApiResponse_69], ['query']),
+    'get_stats_for_serial_range' : IDL.Func(
+        [IDL.Principal, IDL.Nat64, IDL.Nat64],
+        [ApiResponse_70],
+        ['query'],
+      ),
+    'get_suspicious_activity_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(PaginationRequest)],
+        [ApiResponse_71],
+        ['query'],
+      ),
+    'get_terms_status_v2' : IDL.Func([], [ApiResponse_72], ['query']),
+    'get_user_by_id' : IDL.Func([IDL.Principal], [IDL.Opt(User)], ['query']),
+    'get_verification_domain_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_73],
+        ['query'],
+      ),
+    'get_verification_heatmap_v2' : IDL.Func(
+        [IDL.Principal, VerificationHeatmapRange],
+        [ApiResponse_74],
+        ['query'],
+      ),
+    'get_verification_rate_limit' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_75],
+        ['query'],
+      ),
+    'get_verification_retention_policy_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_76],
+        ['query'],
+      ),
+    'grant_reseller_products_v2' : IDL.Func(
+        [UpdateResellerProductsRequest],
+        [ApiResponse_17],
+        [],
+      ),
+    'greet' : IDL.Func([IDL.Text], [IDL.Text], ['query']),
+    'initialize_user_session' : IDL.Func(
+        [IDL.Opt(UserRole)],
+        [ApiResponse_20],
         [],
       ),
-    'find_organizations_by_name' : IDL.Func(
-        [IDL.Text],
-        [IDL.Vec(OrganizationPublic)],
+    'lift_probe_ban_v2' : IDL.Func([IDL.Principal], [ApiResponse_77], []),
+    'link_verification_history' : IDL.Func([IDL.Text], [ApiResponse_14], []),
+    'list_admin_operation_audit_log_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(PaginationRequest)],
+        [ApiResponse_78],
         ['query'],
       ),
-    'find_resellers_by_name_or_id' : IDL.Func(
-        [IDL.Text],
-        [IDL.Vec(Reseller)],
+    'list_admin_operations_v2' : IDL.Func(
+        [IDL.Opt(AdminOperationStatus), IDL.Opt(PaginationRequest)],
+        [ApiResponse_79],
         ['query'],
       ),
-    'generate_product_review_v2' : IDL.Func(
+    'list_bi_export_deliveries_v2' : IDL.Func(
+        [ListBiExportDeliveriesRequest],
+        [ApiResponse_80],
+        ['query'],
+      ),
+    'list_capability_token_audit_log_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(PaginationRequest)],
+        [ApiResponse_78],
+        ['query'],
+      ),
+    'list_capability_tokens_v2' : IDL.Func(
         [IDL.Principal],
-        [ApiResponse_4],
-        [],
+        [ApiResponse_81],
+        ['query'],
       ),
-    'generate_reseller_unique_code_v2' : IDL.Func(
-        [GenerateResellerUniqueCodeRequest],
-        [ApiResponse_5],
-        [],
+    'list_disputes_v2' : IDL.Func(
+        [ListDisputesRequest],
+        [ApiResponse_82],
+        ['query'],
       ),
-    'get_auth_context' : IDL.Func([], [ApiResponse_1], ['query']),
-    'get_available_roles' : IDL.Func([], [ApiResponse_6], ['query']),
-    'get_my_organizations' : IDL.Func([], [ApiResponse_7], ['query']),
-    'get_my_reseller_certification' : IDL.Func([], [ApiResponse_8], ['query']),
-    'get_navigation_context' : IDL.Func([], [ApiResponse_9], ['query']),
-    'get_openai_api_key' : IDL.Func([], [ApiResponse_10], ['query']),
-    'get_organization_analytic' : IDL.Func(
-        [GetOrganizationAnalyticRequest],
-        [ApiResponse_11],
+    'list_feature_flags_v2' : IDL.Func(
+        [IDL.Opt(IDL.Principal)],
+        [ApiResponse_83],
         ['query'],
       ),
-    'get_organization_by_id' : IDL.Func(
+    'list_jobs_v2' : IDL.Func([IDL.Principal], [ApiResponse_84], ['query']),
+    'list_metadata_schemas_v2' : IDL.Func(
         [IDL.Principal],
-        [OrganizationResult],
+        [ApiResponse_85],
         ['query'],
       ),
-    'get_organization_by_id_v2' : IDL.Func(
-        [IDL.Principal],
-        [ApiResponse_3],
+    'list_moderation_cases_v2' : IDL.Func(
+        [IDL.Opt(ModerationStatus), IDL.Opt(PaginationRequest)],
+        [ApiResponse_86],
         ['query'],
       ),
-    'get_organization_private_key' : IDL.Func(
+    'list_my_disputes_v2' : IDL.Func(
+        [IDL.Opt(PaginationRequest)],
+        [ApiResponse_82],
+        ['query'],
+      ),
+    'list_my_notifications_v2' : IDL.Func(
+        [ListNotificationsRequest],
+        [ApiResponse_87],
+        ['query'],
+      ),
+    'list_my_products_v2' : IDL.Func([], [ApiResponse_88], ['query']),
+    'list_my_wallets_v2' : IDL.Func([], [ApiResponse_89], ['query']),
+    'list_notification_rules_v2' : IDL.Func(
         [IDL.Principal],
-        [PrivateKeyResult],
+        [ApiResponse_90],
         ['query'],
       ),
-    'get_product_by_id' : IDL.Func([IDL.Principal], [ProductResult], ['query']),
-    'get_scraper_url' : IDL.Func([], [ApiResponse_10], ['query']),
-    'get_user_by_id' : IDL.Func([IDL.Principal], [IDL.Opt(User)], ['query']),
-    'get_verification_rate_limit' : IDL.Func(
+    'list_org_key_escrow_requests_v2' : IDL.Func(
         [IDL.Principal],
-        [ApiResponse_12],
+        [ApiResponse_91],
         ['query'],
       ),
-    'greet' : IDL.Func([IDL.Text], [IDL.Text], ['query']),
-    'initialize_user_session' : IDL.Func(
-        [IDL.Opt(UserRole)],
-        [ApiResponse_1],
-        [],
+    'list_org_verifications_v2' : IDL.Func(
+        [ListOrgVerificationsRequest],
+        [ApiResponse_92],
+        ['query'],
       ),
     'list_organizations_v2' : IDL.Func(
         [FindOrganizationsRequest],
-        [ApiResponse_13],
+        [ApiResponse_93],
         [],
       ),
+    'list_outbox_deliveries_v2' : IDL.Func(
+        [IDL.Opt(OutboxDeliveryStatus), IDL.Opt(PaginationRequest)],
+        [ApiResponse_94],
+        ['query'],
+      ),
+    'list_pending_redemptions_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_95],
+        ['query'],
+      ),
+    'list_post_verification_messages_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_96],
+        ['query'],
+      ),
+    'list_print_jobs_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_97],
+        ['query'],
+      ),
+    'list_probe_bans_v2' : IDL.Func([], [ApiResponse_98], ['query']),
+    'list_product_audit_log_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(PaginationRequest)],
+        [ApiResponse_78],
+        ['query'],
+      ),
     'list_product_serial_numbers' : IDL.Func(
         [IDL.Opt(IDL.Principal), IDL.Opt(IDL.Principal)],
         [Result],
         ['query'],
       ),
+    'list_product_serial_numbers_v2' : IDL.Func(
+        [ListProductSerialNumbersRequest],
+        [ApiResponse_99],
+        ['query'],
+      ),
+    'list_product_variants_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_100],
+        ['query'],
+      ),
     'list_product_verifications_by_org_id' : IDL.Func(
         [IDL.Principal],
         [IDL.Vec(ProductVerificationDetail)],
         ['query'],
       ),
+    'list_product_verifications_v2' : IDL.Func(
+        [ListProductVerificationsRequest],
+        [ApiResponse_101],
+        ['query'],
+      ),
     'list_products' : IDL.Func([IDL.Principal], [IDL.Vec(Product)], ['query']),
+    'list_products_v2' : IDL.Func(
+        [ListProductsRequest],
+        [ApiResponse_102],
+        ['query'],
+      ),
+    'list_public_brands' : IDL.Func(
+        [IDL.Opt(PaginationRequest)],
+        [ApiResponse_103],
+        ['query'],
+      ),
+    'list_quarantined_entries' : IDL.Func([], [ApiResponse_104], ['query']),
+    'list_readonly_principals_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_105],
+        ['query'],
+      ),
+    'list_recall_campaigns_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_106],
+        ['query'],
+      ),
+    'list_report_schedules_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_107],
+        ['query'],
+      ),
+    'list_report_share_links_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_108],
+        ['query'],
+      ),
+    'list_reports_v2' : IDL.Func(
+        [ListReportsRequest],
+        [ApiResponse_109],
+        ['query'],
+      ),
+    'list_reseller_applications_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(RedemptionApprovalStatus)],
+        [ApiResponse_110],
+        ['query'],
+      ),
+    'list_reseller_preapprovals_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_111],
+        ['query'],
+      ),
     'list_resellers_by_org_id' : IDL.Func(
         [IDL.Principal],
         [IDL.Vec(Reseller)],
         ['query'],
       ),
-    'logout_user' : IDL.Func([], [ApiResponse_14], []),
+    'list_reward_penalties_v2' : IDL.Func(
+        [ListRewardPenaltiesRequest],
+        [ApiResponse_112],
+        ['query'],
+      ),
+    'list_serial_print_history' : IDL.Func(
+        [IDL.Principal, IDL.Principal],
+        [ApiResponse_113],
+        ['query'],
+      ),
+    'list_status_messages_v2' : IDL.Func([], [ApiResponse_114], ['query']),
+    'list_suspected_duplicate_products_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_115],
+        ['query'],
+      ),
+    'list_terms_versions_v2' : IDL.Func([], [ApiResponse_116], ['query']),
+    'list_users_v2' : IDL.Func(
+        [IDL.Opt(UserListFilter), IDL.Opt(PaginationRequest)],
+        [ApiResponse_117],
+        ['query'],
+      ),
+    'list_variant_serial_numbers_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_118],
+        ['query'],
+      ),
+    'list_verification_delegations_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_119],
+        ['query'],
+      ),
+    'logout_user' : IDL.Func([], [ApiResponse_120], []),
+    'lookup_brand_by_code' : IDL.Func([IDL.Text], [ApiResponse_121], []),
+    'mark_notification_read_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_122],
+        [],
+      ),
+    'mark_serials_shipped' : IDL.Func(
+        [UpdateSerialStatusRequest],
+        [ApiResponse_31],
+        [],
+      ),
+    'mark_serials_sold' : IDL.Func(
+        [UpdateSerialStatusRequest],
+        [ApiResponse_31],
+        [],
+      ),
+    'mint_capability_token_v2' : IDL.Func(
+        [MintCapabilityTokenRequest],
+        [ApiResponse_123],
+        [],
+      ),
+    'moderate_directory_listing_v2' : IDL.Func(
+        [ModerateDirectoryListingRequest],
+        [ApiResponse_46],
+        [],
+      ),
+    'notify_reward_pool_deposit_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_34],
+        [],
+      ),
+    'open_dispute_v2' : IDL.Func([OpenDisputeRequest], [ApiResponse_2], []),
+    'penalize_user_rewards_v2' : IDL.Func(
+        [PenalizeUserRewardsRequest],
+        [ApiResponse_124],
+        [],
+      ),
+    'preapprove_resellers_v2' : IDL.Func(
+        [IDL.Principal, IDL.Vec(PreapprovedResellerRow)],
+        [ApiResponse_125],
+        [],
+      ),
     'print_product_serial_number' : IDL.Func(
         [IDL.Principal, IDL.Principal],
         [ProductUniqueCodeResult],
         [],
       ),
+    'print_product_serial_numbers_bulk_v2' : IDL.Func(
+        [BulkPrintRequest],
+        [ApiResponse_126],
+        [],
+      ),
+    'propose_admin_operation_v2' : IDL.Func(
+        [ProposeAdminOperationRequest],
+        [ApiResponse_5],
+        [],
+      ),
+    'publish_terms_v2' : IDL.Func([PublishTermsRequest], [ApiResponse_127], []),
+    'quarantine_entry' : IDL.Func(
+        [IntegrityStore, IDL.Principal, IDL.Opt(IntegritySubKey)],
+        [ApiResponse_128],
+        [],
+      ),
     'redeem_product_reward' : IDL.Func(
         [RedeemRewardRequest],
-        [ApiResponse_15],
+        [ApiResponse_129],
+        [],
+      ),
+    'refresh_organization_analytic_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_61],
         [],
       ),
     'register' : IDL.Func([], [User], []),
@@ -600,17 +3874,270 @@ export const idlFactory = ({ IDL }) => {
         [UserResult],
         [],
       ),
-    'register_as_reseller_v2' : IDL.Func([ResellerInput], [ApiResponse_16], []),
-    'reset_all_stable_storage' : IDL.Func([], [ApiResponse_17], []),
+    'register_as_reseller_v2' : IDL.Func(
+        [ResellerInput],
+        [ApiResponse_130],
+        [],
+      ),
+    'register_verification_domain_v2' : IDL.Func(
+        [RegisterVerificationDomainRequest],
+        [ApiResponse_13],
+        [],
+      ),
+    'reject_admin_operation_v2' : IDL.Func(
+        [AdminOperationDecisionRequest],
+        [ApiResponse_5],
+        [],
+      ),
+    'reject_redemption_v2' : IDL.Func(
+        [RejectRedemptionRequest],
+        [ApiResponse_7],
+        [],
+      ),
+    'reject_reseller_application_v2' : IDL.Func(
+        [RejectResellerApplicationRequest],
+        [ApiResponse_8],
+        [],
+      ),
+    'remove_from_my_products_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_22],
+        [],
+      ),
+    'remove_my_wallet_v2' : IDL.Func([IDL.Principal], [ApiResponse_3], []),
+    'remove_reseller_preapproval_v2' : IDL.Func(
+        [IDL.Principal, IDL.Text],
+        [ApiResponse_131],
+        [],
+      ),
+    'remove_status_message_v2' : IDL.Func(
+        [RemoveStatusMessageRequest],
+        [ApiResponse_132],
+        [],
+      ),
+    'remove_verification_domain_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_133],
+        [],
+      ),
+    'renew_reseller_certification_v2' : IDL.Func(
+        [RenewResellerCertificationRequest],
+        [ApiResponse_20],
+        [],
+      ),
+    'request_org_key_escrow_export_v2' : IDL.Func(
+        [RequestKeyEscrowExportRequest],
+        [ApiResponse_6],
+        [],
+      ),
+    'requeue_outbox_delivery_v2' : IDL.Func([IDL.Nat64], [ApiResponse_134], []),
+    'resend_reseller_email_confirmation_v2' : IDL.Func(
+        [],
+        [ApiResponse_22],
+        [],
+      ),
+    'reset_all_stable_storage' : IDL.Func([], [ApiResponse_135], []),
+    'reset_circuit_breaker_v2' : IDL.Func(
+        [ExternalService],
+        [ApiResponse_136],
+        [],
+      ),
+    'reset_endpoint_metrics_v2' : IDL.Func([], [ApiResponse_11], []),
+    'review_moderation_case_v2' : IDL.Func(
+        [ReviewModerationCaseRequest],
+        [ApiResponse_137],
+        [],
+      ),
+    'revoke_capability_token_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_138],
+        [],
+      ),
+    'revoke_readonly_principal_v2' : IDL.Func(
+        [IDL.Principal, IDL.Principal],
+        [ApiResponse_28],
+        [],
+      ),
+    'revoke_report_share_link_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_139],
+        [],
+      ),
+    'revoke_reseller_products_v2' : IDL.Func(
+        [UpdateResellerProductsRequest],
+        [ApiResponse_17],
+        [],
+      ),
+    'revoke_unique_codes_v2' : IDL.Func(
+        [IDL.Principal, IDL.Vec(IDL.Principal), CodeRevocationReason],
+        [ApiResponse_31],
+        [],
+      ),
+    'revoke_verification_delegation_v2' : IDL.Func(
+        [IDL.Principal],
+        [ApiResponse_33],
+        [],
+      ),
+    'rotate_readonly_principal_v2' : IDL.Func(
+        [IDL.Principal, IDL.Principal, IDL.Principal],
+        [ApiResponse_28],
+        [],
+      ),
+    'run_invariant_checks_v2' : IDL.Func([], [ApiResponse_140], ['query']),
+    'run_verification_rollup_v2' : IDL.Func([], [ApiResponse_141], []),
+    'scan_store_integrity' : IDL.Func(
+        [IntegrityStore],
+        [ApiResponse_142],
+        ['query'],
+      ),
+    'search_products_v2' : IDL.Func(
+        [SearchProductsRequest],
+        [ApiResponse_102],
+        ['query'],
+      ),
     'select_active_organization' : IDL.Func(
         [IDL.Principal],
-        [ApiResponse_1],
+        [ApiResponse_20],
+        [],
+      ),
+    'set_bi_export_config_v2' : IDL.Func(
+        [SetBiExportConfigRequest],
+        [ApiResponse_41],
+        [],
+      ),
+    'set_default_wallet_v2' : IDL.Func([IDL.Principal], [ApiResponse_3], []),
+    'set_feature_flag_v2' : IDL.Func(
+        [SetFeatureFlagRequest],
+        [ApiResponse_15],
+        [],
+      ),
+    'set_label_template_v2' : IDL.Func(
+        [SetLabelTemplateRequest],
+        [ApiResponse_49],
+        [],
+      ),
+    'set_metadata_schema_v2' : IDL.Func(
+        [SetMetadataSchemaRequest],
+        [ApiResponse_143],
+        [],
+      ),
+    'set_openai_api_key' : IDL.Func([IDL.Text], [ApiResponse_22], []),
+    'set_org_plan_status_v2' : IDL.Func(
+        [IDL.Principal, PlanStatus],
+        [ApiResponse_10],
+        [],
+      ),
+    'set_org_quota_v2' : IDL.Func([SetOrgQuotaRequest], [ApiResponse_144], []),
+    'set_post_verification_message_v2' : IDL.Func(
+        [SetPostVerificationMessageRequest],
+        [ApiResponse_145],
+        [],
+      ),
+    'set_product_challenge_mode_v2' : IDL.Func(
+        [IDL.Principal, IDL.Bool],
+        [ApiResponse_16],
         [],
       ),
-    'set_openai_api_key' : IDL.Func([IDL.Text], [ApiResponse_18], []),
-    'set_scraper_url' : IDL.Func([IDL.Text], [ApiResponse_18], []),
+    'set_product_markets_v2' : IDL.Func(
+        [IDL.Principal, IDL.Vec(IDL.Text)],
+        [ApiResponse_16],
+        [],
+      ),
+    'set_product_recall_v2' : IDL.Func(
+        [SetProductRecallRequest],
+        [ApiResponse_16],
+        [],
+      ),
+    'set_product_sequential_serials_v2' : IDL.Func(
+        [IDL.Principal, IDL.Bool],
+        [ApiResponse_16],
+        [],
+      ),
+    'set_product_test_mode_v2' : IDL.Func(
+        [IDL.Principal, IDL.Bool],
+        [ApiResponse_16],
+        [],
+      ),
+    'set_product_warranty_v2' : IDL.Func(
+        [IDL.Principal, IDL.Opt(IDL.Nat32)],
+        [ApiResponse_16],
+        [],
+      ),
+    'set_reward_pool_settings_v2' : IDL.Func(
+        [SetRewardPoolSettingsRequest],
+        [ApiResponse_34],
+        [],
+      ),
+    'set_scraper_url' : IDL.Func([IDL.Text], [ApiResponse_22], []),
     'set_self_role' : IDL.Func([UserRole], [UserResult], []),
-    'transform' : IDL.Func([TransformArgs], [HttpResponse], ['query']),
+    'set_serial_metadata_v2' : IDL.Func(
+        [SetSerialMetadataRequest],
+        [ApiResponse_32],
+        [],
+      ),
+    'set_status_message_v2' : IDL.Func(
+        [SetStatusMessageRequest],
+        [ApiResponse_132],
+        [],
+      ),
+    'set_user_role_admin_v2' : IDL.Func(
+        [IDL.Principal, UserRole],
+        [ApiResponse_36],
+        [],
+      ),
+    'set_verification_retention_policy_v2' : IDL.Func(
+        [SetVerificationRetentionRequest],
+        [ApiResponse_76],
+        [],
+      ),
+    'sign_payload_for_org' : IDL.Func(
+        [IDL.Principal, IDL.Text],
+        [ApiResponse_146],
+        [],
+      ),
+    'simulate_verification' : IDL.Func(
+        [SimulateVerificationRequest],
+        [ApiResponse_147],
+        ['query'],
+      ),
+    'start_guest_session' : IDL.Func([], [ApiResponse_148], []),
+    'start_identity_link' : IDL.Func([], [ApiResponse_149], []),
+    'start_recall_campaign_v2' : IDL.Func(
+        [StartRecallCampaignRequest],
+        [ApiResponse_66],
+        [],
+      ),
+    'start_verification_challenge' : IDL.Func(
+        [StartVerificationChallengeRequest],
+        [ApiResponse_150],
+        [],
+      ),
+    'submit_job_v2' : IDL.Func([SubmitJobRequest], [ApiResponse_9], []),
+    'transform_dns' : IDL.Func([TransformArgs], [HttpResponse], ['query']),
+    'transform_openai' : IDL.Func([TransformArgs], [HttpResponse], ['query']),
+    'transform_scraper' : IDL.Func([TransformArgs], [HttpResponse], ['query']),
+    'transform_webhook' : IDL.Func([TransformArgs], [HttpResponse], ['query']),
+    'trigger_bi_export_v2' : IDL.Func([IDL.Principal], [ApiResponse_151], []),
+    'update_canister_config_v2' : IDL.Func(
+        [UpdateCanisterConfigRequest],
+        [ApiResponse_43],
+        [],
+      ),
+    'update_directory_listing_v2' : IDL.Func(
+        [UpdateDirectoryListingRequest],
+        [ApiResponse_46],
+        [],
+      ),
+    'update_dispute_status_v2' : IDL.Func(
+        [UpdateDisputeStatusRequest],
+        [ApiResponse_2],
+        [],
+      ),
+    'update_org_settings_v2' : IDL.Func(
+        [UpdateOrgSettingsRequest],
+        [ApiResponse_59],
+        [],
+      ),
     'update_organization' : IDL.Func(
         [IDL.Principal, OrganizationInput],
         [OrganizationResult],
@@ -618,7 +4145,7 @@ export const idlFactory = ({ IDL }) => {
       ),
     'update_organization_v2' : IDL.Func(
         [UpdateOrganizationRequest],
-        [ApiResponse_3],
+        [ApiResponse_26],
         [],
       ),
     'update_product' : IDL.Func(
@@ -631,6 +4158,11 @@ export const idlFactory = ({ IDL }) => {
         [ProductSerialNumberResult],
         [],
       ),
+    'update_product_variant_v2' : IDL.Func(
+        [UpdateProductVariantRequest],
+        [ApiResponse_27],
+        [],
+      ),
     'update_self_details' : IDL.Func([UserDetailsInput], [UserResult], []),
     'update_user' : IDL.Func(
         [IDL.Principal, UserDetailsInput],
@@ -642,17 +4174,36 @@ export const idlFactory = ({ IDL }) => {
         [UserResult],
         [],
       ),
+    'upload_coupon_codes_v2' : IDL.Func(
+        [UploadCouponCodesRequest],
+        [ApiResponse_152],
+        [],
+      ),
     'verify_product_v2' : IDL.Func(
         [VerifyProductEnhancedRequest],
-        [ApiResponse_19],
+        [ApiResponse_147],
+        [],
+      ),
+    'verify_products_batch_v2' : IDL.Func(
+        [IDL.Vec(BatchVerificationItem)],
+        [ApiResponse_153],
         [],
       ),
     'verify_reseller_v2' : IDL.Func(
         [VerifyResellerRequest],
-        [ApiResponse_20],
-        ['query'],
+        [ApiResponse_154],
+        [],
+      ),
+    'void_serial_numbers' : IDL.Func(
+        [UpdateSerialStatusRequest],
+        [ApiResponse_31],
+        [],
       ),
     'whoami' : IDL.Func([], [IDL.Opt(User)], ['query']),
+    'ws_close' : IDL.Func([IDL.Nat64], [ApiResponse_22], []),
+    'ws_get_messages' : IDL.Func([IDL.Nat64], [ApiResponse_155], ['query']),
+    'ws_message' : IDL.Func([WsMessageRequest], [ApiResponse_22], []),
+    'ws_open' : IDL.Func([WsOpenRequest], [ApiResponse_156], []),
   });
 };
 export const init = ({ IDL }) => { return []; };